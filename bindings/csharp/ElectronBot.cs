@@ -0,0 +1,335 @@
+// ElectronBot 的 C# 封装（.NET Standard 2.0 / Unity 2019.4 及以上）
+//
+// 基于 Rust 库的 C 接口（`cargo build --release --features ffi`），把生成的动态库放到：
+// - .NET：可执行文件旁边（electron_bot.dll / libelectron_bot.so / libelectron_bot.dylib）
+// - Unity：Assets/Plugins/（按平台设置导入选项）
+//
+// 封送约定（与 include/electron_bot.h 一一对应）：
+// - ElectronBot* 用 SafeHandle 保存，GC 回收或 Dispose 时调用 eb_free
+// - byte[]、float[]、Color32[] 是 blittable 数组，P/Invoke 调用期间自动固定，不会复制
+// - C 的 bool 是 1 字节，必须标注 [MarshalAs(UnmanagedType.U1)]（默认按 4 字节的 Win32 BOOL 封送）
+// - 枚举按 int 传递，数值与头文件一致
+// - eb_last_error 返回当前线程的 UTF-8 字符串，由库持有，只读取不释放
+// - C 接口不是线程安全的，Robot 的每个方法都持有同一把锁，可以在工作线程里调用 Sync
+
+using System;
+using System.Runtime.InteropServices;
+using System.Text;
+
+namespace ElectronBotLib
+{
+    /// <summary>C 接口的错误码（EbError）。</summary>
+    public enum EbError
+    {
+        Ok = 0,
+        NullPointer = 1,
+        InvalidArgument = 2,
+        DeviceNotFound = 3,
+        Usb = 4,
+        SendFailed = 5,
+        ReceiveFailed = 6,
+        Image = 7,
+        NotConnected = 8,
+        InterfaceNotFound = 9,
+        Other = 10,
+        Panic = 11,
+    }
+
+    /// <summary>图片适配方式（EbFitMode）。</summary>
+    public enum FitMode : uint
+    {
+        Stretch = 0,
+        Contain = 1,
+        Cover = 2,
+        Center = 3,
+    }
+
+    /// <summary>像素格式（EbPixelFormat）。</summary>
+    public enum PixelFormat : uint
+    {
+        Rgb24 = 0,
+        Rgba32 = 1,
+        Bgra32 = 2,
+        Bgr24 = 3,
+    }
+
+    /// <summary>C 接口返回错误码时抛出。</summary>
+    public sealed class ElectronBotException : Exception
+    {
+        public EbError Code { get; }
+
+        public ElectronBotException(EbError code, string message)
+            : base($"{code}: {message}")
+        {
+            Code = code;
+        }
+    }
+
+    internal static class Native
+    {
+        private const string Lib = "electron_bot";
+
+        [DllImport(Lib, CallingConvention = CallingConvention.Cdecl)]
+        internal static extern BotHandle eb_new();
+
+        [DllImport(Lib, CallingConvention = CallingConvention.Cdecl)]
+        internal static extern void eb_free(IntPtr bot);
+
+        [DllImport(Lib, CallingConvention = CallingConvention.Cdecl)]
+        internal static extern EbError eb_connect(BotHandle bot);
+
+        [DllImport(Lib, CallingConvention = CallingConvention.Cdecl)]
+        internal static extern void eb_disconnect(BotHandle bot);
+
+        [DllImport(Lib, CallingConvention = CallingConvention.Cdecl)]
+        [return: MarshalAs(UnmanagedType.U1)]
+        internal static extern bool eb_is_connected(BotHandle bot);
+
+        [DllImport(Lib, CallingConvention = CallingConvention.Cdecl)]
+        internal static extern EbError eb_set_image_pixels(
+            BotHandle bot,
+            [In] byte[] data,
+            uint width,
+            uint height,
+            uint stride,
+            uint format,
+            [MarshalAs(UnmanagedType.U1)] bool flipY);
+
+        [DllImport(Lib, CallingConvention = CallingConvention.Cdecl)]
+        internal static extern EbError eb_set_image_pixels(
+            BotHandle bot,
+            IntPtr data,
+            uint width,
+            uint height,
+            uint stride,
+            uint format,
+            [MarshalAs(UnmanagedType.U1)] bool flipY);
+
+        [DllImport(Lib, CallingConvention = CallingConvention.Cdecl)]
+        internal static extern EbError eb_set_image_bytes(BotHandle bot, [In] byte[] data, UIntPtr len);
+
+        [DllImport(Lib, CallingConvention = CallingConvention.Cdecl)]
+        internal static extern EbError eb_set_image_color(BotHandle bot, byte r, byte g, byte b);
+
+        [DllImport(Lib, CallingConvention = CallingConvention.Cdecl)]
+        internal static extern EbError eb_set_fit_mode(BotHandle bot, uint mode);
+
+        [DllImport(Lib, CallingConvention = CallingConvention.Cdecl)]
+        internal static extern EbError eb_set_joints(
+            BotHandle bot,
+            [In] float[] angles,
+            [MarshalAs(UnmanagedType.U1)] bool enable);
+
+        [DllImport(Lib, CallingConvention = CallingConvention.Cdecl)]
+        internal static extern EbError eb_get_joints(BotHandle bot, [Out] float[] angles);
+
+        [DllImport(Lib, CallingConvention = CallingConvention.Cdecl)]
+        internal static extern EbError eb_sync(BotHandle bot);
+
+        [DllImport(Lib, CallingConvention = CallingConvention.Cdecl)]
+        internal static extern IntPtr eb_last_error();
+
+        [DllImport(Lib, CallingConvention = CallingConvention.Cdecl)]
+        internal static extern IntPtr eb_version();
+
+        /// <summary>读取以 0 结尾的 UTF-8 字符串（Marshal.PtrToStringUTF8 在旧版 Mono 中不可用）。</summary>
+        internal static string Utf8(IntPtr ptr)
+        {
+            if (ptr == IntPtr.Zero)
+            {
+                return string.Empty;
+            }
+            var len = 0;
+            while (Marshal.ReadByte(ptr, len) != 0)
+            {
+                len++;
+            }
+            var bytes = new byte[len];
+            Marshal.Copy(ptr, bytes, 0, len);
+            return Encoding.UTF8.GetString(bytes);
+        }
+    }
+
+    /// <summary>ElectronBot* 句柄，释放时调用 eb_free。</summary>
+    internal sealed class BotHandle : SafeHandle
+    {
+        private BotHandle()
+            : base(IntPtr.Zero, true)
+        {
+        }
+
+        public override bool IsInvalid => handle == IntPtr.Zero;
+
+        protected override bool ReleaseHandle()
+        {
+            Native.eb_free(handle);
+            return true;
+        }
+    }
+
+    /// <summary>ElectronBot 机器人。</summary>
+    /// <example>
+    /// <code>
+    /// using (var robot = new Robot())
+    /// {
+    ///     robot.Connect();
+    ///     robot.SetImage(rgba, 240, 240, PixelFormat.Rgba32);
+    ///     robot.SetJoints(new float[] { 0, 0, 10, 0, 10, 0 });
+    ///     robot.Sync();
+    /// }
+    /// </code>
+    /// </example>
+    public sealed class Robot : IDisposable
+    {
+        /// <summary>关节数量：头部、左臂两个关节、右臂两个关节、身体。</summary>
+        public const int JointCount = 6;
+
+        private readonly BotHandle _handle;
+        private readonly object _lock = new object();
+
+        public Robot()
+        {
+            _handle = Native.eb_new();
+        }
+
+        /// <summary>库版本号。</summary>
+        public static string Version => Native.Utf8(Native.eb_version());
+
+        public bool IsConnected
+        {
+            get
+            {
+                lock (_lock)
+                {
+                    return Native.eb_is_connected(_handle);
+                }
+            }
+        }
+
+        public void Connect()
+        {
+            lock (_lock)
+            {
+                Check(Native.eb_connect(_handle));
+            }
+        }
+
+        public void Disconnect()
+        {
+            lock (_lock)
+            {
+                Native.eb_disconnect(_handle);
+            }
+        }
+
+        /// <summary>设置图片适配方式（默认拉伸）。</summary>
+        public void SetFitMode(FitMode mode)
+        {
+            lock (_lock)
+            {
+                Check(Native.eb_set_fit_mode(_handle, (uint)mode));
+            }
+        }
+
+        /// <summary>
+        /// 设置图片，按适配方式缩放到 240x240。
+        /// </summary>
+        /// <param name="pixels">像素数据，每行 <paramref name="stride"/> 字节</param>
+        /// <param name="stride">每行字节数，0 表示紧密排列</param>
+        /// <param name="flipY">数据的第一行是否为图片底部（Unity 纹理为 true）</param>
+        public void SetImage(byte[] pixels, int width, int height, PixelFormat format = PixelFormat.Rgba32,
+            bool flipY = false, int stride = 0)
+        {
+            if (pixels == null)
+            {
+                throw new ArgumentNullException(nameof(pixels));
+            }
+            var bpp = format == PixelFormat.Rgb24 || format == PixelFormat.Bgr24 ? 3 : 4;
+            var rowBytes = width * bpp;
+            var needed = (long)(stride == 0 ? rowBytes : stride) * (height - 1) + rowBytes;
+            if (width <= 0 || height <= 0 || pixels.LongLength < needed)
+            {
+                throw new ArgumentException($"像素数据需要至少 {needed} 字节", nameof(pixels));
+            }
+            lock (_lock)
+            {
+                Check(Native.eb_set_image_pixels(_handle, pixels, (uint)width, (uint)height, (uint)stride,
+                    (uint)format, flipY));
+            }
+        }
+
+        /// <summary>设置图片（指针版本，用于 NativeArray 等非托管内存）。</summary>
+        public void SetImage(IntPtr pixels, int width, int height, PixelFormat format, bool flipY = false,
+            int stride = 0)
+        {
+            lock (_lock)
+            {
+                Check(Native.eb_set_image_pixels(_handle, pixels, (uint)width, (uint)height, (uint)stride,
+                    (uint)format, flipY));
+            }
+        }
+
+        /// <summary>设置 JPEG/PNG 编码的图片。</summary>
+        public void SetImageEncoded(byte[] data)
+        {
+            lock (_lock)
+            {
+                Check(Native.eb_set_image_bytes(_handle, data, (UIntPtr)data.Length));
+            }
+        }
+
+        public void SetColor(byte r, byte g, byte b)
+        {
+            lock (_lock)
+            {
+                Check(Native.eb_set_image_color(_handle, r, g, b));
+            }
+        }
+
+        /// <summary>设置 6 个关节角度（度），enable 为 false 时释放舵机。</summary>
+        public void SetJoints(float[] angles, bool enable = true)
+        {
+            if (angles == null || angles.Length != JointCount)
+            {
+                throw new ArgumentException($"需要 {JointCount} 个角度", nameof(angles));
+            }
+            lock (_lock)
+            {
+                Check(Native.eb_set_joints(_handle, angles, enable));
+            }
+        }
+
+        public float[] GetJoints()
+        {
+            var angles = new float[JointCount];
+            lock (_lock)
+            {
+                Check(Native.eb_get_joints(_handle, angles));
+            }
+            return angles;
+        }
+
+        /// <summary>同步图片和关节角度（阻塞约 30 毫秒，Unity 中建议在工作线程调用）。</summary>
+        public void Sync()
+        {
+            lock (_lock)
+            {
+                Check(Native.eb_sync(_handle));
+            }
+        }
+
+        public void Dispose()
+        {
+            _handle.Dispose();
+        }
+
+        /// <summary>错误信息属于调用线程，因此在持有锁的同一线程里读取。</summary>
+        private static void Check(EbError code)
+        {
+            if (code != EbError.Ok)
+            {
+                throw new ElectronBotException(code, Native.Utf8(Native.eb_last_error()));
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+// Unity 示例：用虚拟形象的骨骼驱动 ElectronBot
+//
+// 把本文件和 ElectronBot.cs 放到 Assets/ 下，把组件挂到任意物体上：
+// - face：渲染机器人屏幕内容的 RenderTexture（例如对准虚拟形象脸部的相机输出，建议 240x240）
+// - head / leftArm / rightArm / body：虚拟形象对应的骨骼
+//
+// 主线程每帧读取骨骼角度和屏幕画面，工作线程负责发送，USB 传输不会阻塞渲染；
+// 发送跟不上渲染帧率时只发送最新的一帧。
+
+#if UNITY_5_3_OR_NEWER
+using System.Threading;
+using ElectronBotLib;
+using UnityEngine;
+
+public sealed class ElectronBotPuppet : MonoBehaviour
+{
+    public RenderTexture face;
+    public Transform head;
+    public Transform leftArm;
+    public Transform rightArm;
+    public Transform body;
+
+    [Tooltip("骨骼角度到关节角度的缩放")]
+    public float angleScale = 1.0f;
+
+    // 与 Rust 库的 DEFAULT_JOINT_LIMITS 一致（度）
+    private static readonly float[] Limits = { 15f, 30f, 60f, 30f, 60f, 60f };
+
+    private Robot _robot;
+    private Texture2D _readback;
+    private Thread _worker;
+    private volatile bool _running;
+
+    // 主线程写入、工作线程读取的最新一帧
+    private readonly object _frameLock = new object();
+    private Color32[] _pixels;
+    private readonly float[] _joints = new float[Robot.JointCount];
+    private bool _dirty;
+
+    private void OnEnable()
+    {
+        _robot = new Robot();
+        try
+        {
+            _robot.Connect();
+        }
+        catch (ElectronBotException e)
+        {
+            Debug.LogWarning($"ElectronBot 连接失败: {e.Message}");
+            _robot.Dispose();
+            _robot = null;
+            return;
+        }
+
+        _readback = new Texture2D(face.width, face.height, TextureFormat.RGBA32, false);
+        _running = true;
+        _worker = new Thread(SendLoop) { IsBackground = true, Name = "ElectronBot" };
+        _worker.Start();
+    }
+
+    private void LateUpdate()
+    {
+        if (_robot == null)
+        {
+            return;
+        }
+
+        // 读取 RenderTexture（Color32 为 RGBA32，第一行是图片底部）
+        var previous = RenderTexture.active;
+        RenderTexture.active = face;
+        _readback.ReadPixels(new Rect(0, 0, face.width, face.height), 0, 0, false);
+        RenderTexture.active = previous;
+        var pixels = _readback.GetPixels32();
+
+        lock (_frameLock)
+        {
+            _pixels = pixels;
+            _joints[0] = Pitch(head);
+            _joints[1] = Roll(leftArm);
+            _joints[2] = Pitch(leftArm);
+            _joints[3] = -Roll(rightArm);
+            _joints[4] = Pitch(rightArm);
+            _joints[5] = Yaw(body);
+            for (var i = 0; i < _joints.Length; i++)
+            {
+                _joints[i] = Mathf.Clamp(_joints[i], -Limits[i], Limits[i]);
+            }
+            _dirty = true;
+            Monitor.Pulse(_frameLock);
+        }
+    }
+
+    private void SendLoop()
+    {
+        var joints = new float[Robot.JointCount];
+        byte[] bytes = null;
+        while (_running)
+        {
+            int width, height;
+            lock (_frameLock)
+            {
+                while (!_dirty && _running)
+                {
+                    Monitor.Wait(_frameLock);
+                }
+                if (!_running)
+                {
+                    return;
+                }
+                // Color32 与 4 字节 RGBA 内存布局相同，复制成 byte[] 后交给 C 接口
+                var count = _pixels.Length * 4;
+                if (bytes == null || bytes.Length != count)
+                {
+                    bytes = new byte[count];
+                }
+                var handle = System.Runtime.InteropServices.GCHandle.Alloc(_pixels,
+                    System.Runtime.InteropServices.GCHandleType.Pinned);
+                System.Runtime.InteropServices.Marshal.Copy(handle.AddrOfPinnedObject(), bytes, 0, count);
+                handle.Free();
+                _joints.CopyTo(joints, 0);
+                width = _readback.width;
+                height = _readback.height;
+                _dirty = false;
+            }
+
+            try
+            {
+                _robot.SetImage(bytes, width, height, PixelFormat.Rgba32, flipY: true);
+                _robot.SetJoints(joints);
+                _robot.Sync();
+            }
+            catch (ElectronBotException e)
+            {
+                Debug.LogWarning($"ElectronBot 同步失败: {e.Message}");
+            }
+        }
+    }
+
+    private void OnDisable()
+    {
+        _running = false;
+        lock (_frameLock)
+        {
+            Monitor.Pulse(_frameLock);
+        }
+        _worker?.Join();
+        _worker = null;
+        _robot?.Dispose();
+        _robot = null;
+    }
+
+    private float Pitch(Transform bone) => bone ? Signed(bone.localEulerAngles.x) * angleScale : 0f;
+
+    private float Roll(Transform bone) => bone ? Signed(bone.localEulerAngles.z) * angleScale : 0f;
+
+    private float Yaw(Transform bone) => bone ? Signed(bone.localEulerAngles.y) * angleScale : 0f;
+
+    /// <summary>欧拉角转换到 -180 到 180 度。</summary>
+    private static float Signed(float degrees) => Mathf.DeltaAngle(0f, degrees);
+}
+#endif
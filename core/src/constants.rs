@@ -0,0 +1,33 @@
+//! 设备 ID、帧布局和关节索引。
+
+/// ElectronBot 的 USB 厂商 ID。
+pub const USB_VID: u16 = 0x1001;
+
+/// ElectronBot 的 USB 产品 ID。
+pub const USB_PID: u16 = 0x8023;
+
+/// 图片尺寸。
+pub const FRAME_WIDTH: usize = 240;
+pub const FRAME_HEIGHT: usize = 240;
+pub const FRAME_SIZE: usize = FRAME_WIDTH * FRAME_HEIGHT * 3;
+pub const PACKET_SIZE: usize = 512;
+pub const PACKET_COUNT: usize = 84;
+pub const TAIL_SIZE: usize = 224;
+
+/// 每个同步周期发送的行数（一帧分为 4 段）。
+pub const QUARTER_ROWS: usize = FRAME_HEIGHT / 4;
+
+/// 每个同步周期发送的图片字节数（84 个包加上尾包中的 192 字节）。
+pub const QUARTER_SIZE: usize = PACKET_COUNT * PACKET_SIZE + 192;
+
+/// 局部刷新时尾包扩展数据中标记段序号的字节位置。
+pub const PARTIAL_MARKER_OFFSET: usize = 31;
+
+/// 局部刷新标记：最高位置 1，低 2 位为段序号。
+pub const PARTIAL_MARKER: u8 = 0x80;
+
+/// 头部俯仰关节的索引。
+pub const JOINT_HEAD: usize = 0;
+
+/// 身体旋转关节的索引。
+pub const JOINT_BODY: usize = 5;
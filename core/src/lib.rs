@@ -0,0 +1,102 @@
+//! ElectronBot 协议核心
+//!
+//! 与主机平台无关的协议部分：帧布局、扩展数据编码、关节角度序列化和分包。
+//! 只依赖 `core` 和 `alloc`，ESP32、没有 libusb 的嵌入式 Linux 等主机可以直接使用，
+//! 构造的包与 `electron-bot` 发送的完全相同。USB 收发、图片处理等由 `electron-bot` 提供。
+//!
+//! ```
+//! use electron_bot_core::{packet, ExtraData, JointAngles, ProtocolProfile, FRAME_SIZE};
+//!
+//! let profile = ProtocolProfile::STOCK;
+//! let frame = vec![0u8; FRAME_SIZE];
+//! let mut extra = ExtraData::new();
+//! extra.set_joint_angles(&JointAngles([0.0, 0.0, 30.0, 0.0, 30.0, 0.0]), true);
+//!
+//! for segment in 0..profile.cycles {
+//!     // 先读取 MCU 的 32 字节请求，再发送这些包
+//!     for packet in packet::segment_packets(&profile, &frame, segment) {
+//!         assert_eq!(packet.len(), 512);
+//!     }
+//!     let tail = packet::tail_packet(&profile, &frame, &extra, segment, false);
+//!     assert_eq!(tail.len(), 224);
+//! }
+//! ```
+//!
+//! # 模块
+//!
+//! - [`constants`] - 设备 ID、帧布局和关节索引
+//! - [`types`] - 角度和关节角度
+//! - [`extra_data`] - 扩展数据编码
+//! - [`protocol`] - 传输布局
+//! - [`packet`] - 分包
+
+#![no_std]
+
+extern crate alloc;
+
+pub mod constants;
+pub mod extra_data;
+pub mod packet;
+pub mod protocol;
+pub mod types;
+
+pub use constants::*;
+pub use extra_data::ExtraData;
+pub use protocol::{ProtocolProfile, EXTRA_SIZE};
+pub use types::{Angle, JointAngles, POSE_NAMES};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_joint_angles_bytes() {
+        let angles = JointAngles([1.0, -2.5, 30.0, 0.0, 45.0, -60.0]);
+        let bytes = angles.to_bytes();
+        assert_eq!(&bytes[4..8], &(-2.5f32).to_le_bytes());
+        assert_eq!(JointAngles::from_bytes(&bytes), angles);
+
+        let mut extra = ExtraData::new();
+        extra.set_joint_angles(&angles, true);
+        assert_eq!(extra.data[0], 1);
+        assert_eq!(&extra.data[1..25], &bytes);
+        assert_eq!(extra.get_joint_angles(), angles);
+    }
+
+    #[test]
+    fn test_packetization() {
+        let profile = ProtocolProfile::STOCK;
+        let frame: Vec<u8> = (0..FRAME_SIZE).map(|i| (i % 251) as u8).collect();
+        let mut extra = ExtraData::new();
+        extra.set_enable(true);
+
+        // 所有段的整包和尾包中的图片数据按顺序拼起来正好是一帧
+        let mut sent = Vec::new();
+        for segment in 0..profile.cycles {
+            let packets = packet::segment_packets(&profile, &frame, segment);
+            assert_eq!(packets.len(), PACKET_COUNT);
+            for packet in packets {
+                sent.extend_from_slice(packet);
+            }
+            let tail = packet::tail_packet(&profile, &frame, &extra, segment, true);
+            assert_eq!(tail.len(), TAIL_SIZE);
+            assert_eq!(tail[profile.tail_image_size], 1);
+            assert_eq!(
+                tail[profile.tail_image_size + PARTIAL_MARKER_OFFSET],
+                PARTIAL_MARKER | segment as u8
+            );
+            sent.extend_from_slice(&tail[..profile.tail_image_size]);
+        }
+        assert_eq!(sent, frame);
+
+        let profile = ProtocolProfile::for_panel(160, 80, 2, 512).unwrap();
+        let frame = alloc::vec![7u8; profile.frame_size()];
+        let tail = packet::tail_packet(&profile, &frame, &extra, 1, false);
+        assert_eq!(tail.len(), profile.tail_size());
+        assert_eq!(
+            packet::segment_packets(&profile, &frame, 1).len(),
+            profile.packet_count
+        );
+    }
+}
@@ -0,0 +1,52 @@
+//! 分包：把一帧和扩展数据切成 USB 包。
+//!
+//! 每个同步周期，主机先读取 MCU 的请求（`request_size` 字节），再发送一段图片：
+//! `packet_count` 个整包，加一个尾包（这一段余下的图片数据后接 32 字节扩展数据）。
+//! [`segment_packets`] 和 [`tail_packet`] 给出一个周期要发送的全部数据，收发由调用方完成。
+
+use alloc::vec::Vec;
+use core::slice::ChunksExact;
+
+use crate::constants::{PARTIAL_MARKER, PARTIAL_MARKER_OFFSET};
+use crate::extra_data::ExtraData;
+use crate::protocol::ProtocolProfile;
+
+/// 第 `segment` 段的整包（每个 `packet_size` 字节）。
+///
+/// `frame` 的长度必须至少为 `profile.frame_size()`，`segment` 必须小于 `profile.cycles`，否则 panic。
+pub fn segment_packets<'a>(
+    profile: &ProtocolProfile,
+    frame: &'a [u8],
+    segment: usize,
+) -> ChunksExact<'a, u8> {
+    let start = segment * profile.segment_size();
+    let end = start + profile.packet_count * profile.packet_size;
+    frame[start..end].chunks_exact(profile.packet_size)
+}
+
+/// 第 `segment` 段的尾包：余下的图片数据加扩展数据。
+///
+/// `marked` 为 `true` 时按局部刷新协议在扩展数据的最后一个字节标记段序号。
+/// 长度要求与 [`segment_packets`] 相同。
+pub fn tail_packet(
+    profile: &ProtocolProfile,
+    frame: &[u8],
+    extra_data: &ExtraData,
+    segment: usize,
+    marked: bool,
+) -> Vec<u8> {
+    let start = segment * profile.segment_size() + profile.packet_count * profile.packet_size;
+    let image_tail = profile.tail_image_size;
+    let mut tail = Vec::with_capacity(profile.tail_size());
+    tail.extend_from_slice(&frame[start..start + image_tail]);
+    tail.extend_from_slice(extra_data.as_data());
+    if marked {
+        tail[image_tail + PARTIAL_MARKER_OFFSET] = partial_marker(segment);
+    }
+    tail
+}
+
+/// 局部刷新时第 `segment` 段的标记字节。
+pub fn partial_marker(segment: usize) -> u8 {
+    PARTIAL_MARKER | (segment as u8 & 0b11)
+}
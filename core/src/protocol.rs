@@ -0,0 +1,141 @@
+//! 一帧在 USB 上的传输布局。
+//!
+//! [`ProtocolProfile`] 描述一帧在 USB 上的布局：屏幕尺寸、每帧的同步周期数、
+//! 每个周期的整包数和包大小，以及尾包中图片数据的字节数（尾包最后附加 32 字节扩展数据）。
+//! 原版固件使用 [`ProtocolProfile::STOCK`]：240×240，4 个周期，每周期 84 个 512 字节的包加
+//! 224 字节的尾包。屏幕或分段方式不同的固件分支只需要换一个参数表，不必修改 `constants.rs`。
+
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use crate::constants::{FRAME_HEIGHT, FRAME_WIDTH, PACKET_COUNT, PACKET_SIZE, TAIL_SIZE};
+
+/// 扩展数据（舵机控制）的字节数。
+pub const EXTRA_SIZE: usize = 32;
+
+/// 一帧的传输布局。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProtocolProfile {
+    /// 屏幕宽度（像素）。
+    pub width: usize,
+    /// 屏幕高度（像素）。
+    pub height: usize,
+    /// 每帧的同步周期数。
+    pub cycles: usize,
+    /// 每个周期的整包数。
+    pub packet_count: usize,
+    /// 整包的字节数。
+    pub packet_size: usize,
+    /// 尾包中图片数据的字节数。
+    pub tail_image_size: usize,
+    /// 每个周期开始时 MCU 请求的字节数。
+    pub request_size: usize,
+}
+
+impl ProtocolProfile {
+    /// 原版固件的参数。
+    pub const STOCK: Self = Self {
+        width: FRAME_WIDTH,
+        height: FRAME_HEIGHT,
+        cycles: 4,
+        packet_count: PACKET_COUNT,
+        packet_size: PACKET_SIZE,
+        tail_image_size: TAIL_SIZE - EXTRA_SIZE,
+        request_size: 32,
+    };
+
+    /// 按屏幕尺寸和周期数计算布局：每个周期尽量用 `packet_size` 字节的整包，余下的放进尾包。
+    ///
+    /// 行数不能被周期数整除或尾包中放不下余下的数据时返回错误。
+    pub fn for_panel(
+        width: usize,
+        height: usize,
+        cycles: usize,
+        packet_size: usize,
+    ) -> Result<Self, String> {
+        if cycles == 0 || packet_size == 0 {
+            return Err("周期数和包大小必须大于 0".to_string());
+        }
+        if !height.is_multiple_of(cycles) {
+            return Err(format!("{} 行不能平均分成 {} 个周期", height, cycles));
+        }
+        let segment = width * (height / cycles) * 3;
+        let mut packet_count = segment / packet_size;
+        let mut tail_image_size = segment % packet_size;
+        // 尾包还要附加扩展数据，超过包大小时少发一个整包
+        if tail_image_size + EXTRA_SIZE > packet_size && packet_count > 0 {
+            packet_count -= 1;
+            tail_image_size += packet_size;
+        }
+        let profile = Self {
+            width,
+            height,
+            cycles,
+            packet_count,
+            packet_size,
+            tail_image_size,
+            ..Self::STOCK
+        };
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    /// 修改每个周期开始时 MCU 请求的字节数（构建器风格）。
+    pub fn request_size(mut self, size: usize) -> Self {
+        self.request_size = size;
+        self
+    }
+
+    /// 一帧的字节数（BGR，每像素 3 字节）。
+    pub fn frame_size(&self) -> usize {
+        self.width * self.height * 3
+    }
+
+    /// 每个周期发送的图片字节数。
+    pub fn segment_size(&self) -> usize {
+        self.packet_count * self.packet_size + self.tail_image_size
+    }
+
+    /// 每个周期发送的行数。
+    pub fn segment_rows(&self) -> usize {
+        self.height / self.cycles.max(1)
+    }
+
+    /// 尾包的字节数（图片数据加扩展数据）。
+    pub fn tail_size(&self) -> usize {
+        self.tail_image_size + EXTRA_SIZE
+    }
+
+    /// 每帧的包数（包括尾包）。
+    pub fn packets_per_frame(&self) -> usize {
+        self.cycles * (self.packet_count + 1)
+    }
+
+    /// 检查各参数是否一致：所有周期正好覆盖一帧。
+    pub fn validate(&self) -> Result<(), String> {
+        if self.width == 0 || self.height == 0 {
+            return Err("屏幕尺寸必须大于 0".to_string());
+        }
+        if self.cycles == 0 || self.packet_size == 0 {
+            return Err("周期数和包大小必须大于 0".to_string());
+        }
+        if self.request_size == 0 || self.request_size > EXTRA_SIZE {
+            return Err(format!("请求长度必须在 1-{} 字节之间", EXTRA_SIZE));
+        }
+        if self.segment_size() * self.cycles != self.frame_size() {
+            return Err(format!(
+                "{} 个周期共 {} 字节，与一帧的 {} 字节不一致",
+                self.cycles,
+                self.segment_size() * self.cycles,
+                self.frame_size()
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ProtocolProfile {
+    fn default() -> Self {
+        Self::STOCK
+    }
+}
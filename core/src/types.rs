@@ -0,0 +1,238 @@
+//! 角度和关节角度。
+
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// 内置姿态的名称。
+pub const POSE_NAMES: [&str; 7] = [
+    "rest",
+    "arms-up",
+    "arms-out",
+    "look-up",
+    "look-down",
+    "turn-left",
+    "turn-right",
+];
+
+/// 带单位的角度。
+///
+/// 舵机和本库的 `f32` 角度接口都以度为单位，三角函数和逆运动学通常使用弧度；
+/// 用 `Angle` 传递时必须写明单位，不会把弧度当成度发给舵机。
+///
+/// ```
+/// use electron_bot_core::Angle;
+///
+/// let a = Angle::radians(core::f32::consts::FRAC_PI_2);
+/// assert!((a.to_degrees() - 90.0).abs() < 1e-4);
+/// assert_eq!(Angle::degrees(30.0) * 2.0, Angle::degrees(60.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Angle(f32);
+
+impl Angle {
+    /// 零角度。
+    pub const ZERO: Angle = Angle(0.0);
+
+    /// 以度为单位创建。
+    pub fn degrees(degrees: f32) -> Self {
+        Self(degrees)
+    }
+
+    /// 以弧度为单位创建。
+    pub fn radians(radians: f32) -> Self {
+        Self(radians.to_degrees())
+    }
+
+    /// 转换为度。
+    pub fn to_degrees(self) -> f32 {
+        self.0
+    }
+
+    /// 转换为弧度。
+    pub fn to_radians(self) -> f32 {
+        self.0.to_radians()
+    }
+
+    /// 绝对值。
+    pub fn abs(self) -> Self {
+        Self(self.0.abs())
+    }
+
+    /// 限制在 `min` 和 `max` 之间。
+    pub fn clamp(self, min: Angle, max: Angle) -> Self {
+        Self(self.0.clamp(min.0, max.0))
+    }
+
+    /// 规范化到 (-180°, 180°]。
+    pub fn normalized(self) -> Self {
+        // `f32::rem_euclid` 需要 std，`%` 的结果与被除数同号，负数时加一圈
+        let mut wrapped = (self.0 + 180.0) % 360.0;
+        if wrapped < 0.0 {
+            wrapped += 360.0;
+        }
+        let wrapped = wrapped - 180.0;
+        Self(if wrapped == -180.0 { 180.0 } else { wrapped })
+    }
+}
+
+impl core::fmt::Display for Angle {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*}°", precision, self.0),
+            None => write!(f, "{}°", self.0),
+        }
+    }
+}
+
+impl Add for Angle {
+    type Output = Angle;
+
+    fn add(self, other: Angle) -> Angle {
+        Angle(self.0 + other.0)
+    }
+}
+
+impl Sub for Angle {
+    type Output = Angle;
+
+    fn sub(self, other: Angle) -> Angle {
+        Angle(self.0 - other.0)
+    }
+}
+
+impl Neg for Angle {
+    type Output = Angle;
+
+    fn neg(self) -> Angle {
+        Angle(-self.0)
+    }
+}
+
+impl Mul<f32> for Angle {
+    type Output = Angle;
+
+    fn mul(self, factor: f32) -> Angle {
+        Angle(self.0 * factor)
+    }
+}
+
+impl Div<f32> for Angle {
+    type Output = Angle;
+
+    fn div(self, divisor: f32) -> Angle {
+        Angle(self.0 / divisor)
+    }
+}
+
+impl AddAssign for Angle {
+    fn add_assign(&mut self, other: Angle) {
+        self.0 += other.0;
+    }
+}
+
+impl SubAssign for Angle {
+    fn sub_assign(&mut self, other: Angle) {
+        self.0 -= other.0;
+    }
+}
+
+/// 6 个舵机的角度（度）。
+#[derive(Debug, Clone, PartialEq)]
+pub struct JointAngles(pub [f32; 6]);
+
+impl JointAngles {
+    /// 创建新的角度数组（初始化为零）。
+    pub fn new() -> Self {
+        Self([0.0; 6])
+    }
+
+    /// 按名称查找内置姿态（见 [`POSE_NAMES`]）。
+    pub fn named(name: &str) -> Option<Self> {
+        let angles = match name {
+            "rest" => [0.0; 6],
+            "arms-up" => [0.0, 0.0, 55.0, 0.0, 55.0, 0.0],
+            "arms-out" => [0.0, 25.0, 20.0, 25.0, 20.0, 0.0],
+            "look-up" => [10.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+            "look-down" => [-12.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+            "turn-left" => [0.0, 0.0, 0.0, 0.0, 0.0, 30.0],
+            "turn-right" => [0.0, 0.0, 0.0, 0.0, 0.0, -30.0],
+            _ => return None,
+        };
+        Some(Self(angles))
+    }
+
+    /// 获取角度数组的引用。
+    pub fn as_array(&self) -> &[f32; 6] {
+        &self.0
+    }
+
+    /// 获取角度数组的可变引用。
+    pub fn as_array_mut(&mut self) -> &mut [f32; 6] {
+        &mut self.0
+    }
+
+    /// 通过索引获取单个角度（0-5）。
+    pub fn get(&self, index: usize) -> Option<f32> {
+        self.0.get(index).copied()
+    }
+
+    /// 通过索引设置单个角度（0-5）。
+    pub fn set(&mut self, index: usize, value: f32) -> Option<()> {
+        self.0.get_mut(index).map(|v| *v = value)
+    }
+
+    /// 从带单位的角度创建。
+    pub fn from_angles(angles: [Angle; 6]) -> Self {
+        Self(angles.map(Angle::to_degrees))
+    }
+
+    /// 从弧度创建。
+    pub fn from_radians(radians: [f32; 6]) -> Self {
+        Self(radians.map(f32::to_degrees))
+    }
+
+    /// 转换为带单位的角度。
+    pub fn to_angles(&self) -> [Angle; 6] {
+        self.0.map(Angle::degrees)
+    }
+
+    /// 转换为弧度。
+    pub fn to_radians(&self) -> [f32; 6] {
+        self.0.map(f32::to_radians)
+    }
+
+    /// 通过索引获取带单位的角度（0-5）。
+    pub fn angle(&self, index: usize) -> Option<Angle> {
+        self.get(index).map(Angle::degrees)
+    }
+
+    /// 通过索引设置带单位的角度（0-5）。
+    pub fn set_angle(&mut self, index: usize, angle: Angle) -> Option<()> {
+        self.set(index, angle.to_degrees())
+    }
+
+    /// 转换为字节（小端序）。
+    pub fn to_bytes(&self) -> [u8; 24] {
+        let mut bytes = [0u8; 24];
+        for (i, &val) in self.0.iter().enumerate() {
+            bytes[i * 4..i * 4 + 4].copy_from_slice(&val.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// 从字节创建（小端序）。
+    pub fn from_bytes(bytes: &[u8; 24]) -> Self {
+        let mut angles = [0.0f32; 6];
+        for i in 0..6 {
+            let mut val_bytes = [0u8; 4];
+            val_bytes.copy_from_slice(&bytes[i * 4..i * 4 + 4]);
+            angles[i] = f32::from_le_bytes(val_bytes);
+        }
+        Self(angles)
+    }
+}
+
+impl Default for JointAngles {
+    fn default() -> Self {
+        Self::new()
+    }
+}
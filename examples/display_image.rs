@@ -12,10 +12,9 @@
 //! - 自动缩放到 240x240
 //! - 同时控制舵机做循环运动
 
-use electron_bot::ElectronBot;
+use electron_bot::{ElectronBot, FramePacer};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::thread;
 use std::time::Duration;
 
 /// 图片文件路径
@@ -94,6 +93,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     .expect("无法设置 Ctrl+C 处理器");
 
     let mut motion_step: i32 = 0;
+    let mut pacer = FramePacer::with_interval(Duration::from_millis(motion_interval_ms));
 
     while running.load(Ordering::SeqCst) {
         // 计算当前角度（正弦波形运动）
@@ -133,11 +133,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             motion_step = 0;
         }
 
-        // 控制循环速度
-        thread::sleep(Duration::from_millis(motion_interval_ms));
+        // 控制循环速度（按固定时间表等待，同步耗时不会累积成漂移）
+        pacer.wait();
     }
 
     println!();
+    print!("{}", pacer.stats());
     println!("正在断开连接...");
 
     // 停止舵机
@@ -0,0 +1,48 @@
+//! 表情引擎示例
+//!
+//! 在预设表情之间依次平滑过渡，每个表情停留约 2 秒。
+//!
+//! 运行方式：
+//! ```bash
+//! cargo run --example face
+//! ```
+
+use electron_bot::{ElectronBot, Expression, Face};
+use std::time::Duration;
+
+/// 每个表情播放的帧数（30 FPS）
+const FRAMES_PER_EXPRESSION: u64 = 60;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "logging")]
+    env_logger::init();
+
+    println!("=== ElectronBot 表情引擎示例 ===");
+
+    let mut bot = ElectronBot::new();
+    println!("正在连接设备...");
+    if let Err(e) = bot.connect() {
+        eprintln!("连接失败: {:?}", e);
+        return Ok(());
+    }
+
+    let expressions = [
+        ("平静", Expression::NEUTRAL),
+        ("开心", Expression::HAPPY),
+        ("惊讶", Expression::SURPRISED),
+        ("难过", Expression::SAD),
+        ("生气", Expression::ANGRY),
+        ("困倦", Expression::SLEEPY),
+        ("看向右上", Expression::NEUTRAL.look(0.8, -0.6)),
+    ];
+
+    let mut face = Face::new();
+    for (name, expression) in expressions {
+        println!("{}", name);
+        face.transition_to(expression, Duration::from_millis(400));
+        bot.play(&mut face, Some(FRAMES_PER_EXPRESSION))?;
+    }
+
+    bot.disconnect();
+    Ok(())
+}
@@ -0,0 +1,46 @@
+//! HTTP 接口示例
+//!
+//! 连接机器人后在 8080 端口提供 HTTP 接口，例如：
+//! ```bash
+//! curl -X POST --data-binary @photo.jpg http://localhost:8080/image
+//! curl -X POST -d '[0, 0, 10, 0, 10, 0]' http://localhost:8080/joints
+//! curl -X POST http://localhost:8080/gesture/wave
+//! curl http://localhost:8080/status
+//! ```
+//!
+//! 运行方式（默认只监听本机；监听局域网时设置访问令牌）：
+//! ```bash
+//! cargo run --example http_server --features http
+//! ELECTRONBOT_HTTP_TOKEN=secret cargo run --example http_server --features http -- 0.0.0.0:8080
+//! curl -H 'Authorization: Bearer secret' http://robot.local:8080/status
+//! ```
+
+use electron_bot::{ElectronBot, FitMode, FitOptions, HttpServer, DEFAULT_HTTP_ADDR};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "logging")]
+    env_logger::init();
+
+    println!("=== ElectronBot HTTP 接口示例 ===");
+
+    let addr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_HTTP_ADDR.to_string());
+
+    let mut bot = ElectronBot::new();
+    // 上传的图片保持比例缩放并居中裁剪
+    bot.set_fit_options(FitOptions::new(FitMode::Cover));
+    println!("正在连接设备...");
+    if let Err(e) = bot.connect() {
+        eprintln!("连接失败: {:?}", e);
+        return Ok(());
+    }
+
+    let mut server = HttpServer::bind(&addr)?;
+    if let Ok(token) = std::env::var("ELECTRONBOT_HTTP_TOKEN") {
+        server = server.token(&token);
+    }
+    println!("HTTP 接口: http://{}", server.local_addr()?);
+    server.serve(&mut bot)?;
+    Ok(())
+}
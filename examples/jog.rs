@@ -0,0 +1,34 @@
+//! 键盘点动示例
+//!
+//! WASD 控制头部俯仰和身体旋转，方向键控制双臂，`+`/`-` 调整步长，
+//! `0` 回到初始姿态，空格使能或释放舵机，`q` 退出。
+//!
+//! 运行方式：
+//! ```bash
+//! cargo run --example jog --features keyboard
+//! ```
+
+use electron_bot::{ElectronBot, KeyboardTeleop, ServoSource};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "logging")]
+    env_logger::init();
+
+    println!("=== ElectronBot 键盘点动示例 ===");
+
+    let mut bot = ElectronBot::new();
+    println!("正在连接设备...");
+    if let Err(e) = bot.connect() {
+        eprintln!("连接失败: {:?}", e);
+        return Ok(());
+    }
+    println!("WASD: 头部/身体  方向键: 双臂  +/-: 步长  0: 复位  空格: 使能/释放  q: 退出");
+
+    // 标定时转动慢一些
+    let mut teleop = KeyboardTeleop::new()?.servo(ServoSource::new().max_speed(Some(45.0)));
+    bot.play(&mut teleop, None)?;
+    drop(teleop);
+
+    println!("当前角度: {:?}", bot.get_joint_angles().as_array());
+    Ok(())
+}
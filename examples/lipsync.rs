@@ -0,0 +1,53 @@
+//! 口型同步示例
+//!
+//! 从标准输入读取 16 位小端单声道 PCM，按音量驱动表情的嘴型。
+//!
+//! 运行方式（Linux 麦克风）：
+//! ```bash
+//! arecord -f S16_LE -r 16000 -c 1 | cargo run --example lipsync --features audio
+//! ```
+//!
+//! 或播放音频文件：
+//! ```bash
+//! ffmpeg -i speech.wav -f s16le -ac 1 -ar 16000 - | cargo run --example lipsync --features audio
+//! ```
+
+use electron_bot::{ElectronBot, IdleBehavior, LipSync};
+use std::io::Read;
+
+/// 每次读取的采样数（16kHz 下约 20ms）
+const CHUNK_SAMPLES: usize = 320;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "logging")]
+    env_logger::init();
+
+    println!("=== ElectronBot 口型同步示例 ===");
+
+    let mut bot = ElectronBot::new();
+    println!("正在连接设备...");
+    if let Err(e) = bot.connect() {
+        eprintln!("连接失败: {:?}", e);
+        return Ok(());
+    }
+
+    let lip_sync = LipSync::new();
+    let level = lip_sync.input();
+    std::thread::spawn(move || {
+        let mut stdin = std::io::stdin().lock();
+        let mut bytes = [0u8; CHUNK_SAMPLES * 2];
+        let mut samples = [0i16; CHUNK_SAMPLES];
+        while stdin.read_exact(&mut bytes).is_ok() {
+            for (s, b) in samples.iter_mut().zip(bytes.chunks_exact(2)) {
+                *s = i16::from_le_bytes([b[0], b[1]]);
+            }
+            level.push_samples_i16(&samples);
+        }
+    });
+
+    let mut idle = IdleBehavior::new().lip_sync(lip_sync);
+    bot.play(&mut idle, None)?;
+
+    bot.disconnect();
+    Ok(())
+}
@@ -0,0 +1,33 @@
+//! 镜子示例
+//!
+//! 采集电脑摄像头画面，居中裁剪为正方形并镜像显示到 ElectronBot 屏幕上。
+//!
+//! 运行方式（需要系统安装 ffmpeg）：
+//! ```bash
+//! cargo run --example mirror --features camera
+//! # 指定设备
+//! cargo run --example mirror --features camera -- /dev/video2
+//! ```
+
+use electron_bot::{CameraOptions, CameraSource, ElectronBot};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "logging")]
+    env_logger::init();
+
+    let device = std::env::args().nth(1).unwrap_or_else(|| "0".to_string());
+    let options = CameraOptions::new(&device).frame_rate(30);
+
+    let mut bot = ElectronBot::new();
+    if let Err(e) = bot.connect() {
+        eprintln!("连接失败: {:?}", e);
+        return Ok(());
+    }
+
+    println!("正在打开摄像头 {}...", device);
+    let mut camera = CameraSource::open(&options)?;
+    let stats = bot.play(&mut camera, None)?;
+    println!("采集结束: 发送 {} 帧", stats.frames);
+    bot.disconnect();
+    Ok(())
+}
@@ -0,0 +1,50 @@
+//! MQTT 接入示例
+//!
+//! 连接机器人和 MQTT 服务器，在 Home Assistant 中自动出现为设备，例如：
+//! ```bash
+//! mosquitto_pub -h 192.168.1.2 -t electronbot/electronbot/expression/set -m happy
+//! mosquitto_pub -h 192.168.1.2 -t electronbot/electronbot/gesture/set -m wave
+//! mosquitto_sub -h 192.168.1.2 -t 'electronbot/#' -v
+//! ```
+//!
+//! 运行方式：
+//! ```bash
+//! cargo run --example mqtt --features mqtt,embedded-font -- 192.168.1.2:1883 [客户端标识]
+//! ```
+
+use electron_bot::{ElectronBot, FitMode, FitOptions, MqttBridge, MqttOptions};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "logging")]
+    env_logger::init();
+
+    println!("=== ElectronBot MQTT 接入示例 ===");
+
+    let mut args = std::env::args().skip(1);
+    let broker = args.next().unwrap_or_else(|| "127.0.0.1:1883".to_string());
+    let (host, port) = match broker.rsplit_once(':') {
+        Some((host, port)) => (host.to_string(), port.parse()?),
+        None => (broker, 1883),
+    };
+    let mut options = MqttOptions::new(&host, port);
+    if let Some(id) = args.next() {
+        options = options.client_id(&id);
+    }
+    if let (Ok(user), Ok(password)) = (std::env::var("MQTT_USER"), std::env::var("MQTT_PASSWORD")) {
+        options = options.credentials(&user, &password);
+    }
+
+    let mut bot = ElectronBot::new();
+    // 下载的图片保持比例缩放并居中裁剪
+    bot.set_fit_options(FitOptions::new(FitMode::Cover));
+    println!("正在连接设备...");
+    if let Err(e) = bot.connect() {
+        eprintln!("连接失败: {:?}", e);
+        return Ok(());
+    }
+
+    let mut bridge = MqttBridge::connect(options)?;
+    println!("已连接 MQTT 服务器，主题前缀: {}", bridge.topic_prefix());
+    bridge.run(&mut bot)?;
+    Ok(())
+}
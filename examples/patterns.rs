@@ -0,0 +1,60 @@
+//! 程序化图案示例
+//!
+//! 依次播放彩条、滚动棋盘格、彩虹渐变、等离子、Perlin 噪声和坏点测试，
+//! 用于检查屏幕和 USB 吞吐量。
+//!
+//! 运行方式：
+//! ```bash
+//! cargo run --example patterns
+//! ```
+
+use electron_bot::{
+    Checkerboard, ColorBars, DeadPixelTest, ElectronBot, FrameSource, Gradient, GradientKind,
+    GradientSweep, Palette, PerlinNoise, Plasma,
+};
+use std::time::Instant;
+
+/// 每个图案播放的帧数
+const FRAMES_PER_PATTERN: u64 = 90;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "logging")]
+    env_logger::init();
+
+    println!("=== ElectronBot 程序化图案示例 ===");
+
+    let mut bot = ElectronBot::new();
+    println!("正在连接设备...");
+    if let Err(e) = bot.connect() {
+        eprintln!("连接失败: {:?}", e);
+        return Ok(());
+    }
+
+    let sources: Vec<(&str, Box<dyn FrameSource>)> = vec![
+        ("彩条", Box::new(ColorBars::new())),
+        ("棋盘格", Box::new(Checkerboard::new(20).scroll(2))),
+        ("彩虹渐变", Box::new(GradientSweep::rainbow(4.0))),
+        ("等离子", Box::new(Plasma::new())),
+        (
+            "Perlin 噪声",
+            Box::new(PerlinNoise::new(7, 48.0).gradient(Gradient::from_palette(
+                GradientKind::Horizontal,
+                Palette::OCEAN,
+            ))),
+        ),
+        ("坏点测试", Box::new(DeadPixelTest::new(60))),
+    ];
+
+    for (name, mut source) in sources {
+        let start = Instant::now();
+        let stats = bot.play(&mut source, Some(FRAMES_PER_PATTERN))?;
+        let fps = stats.frames as f64 / start.elapsed().as_secs_f64();
+        println!(
+            "{}: {} 帧（丢弃 {} 帧）, {:.1} FPS",
+            name, stats.frames, stats.dropped, fps
+        );
+    }
+
+    bot.disconnect();
+    Ok(())
+}
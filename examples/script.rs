@@ -0,0 +1,38 @@
+//! 行为脚本示例
+//!
+//! 加载 Rhai 脚本驱动屏幕和舵机，修改脚本文件后自动重新加载。
+//!
+//! 运行方式：
+//! ```bash
+//! cargo run --example script --features scripting -- examples/behavior.rhai
+//! ```
+
+use electron_bot::{ElectronBot, ScriptSource};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "logging")]
+    env_logger::init();
+
+    println!("=== ElectronBot 行为脚本示例 ===");
+
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "examples/behavior.rhai".to_string());
+    let mut script = ScriptSource::from_file(&path)?;
+    println!("已加载脚本: {}", path);
+
+    let mut bot = ElectronBot::new();
+    println!("正在连接设备...");
+    if let Err(e) = bot.connect() {
+        eprintln!("连接失败: {:?}", e);
+        return Ok(());
+    }
+
+    // 分段播放，定期报告脚本错误
+    loop {
+        bot.play(&mut script, Some(30))?;
+        if let Some(e) = script.last_error() {
+            eprintln!("{}", e);
+        }
+    }
+}
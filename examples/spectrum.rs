@@ -0,0 +1,64 @@
+//! 音频频谱示例
+//!
+//! 从标准输入读取 16 位小端单声道 PCM，在屏幕上显示实时频谱。
+//!
+//! 运行方式（Linux PulseAudio/PipeWire，采集系统声音输出）：
+//! ```bash
+//! parec -d @DEFAULT_MONITOR@ --format=s16le --channels=1 --rate=44100 \
+//!     | cargo run --example spectrum --features audio
+//! ```
+//!
+//! 加 `radial` 参数使用环形样式：
+//! ```bash
+//! parec -d @DEFAULT_MONITOR@ --format=s16le --channels=1 --rate=44100 \
+//!     | cargo run --example spectrum --features audio -- radial
+//! ```
+
+use electron_bot::{ElectronBot, Palette, Spectrum, SpectrumStyle};
+use std::io::Read;
+
+/// 采样率（与输入一致）
+const SAMPLE_RATE: u32 = 44100;
+/// 每次读取的采样数（44.1kHz 下约 10ms）
+const CHUNK_SAMPLES: usize = 441;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "logging")]
+    env_logger::init();
+
+    println!("=== ElectronBot 音频频谱示例 ===");
+
+    let mut bot = ElectronBot::new();
+    println!("正在连接设备...");
+    if let Err(e) = bot.connect() {
+        eprintln!("连接失败: {:?}", e);
+        return Ok(());
+    }
+
+    let mut spectrum = Spectrum::new(SAMPLE_RATE);
+    if std::env::args().any(|a| a == "radial") {
+        spectrum = spectrum
+            .style(SpectrumStyle::Radial)
+            .bars(48)
+            .palette(Palette::SUNSET);
+    }
+
+    let input = spectrum.input();
+    std::thread::spawn(move || {
+        let mut stdin = std::io::stdin().lock();
+        let mut bytes = [0u8; CHUNK_SAMPLES * 2];
+        let mut samples = [0i16; CHUNK_SAMPLES];
+        while stdin.read_exact(&mut bytes).is_ok() {
+            for (s, b) in samples.iter_mut().zip(bytes.chunks_exact(2)) {
+                *s = i16::from_le_bytes([b[0], b[1]]);
+            }
+            input.push_samples_i16(&samples);
+        }
+    });
+
+    let stats = bot.play(&mut spectrum, None)?;
+    println!("发送 {} 帧，丢弃 {} 帧", stats.frames, stats.dropped);
+
+    bot.disconnect();
+    Ok(())
+}
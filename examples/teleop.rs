@@ -0,0 +1,34 @@
+//! 手柄遥控示例
+//!
+//! 左摇杆控制头部俯仰和身体旋转，右摇杆控制双臂，A/B/X/Y 切换表情，
+//! Select 回到初始姿态，Start 使能或释放舵机。
+//!
+//! 运行方式：
+//! ```bash
+//! cargo run --example teleop --features gamepad
+//! ```
+
+use electron_bot::{ElectronBot, GamepadTeleop, ServoSource};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "logging")]
+    env_logger::init();
+
+    println!("=== ElectronBot 手柄遥控示例 ===");
+
+    let mut teleop = GamepadTeleop::new()?.servo(ServoSource::new().max_speed(Some(120.0)));
+    match teleop.gamepad_name() {
+        Some(name) => println!("使用手柄: {}", name),
+        None => println!("未找到手柄，等待连接..."),
+    }
+
+    let mut bot = ElectronBot::new();
+    println!("正在连接设备...");
+    if let Err(e) = bot.connect() {
+        eprintln!("连接失败: {:?}", e);
+        return Ok(());
+    }
+
+    bot.play(&mut teleop, None)?;
+    Ok(())
+}
@@ -11,10 +11,9 @@
 //! - 每 2 秒切换一次随机色块图案（40x40 平铺）
 //! - 所有关节同时循环运动 ±20 度
 
-use electron_bot::ElectronBot;
+use electron_bot::{ElectronBot, FramePacer};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::thread;
 use std::time::Duration;
 
 /// 色块大小
@@ -78,6 +77,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut current_pattern: Option<electron_bot::ImageBuffer> = None;
     let mut motion_step: i32 = 0;
+    let mut pacer = FramePacer::with_interval(Duration::from_millis(motion_interval_ms));
     let mut last_pattern_time = std::time::Instant::now();
 
     while running.load(Ordering::SeqCst) {
@@ -153,11 +153,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             motion_step = 0;
         }
 
-        // 控制循环速度
-        thread::sleep(Duration::from_millis(motion_interval_ms));
+        // 控制循环速度（按固定时间表等待，同步耗时不会累积成漂移）
+        pacer.wait();
     }
 
     println!();
+    print!("{}", pacer.stats());
     println!("正在断开连接...");
 
     // 停止舵机
@@ -0,0 +1,46 @@
+//! 文字显示示例
+//!
+//! 展示如何使用 TTF 字体在 ElectronBot 屏幕上显示文字。
+//!
+//! 运行方式：
+//! ```bash
+//! cargo run --example text --features text -- path/to/font.ttf "你好"
+//! ```
+
+use electron_bot::{Color, ElectronBot, Font};
+
+/// 字体大小（像素）
+const FONT_SIZE: f32 = 48.0;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let font_path = match args.next() {
+        Some(p) => p,
+        None => {
+            eprintln!("用法: text <字体文件> [文字]");
+            return Ok(());
+        }
+    };
+    let text = args.next().unwrap_or_else(|| "Hello".to_string());
+
+    let font = Font::load_from_file(&font_path)?;
+    let (width, height) = font.measure(&text, FONT_SIZE);
+    println!("文字尺寸: {:.1}x{:.1}", width, height);
+
+    let mut bot = ElectronBot::new();
+    println!("正在连接设备...");
+    bot.connect()?;
+
+    // 居中绘制
+    let x = ((electron_bot::FRAME_WIDTH as f32 - width) / 2.0) as i32;
+    let y = ((electron_bot::FRAME_HEIGHT as f32 - height) / 2.0) as i32;
+    let buffer = bot.image_buffer();
+    buffer.clear(Color::Black);
+    buffer.draw_text(x, y, &text, &font, FONT_SIZE, Color::White);
+
+    bot.sync()?;
+    println!("文字已显示");
+
+    bot.disconnect();
+    Ok(())
+}
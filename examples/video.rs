@@ -0,0 +1,43 @@
+//! 视频播放示例
+//!
+//! 通过 ffmpeg 解码视频文件，保持宽高比缩放到 240x240 并按源帧率播放。
+//! USB 发送跟不上时会自动丢帧。
+//!
+//! 运行方式（需要系统安装 ffmpeg）：
+//! ```bash
+//! cargo run --example video --features video -- path/to/video.mp4
+//! ```
+
+use electron_bot::{ElectronBot, FrameSource, VideoSource};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "logging")]
+    env_logger::init();
+
+    let path = match std::env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("用法: video <视频文件>");
+            return Ok(());
+        }
+    };
+
+    let mut video = VideoSource::open(&path)?.looping(true);
+    println!("视频帧率: {:.2} FPS", video.fps());
+    println!("帧间隔: {:?}", video.frame_interval());
+
+    let mut bot = ElectronBot::new();
+    if let Err(e) = bot.connect() {
+        eprintln!("连接失败: {:?}", e);
+        return Ok(());
+    }
+
+    // 解码下一帧与发送当前帧并行
+    let stats = bot.play_pipelined(&mut video, None)?;
+    println!(
+        "播放结束: 发送 {} 帧, 丢弃 {} 帧",
+        stats.frames, stats.dropped
+    );
+    bot.disconnect();
+    Ok(())
+}
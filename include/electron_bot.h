@@ -0,0 +1,267 @@
+#ifndef ELECTRON_BOT_H
+#define ELECTRON_BOT_H
+
+/* 由 cbindgen 生成，请勿手动修改 */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/*
+ C 接口的错误码。
+ */
+typedef enum EbError {
+  /*
+   成功。
+   */
+  EB_ERROR_OK = 0,
+  /*
+   传入了空指针。
+   */
+  EB_ERROR_NULL_POINTER = 1,
+  /*
+   参数无效（如图片尺寸为 0、数据长度不足）。
+   */
+  EB_ERROR_INVALID_ARGUMENT = 2,
+  /*
+   未找到设备。
+   */
+  EB_ERROR_DEVICE_NOT_FOUND = 3,
+  /*
+   USB 错误。
+   */
+  EB_ERROR_USB = 4,
+  /*
+   发送数据失败。
+   */
+  EB_ERROR_SEND_FAILED = 5,
+  /*
+   接收数据失败。
+   */
+  EB_ERROR_RECEIVE_FAILED = 6,
+  /*
+   图片错误。
+   */
+  EB_ERROR_IMAGE = 7,
+  /*
+   未连接到设备。
+   */
+  EB_ERROR_NOT_CONNECTED = 8,
+  /*
+   未找到接口。
+   */
+  EB_ERROR_INTERFACE_NOT_FOUND = 9,
+  /*
+   其他错误（统计、配置）。
+   */
+  EB_ERROR_OTHER = 10,
+  /*
+   库内部发生 panic。
+   */
+  EB_ERROR_PANIC = 11,
+} EbError;
+
+/*
+ 图片适配方式。
+ */
+typedef enum EbFitMode {
+  /*
+   拉伸填满屏幕（默认）。
+   */
+  EB_FIT_MODE_STRETCH = 0,
+  /*
+   保持宽高比完整显示。
+   */
+  EB_FIT_MODE_CONTAIN = 1,
+  /*
+   保持宽高比填满屏幕，居中裁剪。
+   */
+  EB_FIT_MODE_COVER = 2,
+  /*
+   不缩放，居中显示。
+   */
+  EB_FIT_MODE_CENTER = 3,
+} EbFitMode;
+
+/*
+ 像素格式。
+ */
+typedef enum EbPixelFormat {
+  /*
+   R、G、B 各 1 字节。
+   */
+  EB_PIXEL_FORMAT_RGB24 = 0,
+  /*
+   R、G、B、A 各 1 字节（Unity 的 `TextureFormat.RGBA32`、`Color32[]`）。
+   */
+  EB_PIXEL_FORMAT_RGBA32 = 1,
+  /*
+   B、G、R、A 各 1 字节（Windows 位图、Unity 的 `TextureFormat.BGRA32`）。
+   */
+  EB_PIXEL_FORMAT_BGRA32 = 2,
+  /*
+   B、G、R 各 1 字节。
+   */
+  EB_PIXEL_FORMAT_BGR24 = 3,
+} EbPixelFormat;
+
+/*
+ 用于与 ElectronBot 通信的主结构体
+ */
+typedef struct ElectronBot ElectronBot;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ 创建实例（不连接），用 [`eb_free`] 释放。
+ */
+ElectronBot *eb_new(void);
+
+/*
+ 断开连接并释放实例。
+
+ # Safety
+
+ `bot` 必须是 [`eb_new`] 返回的指针或空指针，释放后不能再使用。
+ */
+void eb_free(ElectronBot *bot);
+
+/*
+ 连接到设备。
+
+ # Safety
+
+ `bot` 必须是 [`eb_new`] 返回的有效指针。
+ */
+EbError eb_connect(ElectronBot *bot);
+
+/*
+ 断开设备连接。
+
+ # Safety
+
+ `bot` 必须是 [`eb_new`] 返回的有效指针。
+ */
+void eb_disconnect(ElectronBot *bot);
+
+/*
+ 是否已连接，空指针返回 `false`。
+
+ # Safety
+
+ `bot` 必须是 [`eb_new`] 返回的有效指针或空指针。
+ */
+bool eb_is_connected(const ElectronBot *bot);
+
+/*
+ 设置 RGB 图片（每像素 3 字节），按当前适配方式缩放。
+
+ # Safety
+
+ `bot` 必须是有效指针，`data` 必须指向至少 `width * height * 3` 字节。
+ */
+EbError eb_set_image_rgb(ElectronBot *bot, const uint8_t *data, uint32_t width, uint32_t height);
+
+/*
+ 设置 RGBA 图片（每像素 4 字节，忽略透明度），按当前适配方式缩放。
+
+ # Safety
+
+ `bot` 必须是有效指针，`data` 必须指向至少 `width * height * 4` 字节。
+ */
+EbError eb_set_image_rgba(ElectronBot *bot, const uint8_t *data, uint32_t width, uint32_t height);
+
+/*
+ 设置 JPEG/PNG 等编码后的图片。
+
+ # Safety
+
+ `bot` 必须是有效指针，`data` 必须指向至少 `len` 字节。
+ */
+EbError eb_set_image_bytes(ElectronBot *bot, const uint8_t *data, size_t len);
+
+/*
+ 设置任意行距和像素格式的图片，按当前适配方式缩放。
+
+ `format` 取 [`EbPixelFormat`] 的值，未知的值返回 [`EbError::InvalidArgument`]。
+ `stride` 为每行字节数，0 表示紧密排列；`flip_y` 为 `true` 时数据的第一行是图片底部
+ （Unity 的 `Texture2D.GetRawTextureData`、OpenGL 的 `glReadPixels` 都是这种顺序）。
+
+ # Safety
+
+ `bot` 必须是有效指针，`data` 必须指向至少 `stride * height` 字节。
+ */
+EbError eb_set_image_pixels(ElectronBot *bot,
+                            const uint8_t *data,
+                            uint32_t width,
+                            uint32_t height,
+                            uint32_t stride,
+                            uint32_t format,
+                            bool flip_y);
+
+/*
+ 设置纯色。
+
+ # Safety
+
+ `bot` 必须是 [`eb_new`] 返回的有效指针。
+ */
+EbError eb_set_image_color(ElectronBot *bot, uint8_t r, uint8_t g, uint8_t b);
+
+/*
+ 设置图片适配方式，`mode` 取 [`EbFitMode`] 的值，未知的值返回 [`EbError::InvalidArgument`]。
+
+ # Safety
+
+ `bot` 必须是 [`eb_new`] 返回的有效指针。
+ */
+EbError eb_set_fit_mode(ElectronBot *bot, uint32_t mode);
+
+/*
+ 设置 6 个关节角度（度），`enable` 为 `false` 时释放舵机。
+
+ # Safety
+
+ `bot` 必须是有效指针，`angles` 必须指向 6 个 `float`。
+ */
+EbError eb_set_joints(ElectronBot *bot, const float *angles, bool enable);
+
+/*
+ 读取机器人上报的 6 个关节角度（度），还没有同步过时为设置的目标角度。
+
+ # Safety
+
+ `bot` 必须是有效指针，`out` 必须指向可写入 6 个 `float` 的内存。
+ */
+EbError eb_get_joints(const ElectronBot *bot, float *out);
+
+/*
+ 与机器人同步图片和关节角度。
+
+ # Safety
+
+ `bot` 必须是 [`eb_new`] 返回的有效指针。
+ */
+EbError eb_sync(ElectronBot *bot);
+
+/*
+ 当前线程最后一次的错误信息（UTF-8），没有错误时返回空指针。
+
+ 返回的字符串在同一线程下一次出错前有效，调用方不能释放。
+ */
+const char *eb_last_error(void);
+
+/*
+ 库版本号，例如 `"0.1.0"`。
+ */
+const char *eb_version(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ELECTRON_BOT_H */
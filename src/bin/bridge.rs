@@ -0,0 +1,23 @@
+//! ElectronBot 网络桥接服务
+//!
+//! 在机器人所在的主机（如树莓派）上运行，把 USB 设备转发给网络上的客户端：
+//! ```bash
+//! cargo run --release --bin electronbot-bridge --features bridge -- 0.0.0.0:7878
+//! ```
+//!
+//! 开发机上用 `bot.connect_remote("raspberrypi.local:7878")` 代替 `bot.connect()`。
+
+use electron_bot::BridgeServer;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "logging")]
+    env_logger::init();
+
+    let addr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "0.0.0.0:7878".to_string());
+    let server = BridgeServer::bind(&addr)?;
+    println!("ElectronBot 桥接服务: {}", server.local_addr()?);
+    server.serve()?;
+    Ok(())
+}
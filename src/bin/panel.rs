@@ -0,0 +1,25 @@
+//! ElectronBot 桌面控制面板
+//!
+//! 预览画面、拖动关节滑块、切换表情和播放动作时间轴：
+//! ```bash
+//! cargo run --release --bin electronbot-panel --features panel
+//! ```
+//!
+//! 加 `--fake` 使用模拟 MCU，不需要连接机器人。
+
+use electron_bot::{run_panel, ElectronBot, FakeMcu};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let fake = std::env::args().skip(1).any(|arg| arg == "--fake");
+    let mut bot = ElectronBot::new();
+    if fake {
+        bot.connect_transport(Box::new(FakeMcu::new()))?;
+    } else {
+        bot.connect()?;
+    }
+    // 保持同步以持续读取上报的角度
+    let handle = bot.into_handle()?;
+    handle.set_keepalive(Some(std::time::Duration::from_millis(100)));
+    run_panel(handle)?;
+    Ok(())
+}
@@ -5,24 +5,163 @@
 //!
 //! # 功能特性
 //!
-//! - USB 设备扫描和连接
+//! - USB 设备扫描和连接（匹配多个已知固件版本的 USB ID）
 //! - 图片缓冲区操作
+//! - 绘图图元（直线、多边形、椭圆、圆弧、圆角矩形、贝塞尔曲线），可选抗锯齿
+//! - 链式画面构建（`Frame` 按调用顺序绘制图元、文字和精灵，得到图片缓冲区）
 //! - 舵机控制数据
 //! - 数据同步
+//! - 使用统计（舵机使能时间、帧数、占空比）
+//! - 协议抓包和回放（离线复现协议问题和编写回归测试）
+//! - 底层 USB 访问（直接收发包、取得 USB 句柄，用于固件开发）
+//! - 遥测历史（带时间戳的环形缓冲区，保存扩展数据和关节状态，可导出 CSV）
+//! - 诊断事件日志（最近的连接和传输事件，带时间戳和错误详情，不需要日志后端）
+//! - 带单位的角度（`Angle`，明确区分度和弧度）
+//! - 上报角度滤波（指数平滑或滑动中值，同时保留原始值）
+//! - 关节堵转检测（上报角度持续跟不上或一直不动时发出事件）
+//! - 组装自检（屏幕颜色、关节到位和吞吐量，输出结构化报告）
+//! - 固件更新（`dfu` feature，通过 STM32 bootloader 写入和校验固件）
+//! - 会话录制（每次同步的画面和关节指令，保存为 PNG 序列、GIF 或视频）
+//! - 精灵图和精灵表（预转换 BGR，支持裁剪、翻转和透明色键）
+//! - 离屏图层（z 顺序合成，每层可见性和不透明度）
+//! - 文字渲染（TTF/OTF 字体通过 `text` feature 开启，
+//!   内置中日韩点阵字体通过 `embedded-font` feature 开启），支持自动换行和对齐
+//! - 屏幕熄灭/点亮和亮度缩放（不影响舵机控制）
+//! - 屏幕安装方向（0/90/180/270° 旋转和水平/垂直翻转）
+//! - 帧源和程序化测试图案（彩条、棋盘格、渐变、等离子、Perlin 噪声、坏点测试）
+//! - 取消长时间操作（`CancellationToken`，在包之间中止同步，Ctrl+C 和界面退出不会卡住）
+//! - 急停（舵机立即失能，中止播放中的动作并锁定，直到手动解除）
+//! - 舵机软启动（使能时从上报的当前角度平滑过渡到目标角度）
+//! - 舵机参数透传（速度、加速度和 PID 参数写入扩展数据的保留字节，编码格式可替换）
+//! - 自碰撞保护（按简化的正运动学模型拒绝或限制会让手臂打到头部和身体的目标）
+//! - 简易接口（`SimpleBot` 每个方法内部完成同步和重试，阻塞到动作完成，适合课堂教学）
+//! - 限时同步（整次同步包括重试的总时间预算，超时返回已完成的进度）
+//! - 线程安全句柄（后台同步线程，多个线程分别更新画面和关节）
+//! - 动作触发（句柄在后台播放动作，可等待完成或取消，新动作打断或排队）
+//! - 帧率控制（按绝对时间表睡眠，不累积漂移，统计抖动和设备实际帧率）
+//! - 流水线播放（工作线程渲染下一帧的同时发送当前帧）
+//! - 逐段发送（每准备好 60 行就发送，缩短解码到显示的延迟）
+//! - 可配置的传输布局（屏幕尺寸、周期数、包大小，支持固件分支）
+//! - 局部刷新（只发送变化的 60 行分段，需要固件支持）
+//! - 帧队列（渲染线程和同步线程之间的有界队列，可选阻塞、丢弃最旧或最新的帧）
+//! - 桌面时钟（指针或数字表盘，无操作一段时间后自动切换显示）
+//! - 通知显示（滑入、停留、滑出后恢复原来的内容，按优先级排队和打断）
+//! - 场景管理（多个帧源按优先级、播放时间和无操作条件调度，驱动屏幕和舵机）
+//! - 行为状态机（分层状态播放帧源，按连接、触摸、计时等事件切换，支持事件回调）
+//! - 行为脚本（`scripting` feature，Rhai 脚本控制绘图、表情和舵机，修改后自动重新加载）
+//! - 小部件（圆形仪表、进度条、滚动窗口折线图），可组合成仪表盘
+//! - 参数化表情引擎（眼睛、瞳孔、眼睑、眉毛、嘴型，预设表情之间平滑过渡）
+//! - 空闲动画（随机眨眼、瞳孔漂移、头部和身体轻微摆动）
+//! - 舵机帧源（目标角度按最大速度平滑靠近，限制在角度范围内）
+//! - 手柄遥控（`gamepad` feature，摇杆映射到关节，按键切换表情，可配置死区和响应曲线）
+//! - 键盘点动（`keyboard` feature，终端中用 WASD 和方向键点动关节，可调步长，用于标定和测试）
+//! - 预设动作（挥手、点头、摇头等关节关键帧，平滑插值，可保存为 JSON 动作文件）
+//! - 动作混合（多条动作轨道按关节权重覆盖或叠加，切换时交叉淡化）
+//! - 程序化空闲动作（Perlin 噪声摆动加呼吸起伏，可叠加在其他动作下）
+//! - 注视控制（瞳孔跟随目标，超出眼球范围时转动头部和身体）
+//! - 音频驱动嘴型（`audio` feature，起音/释音平滑）和 TTS 视位口型同步
+//! - 音频频谱可视化（`audio` feature，柱状或环形，可选配色）
+//! - 视频文件播放（`video` feature，通过 ffmpeg 子进程解码）
+//! - 摄像头采集到屏幕（`camera` feature，居中裁剪为正方形，可镜像）
+//! - MJPEG 网络流播放（`mjpeg` feature，断线自动重连）
+//! - HTTP 接口（`http` feature，用 `curl` 上传图片、设置关节、播放动作和查询状态）
+//! - MQTT 接入（`mqtt` feature，订阅命令主题、发布状态，支持 Home Assistant 自动发现）
+//! - 网络桥接（`bridge` feature，控制插在另一台主机上的机器人）
+//! - C 接口（`ffi` feature，导出 C ABI 和头文件，供 C/C++/C#/Unity 调用）
+//! - 桌面控制面板（`panel` feature，egui 窗口中预览画面、拖动关节、切换表情和播放动作时间轴）
+//! - Android USB 主机（`connect_with_fd` 接受 `UsbManager` 打开的文件描述符）
+//! - Windows WinUSB 后端（`winusb` feature，设备已经绑定 WinUSB 时不需要替换驱动）
+//! - Linux 权限检查（打开设备被拒绝时生成并安装对应 USB ID 的 udev 规则）
+//! - 浏览器 WebUSB 传输（`wasm` feature，编译到 `wasm32-unknown-unknown`，网页直接控制机器人）
+//! - no_std 协议核心（帧布局、扩展数据编码和分包在 `electron-bot-core` 子 crate 中，其他主机可以复用）
+//! - 命令行工具 `electronbot`（显示图片、文字，设置姿态，播放和录制动作，终端预览画面）
+//! - 屏幕截图（当前帧导出为 PNG，用于日志、测试和问题报告）
+//! - 终端预览（真彩色半块字符画，通过 SSH 也能检查帧内容）
+//! - YUV（NV12/I420/YUYV）输入直接转换为 BGR
+//! - JPEG/PNG 快速解码和编译期嵌入图片（`include_frame!`）
+//! - 二维码绘制（`qr` feature）
+//! - 颜色工具（HSV、十六进制解析、插值、调色板和渐变填充）
+//! - 色彩校正（亮度、对比度、gamma、白点，按通道查找表应用）
+//! - 连接后自动应用启动姿态和启动画面（TOML 配置需开启 `config` feature）
+//! - 可选的图片解码（默认开启的 `image` feature，关闭后不依赖 `image` 和 `rand`，只推送原始帧和关节指令）
 //! - 可选的日志功能（通过 `logging` feature 开启）
+//! - 同步流水线追踪（`tracing` feature，连接、同步周期、重试和重新连接的 span；`otlp` feature 通过 OTLP 导出）
 //!
 //! # 模块
 //!
+//! - [`modules::protocol`] - 传输布局
+//! - [`modules::transport`] - 传输层抽象
 //! - [`modules::usb`] - USB 底层操作
+//! - `modules::webusb` - 浏览器 WebUSB 传输（`wasm` feature）
+//! - `modules::winusb` - Windows WinUSB 传输（`winusb` feature）
+//! - [`modules::platform`] - 平台相关工具（Linux udev 规则）
+//! - `modules::dfu` - 固件更新（`dfu` feature）
 //! - [`modules::image`] - 图片缓冲区操作
+//! - [`modules::draw`] - 绘图图元
+//! - [`modules::frame`] - 画面构建
+//! - [`modules::sprite`] - 精灵图
+//! - [`modules::pattern`] - 平铺图案填充
+//! - [`modules::source`] - 帧源和测试图案
+//! - [`modules::queue`] - 帧队列
+//! - [`modules::pacer`] - 帧率控制
+//! - [`modules::stream`] - 逐段发送
+//! - [`modules::handle`] - 线程安全句柄
+//! - [`modules::widgets`] - 小部件（时钟、仪表、进度条、折线图）
+//! - [`modules::notify`] - 通知显示
+//! - [`modules::scene`] - 场景管理
+//! - [`modules::event`] - 事件和事件队列
+//! - [`modules::behavior`] - 行为状态机
+//! - [`modules::face`] - 参数化表情引擎
+//! - [`modules::gesture`] - 预设动作
+//! - [`modules::gaze`] - 注视控制（眼球与头部协调）
+//! - `modules::audio` - 音频驱动嘴型（`audio` feature）
+//! - `modules::viseme` - 视位口型同步（`audio` feature）
+//! - `modules::spectrum` - 音频频谱可视化（`audio` feature）
+//! - [`modules::idle`] - 空闲动画（眨眼、瞳孔漂移、头部摆动）
+//! - [`modules::servo`] - 舵机帧源
+//! - [`modules::mixer`] - 动作混合
+//! - [`modules::idle_motion`] - 程序化空闲动作
+//! - `modules::gamepad` - 手柄遥控（`gamepad` feature）
+//! - `modules::keyboard` - 键盘点动（`keyboard` feature）
+//! - `modules::script` - 行为脚本（`scripting` feature）
+//! - `modules::video` - 视频播放（`video` feature）
+//! - `modules::mjpeg` - MJPEG 网络流（`mjpeg` feature）
+//! - `modules::http` - HTTP 接口（`http` feature）
+//! - `modules::mqtt` - MQTT 接入（`mqtt` feature）
+//! - `modules::otlp` - OpenTelemetry 追踪导出（`otlp` feature）
+//! - `modules::bridge` - 网络桥接（`bridge` feature）
+//! - `modules::ffi` - C 接口（`ffi` feature）
+//! - `modules::panel` - 桌面控制面板（`panel` feature）
+//! - `modules::camera` - 摄像头采集（`camera` feature）
+//! - [`modules::layer`] - 图层合成
+//! - [`modules::color`] - 颜色工具
+//! - [`modules::yuv`] - YUV 输入转换
+//! - [`modules::indexed`] - 灰度和索引色输入
+//! - [`modules::pixel_format`] - 打包像素格式输入
+//! - [`modules::decode`] - JPEG/PNG 快速解码
+//! - [`modules::embed`] - 编译期图片嵌入（[`include_frame!`]）
+//! - [`modules::color_adjust`] - 色彩校正
 //! - [`modules::sync`] - 数据同步
 //! - [`modules::extra_data`] - 舵机控制数据
+//! - [`modules::stats`] - 使用统计
+//! - [`modules::selftest`] - 组装自检
+//! - [`modules::filter`] - 关节角度滤波
+//! - [`modules::telemetry`] - 遥测历史
+//! - [`modules::event_log`] - 诊断事件日志
+//! - [`modules::stall`] - 关节堵转检测
+//! - [`modules::soft_start`] - 舵机软启动
+//! - [`modules::tuning`] - 舵机参数透传
+//! - [`modules::kinematics`] - 正运动学和碰撞保护
+//! - [`modules::simple`] - 简易接口
+//! - [`modules::config`] - 配置
+//! - `modules::text` - 文字渲染（`text` / `embedded-font` feature）
+//! - `modules::qr` - 二维码绘制（`qr` feature）
 //! - [`modules::types`] - 公共类型
 //! - [`modules::error`] - 错误类型
 //!
 //! # 示例
 //!
-//! ```rust
+//! ```rust,no_run
 //! use electron_bot::{ElectronBot, Color};
 //!
 //! fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -57,10 +196,11 @@
 //! ```toml
 //! [dependencies]
 //! electron-bot = { path = "...", features = ["logging"] }
-//! env_logger = "0.10"
+//! env_logger = "0.11"
 //! ```
 //!
-//! 使用日志：
+//! 使用日志（库只通过 `log` 输出，日志后端由调用方选择；`logging` feature 带有的 `env_logger`
+//! 供自带的命令行工具、桥接服务和示例使用）：
 //! ```rust,ignore
 //! env_logger::init();
 //! // 现在可以使用 electron-bot 库，日志会自动输出
@@ -71,25 +211,144 @@
 pub mod modules;
 
 // 导出类型
+#[cfg(feature = "image")]
+pub use image::imageops::FilterType;
+#[cfg(feature = "image")]
+pub use modules::assets::{Asset, AssetLoader, AssetSource, Assets};
+#[cfg(feature = "audio")]
+pub use modules::audio::{AudioLevel, LipSync};
+pub use modules::behavior::{State, StateMachine};
+pub use modules::benchmark::BenchmarkReport;
+#[cfg(feature = "bridge")]
+pub use modules::bridge::{BridgeServer, TcpTransport};
+#[cfg(feature = "camera")]
+pub use modules::camera::{CameraOptions, CameraSource};
+pub use modules::capture::{
+    load_capture, read_capture, CaptureKind, CaptureRecord, CaptureTransport, HexDumpTransport,
+    ReplayStats, ReplayTransport,
+};
+pub use modules::color::{Gradient, GradientKind, Palette};
+pub use modules::color_adjust::{ColorAdjust, ColorLut};
+pub use modules::config::{BotConfig, StartupConfig};
 pub use modules::constants::*;
-pub use modules::error::BotError;
+#[cfg(feature = "dfu")]
+pub use modules::dfu::{DfuDevice, DfuProgress, DfuStage, FlashOptions};
+pub use modules::draw::{Point, Rect};
+#[cfg(feature = "image")]
+pub use modules::embed::{convert_frame, embed_frame, embed_frame_with};
+pub use modules::error::{BotError, ErrorClass, TransferError, TransferErrorKind};
+pub use modules::event::{Event, EventQueue};
+pub use modules::event_log::{EventLog, LogEntry, LogKind, DEFAULT_EVENT_LOG_LEN};
 pub use modules::extra_data::ExtraData;
-pub use modules::image::ImageBuffer;
-pub use modules::sync::SyncContext;
-pub use modules::types::{Color, DeviceInfo, JointAngles};
+pub use modules::face::{Expression, Face, FaceStyle, EXPRESSION_NAMES};
+pub use modules::fake_mcu::{FakeMcu, FakeMcuState, Fault};
+pub use modules::filter::{FilterKind, JointFilter};
+pub use modules::frame::Frame;
+pub use modules::frame_cache::{FrameCache, FrameKey, DEFAULT_FRAME_CACHE_BUDGET};
+#[cfg(feature = "gamepad")]
+pub use modules::gamepad::{
+    AxisBinding, AxisMode, ButtonAction, GamepadMapping, GamepadTeleop, ResponseCurve,
+};
+pub use modules::gaze::{Gaze, GazeOptions, GazeTarget};
+pub use modules::gesture::{Gesture, GESTURE_NAMES};
+pub use modules::handle::{BotHandle, GestureMode, GestureOutcome, GesturePlayback};
+#[cfg(feature = "http")]
+pub use modules::http::{HttpServer, DEFAULT_HTTP_ADDR};
+pub use modules::idle::{IdleBehavior, IdleOptions};
+pub use modules::idle_motion::{IdleMotion, IdleMotionOptions, DEFAULT_IDLE_AMPLITUDE};
+pub use modules::image::{FitMode, FitOptions, ImageBuffer, RowLayout, ToneMap};
+pub use modules::indexed::IndexedPalette;
+#[cfg(feature = "keyboard")]
+pub use modules::keyboard::{JogResult, KeyboardJog, KeyboardTeleop};
+pub use modules::kinematics::{Arm, BodyModel, BodyPart, Collision, CollisionGuard, GuardMode};
+pub use modules::layer::{Layer, LayerStack};
+pub use modules::mixer::{AnimationMixer, BlendMode, TrackOptions};
+#[cfg(feature = "mjpeg")]
+pub use modules::mjpeg::{MjpegSource, ReconnectPolicy};
+#[cfg(feature = "mqtt")]
+pub use modules::mqtt::{MqttBridge, MqttOptions};
+pub use modules::notify::{Notification, NotificationOverlay, Notifier, SlideFrom};
+#[cfg(feature = "otlp")]
+pub use modules::otlp::{init_otlp, OtlpGuard, DEFAULT_OTLP_ENDPOINT};
+pub use modules::pacer::{FramePacer, PacerStats};
+#[cfg(feature = "panel")]
+pub use modules::panel::{run_panel, ControlPanel, PanelStatus};
+pub use modules::pattern::StripeDirection;
+pub use modules::pixel_format::{rgb565_to_rgb, PixelFormat, PixelView};
+pub use modules::platform;
+pub use modules::protocol::ProtocolProfile;
+pub use modules::queue::{FrameQueue, QueuePolicy, QueueStats, QueuedFrame};
+#[cfg(feature = "image")]
+pub use modules::recorder::{FrameFormat, RecorderOptions, SessionRecorder};
+pub use modules::scene::{
+    Scene, SceneManager, Theme, NOTIFICATION_SCENE, SCENE_NAMES, THEME_NAMES,
+};
+#[cfg(feature = "scripting")]
+pub use modules::script::ScriptSource;
+pub use modules::selftest::{CheckResult, JointCheck, SelfTestOptions, SelfTestReport, Throughput};
+pub use modules::servo::{ServoSource, DEFAULT_JOINT_LIMITS};
+pub use modules::simple::{LookDirection, SimpleBot};
+pub use modules::soft_start::SoftStart;
+#[cfg(feature = "image")]
+pub use modules::source::RandomBlocks;
+pub use modules::source::{
+    Activity, Checkerboard, ColorBars, DeadPixelTest, FrameSource, GradientSweep, InactivitySwitch,
+    PerlinNoise, Plasma, PlayStats,
+};
+#[cfg(feature = "audio")]
+pub use modules::spectrum::{AudioBuffer, Spectrum, SpectrumStyle};
+pub use modules::sprite::{BlitOptions, Sprite, SpriteSheet};
+pub use modules::stall::{JointStatus, StallDetector, StallOptions};
+pub use modules::stats::UsageStats;
+pub use modules::stream::FrameStream;
+pub use modules::sync::{CancellationToken, EmergencyStop, QuarterMask, SyncContext, SyncProgress};
+pub use modules::telemetry::{TelemetryHistory, TelemetrySample, DEFAULT_HISTORY_LEN};
+#[cfg(any(feature = "text", feature = "embedded-font"))]
+pub use modules::text::{Font, TextAlign, TextStyle};
+pub use modules::transport::{Direction, Transport};
+pub use modules::tuning::{
+    JointTuning, ParamEncoder, Pid, ServoTuning, TuningEncoder, TuningMessage, TUNING_LEN,
+    TUNING_OFFSET,
+};
+pub use modules::types::{
+    Angle, Color, DeviceInfo, FirmwareVariant, JointAngles, Orientation, Rotation, POSE_NAMES,
+};
+pub use modules::usb::{UsbBackend, UsbDevice};
+#[cfg(feature = "video")]
+pub use modules::video::VideoSource;
+#[cfg(feature = "audio")]
+pub use modules::viseme::{MouthShape, Viseme, VisemePlayer, VisemeTrack};
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub use modules::webusb::WebUsb;
+#[cfg(feature = "wasm")]
+pub use modules::webusb::WebUsbTransport;
+pub use modules::widgets::{
+    Clock, ClockKind, ClockStyle, ClockTime, Gauge, ProgressBar, Sparkline, Widget,
+};
+#[cfg(all(windows, feature = "winusb"))]
+pub use modules::winusb::WinUsbDevice;
+pub use modules::yuv::{yuv_to_rgb, YuvFormat};
 
 // USB 操作
 use modules::error::BotError as Error;
+use modules::event::{EventHandler, FrameSentHandler};
+use modules::idle::{UntilGazeSettled, GAZE_MAX_FRAMES};
+use modules::image::PostProcessor;
+use modules::notify::NotificationPlayback;
 use modules::sync::SyncContext as SyncCtx;
-use modules::usb::UsbDevice;
 
 // ==================== 主结构体 ====================
 
+/// 休眠恢复时重新打开设备的方式，返回新的传输通道和匹配的固件版本。
+type Reopen = Box<
+    dyn FnMut(&[FirmwareVariant]) -> Result<(Box<dyn Transport>, FirmwareVariant), Error> + Send,
+>;
+
 /// 用于与 ElectronBot 通信的主结构体
 ///
 /// # 示例
 ///
-/// ```rust
+/// ```rust,no_run
 /// use electron_bot::ElectronBot;
 ///
 /// fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -108,11 +367,47 @@ use modules::usb::UsbDevice;
 /// }
 /// ```
 pub struct ElectronBot {
-    usb: Option<UsbDevice>,
+    transport: Option<Box<dyn Transport>>,
     is_connected: bool,
     image_buffer: ImageBuffer,
     extra_data: ExtraData,
+    received: Option<ExtraData>,
     sync_context: SyncCtx,
+    stats: UsageStats,
+    stats_path: Option<std::path::PathBuf>,
+    /// 已经合并过的统计文件，同一个文件只加载一次
+    stats_loaded: Vec<std::path::PathBuf>,
+    #[cfg(feature = "image")]
+    recorder: Option<SessionRecorder>,
+    config: BotConfig,
+    display_on: bool,
+    brightness: f32,
+    fit_options: FitOptions,
+    color_lut: Option<ColorLut>,
+    dithering: bool,
+    event_handlers: Vec<EventHandler>,
+    frame_sent_handlers: Vec<FrameSentHandler>,
+    post_processors: std::sync::Mutex<Vec<PostProcessor>>,
+    cancel: CancellationToken,
+    estop: EmergencyStop,
+    partial_updates: bool,
+    dirty: QuarterMask,
+    last_sent: Option<ImageBuffer>,
+    variants: Vec<FirmwareVariant>,
+    connected_variant: Option<FirmwareVariant>,
+    usb_backend: UsbBackend,
+    stall_detector: Option<StallDetector>,
+    soft_start: Option<SoftStart>,
+    collision_guard: Option<CollisionGuard>,
+    tuning_encoder: Box<dyn TuningEncoder>,
+    tuning_queue: std::collections::VecDeque<TuningMessage>,
+    joint_filter: JointFilter,
+    telemetry: TelemetryHistory,
+    event_log: EventLog,
+    auto_resume: Option<std::time::Duration>,
+    reopen: Option<Reopen>,
+    last_tuning: Option<ServoTuning>,
+    last_progress: SyncProgress,
 }
 
 impl ElectronBot {
@@ -125,23 +420,92 @@ impl ElectronBot {
         #[cfg(feature = "logging")]
         log::info!("创建新的 ElectronBot 实例");
         Self {
-            usb: None,
+            transport: None,
             is_connected: false,
             image_buffer: ImageBuffer::new(),
             extra_data: ExtraData::new(),
+            received: None,
             sync_context: SyncContext::new(),
+            stats: UsageStats::new(),
+            stats_path: None,
+            stats_loaded: Vec::new(),
+            #[cfg(feature = "image")]
+            recorder: None,
+            config: BotConfig::new(),
+            display_on: true,
+            brightness: 1.0,
+            fit_options: FitOptions::default(),
+            color_lut: None,
+            dithering: false,
+            event_handlers: Vec::new(),
+            frame_sent_handlers: Vec::new(),
+            post_processors: std::sync::Mutex::new(Vec::new()),
+            cancel: CancellationToken::new(),
+            estop: EmergencyStop::new(),
+            partial_updates: false,
+            dirty: QuarterMask::NONE,
+            last_sent: None,
+            variants: modules::usb::known_variants(),
+            connected_variant: None,
+            usb_backend: UsbBackend::Auto,
+            stall_detector: None,
+            soft_start: None,
+            collision_guard: None,
+            tuning_encoder: Box::new(ParamEncoder),
+            tuning_queue: std::collections::VecDeque::new(),
+            joint_filter: JointFilter::default(),
+            telemetry: TelemetryHistory::default(),
+            event_log: EventLog::default(),
+            auto_resume: None,
+            reopen: None,
+            last_tuning: None,
+            last_progress: SyncProgress::default(),
         }
     }
 
+    /// 使用指定配置创建 ElectronBot 实例
+    pub fn with_config(config: BotConfig) -> Self {
+        let mut bot = Self::new();
+        bot.set_config(config);
+        bot
+    }
+
+    // ==================== 配置 ====================
+
+    /// 获取当前配置
+    pub fn config(&self) -> &BotConfig {
+        &self.config
+    }
+
+    /// 替换当前配置（启动状态在下次连接时生效，屏幕方向和色彩校正立即生效）
+    pub fn set_config(&mut self, config: BotConfig) {
+        self.color_lut = lut_for(&config.color_adjust);
+        self.config = config;
+    }
+
+    /// 从 TOML 文件加载配置
+    #[cfg(feature = "config")]
+    pub fn load_config<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<(), Error> {
+        let config = BotConfig::load_from_file(path).map_err(Error::ConfigError)?;
+        self.set_config(config);
+        Ok(())
+    }
+
     // ==================== 设备发现 ====================
 
     /// 扫描所有 USB 设备
     ///
     /// 返回所有连接的 USB 设备列表
     pub fn scan_devices() -> Vec<DeviceInfo> {
+        let variants = modules::usb::known_variants();
         modules::usb::scan_devices()
             .into_iter()
-            .map(|(vid, pid, info)| DeviceInfo { vid, pid, info })
+            .map(|(vid, pid, info)| DeviceInfo {
+                vid,
+                pid,
+                info,
+                variant: modules::usb::find_variant(&variants, vid, pid).map(|v| v.name.clone()),
+            })
             .collect()
     }
 
@@ -150,41 +514,250 @@ impl ElectronBot {
         modules::usb::is_electron_bot_present()
     }
 
-    /// 查找 ElectronBot 设备信息
+    /// 查找 ElectronBot 设备信息（匹配任一已知固件版本）
     pub fn find_electron_bot() -> Option<DeviceInfo> {
-        modules::usb::scan_devices()
+        Self::scan_devices()
             .into_iter()
-            .find(|(vid, pid, _)| *vid == USB_VID && *pid == USB_PID)
-            .map(|(vid, pid, info)| DeviceInfo { vid, pid, info })
+            .find(|device| device.variant.is_some())
+    }
+
+    /// 添加 `connect` 时匹配的固件版本（社区固件分支等）
+    pub fn add_variant(&mut self, variant: FirmwareVariant) {
+        if modules::usb::find_variant(&self.variants, variant.vid, variant.pid).is_none() {
+            self.variants.push(variant);
+        }
+    }
+
+    /// `connect` 时匹配的固件版本
+    pub fn variants(&self) -> &[FirmwareVariant] {
+        &self.variants
+    }
+
+    /// 设置 `connect` 使用的 USB 后端（默认 `Auto`：先用 libusb，Windows 上失败时尝试 WinUSB）
+    pub fn set_usb_backend(&mut self, backend: UsbBackend) {
+        self.usb_backend = backend;
+    }
+
+    /// `connect` 使用的 USB 后端
+    pub fn usb_backend(&self) -> UsbBackend {
+        self.usb_backend
+    }
+
+    /// 当前连接的设备匹配的固件版本（通过 `connect_transport` 连接时为 `None`）
+    pub fn connected_variant(&self) -> Option<&FirmwareVariant> {
+        self.connected_variant.as_ref()
     }
 
     // ==================== 连接 ====================
 
     /// 连接到 ElectronBot
     ///
-    /// 自动查找设备并声明正确的接口
+    /// 自动查找设备并声明正确的接口。
+    /// 如果配置中 `startup.apply_on_connect` 为 true，连接后立即应用启动状态。
     pub fn connect(&mut self) -> Result<bool, Error> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("connect", backend = ?self.usb_backend).entered();
         #[cfg(feature = "logging")]
         log::info!("正在连接 ElectronBot...");
         self.disconnect();
+        if self.cancel.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
 
-        match modules::usb::open_electron_bot() {
-            Ok(usb_device) => {
-                self.usb = Some(usb_device);
-                self.is_connected = true;
-                self.sync_context = SyncContext::new();
+        match modules::usb::open_with_backend(self.usb_backend, &self.variants) {
+            Ok((transport, variant)) => {
                 #[cfg(feature = "logging")]
-                log::info!("ElectronBot 连接成功");
-                Ok(true)
+                log::info!(
+                    "找到固件版本 {} ({:04x}:{:04x})",
+                    variant.name,
+                    variant.vid,
+                    variant.pid
+                );
+                #[cfg(feature = "tracing")]
+                tracing::info!(variant = %variant.name, vid = variant.vid, pid = variant.pid, "connected");
+                let result = self.connect_transport(transport);
+                self.log_connected(&variant);
+                if self.is_connected {
+                    self.connected_variant = Some(variant);
+                    let backend = self.usb_backend;
+                    self.reopen = Some(Box::new(move |variants| {
+                        modules::usb::open_with_backend(backend, variants)
+                    }));
+                }
+                result
+            }
+            Err(e) => {
+                #[cfg(feature = "logging")]
+                log::error!("连接失败: {}", e);
+                #[cfg(feature = "tracing")]
+                tracing::error!(error = %e, "connect failed");
+                self.event_log.push(LogKind::ConnectFailed, e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    /// 用 Android `UsbManager` 打开的文件描述符连接（`UsbDeviceConnection.getFileDescriptor()`）
+    ///
+    /// Android 应用没有枚举 USB 设备的权限，由 Java/Kotlin 层申请权限、打开设备后把描述符传进来。
+    /// 桌面 Linux 上也可以传入自己打开的 `/dev/bus/usb/...` 描述符。连接后的行为与 `connect` 相同
+    ///
+    /// # Safety
+    ///
+    /// `fd` 必须是 USB 设备的有效描述符，并且在断开连接之前保持打开（不要提前调用 `UsbDeviceConnection.close()`）
+    #[cfg(unix)]
+    pub unsafe fn connect_with_fd(&mut self, fd: std::os::fd::RawFd) -> Result<bool, Error> {
+        #[cfg(feature = "logging")]
+        log::info!("正在通过文件描述符连接 ElectronBot...");
+        self.disconnect();
+
+        match modules::usb::open_with_fd(fd, &self.variants) {
+            Ok((usb_device, variant)) => {
+                #[cfg(feature = "logging")]
+                log::info!(
+                    "固件版本 {} ({:04x}:{:04x})",
+                    variant.name,
+                    variant.vid,
+                    variant.pid
+                );
+                let result = self.connect_transport(Box::new(usb_device));
+                self.log_connected(&variant);
+                if self.is_connected {
+                    self.connected_variant = Some(variant);
+                }
+                result
             }
             Err(e) => {
                 #[cfg(feature = "logging")]
                 log::error!("连接失败: {}", e);
-                Err(Error::UsbError(e))
+                self.event_log.push(LogKind::ConnectFailed, e.to_string());
+                Err(e)
             }
         }
     }
 
+    /// 通过指定的传输通道连接（如网络桥接或测试用的模拟设备）
+    ///
+    /// 连接后的行为与 `connect` 相同，包括应用启动状态；应用启动状态失败时断开连接并返回错误
+    pub fn connect_transport(&mut self, transport: Box<dyn Transport>) -> Result<bool, Error> {
+        self.attach(transport);
+        if self.config.startup.apply_on_connect {
+            if let Err(e) = self.apply_startup() {
+                #[cfg(feature = "logging")]
+                log::error!("应用启动配置失败: {}", e);
+                self.disconnect();
+                return Err(e);
+            }
+        }
+        Ok(true)
+    }
+
+    /// 换上新的传输通道，重置同步状态（不应用启动状态）
+    fn attach(&mut self, transport: Box<dyn Transport>) {
+        self.disconnect();
+        self.transport = Some(transport);
+        self.is_connected = true;
+        self.sync_context = SyncContext::with_profile(self.sync_context.profile);
+        self.received = None;
+        self.last_sent = None;
+        self.stats.begin_session();
+        #[cfg(feature = "logging")]
+        log::info!("ElectronBot 连接成功");
+        self.event_log.push(LogKind::Connected, "");
+        self.emit(Event::Connected);
+    }
+
+    /// 连接另一台主机上通过桥接服务转发的 ElectronBot
+    #[cfg(feature = "bridge")]
+    pub fn connect_remote<A: std::net::ToSocketAddrs>(&mut self, addr: A) -> Result<bool, Error> {
+        #[cfg(feature = "logging")]
+        log::info!("正在连接远程 ElectronBot...");
+        self.disconnect();
+        let transport = TcpTransport::connect(addr).map_err(|e| {
+            self.event_log.push(LogKind::ConnectFailed, e.as_str());
+            Error::UsbError(e)
+        })?;
+        self.connect_transport(Box::new(transport))
+    }
+
+    /// 应用配置中的启动姿态和启动画面（纯色、场景、图片依次绘制）并同步一次
+    pub fn apply_startup(&mut self) -> Result<(), Error> {
+        let startup = self.config.startup.clone();
+        if startup.is_empty() {
+            return Ok(());
+        }
+
+        #[cfg(feature = "logging")]
+        log::info!("应用启动配置: {:?}", startup);
+        if let Some([r, g, b]) = startup.color {
+            self.set_image_color(Color::Custom(r, g, b));
+        }
+        if startup.scene.is_some() || startup.theme.is_some() {
+            let theme = match &startup.theme {
+                Some(name) => Theme::named(name)
+                    .ok_or_else(|| Error::ConfigError(format!("未知的主题: {}", name)))?,
+                None => Theme::default(),
+            };
+            let name = startup.scene.as_deref().unwrap_or("face");
+            let scene = Scene::named(name, &theme)
+                .ok_or_else(|| Error::ConfigError(format!("未知的场景: {}", name)))?;
+            let mut scenes = SceneManager::new();
+            scenes.add(scene);
+            scenes.next_frame(&mut self.image_buffer);
+        }
+        if let Some(path) = &startup.image {
+            #[cfg(feature = "image")]
+            self.set_image(path)?;
+            #[cfg(not(feature = "image"))]
+            return Err(Error::ConfigError(format!(
+                "启动画面 {:?} 需要 `image` 功能",
+                path
+            )));
+        }
+        if let Some(pose) = &startup.pose {
+            self.set_joint_angles(pose, startup.enable_servos)?;
+        }
+        self.sync()?;
+        Ok(())
+    }
+
+    /// 开始协议抓包：之后双向的每个包带时间戳写入 `path`，直到断开连接
+    ///
+    /// 抓包文件可以用 `ReplayTransport` 回放
+    pub fn start_capture<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<(), Error> {
+        if !self.is_connected || self.transport.is_none() {
+            return Err(Error::NotConnected);
+        }
+        let writer = modules::capture::create_capture_file(path).map_err(Error::RecordError)?;
+        if let Some(transport) = self.transport.take() {
+            self.transport = Some(Box::new(CaptureTransport::with_writer(transport, writer)));
+        }
+        #[cfg(feature = "logging")]
+        log::info!("开始协议抓包");
+        Ok(())
+    }
+
+    /// 开始十六进制转储：之后 `duration` 内发送和接收的原始包分别写入目录 `dir` 中的
+    /// `tx.hex` 和 `rx.hex`，用于和逻辑分析仪的抓取结果对照
+    pub fn start_hex_dump<P: AsRef<std::path::Path>>(
+        &mut self,
+        dir: P,
+        duration: std::time::Duration,
+    ) -> Result<(), Error> {
+        if !self.is_connected || self.transport.is_none() {
+            return Err(Error::NotConnected);
+        }
+        let writers = modules::capture::create_hex_dump_files(dir).map_err(Error::RecordError)?;
+        if let Some(transport) = self.transport.take() {
+            self.transport = Some(Box::new(HexDumpTransport::with_writers(
+                transport, writers, duration,
+            )));
+        }
+        #[cfg(feature = "logging")]
+        log::info!("开始十六进制转储: {:?}", duration);
+        Ok(())
+    }
+
     /// 连接到指定接口的 ElectronBot
     pub fn connect_with_interface(&mut self, _interface_num: u8) -> Result<bool, Error> {
         // 目前使用相同的连接方式
@@ -193,12 +766,39 @@ impl ElectronBot {
 
     /// 断开设备连接
     pub fn disconnect(&mut self) {
-        #[cfg(feature = "logging")]
         if self.is_connected {
+            #[cfg(feature = "logging")]
             log::info!("断开 ElectronBot 连接");
+            self.stats.end_session();
+            if let Err(_e) = self.save_stats() {
+                #[cfg(feature = "logging")]
+                log::warn!("保存统计失败: {}", _e);
+            }
         }
+        let was_connected = self.is_connected;
         self.is_connected = false;
-        self.usb = None;
+        self.transport = None;
+        self.connected_variant = None;
+        self.reopen = None;
+        if let Some(detector) = &mut self.stall_detector {
+            detector.reset();
+        }
+        self.joint_filter.reset();
+        if let Some(soft_start) = &mut self.soft_start {
+            soft_start.reset();
+        }
+        self.tuning_queue.clear();
+        if was_connected {
+            self.event_log.push(LogKind::Disconnected, "");
+            self.emit(Event::Disconnected);
+        }
+    }
+
+    /// 把 USB 连接的固件版本补充到刚记录的连接事件
+    fn log_connected(&mut self, variant: &FirmwareVariant) {
+        if let Some(entry) = self.event_log.latest_mut(LogKind::Connected) {
+            entry.detail = format!("{} ({:04x}:{:04x})", variant.name, variant.vid, variant.pid);
+        }
     }
 
     /// 检查是否已连接
@@ -206,6 +806,39 @@ impl ElectronBot {
         self.is_connected
     }
 
+    // ==================== 事件 ====================
+
+    /// 注册事件回调，在连接、断开和同步失败时调用
+    ///
+    /// 交给行为状态机处理时把事件写入 `StateMachine::events` 返回的队列
+    pub fn on_event<F: FnMut(&Event) + Send + 'static>(&mut self, handler: F) {
+        self.event_handlers.push(Box::new(handler));
+    }
+
+    /// 移除全部事件回调
+    pub fn clear_event_handlers(&mut self) {
+        self.event_handlers.clear();
+    }
+
+    /// 注册周期发送回调：每次同步后按发送顺序对每个完成的周期调用，参数为段序号和该周期
+    /// 发送的全部数据（所有图片包加尾包），用于固件调试
+    ///
+    /// 数据按最终输出的画面（亮度、方向等已处理）重建
+    pub fn on_frame_sent<F: FnMut(usize, &[u8]) + Send + 'static>(&mut self, handler: F) {
+        self.frame_sent_handlers.push(Box::new(handler));
+    }
+
+    /// 移除全部周期发送回调
+    pub fn clear_frame_sent_handlers(&mut self) {
+        self.frame_sent_handlers.clear();
+    }
+
+    fn emit(&mut self, event: Event) {
+        for handler in &mut self.event_handlers {
+            handler(&event);
+        }
+    }
+
     // ==================== 图片操作 ====================
 
     /// 获取图片缓冲区可变引用
@@ -214,19 +847,48 @@ impl ElectronBot {
     }
 
     /// 从文件设置图片
+    #[cfg(feature = "image")]
     pub fn set_image<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<(), Error> {
         #[cfg(feature = "logging")]
         log::info!("从文件加载图片: {:?}", path.as_ref());
         self.image_buffer
-            .load_from_file(path)
+            .load_from_file_with(path, &self.fit_options)
+            .map_err(Error::ImageError)
+    }
+
+    /// 通过帧缓存从文件设置图片，缓存过的文件跳过解码和转换
+    ///
+    /// 返回是否命中缓存
+    #[cfg(feature = "image")]
+    pub fn set_image_cached<P: AsRef<std::path::Path>>(
+        &mut self,
+        cache: &mut FrameCache,
+        path: P,
+    ) -> Result<bool, Error> {
+        cache
+            .load_file(path, &self.fit_options, &mut self.image_buffer)
             .map_err(Error::ImageError)
     }
 
     /// 从 DynamicImage 设置图片
+    #[cfg(feature = "image")]
     pub fn set_image_from_image(&mut self, img: &image::DynamicImage) {
         #[cfg(feature = "logging")]
         log::info!("从 DynamicImage 加载图片");
-        self.image_buffer.load_from_image(img);
+        self.image_buffer
+            .load_from_image_with(img, &self.fit_options);
+    }
+
+    /// 设置图片适配方式（用于 `set_image` 和 `set_image_from_image`）
+    ///
+    /// 默认为拉伸填满屏幕、最近邻插值。
+    pub fn set_fit_options(&mut self, options: FitOptions) {
+        self.fit_options = options;
+    }
+
+    /// 获取图片适配方式
+    pub fn fit_options(&self) -> &FitOptions {
+        &self.fit_options
     }
 
     /// 从原始 RGB/BGR 数据设置图片
@@ -243,6 +905,135 @@ impl ElectronBot {
             .map_err(Error::ImageError)
     }
 
+    /// 按行布局从原始 RGB 数据设置图片，用于行尾有填充（Windows DIB、OpenCV ROI、
+    /// GPU 回读）或从最下面一行开始存放的数据
+    pub fn set_image_from_data_with(
+        &mut self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        layout: &RowLayout,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "logging")]
+        log::debug!("从原始数据加载图片: {}x{} {:?}", width, height, layout);
+        self.image_buffer
+            .load_from_data_with(data, width, height, layout)
+            .map_err(Error::ImageError)
+    }
+
+    /// 设置预先转换好的 BGR 帧（例如 [`include_frame!`] 嵌入的图片）
+    pub fn set_image_frame(&mut self, frame: &[u8; FRAME_SIZE]) {
+        #[cfg(feature = "logging")]
+        log::debug!("设置 BGR 原始帧");
+        self.image_buffer.load_frame(frame);
+    }
+
+    /// 下一次同步要发送的 BGR 帧，生产者直接写入像素，省去中间 `ImageBuffer` 和
+    /// `set_image_frame` 的复制
+    ///
+    /// 没有后处理、熄屏、调整亮度、色彩校正、抖动和屏幕方向时，同步直接从这块内存发送；
+    /// 开启局部刷新时按内容比较找出变化的分段，不需要另外调用 `mark_dirty`
+    pub fn frame_writer(&mut self) -> &mut [u8; FRAME_SIZE] {
+        self.image_buffer.frame_mut()
+    }
+
+    /// 从 YUV（NV12/I420/YUYV）数据设置图片，按当前适配方式缩放
+    pub fn set_image_from_yuv(
+        &mut self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        format: YuvFormat,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "logging")]
+        log::debug!("从 YUV 数据加载图片: {:?} {}x{}", format, width, height);
+        self.image_buffer
+            .load_from_yuv(data, width, height, format, &self.fit_options)
+            .map_err(Error::ImageError)
+    }
+
+    /// 从 RGB565、RGBA 等打包像素数据设置图片，按当前适配方式缩放
+    ///
+    /// 各种原始像素输入的统一入口，行紧密排列；尺寸与屏幕相同的 BGR888 数据直接复制
+    pub fn load_frame(
+        &mut self,
+        data: &[u8],
+        format: PixelFormat,
+        width: usize,
+        height: usize,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "logging")]
+        log::debug!("从 {:?} 数据加载图片: {}x{}", format, width, height);
+        self.image_buffer
+            .load_pixels(data, format, width, height, &self.fit_options)
+            .map_err(Error::ImageError)
+    }
+
+    /// 按行布局从打包像素数据设置图片，按当前适配方式缩放
+    pub fn load_frame_with(
+        &mut self,
+        data: &[u8],
+        format: PixelFormat,
+        width: usize,
+        height: usize,
+        layout: &RowLayout,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "logging")]
+        log::debug!(
+            "从 {:?} 数据加载图片: {}x{} {:?}",
+            format,
+            width,
+            height,
+            layout
+        );
+        self.image_buffer
+            .load_pixels_with(data, format, width, height, layout, &self.fit_options)
+            .map_err(Error::ImageError)
+    }
+
+    /// 从 8 位灰度数据设置图片，按当前适配方式缩放
+    pub fn set_image_from_gray(
+        &mut self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "logging")]
+        log::debug!("从灰度数据加载图片: {}x{}", width, height);
+        self.image_buffer
+            .load_from_gray(data, width, height, &self.fit_options)
+            .map_err(Error::ImageError)
+    }
+
+    /// 从 8 位索引数据和调色板设置图片，按当前适配方式缩放
+    ///
+    /// 每个像素 1 字节，查表展开为 BGR；只修改调色板即可实现调色板循环动画
+    pub fn set_image_from_indexed(
+        &mut self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        palette: &IndexedPalette,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "logging")]
+        log::debug!("从索引数据加载图片: {}x{}", width, height);
+        self.image_buffer
+            .load_from_indexed(data, width, height, palette, &self.fit_options)
+            .map_err(Error::ImageError)
+    }
+
+    /// 从 JPEG/PNG 等编码后的数据设置图片，按当前适配方式缩放
+    ///
+    /// JPEG 和 PNG 直接解码到帧缓冲区（最近邻插值），跳过中间图片的分配
+    #[cfg(feature = "image")]
+    pub fn set_image_from_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        #[cfg(feature = "logging")]
+        log::debug!("从编码数据加载图片: {} 字节", bytes.len());
+        self.image_buffer
+            .load_image_bytes_with(bytes, &self.fit_options)
+            .map_err(Error::ImageError)
+    }
+
     /// 设置纯色图片
     pub fn set_image_color(&mut self, color: Color) {
         #[cfg(feature = "logging")]
@@ -250,6 +1041,136 @@ impl ElectronBot {
         self.image_buffer.clear(color);
     }
 
+    /// 设置渐变背景图片
+    pub fn set_image_gradient(&mut self, gradient: &Gradient) {
+        #[cfg(feature = "logging")]
+        log::info!("设置渐变图片: {:?}", gradient.kind());
+        self.image_buffer.fill_gradient(gradient);
+    }
+
+    /// 将图层栈按 z 顺序合成到图片缓冲区（黑色背景，设置了平铺背景时先铺背景）
+    pub fn set_image_from_layers(&mut self, layers: &LayerStack) {
+        #[cfg(feature = "logging")]
+        log::debug!("合成 {} 个图层", layers.len());
+        self.image_buffer.clear(Color::Black);
+        layers.composite_onto(&mut self.image_buffer);
+    }
+
+    // ==================== 屏幕控制 ====================
+
+    /// 熄灭屏幕
+    ///
+    /// 之后的同步发送黑帧，图片缓冲区内容保持不变，舵机控制不受影响
+    pub fn display_off(&mut self) {
+        #[cfg(feature = "logging")]
+        log::info!("熄灭屏幕");
+        self.display_on = false;
+    }
+
+    /// 点亮屏幕，恢复显示图片缓冲区内容
+    pub fn display_on(&mut self) {
+        #[cfg(feature = "logging")]
+        log::info!("点亮屏幕");
+        self.display_on = true;
+    }
+
+    /// 检查屏幕是否点亮
+    pub fn is_display_on(&self) -> bool {
+        self.display_on
+    }
+
+    /// 设置亮度缩放系数（0.0-1.0），在发送时应用
+    pub fn set_brightness(&mut self, brightness: f32) {
+        #[cfg(feature = "logging")]
+        log::info!("设置亮度: {}", brightness);
+        self.brightness = brightness.clamp(0.0, 1.0);
+    }
+
+    /// 获取亮度缩放系数
+    pub fn brightness(&self) -> f32 {
+        self.brightness
+    }
+
+    /// 设置屏幕安装方向（旋转和翻转），在发送时应用
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        #[cfg(feature = "logging")]
+        log::info!("设置屏幕方向: {:?}", orientation);
+        self.config.orientation = orientation;
+    }
+
+    /// 获取屏幕安装方向
+    pub fn orientation(&self) -> Orientation {
+        self.config.orientation
+    }
+
+    /// 设置色彩校正（亮度、对比度、gamma、白点），在发送时按查找表应用
+    pub fn set_color_adjust(&mut self, adjust: ColorAdjust) {
+        #[cfg(feature = "logging")]
+        log::info!("设置色彩校正: {:?}", adjust);
+        self.color_lut = lut_for(&adjust);
+        self.config.color_adjust = adjust;
+    }
+
+    /// 获取色彩校正参数
+    pub fn color_adjust(&self) -> &ColorAdjust {
+        &self.config.color_adjust
+    }
+
+    /// 开启或关闭发送时的 RGB565 抖动（默认关闭），用于消除渐变色带
+    pub fn set_dithering(&mut self, enable: bool) {
+        self.dithering = enable;
+    }
+
+    /// 检查是否开启抖动
+    pub fn dithering(&self) -> bool {
+        self.dithering
+    }
+
+    /// 添加后处理回调，按添加顺序在转换和发送之前对画面的副本调用，
+    /// 例如为圆形边框加暗角、扫描线效果或水印，与任何画面来源组合
+    ///
+    /// 回调在色彩校正、亮度、抖动和屏幕方向之前运行，不修改图片缓冲区本身；
+    /// 同一帧可能调用多次（`output_frame`、局部刷新比较、逐段发送的每一段），
+    /// 回调的结果应只取决于输入画面和时间
+    pub fn add_post_process<F: FnMut(&mut ImageBuffer) + Send + 'static>(
+        &mut self,
+        post_process: F,
+    ) {
+        self.post_processors
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Box::new(post_process));
+    }
+
+    /// 移除全部后处理回调
+    pub fn clear_post_processes(&mut self) {
+        self.post_processors
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    /// 获取实际要发送的帧（应用后处理、熄屏、色彩校正、亮度、抖动和屏幕方向）
+    pub fn output_frame(&self) -> std::borrow::Cow<'_, ImageBuffer> {
+        output_frame(
+            &self.image_buffer,
+            &self.post_processors,
+            self.display_on,
+            self.brightness,
+            self.config.orientation,
+            self.color_lut.as_ref(),
+            self.dithering,
+        )
+    }
+
+    /// 把实际要发送的帧保存为图片文件（格式由扩展名决定，如 `.png`）
+    #[cfg(feature = "image")]
+    pub fn screenshot<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), Error> {
+        #[cfg(feature = "logging")]
+        log::info!("保存屏幕截图: {:?}", path.as_ref());
+        self.output_frame().save(path).map_err(Error::ImageError)
+    }
+
     // ==================== 扩展数据操作 ====================
 
     /// 获取扩展数据可变引用
@@ -275,52 +1196,524 @@ impl ElectronBot {
 
     // ==================== 舵机控制 ====================
 
-    /// 设置 6 个舵机的角度
+    /// 设置 6 个舵机的角度（度）
+    ///
+    /// 开启了碰撞保护时，拒绝模式下会碰撞的目标返回 `BotError::Collision` 且不修改目标，
+    /// 限制模式下保存调整后的角度
     pub fn set_joint_angles(&mut self, angles: &[f32; 6], enable: bool) -> Result<(), Error> {
         #[cfg(feature = "logging")]
         log::info!("设置舵机角度: {:?}, 启用: {}", angles, enable);
         let mut ja = JointAngles::new();
         ja.as_array_mut().copy_from_slice(angles);
+        if let Some(guard) = &self.collision_guard {
+            ja = guard
+                .apply(&ja)
+                .map_err(|collision| Error::Collision(collision.to_string()))?;
+        }
         self.extra_data.set_joint_angles(&ja, enable);
         Ok(())
     }
 
-    /// 设置舵机角度（默认启用）
+    /// 设置舵机角度（度，默认启用）
     pub fn set_joint_angles_easy(&mut self, angles: &[f32; 6]) -> Result<(), Error> {
         self.set_joint_angles(angles, true)
     }
 
-    /// 从机器人获取舵机角度
-    pub fn get_joint_angles(&self) -> JointAngles {
-        self.extra_data.get_joint_angles()
+    /// 用带单位的角度设置 6 个舵机
+    ///
+    /// 角度来自逆运动学等使用弧度的计算时，用 `Angle::radians` 包装后传入，不必手动换算
+    pub fn set_angles(&mut self, angles: &[Angle; 6], enable: bool) -> Result<(), Error> {
+        self.set_joint_angles(JointAngles::from_angles(*angles).as_array(), enable)
     }
 
-    // ==================== 同步 ====================
+    /// 从机器人获取带单位的舵机角度（与 `get_joint_angles` 相同的数据）
+    pub fn angles(&self) -> [Angle; 6] {
+        self.get_joint_angles().to_angles()
+    }
 
-    /// 与机器人同步数据
+    /// 从机器人获取舵机角度
     ///
-    /// 这是主要的数据交换函数
-    pub fn sync(&mut self) -> Result<bool, Error> {
-        if !self.is_connected {
-            #[cfg(feature = "logging")]
-            log::error!("同步失败: 未连接到设备");
-            return Err(Error::NotConnected);
+    /// 返回最近一次同步时机器人上报的角度（设置了滤波时为滤波后的角度）；
+    /// 还没有收到机器人数据时返回设置的目标角度。断开连接后保留最后上报的角度，重新连接时清空
+    ///
+    /// 之前的版本同步后也返回目标角度，需要目标角度时用 [`ElectronBot::target_joint_angles`]
+    pub fn get_joint_angles(&self) -> JointAngles {
+        match (self.joint_filter.value(), &self.received) {
+            (Some(filtered), Some(_)) => filtered.clone(),
+            _ => self.raw_joint_angles(),
         }
+    }
 
-        let usb = match &mut self.usb {
-            Some(u) => u,
-            None => return Err(Error::NotConnected),
+    /// 最近一次同步时机器人上报的原始角度（不经过滤波）
+    pub fn raw_joint_angles(&self) -> JointAngles {
+        match &self.received {
+            Some(received) => received.get_joint_angles(),
+            None => self.extra_data.get_joint_angles(),
+        }
+    }
+
+    /// 设置上报角度的滤波方式（默认不滤波），之前的滤波历史被清空
+    pub fn set_joint_filter(&mut self, kind: FilterKind) {
+        self.joint_filter = JointFilter::new(kind);
+    }
+
+    /// 上报角度的滤波方式
+    pub fn joint_filter(&self) -> FilterKind {
+        self.joint_filter.kind()
+    }
+
+    /// 获取设置的目标舵机角度
+    pub fn target_joint_angles(&self) -> JointAngles {
+        self.extra_data.get_joint_angles()
+    }
+
+    /// 最近一次同步时机器人发来的扩展数据（还没有收到时为 `None`）
+    pub fn received_extra_data(&self) -> Option<&[u8; 32]> {
+        self.received.as_ref().map(ExtraData::get_raw)
+    }
+
+    /// 开启或关闭关节堵转检测（默认关闭）
+    ///
+    /// 开启后每次同步比较目标角度和上报角度，关节持续跟不上或一直不动时
+    /// 通过 `on_event` 发出 `JointLagging` / `JointStalled`，恢复后发出 `JointRecovered`
+    pub fn set_stall_detection(&mut self, options: Option<StallOptions>) {
+        self.stall_detector = options.map(StallDetector::new);
+    }
+
+    /// 每个关节的堵转检测状态（未开启检测时都为 `Ok`）
+    pub fn joint_status(&self) -> [JointStatus; 6] {
+        self.stall_detector
+            .as_ref()
+            .map(StallDetector::status)
+            .unwrap_or_default()
+    }
+
+    /// 开启或关闭自碰撞保护（默认关闭）
+    ///
+    /// 开启后 `set_joint_angles` 按保护的处理方式检查目标；帧源、`set_extra_data` 等
+    /// 不经过 `set_joint_angles` 的角度在发送前总是被限制到不碰撞
+    pub fn set_collision_guard(&mut self, guard: Option<CollisionGuard>) {
+        self.collision_guard = guard;
+    }
+
+    /// 当前的碰撞保护设置
+    pub fn collision_guard(&self) -> Option<&CollisionGuard> {
+        self.collision_guard.as_ref()
+    }
+
+    /// 开启或关闭舵机软启动（默认关闭）
+    ///
+    /// 开启后使能标志从 0 变为 1 时，先发送机器人上报的当前角度，
+    /// 再在 `duration` 内平滑过渡到设置的目标角度；还没有收到上报角度时先以失能状态同步一次
+    pub fn set_soft_start(&mut self, duration: Option<std::time::Duration>) {
+        self.soft_start = duration.map(SoftStart::new);
+    }
+
+    /// 软启动的过渡时间（未开启时为 `None`）
+    pub fn soft_start(&self) -> Option<std::time::Duration> {
+        self.soft_start.as_ref().map(SoftStart::duration)
+    }
+
+    /// 是否正在软启动过渡中
+    pub fn is_soft_starting(&self) -> bool {
+        self.soft_start.as_ref().is_some_and(SoftStart::is_ramping)
+    }
+
+    /// 发送舵机参数（速度、加速度、PID），需要固件支持
+    ///
+    /// 参数由编码器（默认 `ParamEncoder`）编码为若干条消息排队，之后每次同步在扩展数据的
+    /// 保留字节中发送一条。返回排队的消息数，编码器不支持的参数返回 `BotError::ConfigError`
+    pub fn send_servo_tuning(&mut self, tuning: &ServoTuning) -> Result<usize, Error> {
+        let messages = self
+            .tuning_encoder
+            .encode(tuning)
+            .map_err(Error::ConfigError)?;
+        #[cfg(feature = "logging")]
+        log::info!("舵机参数编码为 {} 条消息", messages.len());
+        let count = messages.len();
+        self.tuning_queue.extend(messages);
+        self.last_tuning = Some(*tuning);
+        Ok(count)
+    }
+
+    /// 设置舵机参数的编码器，用于不同固件分支的参数格式
+    pub fn set_tuning_encoder<E: TuningEncoder + 'static>(&mut self, encoder: E) {
+        self.tuning_encoder = Box::new(encoder);
+    }
+
+    /// 还没有发送的舵机参数消息数
+    pub fn pending_tuning_messages(&self) -> usize {
+        self.tuning_queue.len()
+    }
+
+    /// 本次同步实际发送的扩展数据，与目标相同时为 `None`
+    ///
+    /// 软启动过渡中时替换关节角度，会碰撞时限制关节角度，有待发送的舵机参数时写入保留字节
+    fn outgoing_extra_data(&mut self) -> Option<ExtraData> {
+        let measured = self.received.as_ref().map(ExtraData::get_joint_angles);
+        let mut outgoing = self.soft_start.as_mut().and_then(|soft_start| {
+            soft_start.apply(
+                modules::time::Instant::now(),
+                &self.extra_data,
+                measured.as_ref(),
+            )
+        });
+        if let Some(guard) = &self.collision_guard {
+            let current = outgoing.as_ref().unwrap_or(&self.extra_data);
+            let angles = current.get_joint_angles();
+            if let Err(_collision) = guard.check(&angles) {
+                #[cfg(feature = "logging")]
+                log::warn!("{}，发送前已限制关节角度", _collision);
+                let mut data = current.clone();
+                data.set_joint_angles(&guard.clamp(&angles), current.is_enabled());
+                outgoing = Some(data);
+            }
+        }
+        let Some(message) = self.tuning_queue.front() else {
+            return outgoing;
+        };
+        let mut data = outgoing.unwrap_or_else(|| self.extra_data.clone());
+        data.as_mut_data()[TUNING_OFFSET..TUNING_OFFSET + TUNING_LEN].copy_from_slice(message);
+        Some(data)
+    }
+
+    /// 最近若干次同步收到的遥测记录（带时间戳，默认保留 256 条）
+    pub fn telemetry_history(&self) -> &TelemetryHistory {
+        &self.telemetry
+    }
+
+    /// 设置遥测历史保留的记录数，0 表示不记录
+    pub fn set_telemetry_history_len(&mut self, len: usize) {
+        self.telemetry.set_capacity(len);
+    }
+
+    /// 最近的连接和传输事件（带时间戳和错误详情，默认保留 64 条），用于诊断面板
+    pub fn recent_events(&self) -> &EventLog {
+        &self.event_log
+    }
+
+    /// 设置诊断事件日志保留的事件数，0 表示不记录
+    pub fn set_event_log_len(&mut self, len: usize) {
+        self.event_log.set_capacity(len);
+    }
+
+    // ==================== 急停 ====================
+
+    /// 急停：舵机立即失能并锁定，直到调用 `clear_emergency_stop`
+    ///
+    /// 已连接时立即同步一次发送失能；之后所有同步都发送失能（包括其他线程正在进行的同步，
+    /// 会中止后重新发送），`play`、`play_pipelined` 和自检返回 `BotError::EmergencyStop`。
+    /// 通过 `on_event` 发出 `Event::EmergencyStop`
+    pub fn emergency_stop(&mut self) -> Result<(), Error> {
+        #[cfg(feature = "logging")]
+        log::warn!("急停");
+        self.estop.trigger();
+        self.extra_data.set_enable(false);
+        self.event_log.push(LogKind::EmergencyStop, "");
+        self.emit(Event::EmergencyStop);
+        if self.is_connected {
+            self.sync()?;
+        }
+        Ok(())
+    }
+
+    /// 解除急停
+    ///
+    /// 舵机保持失能，需要用 `set_joint_angles(..., true)` 重新使能
+    pub fn clear_emergency_stop(&mut self) {
+        #[cfg(feature = "logging")]
+        log::info!("解除急停");
+        self.estop.clear();
+    }
+
+    /// 是否处于急停状态
+    pub fn is_emergency_stopped(&self) -> bool {
+        self.estop.is_triggered()
+    }
+
+    /// 获取急停标志，可以交给急停按钮的回调或其他线程
+    ///
+    /// 其他线程触发后，下一个包之前中止正在进行的同步并重新发送失能，
+    /// 正在播放的帧源在下一帧结束
+    pub fn emergency_stop_handle(&self) -> EmergencyStop {
+        self.estop.clone()
+    }
+
+    /// 急停时发送一次失能并返回 `BotError::EmergencyStop`，用于中止播放
+    fn check_emergency_stop(&mut self) -> Result<(), Error> {
+        if !self.estop.is_triggered() {
+            return Ok(());
+        }
+        if self.is_connected {
+            self.sync()?;
+        }
+        Err(Error::EmergencyStop)
+    }
+
+    /// 用本次同步收到的数据更新滤波、遥测历史和堵转检测
+    fn update_telemetry(&mut self) {
+        let Some(received) = &self.received else {
+            return;
+        };
+        let raw = received.get_joint_angles();
+        let reported = self.joint_filter.update(&raw);
+        self.telemetry.push(TelemetrySample {
+            at: modules::time::Instant::now(),
+            raw: *received.get_raw(),
+            reported: raw,
+            filtered: reported.clone(),
+            target: self.extra_data.get_joint_angles(),
+            enabled: self.extra_data.is_enabled(),
+        });
+        let Some(detector) = &mut self.stall_detector else {
+            return;
+        };
+        let events = detector.update(
+            modules::time::Instant::now(),
+            &self.extra_data.get_joint_angles(),
+            &reported,
+            self.extra_data.is_enabled(),
+        );
+        for event in events {
+            self.emit(event);
+        }
+    }
+
+    // ==================== 同步 ====================
+
+    /// 与机器人同步数据
+    ///
+    /// 这是主要的数据交换函数。开启了自动恢复（`set_auto_resume`）时，
+    /// 设备在主机休眠后丢失会先重新打开设备再同步
+    pub fn sync(&mut self) -> Result<bool, Error> {
+        match self.sync_until(None) {
+            Err(e) if self.can_resume(&e) => {
+                self.resume(e)?;
+                self.sync_until(None)
+            }
+            result => result,
+        }
+    }
+
+    /// 开启或关闭休眠恢复（默认关闭）
+    ///
+    /// 笔记本合盖休眠后 USB 设备会重新枚举，原来的句柄失效。开启后 `sync` 遇到设备丢失时
+    /// 在 `timeout` 内轮询重新打开设备，恢复最近一次发送的舵机参数，重新发送当前画面和姿态，
+    /// 并发出 `Event::Resumed`。只对 `connect` 打开的 USB 连接生效；`sync_with_deadline` 不会等待恢复
+    pub fn set_auto_resume(&mut self, timeout: Option<std::time::Duration>) {
+        self.auto_resume = timeout;
+    }
+
+    /// 休眠恢复等待设备重新出现的时间（未开启时为 `None`）
+    pub fn auto_resume(&self) -> Option<std::time::Duration> {
+        self.auto_resume
+    }
+
+    /// 同步失败是否可以通过重新打开设备恢复
+    fn can_resume(&self, error: &Error) -> bool {
+        self.auto_resume.is_some() && self.reopen.is_some() && error.is_device_lost()
+    }
+
+    /// 重新打开休眠后重新枚举的设备，恢复舵机参数
+    ///
+    /// 超时前没有找到设备时返回原来的同步错误
+    fn resume(&mut self, error: Error) -> Result<(), Error> {
+        let timeout = self.auto_resume.unwrap_or_default();
+        #[cfg(feature = "tracing")]
+        let _span =
+            tracing::info_span!("resume", timeout_ms = timeout.as_millis() as u64).entered();
+        #[cfg(feature = "logging")]
+        log::warn!("设备丢失，等待重新枚举: {}", error);
+        let deadline = modules::time::Instant::now() + timeout;
+        // attach 会断开旧连接并清掉 reopen，恢复成功后再放回去
+        let Some(mut reopen) = self.reopen.take() else {
+            return Err(error);
+        };
+        let variant = loop {
+            if self.cancel.is_cancelled() {
+                self.reopen = Some(reopen);
+                return Err(Error::Cancelled);
+            }
+            match reopen(&self.variants) {
+                Ok((transport, variant)) => {
+                    self.attach(transport);
+                    self.log_connected(&variant);
+                    break variant;
+                }
+                Err(_e) if modules::time::Instant::now() < deadline => {
+                    std::thread::sleep(RESUME_POLL_INTERVAL);
+                }
+                Err(_e) => {
+                    #[cfg(feature = "logging")]
+                    log::error!("休眠恢复失败: {}", _e);
+                    self.reopen = Some(reopen);
+                    return Err(error);
+                }
+            }
+        };
+        #[cfg(feature = "logging")]
+        log::info!("设备已恢复: {}", variant.name);
+        #[cfg(feature = "tracing")]
+        tracing::info!(variant = %variant.name, "resumed");
+        self.event_log.push(LogKind::Resumed, variant.name.clone());
+        self.connected_variant = Some(variant);
+        self.reopen = Some(reopen);
+        if let Some(tuning) = self.last_tuning {
+            self.send_servo_tuning(&tuning)?;
+        }
+        self.emit(Event::Resumed);
+        Ok(())
+    }
+
+    /// 在 `budget` 时间内完成同步（包括所有重试）
+    ///
+    /// 每次收发的超时不超过剩余时间；到期时中止并返回 `BotError::SyncTimeout`，
+    /// 其中包含已完成的周期数和已发送的包数，下一次同步会重新发送完整的一帧
+    pub fn sync_with_deadline(&mut self, budget: std::time::Duration) -> Result<bool, Error> {
+        self.sync_until(Some(modules::time::Instant::now() + budget))
+    }
+
+    /// 最近一次同步的进度：完成的周期数、发送的包数和重试次数
+    pub fn last_sync_progress(&self) -> SyncProgress {
+        self.last_progress
+    }
+
+    fn sync_until(&mut self, deadline: Option<modules::time::Instant>) -> Result<bool, Error> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!(
+            "sync",
+            timestamp = tracing::field::Empty,
+            cycles = tracing::field::Empty,
+            packets = tracing::field::Empty,
+        )
+        .entered();
+        if self.estop.is_triggered() {
+            self.extra_data.set_enable(false);
+        }
+        if !self.is_connected {
+            #[cfg(feature = "logging")]
+            log::error!("同步失败: 未连接到设备");
+            return Err(Error::NotConnected);
+        }
+
+        let outgoing = self.outgoing_extra_data();
+        let transport = match &mut self.transport {
+            Some(t) => t.as_mut(),
+            None => return Err(Error::NotConnected),
         };
 
         #[cfg(feature = "logging")]
         log::info!("开始同步数据...");
-        match modules::sync::sync(
-            usb,
+        let frame = output_frame(
             &self.image_buffer,
-            &self.extra_data,
+            &self.post_processors,
+            self.display_on,
+            self.brightness,
+            self.config.orientation,
+            self.color_lut.as_ref(),
+            self.dithering,
+        );
+        // 局部刷新按 4 段标记，其他分段方式发送完整的一帧
+        let quarters = if self.partial_updates && self.sync_context.profile.cycles == 4 {
+            let mut mask = match &self.last_sent {
+                Some(last) => QuarterMask::diff(&frame, last) | self.dirty,
+                None => QuarterMask::ALL,
+            };
+            // 画面没有变化时仍然发送一段，用于传递舵机角度和读取反馈
+            if mask.is_empty() {
+                mask.insert(0);
+            }
+            Some(mask)
+        } else {
+            None
+        };
+        let extra_data = outgoing.as_ref().unwrap_or(&self.extra_data);
+        let cancel = &self.cancel;
+        let estop = &self.estop;
+        let enabled = extra_data.is_enabled();
+        let mut stopped = false;
+        let mut expired = false;
+        let mut progress = SyncProgress::default();
+        let result = modules::sync::sync_checked(
+            transport,
+            frame.as_data(),
+            extra_data,
             &mut self.sync_context,
-        ) {
+            &mut self.received,
+            quarters,
+            &mut progress,
+            &mut |transport| {
+                if cancel.is_cancelled() {
+                    return Err("同步已取消".to_string());
+                }
+                // 同步期间触发急停时中止，重新发送失能
+                if enabled && estop.is_triggered() {
+                    stopped = true;
+                    return Err("急停".to_string());
+                }
+                if let Some(deadline) = deadline {
+                    let remaining =
+                        deadline.saturating_duration_since(modules::time::Instant::now());
+                    if remaining.is_zero() {
+                        expired = true;
+                        return Err("同步超时".to_string());
+                    }
+                    // 超时为 0 在 libusb 中表示不限时，至少保留 1 毫秒
+                    transport.set_timeout(Some(remaining.clamp(
+                        std::time::Duration::from_millis(1),
+                        std::time::Duration::from_millis(TIMEOUT_MS),
+                    )));
+                }
+                Ok(())
+            },
+        );
+        if deadline.is_some() {
+            transport.set_timeout(None);
+        }
+        #[cfg(feature = "tracing")]
+        _span
+            .record("timestamp", self.sync_context.timestamp)
+            .record("cycles", progress.cycles)
+            .record("packets", progress.packets);
+        self.last_progress = progress;
+        if !self.frame_sent_handlers.is_empty() {
+            let profile = self.sync_context.profile;
+            let segments = modules::sync::sync_segments(&self.sync_context, quarters);
+            for &segment in segments.iter().take(progress.cycles) {
+                let bytes = modules::sync::cycle_data(
+                    &profile,
+                    frame.as_data(),
+                    extra_data,
+                    segment,
+                    quarters.is_some(),
+                );
+                for handler in &mut self.frame_sent_handlers {
+                    handler(segment, &bytes);
+                }
+            }
+        }
+        // 同步失败时设备上的内容未知，下一次发送完整的一帧
+        self.last_sent = match (&result, self.partial_updates) {
+            (Ok(true), true) => Some(frame.clone().into_owned()),
+            _ => None,
+        };
+        if result.is_ok() {
+            self.dirty = QuarterMask::NONE;
+        }
+        match result {
             Ok(true) => {
+                self.tuning_queue.pop_front();
+                self.stats.record_frame(&self.extra_data);
+                #[cfg(feature = "image")]
+                if let Some(recorder) = &mut self.recorder {
+                    if let Err(_e) = recorder.record(&frame, &self.extra_data) {
+                        #[cfg(feature = "logging")]
+                        log::error!("录制失败，已停止录制: {}", _e);
+                        self.recorder = None;
+                    }
+                }
+                drop(frame);
+                self.update_telemetry();
                 #[cfg(feature = "logging")]
                 log::info!("同步成功");
                 Ok(true)
@@ -330,12 +1723,327 @@ impl ElectronBot {
                 log::warn!("同步返回 false");
                 Ok(false)
             }
+            Err(_e) if self.cancel.is_cancelled() => {
+                #[cfg(feature = "logging")]
+                log::warn!("同步已取消");
+                self.event_log.push(LogKind::Cancelled, "");
+                Err(Error::Cancelled)
+            }
+            Err(_e) if stopped => {
+                #[cfg(feature = "logging")]
+                log::warn!("同步期间触发急停，重新发送失能");
+                self.sync_until(deadline)
+            }
+            Err(_e) if expired => {
+                #[cfg(feature = "logging")]
+                log::warn!(
+                    "同步超时: 已完成 {} 个周期，发送 {} 个包",
+                    progress.cycles,
+                    progress.packets
+                );
+                let error = Error::SyncTimeout(progress.cycles, progress.packets);
+                self.event_log.push(LogKind::SyncTimeout, error.to_string());
+                self.emit(Event::SyncFailed);
+                Err(error)
+            }
             Err(e) => {
                 #[cfg(feature = "logging")]
                 log::error!("同步失败: {}", e);
-                Err(Error::SendFailed(e))
+                #[cfg(feature = "tracing")]
+                tracing::error!(error = %e, "sync failed");
+                self.event_log.push(LogKind::SyncFailed, e.to_string());
+                self.emit(Event::SyncFailed);
+                Err(e)
+            }
+        }
+    }
+
+    /// 开始逐段发送一帧：每设置好一段（60 行）就发送，不必等整帧准备好
+    ///
+    /// 段按顺序发送，亮度、色彩校正和水平翻转逐段生效；旋转或垂直翻转时段与屏幕行不对应，
+    /// 所有段在 `finish` 时一起发送
+    pub fn stream_frame(&mut self) -> Result<FrameStream<'_>, Error> {
+        if !self.is_connected || self.transport.is_none() {
+            #[cfg(feature = "logging")]
+            log::error!("逐段发送失败: 未连接到设备");
+            return Err(Error::NotConnected);
+        }
+        if self.cancel.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+        if self.sync_context.profile.cycles != 4 {
+            return Err(Error::ConfigError(
+                "逐段发送只支持每帧 4 个周期的传输布局".to_string(),
+            ));
+        }
+        self.sync_context.toggle();
+        #[cfg(feature = "logging")]
+        log::info!("开始逐段发送: timestamp={}", self.sync_context.timestamp);
+        let orientation = self.config.orientation;
+        let deferred = orientation.rotation != Rotation::Deg0 || orientation.flip_vertical;
+        Ok(FrameStream::new(self, deferred))
+    }
+
+    /// 发送逐段发送中的一段
+    pub(crate) fn stream_segment(
+        &mut self,
+        segment: usize,
+        progress: &mut SyncProgress,
+    ) -> Result<(), Error> {
+        if self.estop.is_triggered() {
+            self.extra_data.set_enable(false);
+        }
+        let outgoing = self.outgoing_extra_data();
+        let Some(transport) = self.transport.as_mut() else {
+            return Err(Error::NotConnected);
+        };
+        // 误差扩散等处理只依赖之前的行，按当前缓冲区计算的这一段与整帧处理的结果相同
+        let frame = output_frame(
+            &self.image_buffer,
+            &self.post_processors,
+            self.display_on,
+            self.brightness,
+            self.config.orientation,
+            self.color_lut.as_ref(),
+            self.dithering,
+        );
+        let cancel = &self.cancel;
+        let result = modules::sync::sync_segment(
+            transport.as_mut(),
+            frame.as_data(),
+            &self.sync_context.profile,
+            outgoing.as_ref().unwrap_or(&self.extra_data),
+            segment,
+            false,
+            &mut self.received,
+            progress,
+            &mut |_| {
+                if cancel.is_cancelled() {
+                    Err("同步已取消".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+        );
+        drop(frame);
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                self.last_sent = None;
+                if self.cancel.is_cancelled() {
+                    #[cfg(feature = "logging")]
+                    log::warn!("逐段发送已取消");
+                    self.event_log.push(LogKind::Cancelled, "");
+                    return Err(Error::Cancelled);
+                }
+                #[cfg(feature = "logging")]
+                log::error!("逐段发送失败: {}", e);
+                self.event_log.push(LogKind::SyncFailed, e.to_string());
+                self.emit(Event::SyncFailed);
+                Err(e)
+            }
+        }
+    }
+
+    /// 逐段发送完成，记录统计和录制
+    pub(crate) fn stream_end(&mut self) {
+        let frame = output_frame(
+            &self.image_buffer,
+            &self.post_processors,
+            self.display_on,
+            self.brightness,
+            self.config.orientation,
+            self.color_lut.as_ref(),
+            self.dithering,
+        )
+        .into_owned();
+        self.stats.record_frame(&self.extra_data);
+        self.tuning_queue.pop_front();
+        self.update_telemetry();
+        #[cfg(feature = "image")]
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(_e) = recorder.record(&frame, &self.extra_data) {
+                #[cfg(feature = "logging")]
+                log::error!("录制失败，已停止录制: {}", _e);
+                self.recorder = None;
+            }
+        }
+        // 逐段发送总是发送完整的一帧
+        self.dirty = QuarterMask::NONE;
+        self.last_sent = self.partial_updates.then_some(frame);
+        #[cfg(feature = "logging")]
+        log::info!("逐段发送完成");
+    }
+
+    /// 播放帧源：逐帧渲染到图片缓冲区并同步
+    ///
+    /// 按帧源的 `frame_interval` 控制帧率；USB 发送跟不上源帧率时丢弃落后的帧，
+    /// 保持播放进度与时间同步。帧源提供舵机角度时随帧一起发送。
+    /// 帧源结束或达到 `max_frames` 时返回发送和丢弃的帧数。
+    pub fn play(
+        &mut self,
+        source: &mut dyn FrameSource,
+        max_frames: Option<u64>,
+    ) -> Result<PlayStats, Error> {
+        let mut frames = 0u64;
+        let mut dropped = 0u64;
+        let mut next_at = modules::time::Instant::now();
+        'play: while max_frames.is_none_or(|max| frames < max) {
+            self.check_emergency_stop()?;
+            if !source.next_frame(&mut self.image_buffer) {
+                break;
             }
+            if let Some(angles) = source.joint_angles() {
+                self.extra_data.set_joint_angles(&angles, true);
+            }
+            self.sync()?;
+            frames += 1;
+
+            if let Some(interval) = source.frame_interval() {
+                next_at += interval;
+                let now = modules::time::Instant::now();
+                if next_at > now {
+                    std::thread::sleep(next_at - now);
+                }
+                // 落后超过一帧时跳过这些帧
+                while now >= next_at + interval {
+                    if !source.next_frame(&mut self.image_buffer) {
+                        break 'play;
+                    }
+                    next_at += interval;
+                    dropped += 1;
+                }
+            }
+        }
+        #[cfg(feature = "logging")]
+        log::info!("帧源播放结束，发送 {} 帧，丢弃 {} 帧", frames, dropped);
+        Ok(PlayStats { frames, dropped })
+    }
+
+    /// 流水线播放帧源：工作线程渲染（解码、转换）下一帧的同时发送当前帧
+    ///
+    /// 与 `play` 的帧率控制和丢帧规则相同，适合解码耗时与 USB 传输相当的视频等帧源。
+    /// 帧源在工作线程中渲染到自己的画布上，渲染完成后复制到两个交替使用的缓冲区之一；
+    /// 帧源的 `next_frame` 一直阻塞时本函数也不会返回
+    pub fn play_pipelined<S: FrameSource + Send>(
+        &mut self,
+        source: &mut S,
+        max_frames: Option<u64>,
+    ) -> Result<PlayStats, Error> {
+        use std::sync::mpsc;
+
+        /// 渲染好的一帧：画面、关节角度和帧间隔
+        type Rendered = (
+            ImageBuffer,
+            Option<JointAngles>,
+            Option<std::time::Duration>,
+        );
+
+        let (frame_tx, frame_rx) = mpsc::channel::<Rendered>();
+        // 发送完的缓冲区连同需要跳过的帧数还给工作线程
+        let (free_tx, free_rx) = mpsc::channel::<(ImageBuffer, u64)>();
+        for _ in 0..2 {
+            let _ = free_tx.send((ImageBuffer::new(), 0));
         }
+
+        std::thread::scope(|scope| {
+            scope.spawn(move || {
+                let mut canvas = ImageBuffer::new();
+                while let Ok((mut buffer, skip)) = free_rx.recv() {
+                    for _ in 0..skip {
+                        if !source.next_frame(&mut canvas) {
+                            return;
+                        }
+                    }
+                    if !source.next_frame(&mut canvas) {
+                        return;
+                    }
+                    buffer.as_mut_data().copy_from_slice(canvas.as_data());
+                    let rendered = (buffer, source.joint_angles(), source.frame_interval());
+                    if frame_tx.send(rendered).is_err() {
+                        return;
+                    }
+                }
+            });
+
+            let mut frames = 0u64;
+            let mut dropped = 0u64;
+            let mut failure = None;
+            let mut next_at = modules::time::Instant::now();
+            while max_frames.is_none_or(|max| frames < max) {
+                if let Err(e) = self.check_emergency_stop() {
+                    failure = Some(e);
+                    break;
+                }
+                let Ok((mut buffer, angles, interval)) = frame_rx.recv() else {
+                    break;
+                };
+                std::mem::swap(&mut self.image_buffer, &mut buffer);
+                if let Some(angles) = angles {
+                    self.extra_data.set_joint_angles(&angles, true);
+                }
+                let result = self.sync();
+                frames += 1;
+
+                let mut skip = 0;
+                if let Some(interval) = interval {
+                    next_at += interval;
+                    let now = modules::time::Instant::now();
+                    if next_at > now {
+                        std::thread::sleep(next_at - now);
+                    }
+                    // 落后超过一帧时让工作线程跳过这些帧
+                    while now >= next_at + interval {
+                        next_at += interval;
+                        skip += 1;
+                    }
+                }
+                dropped += skip;
+                if let Err(e) = result {
+                    failure = Some(e);
+                    break;
+                }
+                let _ = free_tx.send((buffer, skip));
+            }
+            // 关闭通道，工作线程渲染完当前帧后退出
+            drop(free_tx);
+            drop(frame_rx);
+            if let Some(e) = failure {
+                return Err(e);
+            }
+            #[cfg(feature = "logging")]
+            log::info!("流水线播放结束，发送 {} 帧，丢弃 {} 帧", frames, dropped);
+            Ok(PlayStats { frames, dropped })
+        })
+    }
+
+    /// 显示通知：从屏幕边缘滑入，停留后滑出，结束后恢复原来的画面
+    ///
+    /// 阻塞直到通知播放完毕；需要在帧源播放期间显示通知时使用 `NotificationOverlay`
+    pub fn notify(&mut self, notification: Notification) -> Result<PlayStats, Error> {
+        #[cfg(feature = "logging")]
+        log::info!("显示通知，时长 {:?}", notification.total_duration());
+        let mut playback = NotificationPlayback::new(notification, self.image_buffer.clone());
+        let stats = self.play(&mut playback, None)?;
+        self.image_buffer
+            .as_mut_data()
+            .copy_from_slice(playback.background().as_data());
+        self.sync()?;
+        Ok(stats)
+    }
+
+    /// 注视目标：在空闲动画上移动瞳孔，超出眼球范围时转动头部和身体
+    ///
+    /// 逐帧播放直到视线稳定在目标上，返回播放的帧数
+    pub fn gaze_at(
+        &mut self,
+        idle: &mut IdleBehavior,
+        target: GazeTarget,
+    ) -> Result<PlayStats, Error> {
+        #[cfg(feature = "logging")]
+        log::info!("注视目标: {:?}", target);
+        idle.gaze_at(target);
+        self.play(&mut UntilGazeSettled(idle), Some(GAZE_MAX_FRAMES))
     }
 
     /// 快速同步（不处理错误）
@@ -347,199 +2055,6013 @@ impl ElectronBot {
     pub fn sync_context(&self) -> &SyncContext {
         &self.sync_context
     }
-}
 
-impl Default for ElectronBot {
-    fn default() -> Self {
-        Self::new()
+    /// 设置传输布局（用于分段方式不同的固件分支）
+    ///
+    /// 图片缓冲区固定为 240×240，布局的帧大小必须与之相同；
+    /// 屏幕尺寸不同的固件请直接使用 `modules::sync::sync_frame`
+    pub fn set_protocol_profile(&mut self, profile: ProtocolProfile) -> Result<(), Error> {
+        profile.validate().map_err(Error::ConfigError)?;
+        if profile.frame_size() != FRAME_SIZE {
+            return Err(Error::ConfigError(format!(
+                "帧大小 {}×{} 与图片缓冲区 {}×{} 不一致",
+                profile.width, profile.height, FRAME_WIDTH, FRAME_HEIGHT
+            )));
+        }
+        #[cfg(feature = "logging")]
+        log::info!("传输布局: {:?}", profile);
+        self.sync_context.profile = profile;
+        self.sync_context.cycles = profile.cycles;
+        self.last_sent = None;
+        Ok(())
     }
-}
 
-impl Drop for ElectronBot {
-    fn drop(&mut self) {
-        self.disconnect();
+    /// 当前的传输布局
+    pub fn protocol_profile(&self) -> ProtocolProfile {
+        self.sync_context.profile
     }
-}
 
-// ==================== 便捷函数 ====================
+    /// 开启或关闭局部刷新（默认关闭）
+    ///
+    /// 开启后同步只发送与上一次发送内容不同（或用 `mark_dirty` 标记）的 60 行分段，
+    /// 尾包扩展数据的最后一个字节标记段序号。需要固件支持，原版固件按顺序接收 4 段，
+    /// 开启后画面会错位
+    pub fn set_partial_updates(&mut self, enable: bool) {
+        #[cfg(feature = "logging")]
+        log::info!("局部刷新: {}", if enable { "开启" } else { "关闭" });
+        self.partial_updates = enable;
+        self.last_sent = None;
+    }
 
-/// 快速测试函数
-pub fn quick_test() -> Result<bool, Error> {
-    let mut bot = ElectronBot::new();
-    bot.connect()?;
-    println!("已连接到 ElectronBot!");
-    bot.set_image_color(Color::Red);
-    bot.sync()?;
-    println!("同步成功!");
-    bot.disconnect();
-    Ok(true)
-}
+    /// 是否开启了局部刷新
+    pub fn partial_updates(&self) -> bool {
+        self.partial_updates
+    }
 
-/// 扫描并打印所有设备
-pub fn list_devices() {
-    println!("正在扫描 USB 设备...");
-    let devices = ElectronBot::scan_devices();
-    println!("找到 {} 个设备:", devices.len());
+    /// 标记区域需要重新发送（局部刷新时即使内容没有变化也发送覆盖该区域的分段）
+    pub fn mark_dirty(&mut self, x: usize, y: usize, width: usize, height: usize) {
+        // 分段按行划分，只用到纵向范围
+        let _ = (x, width);
+        if self.config.orientation.is_identity() {
+            self.dirty |= QuarterMask::from_rows(y, height);
+        } else {
+            // 旋转或翻转后区域对应的行不同，直接标记全部
+            self.dirty = QuarterMask::ALL;
+        }
+    }
+
+    /// 标记整帧需要重新发送
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty = QuarterMask::ALL;
+    }
+
+    /// 下一次同步将发送的分段（未开启局部刷新时为全部）
+    pub fn dirty_quarters(&self) -> QuarterMask {
+        if !self.partial_updates {
+            return QuarterMask::ALL;
+        }
+        let Some(last) = &self.last_sent else {
+            return QuarterMask::ALL;
+        };
+        let frame = output_frame(
+            &self.image_buffer,
+            &self.post_processors,
+            self.display_on,
+            self.brightness,
+            self.config.orientation,
+            self.color_lut.as_ref(),
+            self.dithering,
+        );
+        QuarterMask::diff(&frame, last) | self.dirty
+    }
+
+    // ==================== 底层访问 ====================
+
+    /// 底层传输通道，未连接时为 `None`
+    ///
+    /// 绕过同步协议直接收发会打乱 MCU 的请求节奏，只用于固件开发时试验新的控制消息，
+    /// 之后的第一次同步可能需要重试
+    pub fn transport_mut(&mut self) -> Option<&mut dyn Transport> {
+        match &mut self.transport {
+            Some(transport) => Some(transport.as_mut()),
+            None => None,
+        }
+    }
+
+    /// 底层 USB 设备（句柄和端点），不是通过 USB 连接时为 `None`
+    ///
+    /// 可以用 `handle` 做控制传输等操作，不必另外用 rusb 打开设备争夺已声明的接口；
+    /// 注意事项同 `transport_mut`
+    pub fn usb_device_mut(&mut self) -> Option<&mut UsbDevice> {
+        self.transport
+            .as_mut()?
+            .as_any_mut()?
+            .downcast_mut::<UsbDevice>()
+    }
+
+    /// 重启进入 bootloader 以更新固件，成功后断开连接（`dfu` feature）
+    ///
+    /// 需要固件提供 DFU 运行时接口；原版固件没有这个接口，返回错误时需要按住 BOOT0 键重新上电
+    #[cfg(feature = "dfu")]
+    pub fn enter_bootloader(&mut self) -> Result<(), Error> {
+        let usb = self.usb_device_mut().ok_or(Error::NotConnected)?;
+        modules::dfu::reboot_to_bootloader(usb).map_err(Error::UsbError)?;
+        #[cfg(feature = "logging")]
+        log::info!("已重启进入 bootloader");
+        self.disconnect();
+        Ok(())
+    }
+
+    /// 直接发送一个包（不经过同步协议）
+    pub fn send_raw(&mut self, data: &[u8]) -> Result<(), Error> {
+        let transport = self.transport.as_mut().ok_or(Error::NotConnected)?;
+        #[cfg(feature = "logging")]
+        log::debug!("直接发送 {} 字节", data.len());
+        transport.transmit(data).map(|_| ()).map_err(|message| {
+            Error::SendFailed(TransferError {
+                message,
+                expected: data.len(),
+                kind: transport.last_error_kind().unwrap_or_default(),
+                ..TransferError::default()
+            })
+        })
+    }
+
+    /// 直接接收一个包，返回接收的字节数；`timeout` 为 `None` 时使用默认超时
+    pub fn recv_raw(
+        &mut self,
+        buffer: &mut [u8],
+        timeout: Option<std::time::Duration>,
+    ) -> Result<usize, Error> {
+        let transport = self.transport.as_mut().ok_or(Error::NotConnected)?;
+        if timeout.is_some() {
+            transport.set_timeout(timeout);
+        }
+        let result = transport.receive(buffer);
+        if timeout.is_some() {
+            transport.set_timeout(None);
+        }
+        #[cfg(feature = "logging")]
+        if let Ok(len) = &result {
+            log::debug!("直接接收 {} 字节", len);
+        }
+        result.map_err(|message| {
+            Error::ReceiveFailed(TransferError {
+                message,
+                expected: buffer.len(),
+                kind: transport.last_error_kind().unwrap_or_default(),
+                ..TransferError::default()
+            })
+        })
+    }
+
+    /// 获取取消标志，可以交给 Ctrl+C 回调或其他线程
+    ///
+    /// 取消后正在进行的同步在下一个包之前中止并返回 `BotError::Cancelled`，
+    /// 之后的连接和同步也立即返回该错误，调用 `reset()` 后恢复
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancel.clone()
+    }
+
+    /// 使用共享的取消标志（例如多个机器人共用一个）
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancel = token;
+    }
+
+    /// 交给后台同步线程，返回可克隆、可跨线程共享的句柄
+    ///
+    /// 多个线程可以分别更新画面和关节，不会被正在进行的同步阻塞；
+    /// 无法创建同步线程时返回 `BotError::ThreadError`
+    pub fn into_handle(self) -> Result<BotHandle, BotError> {
+        #[cfg(feature = "logging")]
+        log::info!("启动后台同步线程");
+        BotHandle::spawn(self)
+    }
+
+    // ==================== 自检 ====================
+
+    /// 运行组装后的自检：纯色和渐变画面、逐个关节小幅转动并比较上报角度、吞吐量测量
+    ///
+    /// 结束后恢复原来的画面和关节指令
+    pub fn self_test(&mut self) -> Result<SelfTestReport, Error> {
+        self.self_test_with(&SelfTestOptions::new())
+    }
+
+    /// 按指定选项运行自检
+    pub fn self_test_with(&mut self, options: &SelfTestOptions) -> Result<SelfTestReport, Error> {
+        #[cfg(feature = "logging")]
+        log::info!("开始自检");
+        modules::selftest::run(self, options)
+    }
+
+    /// 在 `duration` 内尽快发送生成的画面，测量帧率、传输速率、重试次数和同步延迟分位数
+    ///
+    /// 连接 `FakeMcu` 时只测量主机一侧的开销。结束后恢复原来的画面
+    pub fn benchmark(&mut self, duration: std::time::Duration) -> Result<BenchmarkReport, Error> {
+        #[cfg(feature = "logging")]
+        log::info!("开始基准测试: {:?}", duration);
+        modules::benchmark::run(self, duration)
+    }
+
+    // ==================== 使用统计 ====================
+
+    /// 获取累计使用统计
+    pub fn stats(&self) -> &UsageStats {
+        &self.stats
+    }
+
+    /// 清空使用统计
+    pub fn reset_stats(&mut self) {
+        self.stats = UsageStats::new();
+    }
+
+    /// 设置统计文件路径，实现跨会话累计
+    ///
+    /// 如果文件已存在，会将其中的累计值合并到当前统计（同一个文件只合并一次，
+    /// 重复设置不会重复计数）；之后每次断开连接时自动保存。
+    pub fn set_stats_path<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<(), Error> {
+        let path = path.as_ref().to_path_buf();
+        if path.exists() {
+            let key = path.canonicalize().unwrap_or_else(|_| path.clone());
+            if !self.stats_loaded.contains(&key) {
+                let saved = UsageStats::load_from_file(&path).map_err(Error::StatsError)?;
+                self.stats.merge(&saved);
+                self.stats_loaded.push(key);
+            }
+        }
+        self.stats_path = Some(path);
+        Ok(())
+    }
+
+    // ==================== 录制 ====================
+
+    /// 开始录制会话：之后每次同步发送的画面和关节指令带时间戳写入 `dir`
+    ///
+    /// 已经在录制时先结束之前的录制
+    #[cfg(feature = "image")]
+    pub fn start_recording<P: AsRef<std::path::Path>>(
+        &mut self,
+        dir: P,
+        options: RecorderOptions,
+    ) -> Result<(), Error> {
+        self.stop_recording()?;
+        self.recorder = Some(SessionRecorder::start(dir, options).map_err(Error::RecordError)?);
+        Ok(())
+    }
+
+    /// 结束录制（没有在录制时不做任何事）
+    #[cfg(feature = "image")]
+    pub fn stop_recording(&mut self) -> Result<(), Error> {
+        match self.recorder.take() {
+            Some(recorder) => recorder.finish().map_err(Error::RecordError),
+            None => Ok(()),
+        }
+    }
+
+    /// 是否正在录制
+    #[cfg(feature = "image")]
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// 获取当前的录制器
+    #[cfg(feature = "image")]
+    pub fn recorder(&self) -> Option<&SessionRecorder> {
+        self.recorder.as_ref()
+    }
+
+    /// 立即保存统计到已设置的文件（未设置路径时不做任何事）
+    pub fn save_stats(&self) -> Result<(), Error> {
+        match &self.stats_path {
+            Some(path) => self.stats.save_to_file(path).map_err(Error::StatsError),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for ElectronBot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ElectronBot {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+/// 根据屏幕状态和亮度准备要发送的帧
+///
+/// 先在副本上运行后处理回调，缓冲区中的画面保持不变
+fn output_frame<'a>(
+    image_buffer: &'a ImageBuffer,
+    post_processors: &std::sync::Mutex<Vec<PostProcessor>>,
+    display_on: bool,
+    brightness: f32,
+    orientation: Orientation,
+    lut: Option<&ColorLut>,
+    dithering: bool,
+) -> std::borrow::Cow<'a, ImageBuffer> {
+    if !display_on {
+        return std::borrow::Cow::Owned(ImageBuffer::new());
+    }
+    let mut frame = std::borrow::Cow::Borrowed(image_buffer);
+    let mut post_processors = post_processors.lock().unwrap_or_else(|e| e.into_inner());
+    if !post_processors.is_empty() {
+        let processed = frame.to_mut();
+        for post_process in post_processors.iter_mut() {
+            post_process(processed);
+        }
+    }
+    drop(post_processors);
+    if brightness < 1.0 || !orientation.is_identity() || lut.is_some() || dithering {
+        let frame = frame.to_mut();
+        if let Some(lut) = lut {
+            frame.apply_lut(lut);
+        }
+        frame.scale_brightness(brightness);
+        if dithering {
+            frame.dither_rgb565();
+        }
+        frame.apply_orientation(&orientation);
+    }
+    frame
+}
+
+/// 色彩校正不为空时计算查找表。
+fn lut_for(adjust: &ColorAdjust) -> Option<ColorLut> {
+    if adjust.is_identity() {
+        None
+    } else {
+        Some(adjust.to_lut())
+    }
+}
+
+// ==================== 便捷函数 ====================
+
+/// 快速测试函数
+pub fn quick_test() -> Result<bool, Error> {
+    let mut bot = ElectronBot::new();
+    bot.connect()?;
+    println!("已连接到 ElectronBot!");
+    bot.set_image_color(Color::Red);
+    bot.sync()?;
+    println!("同步成功!");
+    bot.disconnect();
+    Ok(true)
+}
+
+/// 扫描并打印所有设备
+pub fn list_devices() {
+    println!("正在扫描 USB 设备...");
+    let devices = ElectronBot::scan_devices();
+    println!("找到 {} 个设备:", devices.len());
+
+    for (i, device) in devices.iter().enumerate() {
+        match &device.variant {
+            Some(variant) => println!("  [{}] {} <-- ElectronBot ({})", i, device.info, variant),
+            None => println!("  [{}] {}", i, device.info),
+        }
+    }
+}
+
+// ==================== 测试 ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_joint_angles_default() {
+        let angles = JointAngles::new();
+        assert_eq!(angles.0, [0.0; 6]);
+    }
+
+    #[test]
+    fn test_joint_angles_get_set() {
+        let mut angles = JointAngles::new();
+        angles.set(0, 45.0);
+        angles.set(1, 90.0);
+        assert_eq!(angles.get(0), Some(45.0));
+        assert_eq!(angles.get(1), Some(90.0));
+        assert_eq!(angles.get(5), Some(0.0));
+        assert_eq!(angles.get(6), None);
+    }
+
+    #[test]
+    #[allow(clippy::useless_conversion)]
+    fn test_joint_angles_bytes() {
+        let angles = JointAngles::new();
+        let bytes = angles.to_bytes();
+        assert_eq!(bytes.len(), 24);
+        let restored = JointAngles::from_bytes(&bytes.try_into().unwrap());
+        assert_eq!(restored.0, [0.0; 6]);
+    }
+
+    #[test]
+    fn test_color_rgb() {
+        assert_eq!(Color::Red.rgb(), (255, 0, 0));
+        assert_eq!(Color::Green.rgb(), (0, 255, 0));
+        assert_eq!(Color::Blue.rgb(), (0, 0, 255));
+        assert_eq!(Color::Cyan.rgb(), (0, 255, 255));
+        assert_eq!(Color::Custom(100, 150, 200).rgb(), (100, 150, 200));
+    }
+
+    #[test]
+    fn test_color_bgr() {
+        assert_eq!(Color::Red.bgr(), (0, 0, 255));
+        assert_eq!(Color::Green.bgr(), (0, 255, 0));
+        assert_eq!(Color::Blue.bgr(), (255, 0, 0));
+    }
+
+    #[test]
+    fn test_electron_bot_new() {
+        let bot = ElectronBot::new();
+        assert!(!bot.is_connected());
+    }
+
+    #[test]
+    fn test_image_buffer_new() {
+        let buf = ImageBuffer::new();
+        assert_eq!(buf.as_data().len(), FRAME_SIZE);
+    }
+
+    #[test]
+    fn test_image_buffer_clear() {
+        let mut buf = ImageBuffer::new();
+        buf.clear(Color::Red);
+        // 检查第一个像素是红色（存储为 BGR: 0, 0, 255）
+        // get_pixel 返回 RGB，所以 BGR(0,0,255) -> RGB(255,0,0)
+        assert_eq!(buf.get_pixel(0, 0), Some(Color::Custom(0, 0, 255)));
+    }
+
+    #[test]
+    fn test_image_buffer_set_pixel() {
+        let mut buf = ImageBuffer::new();
+        buf.set_pixel(10, 10, Color::Green);
+        assert_eq!(buf.get_pixel(10, 10), Some(Color::Custom(0, 255, 0)));
+    }
+
+    fn is_lit(buf: &ImageBuffer, x: usize, y: usize) -> bool {
+        buf.get_pixel(x, y) != Some(Color::Custom(0, 0, 0))
+    }
+
+    #[test]
+    fn test_draw_line() {
+        let mut buf = ImageBuffer::new();
+        buf.draw_line((0, 0), (9, 9), 1, Color::White);
+        for i in 0..10 {
+            assert!(is_lit(&buf, i, i));
+        }
+        assert!(!is_lit(&buf, 1, 0));
+
+        // 超出屏幕的部分被裁剪
+        buf.draw_line((-20, 100), (300, 100), 3, Color::White);
+        assert!(is_lit(&buf, 0, 100) && is_lit(&buf, 239, 101));
+        assert!(!is_lit(&buf, 120, 103));
+    }
+
+    #[test]
+    fn test_fill_polygon() {
+        let mut buf = ImageBuffer::new();
+        buf.fill_polygon(&[(10, 10), (50, 10), (50, 50), (10, 50)], Color::White);
+        assert!(is_lit(&buf, 10, 10) && is_lit(&buf, 30, 30) && is_lit(&buf, 50, 50));
+        assert!(!is_lit(&buf, 51, 30) && !is_lit(&buf, 30, 9));
+
+        let mut tri = ImageBuffer::new();
+        tri.fill_polygon(&[(0, 0), (100, 0), (0, 100)], Color::White);
+        assert!(is_lit(&tri, 20, 20));
+        assert!(!is_lit(&tri, 80, 80));
+    }
+
+    #[test]
+    fn test_ellipse_and_arc() {
+        let mut buf = ImageBuffer::new();
+        buf.draw_ellipse((120, 120), 40, 20, 2, Color::White);
+        assert!(is_lit(&buf, 160, 120) && is_lit(&buf, 120, 100));
+        assert!(!is_lit(&buf, 120, 120));
+
+        buf.fill_ellipse((120, 120), 10, 10, Color::White);
+        assert!(is_lit(&buf, 120, 120) && is_lit(&buf, 130, 120));
+        assert!(!is_lit(&buf, 128, 128));
+
+        let mut arc = ImageBuffer::new();
+        // 0°-90°：从右侧顺时针到底部
+        arc.draw_arc((120, 120), 50, 0.0, 90.0, 1, Color::White);
+        assert!(is_lit(&arc, 170, 120) && is_lit(&arc, 120, 170));
+        assert!(!is_lit(&arc, 70, 120) && !is_lit(&arc, 120, 70));
+    }
+
+    #[test]
+    fn test_rounded_rect_and_bezier() {
+        let mut buf = ImageBuffer::new();
+        buf.fill_rounded_rect(Rect::new(20, 20, 100, 60), 20, Color::White);
+        assert!(is_lit(&buf, 70, 20) && is_lit(&buf, 20, 50));
+        assert!(!is_lit(&buf, 20, 20) && !is_lit(&buf, 119, 79));
+
+        let mut outline = ImageBuffer::new();
+        outline.draw_rounded_rect(Rect::new(20, 20, 100, 60), 10, 1, Color::White);
+        assert!(is_lit(&outline, 70, 20) && is_lit(&outline, 119, 50));
+        assert!(!is_lit(&outline, 70, 50) && !is_lit(&outline, 20, 20));
+
+        let mut curve = ImageBuffer::new();
+        curve.draw_quad_bezier((0, 100), (120, 0), (239, 100), 1, Color::White);
+        assert!(is_lit(&curve, 0, 100) && is_lit(&curve, 239, 100) && is_lit(&curve, 120, 50));
+        curve.draw_cubic_bezier((0, 200), (80, 150), (160, 250), (239, 200), 2, Color::White);
+        assert!(is_lit(&curve, 0, 200) && is_lit(&curve, 239, 200));
+    }
+
+    #[test]
+    fn test_antialias_mode() {
+        let mut buf = ImageBuffer::new();
+        assert!(!buf.antialias());
+        buf.set_antialias(true);
+
+        // 斜线两侧出现部分覆盖的像素
+        buf.draw_line((0, 0), (100, 30), 1, Color::White);
+        let partial = buf.as_data().iter().filter(|&&v| v > 0 && v < 255).count();
+        assert!(partial > 0);
+
+        // 抗锯齿圆：中心完全覆盖，边缘部分覆盖，外部不受影响
+        let mut circle = ImageBuffer::new();
+        circle.set_antialias(true);
+        circle.fill_ellipse((120, 120), 30, 30, Color::White);
+        assert_eq!(
+            circle.get_pixel(120, 120),
+            Some(Color::Custom(255, 255, 255))
+        );
+        assert!(!is_lit(&circle, 120, 160));
+        assert!(circle.as_data().iter().any(|&v| v > 0 && v < 255));
+
+        // 填充多边形内部完全覆盖
+        let mut poly = ImageBuffer::new();
+        poly.set_antialias(true);
+        poly.fill_polygon(&[(10, 10), (200, 40), (60, 200)], Color::White);
+        assert_eq!(poly.get_pixel(80, 80), Some(Color::Custom(255, 255, 255)));
+        assert!(!is_lit(&poly, 230, 230));
+
+        // 关闭抗锯齿后只有完全覆盖的像素
+        let mut hard = ImageBuffer::new();
+        hard.draw_line((0, 0), (100, 30), 1, Color::White);
+        assert!(hard.as_data().iter().all(|&v| v == 0 || v == 255));
+    }
+
+    #[test]
+    fn test_antialias_strokes_stay_in_bounds() {
+        let mut buf = ImageBuffer::new();
+        buf.set_antialias(true);
+        buf.draw_line((-50, -50), (300, 300), 5, Color::White);
+        buf.draw_arc((120, 120), 200, 0.0, 360.0, 3, Color::White);
+        buf.draw_ellipse((0, 0), 50, 30, 4, Color::White);
+        buf.draw_rounded_rect(Rect::new(-10, -10, 300, 300), 30, 2, Color::White);
+        buf.fill_rounded_rect(Rect::new(50, 50, 100, 80), 20, Color::White);
+        buf.draw_quad_bezier((0, 239), (120, -100), (239, 239), 2, Color::White);
+        buf.draw_circle(239, 239, 10, Color::White);
+        assert!(is_lit(&buf, 100, 90));
+    }
+
+    fn checker_sprite() -> Sprite {
+        // 2x2：左上白、右上红（BGR 0,0,255）、下方黑
+        Sprite::from_bgr(2, 2, vec![255, 255, 255, 0, 0, 255, 0, 0, 0, 0, 0, 0]).unwrap()
+    }
+
+    #[test]
+    fn test_sprite_blit_and_clip() {
+        let sprite = checker_sprite();
+        assert!(Sprite::from_bgr(2, 2, vec![0; 5]).is_err());
+
+        let mut buf = ImageBuffer::new();
+        buf.blit(&sprite, 10, 10);
+        assert_eq!(buf.as_data()[(10 * FRAME_WIDTH + 10) * 3], 255);
+        assert_eq!(
+            buf.as_data()[(10 * FRAME_WIDTH + 11) * 3..][..3],
+            [0, 0, 255]
+        );
+
+        // 部分超出屏幕
+        buf.blit(&sprite, -1, -1);
+        assert_eq!(buf.as_data()[..3], [0, 0, 0]);
+        buf.blit(&sprite, 239, 239);
+        assert_eq!(buf.as_data()[(239 * FRAME_WIDTH + 239) * 3], 255);
+        // 完全在屏幕外
+        buf.blit(&sprite, 500, 500);
+    }
+
+    #[test]
+    fn test_sprite_flip_and_color_key() {
+        let sprite = checker_sprite().with_color_key(Color::Red);
+        assert_eq!(sprite.alpha(), &[255, 0, 255, 255]);
+
+        let mut buf = ImageBuffer::new();
+        buf.clear(Color::Custom(1, 2, 3));
+        buf.blit_with(&sprite, 0, 0, &BlitOptions::default().flip_horizontal());
+        // 翻转后白色位于右上；透明的红色像素保留背景
+        assert_eq!(buf.as_data()[3..6], [255, 255, 255]);
+        assert_eq!(buf.as_data()[..3], [1, 2, 3]);
+
+        let mut flipped = ImageBuffer::new();
+        flipped.blit_with(&sprite, 0, 0, &BlitOptions::default().flip_vertical());
+        assert_eq!(flipped.as_data()[(FRAME_WIDTH) * 3], 255);
+        assert_eq!(flipped.as_data()[0], 0);
+    }
+
+    #[test]
+    fn test_sprite_sheet() {
+        let atlas = Sprite::from_bgr(4, 2, (0..24).collect()).unwrap();
+        let sheet = SpriteSheet::new(&atlas, 2, 1).unwrap();
+        assert_eq!(sheet.len(), 4);
+        assert_eq!(sheet.frame(1).unwrap().as_data(), &[6, 7, 8, 9, 10, 11]);
+        assert_eq!(sheet.frame(2).unwrap().as_data(), &[12, 13, 14, 15, 16, 17]);
+        assert!(sheet.frame(4).is_none());
+        assert!(SpriteSheet::new(&atlas, 0, 1).is_err());
+    }
+
+    #[test]
+    fn test_frame_cache() {
+        let render = |color: Color| {
+            move |buffer: &mut ImageBuffer| -> Result<(), String> {
+                buffer.clear(color);
+                Ok(())
+            }
+        };
+        let mut cache = FrameCache::new(2 * FRAME_SIZE);
+        let mut target = ImageBuffer::new();
+        let red = FrameKey::content(b"red");
+        let green = FrameKey::content(b"green");
+        let blue = FrameKey::content(b"blue");
+        assert!(!cache
+            .show_with(red.clone(), &mut target, render(Color::Red))
+            .unwrap());
+        assert!(!cache
+            .show_with(green.clone(), &mut target, render(Color::Green))
+            .unwrap());
+        assert_eq!(cache.used(), 2 * FRAME_SIZE);
+
+        // 命中时不调用 render，直接复制缓存的帧
+        assert!(cache
+            .show_with(
+                red.clone(),
+                &mut target,
+                |_| Err("不应重新生成".to_string())
+            )
+            .unwrap());
+        let mut expected = ImageBuffer::new();
+        expected.clear(Color::Red);
+        assert_eq!(target.as_data(), expected.as_data());
+
+        // 超出预算时淘汰最久没有使用的绿色
+        cache
+            .show_with(blue.clone(), &mut target, render(Color::Blue))
+            .unwrap();
+        assert!(cache.contains(&red) && cache.contains(&blue));
+        assert!(!cache.contains(&green));
+        assert_eq!((cache.hits(), cache.misses()), (1, 3));
+
+        // 生成失败时不缓存
+        assert!(cache
+            .show_with(green.clone(), &mut target, |_| Err("坏图".to_string()))
+            .is_err());
+        assert!(!cache.contains(&green));
+
+        cache.set_budget(FRAME_SIZE);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.contains(&blue));
+        cache.set_budget(0);
+        assert!(cache.is_empty());
+
+        #[cfg(feature = "image")]
+        {
+            let src = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/test.png");
+            let mut cache = FrameCache::default();
+            let mut bot = ElectronBot::new();
+            assert!(!bot.set_image_cached(&mut cache, src).unwrap());
+            let first = bot.image_buffer().as_data().to_vec();
+            bot.set_image_color(Color::Black);
+            assert!(bot.set_image_cached(&mut cache, src).unwrap());
+            assert_eq!(bot.image_buffer().as_data(), first);
+
+            // 适配方式变化时重新转换
+            bot.set_fit_options(FitOptions {
+                mode: FitMode::Contain,
+                ..FitOptions::default()
+            });
+            assert!(!bot.set_image_cached(&mut cache, src).unwrap());
+            assert!(bot.set_image_cached(&mut cache, "missing.png").is_err());
+        }
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_assets() {
+        use image::codecs::gif::GifEncoder;
+        use image::{Delay, Rgb, RgbImage, Rgba, RgbaImage};
+
+        let dir = std::env::temp_dir().join(format!("electron_bot_assets_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("blink")).unwrap();
+        RgbImage::from_pixel(4, 4, Rgb([255, 0, 0]))
+            .save(dir.join("red.png"))
+            .unwrap();
+        for (i, value) in [0u8, 128, 255].iter().enumerate() {
+            RgbImage::from_pixel(4, 4, Rgb([*value; 3]))
+                .save(dir.join("blink").join(format!("{:02}.png", i)))
+                .unwrap();
+        }
+        {
+            let file = std::fs::File::create(dir.join("spin.gif")).unwrap();
+            let frames = [[0u8, 0, 255, 255], [0, 255, 0, 255]].map(|pixel| {
+                image::Frame::from_parts(
+                    RgbaImage::from_pixel(4, 4, Rgba(pixel)),
+                    0,
+                    0,
+                    Delay::from_numer_denom_ms(200, 1),
+                )
+            });
+            GifEncoder::new(file).encode_frames(frames).unwrap();
+        }
+        std::fs::write(dir.join("notes.txt"), "不是图片").unwrap();
+
+        let assets = Assets::loader(&dir).parallel(true).load().unwrap();
+        assert_eq!(assets.names().collect::<Vec<_>>(), ["blink", "red", "spin"]);
+        assert_eq!(assets.memory(), 6 * FRAME_SIZE);
+        let sequential = Assets::load_dir(&dir).unwrap();
+        for (name, asset) in assets.iter() {
+            let other = sequential.get(name).unwrap();
+            assert_eq!(asset.frame(0), other.frame(0));
+        }
+
+        let red = assets.get("red").unwrap();
+        assert!(!red.is_animation());
+        let mut expected = ImageBuffer::new();
+        expected.load_from_file(dir.join("red.png")).unwrap();
+        assert_eq!(&red.frame(0).unwrap()[..], expected.as_data());
+
+        // 子目录按文件名顺序播放，GIF 保留每帧的显示时间
+        let blink = assets.get("blink").unwrap();
+        assert_eq!(blink.len(), 3);
+        assert_eq!(blink.frame(1).unwrap()[0], 128);
+        let spin = assets.get("spin").unwrap();
+        assert_eq!(spin.delay(1), Some(std::time::Duration::from_millis(200)));
+        assert_eq!(spin.duration(), std::time::Duration::from_millis(400));
+
+        let mut buffer = ImageBuffer::new();
+        let mut source = blink.source().once();
+        let mut shown = Vec::new();
+        while source.next_frame(&mut buffer) {
+            shown.push(buffer.as_data()[0]);
+        }
+        assert_eq!(shown, [0, 128, 255]);
+        let mut still = red.source().once();
+        assert!(still.next_frame(&mut buffer) && still.next_frame(&mut buffer));
+        assert_eq!(still.frame_interval(), None);
+
+        let mut scenes = SceneManager::new();
+        scenes.add(assets.scene("spin").unwrap());
+        assert!(scenes.contains("spin"));
+        assert!(assets.scene("missing").is_none());
+
+        std::fs::write(dir.join("broken.png"), "坏数据").unwrap();
+        let error = Assets::load_dir(&dir).unwrap_err();
+        assert!(error.contains("broken.png"), "{}", error);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_extra_data_new() {
+        let extra = ExtraData::new();
+        assert_eq!(extra.as_data().len(), 32);
+        assert!(!extra.is_enabled());
+    }
+
+    #[test]
+    fn test_extra_data_enable() {
+        let mut extra = ExtraData::new();
+        extra.set_enable(true);
+        assert!(extra.is_enabled());
+        extra.set_enable(false);
+        assert!(!extra.is_enabled());
+    }
+
+    #[test]
+    fn test_extra_data_joint_angles() {
+        let mut extra = ExtraData::new();
+        let angles = JointAngles::new();
+        extra.set_joint_angles(&angles, true);
+        assert!(extra.is_enabled());
+        let restored = extra.get_joint_angles();
+        assert_eq!(restored.0, [0.0; 6]);
+    }
+
+    #[test]
+    fn test_extra_data_bytes() {
+        let mut extra = ExtraData::new();
+        extra.set_byte(0, 0xAB);
+        assert_eq!(extra.get_byte(0), Some(0xAB));
+        extra.set_u16(1, 0x1234);
+        assert_eq!(extra.get_u16(1), Some(0x1234));
+    }
+
+    #[test]
+    fn test_sync_context_new() {
+        let ctx = SyncContext::new();
+        assert_eq!(ctx.timestamp, 0);
+        assert_eq!(ctx.ping_pong_index, 0);
+        assert_eq!(ctx.cycles, 4);
+    }
+
+    #[test]
+    fn test_sync_context_toggle() {
+        let mut ctx = SyncContext::new();
+        assert_eq!(ctx.ping_pong_index, 0);
+        ctx.toggle();
+        assert_eq!(ctx.ping_pong_index, 1);
+        ctx.toggle();
+        assert_eq!(ctx.ping_pong_index, 0);
+    }
+
+    #[test]
+    fn test_joint_command_latched() {
+        let transport = RecordingTransport::default();
+        let sent = transport.sent.clone();
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(transport)).unwrap();
+        // 连续同步时乒乓索引交替，每次同步的所有尾包都带最近一次设置的角度
+        for step in 0..4 {
+            let angles = [step as f32 * 10.0, 0.0, 5.0, 0.0, -5.0, 1.0];
+            bot.set_joint_angles(&angles, true).unwrap();
+            sent.lock().unwrap().clear();
+            assert!(bot.sync().unwrap());
+            let sent = sent.lock().unwrap();
+            let tails: Vec<&Vec<u8>> = sent.iter().filter(|p| p.len() == 224).collect();
+            assert_eq!(tails.len(), 4);
+            for tail in tails {
+                let mut extra = ExtraData::new();
+                extra.set_raw(&tail[192..]);
+                assert_eq!(extra.get_joint_angles().0, angles);
+                assert!(extra.is_enabled());
+            }
+        }
+    }
+
+    #[test]
+    #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
+    fn test_scan_devices() {
+        let devices = ElectronBot::scan_devices();
+        assert!(devices.len() >= 0);
+    }
+
+    #[test]
+    fn test_is_device_present() {
+        let _present = ElectronBot::is_device_present();
+    }
+
+    #[test]
+    fn test_quick_test_function() {
+        let result = quick_test();
+        assert!(result.is_ok() || result.is_err());
+    }
+
+    #[test]
+    fn test_list_devices_function() {
+        list_devices();
+    }
+
+    #[test]
+    fn test_bot_config_default() {
+        let bot = ElectronBot::with_config(BotConfig::new());
+        assert!(!bot.config().startup.apply_on_connect);
+        assert!(bot.config().startup.is_empty());
+    }
+
+    #[test]
+    fn test_apply_startup_requires_connection() {
+        let mut config = BotConfig::new();
+        config.startup.pose = Some([1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        config.startup.color = Some([10, 20, 30]);
+        let mut bot = ElectronBot::with_config(config);
+
+        assert!(matches!(bot.apply_startup(), Err(BotError::NotConnected)));
+        assert_eq!(
+            bot.get_joint_angles().as_array(),
+            &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+        );
+        let mut expected = ImageBuffer::new();
+        expected.clear(Color::Custom(10, 20, 30));
+        assert_eq!(bot.image_buffer().as_data(), expected.as_data());
+    }
+
+    #[test]
+    fn test_apply_startup_scene() {
+        let mut config = BotConfig::new();
+        config.startup.apply_on_connect = true;
+        config.startup.scene = Some("happy".to_string());
+        config.startup.theme = Some("night".to_string());
+        let mut bot = ElectronBot::with_config(config.clone());
+        let mcu = FakeMcu::new();
+        let state = mcu.state();
+        bot.connect_transport(Box::new(mcu)).unwrap();
+
+        // 连接后按主题绘制场景的第一帧并发送
+        let mut expected = ImageBuffer::new();
+        expected.draw_face(&Expression::HAPPY, &Theme::named("night").unwrap().face);
+        assert_eq!(bot.image_buffer().as_data(), expected.as_data());
+        assert_eq!(state.lock().unwrap().frames, 1);
+        assert!(THEME_NAMES.iter().all(|name| Theme::named(name).is_some()));
+        assert!(SCENE_NAMES
+            .iter()
+            .chain(EXPRESSION_NAMES.iter())
+            .all(|name| Scene::named(name, &Theme::default()).is_some()));
+
+        // 未知的场景：返回配置错误并断开连接
+        config.startup.scene = Some("missing".to_string());
+        let mut bot = ElectronBot::with_config(config);
+        let error = bot.connect_transport(Box::new(FakeMcu::new())).unwrap_err();
+        assert!(
+            matches!(&error, BotError::ConfigError(message) if message.contains("missing")),
+            "{}",
+            error
+        );
+        assert!(!bot.is_connected());
+        assert!(matches!(bot.sync(), Err(BotError::NotConnected)));
+    }
+
+    #[cfg(not(feature = "image"))]
+    #[test]
+    fn test_without_image_feature() {
+        // 没有图片解码时启动画面报配置错误，不修改姿态
+        let mut config = BotConfig::new();
+        config.startup.image = Some("face.png".into());
+        config.startup.pose = Some([1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let mut bot = ElectronBot::with_config(config);
+        let mcu = FakeMcu::new();
+        let state = mcu.state();
+        bot.connect_transport(Box::new(mcu)).unwrap();
+        let error = bot.apply_startup().unwrap_err();
+        assert!(
+            matches!(&error, BotError::ConfigError(message) if message.contains("`image`")),
+            "{}",
+            error
+        );
+        assert_eq!(bot.target_joint_angles(), JointAngles::new());
+
+        // 原始帧和关节指令照常发送
+        let mut frame = vec![0u8; FRAME_SIZE];
+        frame[..3].copy_from_slice(&[1, 2, 3]);
+        bot.set_image_from_data(&frame, FRAME_WIDTH, FRAME_HEIGHT)
+            .unwrap();
+        bot.set_joint_angles(&[0.0, 0.0, 20.0, 0.0, 0.0, 0.0], true)
+            .unwrap();
+        assert!(bot.sync().unwrap());
+        let state = state.lock().unwrap();
+        assert_eq!(&state.frame.as_ref().unwrap()[..3], [3, 2, 1]);
+        assert_eq!(
+            state.extra_data.as_ref().unwrap().get_joint_angles().0[2],
+            20.0
+        );
+    }
+
+    #[test]
+    fn test_idle_seed_reproducible() {
+        // 空闲动画的随机数不依赖 `rand`，同一个种子得到相同的动画
+        let run = |seed| {
+            let mut idle = IdleBehavior::with_seed(seed);
+            let mut buf = ImageBuffer::new();
+            (0..150)
+                .map(|_| {
+                    idle.next_frame(&mut buf);
+                    let e = idle.expression();
+                    (
+                        e.pupil_x,
+                        e.pupil_y,
+                        idle.is_blinking(),
+                        idle.joint_angles(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let first = run(7);
+        assert_eq!(first, run(7));
+        // 相近的种子也会得到不同的动画
+        assert_ne!(first, run(8));
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_bot_config_from_toml() {
+        let config = BotConfig::from_toml_str(
+            r#"
+            [startup]
+            apply_on_connect = true
+            pose = [0.0, 10.0, 0.0, 0.0, 0.0, 0.0]
+            color = [255, 0, 0]
+            scene = "clock"
+            theme = "light"
+            "#,
+        )
+        .unwrap();
+        assert!(config.startup.apply_on_connect);
+        assert_eq!(config.startup.scene.as_deref(), Some("clock"));
+        assert_eq!(config.startup.theme.as_deref(), Some("light"));
+        assert_eq!(config.startup.pose, Some([0.0, 10.0, 0.0, 0.0, 0.0, 0.0]));
+        assert_eq!(config.startup.color, Some([255, 0, 0]));
+        assert!(config.startup.image.is_none());
+        assert!(BotConfig::from_toml_str("startup = 1").is_err());
+
+        let config =
+            BotConfig::from_toml_str("[orientation]\nrotation = 270\nflip_vertical = true")
+                .unwrap();
+        assert_eq!(
+            config.orientation,
+            Orientation::new(Rotation::Deg270).flip_vertical()
+        );
+        assert!(BotConfig::from_toml_str("[orientation]\nrotation = 45").is_err());
+    }
+
+    #[test]
+    fn test_display_off_sends_black_frame() {
+        let mut bot = ElectronBot::new();
+        bot.set_image_color(Color::White);
+        assert!(bot.is_display_on());
+
+        bot.display_off();
+        assert!(!bot.is_display_on());
+        assert!(bot.output_frame().as_data().iter().all(|&v| v == 0));
+        // 图片缓冲区本身保持不变
+        assert!(bot.image_buffer().as_data().iter().all(|&v| v == 255));
+
+        bot.display_on();
+        assert!(bot.output_frame().as_data().iter().all(|&v| v == 255));
+    }
+
+    #[test]
+    fn test_brightness_scaling() {
+        let mut bot = ElectronBot::new();
+        bot.set_image_color(Color::Custom(200, 100, 50));
+        bot.set_brightness(0.5);
+        assert_eq!(bot.brightness(), 0.5);
+        let frame = bot.output_frame();
+        assert_eq!(frame.as_data()[..3], [100, 50, 25]);
+
+        bot.set_brightness(3.0);
+        assert_eq!(bot.brightness(), 1.0);
+    }
+
+    #[test]
+    fn test_image_buffer_blend_pixel() {
+        let mut buf = ImageBuffer::new();
+        buf.blend_pixel(0, 0, Color::White, 0.5);
+        assert_eq!(buf.as_data()[..3], [128, 128, 128]);
+        buf.blend_pixel(1, 0, Color::White, 1.0);
+        assert_eq!(buf.as_data()[3..6], [255, 255, 255]);
+        buf.blend_pixel(2, 0, Color::White, 0.0);
+        assert_eq!(buf.as_data()[6..9], [0, 0, 0]);
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_font_from_invalid_bytes() {
+        assert!(Font::from_bytes(&[0u8; 16]).is_err());
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_draw_text_with_system_font() {
+        // 仅在系统提供 DejaVu 字体时运行
+        let path = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+        let font = match Font::load_from_file(path) {
+            Ok(f) => f,
+            Err(_) => return,
+        };
+        let mut buf = ImageBuffer::new();
+        let (measured, _) = font.measure("Hi", 32.0);
+        let width = buf.draw_text(10, 10, "Hi", &font, 32.0, Color::White);
+        assert!((width - measured).abs() < 0.01);
+        assert!(buf.as_data().iter().any(|&v| v > 0));
+        // 文字区域之外保持黑色
+        assert_eq!(buf.get_pixel(200, 200), Some(Color::Custom(0, 0, 0)));
+
+        // 部分超出屏幕时不会越界
+        buf.draw_text(-10, 230, "Hello", &font, 32.0, Color::White);
+    }
+
+    #[cfg(feature = "embedded-font")]
+    #[test]
+    fn test_embedded_font_cjk() {
+        let font = Font::embedded();
+        assert!(font.has_glyph('A'));
+        assert!(font.has_glyph('你'));
+        // 半角 8 像素，全角 16 像素
+        assert_eq!(font.measure("A", 16.0).0, 8.0);
+        assert_eq!(font.measure("你好", 16.0).0, 32.0);
+        assert_eq!(font.measure("你好", 32.0).0, 64.0);
+
+        let mut buf = ImageBuffer::new();
+        let width = buf.draw_text(0, 0, "你好", &font, 16.0, Color::White);
+        assert_eq!(width, 32.0);
+        assert!(buf.as_data().contains(&255));
+    }
+
+    #[cfg(feature = "embedded-font")]
+    #[test]
+    fn test_text_wrap() {
+        let font = Font::embedded();
+        // 每个半角字符 8 像素，最大 40 像素即 5 个字符
+        let lines = font.wrap("hello world", 16.0, Some(40.0));
+        assert_eq!(lines, vec!["hello", "world"]);
+        // 中文可在任意字符间断行
+        let lines = font.wrap("你好世界", 16.0, Some(40.0));
+        assert_eq!(lines, vec!["你好", "世界"]);
+        // 超长单词按字符断开
+        let lines = font.wrap("abcdefgh", 16.0, Some(40.0));
+        assert_eq!(lines, vec!["abcde", "fgh"]);
+        // 强制换行
+        assert_eq!(font.wrap("a\nb", 16.0, None), vec!["a", "b"]);
+    }
+
+    #[cfg(feature = "embedded-font")]
+    #[test]
+    fn test_draw_text_block_align() {
+        let font = Font::embedded();
+        let mut buf = ImageBuffer::new();
+        let style = TextStyle::new(16.0, Color::White)
+            .align(TextAlign::Right)
+            .max_width(240.0);
+        let (w, h) = buf.draw_text_block(0, 0, "第一行\n2", &font, &style);
+        assert_eq!((w, h), (240.0, 32.0));
+        // 右对齐：第二行 "2" 位于最右侧 8 像素内
+        let row_has_ink = |buf: &ImageBuffer, y: usize, xs: std::ops::Range<usize>| {
+            xs.into_iter()
+                .any(|x| buf.get_pixel(x, y) != Some(Color::Custom(0, 0, 0)))
+        };
+        assert!((16..32).any(|y| row_has_ink(&buf, y, 232..240)));
+        assert!(!(16..32).any(|y| row_has_ink(&buf, y, 0..232)));
+    }
+
+    #[test]
+    fn test_usage_stats_duty_cycle() {
+        let start = std::time::Instant::now();
+        let mut stats = UsageStats::new();
+        let mut extra = ExtraData::new();
+        stats.begin_session();
+
+        let mut angles = JointAngles::new();
+        extra.set_joint_angles(&angles, true);
+        stats.record_frame_at(start, &extra);
+
+        angles.set(0, 10.0);
+        extra.set_joint_angles(&angles, true);
+        stats.record_frame_at(start + std::time::Duration::from_secs(1), &extra);
+
+        extra.set_enable(false);
+        stats.record_frame_at(start + std::time::Duration::from_secs(2), &extra);
+        stats.record_frame_at(start + std::time::Duration::from_secs(4), &extra);
+
+        assert_eq!(stats.frames_transmitted, 4);
+        assert_eq!(stats.connected_time, std::time::Duration::from_secs(4));
+        assert_eq!(stats.servo_enabled_time, std::time::Duration::from_secs(2));
+        assert_eq!(stats.joint_duty_cycle(0), Some(0.25));
+        assert_eq!(stats.joint_duty_cycle(1), Some(0.0));
+        assert_eq!(stats.joint_duty_cycle(6), None);
+        assert_eq!(stats.servo_duty_cycle(), 0.5);
+    }
+
+    #[test]
+    fn test_usage_stats_text_roundtrip() {
+        let mut stats = UsageStats::new();
+        stats.frames_transmitted = 42;
+        stats.sessions = 3;
+        stats.servo_enabled_time = std::time::Duration::from_millis(1500);
+        stats.joint_active_time[2] = std::time::Duration::from_millis(250);
+
+        let restored = UsageStats::from_text(&stats.to_text()).unwrap();
+        assert_eq!(restored.frames_transmitted, 42);
+        assert_eq!(restored.sessions, 3);
+        assert_eq!(
+            restored.servo_enabled_time,
+            std::time::Duration::from_millis(1500)
+        );
+        assert_eq!(
+            restored.joint_active_time[2],
+            std::time::Duration::from_millis(250)
+        );
+
+        assert!(UsageStats::from_text("frames_transmitted=abc").is_err());
+        assert!(UsageStats::from_text("joint_active_secs=1,2").is_err());
+    }
+
+    #[test]
+    fn test_usage_stats_idle_session() {
+        let start = std::time::Instant::now();
+        let mut stats = UsageStats::new();
+        let mut extra = ExtraData::new();
+
+        // 连接后一直没有发送帧也计入连接时间
+        stats.begin_session_at(start);
+        stats.end_session_at(start + std::time::Duration::from_secs(3));
+        assert_eq!(stats.connected_time, std::time::Duration::from_secs(3));
+
+        // 最后一帧到断开之间按最后一帧的状态计入
+        stats.begin_session_at(start + std::time::Duration::from_secs(10));
+        extra.set_joint_angles(&JointAngles::new(), true);
+        stats.record_frame_at(start + std::time::Duration::from_secs(11), &extra);
+        stats.end_session_at(start + std::time::Duration::from_secs(15));
+        assert_eq!(stats.sessions, 2);
+        assert_eq!(stats.connected_time, std::time::Duration::from_secs(8));
+        assert_eq!(stats.servo_enabled_time, std::time::Duration::from_secs(4));
+
+        // 断开之后的时间不再计入
+        stats.end_session_at(start + std::time::Duration::from_secs(20));
+        assert_eq!(stats.connected_time, std::time::Duration::from_secs(8));
+    }
+
+    #[test]
+    fn test_set_stats_path_loads_once() {
+        let path =
+            std::env::temp_dir().join(format!("electronbot-stats-{}.txt", std::process::id()));
+        let mut saved = UsageStats::new();
+        saved.frames_transmitted = 10;
+        saved.sessions = 2;
+        saved.save_to_file(&path).unwrap();
+
+        let mut bot = ElectronBot::new();
+        bot.set_stats_path(&path).unwrap();
+        bot.set_stats_path(&path).unwrap();
+        assert_eq!(bot.stats().frames_transmitted, 10);
+        assert_eq!(bot.stats().sessions, 2);
+
+        bot.save_stats().unwrap();
+        bot.set_stats_path(&path).unwrap();
+        assert_eq!(bot.stats().frames_transmitted, 10);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_layer_stack_z_order_and_color_key() {
+        let mut stack = LayerStack::new();
+        // 后添加但 z 更小的图层在下方
+        let top = stack.add_layer("clock", 10);
+        top.buffer.clear(Color::Black);
+        top.color_key = Some(Color::Black);
+        top.buffer.set_pixel(5, 5, Color::White);
+        stack
+            .add_layer("video", 0)
+            .buffer
+            .clear(Color::Custom(1, 2, 3));
+        assert_eq!(stack.len(), 2);
+        assert_eq!(
+            stack.iter().map(|l| l.name()).collect::<Vec<_>>(),
+            ["video", "clock"]
+        );
+
+        let out = stack.flatten();
+        assert_eq!(out.as_data()[..3], [1, 2, 3]);
+        assert_eq!(
+            out.as_data()[(5 * FRAME_WIDTH + 5) * 3..][..3],
+            [255, 255, 255]
+        );
+
+        // 隐藏上层后只剩背景
+        assert!(stack.set_visible("clock", false));
+        assert!(!stack.set_visible("missing", false));
+        let out = stack.flatten();
+        assert_eq!(out.as_data()[(5 * FRAME_WIDTH + 5) * 3..][..3], [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_post_process() {
+        let mcu = FakeMcu::new();
+        let state = mcu.state();
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(mcu)).unwrap();
+        bot.set_image_color(Color::White);
+        let original = bot.image_buffer().as_data().to_vec();
+
+        // 按添加顺序运行：先画水印，再整体减半
+        bot.add_post_process(|buf| buf.set_pixel(0, 0, Color::Black));
+        bot.add_post_process(|buf| buf.scale_brightness(0.5));
+        assert!(matches!(bot.output_frame(), std::borrow::Cow::Owned(_)));
+        bot.sync().unwrap();
+        let sent = state.lock().unwrap().frame.clone().unwrap();
+        assert_eq!(&sent[..3], &[0, 0, 0]);
+        assert_eq!(&sent[3..6], &[128, 128, 128]);
+        assert_eq!(sent, bot.output_frame().as_data());
+        // 缓冲区本身不变，效果不会逐帧累积
+        assert_eq!(bot.image_buffer().as_data(), &original[..]);
+        bot.sync().unwrap();
+        assert_eq!(
+            &state.lock().unwrap().frame.as_ref().unwrap()[3..6],
+            &[128; 3]
+        );
+
+        // 在屏幕方向之前运行：水印随画面一起旋转
+        bot.set_orientation(Orientation::new(Rotation::Deg90));
+        let frame = bot.output_frame();
+        let top_right = (FRAME_WIDTH - 1) * 3;
+        assert_eq!(&frame.as_data()[top_right..top_right + 3], &[0, 0, 0]);
+        drop(frame);
+
+        bot.clear_post_processes();
+        bot.set_orientation(Orientation::default());
+        assert!(matches!(bot.output_frame(), std::borrow::Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_pattern_fill() {
+        let pixel = |buf: &ImageBuffer, x: usize, y: usize| {
+            buf.as_data()[(y * FRAME_WIDTH + x) * 3..][..3].to_vec()
+        };
+        let (red, blue) = ([0, 0, 255].to_vec(), [255, 0, 0].to_vec());
+
+        // 棋盘格：4 像素见方，左上角与矩形对齐
+        let checker = Sprite::checker(4, Color::Red, Color::Blue);
+        assert_eq!((checker.width(), checker.height()), (8, 8));
+        let mut buf = ImageBuffer::new();
+        buf.fill_pattern(Rect::new(0, 0, FRAME_WIDTH, FRAME_HEIGHT), &checker);
+        assert_eq!(pixel(&buf, 0, 0), red);
+        assert_eq!(pixel(&buf, 4, 0), blue);
+        assert_eq!(pixel(&buf, 4, 4), red);
+        assert_eq!(pixel(&buf, 239, 239), red);
+
+        // 裁剪到屏幕，矩形外不变；偏移后整体平移
+        let stripes = Sprite::stripes(3, Color::Red, Color::Blue, StripeDirection::Vertical);
+        let mut buf = ImageBuffer::new();
+        buf.fill_pattern(Rect::new(-2, 10, 6, 4), &stripes);
+        // 矩形从 x = -2 开始，屏幕第 0 列是图块第 2 列
+        assert_eq!(pixel(&buf, 0, 10), red);
+        assert_eq!(pixel(&buf, 1, 13), blue);
+        assert_eq!(pixel(&buf, 4, 10), [0, 0, 0]);
+        assert_eq!(pixel(&buf, 0, 14), [0, 0, 0]);
+        let full = Rect::new(0, 0, FRAME_WIDTH, FRAME_HEIGHT);
+        let mut shifted = ImageBuffer::new();
+        buf.fill_pattern(full, &stripes);
+        shifted.fill_pattern_offset(full, &stripes, (1, 0));
+        for x in 1..20 {
+            assert_eq!(pixel(&shifted, x, 7), pixel(&buf, x - 1, 7));
+        }
+        let diagonal = Sprite::stripes(2, Color::Red, Color::Blue, StripeDirection::Diagonal);
+        buf.fill_pattern(full, &diagonal);
+        assert_eq!(pixel(&buf, 1, 0), pixel(&buf, 0, 1));
+        assert_ne!(pixel(&buf, 2, 0), pixel(&buf, 0, 0));
+
+        // 透明像素保留原有内容
+        let mut buf = ImageBuffer::new();
+        buf.clear(Color::White);
+        let keyed = Sprite::checker(1, Color::Red, Color::Blue).with_color_key(Color::Blue);
+        buf.fill_pattern(full, &keyed);
+        assert_eq!(pixel(&buf, 0, 0), red);
+        assert_eq!(pixel(&buf, 1, 0), [255; 3]);
+
+        // 图层栈的平铺背景铺在所有图层之下
+        let mut stack = LayerStack::new();
+        let top = stack.add_layer("clock", 1);
+        top.color_key = Some(Color::Black);
+        top.buffer.set_pixel(0, 0, Color::White);
+        stack.set_background(checker.clone());
+        let out = stack.flatten();
+        assert_eq!(pixel(&out, 0, 0), [255; 3]);
+        assert_eq!(pixel(&out, 4, 0), blue);
+        stack.set_background_offset((4, 0));
+        assert_eq!(stack.background_offset(), (4, 0));
+        let mut bot = ElectronBot::new();
+        bot.set_image_from_layers(&stack);
+        assert_eq!(pixel(bot.image_buffer(), 4, 0), red);
+        stack.clear_background();
+        assert!(stack.background().is_none());
+        assert_eq!(pixel(&stack.flatten(), 4, 0), [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_layer_opacity() {
+        let mut stack = LayerStack::new();
+        stack.add_layer("bg", 0);
+        stack.add_layer("fg", 1).buffer.clear(Color::White);
+        assert!(stack.set_opacity("fg", 0.5));
+        assert_eq!(stack.layer("fg").unwrap().opacity, 0.5);
+        let out = stack.flatten();
+        assert_eq!(out.as_data()[0], 128);
+
+        let mut bot = ElectronBot::new();
+        bot.set_image_from_layers(&stack);
+        assert_eq!(bot.image_buffer().as_data()[0], 128);
+        assert!(stack.remove_layer("fg").is_some());
+        assert!(stack.layer("fg").is_none());
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_fit_modes() {
+        // 120x60 红色横图
+        let img = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+            120,
+            60,
+            image::Rgb([255, 0, 0]),
+        ));
+        let red = [0, 0, 255];
+        let px = |buf: &ImageBuffer, x: usize, y: usize| {
+            let idx = (y * FRAME_WIDTH + x) * 3;
+            [buf.data[idx], buf.data[idx + 1], buf.data[idx + 2]]
+        };
+
+        let mut buf = ImageBuffer::new();
+        buf.load_from_image(&img);
+        assert_eq!(px(&buf, 0, 0), red);
+
+        // Contain：上下留边（240x120 居中）
+        let options = FitOptions::new(FitMode::Contain).background(Color::Custom(0, 0, 0));
+        buf.load_from_image_with(&img, &options);
+        assert_eq!(px(&buf, 120, 10), [0, 0, 0]);
+        assert_eq!(px(&buf, 120, 120), red);
+
+        // Cover：填满
+        buf.load_from_image_with(&img, &FitOptions::new(FitMode::Cover));
+        assert_eq!(px(&buf, 0, 0), red);
+        assert_eq!(px(&buf, 239, 239), red);
+
+        // Center：原尺寸居中
+        buf.load_from_image_with(&img, &FitOptions::new(FitMode::Center));
+        assert_eq!(px(&buf, 59, 120), [0, 0, 0]);
+        assert_eq!(px(&buf, 60, 90), red);
+        assert_eq!(px(&buf, 179, 149), red);
+        assert_eq!(px(&buf, 180, 120), [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_orientation() {
+        let mut buf = ImageBuffer::new();
+        buf.set_pixel(0, 0, Color::White);
+        let lit =
+            |buf: &ImageBuffer, x: usize, y: usize| buf.data[(y * FRAME_WIDTH + x) * 3] == 255;
+
+        let mut rotated = buf.clone();
+        rotated.apply_orientation(&Orientation::new(Rotation::Deg90));
+        assert!(lit(&rotated, 239, 0));
+
+        let mut rotated = buf.clone();
+        rotated.apply_orientation(&Orientation::new(Rotation::Deg270));
+        assert!(lit(&rotated, 0, 239));
+
+        // 旋转 180° 后再水平翻转 = 垂直翻转
+        let mut a = buf.clone();
+        a.apply_orientation(&Orientation::new(Rotation::Deg180).flip_horizontal());
+        let mut b = buf.clone();
+        b.apply_orientation(&Orientation::default().flip_vertical());
+        assert!(lit(&a, 0, 239));
+        assert_eq!(a.as_data(), b.as_data());
+
+        assert_eq!(Rotation::try_from(180), Ok(Rotation::Deg180));
+        assert!(Rotation::try_from(45).is_err());
+
+        // 方向在发送时应用，图片缓冲区保持不变
+        let mut bot = ElectronBot::new();
+        bot.image_buffer().set_pixel(0, 0, Color::White);
+        bot.set_orientation(Orientation::new(Rotation::Deg90));
+        assert!(lit(&bot.output_frame(), 239, 0));
+        assert!(lit(bot.image_buffer(), 0, 0));
+    }
+
+    #[test]
+    fn test_color_adjust_lut() {
+        assert!(ColorAdjust::new().is_identity());
+        assert_eq!(ColorAdjust::new().to_lut(), ColorLut::identity());
+
+        // 白点增益按 R, G, B 给出，查找表按 B, G, R 排列
+        let lut = ColorAdjust::new().white_point(1.0, 1.0, 0.5).to_lut();
+        assert_eq!(lut.table(0).unwrap()[255], 128);
+        assert_eq!(lut.table(2).unwrap()[255], 255);
+
+        // gamma 大于 1 提亮中间调，端点不变
+        let lut = ColorAdjust::new().gamma(2.2).to_lut();
+        let table = lut.table(1).unwrap();
+        assert_eq!((table[0], table[255]), (0, 255));
+        assert!(table[64] > 64);
+
+        // 对比度增强拉开中间调
+        let lut = ColorAdjust::new().contrast(2.0).to_lut();
+        assert!(lut.table(1).unwrap()[64] < 64);
+        assert!(lut.table(1).unwrap()[192] > 192);
+    }
+
+    #[test]
+    fn test_color_adjust_applied_on_output() {
+        let mut bot = ElectronBot::new();
+        bot.image_buffer().data.fill(200);
+        bot.set_color_adjust(ColorAdjust::new().brightness(-0.5));
+        assert!(bot.output_frame().as_data().iter().all(|&v| v == 73));
+        assert!(bot.image_buffer().as_data().iter().all(|&v| v == 200));
+
+        bot.set_color_adjust(ColorAdjust::new());
+        assert!(matches!(bot.output_frame(), std::borrow::Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_dither_rgb565() {
+        // 水平灰度渐变
+        let mut buf = ImageBuffer::new();
+        for x in 0..FRAME_WIDTH {
+            let v = (x * 255 / (FRAME_WIDTH - 1)) as u8;
+            buf.fill_rect(x, 0, 1, FRAME_HEIGHT, Color::Custom(v, v, v));
+        }
+        let original = buf.clone();
+        buf.dither_rgb565();
+
+        // 每个通道的低位与高位一致（可无损截断为 RGB565）
+        for px in buf.as_data().chunks_exact(3) {
+            assert_eq!(px[0] & 0x07, px[0] >> 5);
+            assert_eq!(px[1] & 0x03, px[1] >> 6);
+        }
+        // 一行内的平均亮度与原图接近
+        let row = |b: &ImageBuffer| -> u32 {
+            b.as_data()[120 * FRAME_WIDTH * 3..121 * FRAME_WIDTH * 3]
+                .iter()
+                .map(|&v| v as u32)
+                .sum()
+        };
+        let (a, b) = (row(&original), row(&buf));
+        assert!(a.abs_diff(b) < FRAME_WIDTH as u32 * 3);
+
+        let mut bot = ElectronBot::new();
+        assert!(!bot.dithering());
+        bot.set_dithering(true);
+        assert!(matches!(bot.output_frame(), std::borrow::Cow::Owned(_)));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_hdr_tone_mapping() {
+        let px = |buf: &ImageBuffer| buf.as_data()[0];
+
+        // 16 位图片按比例缩放而不是截断
+        let img = image::DynamicImage::ImageRgb16(image::ImageBuffer::from_pixel(
+            4,
+            4,
+            image::Rgb([32768u16, 32768, 32768]),
+        ));
+        let mut buf = ImageBuffer::new();
+        buf.load_from_image(&img);
+        assert_eq!(px(&buf), 128);
+
+        // 浮点 HDR：高光压缩到白点，中间调不会被截断为白色
+        let mut hdr = image::Rgb32FImage::from_pixel(2, 1, image::Rgb([1.0, 1.0, 1.0]));
+        hdr.put_pixel(1, 0, image::Rgb([8.0, 8.0, 8.0]));
+        let img = image::DynamicImage::ImageRgb32F(hdr);
+        buf.load_from_image(&img);
+        assert!(px(&buf) > 100 && px(&buf) < 200);
+        assert_eq!(buf.as_data()[(FRAME_WIDTH - 1) * 3], 255);
+
+        // 曝光 -3 EV：8.0 缩放为 1.0
+        let options = FitOptions::default().tone_map(ToneMap::Exposure(-3.0));
+        buf.load_from_image_with(&img, &options);
+        assert_eq!(buf.as_data()[(FRAME_WIDTH - 1) * 3], 255);
+        assert_eq!(px(&buf), 32);
+    }
+
+    #[test]
+    fn test_color_hsv_hex_lerp() {
+        assert_eq!(Color::from_hsv(0.0, 1.0, 1.0).rgb(), (255, 0, 0));
+        assert_eq!(Color::from_hsv(120.0, 1.0, 1.0).rgb(), (0, 255, 0));
+        assert_eq!(Color::from_hsv(-120.0, 1.0, 1.0).rgb(), (0, 0, 255));
+        let (h, s, v) = Color::Custom(0, 255, 255).to_hsv();
+        assert_eq!((h, s, v), (180.0, 1.0, 1.0));
+
+        assert_eq!(Color::from_hex("#FF8000").unwrap().rgb(), (255, 128, 0));
+        assert_eq!(Color::from_hex("0f0").unwrap().rgb(), (0, 255, 0));
+        assert_eq!("#0000ff".parse::<Color>().unwrap().rgb(), (0, 0, 255));
+        assert!(Color::from_hex("#12345").is_err());
+        assert!(Color::from_hex("#GGGGGG").is_err());
+        assert_eq!(Color::Custom(1, 2, 171).to_hex(), "#0102AB");
+
+        assert_eq!(Color::Black.lerp(Color::White, 0.5).rgb(), (128, 128, 128));
+        assert_eq!(Color::Black.lerp(Color::Red, 2.0).rgb(), (255, 0, 0));
+    }
+
+    #[test]
+    fn test_gradient_fill() {
+        let gradient = Gradient::new(GradientKind::Horizontal, Color::Black, Color::White)
+            .with_stop(0.5, Color::Red);
+        assert_eq!(gradient.at(0.0).rgb(), (0, 0, 0));
+        assert_eq!(gradient.at(0.5).rgb(), (255, 0, 0));
+        assert_eq!(gradient.at(0.75).rgb(), (255, 128, 128));
+        assert_eq!(gradient.at(1.0).rgb(), (255, 255, 255));
+
+        let rainbow = Gradient::from_palette(GradientKind::Vertical, Palette::RAINBOW);
+        assert_eq!(rainbow.at(1.0), Palette::RAINBOW[6]);
+
+        let mut bot = ElectronBot::new();
+        bot.set_image_gradient(&Gradient::new(
+            GradientKind::Horizontal,
+            Color::Black,
+            Color::White,
+        ));
+        let data = bot.image_buffer().as_data();
+        assert_eq!(data[0], 0);
+        assert_eq!(data[(FRAME_WIDTH - 1) * 3], 255);
+        assert!(data[120 * 3] > 100 && data[120 * 3] < 160);
+
+        let mut buf = ImageBuffer::new();
+        buf.fill_rect_gradient(10, 10, 21, 21, &rainbow);
+        assert_eq!(
+            buf.as_data()[(10 * FRAME_WIDTH + 10) * 3..][..3],
+            [255, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_frame_sources() {
+        let mut buf = ImageBuffer::new();
+
+        assert!(ColorBars::new().next_frame(&mut buf));
+        assert!(buf.as_data()[..3].iter().all(|&v| v == 255));
+        assert!(buf.as_data()[(FRAME_WIDTH - 1) * 3..][..3]
+            .iter()
+            .all(|&v| v == 0));
+
+        let mut board = Checkerboard::new(10).scroll(5);
+        board.next_frame(&mut buf);
+        assert_eq!(buf.as_data()[0], 0);
+        assert_eq!(buf.as_data()[10 * 3], 255);
+        board.next_frame(&mut buf);
+        assert_eq!(buf.as_data()[5 * 3], 255);
+        assert!(board.frame_interval().is_some());
+
+        // 动画帧源每帧内容不同
+        let sources: Vec<Box<dyn FrameSource>> = vec![
+            Box::new(GradientSweep::rainbow(10.0)),
+            Box::new(Plasma::new()),
+            Box::new(PerlinNoise::new(42, 32.0)),
+        ];
+        for mut source in sources {
+            source.next_frame(&mut buf);
+            let first = buf.as_data().to_vec();
+            source.next_frame(&mut buf);
+            assert_ne!(first, buf.as_data());
+        }
+    }
+
+    #[test]
+    fn test_dead_pixel_test_ends() {
+        let mut buf = ImageBuffer::new();
+        let mut test = DeadPixelTest::new(2);
+        let mut frames = 0;
+        while test.next_frame(&mut buf) {
+            // 第一帧边框在最外圈
+            if frames == 0 {
+                assert_eq!(buf.as_data()[..3], [0, 0, 0]);
+                assert_ne!(buf.as_data()[(FRAME_WIDTH + 1) * 3..][..3], [0, 0, 0]);
+            }
+            frames += 1;
+        }
+        assert_eq!(frames, DeadPixelTest::COLORS.len() * 2);
+    }
+
+    #[cfg(feature = "video")]
+    #[test]
+    fn test_video_frame_rate_parsing() {
+        use modules::video::parse_rate;
+        assert_eq!(parse_rate("25"), Some(25.0));
+        assert!((parse_rate("30000/1001").unwrap() - 29.97).abs() < 0.01);
+        assert_eq!(parse_rate("0/0"), None);
+        assert_eq!(parse_rate("N/A"), None);
+        assert!(VideoSource::open("no_such_video.mp4").is_err());
+    }
+
+    #[cfg(feature = "mjpeg")]
+    #[test]
+    fn test_mjpeg_parsing() {
+        use modules::mjpeg::{parse_boundary, read_part};
+        use modules::net::{base64, read_headers, read_line, HttpUrl};
+
+        let url = HttpUrl::parse("http://admin:pw@192.168.1.10:8080/video?x=1").unwrap();
+        assert_eq!(url.host, "192.168.1.10");
+        assert_eq!(url.port, 8080);
+        assert_eq!(url.path, "/video?x=1");
+        assert_eq!(url.credentials.as_deref(), Some("admin:pw"));
+        assert_eq!(HttpUrl::parse("http://cam").unwrap().port, 80);
+        assert!(HttpUrl::parse("rtsp://cam").is_err());
+        assert_eq!(base64(b"admin:pw"), "YWRtaW46cHc=");
+
+        let ct = "multipart/x-mixed-replace; boundary=\"--frame\"";
+        assert_eq!(parse_boundary(ct).as_deref(), Some("frame"));
+        assert_eq!(parse_boundary("image/jpeg"), None);
+
+        // 一段带 Content-Length，一段不带
+        let mut stream = Vec::new();
+        stream
+            .extend_from_slice(b"--frame\r\nContent-Type: image/jpeg\r\nContent-Length: 4\r\n\r\n");
+        stream.extend_from_slice(&[0xFF, 0xD8, 0xFF, 0xD9]);
+        stream.extend_from_slice(b"\r\n--frame\r\nContent-Type: image/jpeg\r\n\r\n");
+        stream.extend_from_slice(&[0xFF, 0xD8, 0x01, 0xFF, 0xD9]);
+        let mut reader = std::io::Cursor::new(stream);
+        assert_eq!(read_part(&mut reader, "frame").unwrap().len(), 4);
+        assert_eq!(read_part(&mut reader, "frame").unwrap().len(), 5);
+        assert!(read_part(&mut reader, "frame").is_err());
+
+        // 过长的行和过大的头部返回错误，不会一直读下去
+        let mut long_line = vec![b'a'; 9 * 1024];
+        long_line.extend_from_slice(b"\r\n");
+        let error = read_line(&mut std::io::Cursor::new(long_line)).unwrap_err();
+        assert!(error.contains("8192"), "{}", error);
+        let headers = "X-Padding: 0123456789012345678901234567890123456789\r\n".repeat(2000);
+        let error = read_headers(&mut std::io::Cursor::new(headers)).unwrap_err();
+        assert!(error.contains("65536"), "{}", error);
+        let headers = read_headers(&mut std::io::Cursor::new("A: 1\r\nB: 2\r\n\r\n")).unwrap();
+        assert_eq!(headers.len(), 2);
+    }
+
+    #[cfg(feature = "mjpeg")]
+    #[test]
+    fn test_mjpeg_source_local_stream() {
+        use std::io::Write;
+
+        // 本地服务器发送一帧红色 JPEG 后关闭连接
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut conn, _) = listener.accept().unwrap();
+            let mut jpeg = Vec::new();
+            image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+                16,
+                16,
+                image::Rgb([255, 0, 0]),
+            ))
+            .write_to(
+                &mut std::io::Cursor::new(&mut jpeg),
+                image::ImageFormat::Jpeg,
+            )
+            .unwrap();
+            let mut response =
+                b"HTTP/1.0 200 OK\r\nContent-Type: multipart/x-mixed-replace; boundary=frame\r\n\r\n"
+                    .to_vec();
+            response.extend_from_slice(
+                format!(
+                    "--frame\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
+                    jpeg.len()
+                )
+                .as_bytes(),
+            );
+            response.extend_from_slice(&jpeg);
+            conn.write_all(&response).unwrap();
+        });
+
+        let mut source = MjpegSource::new(&format!("http://127.0.0.1:{}/stream", port))
+            .unwrap()
+            .reconnect(ReconnectPolicy::never());
+        let mut buf = ImageBuffer::new();
+        assert!(source.next_frame(&mut buf));
+        let px = &buf.as_data()[(120 * FRAME_WIDTH + 120) * 3..][..3];
+        assert!(px[2] > 200 && px[0] < 60);
+        server.join().unwrap();
+        // 服务器关闭后不再重连
+        assert!(!source.next_frame(&mut buf));
+    }
+
+    #[cfg(feature = "camera")]
+    #[test]
+    fn test_camera_ffmpeg_arguments() {
+        let options = CameraOptions::new("1").frame_rate(30).resolution(640, 480);
+        let args = options.input_args();
+        assert_eq!(args[0], "-f");
+        assert!(args.windows(2).any(|w| w == ["-framerate", "30"]));
+        assert!(args.windows(2).any(|w| w == ["-video_size", "640x480"]));
+        #[cfg(target_os = "linux")]
+        assert_eq!(args.last().unwrap(), "/dev/video1");
+
+        assert!(options.filter().starts_with("crop='min(iw,ih)'"));
+        assert!(options.filter().ends_with(",hflip"));
+        assert!(!options.clone().mirror(false).filter().contains("hflip"));
+    }
+
+    #[test]
+    fn test_pixel_formats() {
+        assert_eq!(rgb565_to_rgb(0xffff), (255, 255, 255));
+        assert_eq!(rgb565_to_rgb(0xf800), (255, 0, 0));
+        assert_eq!(rgb565_to_rgb(0x07e0), (0, 255, 0));
+        assert_eq!(rgb565_to_rgb(0x001f), (0, 0, 255));
+        assert_eq!(rgb565_to_rgb(0x8410), (132, 130, 132));
+
+        // 同一张 2x1 图片（左红右蓝）的各种格式转换结果与 RGB 原始数据相同
+        let mut expected = ImageBuffer::new();
+        let rgb = [255, 0, 0, 0, 0, 255];
+        expected
+            .load_pixels(&rgb, PixelFormat::Rgb888, 2, 1, &FitOptions::default())
+            .unwrap();
+        let cases: [(PixelFormat, &[u8]); 4] = [
+            (PixelFormat::Bgr888, &[0, 0, 255, 255, 0, 0]),
+            (PixelFormat::Rgb565, &[0x00, 0xf8, 0x1f, 0x00]),
+            (PixelFormat::Rgba8888, &[255, 0, 0, 255, 0, 0, 255, 255]),
+            (PixelFormat::Rgb888, &rgb),
+        ];
+        let mut bot = ElectronBot::new();
+        for (format, data) in cases {
+            assert_eq!(Some(data.len()), format.frame_len(2, 1));
+            assert_eq!(format.frame_len(usize::MAX, 2), None);
+            bot.load_frame(data, format, 2, 1).unwrap();
+            assert_eq!(
+                bot.image_buffer().as_data(),
+                expected.as_data(),
+                "{:?}",
+                format
+            );
+            assert!(bot.load_frame(&data[1..], format, 2, 1).is_err());
+        }
+        // 与 load_from_data 的字节顺序一致（不缩放，居中放在 (119, 119)）
+        let mut direct = ImageBuffer::new();
+        direct.load_from_data(&rgb, 2, 1).unwrap();
+        let center = (119 * FRAME_WIDTH + 119) * 3;
+        assert_eq!(
+            &expected.as_data()[..3],
+            &direct.as_data()[center..center + 3]
+        );
+
+        // 半透明与黑色混合，灰度三通道相同
+        bot.load_frame(&[200, 100, 0, 128], PixelFormat::Rgba8888, 1, 1)
+            .unwrap();
+        assert_eq!(&bot.image_buffer().as_data()[..3], &[0, 50, 100]);
+        bot.load_frame(&[77], PixelFormat::Gray8, 1, 1).unwrap();
+        assert_eq!(&bot.image_buffer().as_data()[..3], &[77; 3]);
+
+        // 整屏 BGR888 直接复制
+        let frame: Vec<u8> = (0..FRAME_SIZE).map(|i| (i % 251) as u8).collect();
+        bot.load_frame(&frame, PixelFormat::Bgr888, FRAME_WIDTH, FRAME_HEIGHT)
+            .unwrap();
+        assert_eq!(bot.image_buffer().as_data(), &frame[..]);
+    }
+
+    #[test]
+    fn test_row_layout() {
+        // 3x2 RGB 图片，每行填充到 12 字节
+        let rows = [
+            [[255, 0, 0], [0, 255, 0], [0, 0, 255]],
+            [[1, 2, 3], [4, 5, 6], [7, 8, 9]],
+        ];
+        let packed: Vec<u8> = rows.iter().flatten().flatten().copied().collect();
+        let mut padded = Vec::new();
+        for row in &rows {
+            padded.extend(row.iter().flatten());
+            padded.extend([0xee; 3]);
+        }
+        let mut flipped = Vec::new();
+        for row in rows.iter().rev() {
+            flipped.extend(row.iter().flatten());
+            flipped.extend([0xee; 3]);
+        }
+
+        let mut bot = ElectronBot::new();
+        bot.set_image_from_data(&packed, 3, 2).unwrap();
+        let expected = bot.image_buffer().as_data().to_vec();
+        bot.set_image_color(Color::Black);
+        bot.set_image_from_data_with(&padded, 3, 2, &RowLayout::new().stride(12))
+            .unwrap();
+        assert_eq!(bot.image_buffer().as_data(), expected);
+        bot.set_image_color(Color::Black);
+        // 最后一行不要求包含填充
+        let layout = RowLayout::new().stride(12).bottom_up(true);
+        bot.set_image_from_data_with(&flipped[..21], 3, 2, &layout)
+            .unwrap();
+        assert_eq!(bot.image_buffer().as_data(), expected);
+        assert!(bot
+            .set_image_from_data_with(&flipped[..20], 3, 2, &layout)
+            .is_err());
+        assert!(bot
+            .set_image_from_data_with(&padded, 3, 2, &RowLayout::new().stride(8))
+            .is_err());
+
+        // 尺寸大到字节数溢出时报错而不是回绕或崩溃
+        let error = bot
+            .set_image_from_data(&packed, usize::MAX / 2, 2)
+            .unwrap_err();
+        assert!(error.to_string().contains("数据太小"), "{}", error);
+        let error = bot
+            .set_image_from_data_with(&padded, 3, usize::MAX, &RowLayout::new().stride(12))
+            .unwrap_err();
+        assert!(error.to_string().contains("数据太小"), "{}", error);
+        let error = PixelView::with_layout(
+            &packed,
+            PixelFormat::Rgba8888,
+            usize::MAX / 2,
+            1,
+            &RowLayout::new(),
+        )
+        .unwrap_err();
+        assert!(error.contains("数据太小"), "{}", error);
+
+        // 打包格式同样支持行跨度和倒序
+        let mut expected = ImageBuffer::new();
+        expected
+            .load_pixels(&packed, PixelFormat::Rgb888, 3, 2, &FitOptions::default())
+            .unwrap();
+        bot.load_frame_with(&flipped, PixelFormat::Rgb888, 3, 2, &layout)
+            .unwrap();
+        assert_eq!(bot.image_buffer().as_data(), expected.as_data());
+
+        // 整屏 BGR888 带填充时不能直接复制
+        let stride = FRAME_WIDTH * 3 + 4;
+        let mut frame = vec![0u8; stride * FRAME_HEIGHT];
+        for row in frame.chunks_exact_mut(stride) {
+            row[..3].copy_from_slice(&[1, 2, 3]);
+        }
+        bot.load_frame_with(
+            &frame,
+            PixelFormat::Bgr888,
+            FRAME_WIDTH,
+            FRAME_HEIGHT,
+            &RowLayout::new().stride(stride),
+        )
+        .unwrap();
+        let data = bot.image_buffer().as_data();
+        for y in 0..FRAME_HEIGHT {
+            assert_eq!(&data[y * FRAME_WIDTH * 3..][..4], &[1, 2, 3, 0]);
+        }
+    }
+
+    #[test]
+    fn test_copy_from_region() {
+        // 400x300 RGB 源图片，像素值由坐标决定
+        let (w, h) = (400, 300);
+        let rgb_at =
+            |x: usize, y: usize| [(x % 256) as u8, (y % 256) as u8, (x / 256 + y / 256) as u8];
+        let rgb: Vec<u8> = (0..h)
+            .flat_map(|y| (0..w).flat_map(move |x| rgb_at(x, y)))
+            .collect();
+        let bgr_at = |x: usize, y: usize| {
+            let [r, g, b] = rgb_at(x, y);
+            [b, g, r]
+        };
+        let pixel = |buf: &ImageBuffer, x: usize, y: usize| {
+            let i = (y * FRAME_WIDTH + x) * 3;
+            [buf.as_data()[i], buf.as_data()[i + 1], buf.as_data()[i + 2]]
+        };
+
+        let view = PixelView::new(&rgb, PixelFormat::Rgb888, w, h).unwrap();
+        assert_eq!((view.width(), view.height()), (w, h));
+        let mut buf = ImageBuffer::new();
+        buf.copy_from(&view, Rect::new(100, 50, 240, 240), (0, 0));
+        for (x, y) in [(0, 0), (239, 0), (17, 123), (239, 239)] {
+            assert_eq!(pixel(&buf, x, y), bgr_at(100 + x, 50 + y));
+        }
+
+        // BGR888 按行复制，结果相同
+        let bgr: Vec<u8> = rgb
+            .chunks_exact(3)
+            .flat_map(|p| [p[2], p[1], p[0]])
+            .collect();
+        let mut fast = ImageBuffer::new();
+        let bgr_view = PixelView::new(&bgr, PixelFormat::Bgr888, w, h).unwrap();
+        fast.copy_from(&bgr_view, Rect::new(100, 50, 240, 240), (0, 0));
+        assert_eq!(fast.as_data(), buf.as_data());
+
+        // 源区域和目标都会裁剪，区域外保持不变
+        let mut buf = ImageBuffer::new();
+        buf.clear(Color::White);
+        let white = pixel(&buf, 0, 0);
+        buf.copy_from(&view, Rect::new(380, -10, 50, 50), (-5, 200));
+        assert_eq!(pixel(&buf, 0, 210), bgr_at(385, 0));
+        assert_eq!(pixel(&buf, 14, 239), bgr_at(399, 29));
+        assert_eq!(pixel(&buf, 15, 239), white);
+        assert_eq!(pixel(&buf, 0, 209), white);
+        buf.copy_from(&view, Rect::new(0, 0, 10, 10), (240, 0));
+        buf.copy_from(&view, Rect::new(500, 0, 10, 10), (0, 0));
+        let changed = buf.as_data().chunks_exact(3).filter(|p| *p != white);
+        assert_eq!(changed.count(), 15 * 30);
+
+        // 倒序存放的源图片
+        let flipped: Vec<u8> = rgb.chunks_exact(w * 3).rev().flatten().copied().collect();
+        let view = PixelView::with_layout(
+            &flipped,
+            PixelFormat::Rgb888,
+            w,
+            h,
+            &RowLayout::new().bottom_up(true),
+        )
+        .unwrap();
+        buf.copy_from(&view, Rect::new(100, 50, 240, 240), (0, 0));
+        assert_eq!(pixel(&buf, 17, 123), bgr_at(117, 173));
+        assert!(PixelView::new(&rgb[1..], PixelFormat::Rgb888, w, h).is_err());
+    }
+
+    #[test]
+    fn test_indexed_input() {
+        // 整屏灰度：逐字节查表
+        let gray: Vec<u8> = (0..FRAME_WIDTH * FRAME_HEIGHT).map(|i| i as u8).collect();
+        let mut bot = ElectronBot::new();
+        bot.set_image_from_gray(&gray, FRAME_WIDTH, FRAME_HEIGHT)
+            .unwrap();
+        let data = bot.image_buffer().as_data();
+        assert_eq!(&data[..9], &[0, 0, 0, 1, 1, 1, 2, 2, 2]);
+        assert_eq!(&data[FRAME_SIZE - 3..], &[255; 3]);
+
+        // 调色板与 RGB 原始数据展开的结果一致
+        let palette = IndexedPalette::new(&[Color::Black, Color::Red, Color::Custom(1, 2, 3)]);
+        assert_eq!(palette.get(2), Color::Custom(1, 2, 3));
+        assert_eq!(palette.get(200), Color::Custom(0, 0, 0));
+        assert_eq!(
+            IndexedPalette::from_rgb(&[0, 0, 0, 255, 0, 0, 1, 2, 3, 9]),
+            palette
+        );
+        let indices: Vec<u8> = (0..FRAME_WIDTH * FRAME_HEIGHT)
+            .map(|i| (i % 3) as u8)
+            .collect();
+        let rgb: Vec<u8> = indices
+            .iter()
+            .flat_map(|&i| {
+                let (r, g, b) = palette.get(i).rgb();
+                [r, g, b]
+            })
+            .collect();
+        let options = FitOptions::default();
+        let mut indexed = ImageBuffer::new();
+        indexed
+            .load_from_indexed(&indices, FRAME_WIDTH, FRAME_HEIGHT, &palette, &options)
+            .unwrap();
+        let mut direct = ImageBuffer::new();
+        direct
+            .load_from_data(&rgb, FRAME_WIDTH, FRAME_HEIGHT)
+            .unwrap();
+        assert_eq!(indexed.as_data(), direct.as_data());
+
+        // 缩放：2x1 拉伸后左半为红色，右半为 (1, 2, 3)
+        indexed
+            .load_from_indexed(&[1, 2], 2, 1, &palette, &options)
+            .unwrap();
+        assert_eq!(&indexed.as_data()[..3], &direct.as_data()[3..6]);
+        assert_eq!(
+            &indexed.as_data()[FRAME_SIZE - 3..],
+            &direct.as_data()[6..9]
+        );
+        assert!(indexed
+            .load_from_indexed(&[1], 2, 1, &palette, &options)
+            .is_err());
+        let error = indexed
+            .load_from_indexed(&[1], usize::MAX, 2, &palette, &options)
+            .unwrap_err();
+        assert!(error.contains("索引数据太小"), "{}", error);
+        assert!(indexed.load_from_gray(&[], 0, 0, &options).is_err());
+
+        // 调色板循环
+        let mut cycled = palette.clone();
+        cycled.rotate(1);
+        assert_eq!(cycled.get(0), Color::Custom(255, 0, 0));
+        assert_eq!(cycled.get(255), Color::Custom(0, 0, 0));
+    }
+
+    #[test]
+    fn test_yuv_conversion() {
+        // 白、黑和纯红（BT.601 有限范围）
+        assert_eq!(yuv_to_rgb(235, 128, 128), (255, 255, 255));
+        assert_eq!(yuv_to_rgb(16, 128, 128), (0, 0, 0));
+        let (r, g, b) = yuv_to_rgb(81, 90, 240);
+        assert!(r > 250 && g < 5 && b < 5);
+
+        // 4x2 图片：左半红色，右半白色
+        let (w, h) = (4, 2);
+        let mut i420 = vec![0u8; YuvFormat::I420.frame_len(w, h).unwrap()];
+        for y in 0..h {
+            i420[y * w..y * w + 2].fill(81);
+            i420[y * w + 2..y * w + 4].fill(235);
+        }
+        i420[8..10].copy_from_slice(&[90, 128]);
+        i420[10..12].copy_from_slice(&[240, 128]);
+
+        // 同样内容的 NV12 和 YUYV
+        let mut nv12 = i420[..8].to_vec();
+        nv12.extend_from_slice(&[90, 240, 128, 128]);
+        let mut yuyv = Vec::new();
+        for _ in 0..h {
+            yuyv.extend_from_slice(&[81, 90, 81, 240, 235, 128, 235, 128]);
+        }
+
+        let options = FitOptions::default();
+        for (format, data) in [
+            (YuvFormat::I420, &i420),
+            (YuvFormat::Nv12, &nv12),
+            (YuvFormat::Yuyv, &yuyv),
+        ] {
+            let mut buf = ImageBuffer::new();
+            buf.load_from_yuv(data, w, h, format, &options).unwrap();
+            let left = &buf.as_data()[..3];
+            assert!(left[2] > 250 && left[0] < 5, "{:?}", format);
+            assert!(buf.as_data()[(FRAME_WIDTH - 1) * 3..][..3]
+                .iter()
+                .all(|&v| v == 255));
+            assert!(buf
+                .load_from_yuv(&data[..3], w, h, format, &options)
+                .is_err());
+            // 尺寸乘积溢出时返回错误而不是 panic
+            assert_eq!(format.frame_len(usize::MAX, 2), None);
+            assert!(buf
+                .load_from_yuv(data, usize::MAX, 2, format, &options)
+                .is_err());
+        }
+
+        // Contain：横图上下留边
+        let mut bot = ElectronBot::new();
+        bot.set_fit_options(FitOptions::new(FitMode::Contain));
+        bot.set_image_from_yuv(&i420, w, h, YuvFormat::I420)
+            .unwrap();
+        assert_eq!(bot.image_buffer().as_data()[..3], [0, 0, 0]);
+        assert_ne!(
+            bot.image_buffer().as_data()[120 * FRAME_WIDTH * 3..][..3],
+            [0, 0, 0]
+        );
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_fast_decode_matches_generic_path() {
+        use image::{ImageFormat, Rgb, RgbImage};
+        use std::io::Cursor;
+
+        let img = RgbImage::from_fn(60, 30, |x, y| Rgb([(x * 4) as u8, (y * 8) as u8, 200]));
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgb8(img.clone())
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        // 拉伸 + 最近邻时与通用路径逐字节一致
+        let mut fast = ImageBuffer::new();
+        fast.load_png_bytes(&png).unwrap();
+        let mut generic = ImageBuffer::new();
+        generic.load_from_image(&image::DynamicImage::ImageRgb8(img));
+        assert_eq!(fast.as_data(), generic.as_data());
+        assert_eq!(fast.as_data()[..3], [200, 0, 0]);
+
+        // 保持宽高比时上下留边
+        let options = FitOptions::new(FitMode::Contain).background(Color::Black);
+        fast.load_image_bytes_with(&png, &options).unwrap();
+        assert_eq!(fast.as_data()[..3], [0, 0, 0]);
+        assert_eq!(fast.as_data()[120 * FRAME_WIDTH * 3..][..3], [200, 120, 0]);
+
+        assert!(fast.load_jpeg_bytes(&png).is_err());
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_fast_decode_jpeg() {
+        use image::{ImageFormat, Luma};
+        use std::io::Cursor;
+
+        let img = image::GrayImage::from_pixel(240, 240, Luma([128]));
+        let mut jpeg = Vec::new();
+        image::DynamicImage::ImageLuma8(img)
+            .write_to(&mut Cursor::new(&mut jpeg), ImageFormat::Jpeg)
+            .unwrap();
+
+        let mut bot = ElectronBot::new();
+        bot.set_image_from_bytes(&jpeg).unwrap();
+        let px = &bot.image_buffer().as_data()[..3];
+        assert!(px.iter().all(|&v| v.abs_diff(128) <= 2));
+        assert!(bot.set_image_from_bytes(b"not an image").is_err());
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_convert_frame() {
+        let src = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/test.png");
+        let dst = std::env::temp_dir()
+            .join(format!("electron_bot_embed_{}", std::process::id()))
+            .join("test.png.bgr");
+        convert_frame(src, &dst, &FitOptions::default()).unwrap();
+
+        let bytes = std::fs::read(&dst).unwrap();
+        let frame: &[u8; FRAME_SIZE] = bytes.as_slice().try_into().unwrap();
+        let mut expected = ImageBuffer::new();
+        expected.load_from_file(src).unwrap();
+
+        let mut bot = ElectronBot::new();
+        bot.set_image_frame(frame);
+        assert_eq!(bot.image_buffer().as_data(), expected.as_data());
+        bot.frame_writer().fill(0);
+        bot.frame_writer()[..3].copy_from_slice(&[1, 2, 3]);
+        assert_eq!(&bot.image_buffer().as_data()[..4], [1, 2, 3, 0]);
+        let _ = std::fs::remove_dir_all(dst.parent().unwrap());
+
+        assert!(convert_frame("missing.png", &dst, &FitOptions::default()).is_err());
+    }
+
+    #[cfg(feature = "qr")]
+    #[test]
+    fn test_draw_qr() {
+        let mut buf = ImageBuffer::new();
+        let side = buf
+            .draw_qr("https://example.com", 0, 0, FRAME_WIDTH)
+            .unwrap();
+        // 版本 2（25 模块）+ 静区 4 模块，每模块 8 像素
+        assert_eq!(side, 29 * 8);
+        let offset = (FRAME_WIDTH - side) / 2;
+        let px = |buf: &ImageBuffer, x: usize, y: usize| {
+            let idx = (y * FRAME_WIDTH + x) * 3;
+            buf.as_data()[idx..idx + 3].to_vec()
+        };
+        assert_eq!(px(&buf, offset, offset), [255, 255, 255]);
+        // 左上角定位图案的外框为深色
+        let finder = offset + 2 * 8;
+        assert_eq!(px(&buf, finder, finder), [0, 0, 0]);
+        assert_eq!(px(&buf, 0, 0), [0, 0, 0]);
+
+        assert!(buf.draw_qr("https://example.com", 0, 0, 20).is_err());
+    }
+
+    #[test]
+    fn test_face_transition() {
+        let mut face = Face::new();
+        face.transition_to(Expression::HAPPY, std::time::Duration::from_millis(300));
+        assert!(face.is_transitioning());
+        assert_eq!(face.expression(), Expression::NEUTRAL);
+
+        face.advance(std::time::Duration::from_millis(150));
+        let mid = face.expression();
+        let half = Expression::NEUTRAL.lerp(&Expression::HAPPY, 0.5);
+        assert!((mid.mouth_curve - half.mouth_curve).abs() < 1e-4);
+
+        // 过渡途中切换目标时从当前表情继续
+        face.transition_to(Expression::SAD, std::time::Duration::from_millis(300));
+        assert_eq!(face.expression(), mid);
+        face.advance(std::time::Duration::from_secs(1));
+        assert!(!face.is_transitioning());
+        assert_eq!(face.expression(), Expression::SAD);
+    }
+
+    #[test]
+    fn test_draw_face() {
+        let eye_center = (100 * FRAME_WIDTH + 120 - 48 - 20) * 3;
+        let mut buf = ImageBuffer::new();
+        buf.draw_face(&Expression::NEUTRAL, &FaceStyle::default());
+        assert_eq!(buf.as_data()[eye_center..][..3], [255, 255, 255]);
+        // 瞳孔在眼睛中心
+        assert_eq!(
+            buf.as_data()[(100 * FRAME_WIDTH + 72) * 3..][..3],
+            [0, 0, 0]
+        );
+
+        // 闭眼时眼睑把眼白盖住
+        let closed = Expression {
+            eye_open: 0.0,
+            ..Expression::NEUTRAL
+        };
+        buf.draw_face(&closed, &FaceStyle::default());
+        assert_eq!(buf.as_data()[eye_center..][..3], [0, 0, 0]);
+
+        let mut face = Face::new();
+        assert!(face.next_frame(&mut buf));
+        assert_eq!(buf.as_data()[eye_center..][..3], [255, 255, 255]);
+    }
+
+    #[test]
+    fn test_idle_behavior() {
+        let mut idle = IdleBehavior::with_seed(42);
+        let mut buf = ImageBuffer::new();
+        let mut blinks = 0;
+        let mut was_blinking = false;
+        // 模拟 20 秒
+        for _ in 0..600 {
+            assert!(idle.next_frame(&mut buf));
+            let e = idle.expression();
+            assert!(e.pupil_x.abs() <= 0.36 && e.pupil_y.abs() <= 0.36);
+            let pose = idle.joint_angles().unwrap();
+            assert!(pose.0[JOINT_HEAD].abs() <= 4.0);
+            assert!(pose.0[JOINT_BODY].abs() <= 6.0);
+            assert_eq!(pose.0[1..5], [0.0; 4]);
+            if idle.is_blinking() && !was_blinking {
+                blinks += 1;
+            }
+            was_blinking = idle.is_blinking();
+        }
+        assert!((3..=12).contains(&blinks), "blinks = {}", blinks);
+
+        // 关闭头部摆动且注视只动眼球时不控制舵机
+        let still = IdleBehavior::with_seed(1)
+            .options(IdleOptions::default().head_motion(0.0))
+            .gaze_options(GazeOptions::eyes_only());
+        assert_eq!(still.joint_angles(), None);
+    }
+
+    #[test]
+    fn test_gaze_eyes_and_head() {
+        let dt = std::time::Duration::from_millis(33);
+        let mut gaze = Gaze::default();
+
+        // 眼球范围内只移动瞳孔
+        gaze.look_at(GazeTarget::Direction {
+            yaw: 10.0,
+            pitch: 0.0,
+        });
+        for _ in 0..30 {
+            gaze.advance(dt);
+        }
+        assert!(gaze.is_settled());
+        assert!((gaze.pupil().0 - 0.5).abs() < 0.02);
+        assert_eq!(gaze.head(), (0.0, 0.0));
+
+        // 超出部分由身体旋转承担，眼球停在边缘
+        gaze.look_at(GazeTarget::World {
+            x: 1.0,
+            y: 0.0,
+            z: 1.0,
+        });
+        assert!((gaze.target().0 - 45.0).abs() < 1e-3);
+        let mut steps = 0;
+        while !gaze.is_settled() {
+            gaze.advance(dt);
+            steps += 1;
+            assert!(steps < 90);
+        }
+        assert!((gaze.head().0 - 25.0).abs() < 0.5);
+        assert!((gaze.pupil().0 - 1.0).abs() < 0.03);
+
+        // 只动眼球时不转头
+        let mut eyes = Gaze::new(GazeOptions::eyes_only());
+        eyes.look_at(GazeTarget::Screen { x: 1.0, y: -1.0 });
+        for _ in 0..30 {
+            eyes.advance(dt);
+        }
+        assert_eq!(eyes.head(), (0.0, 0.0));
+        assert!(eyes.pupil().0 > 0.98 && eyes.pupil().1 < -0.98);
+    }
+
+    #[test]
+    fn test_idle_gaze_moves_body() {
+        let options = IdleOptions::default().head_motion(0.0).pupil_drift(0.0);
+        let mut idle = IdleBehavior::with_seed(3).options(options);
+        idle.gaze_at(GazeTarget::Direction {
+            yaw: -40.0,
+            pitch: 0.0,
+        });
+        let mut buf = ImageBuffer::new();
+        for _ in 0..90 {
+            idle.next_frame(&mut buf);
+        }
+        let pose = idle.joint_angles().unwrap();
+        assert!((pose.0[JOINT_BODY] + 20.0).abs() < 0.5);
+        assert!(idle.expression().pupil_x < -0.97);
+    }
+
+    #[cfg(feature = "audio")]
+    #[test]
+    fn test_lip_sync_envelope() {
+        let dt = std::time::Duration::from_millis(33);
+        let mut lip = LipSync::new();
+        let level = lip.input();
+
+        // 静音低于门限时不张嘴
+        level.push_samples(&[0.01; 64]);
+        assert_eq!(lip.update(dt), 0.0);
+
+        // 多次写入取最大值，并在读取后清零
+        level.push_rms(0.1);
+        level.push_samples_i16(&[16384; 64]);
+        level.push_rms(0.2);
+        let open = lip.update(dt);
+        assert!(open > 0.5 && open <= lip.max_open);
+        assert_eq!(level.take(), 0.0);
+
+        // 静音后按释音时间慢慢闭嘴
+        let after = lip.update(dt);
+        assert!(after < open && after > open * 0.5);
+        for _ in 0..30 {
+            lip.update(dt);
+        }
+        assert!(lip.mouth_open() < 0.01);
+
+        let mut idle = IdleBehavior::with_seed(5).lip_sync(
+            LipSync::new().smoothing(std::time::Duration::ZERO, std::time::Duration::ZERO),
+        );
+        let input = idle.lip_sync_mut().unwrap().input();
+        input.push_rms(0.5);
+        idle.advance(dt);
+        assert!((idle.expression().mouth_open - 0.9).abs() < 1e-4);
+    }
+
+    #[cfg(feature = "audio")]
+    #[test]
+    fn test_viseme_track() {
+        use std::time::Duration;
+
+        let marks = r#"{"time":0,"type":"sentence","start":0,"end":5,"value":"Hello"}
+{"time":6,"type":"word","start":0,"end":5,"value":"Hello"}
+{"time":6,"type":"viseme","value":"k"}
+{"time":200,"type":"viseme","value":"a"}
+{"time":400,"type":"viseme","value":"sil"}"#;
+        let track = VisemeTrack::from_polly_marks(marks).unwrap();
+        assert_eq!(
+            track.events(),
+            [
+                (Duration::from_millis(6), Viseme::KK),
+                (Duration::from_millis(200), Viseme::AA),
+                (Duration::from_millis(400), Viseme::Silence),
+            ]
+        );
+        // 过渡完成后保持当前口型，过渡中间取两者之间
+        assert_eq!(
+            track.mouth_at(Duration::from_millis(300)),
+            Viseme::AA.mouth()
+        );
+        let mid = track.mouth_at(Duration::from_millis(230));
+        let expected = Viseme::KK.mouth().lerp(&Viseme::AA.mouth(), 0.5);
+        assert!((mid.open - expected.open).abs() < 1e-3);
+        assert_eq!(track.duration(), Duration::from_millis(460));
+        assert!(
+            VisemeTrack::from_polly_marks(r#"{"time":1,"type":"viseme","value":"?"}"#).is_err()
+        );
+
+        // Azure 视位 ID 和音素
+        let mut track = VisemeTrack::new();
+        track.push_azure(5_000_000, 21);
+        track.push_phoneme(Duration::from_millis(100), "a:");
+        track.push_phoneme(Duration::from_millis(150), "??");
+        assert_eq!(
+            track.events(),
+            [
+                (Duration::from_millis(100), Viseme::AA),
+                (Duration::from_millis(500), Viseme::PP),
+            ]
+        );
+        assert_eq!(Viseme::from_arpabet("ow1"), Some(Viseme::OH));
+        assert_eq!(Viseme::from_xsampa("S"), Some(Viseme::CH));
+
+        // 播放期间覆盖空闲动画的嘴型
+        let mut track = VisemeTrack::new();
+        track.push(Duration::ZERO, Viseme::AA);
+        track.push(Duration::from_secs(10), Viseme::Silence);
+        let mut player = VisemePlayer::start(track);
+        player.sync(Duration::from_secs(1));
+        assert!(player.position() >= Duration::from_secs(1));
+        let mut idle = IdleBehavior::with_seed(9);
+        idle.speak(player);
+        assert_eq!(idle.expression().mouth_open, Viseme::AA.mouth().open);
+    }
+
+    #[cfg(feature = "audio")]
+    #[test]
+    fn test_spectrum_sine_peak() {
+        let mut spectrum = Spectrum::new(44100).bars(16);
+        spectrum.update();
+        assert!(spectrum.levels().iter().all(|&l| l == 0.0));
+
+        // 满幅 1kHz 正弦波：所在频带接近满格，远离的频带保持安静
+        let samples: Vec<f32> = (0..2048)
+            .map(|i| (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / 44100.0).sin())
+            .collect();
+        spectrum.input().push_samples(&samples);
+        spectrum.update();
+        let levels = spectrum.levels();
+        let loudest = (0..levels.len())
+            .max_by(|&a, &b| levels[a].total_cmp(&levels[b]))
+            .unwrap();
+        // 40Hz 到 16kHz 分 16 段，1kHz 落在第 8 段
+        assert_eq!(loudest, 8);
+        assert!(levels[loudest] > 0.9);
+        assert!(levels[0] < 0.3 && levels[15] < 0.3);
+
+        let mut buffer = ImageBuffer::new();
+        let mut radial = spectrum.clone().style(SpectrumStyle::Radial);
+        assert!(radial.next_frame(&mut buffer));
+    }
+
+    #[test]
+    fn test_clock_time_and_render() {
+        let time = ClockTime::new(2026, 10, 14, 22, 9, 42).unwrap();
+        // 2026-10-14 是星期三
+        assert_eq!(time.weekday, 2);
+        assert!(ClockTime::new(2026, 2, 30, 0, 0, 0).is_none());
+
+        let mut buffer = ImageBuffer::new();
+        let clock = Clock::digital();
+        clock.render_at(&mut buffer, &time);
+        let style = clock.clock_style();
+        let pixel = |buffer: &ImageBuffer, x: usize, y: usize| {
+            let i = (y * FRAME_WIDTH + x) * 3;
+            buffer.as_data()[i..i + 3].to_vec()
+        };
+        let (r, g, b) = style.accent.rgb();
+        // 星期标记：星期三高亮
+        assert_eq!(pixel(&buffer, 120 - 16, 60), vec![r, g, b]);
+        assert_ne!(pixel(&buffer, 120, 60), vec![r, g, b]);
+
+        Clock::new().render_at(&mut buffer, &time);
+        let (r, g, b) = style.dial.rgb();
+        assert_eq!(pixel(&buffer, 120, 60), vec![r, g, b]);
+    }
+
+    #[test]
+    fn test_inactivity_switch() {
+        let mut buffer = ImageBuffer::new();
+        let mut switch = InactivitySwitch::new(
+            ColorBars::new(),
+            Clock::new(),
+            std::time::Duration::from_secs(60),
+        );
+        assert!(switch.next_frame(&mut buffer));
+        assert!(!switch.is_idle());
+        assert_eq!(switch.frame_interval(), None);
+
+        let mut switch =
+            InactivitySwitch::new(ColorBars::new(), Clock::new(), std::time::Duration::ZERO);
+        switch.activity().touch();
+        assert!(switch.next_frame(&mut buffer));
+        assert!(switch.is_idle());
+        assert!(switch.frame_interval().is_some());
+    }
+
+    #[test]
+    fn test_widgets() {
+        let mut line = Sparkline::new(3);
+        for v in [5.0, 1.0, 4.0, 2.0] {
+            line.push(v);
+        }
+        assert_eq!(line.data().collect::<Vec<_>>(), vec![1.0, 4.0, 2.0]);
+        assert_eq!(line.bounds(), (1.0, 4.0));
+        assert_eq!(line.clone().range(0.0, 10.0).bounds(), (0.0, 10.0));
+
+        let mut buffer = ImageBuffer::new();
+        buffer.clear(Color::Black);
+        let mut bar = ProgressBar::new().colors(Color::Blue, Color::Red);
+        bar.set_progress(0.5);
+        buffer.draw_widget(&bar, Rect::new(20, 100, 200, 20));
+        let pixel = |x: usize, y: usize| {
+            let i = (y * FRAME_WIDTH + x) * 3;
+            buffer.as_data()[i..i + 3].to_vec()
+        };
+        assert_eq!(pixel(60, 110), vec![255, 0, 0]);
+        assert_eq!(pixel(180, 110), vec![0, 0, 255]);
+
+        let mut gauge = Gauge::new(0.0, 100.0).label(None);
+        gauge.set_value(250.0);
+        buffer.draw_widget(&gauge, Rect::new(0, 0, 240, 240));
+        buffer.draw_widget(&line, Rect::new(0, 0, 240, 60));
+        assert_eq!(gauge.value(), 250.0);
+    }
+
+    #[test]
+    fn test_notification_overlay() {
+        use std::time::Duration;
+
+        let solid = |color: Color| {
+            let mut buffer = ImageBuffer::new();
+            buffer.clear(color);
+            buffer
+        };
+        let mut bars = ImageBuffer::new();
+        ColorBars::new().next_frame(&mut bars);
+
+        let mut overlay = NotificationOverlay::new(ColorBars::new());
+        let notifier = overlay.notifier();
+        let mut buffer = ImageBuffer::new();
+        assert!(overlay.next_frame(&mut buffer));
+        assert!(!overlay.is_showing());
+
+        notifier.notify(
+            Notification::image(solid(Color::Red), Duration::from_millis(100))
+                .transition(Duration::ZERO),
+        );
+        assert!(overlay.next_frame(&mut buffer));
+        assert!(overlay.is_showing());
+        assert_eq!(buffer.as_data(), solid(Color::Red).as_data());
+
+        // 更高优先级的通知打断当前通知，被打断的通知放回队列
+        notifier.notify(
+            Notification::image(solid(Color::Blue), Duration::ZERO)
+                .transition(Duration::ZERO)
+                .priority(5),
+        );
+        overlay.next_frame(&mut buffer);
+        assert_eq!(buffer.as_data(), solid(Color::Blue).as_data());
+        assert_eq!(notifier.pending(), 1);
+
+        let mut frames = 0;
+        while {
+            overlay.next_frame(&mut buffer);
+            overlay.is_showing()
+        } {
+            frames += 1;
+        }
+        // 红色通知重新完整显示 100ms（4 帧）
+        assert_eq!(frames, 4);
+        assert_eq!(buffer.as_data(), bars.as_data());
+        assert_eq!(notifier.pending(), 0);
+    }
+
+    #[test]
+    fn test_scene_manager() {
+        use std::time::Duration;
+
+        let mut buffer = ImageBuffer::new();
+        let mut scenes = SceneManager::new();
+        scenes.add(Scene::new("idle", ColorBars::new()));
+        scenes.add(
+            Scene::new("clock", Clock::new())
+                .priority(1)
+                .after_inactivity(Duration::from_secs(60)),
+        );
+        assert!(scenes.next_frame(&mut buffer));
+        assert_eq!(scenes.active(), Some("idle"));
+
+        // 手动控制抢占 100ms（4 帧）后移除
+        scenes.add(
+            Scene::new("manual", Checkerboard::new(20))
+                .priority(10)
+                .duration(Duration::from_millis(100)),
+        );
+        let mut red = ImageBuffer::new();
+        red.clear(Color::Red);
+        scenes
+            .notifier()
+            .notify(Notification::image(red.clone(), Duration::ZERO).transition(Duration::ZERO));
+        let active: Vec<_> = (0..6)
+            .map(|_| {
+                scenes.next_frame(&mut buffer);
+                scenes.active().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(
+            active,
+            [
+                "manual",
+                "manual",
+                "manual",
+                "manual",
+                NOTIFICATION_SCENE,
+                "idle"
+            ]
+        );
+        assert!(!scenes.contains("manual") && !scenes.contains(NOTIFICATION_SCENE));
+
+        // 没有可用场景时播放结束
+        assert!(scenes.set_enabled("idle", false));
+        assert!(!scenes.next_frame(&mut buffer));
+        assert_eq!(scenes.active(), None);
+        assert_eq!(scenes.len(), 2);
+    }
+
+    #[cfg(feature = "scripting")]
+    #[test]
+    fn test_script_source() {
+        let code = r#"
+            fn init() { this.count = 0; }
+            fn frame(t) {
+                this.count += 1;
+                show_face(false);
+                fill_screen("black");
+                fill_rect(0, 0, 10, 10, rgb(255, 0, 0));
+                if this.count == 1 { move_joint(0, 30, 100); }
+            }
+        "#;
+        let mut script = ScriptSource::from_code(code).unwrap();
+        let mut buffer = ImageBuffer::new();
+        assert!(script.next_frame(&mut buffer));
+        assert_eq!(script.last_error(), None);
+        assert_eq!(&buffer.as_data()[..3], &[255, 0, 0]);
+        // 第一帧先绘制了表情，脚本清屏后眼白位置为黑色
+        assert_eq!(
+            &buffer.as_data()[(100 * FRAME_WIDTH + 72) * 3..][..3],
+            &[0, 0, 0]
+        );
+
+        // 100ms 的补间在之后的 3 帧内完成
+        for _ in 0..3 {
+            script.next_frame(&mut buffer);
+        }
+        assert_eq!(script.joint_angles().unwrap().0[0], 30.0);
+
+        // 运行错误不会中断播放
+        let mut broken = ScriptSource::from_code(r#"fn frame(t) { joint(9, 0); }"#).unwrap();
+        assert!(broken.next_frame(&mut buffer));
+        assert!(broken.last_error().unwrap().contains("关节索引"));
+        assert!(ScriptSource::from_code("fn frame(t) {").is_err());
+    }
+
+    #[test]
+    fn test_state_machine() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+        use std::time::Duration;
+
+        let greeted = Rc::new(Cell::new(0));
+        let counter = greeted.clone();
+        let mut machine = StateMachine::new("idle")
+            .state(State::new("idle").source(ColorBars::new()))
+            .state(State::new("sleepy").parent("idle"))
+            .state(
+                State::new("greet")
+                    .source(Checkerboard::new(20))
+                    .timeout(Duration::from_millis(100))
+                    .on_enter(move || counter.set(counter.get() + 1)),
+            )
+            .state(State::new("off").terminal())
+            .transition("idle", Event::Touched, "greet")
+            .transition("idle", Event::custom("sleep"), "sleepy")
+            .transition("greet", Event::TimerElapsed, "idle")
+            .transition("idle", Event::Disconnected, "off");
+        assert!(machine.validate().is_ok());
+
+        let mut buffer = ImageBuffer::new();
+        let mut bars = ImageBuffer::new();
+        ColorBars::new().next_frame(&mut bars);
+        assert!(machine.next_frame(&mut buffer));
+        assert_eq!(machine.current(), Some("idle"));
+
+        // 没有帧源的子状态播放父状态的帧源，未处理的事件交给父状态
+        machine.send(Event::custom("sleep"));
+        buffer.clear(Color::Black);
+        assert!(machine.next_frame(&mut buffer));
+        assert_eq!(machine.current(), Some("sleepy"));
+        assert!(machine.is_in("idle"));
+        assert_eq!(buffer.as_data(), bars.as_data());
+
+        // 触摸后问候 100ms（4 帧），计时结束回到空闲
+        let events = machine.events();
+        events.push(Event::Touched);
+        let states: Vec<_> = (0..6)
+            .map(|_| {
+                machine.next_frame(&mut buffer);
+                machine.current().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(states, ["greet", "greet", "greet", "greet", "idle", "idle"]);
+        assert_eq!(greeted.get(), 1);
+
+        // 进入终止状态后播放结束
+        events.push(Event::Disconnected);
+        assert!(!machine.next_frame(&mut buffer));
+        assert_eq!(machine.current(), Some("off"));
+
+        let broken = StateMachine::new("idle").transition("idle", Event::Touched, "missing");
+        assert!(broken.validate().is_err());
+    }
+
+    #[test]
+    fn test_servo_source() {
+        use std::time::Duration;
+
+        let mut buffer = ImageBuffer::new();
+        let mut servo = ServoSource::new().max_speed(Some(30.0));
+        servo.set_target(JOINT_HEAD, 90.0);
+        assert_eq!(
+            servo.target().0[JOINT_HEAD],
+            DEFAULT_JOINT_LIMITS[JOINT_HEAD].1
+        );
+
+        // 每帧 1/30 秒，最多转动 1 度
+        servo.next_frame(&mut buffer);
+        assert!((servo.joint_angles().unwrap().0[JOINT_HEAD] - 1.0).abs() < 1e-4);
+        servo.advance(Duration::from_secs(1));
+        assert_eq!(servo.angles().0[JOINT_HEAD], 15.0);
+
+        servo.jog(JOINT_BODY, -10.0);
+        servo.jog(JOINT_BODY, -5.0);
+        assert_eq!(servo.target().0[JOINT_BODY], -15.0);
+        servo.set_enabled(false);
+        assert!(servo.joint_angles().is_none());
+    }
+
+    #[cfg(feature = "gamepad")]
+    #[test]
+    fn test_gamepad_mapping() {
+        use modules::gamepad::{Axis, Button};
+        use std::time::Duration;
+
+        let binding = AxisBinding::position(JOINT_HEAD)
+            .deadzone(0.2)
+            .curve(ResponseCurve::Quadratic);
+        assert_eq!(binding.response(0.15), 0.0);
+        assert!((binding.response(-0.6) + 0.25).abs() < 1e-6);
+        assert_eq!(binding.inverted().response(1.0), -1.0);
+
+        let mapping = GamepadMapping::new()
+            .axis(Axis::LeftStickY, binding)
+            .axis(Axis::RightStickY, AxisBinding::rate(2, 40.0))
+            .button(Button::South, ButtonAction::Expression(Expression::HAPPY));
+        let mut servo = ServoSource::new().max_speed(None);
+        let stick = |axis| match axis {
+            Axis::LeftStickY => 1.0,
+            Axis::RightStickY => 0.6,
+            _ => 0.0,
+        };
+        for _ in 0..4 {
+            mapping.apply_axes(&mut servo, stick, Duration::from_millis(250));
+        }
+        assert_eq!(servo.target().0[JOINT_HEAD], 15.0);
+        // 死区 0.1：0.6 对应 40 * 0.5 / 0.9 度每秒
+        assert!((servo.target().0[2] - 40.0 * 0.5 / 0.9).abs() < 1e-3);
+
+        assert!(mapping.press(&mut servo, Button::South));
+        assert_eq!(servo.face_mut().target(), Expression::HAPPY);
+        assert!(!mapping.press(&mut servo, Button::East));
+    }
+
+    #[cfg(feature = "keyboard")]
+    #[test]
+    fn test_keyboard_jog() {
+        use modules::keyboard::KeyCode;
+
+        let mut jog = KeyboardJog::new();
+        let mut servo = ServoSource::new();
+        assert_eq!(
+            jog.handle(&mut servo, KeyCode::Char('w')),
+            JogResult::Handled
+        );
+        assert_eq!(
+            jog.handle(&mut servo, KeyCode::Char('+')),
+            JogResult::Handled
+        );
+        assert_eq!(jog.step(), 10.0);
+        jog.handle(&mut servo, KeyCode::Left);
+        assert_eq!(servo.target().0[JOINT_HEAD], 5.0);
+        assert_eq!((servo.target().0[1], servo.target().0[3]), (10.0, -10.0));
+
+        // 超出角度范围的命令被限制
+        for _ in 0..5 {
+            jog.handle(&mut servo, KeyCode::Char('w'));
+        }
+        assert_eq!(servo.target().0[JOINT_HEAD], 15.0);
+
+        jog.handle(&mut servo, KeyCode::Char('0'));
+        assert_eq!(servo.target(), &JointAngles::new());
+        assert_eq!(jog.handle(&mut servo, KeyCode::Tab), JogResult::Ignored);
+        assert_eq!(jog.handle(&mut servo, KeyCode::Esc), JogResult::Quit);
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_http_server() {
+        use std::io::{Read, Write};
+
+        let server = HttpServer::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        let request = move |head: &str, body: &[u8]| {
+            let mut stream = std::net::TcpStream::connect(addr).unwrap();
+            write!(stream, "{}\r\nContent-Length: {}\r\n\r\n", head, body.len()).unwrap();
+            stream.write_all(body).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let mut png = Vec::new();
+        image::RgbImage::from_pixel(8, 8, image::Rgb([255, 0, 0]))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let mut multipart = b"--xyz\r\nContent-Disposition: form-data; name=\"file\"; \
+            filename=\"red.png\"\r\nContent-Type: image/png\r\n\r\n"
+            .to_vec();
+        multipart.extend_from_slice(&png);
+        multipart.extend_from_slice(b"\r\n--xyz--\r\n");
+
+        let client = std::thread::spawn(move || {
+            [
+                request("GET /status HTTP/1.1", b""),
+                request(
+                    "POST /image HTTP/1.1\r\nContent-Type: multipart/form-data; boundary=xyz",
+                    &multipart,
+                ),
+                request("POST /joints HTTP/1.1", b"[1, 2, 3]"),
+                request("POST /gesture/dance HTTP/1.1", b""),
+                request("DELETE /status HTTP/1.1", b""),
+            ]
+        });
+        let mut bot = ElectronBot::new();
+        for _ in 0..5 {
+            server.handle_next(&mut bot).unwrap();
+        }
+        let responses = client.join().unwrap();
+
+        assert!(responses[0].starts_with("HTTP/1.1 200"));
+        assert!(responses[0].contains("\"connected\":false"));
+        // 未连接时图片已经解码到缓冲区，但同步失败
+        assert!(responses[1].starts_with("HTTP/1.1 503"));
+        assert_eq!(
+            bot.image_buffer().get_pixel(120, 120),
+            Some(Color::Custom(255, 0, 0))
+        );
+        assert!(responses[2].starts_with("HTTP/1.1 400"));
+        assert!(responses[2].contains("需要 6 个角度"));
+        assert!(responses[3].starts_with("HTTP/1.1 404"));
+        assert!(responses[4].starts_with("HTTP/1.1 405"));
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_http_server_limits_and_token() {
+        use std::io::{Read, Write};
+        use std::time::Duration;
+
+        let server = HttpServer::bind("127.0.0.1:0")
+            .unwrap()
+            .timeout(Duration::from_millis(500))
+            .token("secret");
+        let addr = server.local_addr().unwrap();
+        let request = move |head: String| {
+            let mut stream = std::net::TcpStream::connect(addr).unwrap();
+            // 服务器可能在读完请求之前就回复并关闭连接，写入失败可以忽略
+            let _ = stream.write_all(head.as_bytes());
+            let mut response = String::new();
+            let _ = stream.read_to_string(&mut response);
+            response
+        };
+        std::thread::spawn(move || {
+            let mut bot = ElectronBot::new();
+            server.serve(&mut bot)
+        });
+
+        // 只发送一半请求行的慢速客户端不会阻塞其他客户端，超时后收到 408
+        let mut slow = std::net::TcpStream::connect(addr).unwrap();
+        slow.write_all(b"GET /sta").unwrap();
+        let started = std::time::Instant::now();
+        let response = request("GET /status HTTP/1.1\r\n\r\n".to_string());
+        assert!(response.starts_with("HTTP/1.1 401"));
+        assert!(response.contains("WWW-Authenticate: Bearer"));
+        assert!(started.elapsed() < Duration::from_millis(400));
+        let mut response = String::new();
+        slow.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 408"));
+
+        let response =
+            request("GET /status HTTP/1.1\r\nAuthorization: Bearer wrong\r\n\r\n".to_string());
+        assert!(response.starts_with("HTTP/1.1 401"));
+        let response =
+            request("GET /status HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n".to_string());
+        assert!(response.starts_with("HTTP/1.1 200"));
+
+        // 过长的请求行和请求头
+        let response = request(format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(10_000)));
+        assert!(response.starts_with("HTTP/1.1 400"));
+        let response = request(format!(
+            "GET /status HTTP/1.1\r\nX-Long: {}\r\n\r\n",
+            "a".repeat(10_000)
+        ));
+        assert!(response.starts_with("HTTP/1.1 431"));
+        let headers = "X-Header: value\r\n".repeat(5_000);
+        let response = request(format!("GET /status HTTP/1.1\r\n{}\r\n", headers));
+        assert!(response.starts_with("HTTP/1.1 431"));
+    }
+
+    #[test]
+    fn test_gesture() {
+        use std::time::Duration;
+
+        let mut buffer = ImageBuffer::new();
+        let mut nod = Gesture::named("nod").unwrap();
+        assert_eq!(nod.duration(), Duration::from_millis(1000));
+        assert!((nod.pose_at(Duration::from_millis(250)).0[JOINT_HEAD] + 12.0).abs() < 1e-4);
+        let frames = std::iter::from_fn(|| nod.next_frame(&mut buffer).then_some(())).count();
+        assert_eq!(frames, 31);
+        assert_eq!(nod.joint_angles(), Some(JointAngles::new()));
+        assert!(nod.is_finished());
+        assert!(GESTURE_NAMES.iter().all(|n| Gesture::named(n).is_some()));
+        assert!(Gesture::named("dance").is_none());
+    }
+
+    #[cfg(feature = "mqtt")]
+    #[test]
+    fn test_mqtt_bridge() {
+        use std::io::{Read, Write};
+
+        fn read_packet(stream: &mut std::net::TcpStream) -> Option<(u8, Vec<u8>)> {
+            let mut byte = [0u8; 1];
+            stream.read_exact(&mut byte).ok()?;
+            let header = byte[0];
+            let (mut len, mut shift) = (0usize, 0);
+            loop {
+                stream.read_exact(&mut byte).ok()?;
+                len |= ((byte[0] & 0x7F) as usize) << shift;
+                shift += 7;
+                if byte[0] & 0x80 == 0 {
+                    break;
+                }
+            }
+            let mut body = vec![0u8; len];
+            stream.read_exact(&mut body).ok()?;
+            Some((header, body))
+        }
+        fn publish(topic: &str, payload: &str) -> Vec<u8> {
+            let mut packet = vec![0x30, (topic.len() + payload.len() + 2) as u8, 0];
+            packet.push(topic.len() as u8);
+            packet.extend_from_slice(topic.as_bytes());
+            packet.extend_from_slice(payload.as_bytes());
+            packet
+        }
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let broker = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let (header, connect) = read_packet(&mut stream).unwrap();
+            assert_eq!(header, 0x10);
+            stream.write_all(&[0x20, 2, 0, 0]).unwrap();
+            let (header, subscribe) = read_packet(&mut stream).unwrap();
+            assert_eq!(header, 0x82);
+            stream.write_all(&[0x90, 3, 0, 1, 0]).unwrap();
+            let mut commands = publish("electronbot/bot_1/expression/set", "happy");
+            commands.extend(publish("electronbot/bot_1/pose/set", "[1, 2]"));
+            stream.write_all(&commands).unwrap();
+
+            let mut published = Vec::new();
+            while let Some((header, body)) = read_packet(&mut stream) {
+                if header & 0xF0 == 0x30 {
+                    let len = u16::from_be_bytes([body[0], body[1]]) as usize;
+                    let topic = String::from_utf8_lossy(&body[2..2 + len]).into_owned();
+                    let payload = String::from_utf8_lossy(&body[2 + len..]).into_owned();
+                    published.push((topic, payload, header & 1 == 1));
+                }
+            }
+            (connect, subscribe, published)
+        });
+
+        let options = MqttOptions::new("127.0.0.1", port).client_id("bot 1");
+        let mut bridge = MqttBridge::connect(options).unwrap();
+        assert_eq!(bridge.topic_prefix(), "electronbot/bot_1");
+        let mut bot = ElectronBot::new();
+        // 未连接时同步失败，命令不计入，但表情已经绘制到缓冲区
+        assert_eq!(bridge.poll(&mut bot).unwrap(), 0);
+        assert!(bot.image_buffer().as_data().iter().any(|&v| v > 0));
+        drop(bridge);
+
+        let (connect, subscribe, published) = broker.join().unwrap();
+        // 遗嘱消息：<prefix>/availability = offline
+        let connect = String::from_utf8_lossy(&connect);
+        assert!(connect.contains("electronbot/bot_1/availability"));
+        assert!(connect.ends_with("offline"));
+        assert!(String::from_utf8_lossy(&subscribe).contains("electronbot/bot_1/+/set"));
+
+        let find = |topic: &str| published.iter().find(|(t, _, _)| t == topic);
+        let (_, config, retain) = find("homeassistant/select/bot_1/expression/config").unwrap();
+        assert!(retain);
+        assert!(config.contains("\"command_topic\":\"electronbot/bot_1/expression/set\""));
+        assert!(config.contains("\"happy\""));
+        assert!(config.contains("\"identifiers\":[\"bot_1\"]"));
+        assert!(find("homeassistant/binary_sensor/bot_1/connected/config").is_some());
+        let states: Vec<_> = published
+            .iter()
+            .filter(|(t, _, _)| t == "electronbot/bot_1/availability")
+            .map(|(_, p, _)| p.as_str())
+            .collect();
+        assert_eq!(states, ["online", "offline"]);
+        let (_, state, _) = find("electronbot/bot_1/state").unwrap();
+        assert!(state.contains("\"connected\":false"));
+    }
+
+    #[cfg(feature = "mqtt")]
+    #[test]
+    fn test_mqtt_discovery_escaping() {
+        use std::io::{Read, Write};
+
+        fn read_packet(stream: &mut std::net::TcpStream) -> Option<(u8, Vec<u8>)> {
+            let mut byte = [0u8; 1];
+            stream.read_exact(&mut byte).ok()?;
+            let header = byte[0];
+            let (mut len, mut shift) = (0usize, 0);
+            loop {
+                stream.read_exact(&mut byte).ok()?;
+                len |= ((byte[0] & 0x7F) as usize) << shift;
+                shift += 7;
+                if byte[0] & 0x80 == 0 {
+                    break;
+                }
+            }
+            let mut body = vec![0u8; len];
+            stream.read_exact(&mut body).ok()?;
+            Some((header, body))
+        }
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let broker = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            read_packet(&mut stream).unwrap();
+            stream.write_all(&[0x20, 2, 0, 0]).unwrap();
+            read_packet(&mut stream).unwrap();
+            stream.write_all(&[0x90, 3, 0, 1, 0]).unwrap();
+            let mut configs = Vec::new();
+            while let Some((header, body)) = read_packet(&mut stream) {
+                if header & 0xF0 != 0x30 {
+                    continue;
+                }
+                let len = u16::from_be_bytes([body[0], body[1]]) as usize;
+                let topic = String::from_utf8_lossy(&body[2..2 + len]).into_owned();
+                if topic.ends_with("/config") {
+                    configs.push(String::from_utf8_lossy(&body[2 + len..]).into_owned());
+                }
+            }
+            configs
+        });
+
+        // 主题前缀和设备名称中的引号和反斜杠不能破坏 JSON
+        let options = MqttOptions::new("127.0.0.1", port)
+            .client_id("bot")
+            .topic_prefix("home/\"living\"\\room")
+            .device_name("客厅 \"Bot\" \\ 1");
+        drop(MqttBridge::connect(options).unwrap());
+        let configs = broker.join().unwrap();
+        assert!(!configs.is_empty());
+        for config in &configs {
+            let root = modules::json::parse(config).unwrap();
+            let availability = root.get("availability_topic").unwrap().as_str().unwrap();
+            assert_eq!(availability, "home/\"living\"\\room/availability");
+            let device = root.get("device").unwrap();
+            assert_eq!(
+                device.get("name").unwrap().as_str(),
+                Some("客厅 \"Bot\" \\ 1")
+            );
+        }
+    }
+
+    /// 记录发送的包，接收时返回 32 字节请求的模拟设备。
+    #[derive(Default)]
+    struct RecordingTransport {
+        sent: std::sync::Arc<std::sync::Mutex<Vec<Vec<u8>>>>,
+        receives: usize,
+    }
+
+    impl Transport for RecordingTransport {
+        fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+            self.sent.lock().unwrap().push(data.to_vec());
+            Ok(true)
+        }
+
+        fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+            self.receives += 1;
+            let len = data.len().min(32);
+            data[..len].fill(0);
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn test_connect_transport() {
+        let transport = RecordingTransport::default();
+        let sent = transport.sent.clone();
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(transport)).unwrap();
+        assert!(bot.is_connected());
+        bot.set_image_color(Color::Red);
+        bot.set_joint_angles(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], true)
+            .unwrap();
+        assert_eq!(bot.get_joint_angles().0, [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert!(bot.received_extra_data().is_none());
+        assert!(bot.sync().unwrap());
+
+        // 同步后返回模拟设备上报的角度（全为 0），目标角度不变
+        assert_eq!(bot.get_joint_angles(), JointAngles::new());
+        assert_eq!(bot.target_joint_angles().0, [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(bot.received_extra_data(), Some(&[0u8; 32]));
+
+        // 4 个周期，每个周期 84 个整包加一个尾包
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 4 * 85);
+        assert!(sent[..84].iter().all(|p| p.len() == PACKET_SIZE));
+        let tail = &sent[84];
+        assert_eq!(tail.len(), TAIL_SIZE);
+        assert_eq!(&tail[192..], bot.extra_data().as_data());
+        bot.disconnect();
+        assert!(bot.sync().is_err());
+    }
+
+    #[cfg(feature = "bridge")]
+    #[test]
+    fn test_bridge_transport() {
+        let server = BridgeServer::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        let daemon = std::thread::spawn(move || {
+            let mut transport = RecordingTransport::default();
+            server.handle_next(&mut transport).unwrap();
+            let sent = transport.sent.lock().unwrap().clone();
+            (sent, transport.receives)
+        });
+
+        let mut bot = ElectronBot::new();
+        bot.connect_remote(addr).unwrap();
+        bot.set_image_color(Color::Blue);
+        assert!(bot.sync().unwrap());
+        let extra = bot.extra_data().as_data().to_vec();
+        bot.disconnect();
+
+        let (sent, receives) = daemon.join().unwrap();
+        assert_eq!(receives, 4);
+        assert_eq!(sent.len(), 4 * 85);
+        assert_eq!(&sent[84][192..], &extra[..]);
+        // 本地和远程发送的像素数据一致
+        let mut frame = ImageBuffer::new();
+        frame.clear(Color::Blue);
+        assert_eq!(&sent[0][..], &frame.as_data()[..PACKET_SIZE]);
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_ffi() {
+        use modules::ffi::*;
+        use std::ffi::CStr;
+
+        unsafe {
+            let bot = eb_new();
+            assert!(!eb_is_connected(bot));
+            let red = [255u8, 0, 0].repeat(4);
+            assert_eq!(eb_set_image_rgb(bot, red.as_ptr(), 2, 2), EbError::Ok);
+            assert_eq!(
+                (*bot).image_buffer().get_pixel(120, 120),
+                Some(Color::Custom(255, 0, 0))
+            );
+            assert_eq!(
+                eb_set_image_rgba(bot, red.as_ptr(), 0, 1),
+                EbError::InvalidArgument
+            );
+
+            let angles = [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0];
+            assert_eq!(eb_set_joints(bot, angles.as_ptr(), true), EbError::Ok);
+            let mut out = [0.0f32; 6];
+            assert_eq!(eb_get_joints(bot, out.as_mut_ptr()), EbError::Ok);
+            assert_eq!(out, angles);
+
+            assert_eq!(eb_sync(bot), EbError::NotConnected);
+            let message = CStr::from_ptr(eb_last_error()).to_str().unwrap();
+            assert!(message.contains("未连接"));
+            assert_eq!(eb_sync(std::ptr::null_mut()), EbError::NullPointer);
+            assert_eq!(
+                CStr::from_ptr(eb_version()).to_str().unwrap(),
+                env!("CARGO_PKG_VERSION")
+            );
+            eb_free(bot);
+        }
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_ffi_set_image_pixels() {
+        use modules::ffi::*;
+
+        // 2x2 BGRA，行距 12 字节；数据第一行（图片底部）为蓝色，第二行（顶部）为红色
+        let mut data = [0u8; 12 * 2];
+        for x in 0..2 {
+            data[x * 4..x * 4 + 4].copy_from_slice(&[255, 0, 0, 255]);
+            data[12 + x * 4..12 + x * 4 + 4].copy_from_slice(&[0, 0, 255, 255]);
+        }
+        unsafe {
+            let bot = eb_new();
+            let set = |stride, flip| {
+                eb_set_image_pixels(
+                    bot,
+                    data.as_ptr(),
+                    2,
+                    2,
+                    stride,
+                    EbPixelFormat::Bgra32 as u32,
+                    flip,
+                )
+            };
+            assert_eq!(set(12, true), EbError::Ok);
+            let buffer = (*bot).image_buffer();
+            assert_eq!(buffer.get_pixel(120, 10), Some(Color::Custom(255, 0, 0)));
+            assert_eq!(buffer.get_pixel(120, 230), Some(Color::Custom(0, 0, 255)));
+            assert_eq!(set(12, false), EbError::Ok);
+            let buffer = (*bot).image_buffer();
+            assert_eq!(buffer.get_pixel(120, 10), Some(Color::Custom(0, 0, 255)));
+            assert_eq!(set(4, false), EbError::InvalidArgument);
+
+            // 未知的枚举值和溢出的尺寸不会读取数据
+            assert_eq!(
+                eb_set_image_pixels(bot, data.as_ptr(), 2, 2, 0, 99, false),
+                EbError::InvalidArgument
+            );
+            assert_eq!(eb_set_fit_mode(bot, 4), EbError::InvalidArgument);
+            assert_eq!(eb_set_fit_mode(bot, EbFitMode::Contain as u32), EbError::Ok);
+            assert_eq!(
+                eb_set_image_pixels(bot, data.as_ptr(), u32::MAX, u32::MAX, u32::MAX, 1, false),
+                EbError::InvalidArgument
+            );
+            assert_eq!(
+                eb_set_image_rgba(bot, data.as_ptr(), u32::MAX, u32::MAX),
+                EbError::InvalidArgument
+            );
+            eb_free(bot);
+        }
+    }
+
+    #[cfg(feature = "panel")]
+    #[test]
+    fn test_control_panel() {
+        use std::time::Duration;
+
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(FakeMcu::new())).unwrap();
+        let mut panel = ControlPanel::new(bot.into_handle().unwrap());
+
+        // 滑块限制在关节范围内，未知关节被忽略
+        panel.set_joint(JOINT_HEAD, 40.0);
+        panel.set_joint(2, -20.0);
+        panel.set_joint(6, 10.0);
+        assert_eq!(panel.joints(), [15.0, 0.0, -20.0, 0.0, 0.0, 0.0]);
+        assert!(panel.set_expression("happy").is_ok());
+        assert!(panel.set_expression("bored").is_err());
+        assert_eq!(panel.expression(), "happy");
+
+        // 每次前进把画面和关节交给同步线程
+        panel.advance(Duration::from_millis(300));
+        let handle = panel.handle().clone();
+        handle.flush(Duration::from_secs(5)).unwrap();
+        let status = panel.status();
+        assert!(status.connected);
+        assert!(status.frames >= 1);
+        assert_eq!(status.error, None);
+
+        // 时间轴：播放到一半、拖动、播放结束后停在结尾
+        let gesture = Gesture::new("lift", JointAngles::new())
+            .then([0.0, 0.0, 40.0, 0.0, 0.0, 0.0], Duration::from_millis(400));
+        panel.load_timeline(gesture);
+        assert_eq!(panel.joints(), [0.0; 6]);
+        assert!(!panel.is_playing());
+        panel.play();
+        panel.advance(Duration::from_millis(200));
+        assert_eq!(panel.timeline_position(), Some(Duration::from_millis(200)));
+        assert!((panel.joints()[2] - 20.0).abs() < 0.01);
+        panel.seek(Duration::from_secs(10));
+        assert_eq!(panel.timeline_position(), Some(Duration::from_millis(400)));
+        assert_eq!(panel.joints()[2], 40.0);
+        panel.advance(Duration::from_millis(100));
+        assert!(!panel.is_playing());
+
+        // 循环播放回到开头，手动调整关节暂停播放
+        panel.set_looping(true);
+        panel.play();
+        panel.advance(Duration::from_millis(500));
+        assert_eq!(panel.timeline_position(), Some(Duration::from_millis(100)));
+        assert!(panel.is_playing());
+        panel.set_joint(JOINT_BODY, 5.0);
+        assert!(!panel.is_playing());
+
+        // 面板发送的就是预览画面和关节
+        panel.set_torque(false);
+        panel.advance(Duration::ZERO);
+        handle.flush(Duration::from_secs(5)).unwrap();
+        let mut bot = handle.stop().unwrap();
+        assert_eq!(bot.image_buffer().as_data(), panel.preview().as_data());
+        assert_eq!(bot.target_joint_angles().0, panel.joints());
+        assert!(!bot.extra_data().is_enabled());
+    }
+
+    #[test]
+    fn test_gesture_json() {
+        use std::time::Duration;
+
+        let wave = Gesture::named("wave").unwrap();
+        let parsed = Gesture::from_json(&wave.to_json()).unwrap();
+        assert_eq!(parsed, wave);
+        assert_eq!(parsed.len(), 5);
+
+        let text =
+            r#"{"keyframes": [{"pose": [0, 0, 30.5, 0, -1e1, 0], "ms": 250}], "note": "你😀"}"#;
+        let gesture = Gesture::from_json(text).unwrap();
+        assert_eq!(gesture.name(), "");
+        assert_eq!(gesture.duration(), Duration::from_millis(250));
+        assert_eq!(
+            gesture.pose_at(Duration::from_secs(1)),
+            JointAngles([0.0, 0.0, 30.5, 0.0, -10.0, 0.0])
+        );
+
+        let named =
+            Gesture::from_json(r#"{"name": "\u4f60\ud83d\ude00\"", "keyframes": []}"#).unwrap();
+        assert_eq!(named.name(), "你😀\"");
+        assert_eq!(Gesture::from_json(&named.to_json()).unwrap(), named);
+
+        assert!(Gesture::from_json("{}").is_err());
+        assert!(Gesture::from_json(r#"{"keyframes": [{"pose": [1, 2], "ms": 10}]}"#).is_err());
+        assert!(
+            Gesture::from_json(r#"{"keyframes": [{"pose": [0,0,0,0,0,0], "ms": -1}]}"#).is_err()
+        );
+        assert!(Gesture::from_json(r#"{"keyframes": []} x"#).is_err());
+        assert!(Gesture::from_json(&"[".repeat(100)).is_err());
+
+        assert_eq!(JointAngles::named("rest"), Some(JointAngles::new()));
+        assert!(POSE_NAMES.iter().all(|n| JointAngles::named(n).is_some()));
+        assert!(JointAngles::named("dance").is_none());
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_session_recorder() {
+        let dir = std::env::temp_dir().join(format!("electronbot-rec-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(RecordingTransport::default()))
+            .unwrap();
+        bot.start_recording(&dir, RecorderOptions::new().every(2))
+            .unwrap();
+        assert!(bot.is_recording());
+
+        let red = image::RgbImage::from_pixel(8, 8, image::Rgb([255, 0, 0]));
+        bot.set_image_from_image(&image::DynamicImage::ImageRgb8(red));
+        bot.set_joint_angles(&[1.0, 0.0, 0.0, 0.0, 0.0, 0.0], true)
+            .unwrap();
+        for _ in 0..3 {
+            bot.sync().unwrap();
+        }
+        assert_eq!(bot.recorder().unwrap().frames(), 3);
+        assert_eq!(bot.recorder().unwrap().saved_frames(), 2);
+        bot.stop_recording().unwrap();
+        assert!(!bot.is_recording());
+        bot.sync().unwrap();
+
+        let log = std::fs::read_to_string(dir.join("session.log")).unwrap();
+        let lines: Vec<&str> = log.lines().skip(1).collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].ends_with("frame 1 frame_000001.png"));
+        assert!(lines[1].ends_with("joints 1.00 0.00 0.00 0.00 0.00 0.00 on"));
+        assert!(lines[2].ends_with("frame 2"));
+        assert!(lines[3].ends_with("frame 3 frame_000003.png"));
+        // 帧缓冲区为 BGR，保存为 RGB
+        let png = image::open(dir.join("frame_000003.png")).unwrap().to_rgb8();
+        assert_eq!(png.get_pixel(0, 0).0, [255, 0, 0]);
+        assert!(!dir.join("frame_000002.png").exists());
+
+        bot.start_recording(&dir, RecorderOptions::new().format(FrameFormat::Gif))
+            .unwrap();
+        bot.sync().unwrap();
+        bot.set_image_color(Color::Blue);
+        bot.sync().unwrap();
+        bot.stop_recording().unwrap();
+        let gif = std::fs::File::open(dir.join("session.gif")).unwrap();
+        let decoder = image::codecs::gif::GifDecoder::new(std::io::BufReader::new(gif)).unwrap();
+        assert_eq!(image::AnimationDecoder::into_frames(decoder).count(), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_capture_replay() {
+        /// 每次接收时上报的角度加 1
+        struct CountingMcu(f32);
+
+        impl Transport for CountingMcu {
+            fn transmit(&mut self, _data: &[u8]) -> Result<bool, String> {
+                Ok(true)
+            }
+
+            fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+                self.0 += 1.0;
+                let mut extra = ExtraData::new();
+                extra.set_joint_angles(&JointAngles([self.0; 6]), true);
+                data[..32].copy_from_slice(extra.as_data());
+                Ok(32)
+            }
+        }
+
+        let path =
+            std::env::temp_dir().join(format!("electronbot-capture-{}.bin", std::process::id()));
+        let mut bot = ElectronBot::new();
+        assert!(matches!(
+            bot.start_capture(&path),
+            Err(BotError::NotConnected)
+        ));
+        bot.connect_transport(Box::new(CountingMcu(0.0))).unwrap();
+        bot.start_capture(&path).unwrap();
+        bot.set_image_color(Color::Green);
+        bot.sync().unwrap();
+        bot.sync().unwrap();
+        assert_eq!(bot.get_joint_angles(), JointAngles([8.0; 6]));
+        bot.disconnect();
+
+        // 每个周期接收 1 个包、发送 85 个包
+        let records = load_capture(&path).unwrap();
+        assert_eq!(records.len(), 2 * 4 * 86);
+        assert_eq!(records[0].kind, CaptureKind::Receive);
+        assert_eq!(records[1].kind, CaptureKind::Transmit);
+        assert!(records.windows(2).all(|w| w[0].time <= w[1].time));
+
+        // 相同的画面回放：MCU 回复一致，发送的数据与抓包一致
+        let replay = ReplayTransport::load(&path).unwrap();
+        let stats = replay.stats();
+        bot.connect_transport(Box::new(replay)).unwrap();
+        bot.set_image_color(Color::Green);
+        bot.sync().unwrap();
+        bot.sync().unwrap();
+        assert_eq!(bot.get_joint_angles(), JointAngles([8.0; 6]));
+        let replayed = stats.lock().unwrap().clone();
+        assert_eq!(replayed.transmitted, 2 * 4 * 85);
+        assert_eq!(replayed.received, 8);
+        assert_eq!(replayed.mismatches, 0);
+
+        // 画面改变后从第一个包开始不一致
+        let replay = ReplayTransport::new(records);
+        let stats = replay.stats();
+        bot.connect_transport(Box::new(replay)).unwrap();
+        bot.set_image_color(Color::Red);
+        bot.sync().unwrap();
+        assert_eq!(stats.lock().unwrap().first_mismatch, Some(0));
+        bot.disconnect();
+
+        assert!(read_capture(&b"not a capture"[..]).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_render_ansi() {
+        let img = image::RgbImage::from_fn(240, 240, |_, y| {
+            if y < 120 {
+                image::Rgb([255, 0, 0])
+            } else {
+                image::Rgb([0, 0, 255])
+            }
+        });
+        let mut buffer = ImageBuffer::new();
+        buffer.load_from_image(&image::DynamicImage::ImageRgb8(img));
+
+        let preview = buffer.render_ansi(4);
+        let lines: Vec<&str> = preview.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for line in &lines {
+            assert_eq!(line.matches('▀').count(), 4);
+            assert!(line.ends_with("\x1b[0m"));
+        }
+        // 上半屏红色，下半屏蓝色
+        assert!(lines[0].starts_with("\x1b[38;2;255;0;0m\x1b[48;2;255;0;0m▀"));
+        assert!(lines[1].starts_with("\x1b[38;2;0;0;255m\x1b[48;2;0;0;255m▀"));
+
+        // 奇数列数时最后一行的下半格为黑色，宽度限制在屏幕尺寸内
+        let odd = buffer.render_ansi(3);
+        assert_eq!(odd.lines().count(), 2);
+        assert!(odd.lines().nth(1).unwrap().contains("\x1b[48;2;0;0;0m"));
+        assert_eq!(buffer.render_ansi(1000).lines().count(), 120);
+        assert_eq!(buffer.render_ansi(0).lines().count(), 1);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_screenshot() {
+        let dir = std::env::temp_dir().join(format!("eb_screenshot_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut bot = ElectronBot::new();
+        bot.set_image_from_image(&image::DynamicImage::ImageRgb8(
+            image::RgbImage::from_pixel(240, 240, image::Rgb([200, 100, 50])),
+        ));
+        let path = dir.join("shot.png");
+        bot.screenshot(&path).unwrap();
+
+        let saved = image::open(&path).unwrap().to_rgb8();
+        assert_eq!(saved.dimensions(), (240, 240));
+        assert_eq!(saved.get_pixel(10, 10).0, [200, 100, 50]);
+        assert_eq!(bot.image_buffer().to_rgb_image(), saved);
+
+        // 截图反映熄屏等发送时的处理
+        bot.display_off();
+        bot.screenshot(&path).unwrap();
+        assert_eq!(
+            image::open(&path).unwrap().to_rgb8().get_pixel(10, 10).0,
+            [0, 0, 0]
+        );
+
+        assert!(bot.screenshot(dir.join("shot.unknown")).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_frame_queue() {
+        let frame = |value: u8| {
+            let mut image = ImageBuffer::new();
+            image.as_mut_data().fill(value);
+            image
+        };
+
+        // 丢弃最旧的帧：只保留最新的两帧
+        let queue = FrameQueue::new(2, QueuePolicy::DropOldest);
+        for value in 1..=4 {
+            assert!(queue.push(frame(value)).unwrap());
+        }
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.try_pop().unwrap().image.as_data()[0], 3);
+        assert_eq!(queue.try_pop().unwrap().image.as_data()[0], 4);
+        assert!(queue.try_pop().is_none());
+        assert_eq!(
+            queue.stats(),
+            QueueStats {
+                pushed: 4,
+                popped: 2,
+                dropped: 2
+            }
+        );
+
+        // 丢弃新来的帧：保留最早排队的帧
+        let queue = FrameQueue::new(1, QueuePolicy::DropNewest);
+        assert!(queue.push(frame(1)).unwrap());
+        assert!(!queue.push(frame(2)).unwrap());
+        assert_eq!(queue.pop().unwrap().image.as_data()[0], 1);
+        assert_eq!(queue.stats().dropped, 1);
+        assert!(queue
+            .pop_timeout(std::time::Duration::from_millis(10))
+            .is_none());
+
+        // 阻塞：生产者等待同步线程取走帧，不丢帧
+        let queue = FrameQueue::new(1, QueuePolicy::Block);
+        let producer = queue.clone();
+        let handle = std::thread::spawn(move || {
+            for value in 1..=5 {
+                producer.push(frame(value)).unwrap();
+            }
+            producer.close();
+            assert!(producer.push(frame(0)).is_err());
+        });
+        let mut received = Vec::new();
+        while let Some(queued) = queue.pop() {
+            received.push(queued.image.as_data()[0]);
+        }
+        handle.join().unwrap();
+        assert_eq!(received, [1, 2, 3, 4, 5]);
+        assert_eq!(queue.stats().dropped, 0);
+
+        // 作为帧源交给 play：关节角度随帧发送，关闭后播放结束
+        let queue = FrameQueue::new(4, QueuePolicy::DropOldest);
+        queue
+            .push_with_joints(frame(9), JointAngles([0.0, 0.0, 10.0, 0.0, 0.0, 0.0]))
+            .unwrap();
+        queue.push(frame(7)).unwrap();
+        queue.close();
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(RecordingTransport::default()))
+            .unwrap();
+        let mut source = queue.clone();
+        let stats = bot.play(&mut source, None).unwrap();
+        assert_eq!(stats.frames, 2);
+        assert_eq!(bot.image_buffer().as_data()[0], 7);
+        assert_eq!(bot.target_joint_angles().0[2], 10.0);
+    }
+
+    #[test]
+    fn test_bot_handle() {
+        let transport = RecordingTransport::default();
+        let sent = transport.sent.clone();
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(transport)).unwrap();
+        let connect_packets = sent.lock().unwrap().len();
+        let handle = bot.into_handle().unwrap();
+        assert!(handle.is_connected());
+
+        // 两个线程分别更新画面和关节
+        let control = handle.clone();
+        std::thread::spawn(move || {
+            control.set_joint_angles(&[0.0, 0.0, 20.0, 0.0, 0.0, 0.0], true)
+        })
+        .join()
+        .unwrap();
+        handle.update_image(|buffer| buffer.as_mut_data().fill(0x42));
+        handle.flush(std::time::Duration::from_secs(5)).unwrap();
+        assert!(handle.frames() >= 1);
+        assert!(handle.take_error().is_none());
+        // 模拟设备上报的角度全为 0
+        assert_eq!(handle.joint_angles(), JointAngles::new());
+
+        // 没有新内容且没有保活时不同步
+        let frames = handle.frames();
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        assert_eq!(handle.frames(), frames);
+        handle.set_keepalive(Some(std::time::Duration::from_millis(5)));
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while handle.frames() < frames + 2 && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert!(handle.frames() >= frames + 2);
+        handle.set_keepalive(None);
+
+        let mut bot = handle.stop().unwrap();
+        assert!(handle.stop().is_none());
+        assert!(handle.flush(std::time::Duration::from_secs(1)).is_ok());
+        assert_eq!(bot.target_joint_angles().0[2], 20.0);
+        assert!(bot.image_buffer().as_data().iter().all(|&b| b == 0x42));
+        // 每次同步 85 个包，最后一次发送的尾包包含设置的关节
+        let sent = sent.lock().unwrap();
+        assert_eq!((sent.len() - connect_packets) % 85, 0);
+        assert_eq!(&sent.last().unwrap()[192..], bot.extra_data().as_data());
+
+        // 停止后的修改被忽略
+        handle.update_image(|buffer| buffer.as_mut_data().fill(0));
+        assert!(handle.flush(std::time::Duration::from_millis(10)).is_ok());
+    }
+
+    #[test]
+    fn test_bot_handle_flush_error() {
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(FakeMcu::new().fail_transmit(0, Fault::Disconnect)))
+            .unwrap();
+        let handle = bot.into_handle().unwrap();
+
+        // 发送失败时 flush 立即返回该次同步的错误，而不是等到超时
+        handle.update_image(|buffer| buffer.as_mut_data().fill(0x42));
+        let started = std::time::Instant::now();
+        let error = handle
+            .flush(std::time::Duration::from_secs(30))
+            .unwrap_err();
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+        assert_eq!(Some(error), handle.take_error());
+        assert_eq!(handle.frames(), 0);
+        handle.stop();
+    }
+
+    #[test]
+    fn test_bot_handle_rejected_target() {
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(RecordingTransport::default()))
+            .unwrap();
+        bot.set_collision_guard(Some(CollisionGuard::new()));
+        let handle = bot.into_handle().unwrap();
+
+        // 会碰撞的目标被拒绝，错误通过 take_error 报告
+        handle.set_joint_angles(&[0.0, -25.0, 0.0, 0.0, 0.0, 0.0], true);
+        handle.flush(std::time::Duration::from_secs(5)).unwrap();
+        let error = handle.take_error().unwrap();
+        assert!(error.contains("左臂会碰到身体"), "{}", error);
+        assert!(handle.take_error().is_none());
+
+        // 合法目标正常生效
+        handle.set_joint_angles(&[0.0, 0.0, 20.0, 0.0, 0.0, 0.0], true);
+        handle.flush(std::time::Duration::from_secs(5)).unwrap();
+        assert!(handle.take_error().is_none());
+        let bot = handle.stop().unwrap();
+        assert_eq!(bot.target_joint_angles().0[2], 20.0);
+    }
+
+    #[test]
+    fn test_cancellation() {
+        /// 发送若干个包后请求取消的模拟设备。
+        struct CancelAfter {
+            inner: RecordingTransport,
+            token: CancellationToken,
+            after: usize,
+        }
+
+        impl Transport for CancelAfter {
+            fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+                let sent = self.inner.sent.lock().unwrap().len();
+                if sent + 1 == self.after {
+                    self.token.cancel();
+                }
+                self.inner.transmit(data)
+            }
+
+            fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+                self.inner.receive(data)
+            }
+        }
+
+        let mut bot = ElectronBot::new();
+        let token = bot.cancellation_token();
+        let inner = RecordingTransport::default();
+        let sent = inner.sent.clone();
+        bot.connect_transport(Box::new(CancelAfter {
+            inner,
+            token: token.clone(),
+            after: 10,
+        }))
+        .unwrap();
+
+        // 第 10 个包发出后取消，同步在下一个包之前中止
+        assert!(matches!(bot.sync(), Err(BotError::Cancelled)));
+        assert_eq!(sent.lock().unwrap().len(), 10);
+
+        // 取消后同步和连接都立即失败
+        assert!(matches!(bot.sync(), Err(BotError::Cancelled)));
+        assert_eq!(sent.lock().unwrap().len(), 10);
+        assert!(matches!(bot.connect(), Err(BotError::Cancelled)));
+
+        // 恢复后正常同步
+        token.reset();
+        bot.connect_transport(Box::new(RecordingTransport::default()))
+            .unwrap();
+        assert!(bot.sync().unwrap());
+
+        // 共享的取消标志
+        let shared = CancellationToken::new();
+        bot.set_cancellation_token(shared.clone());
+        shared.cancel();
+        assert!(matches!(bot.sync(), Err(BotError::Cancelled)));
+        assert!(!token.is_cancelled());
+    }
+
+    #[test]
+    fn test_sync_with_deadline() {
+        /// 每次发送耗时 1 毫秒，并记录设置的超时。
+        struct SlowTransport {
+            inner: RecordingTransport,
+            timeouts: std::sync::Arc<std::sync::Mutex<Vec<Option<std::time::Duration>>>>,
+        }
+
+        impl Transport for SlowTransport {
+            fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+                std::thread::sleep(std::time::Duration::from_millis(1));
+                self.inner.transmit(data)
+            }
+
+            fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+                self.inner.receive(data)
+            }
+
+            fn set_timeout(&mut self, timeout: Option<std::time::Duration>) {
+                self.timeouts.lock().unwrap().push(timeout);
+            }
+        }
+
+        let inner = RecordingTransport::default();
+        let sent = inner.sent.clone();
+        let timeouts = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(SlowTransport {
+            inner,
+            timeouts: timeouts.clone(),
+        }))
+        .unwrap();
+
+        // 340 个包至少需要 340 毫秒，20 毫秒内只能完成一部分
+        let started = std::time::Instant::now();
+        match bot.sync_with_deadline(std::time::Duration::from_millis(20)) {
+            Err(BotError::SyncTimeout(cycles, packets)) => {
+                assert_eq!(cycles, 0);
+                assert!(packets > 0 && packets < 85);
+                assert_eq!(packets, sent.lock().unwrap().len());
+            }
+            other => panic!("应该超时: {:?}", other),
+        }
+        assert!(started.elapsed() < std::time::Duration::from_millis(200));
+
+        // 每次收发的超时不超过剩余时间，结束后恢复默认值
+        {
+            let timeouts = timeouts.lock().unwrap();
+            assert!(timeouts[..timeouts.len() - 1]
+                .iter()
+                .all(|t| t.is_some_and(|t| t <= std::time::Duration::from_millis(20))));
+            assert_eq!(timeouts.last(), Some(&None));
+        }
+
+        // 时间足够时正常完成，不限时的同步不调整超时
+        sent.lock().unwrap().clear();
+        assert!(bot
+            .sync_with_deadline(std::time::Duration::from_secs(10))
+            .unwrap());
+        assert_eq!(sent.lock().unwrap().len(), 4 * 85);
+        timeouts.lock().unwrap().clear();
+        assert!(bot.sync().unwrap());
+        assert!(timeouts.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_frame_pacer() {
+        let mut pacer = FramePacer::new(100.0);
+        assert_eq!(pacer.interval(), std::time::Duration::from_millis(10));
+
+        // 每帧做 3 毫秒的工作，绝对时间表不会把工作时间累积成漂移
+        let start = std::time::Instant::now();
+        assert_eq!(pacer.wait(), std::time::Duration::ZERO);
+        for _ in 0..10 {
+            std::thread::sleep(std::time::Duration::from_millis(3));
+            pacer.wait();
+        }
+        let elapsed = start.elapsed();
+        assert!(elapsed >= std::time::Duration::from_millis(100));
+        assert!(elapsed < std::time::Duration::from_millis(200));
+
+        let stats = pacer.stats();
+        assert_eq!(stats.frames, 11);
+        assert!((stats.target_fps - 100.0).abs() < 0.01);
+        assert!(stats.actual_fps > 50.0 && stats.actual_fps <= 101.0);
+        assert_eq!(stats.device_fps, 0.0);
+
+        // 落后超过一帧时重新对齐，不连续补发
+        std::thread::sleep(std::time::Duration::from_millis(35));
+        pacer.wait();
+        assert_eq!(pacer.stats().missed, 1);
+        let before = std::time::Instant::now();
+        pacer.wait();
+        assert!(before.elapsed() >= std::time::Duration::from_millis(5));
+
+        // 同步耗时决定设备帧率
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(RecordingTransport::default()))
+            .unwrap();
+        pacer.reset();
+        assert!(pacer.sync(&mut bot).unwrap());
+        pacer.record_sync(std::time::Duration::from_millis(50));
+        let stats = pacer.stats();
+        assert!(stats.device_fps > 0.0);
+        assert!(stats.to_string().contains("帧率"));
+    }
+
+    #[test]
+    fn test_play_pipelined() {
+        // 每帧只改写一个字节，依赖上一帧的内容
+        struct Incremental(u8);
+        impl FrameSource for Incremental {
+            fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+                if self.0 == 5 {
+                    return false;
+                }
+                buffer.as_mut_data()[self.0 as usize] = self.0 + 1;
+                self.0 += 1;
+                true
+            }
+
+            fn joint_angles(&self) -> Option<JointAngles> {
+                Some(JointAngles([self.0 as f32; 6]))
+            }
+        }
+
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(RecordingTransport::default()))
+            .unwrap();
+        let stats = bot.play_pipelined(&mut Incremental(0), None).unwrap();
+        assert_eq!(
+            stats,
+            PlayStats {
+                frames: 5,
+                dropped: 0
+            }
+        );
+        assert_eq!(&bot.image_buffer().as_data()[..6], &[1, 2, 3, 4, 5, 0]);
+        assert_eq!(bot.target_joint_angles().0[0], 5.0);
+
+        let stats = bot.play_pipelined(&mut Incremental(0), Some(2)).unwrap();
+        assert_eq!(stats.frames, 2);
+
+        // 同步失败时返回错误，工作线程随之退出
+        let mut disconnected = ElectronBot::new();
+        assert!(disconnected
+            .play_pipelined(&mut Incremental(0), None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_partial_updates() {
+        assert_eq!(QuarterMask::from_rows(50, 20), QuarterMask(0b0011));
+        assert_eq!(QuarterMask::from_rows(0, 240), QuarterMask::ALL);
+        assert_eq!(QuarterMask::from_rows(239, 10), QuarterMask(0b1000));
+        assert!(QuarterMask::from_rows(240, 10).is_empty());
+        // 区域高度溢出时不会 panic
+        assert_eq!(QuarterMask::from_rows(200, usize::MAX), QuarterMask(0b1000));
+        assert!(QuarterMask::from_rows(usize::MAX, usize::MAX).is_empty());
+
+        let transport = RecordingTransport::default();
+        let sent = transport.sent.clone();
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(transport)).unwrap();
+        let packets = |sent: &std::sync::Arc<std::sync::Mutex<Vec<Vec<u8>>>>| {
+            let mut sent = sent.lock().unwrap();
+            std::mem::take(&mut *sent)
+        };
+
+        // 默认发送完整的一帧
+        bot.sync().unwrap();
+        assert_eq!(packets(&sent).len(), 4 * 85);
+        assert_eq!(bot.dirty_quarters(), QuarterMask::ALL);
+
+        // 开启后第一帧仍然完整发送，之后只发送变化的分段
+        bot.set_partial_updates(true);
+        bot.sync().unwrap();
+        let first = packets(&sent);
+        assert_eq!(first.len(), 4 * 85);
+        assert_eq!(first[84][223], 0x80);
+        assert_eq!(first[339][223], 0x83);
+
+        bot.image_buffer().as_mut_data()[130 * 240 * 3] = 200;
+        assert_eq!(bot.dirty_quarters(), QuarterMask(0b0100));
+        bot.sync().unwrap();
+        let partial = packets(&sent);
+        assert_eq!(partial.len(), 85);
+        assert_eq!(
+            partial[0][..],
+            bot.image_buffer().as_data()[2 * 43200..2 * 43200 + 512]
+        );
+        assert_eq!(partial[84][223], 0x82);
+
+        // 没有变化时只发送第一段，用于传递舵机角度
+        assert!(bot.dirty_quarters().is_empty());
+        bot.sync().unwrap();
+        assert_eq!(packets(&sent).len(), 85);
+
+        bot.mark_dirty(0, 100, 240, 100);
+        assert_eq!(bot.dirty_quarters(), QuarterMask(0b1110));
+        bot.mark_dirty(0, 100, usize::MAX, usize::MAX);
+        assert_eq!(bot.dirty_quarters(), QuarterMask(0b1110));
+        bot.sync().unwrap();
+        assert_eq!(packets(&sent).len(), 3 * 85);
+
+        bot.set_partial_updates(false);
+        bot.sync().unwrap();
+        assert_eq!(packets(&sent).len(), 4 * 85);
+    }
+
+    #[test]
+    fn test_frame_writer() {
+        // 直接写入的像素原样发送给 MCU
+        let mcu = FakeMcu::new();
+        let state = mcu.state();
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(mcu)).unwrap();
+        for (i, pixel) in bot.frame_writer().chunks_exact_mut(3).enumerate() {
+            pixel.copy_from_slice(&[i as u8, (i >> 8) as u8, 0x5a]);
+        }
+        bot.sync().unwrap();
+        let frame = state.lock().unwrap().frame.clone().unwrap();
+        assert_eq!(&frame[..], &bot.frame_writer()[..]);
+        assert_eq!(&frame[3 * 300..3 * 301], [44, 1, 0x5a]);
+
+        // 局部刷新按内容找出变化的分段，不需要 mark_dirty
+        let transport = RecordingTransport::default();
+        let sent = transport.sent.clone();
+        bot.connect_transport(Box::new(transport)).unwrap();
+        bot.set_partial_updates(true);
+        bot.sync().unwrap();
+        sent.lock().unwrap().clear();
+        bot.frame_writer()[200 * 240 * 3] = 0xff;
+        bot.sync().unwrap();
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 85);
+        assert_eq!(sent[0][..], bot.frame_writer()[3 * 43200..3 * 43200 + 512]);
+        assert_eq!(sent[84][223], 0x83);
+    }
+
+    #[test]
+    fn test_frame_stream() {
+        let mut buffer = ImageBuffer::new();
+        assert!(buffer.set_quarter(1, &[7u8; 43200]).is_ok());
+        assert_eq!(buffer.quarter(1).unwrap()[0], 7);
+        assert_eq!(buffer.as_data()[60 * 240 * 3 - 1], 0);
+        assert!(buffer.set_quarter(4, &[0u8; 43200]).is_err());
+        assert!(buffer.set_quarter(0, &[0u8; 100]).is_err());
+
+        let transport = RecordingTransport::default();
+        let sent = transport.sent.clone();
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(transport)).unwrap();
+        let count =
+            |sent: &std::sync::Arc<std::sync::Mutex<Vec<Vec<u8>>>>| sent.lock().unwrap().len();
+
+        // 段按顺序发送：第 1 段要等第 0 段设置后才发送
+        let mut stream = bot.stream_frame().unwrap();
+        assert_eq!(stream.set_quarter(1, &[1u8; 43200]).unwrap(), 0);
+        assert_eq!(count(&sent), 0);
+        assert_eq!(stream.set_quarter(0, &[2u8; 43200]).unwrap(), 2);
+        assert_eq!(count(&sent), 2 * 85);
+        assert_eq!(sent.lock().unwrap()[85], vec![1u8; 512]);
+        assert!(matches!(
+            stream.set_quarter(2, &[0u8; 10]),
+            Err(BotError::ImageError(_))
+        ));
+        assert!(stream.finish().unwrap());
+        assert_eq!(count(&sent), 4 * 85);
+        assert_eq!(bot.stats().frames_transmitted, 1);
+
+        // 丢弃时补齐剩余的段
+        let mut stream = bot.stream_frame().unwrap();
+        stream.set_quarter(0, &[3u8; 43200]).unwrap();
+        drop(stream);
+        assert_eq!(count(&sent), 8 * 85);
+
+        // 取消后本帧中止
+        let token = bot.cancellation_token();
+        let mut stream = bot.stream_frame().unwrap();
+        token.cancel();
+        assert!(matches!(
+            stream.set_quarter(0, &[0u8; 43200]),
+            Err(BotError::Cancelled)
+        ));
+        assert!(!stream.finish().unwrap());
+        assert_eq!(count(&sent), 8 * 85);
+        assert!(matches!(bot.stream_frame(), Err(BotError::Cancelled)));
+    }
+
+    #[test]
+    fn test_protocol_profile() {
+        let stock = ProtocolProfile::STOCK;
+        assert!(stock.validate().is_ok());
+        assert_eq!(stock.segment_size(), 43200);
+        assert_eq!(stock.tail_size(), TAIL_SIZE);
+        assert_eq!(stock.packets_per_frame(), 340);
+        assert_eq!(ProtocolProfile::for_panel(240, 240, 4, 512).unwrap(), stock);
+        assert!(ProtocolProfile::for_panel(240, 240, 7, 512).is_err());
+        assert!(ProtocolProfile {
+            packet_count: 83,
+            ..stock
+        }
+        .validate()
+        .is_err());
+
+        // 320×240 的固件分支：收发原始数据
+        let wide = ProtocolProfile::for_panel(320, 240, 4, 512).unwrap();
+        assert_eq!(wide.segment_rows(), 60);
+        assert_eq!(wide.segment_size() * 4, 320 * 240 * 3);
+        let mut transport = RecordingTransport::default();
+        let sent = transport.sent.clone();
+        let mut context = SyncContext::with_profile(wide);
+        let frame = vec![9u8; wide.frame_size()];
+        let extra = ExtraData::new();
+        assert!(
+            modules::sync::sync_frame(&mut transport, &frame, &extra, &mut context, &mut None)
+                .unwrap()
+        );
+        {
+            let sent = sent.lock().unwrap();
+            assert_eq!(sent.len(), wide.packets_per_frame());
+            assert_eq!(sent[wide.packet_count].len(), wide.tail_size());
+        }
+        assert!(modules::sync::sync_frame(
+            &mut transport,
+            &frame[..100],
+            &extra,
+            &mut context,
+            &mut None
+        )
+        .is_err());
+
+        // ElectronBot 只接受与图片缓冲区大小相同的布局
+        let mut bot = ElectronBot::new();
+        assert!(matches!(
+            bot.set_protocol_profile(wide),
+            Err(BotError::ConfigError(_))
+        ));
+        let eighths = ProtocolProfile::for_panel(240, 240, 8, 256).unwrap();
+        bot.set_protocol_profile(eighths).unwrap();
+        let transport = RecordingTransport::default();
+        let sent = transport.sent.clone();
+        bot.connect_transport(Box::new(transport)).unwrap();
+        assert_eq!(bot.protocol_profile(), eighths);
+        bot.sync().unwrap();
+        assert_eq!(sent.lock().unwrap().len(), eighths.packets_per_frame());
+        assert!(matches!(bot.stream_frame(), Err(BotError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_firmware_variants() {
+        let variants = modules::usb::parse_device_ids("1234:abcd=fork, 0x1001:0x8024").unwrap();
+        assert_eq!(variants[0], FirmwareVariant::new("fork", 0x1234, 0xabcd));
+        assert_eq!(variants[1].name, "custom");
+        assert_eq!(variants[1].pid, 0x8024);
+        assert!(modules::usb::parse_device_ids("1234").is_err());
+        assert!(modules::usb::parse_device_ids("xyz:1").is_err());
+        assert!(modules::usb::parse_device_ids("").unwrap().is_empty());
+
+        let known = modules::usb::known_variants();
+        assert_eq!(known[0], FirmwareVariant::stock());
+        assert_eq!(
+            modules::usb::find_variant(&known, USB_VID, USB_PID).map(|v| v.name.as_str()),
+            Some("stock")
+        );
+
+        let mut bot = ElectronBot::new();
+        bot.add_variant(FirmwareVariant::new("fork", 0x1234, 0xabcd));
+        bot.add_variant(FirmwareVariant::new("duplicate", 0x1234, 0xabcd));
+        assert_eq!(bot.variants().last().unwrap().name, "fork");
+        bot.connect_transport(Box::new(RecordingTransport::default()))
+            .unwrap();
+        assert!(bot.connected_variant().is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_udev_rule() {
+        let variants = [
+            FirmwareVariant::stock(),
+            FirmwareVariant::new("fork", 0x1234, 0xabcd),
+        ];
+        let rule = platform::linux::udev_rule(&variants);
+        let lines: Vec<&str> = rule.lines().filter(|l| !l.starts_with('#')).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            "SUBSYSTEM==\"usb\", ATTR{idVendor}==\"1001\", ATTR{idProduct}==\"8023\", \
+             MODE=\"0666\", GROUP=\"plugdev\""
+        );
+        assert!(lines[1].contains("ATTR{idVendor}==\"1234\", ATTR{idProduct}==\"abcd\""));
+
+        let written = platform::linux::install_udev_rule(&variants, |path, text| {
+            assert_eq!(path, std::path::Path::new(platform::linux::UDEV_RULE_PATH));
+            assert_eq!(text, rule);
+            false
+        });
+        assert_eq!(written, Ok(false));
+    }
+
+    #[test]
+    fn test_usb_access_errors() {
+        use modules::usb::{claim_error_on, open_error_on};
+
+        // macOS 上拒绝访问单独报告，并说明沙盒权限
+        let error = open_error_on(rusb::Error::Access, "macos");
+        assert!(
+            matches!(&error, BotError::MacOsAccess(message)
+                if message.contains("com.apple.security.device.usb")),
+            "{}",
+            error
+        );
+        assert!(matches!(
+            open_error_on(rusb::Error::Access, "linux"),
+            BotError::UsbError(message) if message.contains("udev --install")
+        ));
+        assert!(matches!(
+            open_error_on(rusb::Error::NoDevice, "macos"),
+            BotError::UsbError(_)
+        ));
+
+        // 接口被独占只在 macOS 上给出说明
+        let details = claim_error_on(0, rusb::Error::Busy, "macos").unwrap();
+        assert!(details.contains("接口 0 被其他进程独占"), "{}", details);
+        assert!(claim_error_on(0, rusb::Error::Access, "macos").is_some());
+        assert!(claim_error_on(0, rusb::Error::Io, "macos").is_none());
+        assert!(claim_error_on(0, rusb::Error::Busy, "linux").is_none());
+    }
+
+    #[test]
+    fn test_raw_transport_access() {
+        let mut bot = ElectronBot::new();
+        assert!(bot.transport_mut().is_none());
+        assert!(matches!(bot.send_raw(&[1]), Err(BotError::NotConnected)));
+
+        let transport = RecordingTransport::default();
+        let sent = transport.sent.clone();
+        bot.connect_transport(Box::new(transport)).unwrap();
+        bot.send_raw(&[0xA5, 1, 2]).unwrap();
+        assert_eq!(sent.lock().unwrap().last().unwrap(), &vec![0xA5, 1, 2]);
+        let mut reply = [0xFFu8; 32];
+        assert_eq!(
+            bot.recv_raw(&mut reply, Some(std::time::Duration::from_millis(10)))
+                .unwrap(),
+            32
+        );
+        assert_eq!(reply, [0u8; 32]);
+        assert!(bot.transport_mut().is_some());
+        // 不是 USB 连接
+        assert!(bot.usb_device_mut().is_none());
+    }
+
+    #[cfg(feature = "dfu")]
+    #[test]
+    fn test_dfu_flash() {
+        use modules::dfu::{parse_layout, DfuIo, FlashSector};
+
+        /// 模拟 STM32 bootloader 的 DfuSe 状态机
+        struct FakeBootloader {
+            flash: Vec<u8>,
+            pointer: u32,
+            state: u8,
+            erased: Vec<u32>,
+            left: bool,
+        }
+
+        impl DfuIo for FakeBootloader {
+            fn control_out(&mut self, request: u8, value: u16, data: &[u8]) -> Result<(), String> {
+                match (request, value) {
+                    // DNLOAD 块 0：DfuSe 命令
+                    (1, 0) => {
+                        let address = u32::from_le_bytes(data[1..5].try_into().unwrap());
+                        match data[0] {
+                            0x21 => self.pointer = address,
+                            0x41 => self.erased.push(address),
+                            _ => return Err("未知命令".to_string()),
+                        }
+                        self.state = 5;
+                    }
+                    (1, block) if data.is_empty() => {
+                        assert_eq!(block, 2);
+                        self.left = true;
+                        self.state = 8;
+                    }
+                    (1, block) => {
+                        let offset =
+                            (self.pointer - FLASH_BASE) as usize + (block as usize - 2) * 1024;
+                        self.flash[offset..offset + data.len()].copy_from_slice(data);
+                        self.state = 5;
+                    }
+                    (4, _) | (6, _) => self.state = 2,
+                    _ => return Err("未知请求".to_string()),
+                }
+                Ok(())
+            }
+
+            fn control_in(
+                &mut self,
+                request: u8,
+                value: u16,
+                buffer: &mut [u8],
+            ) -> Result<usize, String> {
+                match request {
+                    3 => {
+                        buffer[..6].copy_from_slice(&[0, 0, 0, 0, self.state, 0]);
+                        Ok(6)
+                    }
+                    2 => {
+                        let offset =
+                            (self.pointer - FLASH_BASE) as usize + (value as usize - 2) * 1024;
+                        buffer.copy_from_slice(&self.flash[offset..offset + buffer.len()]);
+                        Ok(buffer.len())
+                    }
+                    _ => Err("未知请求".to_string()),
+                }
+            }
+        }
+
+        const FLASH_BASE: u32 = modules::dfu::FLASH_BASE;
+        let layout =
+            parse_layout("@Internal Flash  /0x08000000/04*016Kg,01*064Kg,07*128Kg").unwrap();
+        assert_eq!(layout.len(), 12);
+        assert_eq!(
+            layout[4],
+            FlashSector {
+                address: 0x0801_0000,
+                size: 64 * 1024
+            }
+        );
+        assert!(parse_layout("@Internal Flash").is_err());
+
+        let io = FakeBootloader {
+            flash: vec![0xFF; 64 * 1024],
+            pointer: 0,
+            state: 2,
+            erased: Vec::new(),
+            left: false,
+        };
+        let mut device = DfuDevice::with_io(io, 1024, layout[..4].to_vec());
+        let firmware: Vec<u8> = (0..20_000u32).map(|i| (i * 7) as u8).collect();
+        let mut stages = Vec::new();
+        device
+            .flash(&firmware, &FlashOptions::new(), &mut |p| {
+                if p.done == p.total {
+                    stages.push(p.stage);
+                }
+            })
+            .unwrap();
+        assert_eq!(
+            stages,
+            [DfuStage::Erase, DfuStage::Download, DfuStage::Verify]
+        );
+        assert_eq!(device.read(FLASH_BASE, firmware.len()).unwrap(), firmware);
+
+        // 超出闪存范围
+        let large = vec![0u8; 65 * 1024];
+        assert!(device
+            .flash(&large, &FlashOptions::new().leave(false), &mut |_| {})
+            .is_err());
+        let io = device.into_inner();
+        assert_eq!(io.erased, [0x0800_0000, 0x0800_4000]);
+        assert!(io.left);
+    }
+
+    #[test]
+    fn test_self_test() {
+        /// 把发送的扩展数据原样上报，模拟舵机立即到位
+        #[derive(Default)]
+        struct EchoMcu {
+            reply: Vec<u8>,
+        }
+
+        impl Transport for EchoMcu {
+            fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+                if data.len() == 224 {
+                    self.reply = data[192..].to_vec();
+                }
+                Ok(true)
+            }
+
+            fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+                data[..32].fill(0);
+                if self.reply.len() == 32 {
+                    data[..32].copy_from_slice(&self.reply);
+                }
+                Ok(32)
+            }
+        }
+
+        let mut bot = ElectronBot::new();
+        assert!(matches!(bot.self_test(), Err(BotError::NotConnected)));
+
+        let options = SelfTestOptions::new()
+            .hold(std::time::Duration::ZERO)
+            .settle(std::time::Duration::from_millis(50))
+            .throughput_frames(3);
+        bot.connect_transport(Box::new(EchoMcu::default())).unwrap();
+        bot.set_image_color(Color::Cyan);
+        let image = bot.image_buffer().clone();
+        bot.set_joint_angles(&[5.0, 0.0, 0.0, 0.0, 0.0, 0.0], false)
+            .unwrap();
+        let report = bot.self_test_with(&options).unwrap();
+        assert!(report.passed(), "{}", report);
+        assert_eq!(report.display.len(), 6);
+        assert_eq!(report.joints.len(), 6);
+        assert_eq!(report.joints[0].target, 15.0);
+        assert_eq!(report.throughput.unwrap().frames, 3);
+        // 恢复原来的画面和关节指令
+        assert_eq!(bot.image_buffer().as_data(), image.as_data());
+        assert_eq!(bot.target_joint_angles().0[0], 5.0);
+        assert!(!bot.extra_data().is_enabled());
+
+        // 上报角度一直为 0：关节没有到位
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(RecordingTransport::default()))
+            .unwrap();
+        let report = bot
+            .self_test_with(&options.settle(std::time::Duration::ZERO))
+            .unwrap();
+        assert!(!report.passed());
+        assert!(report.failures().iter().any(|f| f.starts_with("关节 0")));
+        assert!(report.to_string().contains("未到位"));
+    }
+
+    #[test]
+    fn test_stall_detection() {
+        use std::time::{Duration, Instant};
+
+        let mut detector = StallDetector::new(StallOptions::new());
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let target = JointAngles([0.0, 0.0, 40.0, 0.0, 40.0, 0.0]);
+        let mut reported = JointAngles::new();
+        // 关节 2 慢慢跟上，关节 4 一直不动
+        assert!(detector.update(at(0), &target, &reported, true).is_empty());
+        reported.0[2] = 10.0;
+        assert!(detector
+            .update(at(800), &target, &reported, true)
+            .is_empty());
+        reported.0[2] = 20.0;
+        assert_eq!(
+            detector.update(at(1200), &target, &reported, true),
+            [Event::JointLagging(2), Event::JointLagging(4)]
+        );
+        reported.0[2] = 30.0;
+        assert_eq!(
+            detector.update(at(1600), &target, &reported, true),
+            [Event::JointStalled(4)]
+        );
+        assert_eq!(detector.status()[2], JointStatus::Lagging);
+        assert_eq!(detector.status()[4], JointStatus::Stalled);
+        reported.0[2] = 39.0;
+        assert_eq!(
+            detector.update(at(2000), &target, &reported, true),
+            [Event::JointRecovered(2)]
+        );
+        // 舵机释放时不检测
+        assert!(detector
+            .update(at(2400), &target, &reported, false)
+            .is_empty());
+        assert_eq!(detector.status(), [JointStatus::Ok; 6]);
+
+        // 接入 ElectronBot：模拟设备上报的角度一直为 0
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut bot = ElectronBot::new();
+        let log = events.clone();
+        bot.on_event(move |event| log.lock().unwrap().push(event.clone()));
+        bot.set_stall_detection(Some(StallOptions::new().stall_time(Duration::ZERO)));
+        bot.connect_transport(Box::new(RecordingTransport::default()))
+            .unwrap();
+        bot.set_joint_angles(&[0.0, 0.0, 30.0, 0.0, 0.0, 0.0], true)
+            .unwrap();
+        bot.sync().unwrap();
+        bot.sync().unwrap();
+        assert_eq!(bot.joint_status()[2], JointStatus::Stalled);
+        bot.set_joint_angles(&[0.0; 6], true).unwrap();
+        bot.sync().unwrap();
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                Event::Connected,
+                Event::JointStalled(2),
+                Event::JointRecovered(2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_joint_filter() {
+        let sample = |v: f32| JointAngles([v, 0.0, 0.0, 0.0, 0.0, -v]);
+
+        let mut smooth = JointFilter::exponential(0.5);
+        assert_eq!(smooth.update(&sample(10.0)).0[0], 10.0);
+        assert_eq!(smooth.update(&sample(20.0)).0[0], 15.0);
+        assert_eq!(smooth.update(&sample(20.0)).0[5], -17.5);
+
+        // 中值滤波去掉单次跳变
+        let mut median = JointFilter::median(3);
+        assert_eq!(median.update(&sample(10.0)).0[0], 10.0);
+        assert_eq!(median.update(&sample(90.0)).0[0], 50.0);
+        assert_eq!(median.update(&sample(12.0)).0[0], 12.0);
+        assert_eq!(median.update(&sample(11.0)).0[0], 12.0);
+        median.reset();
+        assert!(median.value().is_none());
+        assert_eq!(
+            JointFilter::median(100).kind(),
+            FilterKind::Median { window: 31 }
+        );
+
+        /// 每次同步上报下一个角度
+        struct NoisyMcu {
+            samples: Vec<f32>,
+            receives: usize,
+        }
+
+        impl Transport for NoisyMcu {
+            fn transmit(&mut self, _data: &[u8]) -> Result<bool, String> {
+                Ok(true)
+            }
+
+            fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+                let value = self.samples[(self.receives / 4).min(self.samples.len() - 1)];
+                self.receives += 1;
+                let mut reply = ExtraData::new();
+                reply.set_joint_angles(&JointAngles([value, 0.0, 0.0, 0.0, 0.0, -value]), true);
+                data[..32].copy_from_slice(reply.get_raw());
+                Ok(32)
+            }
+        }
+
+        let mut bot = ElectronBot::new();
+        bot.set_joint_filter(FilterKind::Median { window: 3 });
+        bot.connect_transport(Box::new(NoisyMcu {
+            samples: vec![10.0, 80.0, 12.0],
+            receives: 0,
+        }))
+        .unwrap();
+        for _ in 0..3 {
+            bot.sync().unwrap();
+        }
+        assert_eq!(bot.raw_joint_angles().0[0], 12.0);
+        assert_eq!(bot.get_joint_angles().0[0], 12.0);
+        bot.set_joint_filter(FilterKind::None);
+        assert_eq!(bot.get_joint_angles().0[0], 12.0);
+    }
+
+    #[test]
+    fn test_telemetry_history() {
+        let mut bot = ElectronBot::new();
+        assert_eq!(bot.telemetry_history().capacity(), DEFAULT_HISTORY_LEN);
+        bot.set_telemetry_history_len(3);
+        bot.connect_transport(Box::new(RecordingTransport::default()))
+            .unwrap();
+        for i in 0..5 {
+            bot.set_joint_angles(&[i as f32, 0.0, 0.0, 0.0, 0.0, 0.0], i % 2 == 0)
+                .unwrap();
+            bot.sync().unwrap();
+        }
+        let history = bot.telemetry_history();
+        assert_eq!(history.len(), 3);
+        let targets: Vec<f32> = history.iter().map(|s| s.target.0[0]).collect();
+        assert_eq!(targets, [2.0, 3.0, 4.0]);
+        let latest = history.latest().unwrap();
+        assert!(latest.enabled);
+        assert_eq!(latest.raw, [0u8; 32]);
+        assert_eq!(latest.reported, JointAngles::new());
+        assert_eq!(history.since(std::time::Duration::from_secs(60)).count(), 3);
+
+        let csv = history.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("ms,enabled,target0,"));
+        assert!(lines[3].contains(",1,4.00,"));
+
+        bot.set_telemetry_history_len(0);
+        bot.sync().unwrap();
+        assert!(bot.telemetry_history().is_empty());
+    }
+
+    #[test]
+    fn test_get_joint_angles_after_sync() {
+        // 舵机释放时模拟 MCU 一直上报手动摆好的姿态，与目标角度不同
+        let mcu = FakeMcu::new();
+        let reported = JointAngles([3.0, -4.0, 12.0, 0.0, 25.0, -8.0]);
+        mcu.state().lock().unwrap().reported = reported.clone();
+        let target = [0.0, 0.0, 30.0, 0.0, 30.0, 0.0];
+
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(mcu)).unwrap();
+        bot.set_joint_angles(&target, false).unwrap();
+
+        // 同步前还没有收到机器人数据，返回目标角度
+        assert_eq!(bot.get_joint_angles().0, target);
+        assert!(bot.sync().unwrap());
+
+        // 同步后返回机器人上报的角度，目标角度通过 target_joint_angles 读取
+        assert_eq!(bot.get_joint_angles(), reported);
+        assert_eq!(bot.raw_joint_angles(), reported);
+        assert_eq!(bot.target_joint_angles().0, target);
+
+        // 断开后仍保留最后上报的角度，重新连接时清空，回到目标角度
+        bot.disconnect();
+        assert_eq!(bot.get_joint_angles(), reported);
+        bot.connect_transport(Box::new(FakeMcu::new())).unwrap();
+        assert_eq!(bot.get_joint_angles().0, target);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing_spans() {
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+
+        /// 把字段格式化为 ` name=value`。
+        struct Fields(String);
+
+        impl Visit for Fields {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                self.0.push_str(&format!(" {}={:?}", field.name(), value));
+            }
+        }
+
+        /// 记录的 span（名称和字段）和事件（所在 span 的名称和字段）。
+        #[derive(Default)]
+        struct Recorded {
+            spans: Vec<(&'static str, String)>,
+            stack: Vec<usize>,
+            events: Vec<(&'static str, String)>,
+        }
+
+        struct Recorder(Arc<Mutex<Recorded>>);
+
+        impl tracing::Subscriber for Recorder {
+            fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+                let mut fields = Fields(String::new());
+                attrs.record(&mut fields);
+                let mut recorded = self.0.lock().unwrap();
+                recorded.spans.push((attrs.metadata().name(), fields.0));
+                Id::from_u64(recorded.spans.len() as u64)
+            }
+
+            fn record(&self, id: &Id, values: &Record<'_>) {
+                let mut fields = Fields(String::new());
+                values.record(&mut fields);
+                let mut recorded = self.0.lock().unwrap();
+                recorded.spans[id.into_u64() as usize - 1]
+                    .1
+                    .push_str(&fields.0);
+            }
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, event: &tracing::Event<'_>) {
+                let mut fields = Fields(String::new());
+                event.record(&mut fields);
+                let mut recorded = self.0.lock().unwrap();
+                let span = recorded
+                    .stack
+                    .last()
+                    .map_or("", |&index| recorded.spans[index].0);
+                recorded.events.push((span, fields.0));
+            }
+
+            fn enter(&self, id: &Id) {
+                let mut recorded = self.0.lock().unwrap();
+                recorded.stack.push(id.into_u64() as usize - 1);
+            }
+
+            fn exit(&self, _: &Id) {
+                self.0.lock().unwrap().stack.pop();
+            }
+        }
+
+        let recorded = Arc::new(Mutex::new(Recorded::default()));
+        tracing::subscriber::with_default(Recorder(Arc::clone(&recorded)), || {
+            // 一次接收超时后重试成功
+            let mut bot = ElectronBot::new();
+            bot.connect_transport(Box::new(FakeMcu::new().fail_receive(2, Fault::Timeout)))
+                .unwrap();
+            assert!(bot.sync().unwrap());
+            // 设备拔出
+            bot.connect_transport(Box::new(FakeMcu::new().fail_transmit(0, Fault::Disconnect)))
+                .unwrap();
+            assert!(bot.sync().is_err());
+        });
+
+        let recorded = recorded.lock().unwrap();
+        let syncs: Vec<_> = recorded
+            .spans
+            .iter()
+            .filter(|(name, _)| *name == "sync")
+            .collect();
+        assert_eq!(syncs.len(), 2);
+        assert!(
+            syncs[0].1.contains(" cycles=4 packets=340"),
+            "{}",
+            syncs[0].1
+        );
+        assert!(syncs[1].1.contains(" cycles=0 packets=0"), "{}", syncs[1].1);
+        let cycles = recorded.spans.iter().filter(|(name, _)| *name == "cycle");
+        assert_eq!(cycles.count(), 5);
+
+        // 重试事件在周期 span 内，失败事件在同步 span 内
+        let retry = recorded
+            .events
+            .iter()
+            .find(|(_, fields)| fields.contains("receive retry"))
+            .unwrap();
+        assert_eq!(retry.0, "cycle");
+        assert!(retry.1.contains(" attempt=1"), "{}", retry.1);
+        let failed = recorded
+            .events
+            .iter()
+            .find(|(_, fields)| fields.contains("sync failed"))
+            .unwrap();
+        assert_eq!(failed.0, "sync");
+        assert!(failed.1.contains("No such device"), "{}", failed.1);
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn test_webusb_transport() {
+        use modules::webusb::Op;
+
+        // 按 `WebUsb::flush` 的顺序把记录的收发交给模拟 MCU，收到的请求留给下一次同步
+        let flush = |transport: &WebUsbTransport, mcu: &mut FakeMcu| {
+            let mut replies = std::collections::VecDeque::new();
+            for op in transport.take_ops() {
+                match op {
+                    Op::Receive(len) => {
+                        let mut request = vec![0u8; len];
+                        let received = mcu.receive(&mut request).unwrap();
+                        replies.push_back(request[..received].to_vec());
+                    }
+                    Op::Transmit(data) => {
+                        mcu.transmit(&data).unwrap();
+                    }
+                }
+            }
+            transport.set_replies(replies);
+        };
+
+        let transport = WebUsbTransport::default();
+        let mut mcu = FakeMcu::new();
+        let state = mcu.state();
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(transport.clone())).unwrap();
+        let angles = [10.0, 0.0, 20.0, 0.0, -20.0, 5.0];
+        bot.set_image_color(Color::Blue);
+        bot.set_joint_angles(&angles, true).unwrap();
+
+        // 同步只记录收发：每个周期先收请求再发 85 个包
+        assert!(bot.sync().unwrap());
+        assert_eq!(state.lock().unwrap().packets, 0);
+        let ops = transport.take_ops();
+        assert_eq!(ops.len(), 4 * 86);
+        assert_eq!(ops[0], Op::Receive(32));
+        assert!(matches!(&ops[1], Op::Transmit(data) if data.len() == 512));
+        transport.set_replies(Default::default());
+
+        // 实际收发后 MCU 收到整帧；上报的角度比发送晚一帧
+        bot.sync().unwrap();
+        flush(&transport, &mut mcu);
+        {
+            let state = state.lock().unwrap();
+            assert_eq!((state.frames, state.torn_cycles), (1, 0));
+            assert_eq!(state.frame.as_deref(), Some(bot.output_frame().as_data()));
+        }
+        assert_eq!(bot.raw_joint_angles(), JointAngles::new());
+        bot.sync().unwrap();
+        flush(&transport, &mut mcu);
+        assert_eq!(bot.raw_joint_angles().0, angles);
+        assert_eq!(state.lock().unwrap().frames, 2);
+    }
 
-    for (i, device) in devices.iter().enumerate() {
-        let marker = if device.vid == USB_VID && device.pid == USB_PID {
-            " <-- ElectronBot"
-        } else {
-            ""
+    #[test]
+    fn test_fake_mcu_sync() {
+        let angles = [10.0, 0.0, 20.0, 0.0, -20.0, 5.0];
+        let sync_with = |mcu: FakeMcu| {
+            let state = mcu.state();
+            let mut bot = ElectronBot::new();
+            bot.connect_transport(Box::new(mcu)).unwrap();
+            bot.set_image_color(Color::Blue);
+            bot.set_joint_angles(&angles, true).unwrap();
+            let result = bot.sync();
+            (bot, state, result)
         };
-        println!("  [{}] {}{}", i, device.info, marker);
+
+        // 正常同步：MCU 收到整帧和扩展数据，下一次请求上报新的角度
+        let (mut bot, state, result) = sync_with(FakeMcu::new());
+        assert!(result.unwrap());
+        {
+            let state = state.lock().unwrap();
+            assert_eq!((state.requests, state.packets), (4, 340));
+            assert_eq!((state.cycles, state.frames, state.torn_cycles), (4, 1, 0));
+            assert_eq!(state.frame.as_deref(), Some(bot.output_frame().as_data()));
+            assert_eq!(
+                state.extra_data.as_ref().unwrap().get_joint_angles().0,
+                angles
+            );
+        }
+        assert!(bot.sync().unwrap());
+        assert_eq!(bot.raw_joint_angles().0, angles);
+        assert_eq!(state.lock().unwrap().frames, 2);
+
+        // 短读和单次超时重试后成功，帧完整
+        for mcu in [
+            FakeMcu::new().fail_receive(0, Fault::ShortRead(8)),
+            FakeMcu::new().fail_receive(2, Fault::Timeout),
+            FakeMcu::new().fail_transmit(100, Fault::Timeout),
+        ] {
+            let (bot, state, result) = sync_with(mcu);
+            assert!(result.unwrap());
+            let state = state.lock().unwrap();
+            assert_eq!((state.frames, state.torn_cycles), (1, 0));
+            assert_eq!(state.frame.as_deref(), Some(bot.output_frame().as_data()));
+        }
+
+        // 端点停止：清除停止状态后继续
+        let (_, state, result) = sync_with(FakeMcu::new().fail_transmit(10, Fault::Stall));
+        assert!(result.unwrap());
+        let state = state.lock().unwrap();
+        assert_eq!(
+            (state.halts_cleared, state.frames, state.torn_cycles),
+            (1, 1, 0)
+        );
+        drop(state);
+
+        // 重试用完仍然超时：中止同步，返回暂时性的发送错误
+        let mcu = (5..8).fold(FakeMcu::new(), |mcu, call| {
+            mcu.fail_transmit(call, Fault::Timeout)
+        });
+        let (_, state, result) = sync_with(mcu);
+        let error = result.unwrap_err();
+        assert!(matches!(error, BotError::SendFailed(_)));
+        assert_eq!(error.class(), ErrorClass::Transient);
+        assert_eq!(state.lock().unwrap().frames, 0);
+
+        // 拔出：立即中止并报告位置
+        let (_, state, result) = sync_with(FakeMcu::new().fail_transmit(90, Fault::Disconnect));
+        let error = result.unwrap_err();
+        assert!(error.is_device_lost());
+        let transfer = error.transfer_error().unwrap();
+        assert_eq!((transfer.cycle, transfer.packet), (Some(1), Some(90)));
+        assert_eq!(state.lock().unwrap().frames, 0);
     }
-}
 
-// ==================== 测试 ====================
+    #[test]
+    fn test_fake_mcu_persistent_timeout() {
+        let sync_with = |mcu: FakeMcu| {
+            let mut bot = ElectronBot::new();
+            bot.connect_transport(Box::new(mcu)).unwrap();
+            bot.sync().unwrap_err()
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        // 第二个周期的请求一直超时：5 次接收都失败
+        let mcu = (1..6).fold(FakeMcu::new(), |mcu, call| {
+            mcu.fail_receive(call, Fault::Timeout)
+        });
+        let error = sync_with(mcu);
+        assert_eq!(error.class(), ErrorClass::Transient);
+        let BotError::ReceiveFailed(transfer) = &error else {
+            panic!("应为接收失败: {:?}", error);
+        };
+        assert_eq!((transfer.cycle, transfer.packet), (Some(1), Some(85)));
+        assert_eq!((transfer.expected, transfer.transferred), (32, 0));
+        assert_eq!(transfer.retries, 4);
+        assert_eq!(transfer.kind, TransferErrorKind::Timeout);
+
+        // 第二个周期的第 6 个包一直超时：3 次发送都失败
+        let mcu = (90..93).fold(FakeMcu::new(), |mcu, call| {
+            mcu.fail_transmit(call, Fault::Timeout)
+        });
+        let error = sync_with(mcu);
+        assert_eq!(error.class(), ErrorClass::Transient);
+        let BotError::SendFailed(transfer) = &error else {
+            panic!("应为发送失败: {:?}", error);
+        };
+        assert_eq!((transfer.cycle, transfer.packet), (Some(1), Some(90)));
+        assert_eq!((transfer.expected, transfer.transferred), (PACKET_SIZE, 0));
+        assert_eq!(transfer.retries, 2);
+        assert_eq!(transfer.kind, TransferErrorKind::Timeout);
+    }
 
     #[test]
-    fn test_joint_angles_default() {
-        let angles = JointAngles::new();
-        assert_eq!(angles.0, [0.0; 6]);
+    fn test_frame_sent_hook_and_hex_dump() {
+        let mut bot = ElectronBot::new();
+        let dir = std::env::temp_dir().join(format!("electronbot-hex-{}", std::process::id()));
+        assert!(matches!(
+            bot.start_hex_dump(&dir, std::time::Duration::from_secs(1)),
+            Err(BotError::NotConnected)
+        ));
+
+        let mcu = FakeMcu::new();
+        let state = mcu.state();
+        bot.connect_transport(Box::new(mcu)).unwrap();
+        let sent = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let cycles = sent.clone();
+        bot.on_frame_sent(move |cycle, bytes| {
+            cycles.lock().unwrap().push((cycle, bytes.to_vec()));
+        });
+        bot.start_hex_dump(&dir, std::time::Duration::from_secs(60))
+            .unwrap();
+        bot.set_image_color(Color::Blue);
+        bot.set_joint_angles(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], true)
+            .unwrap();
+        bot.sync().unwrap();
+
+        // 回调收到的数据与 MCU 收到的一致
+        let sent = sent.lock().unwrap();
+        assert_eq!(
+            sent.iter().map(|(cycle, _)| *cycle).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        let profile = ProtocolProfile::STOCK;
+        let image_len = profile.segment_size();
+        let frame: Vec<u8> = sent
+            .iter()
+            .flat_map(|(_, bytes)| bytes[..image_len].to_vec())
+            .collect();
+        let state = state.lock().unwrap();
+        assert_eq!(state.frame.as_deref(), Some(&frame[..]));
+        let last = sent[3].1.clone();
+        assert_eq!(last.len(), image_len + modules::protocol::EXTRA_SIZE);
+        assert_eq!(
+            &last[image_len..],
+            state.extra_data.as_ref().unwrap().as_data()
+        );
+
+        drop(state);
+        drop(sent);
+
+        // 断开后转储文件写完，每行一个包
+        bot.disconnect();
+        let tx = std::fs::read_to_string(dir.join("tx.hex")).unwrap();
+        let rx = std::fs::read_to_string(dir.join("rx.hex")).unwrap();
+        assert_eq!(tx.lines().count(), 4 * (profile.packet_count + 1));
+        assert_eq!(rx.lines().count(), 4);
+        let first: Vec<&str> = rx.lines().next().unwrap().split(' ').collect();
+        assert_eq!(first[1], "32");
+        assert_eq!(first.len(), 2 + 32);
+        let tail: Vec<&str> = tx.lines().last().unwrap().split(' ').collect();
+        let hex: Vec<String> = last[image_len..]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        assert_eq!(tail[tail.len() - modules::protocol::EXTRA_SIZE..], hex[..]);
+
+        // 超过时限后不再写入
+        bot.clear_frame_sent_handlers();
+        bot.connect_transport(Box::new(FakeMcu::new())).unwrap();
+        bot.start_hex_dump(&dir, std::time::Duration::ZERO).unwrap();
+        bot.sync().unwrap();
+        bot.disconnect();
+        assert!(std::fs::read_to_string(dir.join("tx.hex"))
+            .unwrap()
+            .is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn test_joint_angles_get_set() {
-        let mut angles = JointAngles::new();
-        angles.set(0, 45.0);
-        angles.set(1, 90.0);
-        assert_eq!(angles.get(0), Some(45.0));
-        assert_eq!(angles.get(1), Some(90.0));
-        assert_eq!(angles.get(5), Some(0.0));
-        assert_eq!(angles.get(6), None);
+    fn test_benchmark() {
+        let mut bot = ElectronBot::new();
+        assert!(matches!(
+            bot.benchmark(std::time::Duration::from_millis(10)),
+            Err(BotError::NotConnected)
+        ));
+
+        let mcu = FakeMcu::new().fail_transmit(3, Fault::Timeout);
+        let state = mcu.state();
+        bot.connect_transport(Box::new(mcu)).unwrap();
+        bot.set_image_color(Color::Green);
+        let before = bot.image_buffer().as_data().to_vec();
+        let report = bot
+            .benchmark(std::time::Duration::from_millis(100))
+            .unwrap();
+        assert!(report.frames > 0);
+        assert_eq!(report.failed, 0);
+        assert_eq!(report.retries, 1);
+        assert_eq!(report.frames as u64, state.lock().unwrap().frames);
+        assert_eq!(report.latencies.len(), report.frames);
+        assert!(report.latency(0.5) <= report.latency(0.99));
+        assert_eq!(report.latency(1.0), report.latencies.last().copied());
+        assert!(report.fps() > 0.0);
+        assert!(report.to_string().contains("重试: 1 次"));
+        assert_eq!(bot.image_buffer().as_data(), before);
+
+        // 拔出时中止
+        bot.connect_transport(Box::new(FakeMcu::new().fail_transmit(0, Fault::Disconnect)))
+            .unwrap();
+        let error = bot
+            .benchmark(std::time::Duration::from_millis(100))
+            .unwrap_err();
+        assert!(error.is_device_lost());
     }
 
     #[test]
-    fn test_joint_angles_bytes() {
-        let angles = JointAngles::new();
-        let bytes = angles.to_bytes();
-        assert_eq!(bytes.len(), 24);
-        let restored = JointAngles::from_bytes(&bytes.try_into().unwrap());
-        assert_eq!(restored.0, [0.0; 6]);
+    fn test_transfer_error_context() {
+        /// 第 `packets` 个包之后拔出的设备。
+        struct Unplugged {
+            packets: usize,
+        }
+
+        impl Transport for Unplugged {
+            fn transmit(&mut self, _data: &[u8]) -> Result<bool, String> {
+                if self.packets == 0 {
+                    return Err("No such device".to_string());
+                }
+                self.packets -= 1;
+                Ok(true)
+            }
+
+            fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+                let len = data.len().min(32);
+                data[..len].fill(0);
+                Ok(len)
+            }
+
+            fn last_error_kind(&self) -> Option<TransferErrorKind> {
+                Some(TransferErrorKind::NoDevice)
+            }
+        }
+
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(Unplugged { packets: 90 }))
+            .unwrap();
+        let error = bot.sync().unwrap_err();
+        assert!(error.is_device_lost());
+        let BotError::SendFailed(transfer) = &error else {
+            panic!("应为发送失败: {:?}", error);
+        };
+        // 第一个周期 85 个包，第二个周期的第 6 个包失败，拔出后不再重试
+        assert_eq!(transfer.cycle, Some(1));
+        assert_eq!(transfer.packet, Some(90));
+        assert_eq!(transfer.expected, PACKET_SIZE);
+        assert_eq!(transfer.transferred, 0);
+        assert_eq!(transfer.retries, 0);
+        assert_eq!(
+            transfer.to_string(),
+            "No such device（周期 1，包 90，0/512 字节，NoDevice）"
+        );
+        assert_eq!(
+            bot.recent_events().last_error().unwrap().kind,
+            LogKind::SyncFailed
+        );
+
+        // 休眠恢复只重新打开 `connect` 打开的 USB 设备，其他传输通道直接返回错误
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = std::sync::Arc::clone(&events);
+        bot.on_event(move |event| sink.lock().unwrap().push(event.clone()));
+        bot.set_auto_resume(Some(std::time::Duration::from_millis(10)));
+        assert_eq!(
+            bot.auto_resume(),
+            Some(std::time::Duration::from_millis(10))
+        );
+        assert!(bot.sync().unwrap_err().is_device_lost());
+        assert!(!events.lock().unwrap().contains(&Event::Resumed));
+        assert_eq!(bot.recent_events().of_kind(LogKind::Resumed).count(), 0);
+
+        // 没有错误类型的普通失败不中止同步，直接收发时带上字节数
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(RecordingTransport::default()))
+            .unwrap();
+        assert!(bot.sync().unwrap());
+        let plain = TransferError::new("timeout");
+        assert_eq!(plain.to_string(), "timeout");
+        assert_eq!(plain.kind, TransferErrorKind::Other);
+        assert!(!BotError::ReceiveFailed(plain).is_device_lost());
     }
 
     #[test]
-    fn test_color_rgb() {
-        assert_eq!(Color::Red.rgb(), (255, 0, 0));
-        assert_eq!(Color::Green.rgb(), (0, 255, 0));
-        assert_eq!(Color::Blue.rgb(), (0, 0, 255));
-        assert_eq!(Color::Cyan.rgb(), (0, 255, 255));
-        assert_eq!(Color::Custom(100, 150, 200).rgb(), (100, 150, 200));
+    fn test_error_class() {
+        let transfer = |kind| {
+            BotError::ReceiveFailed(TransferError {
+                kind,
+                ..TransferError::new("failed")
+            })
+        };
+        assert_eq!(
+            transfer(TransferErrorKind::Timeout).class(),
+            ErrorClass::Transient
+        );
+        assert_eq!(
+            transfer(TransferErrorKind::Pipe).class(),
+            ErrorClass::Transient
+        );
+        assert_eq!(
+            transfer(TransferErrorKind::NoDevice).class(),
+            ErrorClass::Disconnected
+        );
+        assert_eq!(
+            transfer(TransferErrorKind::Access).class(),
+            ErrorClass::Fatal
+        );
+        assert_eq!(BotError::SyncTimeout(1, 2).class(), ErrorClass::Transient);
+        assert_eq!(BotError::NotConnected.class(), ErrorClass::Disconnected);
+        assert_eq!(BotError::Cancelled.class(), ErrorClass::Fatal);
+        assert_eq!(
+            BotError::ConfigError(String::new()).class(),
+            ErrorClass::Fatal
+        );
+
+        /// 没有访问权限的通道：不重试，同步立即中止
+        struct Denied {
+            attempts: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+        }
+
+        impl Transport for Denied {
+            fn transmit(&mut self, _data: &[u8]) -> Result<bool, String> {
+                Ok(true)
+            }
+
+            fn receive(&mut self, _data: &mut [u8]) -> Result<usize, String> {
+                self.attempts
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Err("Access denied".to_string())
+            }
+
+            fn last_error_kind(&self) -> Option<TransferErrorKind> {
+                Some(TransferErrorKind::Access)
+            }
+        }
+
+        let attempts = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(Denied {
+            attempts: attempts.clone(),
+        }))
+        .unwrap();
+        let mut simple = SimpleBot::from_bot(bot);
+        let error = simple.show_color(Color::Red).unwrap_err();
+        assert!(matches!(&error, BotError::ReceiveFailed(t) if t.cycle == Some(0)));
+        assert_eq!(error.class(), ErrorClass::Fatal);
+        // SimpleBot 不重试致命错误，通道也只接收了一次
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 1);
     }
 
     #[test]
-    fn test_color_bgr() {
-        assert_eq!(Color::Red.bgr(), (0, 0, 255));
-        assert_eq!(Color::Green.bgr(), (0, 255, 0));
-        assert_eq!(Color::Blue.bgr(), (255, 0, 0));
+    fn test_clear_halt_on_stall() {
+        /// 第一次接收和发送时端点停止，清除后恢复
+        struct Stalled {
+            inner: RecordingTransport,
+            stalled: [bool; 2],
+            cleared: std::sync::Arc<std::sync::Mutex<Vec<Direction>>>,
+        }
+
+        impl Transport for Stalled {
+            fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+                if self.stalled[1] {
+                    return Err("Pipe error".to_string());
+                }
+                self.inner.transmit(data)
+            }
+
+            fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+                if self.stalled[0] {
+                    return Err("Pipe error".to_string());
+                }
+                self.inner.receive(data)
+            }
+
+            fn last_error_kind(&self) -> Option<TransferErrorKind> {
+                Some(TransferErrorKind::Pipe)
+            }
+
+            fn clear_halt(&mut self, direction: Direction) -> Result<(), String> {
+                self.stalled[(direction == Direction::Out) as usize] = false;
+                self.cleared.lock().unwrap().push(direction);
+                Ok(())
+            }
+        }
+
+        let inner = RecordingTransport::default();
+        let sent = inner.sent.clone();
+        let cleared = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(Stalled {
+            inner,
+            stalled: [true, true],
+            cleared: cleared.clone(),
+        }))
+        .unwrap();
+        assert!(bot.sync().unwrap());
+        // 清除后重试成功，没有丢包，也收到了请求
+        assert_eq!(*cleared.lock().unwrap(), [Direction::In, Direction::Out]);
+        assert_eq!(sent.lock().unwrap().len(), 4 * 85);
+        assert!(bot.received_extra_data().is_some());
     }
 
     #[test]
-    fn test_electron_bot_new() {
-        let bot = ElectronBot::new();
-        assert!(!bot.is_connected());
+    fn test_recent_events() {
+        let mut bot = ElectronBot::new();
+        assert_eq!(bot.recent_events().capacity(), DEFAULT_EVENT_LOG_LEN);
+        bot.connect_transport(Box::new(RecordingTransport::default()))
+            .unwrap();
+        bot.emergency_stop().unwrap();
+        bot.cancellation_token().cancel();
+        assert!(matches!(bot.sync(), Err(BotError::Cancelled)));
+        bot.disconnect();
+
+        let log = bot.recent_events();
+        let kinds: Vec<LogKind> = log.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                LogKind::Connected,
+                LogKind::EmergencyStop,
+                LogKind::Cancelled,
+                LogKind::Disconnected
+            ]
+        );
+        assert!(log.last_error().is_none());
+        assert_eq!(log.of_kind(LogKind::Cancelled).count(), 1);
+        assert_eq!(log.since(std::time::Duration::from_secs(60)).count(), 4);
+        assert!(log.latest().unwrap().to_string().ends_with(" 已断开"));
+
+        let mut log = EventLog::new(2);
+        log.push(LogKind::SyncFailed, "timeout");
+        log.push(LogKind::Connected, "");
+        log.push(LogKind::SyncTimeout, "超时");
+        assert_eq!(log.len(), 2);
+        assert_eq!(log.last_error().unwrap().detail, "超时");
+        assert!(log.iter().next().unwrap().to_string().ends_with("已连接"));
+
+        bot.set_event_log_len(0);
+        assert!(bot.recent_events().is_empty());
     }
 
     #[test]
-    fn test_image_buffer_new() {
-        let buf = ImageBuffer::new();
-        assert_eq!(buf.as_data().len(), FRAME_SIZE);
+    fn test_angle_units() {
+        use std::f32::consts::{FRAC_PI_2, PI};
+
+        let right = Angle::radians(FRAC_PI_2);
+        assert!((right.to_degrees() - 90.0).abs() < 1e-4);
+        assert!((Angle::degrees(180.0).to_radians() - PI).abs() < 1e-6);
+        assert_eq!(
+            Angle::degrees(10.0) + Angle::degrees(5.0) - Angle::degrees(20.0),
+            Angle::degrees(-5.0)
+        );
+        assert_eq!(-Angle::degrees(30.0) / 2.0, Angle::degrees(-15.0));
+        assert_eq!(Angle::degrees(270.0).normalized(), Angle::degrees(-90.0));
+        assert_eq!(Angle::degrees(-180.0).normalized(), Angle::degrees(180.0));
+        assert_eq!(
+            Angle::degrees(75.0).clamp(Angle::degrees(-60.0), Angle::degrees(60.0)),
+            Angle::degrees(60.0)
+        );
+        assert_eq!(format!("{:.1}", Angle::degrees(12.34)), "12.3°");
+
+        let pose = JointAngles::from_radians([0.0, 0.0, FRAC_PI_2, 0.0, 0.0, -FRAC_PI_2]);
+        assert!((pose.0[2] - 90.0).abs() < 1e-4);
+        assert!((pose.to_radians()[5] + FRAC_PI_2).abs() < 1e-6);
+        let mut pose = JointAngles::new();
+        pose.set_angle(JOINT_HEAD, Angle::degrees(12.0)).unwrap();
+        assert_eq!(pose.angle(JOINT_HEAD), Some(Angle::degrees(12.0)));
+        assert!(pose.set_angle(6, Angle::ZERO).is_none());
+
+        let mut bot = ElectronBot::new();
+        let mut angles = [Angle::ZERO; 6];
+        angles[2] = Angle::radians(PI / 6.0);
+        bot.set_angles(&angles, true).unwrap();
+        assert!((bot.target_joint_angles().0[2] - 30.0).abs() < 1e-4);
+        assert_eq!(bot.angles()[2], angles[2]);
     }
 
     #[test]
-    fn test_image_buffer_clear() {
-        let mut buf = ImageBuffer::new();
-        buf.clear(Color::Red);
-        // 检查第一个像素是红色（存储为 BGR: 0, 0, 255）
-        // get_pixel 返回 RGB，所以 BGR(0,0,255) -> RGB(255,0,0)
-        assert_eq!(buf.get_pixel(0, 0), Some(Color::Custom(0, 0, 255)));
+    fn test_animation_mixer() {
+        use std::time::Duration;
+
+        /// 保持固定角度 `frames` 帧
+        struct Hold(JointAngles, usize);
+
+        impl FrameSource for Hold {
+            fn next_frame(&mut self, _buffer: &mut ImageBuffer) -> bool {
+                if self.1 == 0 {
+                    return false;
+                }
+                self.1 -= 1;
+                true
+            }
+
+            fn joint_angles(&self) -> Option<JointAngles> {
+                Some(self.0.clone())
+            }
+        }
+
+        let mut buffer = ImageBuffer::new();
+        let mut mixer = AnimationMixer::new(JointAngles::new()).interval(Duration::from_millis(10));
+        mixer
+            .add_track(
+                "idle",
+                Hold(JointAngles([5.0; 6]), usize::MAX),
+                TrackOptions::new(),
+            )
+            .add_track(
+                "arms",
+                Hold(JointAngles([0.0, 0.0, 40.0, 0.0, 40.0, 0.0]), usize::MAX),
+                TrackOptions::new().joints(&[2, 4]),
+            )
+            .add_track(
+                "breathe",
+                Hold(JointAngles([2.0; 6]), usize::MAX),
+                TrackOptions::new().additive().weights([0.5; 6]),
+            );
+        assert_eq!(mixer.track_names(), ["idle", "arms", "breathe"]);
+        assert!(mixer.next_frame(&mut buffer));
+        assert_eq!(mixer.pose().0, [6.0, 6.0, 41.0, 6.0, 41.0, 6.0]);
+
+        // 交叉淡化：手臂在 100 毫秒内从 40° 过渡到 0°
+        mixer.play(
+            "arms",
+            Hold(JointAngles([0.0; 6]), usize::MAX),
+            Duration::from_millis(100),
+        );
+        for _ in 0..5 {
+            mixer.next_frame(&mut buffer);
+        }
+        let middle = mixer.pose().0[2];
+        assert!(middle > 1.0 && middle < 41.0, "{}", middle);
+        for _ in 0..5 {
+            mixer.next_frame(&mut buffer);
+        }
+        assert_eq!(mixer.pose().0[2], 1.0);
+
+        // 停止后淡出并移除，手臂回到底层的角度
+        mixer.stop("arms", Duration::ZERO);
+        mixer.next_frame(&mut buffer);
+        assert!(!mixer.contains("arms"));
+        assert_eq!(mixer.pose().0[2], 6.0);
+
+        // 播放结束的轨道淡出后移除，没有轨道时结束
+        let mut mixer = AnimationMixer::new(JointAngles::new()).interval(Duration::from_millis(10));
+        mixer.add_track(
+            "once",
+            Hold(JointAngles([10.0; 6]), 2),
+            TrackOptions::new().fade_out(Duration::from_millis(20)),
+        );
+        let mut frames = 0;
+        while mixer.next_frame(&mut buffer) {
+            frames += 1;
+            assert!(frames < 10);
+        }
+        assert_eq!(frames, 5);
+        assert_eq!(mixer.pose().0, [0.0; 6]);
     }
 
     #[test]
-    fn test_image_buffer_set_pixel() {
-        let mut buf = ImageBuffer::new();
-        buf.set_pixel(10, 10, Color::Green);
-        assert_eq!(buf.get_pixel(10, 10), Some(Color::Custom(0, 255, 0)));
+    fn test_idle_motion() {
+        use std::time::Duration;
+
+        let options = IdleMotionOptions::new()
+            .amplitude([4.0, 0.0, 2.0, 0.0, 2.0, 6.0])
+            .breathing(1.0, 0.5)
+            .seed(3);
+        let mut idle = IdleMotion::new(options).interval(Duration::from_millis(50));
+        assert_eq!(idle.offsets_at(Duration::ZERO).0, [0.0; 6]);
+
+        // 偏移不超过幅度加呼吸，不驱动的关节保持不动，动作是连续变化的
+        let mut buffer = ImageBuffer::new();
+        let mut previous = JointAngles::new();
+        let mut moved = [false; 6];
+        for _ in 0..200 {
+            assert!(idle.next_frame(&mut buffer));
+            let angles = idle.joint_angles().unwrap();
+            for joint in 0..6 {
+                let limit = options.amplitude[joint] + [0.5, 0.0, 1.0, 0.0, 1.0, 0.0][joint];
+                let angle = angles.0[joint];
+                assert!(angle.abs() <= limit + 1e-4, "{} {}", joint, angle);
+                assert!((angle - previous.0[joint]).abs() < 1.0);
+                moved[joint] |= angle.abs() > 0.1;
+            }
+            previous = angles;
+        }
+        assert_eq!(moved, [true, false, true, false, true, true]);
+        assert_eq!(idle.elapsed(), Duration::from_secs(10));
+        assert_eq!(buffer.as_data(), ImageBuffer::new().as_data());
+
+        // 相同种子的动作相同，不同种子不同
+        let t = Duration::from_millis(2300);
+        let same = IdleMotion::new(options);
+        let other = IdleMotion::new(options.seed(4));
+        assert_eq!(same.offsets_at(t), idle.offsets_at(t));
+        assert_ne!(other.offsets_at(t), idle.offsets_at(t));
+
+        // 作为叠加轨道放在混合器里，偏移加到基础姿态上
+        let base = JointAngles([10.0; 6]);
+        let mut mixer = AnimationMixer::new(base.clone()).interval(Duration::from_millis(50));
+        mixer.add_track(
+            "idle",
+            IdleMotion::new(options).interval(Duration::from_millis(50)),
+            TrackOptions::new().additive(),
+        );
+        mixer.next_frame(&mut buffer);
+        let offsets = same.offsets_at(Duration::from_millis(50));
+        for joint in 0..6 {
+            assert!((mixer.pose().0[joint] - 10.0 - offsets.0[joint]).abs() < 1e-4);
+        }
+
+        let mut standalone = IdleMotion::new(options).base(base);
+        standalone.next_frame(&mut buffer);
+        standalone.reset();
+        assert_eq!(standalone.joint_angles().unwrap().0, [10.0; 6]);
     }
 
     #[test]
-    fn test_extra_data_new() {
-        let extra = ExtraData::new();
-        assert_eq!(extra.as_data().len(), 32);
-        assert!(!extra.is_enabled());
+    fn test_gesture_playback() {
+        use std::future::Future;
+        use std::time::Duration;
+
+        let transport = RecordingTransport::default();
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(transport)).unwrap();
+        let handle = bot.into_handle().unwrap();
+        let timeout = Duration::from_secs(5);
+        let short = |name: &str, angle: f32| {
+            Gesture::new(name, JointAngles::new())
+                .then([0.0, 0.0, angle, 0.0, 0.0, 0.0], Duration::from_millis(60))
+        };
+
+        // 排队的动作依次播放完
+        let first = handle.play_gesture_with(short("first", 10.0), GestureMode::Queue);
+        let second = handle.play_gesture_with(short("second", 20.0), GestureMode::Queue);
+        assert_eq!(second.name(), "second");
+        assert_eq!(second.wait_timeout(timeout), Some(GestureOutcome::Finished));
+        assert_eq!(first.outcome(), Some(GestureOutcome::Finished));
+        assert!(handle.current_gesture().is_none());
+
+        // 打断当前动作，取消排队的动作
+        let long = Gesture::new("long", JointAngles::new())
+            .then([0.0, 0.0, 30.0, 0.0, 0.0, 0.0], Duration::from_secs(60));
+        let long = handle.play_gesture_with(long, GestureMode::Queue);
+        let queued = handle.play_gesture("wave").unwrap();
+        let deadline = std::time::Instant::now() + timeout;
+        while handle.current_gesture().is_none() && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(handle.current_gesture().as_deref(), Some("long"));
+        queued.cancel();
+        assert_eq!(queued.outcome(), Some(GestureOutcome::Cancelled));
+        let last = handle.play_gesture_with(short("last", 5.0), GestureMode::Interrupt);
+        assert_eq!(
+            long.wait_timeout(timeout),
+            Some(GestureOutcome::Interrupted)
+        );
+
+        // 作为 Future 等待
+        struct Noop;
+        impl std::task::Wake for Noop {
+            fn wake(self: std::sync::Arc<Self>) {}
+        }
+        let waker = std::task::Waker::from(std::sync::Arc::new(Noop));
+        let mut cx = std::task::Context::from_waker(&waker);
+        let mut future = last.clone();
+        let outcome = loop {
+            if let std::task::Poll::Ready(outcome) = std::pin::Pin::new(&mut future).poll(&mut cx) {
+                break outcome;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        };
+        assert_eq!(outcome, GestureOutcome::Finished);
+        assert!(handle.play_gesture("dance").is_err());
+
+        // 停止同步线程时结束所有动作
+        let pending = handle.play_gesture("wave").unwrap();
+        let bot = handle.stop().unwrap();
+        assert_eq!(pending.wait(), GestureOutcome::Stopped);
+        assert!(bot.target_joint_angles().0[2] <= 30.0);
+        let after = handle.play_gesture("nod").unwrap();
+        assert_eq!(after.outcome(), Some(GestureOutcome::Stopped));
     }
 
     #[test]
-    fn test_extra_data_enable() {
-        let mut extra = ExtraData::new();
-        extra.set_enable(true);
-        assert!(extra.is_enabled());
-        extra.set_enable(false);
-        assert!(!extra.is_enabled());
+    fn test_emergency_stop() {
+        use std::sync::{Arc, Mutex};
+        use std::time::Duration;
+
+        /// 发送若干个包后触发急停的模拟设备。
+        struct StopAfter {
+            inner: RecordingTransport,
+            estop: EmergencyStop,
+            after: usize,
+        }
+
+        impl Transport for StopAfter {
+            fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+                if self.inner.sent.lock().unwrap().len() + 1 == self.after {
+                    self.estop.trigger();
+                }
+                self.inner.transmit(data)
+            }
+
+            fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+                self.inner.receive(data)
+            }
+        }
+
+        let transport = RecordingTransport::default();
+        let sent = transport.sent.clone();
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(transport)).unwrap();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let log = events.clone();
+        bot.on_event(move |event| log.lock().unwrap().push(event.clone()));
+        let enable_flag =
+            |sent: &Arc<Mutex<Vec<Vec<u8>>>>| sent.lock().unwrap().last().unwrap()[192];
+
+        bot.set_joint_angles(&[0.0, 0.0, 30.0, 0.0, 0.0, 0.0], true)
+            .unwrap();
+        bot.sync().unwrap();
+        assert_eq!(enable_flag(&sent), 1);
+
+        // 急停立即发送失能，之后的使能被忽略
+        let before = sent.lock().unwrap().len();
+        bot.emergency_stop().unwrap();
+        assert!(bot.is_emergency_stopped());
+        assert!(sent.lock().unwrap().len() > before);
+        assert_eq!(enable_flag(&sent), 0);
+        assert_eq!(*events.lock().unwrap(), [Event::EmergencyStop]);
+        bot.set_joint_angles(&[0.0, 0.0, 30.0, 0.0, 0.0, 0.0], true)
+            .unwrap();
+        bot.sync().unwrap();
+        assert_eq!(enable_flag(&sent), 0);
+
+        // 播放和自检被拒绝，但仍然发送一次失能
+        let before = sent.lock().unwrap().len();
+        let mut wave = Gesture::named("wave").unwrap();
+        assert!(matches!(
+            bot.play(&mut wave, None),
+            Err(BotError::EmergencyStop)
+        ));
+        assert_eq!(sent.lock().unwrap().len() - before, 4 * 85);
+        assert!(matches!(bot.self_test(), Err(BotError::EmergencyStop)));
+
+        // 解除后舵机保持失能，重新使能后恢复
+        bot.clear_emergency_stop();
+        bot.sync().unwrap();
+        assert_eq!(enable_flag(&sent), 0);
+        bot.set_joint_angles(&[0.0; 6], true).unwrap();
+        bot.sync().unwrap();
+        assert_eq!(enable_flag(&sent), 1);
+
+        // 其他线程在同步中途触发：中止后重新发送失能
+        let mut bot = ElectronBot::new();
+        let inner = RecordingTransport::default();
+        let sent = inner.sent.clone();
+        bot.connect_transport(Box::new(StopAfter {
+            inner,
+            estop: bot.emergency_stop_handle(),
+            after: 10,
+        }))
+        .unwrap();
+        bot.set_joint_angles(&[0.0; 6], true).unwrap();
+        let before = sent.lock().unwrap().len();
+        assert!(bot.sync().unwrap());
+        assert_eq!(sent.lock().unwrap().len() - before, 10 + 4 * 85);
+        assert_eq!(enable_flag(&sent), 0);
+
+        // 句柄急停中止动作
+        let transport = RecordingTransport::default();
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(transport)).unwrap();
+        let handle = bot.into_handle().unwrap();
+        let long = Gesture::new("long", JointAngles::new())
+            .then([0.0, 0.0, 30.0, 0.0, 0.0, 0.0], Duration::from_secs(60));
+        let playback = handle.play_gesture_with(long, GestureMode::Queue);
+        handle.emergency_stop();
+        assert_eq!(
+            playback.wait_timeout(Duration::from_secs(5)),
+            Some(GestureOutcome::EmergencyStop)
+        );
+        assert_eq!(
+            handle.play_gesture("nod").unwrap().outcome(),
+            Some(GestureOutcome::EmergencyStop)
+        );
+        handle.flush(Duration::from_secs(5)).unwrap();
+        let bot = handle.stop().unwrap();
+        assert!(bot.is_emergency_stopped());
+        assert_eq!(bot.get_extra_data()[0], 0);
     }
 
     #[test]
-    fn test_extra_data_joint_angles() {
-        let mut extra = ExtraData::new();
-        let angles = JointAngles::new();
-        extra.set_joint_angles(&angles, true);
-        assert!(extra.is_enabled());
-        let restored = extra.get_joint_angles();
-        assert_eq!(restored.0, [0.0; 6]);
+    fn test_soft_start() {
+        use std::time::Duration;
+
+        /// 舵机停在固定姿态、上报该姿态的模拟设备。
+        struct Parked {
+            inner: RecordingTransport,
+            reply: [u8; 32],
+        }
+
+        impl Transport for Parked {
+            fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+                self.inner.transmit(data)
+            }
+
+            fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+                data[..32].copy_from_slice(&self.reply);
+                Ok(32)
+            }
+        }
+
+        let mut parked = ExtraData::new();
+        parked.set_joint_angles(&JointAngles([0.0, 0.0, -40.0, 0.0, -40.0, 0.0]), false);
+        let inner = RecordingTransport::default();
+        let sent = inner.sent.clone();
+        let last_sent = || {
+            let mut data = ExtraData::new();
+            data.set_raw(&sent.lock().unwrap().last().unwrap()[192..]);
+            data
+        };
+        let mut bot = ElectronBot::new();
+        bot.set_soft_start(Some(Duration::from_millis(200)));
+        assert_eq!(bot.soft_start(), Some(Duration::from_millis(200)));
+        bot.connect_transport(Box::new(Parked {
+            inner,
+            reply: *parked.get_raw(),
+        }))
+        .unwrap();
+        let target = [0.0, 0.0, 30.0, 0.0, 30.0, 0.0];
+        bot.set_joint_angles(&target, true).unwrap();
+
+        // 还没有上报角度：先失能同步一次
+        bot.sync().unwrap();
+        assert!(!last_sent().is_enabled());
+        assert!(bot.extra_data().is_enabled());
+
+        // 从上报的角度开始过渡
+        bot.sync().unwrap();
+        assert!(bot.is_soft_starting());
+        assert!(last_sent().is_enabled());
+        assert!((last_sent().get_joint_angles().0[2] + 40.0).abs() < 5.0);
+        std::thread::sleep(Duration::from_millis(100));
+        bot.sync().unwrap();
+        let middle = last_sent().get_joint_angles().0[2];
+        assert!(middle > -40.0 && middle < 30.0, "{}", middle);
+        std::thread::sleep(Duration::from_millis(150));
+        bot.sync().unwrap();
+        assert!(!bot.is_soft_starting());
+        assert_eq!(last_sent().get_joint_angles().0, target);
+        assert_eq!(bot.target_joint_angles().0, target);
+
+        // 失能后再使能重新过渡，已经有上报角度时直接开始
+        bot.set_joint_angles(&target, false).unwrap();
+        bot.sync().unwrap();
+        bot.set_joint_angles(&target, true).unwrap();
+        bot.sync().unwrap();
+        assert!(bot.is_soft_starting());
+        assert!(last_sent().get_joint_angles().0[2] < 0.0);
+
+        // 关闭后直接发送目标角度
+        bot.set_soft_start(None);
+        bot.sync().unwrap();
+        assert_eq!(last_sent().get_joint_angles().0, target);
     }
 
     #[test]
-    fn test_extra_data_bytes() {
-        let mut extra = ExtraData::new();
-        extra.set_byte(0, 0xAB);
-        assert_eq!(extra.get_byte(0), Some(0xAB));
-        extra.set_u16(1, 0x1234);
-        assert_eq!(extra.get_u16(1), Some(0x1234));
+    fn test_servo_tuning() {
+        /// 只支持速度、一条消息发送 6 个关节（每单位 4 度每秒）的固件格式。
+        struct SpeedTable;
+
+        impl TuningEncoder for SpeedTable {
+            fn encode(&self, tuning: &ServoTuning) -> Result<Vec<TuningMessage>, String> {
+                let mut message = [0; TUNING_LEN];
+                for (byte, joint) in message.iter_mut().zip(tuning.joints()) {
+                    if joint.pid.is_some() || joint.acceleration.is_some() {
+                        return Err("只支持速度".to_string());
+                    }
+                    *byte = joint
+                        .speed
+                        .map_or(0, |speed| (speed / 4.0).min(255.0) as u8);
+                }
+                Ok(vec![message])
+            }
+        }
+
+        let transport = RecordingTransport::default();
+        let sent = transport.sent.clone();
+        let reserved = || sent.lock().unwrap().last().unwrap()[192 + TUNING_OFFSET..].to_vec();
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(transport)).unwrap();
+        bot.set_joint_angles(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], true)
+            .unwrap();
+
+        // 默认编码：每条消息一个参数，每次同步发送一条
+        let tuning = ServoTuning::new()
+            .speed(1, 90.0)
+            .pid(2, Pid::new(1.5, 0.25, 0.5));
+        assert!(!tuning.is_empty());
+        assert_eq!(bot.send_servo_tuning(&tuning).unwrap(), 4);
+        let mut messages = Vec::new();
+        while bot.pending_tuning_messages() > 0 {
+            bot.sync().unwrap();
+            messages.push(reserved());
+        }
+        let expect = |param: u8, joint: u8, value: f32| {
+            let mut message = vec![param, joint];
+            message.extend(value.to_le_bytes());
+            message.push(0);
+            message
+        };
+        assert_eq!(
+            messages,
+            [
+                expect(ParamEncoder::SPEED, 1, 90.0),
+                expect(ParamEncoder::KP, 2, 1.5),
+                expect(ParamEncoder::KI, 2, 0.25),
+                expect(ParamEncoder::KD, 2, 0.5),
+            ]
+        );
+        // 消息发送完后保留字节恢复，角度不受影响
+        bot.sync().unwrap();
+        assert_eq!(reserved(), [0; 7]);
+        assert_eq!(bot.target_joint_angles().0, [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert!(matches!(
+            bot.send_servo_tuning(&ServoTuning::new().speed(0, f32::NAN)),
+            Err(BotError::ConfigError(_))
+        ));
+
+        // 自定义编码器
+        bot.set_tuning_encoder(SpeedTable);
+        assert_eq!(
+            bot.send_servo_tuning(&ServoTuning::new().speed_all(200.0).speed(5, 40.0))
+                .unwrap(),
+            1
+        );
+        bot.sync().unwrap();
+        assert_eq!(reserved(), [50, 50, 50, 50, 50, 10, 0]);
+        assert!(bot
+            .send_servo_tuning(&ServoTuning::new().acceleration(0, 1.0))
+            .is_err());
+
+        // 断开连接时丢弃没有发送的消息
+        bot.send_servo_tuning(&ServoTuning::new()).unwrap();
+        bot.disconnect();
+        assert_eq!(bot.pending_tuning_messages(), 0);
     }
 
     #[test]
-    fn test_sync_context_new() {
-        let ctx = SyncContext::new();
-        assert_eq!(ctx.timestamp, 0);
-        assert_eq!(ctx.ping_pong_index, 0);
-        assert_eq!(ctx.cycles, 4);
+    fn test_auto_resume() {
+        let angles = [10.0, 0.0, 20.0, 0.0, -20.0, 5.0];
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(
+            FakeMcu::new().fail_transmit(90, Fault::Disconnect),
+        ))
+        .unwrap();
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = std::sync::Arc::clone(&events);
+        bot.on_event(move |event| sink.lock().unwrap().push(event.clone()));
+        bot.set_image_color(Color::Blue);
+        bot.set_joint_angles(&angles, true).unwrap();
+        bot.send_servo_tuning(&ServoTuning::new().speed(1, 90.0))
+            .unwrap();
+
+        // 模拟 `connect` 打开的 USB 设备，第一次重新打开时设备还没有重新枚举
+        let resumed = FakeMcu::new();
+        let state = resumed.state();
+        let mut pending = Some(resumed);
+        let mut attempts = 0;
+        bot.reopen = Some(Box::new(move |variants| {
+            attempts += 1;
+            match pending.take() {
+                Some(mcu) if attempts > 1 => {
+                    Ok((Box::new(mcu) as Box<dyn Transport>, variants[0].clone()))
+                }
+                mcu => {
+                    pending = mcu;
+                    Err(BotError::DeviceNotFound(variants[0].vid, variants[0].pid))
+                }
+            }
+        }));
+
+        // 未开启时直接返回设备丢失
+        assert!(bot.sync().unwrap_err().is_device_lost());
+        assert!(!events.lock().unwrap().contains(&Event::Resumed));
+
+        // 开启后重新打开设备，恢复舵机参数并重新发送画面和姿态
+        bot.set_auto_resume(Some(std::time::Duration::from_secs(5)));
+        assert!(bot.sync().unwrap());
+        assert!(bot.is_connected());
+        assert!(events.lock().unwrap().contains(&Event::Resumed));
+        assert_eq!(bot.recent_events().of_kind(LogKind::Resumed).count(), 1);
+        {
+            let state = state.lock().unwrap();
+            assert_eq!(state.frames, 1);
+            assert_eq!(state.frame.as_deref(), Some(bot.output_frame().as_data()));
+            let extra = state.extra_data.as_ref().unwrap();
+            assert_eq!(extra.get_joint_angles().0, angles);
+            assert_eq!(extra.as_data()[TUNING_OFFSET], ParamEncoder::SPEED);
+        }
+
+        // 断开后不再恢复
+        bot.disconnect();
+        assert!(bot.reopen.is_none());
     }
 
     #[test]
-    fn test_sync_context_toggle() {
-        let mut ctx = SyncContext::new();
-        assert_eq!(ctx.ping_pong_index, 0);
-        ctx.toggle();
-        assert_eq!(ctx.ping_pong_index, 1);
-        ctx.toggle();
-        assert_eq!(ctx.ping_pong_index, 0);
+    fn test_collision_guard() {
+        let guard = CollisionGuard::new();
+        // 内置姿态和动作都不会碰撞
+        for name in POSE_NAMES {
+            assert!(
+                guard.check(&JointAngles::named(name).unwrap()).is_ok(),
+                "{}",
+                name
+            );
+        }
+        for name in GESTURE_NAMES {
+            let gesture = Gesture::named(name).unwrap();
+            let mut t = std::time::Duration::ZERO;
+            while t <= gesture.duration() {
+                assert!(guard.check(&gesture.pose_at(t)).is_ok(), "{} {:?}", name, t);
+                t += std::time::Duration::from_millis(20);
+            }
+        }
+
+        // 手臂内收打到身体，抬过头顶内收打到头部
+        let into_body = JointAngles([0.0, -25.0, 0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(
+            guard.check(&into_body),
+            Err(Collision {
+                arm: Arm::Left,
+                part: BodyPart::Body
+            })
+        );
+        let into_head = JointAngles([0.0, 0.0, 0.0, -20.0, 170.0, 0.0]);
+        assert_eq!(
+            guard.check(&into_head).unwrap_err().to_string(),
+            "右臂会碰到头部"
+        );
+        let hand = guard.model.hand(Arm::Right, &JointAngles::new());
+        assert_eq!(hand, [24.0, -30.0, 0.0]);
+
+        // 限制模式只调整碰撞手臂的展开角度
+        let clamped = guard.clamp(&into_body);
+        assert!(guard.check(&clamped).is_ok());
+        assert!(
+            clamped.0[1] < -5.0 && clamped.0[1] > -25.0,
+            "{}",
+            clamped.0[1]
+        );
+        assert_eq!(clamped.0[2..], into_body.0[2..]);
+
+        // 拒绝模式下 set_joint_angles 返回错误，目标不变
+        let transport = RecordingTransport::default();
+        let sent = transport.sent.clone();
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(transport)).unwrap();
+        bot.set_collision_guard(Some(guard));
+        assert!(matches!(
+            bot.set_joint_angles(into_body.as_array(), true),
+            Err(BotError::Collision(_))
+        ));
+        assert_eq!(bot.target_joint_angles(), JointAngles::new());
+        bot.set_collision_guard(Some(guard.mode(GuardMode::Clamp)));
+        bot.set_joint_angles(into_body.as_array(), true).unwrap();
+        assert_eq!(bot.target_joint_angles(), clamped);
+
+        // 绕过 set_joint_angles 的角度在发送前被限制
+        bot.set_collision_guard(Some(guard));
+        bot.extra_data().set_joint_angles(&into_head, true);
+        bot.sync().unwrap();
+        let mut last = ExtraData::new();
+        last.set_raw(&sent.lock().unwrap().last().unwrap()[192..]);
+        assert!(last.is_enabled());
+        assert!(guard.check(&last.get_joint_angles()).is_ok());
+        assert_eq!(last.get_joint_angles().0[4], 170.0);
+        assert_eq!(bot.target_joint_angles(), into_head);
+
+        bot.set_collision_guard(None);
+        assert!(bot.collision_guard().is_none());
+        bot.set_joint_angles(into_body.as_array(), true).unwrap();
     }
 
     #[test]
-    #[allow(unused_comparisons)]
-    fn test_scan_devices() {
-        let devices = ElectronBot::scan_devices();
-        assert!(devices.len() >= 0);
+    fn test_simple_bot() {
+        /// 前几次接收失败的传输
+        struct Flaky {
+            inner: RecordingTransport,
+            failures: usize,
+        }
+
+        impl Transport for Flaky {
+            fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+                self.inner.transmit(data)
+            }
+
+            fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+                if self.failures > 0 {
+                    self.failures -= 1;
+                    return Err("timeout".to_string());
+                }
+                self.inner.receive(data)
+            }
+        }
+
+        let inner = RecordingTransport::default();
+        let sent = inner.sent.clone();
+        let mut bot = ElectronBot::new();
+        bot.connect_transport(Box::new(Flaky { inner, failures: 5 }))
+            .unwrap();
+        // 第一次同步的接收全部失败，重试后成功
+        let mut simple = SimpleBot::from_bot(bot).speed(600.0);
+        simple.show_color(Color::Red).unwrap();
+        // 与 `test_image_buffer_clear` 相同，红色读回为 Custom(0, 0, 255)
+        let red = Some(Color::Custom(0, 0, 255));
+        let frames = sent.lock().unwrap().len() / 340;
+        assert!(frames >= 1);
+        assert_eq!(simple.bot_mut().image_buffer().get_pixel(0, 0), red);
+
+        assert!(matches!(
+            simple.say_face("confused"),
+            Err(BotError::ConfigError(_))
+        ));
+        simple.say_face("happy").unwrap();
+        assert!(sent.lock().unwrap().len() / 340 > frames);
+        assert_ne!(simple.bot_mut().image_buffer().get_pixel(0, 0), red);
+
+        // 超出范围的角度被限制，其他关节不变
+        simple
+            .bot_mut()
+            .set_joint_angles(&[0.0, 0.0, 10.0, 0.0, 0.0, 0.0], true)
+            .unwrap();
+        simple.move_head(100.0, -20.0).unwrap();
+        assert_eq!(
+            simple.bot().target_joint_angles(),
+            JointAngles([15.0, 0.0, 10.0, 0.0, 0.0, -20.0])
+        );
+        let mut last = ExtraData::new();
+        last.set_raw(&sent.lock().unwrap().last().unwrap()[192..]);
+        assert_eq!(last.get_joint_angles(), simple.bot().target_joint_angles());
+
+        assert_eq!(LookDirection::named("right"), Some(LookDirection::Right));
+        simple.look(LookDirection::Right).unwrap();
+        assert_eq!(simple.bot().target_joint_angles().0[5], 30.0);
+        simple.look(LookDirection::Center).unwrap();
+
+        let before = simple.bot().target_joint_angles();
+        assert!(simple.gesture("juggle").is_err());
+        assert_eq!(simple.bot().target_joint_angles(), before);
+
+        // 不能重新连接的传输断开后直接返回错误
+        let mut bot = simple.into_inner();
+        bot.disconnect();
+        let mut simple = SimpleBot::from_bot(bot);
+        assert!(matches!(
+            simple.show_color(Color::Blue),
+            Err(BotError::NotConnected)
+        ));
     }
 
     #[test]
-    fn test_is_device_present() {
-        let _present = ElectronBot::is_device_present();
+    fn test_frame_builder() {
+        let sprite = Sprite::from_bgr(4, 4, vec![200; 48]).unwrap();
+        let frame = Frame::new()
+            .background(Color::Blue)
+            .rect(Rect::new(-10, -10, 30, 30), Color::Green)
+            .circle((120, 120), 20, Color::White)
+            .line((0, 239), (239, 239), 1, Color::Red)
+            .polygon(&[(200, 10), (230, 10), (215, 40)], Color::Yellow)
+            .sprite(&sprite, 100, 200)
+            .pixel(-1, 5, Color::Red)
+            .with(|buffer| buffer.set_pixel(239, 0, Color::Magenta));
+
+        // 与逐个调用绘图函数的结果相同
+        let mut expected = ImageBuffer::new();
+        expected.clear(Color::Blue);
+        expected.fill_rounded_rect(Rect::new(-10, -10, 30, 30), 0, Color::Green);
+        expected.fill_ellipse((120, 120), 20, 20, Color::White);
+        expected.draw_line((0, 239), (239, 239), 1, Color::Red);
+        expected.fill_polygon(&[(200, 10), (230, 10), (215, 40)], Color::Yellow);
+        expected.blit(&sprite, 100, 200);
+        expected.set_pixel(239, 0, Color::Magenta);
+        assert_eq!(frame.buffer().as_data(), expected.as_data());
+
+        let buffer: ImageBuffer = frame.into();
+        assert_eq!(buffer.get_pixel(0, 0), Some(Color::Custom(0, 255, 0)));
+        assert_eq!(buffer.get_pixel(25, 25), Some(Color::Custom(255, 0, 0)));
+        assert_eq!(
+            buffer.get_pixel(120, 120),
+            Some(Color::Custom(255, 255, 255))
+        );
+        assert_eq!(
+            buffer.get_pixel(101, 201),
+            Some(Color::Custom(200, 200, 200))
+        );
+
+        // 在已有画面上继续绘制
+        let next = Frame::from_buffer(buffer.clone())
+            .rect_outline(Rect::new(50, 50, 20, 20), 1, Color::Black)
+            .build();
+        assert_eq!(next.get_pixel(50, 60), Some(Color::Custom(0, 0, 0)));
+        assert_eq!(next.get_pixel(60, 60), buffer.get_pixel(60, 60));
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_quick_test_function() {
-        let result = quick_test();
-        assert!(result.is_ok() || result.is_err());
+    fn test_connect_with_invalid_fd() {
+        let mut bot = ElectronBot::new();
+        let result = unsafe { bot.connect_with_fd(-1) };
+        assert!(matches!(result, Err(BotError::UsbError(_))));
+        assert!(!bot.is_connected());
+        assert!(bot.connected_variant().is_none());
     }
 
     #[test]
-    fn test_list_devices_function() {
-        list_devices();
+    fn test_parse_device_path() {
+        assert_eq!(
+            modules::usb::parse_device_path(
+                r"\\?\usb#vid_1001&pid_8023#6&1a2b3c4d&0&2#{a5dcbf10-6530-11d2-901f-00c04fb951ed}"
+            ),
+            Some((0x1001, 0x8023))
+        );
+        assert_eq!(
+            modules::usb::parse_device_path(r"\\?\USB#VID_ABCD&PID_00EF#1"),
+            Some((0xabcd, 0x00ef))
+        );
+        assert_eq!(
+            modules::usb::parse_device_path(r"\\?\usb#vid_10&pid_8023"),
+            None
+        );
+        assert_eq!(modules::usb::parse_device_path(r"\\?\hid#vid_1001"), None);
+
+        let mut bot = ElectronBot::new();
+        assert_eq!(bot.usb_backend(), UsbBackend::Auto);
+        bot.set_usb_backend(UsbBackend::WinUsb);
+        assert_eq!(bot.usb_backend(), UsbBackend::WinUsb);
     }
 }
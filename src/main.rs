@@ -0,0 +1,785 @@
+//! ElectronBot 命令行工具
+//!
+//! ```bash
+//! cargo install --path .
+//! electronbot scan
+//! electronbot display face.png --fit contain
+//! electronbot color "#ff8800"
+//! electronbot pose wave
+//! electronbot joints 0 0 30 0 30 0
+//! electronbot record wave.json --seconds 5
+//! electronbot play wave.json
+//! electronbot preview face.png --width 60
+//! electronbot assets ./assets --play blink
+//! electronbot monitor --preview 48
+//! electronbot benchmark --seconds 10
+//! electronbot stats show
+//! sudo electronbot udev --install
+//! ```
+//!
+//! `electronbot help` 列出所有命令和选项。
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use electron_bot::{
+    Assets, BotError, Color, ElectronBot, FakeMcu, FilterKind, FitMode, FitOptions, Gesture,
+    ImageBuffer, JointAngles, SelfTestOptions, UsageStats, GESTURE_NAMES, POSE_NAMES,
+};
+
+const USAGE: &str = "\
+ElectronBot 命令行工具
+
+用法: electronbot <命令> [参数]
+
+命令:
+  scan                              列出 USB 设备
+  info                              显示设备信息和当前关节角度
+  display <图片> [--fit <方式>]     显示图片（stretch/contain/cover/center）
+  color <颜色>                      纯色填充，例如 #ff8800
+  text <文字> [--size <字号>] [--font <字体>]
+                                    显示文字
+  pose <名称>                       摆出内置姿态或播放内置动作
+  joints <a> <b> <c> <d> <e> <f>    设置 6 个关节角度（度）
+  play <动作.json> [--repeat <次数>]
+                                    播放动作文件
+  record <动作.json> [--seconds <秒>] [--interval <毫秒>] [--median <采样数>]
+                                    释放舵机，记录手动摆动的关节角度（可选中值滤波去抖）
+  preview <图片> [--width <列数>] [--fit <方式>]
+                                    在终端预览图片，不需要连接设备
+  assets <目录> [--fit <方式>] [--play <名称>] [--repeat <次数>]
+                                    预加载目录中的图片和动画并列出，或播放其中一项
+  monitor [--interval <毫秒>] [--preview <列数>]
+                                    持续显示关节角度和同步帧率，可同时预览屏幕画面
+  selftest [--no-joints]            组装后自检：屏幕颜色、关节到位和吞吐量
+  benchmark [--seconds <秒>] [--fake]
+                                    尽快发送画面，报告帧率、吞吐量、重试和延迟（--fake 使用模拟 MCU）
+  flash <固件.bin> [--address <地址>] [--no-verify] [--reboot]
+                                    通过 bootloader 更新固件（需要 dfu feature）
+  udev [--install] [--yes]          检查设备访问权限，打印或安装 udev 规则（Linux）
+  stats [show|reset|path]           显示、清空累计使用统计，或打印统计文件路径
+  help                              显示本帮助
+
+全局选项:
+  --remote <地址>                   通过网络桥接连接（需要 bridge feature）
+
+环境变量:
+  ELECTRONBOT_STATS                 使用统计文件路径（默认 ~/.electronbot_stats）";
+
+fn main() -> ExitCode {
+    #[cfg(feature = "logging")]
+    env_logger::init();
+
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&mut args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &mut Vec<String>) -> Result<(), String> {
+    let remote = take_option(args, "--remote")?;
+    if args.is_empty() {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    let command = args.remove(0);
+    let connect = || open(remote.as_deref());
+
+    match command.as_str() {
+        "scan" => {
+            no_extra_args(args)?;
+            electron_bot::list_devices();
+            Ok(())
+        }
+        "info" => {
+            no_extra_args(args)?;
+            info(connect()?, remote.is_some())
+        }
+        "display" => {
+            let fit = take_option(args, "--fit")?
+                .map(|mode| parse_fit_mode(&mode))
+                .transpose()?;
+            let [path] = positional::<1>(args, "display <图片>")?;
+            let mut bot = connect()?;
+            if let Some(mode) = fit {
+                bot.set_fit_options(FitOptions::new(mode));
+            }
+            bot.set_image(&path).map_err(|e| e.to_string())?;
+            sync(&mut bot)
+        }
+        "color" => {
+            let [hex] = positional::<1>(args, "color <颜色>")?;
+            let color = Color::from_hex(&hex)?;
+            let mut bot = connect()?;
+            bot.set_image_color(color);
+            sync(&mut bot)
+        }
+        "text" => text(args, connect),
+        "pose" => {
+            let [name] = positional::<1>(args, "pose <名称>")?;
+            let mut bot = connect()?;
+            if let Some(pose) = JointAngles::named(&name) {
+                bot.set_joint_angles_easy(pose.as_array())
+                    .map_err(|e| e.to_string())?;
+                sync(&mut bot)
+            } else if let Some(mut gesture) = Gesture::named(&name) {
+                bot.play(&mut gesture, None).map_err(|e| e.to_string())?;
+                Ok(())
+            } else {
+                Err(format!(
+                    "未知的姿态: {}（姿态: {}；动作: {}）",
+                    name,
+                    POSE_NAMES.join(", "),
+                    GESTURE_NAMES.join(", ")
+                ))
+            }
+        }
+        "joints" => {
+            let values = positional::<6>(args, "joints <a> <b> <c> <d> <e> <f>")?;
+            let mut angles = [0.0f32; 6];
+            for (angle, value) in angles.iter_mut().zip(&values) {
+                *angle = value
+                    .parse()
+                    .map_err(|_| format!("无效的角度: {}", value))?;
+            }
+            let mut bot = connect()?;
+            bot.set_joint_angles_easy(&angles)
+                .map_err(|e| e.to_string())?;
+            sync(&mut bot)
+        }
+        "play" => {
+            let repeat = take_number(args, "--repeat")?.unwrap_or(1);
+            let [path] = positional::<1>(args, "play <动作.json>")?;
+            let mut gesture = Gesture::load_from_file(&path)?;
+            let mut bot = connect()?;
+            let running = interrupt_flag(&bot);
+            for _ in 0..repeat {
+                if !running.load(Ordering::SeqCst) {
+                    break;
+                }
+                gesture.restart();
+                match bot.play(&mut gesture, None) {
+                    Ok(_) => {}
+                    Err(BotError::Cancelled) => break,
+                    Err(e) => return Err(e.to_string()),
+                }
+            }
+            Ok(())
+        }
+        "record" => {
+            let seconds = take_number(args, "--seconds")?.unwrap_or(10);
+            let interval = take_number(args, "--interval")?.unwrap_or(100);
+            let median = take_number(args, "--median")?;
+            let [path] = positional::<1>(args, "record <动作.json>")?;
+            let mut bot = connect()?;
+            if let Some(window) = median {
+                bot.set_joint_filter(FilterKind::Median {
+                    window: window as usize,
+                });
+            }
+            record(
+                bot,
+                &path,
+                Duration::from_secs(seconds),
+                Duration::from_millis(interval.max(1)),
+            )
+        }
+        "preview" => {
+            let width = take_number(args, "--width")?.unwrap_or(60);
+            let fit = take_option(args, "--fit")?
+                .map(|mode| parse_fit_mode(&mode))
+                .transpose()?
+                .unwrap_or_default();
+            let [path] = positional::<1>(args, "preview <图片>")?;
+            let mut buffer = ImageBuffer::new();
+            buffer.load_from_file_with(&path, &FitOptions::new(fit))?;
+            print!("{}", buffer.render_ansi(width as usize));
+            Ok(())
+        }
+        "assets" => {
+            let fit = take_option(args, "--fit")?
+                .map(|mode| parse_fit_mode(&mode))
+                .transpose()?
+                .unwrap_or_default();
+            let play = take_option(args, "--play")?;
+            let repeat = take_number(args, "--repeat")?.unwrap_or(1);
+            let [dir] = positional::<1>(args, "assets <目录>")?;
+            let started = Instant::now();
+            let assets = Assets::loader(&dir)
+                .fit(FitOptions::new(fit))
+                .parallel(true)
+                .load()?;
+            match play {
+                Some(name) => play_asset(connect()?, &assets, &name, repeat),
+                None => {
+                    list_assets(&assets, started.elapsed());
+                    Ok(())
+                }
+            }
+        }
+        "monitor" => {
+            let interval = take_number(args, "--interval")?.unwrap_or(100);
+            let preview = take_number(args, "--preview")?;
+            no_extra_args(args)?;
+            monitor(
+                connect()?,
+                Duration::from_millis(interval),
+                preview.map(|w| w as usize),
+            )
+        }
+        "selftest" => {
+            let joints = !take_flag(args, "--no-joints");
+            no_extra_args(args)?;
+            let mut bot = connect()?;
+            let report = bot
+                .self_test_with(&SelfTestOptions::new().joints(joints))
+                .map_err(|e| e.to_string())?;
+            print!("{}", report);
+            if report.passed() {
+                Ok(())
+            } else {
+                Err(format!("自检未通过: {}", report.failures().join(", ")))
+            }
+        }
+        "benchmark" => {
+            let seconds = take_number(args, "--seconds")?.unwrap_or(10);
+            let fake = take_flag(args, "--fake");
+            no_extra_args(args)?;
+            let mut bot = if fake {
+                let mut bot = ElectronBot::new();
+                bot.connect_transport(Box::new(FakeMcu::new()))
+                    .map_err(|e| e.to_string())?;
+                bot
+            } else {
+                connect()?
+            };
+            let report = bot
+                .benchmark(Duration::from_secs(seconds))
+                .map_err(|e| e.to_string())?;
+            print!("{}", report);
+            Ok(())
+        }
+        "flash" => {
+            let address = take_option(args, "--address")?
+                .map(|value| {
+                    let digits = value.trim_start_matches("0x").trim_start_matches("0X");
+                    u32::from_str_radix(digits, 16).map_err(|_| format!("无效的地址: {}", value))
+                })
+                .transpose()?;
+            let verify = !take_flag(args, "--no-verify");
+            let reboot = take_flag(args, "--reboot");
+            let [path] = positional::<1>(args, "flash <固件.bin>")?;
+            let firmware = std::fs::read(&path).map_err(|e| format!("读取固件失败: {}", e))?;
+            flash(&firmware, address, verify, reboot.then_some(connect))
+        }
+        "udev" => {
+            let install = take_flag(args, "--install");
+            let yes = take_flag(args, "--yes");
+            no_extra_args(args)?;
+            udev(install, yes)
+        }
+        "stats" => {
+            let action = if args.is_empty() {
+                "show".to_string()
+            } else {
+                let [action] = positional::<1>(args, "stats [show|reset|path]")?;
+                action
+            };
+            stats(&action)
+        }
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(format!(
+            "未知的命令: {}（运行 electronbot help 查看帮助）",
+            other
+        )),
+    }
+}
+
+/// 连接本机的设备，或者通过网络桥接连接远程设备。
+fn open(remote: Option<&str>) -> Result<ElectronBot, String> {
+    let mut bot = ElectronBot::new();
+    match remote {
+        #[cfg(feature = "bridge")]
+        Some(addr) => bot.connect_remote(addr).map_err(|e| e.to_string())?,
+        #[cfg(not(feature = "bridge"))]
+        Some(_) => return Err("--remote 需要启用 bridge feature".to_string()),
+        None => bot.connect().map_err(|e| e.to_string())?,
+    };
+    // 统计文件损坏时不影响其他命令，断开时会用当前统计覆盖
+    if let Err(e) = bot.set_stats_path(stats_path()) {
+        eprintln!("警告: {}", e);
+    }
+    Ok(bot)
+}
+
+/// 使用统计文件路径：`ELECTRONBOT_STATS`，或者用户主目录下的 `.electronbot_stats`。
+fn stats_path() -> PathBuf {
+    if let Some(path) = std::env::var_os("ELECTRONBOT_STATS") {
+        return PathBuf::from(path);
+    }
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .unwrap_or_default()
+        .join(".electronbot_stats")
+}
+
+fn stats(action: &str) -> Result<(), String> {
+    let path = stats_path();
+    match action {
+        "show" => {
+            let stats = if path.exists() {
+                UsageStats::load_from_file(&path)?
+            } else {
+                UsageStats::new()
+            };
+            print!("{}", stats);
+            Ok(())
+        }
+        "reset" => {
+            if path.exists() {
+                std::fs::remove_file(&path).map_err(|e| format!("删除统计文件失败: {}", e))?;
+            }
+            println!("已清空使用统计");
+            Ok(())
+        }
+        "path" => {
+            println!("{}", path.display());
+            Ok(())
+        }
+        other => Err(format!("未知的 stats 操作: {}（show/reset/path）", other)),
+    }
+}
+
+/// 同步一次，按下 Ctrl+C 中止的同步不算错误。
+fn sync(bot: &mut ElectronBot) -> Result<(), String> {
+    match bot.sync() {
+        Ok(_) | Err(BotError::Cancelled) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn info(mut bot: ElectronBot, remote: bool) -> Result<(), String> {
+    // 同步一次以读取机器人上报的角度
+    sync(&mut bot)?;
+    println!("库版本: {}", env!("CARGO_PKG_VERSION"));
+    if !remote {
+        if let Some(variant) = bot.connected_variant() {
+            println!(
+                "设备: {:04x}:{:04x} (固件: {})",
+                variant.vid, variant.pid, variant.name
+            );
+        }
+    }
+    println!(
+        "关节角度: {}",
+        format_angles(bot.get_joint_angles().as_array())
+    );
+    Ok(())
+}
+
+#[cfg(any(feature = "text", feature = "embedded-font"))]
+fn text(
+    args: &mut Vec<String>,
+    connect: impl FnOnce() -> Result<ElectronBot, String>,
+) -> Result<(), String> {
+    use electron_bot::{Font, Notification, TextStyle};
+
+    let size = take_option(args, "--size")?
+        .map(|size| {
+            size.parse::<f32>()
+                .ok()
+                .filter(|s| *s > 0.0)
+                .ok_or_else(|| format!("无效的字号: {}", size))
+        })
+        .transpose()?
+        .unwrap_or(32.0);
+    let font_path = take_option(args, "--font")?;
+    reject_options(args)?;
+    if args.is_empty() {
+        return Err("用法: electronbot text <文字>".to_string());
+    }
+    let message = args.join(" ");
+
+    let font = match font_path {
+        #[cfg(feature = "text")]
+        Some(path) => Font::load_from_file(path)?,
+        #[cfg(not(feature = "text"))]
+        Some(_) => return Err("--font 需要启用 text feature".to_string()),
+        #[cfg(feature = "embedded-font")]
+        None => Font::embedded(),
+        #[cfg(not(feature = "embedded-font"))]
+        None => return Err("没有内置字体，请用 --font 指定字体文件".to_string()),
+    };
+    let card = Notification::text(
+        &message,
+        &font,
+        &TextStyle::new(size, Color::White),
+        Duration::ZERO,
+    );
+
+    let mut bot = connect()?;
+    *bot.image_buffer() = card.content().clone();
+    sync(&mut bot)
+}
+
+#[cfg(not(any(feature = "text", feature = "embedded-font")))]
+fn text(
+    _args: &mut Vec<String>,
+    _connect: impl FnOnce() -> Result<ElectronBot, String>,
+) -> Result<(), String> {
+    Err("显示文字需要启用 text 或 embedded-font feature".to_string())
+}
+
+/// 通过 STM32 bootloader 写入固件，`--reboot` 时先让机器人重启进入 bootloader。
+#[cfg(feature = "dfu")]
+fn flash(
+    firmware: &[u8],
+    address: Option<u32>,
+    verify: bool,
+    reboot: Option<impl FnOnce() -> Result<ElectronBot, String>>,
+) -> Result<(), String> {
+    use electron_bot::{DfuDevice, DfuStage, FlashOptions};
+
+    let mut device = if let Some(connect) = reboot {
+        connect()?.enter_bootloader().map_err(|e| e.to_string())?;
+        // 重启后 bootloader 需要一点时间完成枚举
+        let mut device = DfuDevice::open();
+        for _ in 0..20 {
+            if device.is_ok() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(250));
+            device = DfuDevice::open();
+        }
+        device?
+    } else {
+        DfuDevice::open()?
+    };
+
+    let mut options = FlashOptions::new().verify(verify);
+    if let Some(address) = address {
+        options = options.address(address);
+    }
+    let mut current = None;
+    device.flash(firmware, &options, &mut |progress| {
+        let name = match progress.stage {
+            DfuStage::Erase => "擦除",
+            DfuStage::Download => "写入",
+            DfuStage::Verify => "校验",
+        };
+        let percent = progress.done * 100 / progress.total.max(1);
+        if current != Some((progress.stage, percent)) {
+            current = Some((progress.stage, percent));
+            print!("\r{}: {:3}%", name, percent);
+            flush();
+            if progress.done == progress.total {
+                println!();
+            }
+        }
+    })?;
+    println!("固件更新完成");
+    Ok(())
+}
+
+#[cfg(not(feature = "dfu"))]
+fn flash(
+    _firmware: &[u8],
+    _address: Option<u32>,
+    _verify: bool,
+    _reboot: Option<impl FnOnce() -> Result<ElectronBot, String>>,
+) -> Result<(), String> {
+    Err("更新固件需要启用 dfu feature".to_string())
+}
+
+/// 检查设备访问权限并打印 udev 规则，`install` 时确认后写入规则文件。
+#[cfg(target_os = "linux")]
+fn udev(install: bool, yes: bool) -> Result<(), String> {
+    use electron_bot::platform::linux::{self, Permission};
+
+    let variants = ElectronBot::new().variants().to_vec();
+    match linux::check_permissions(&variants)? {
+        Permission::NotFound => println!("未找到设备"),
+        Permission::Granted(variant) => println!("设备 {} 可以访问", variant.name),
+        Permission::Denied(variant) => {
+            println!("设备 {} 被拒绝访问，需要安装 udev 规则", variant.name)
+        }
+    }
+    if !install {
+        println!("\n{} 的内容应为:\n", linux::UDEV_RULE_PATH);
+        print!("{}", linux::udev_rule(&variants));
+        println!("\n用 sudo electronbot udev --install 安装");
+        return Ok(());
+    }
+
+    let installed = linux::install_udev_rule(&variants, |path, rule| {
+        print!("将写入 {}:\n\n{}\n", path.display(), rule);
+        if yes {
+            return true;
+        }
+        print!("确认写入？[y/N] ");
+        flush();
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer).is_ok()
+            && matches!(answer.trim(), "y" | "Y" | "yes")
+    })?;
+    if installed {
+        println!("规则已安装，请重新插拔设备");
+    } else {
+        println!("已取消");
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn udev(_install: bool, _yes: bool) -> Result<(), String> {
+    Err("udev 规则只适用于 Linux".to_string())
+}
+
+/// 释放舵机，按固定间隔记录关节角度，保存为动作文件。
+fn record(
+    mut bot: ElectronBot,
+    path: &str,
+    length: Duration,
+    interval: Duration,
+) -> Result<(), String> {
+    let current = bot.get_joint_angles();
+    bot.set_joint_angles(current.as_array(), false)
+        .map_err(|e| e.to_string())?;
+    sync(&mut bot)?;
+
+    let name = std::path::Path::new(path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("recorded");
+    let mut gesture = Gesture::new(name, bot.get_joint_angles());
+    println!(
+        "舵机已释放，开始记录 {} 秒（按 Ctrl+C 提前结束）",
+        length.as_secs()
+    );
+
+    let running = interrupt_flag(&bot);
+    let start = Instant::now();
+    let mut next_at = start;
+    while running.load(Ordering::SeqCst) && start.elapsed() < length {
+        next_at += interval;
+        if let Some(wait) = next_at.checked_duration_since(Instant::now()) {
+            std::thread::sleep(wait);
+        }
+        sync(&mut bot)?;
+        if !running.load(Ordering::SeqCst) {
+            break;
+        }
+        let angles = bot.get_joint_angles();
+        print!("\r{}", format_angles(angles.as_array()));
+        flush();
+        gesture = gesture.then(angles.0, interval);
+    }
+    println!();
+
+    gesture.save_to_file(path)?;
+    println!(
+        "已保存 {} 个关键帧（{:.1} 秒）到 {}",
+        gesture.len(),
+        gesture.duration().as_secs_f32(),
+        path
+    );
+    Ok(())
+}
+
+/// 持续同步并显示关节角度和帧率，直到按下 Ctrl+C。
+///
+/// 指定 `preview` 时清屏，每次同步后从第二行开始重画 `preview` 列宽的屏幕画面。
+fn monitor(mut bot: ElectronBot, interval: Duration, preview: Option<usize>) -> Result<(), String> {
+    if preview.is_some() {
+        print!("\x1b[2J\x1b[H");
+    }
+    println!("按 Ctrl+C 退出");
+    let running = interrupt_flag(&bot);
+    let mut frames = 0u32;
+    let mut window = Instant::now();
+    let mut fps = 0.0;
+    while running.load(Ordering::SeqCst) {
+        let begin = Instant::now();
+        sync(&mut bot)?;
+        frames += 1;
+        let elapsed = window.elapsed();
+        if elapsed >= Duration::from_secs(1) {
+            fps = frames as f32 / elapsed.as_secs_f32();
+            frames = 0;
+            window = Instant::now();
+        }
+        if let Some(width) = preview {
+            print!("\x1b[2;1H{}", bot.output_frame().render_ansi(width));
+        }
+        print!(
+            "\r关节: {}  帧率: {:5.1}",
+            format_angles(bot.get_joint_angles().as_array()),
+            fps
+        );
+        flush();
+        if let Some(wait) = interval.checked_sub(begin.elapsed()) {
+            std::thread::sleep(wait);
+        }
+    }
+    println!();
+    Ok(())
+}
+
+/// 运行标志，按下 Ctrl+C 后变为 false，同时中止 `bot` 正在进行的同步（需要 ctrlc feature）。
+fn interrupt_flag(bot: &ElectronBot) -> Arc<AtomicBool> {
+    let running = Arc::new(AtomicBool::new(true));
+    #[cfg(feature = "ctrlc")]
+    {
+        let flag = running.clone();
+        let token = bot.cancellation_token();
+        let _ = ctrlc::set_handler(move || {
+            flag.store(false, Ordering::SeqCst);
+            token.cancel();
+        });
+    }
+    #[cfg(not(feature = "ctrlc"))]
+    let _ = bot;
+    running
+}
+
+fn list_assets(assets: &Assets, elapsed: Duration) {
+    for (name, asset) in assets.iter() {
+        if asset.is_animation() {
+            println!(
+                "{:<24} 动画 {} 帧，{:.1} 秒",
+                name,
+                asset.len(),
+                asset.duration().as_secs_f32()
+            );
+        } else {
+            println!("{:<24} 图片", name);
+        }
+    }
+    println!(
+        "共 {} 项，{:.1} MB，加载用时 {} 毫秒",
+        assets.len(),
+        assets.memory() as f32 / (1024.0 * 1024.0),
+        elapsed.as_millis()
+    );
+}
+
+fn play_asset(
+    mut bot: ElectronBot,
+    assets: &Assets,
+    name: &str,
+    repeat: u64,
+) -> Result<(), String> {
+    let Some(asset) = assets.get(name) else {
+        let names: Vec<&str> = assets.names().collect();
+        return Err(format!("没有素材: {}（可用: {}）", name, names.join(", ")));
+    };
+    if !asset.is_animation() {
+        bot.image_buffer()
+            .load_frame(asset.frame(0).expect("素材至少有一帧"));
+        return sync(&mut bot);
+    }
+    let running = interrupt_flag(&bot);
+    let mut source = asset.source().once();
+    for _ in 0..repeat {
+        if !running.load(Ordering::SeqCst) {
+            break;
+        }
+        source.restart();
+        match bot.play(&mut source, None) {
+            Ok(_) => {}
+            Err(BotError::Cancelled) => break,
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    Ok(())
+}
+
+fn format_angles(angles: &[f32; 6]) -> String {
+    let values: Vec<String> = angles.iter().map(|a| format!("{:6.1}", a)).collect();
+    format!("[{}]", values.join(", "))
+}
+
+fn flush() {
+    use std::io::Write;
+    let _ = std::io::stdout().flush();
+}
+
+fn parse_fit_mode(mode: &str) -> Result<FitMode, String> {
+    match mode {
+        "stretch" => Ok(FitMode::Stretch),
+        "contain" => Ok(FitMode::Contain),
+        "cover" => Ok(FitMode::Cover),
+        "center" => Ok(FitMode::Center),
+        _ => Err(format!(
+            "未知的适配方式: {}（stretch/contain/cover/center）",
+            mode
+        )),
+    }
+}
+
+/// 取出 `name <值>` 形式的选项。
+fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>, String> {
+    let Some(index) = args.iter().position(|a| a == name) else {
+        return Ok(None);
+    };
+    if index + 1 >= args.len() {
+        return Err(format!("{} 需要一个值", name));
+    }
+    let value = args.remove(index + 1);
+    args.remove(index);
+    Ok(Some(value))
+}
+
+fn take_flag(args: &mut Vec<String>, name: &str) -> bool {
+    match args.iter().position(|a| a == name) {
+        Some(index) => {
+            args.remove(index);
+            true
+        }
+        None => false,
+    }
+}
+
+fn take_number(args: &mut Vec<String>, name: &str) -> Result<Option<u64>, String> {
+    take_option(args, name)?
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| format!("{} 需要非负整数，收到 {}", name, value))
+        })
+        .transpose()
+}
+
+/// 取出剩余的 `N` 个位置参数，数量不符时返回用法提示。
+fn positional<const N: usize>(args: &mut Vec<String>, usage: &str) -> Result<[String; N], String> {
+    reject_options(args)?;
+    std::mem::take(args)
+        .try_into()
+        .map_err(|_| format!("用法: electronbot {}", usage))
+}
+
+fn no_extra_args(args: &[String]) -> Result<(), String> {
+    reject_options(args)?;
+    match args.first() {
+        Some(extra) => Err(format!("多余的参数: {}", extra)),
+        None => Ok(()),
+    }
+}
+
+fn reject_options(args: &[String]) -> Result<(), String> {
+    match args.iter().find(|a| a.starts_with("--")) {
+        Some(option) => Err(format!("未知的选项: {}", option)),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,253 @@
+//! ElectronBot 库的抗锯齿光栅化（Wu 直线、覆盖率描边、子扫描线填充、超采样椭圆）。
+//!
+//! 像素中心位于整数坐标，像素 `(x, y)` 覆盖 `[x-0.5, x+0.5] x [y-0.5, y+0.5]`。
+
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_WIDTH};
+use crate::modules::draw::PointF;
+use crate::modules::image::ImageBuffer;
+use crate::modules::types::Color;
+
+/// 多边形填充时每个像素行的子扫描线数。
+const SUBSCANLINES: usize = 4;
+
+/// 椭圆超采样时每个方向的采样数。
+const SUPERSAMPLE: usize = 4;
+
+/// 裁剪到屏幕内的包围盒 `(x0, y0, x1, y1)`（含端点），完全在屏幕外时返回 `None`。
+fn clip_bounds(min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> Option<(i32, i32, i32, i32)> {
+    let x0 = (min_x.floor() as i32).max(0);
+    let y0 = (min_y.floor() as i32).max(0);
+    let x1 = (max_x.ceil() as i32).min(FRAME_WIDTH as i32 - 1);
+    let y1 = (max_y.ceil() as i32).min(FRAME_HEIGHT as i32 - 1);
+    if x0 > x1 || y0 > y1 {
+        None
+    } else {
+        Some((x0, y0, x1, y1))
+    }
+}
+
+/// 点到线段的距离。
+fn distance_to_segment(p: PointF, a: PointF, b: PointF) -> f32 {
+    let (abx, aby) = (b.0 - a.0, b.1 - a.1);
+    let (apx, apy) = (p.0 - a.0, p.1 - a.1);
+    let len2 = abx * abx + aby * aby;
+    let t = if len2 > 0.0 {
+        ((apx * abx + apy * aby) / len2).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (dx, dy) = (apx - t * abx, apy - t * aby);
+    (dx * dx + dy * dy).sqrt()
+}
+
+impl ImageBuffer {
+    /// 以覆盖率混合单个像素（带裁剪）。
+    fn plot(&mut self, x: i32, y: i32, color: Color, coverage: f32) {
+        if x >= 0 && y >= 0 {
+            self.blend_pixel(x as usize, y as usize, color, coverage);
+        }
+    }
+
+    /// Wu 抗锯齿直线（1 像素宽）。
+    pub(crate) fn aa_line(&mut self, from: PointF, to: PointF, color: Color) {
+        let (mut x0, mut y0) = from;
+        let (mut x1, mut y1) = to;
+        let steep = (y1 - y0).abs() > (x1 - x0).abs();
+        if steep {
+            std::mem::swap(&mut x0, &mut y0);
+            std::mem::swap(&mut x1, &mut y1);
+        }
+        if x0 > x1 {
+            std::mem::swap(&mut x0, &mut x1);
+            std::mem::swap(&mut y0, &mut y1);
+        }
+        let dx = x1 - x0;
+        let gradient = if dx == 0.0 { 1.0 } else { (y1 - y0) / dx };
+
+        let plot = |buf: &mut Self, x: i32, y: i32, c: f32| {
+            if steep {
+                buf.plot(y, x, color, c);
+            } else {
+                buf.plot(x, y, color, c);
+            }
+        };
+
+        let xs = x0.round() as i32;
+        let xe = x1.round() as i32;
+        let mut y = y0 + gradient * (xs as f32 - x0);
+        for x in xs..=xe {
+            let base = y.floor();
+            let frac = y - base;
+            plot(self, x, base as i32, 1.0 - frac);
+            if frac > 0.0 {
+                plot(self, x, base as i32 + 1, frac);
+            }
+            y += gradient;
+        }
+    }
+
+    /// 按覆盖率描边折线（圆头圆角），重叠部分取最大覆盖率避免接缝变色。
+    pub(crate) fn aa_stroke(&mut self, points: &[PointF], width: f32, closed: bool, color: Color) {
+        if points.is_empty() {
+            return;
+        }
+        let half = width / 2.0;
+        let pad = half + 1.0;
+        let min_x = points.iter().map(|p| p.0).fold(f32::MAX, f32::min) - pad;
+        let min_y = points.iter().map(|p| p.1).fold(f32::MAX, f32::min) - pad;
+        let max_x = points.iter().map(|p| p.0).fold(f32::MIN, f32::max) + pad;
+        let max_y = points.iter().map(|p| p.1).fold(f32::MIN, f32::max) + pad;
+        let (bx0, by0, bx1, by1) = match clip_bounds(min_x, min_y, max_x, max_y) {
+            Some(b) => b,
+            None => return,
+        };
+        let mask_w = (bx1 - bx0 + 1) as usize;
+        let mut mask = vec![0.0f32; mask_w * (by1 - by0 + 1) as usize];
+
+        let mut segments: Vec<(PointF, PointF)> = points.windows(2).map(|w| (w[0], w[1])).collect();
+        if closed && points.len() > 2 {
+            segments.push((points[points.len() - 1], points[0]));
+        }
+        if segments.is_empty() {
+            segments.push((points[0], points[0]));
+        }
+
+        for (a, b) in segments {
+            let seg = clip_bounds(
+                a.0.min(b.0) - pad,
+                a.1.min(b.1) - pad,
+                a.0.max(b.0) + pad,
+                a.1.max(b.1) + pad,
+            );
+            let (sx0, sy0, sx1, sy1) = match seg {
+                Some(s) => s,
+                None => continue,
+            };
+            for y in sy0..=sy1 {
+                for x in sx0..=sx1 {
+                    let d = distance_to_segment((x as f32, y as f32), a, b);
+                    let c = (half + 0.5 - d).clamp(0.0, 1.0);
+                    let m = &mut mask[(y - by0) as usize * mask_w + (x - bx0) as usize];
+                    *m = m.max(c);
+                }
+            }
+        }
+
+        for y in by0..=by1 {
+            for x in bx0..=bx1 {
+                let c = mask[(y - by0) as usize * mask_w + (x - bx0) as usize];
+                if c > 0.0 {
+                    self.blend_pixel(x as usize, y as usize, color, c);
+                }
+            }
+        }
+    }
+
+    /// 子扫描线抗锯齿多边形填充（奇偶规则）。
+    pub(crate) fn aa_fill_path(&mut self, points: &[PointF], color: Color) {
+        if points.len() < 3 {
+            self.aa_stroke(points, 1.0, false, color);
+            return;
+        }
+        let min_x = points.iter().map(|p| p.0).fold(f32::MAX, f32::min);
+        let min_y = points.iter().map(|p| p.1).fold(f32::MAX, f32::min);
+        let max_x = points.iter().map(|p| p.0).fold(f32::MIN, f32::max);
+        let max_y = points.iter().map(|p| p.1).fold(f32::MIN, f32::max);
+        let (bx0, by0, bx1, by1) = match clip_bounds(min_x, min_y, max_x, max_y) {
+            Some(b) => b,
+            None => return,
+        };
+        let row_w = (bx1 - bx0 + 1) as usize;
+        let mut row = vec![0.0f32; row_w];
+        let mut nodes: Vec<f32> = Vec::with_capacity(points.len());
+        let step = 1.0 / SUBSCANLINES as f32;
+
+        for y in by0..=by1 {
+            row.fill(0.0);
+            for k in 0..SUBSCANLINES {
+                let sy = y as f32 - 0.5 + (k as f32 + 0.5) * step;
+                nodes.clear();
+                for i in 0..points.len() {
+                    let a = points[i];
+                    let b = points[(i + 1) % points.len()];
+                    if (a.1 <= sy && b.1 > sy) || (b.1 <= sy && a.1 > sy) {
+                        let t = (sy - a.1) / (b.1 - a.1);
+                        nodes.push(a.0 + t * (b.0 - a.0));
+                    }
+                }
+                nodes.sort_unstable_by(|a, b| a.total_cmp(b));
+                for pair in nodes.chunks(2) {
+                    if let [xa, xb] = pair {
+                        let first = ((xa + 0.5).floor() as i32).max(bx0);
+                        let last = ((xb + 0.5).floor() as i32).min(bx1);
+                        for x in first..=last {
+                            let px = x as f32;
+                            let overlap = xb.min(px + 0.5) - xa.max(px - 0.5);
+                            if overlap > 0.0 {
+                                row[(x - bx0) as usize] += overlap * step;
+                            }
+                        }
+                    }
+                }
+            }
+            for (i, &c) in row.iter().enumerate() {
+                if c > 0.0 {
+                    self.blend_pixel(bx0 as usize + i, y as usize, color, c.min(1.0));
+                }
+            }
+        }
+    }
+
+    /// 超采样抗锯齿椭圆；`stroke` 为线宽，`None` 表示填充。
+    pub(crate) fn aa_ellipse(
+        &mut self,
+        center: PointF,
+        rx: f32,
+        ry: f32,
+        stroke: Option<f32>,
+        color: Color,
+    ) {
+        // 与整数像素路径保持一致：半径向外扩展半个像素
+        let (orx, ory) = (rx + 0.5, ry + 0.5);
+        let inner = stroke.map(|w| (orx - w, ory - w));
+        let (bx0, by0, bx1, by1) = match clip_bounds(
+            center.0 - orx,
+            center.1 - ory,
+            center.0 + orx,
+            center.1 + ory,
+        ) {
+            Some(b) => b,
+            None => return,
+        };
+        let total = (SUPERSAMPLE * SUPERSAMPLE) as f32;
+        let step = 1.0 / SUPERSAMPLE as f32;
+
+        for y in by0..=by1 {
+            for x in bx0..=bx1 {
+                let mut hits = 0usize;
+                for sy in 0..SUPERSAMPLE {
+                    let fy = y as f32 - 0.5 + (sy as f32 + 0.5) * step - center.1;
+                    for sx in 0..SUPERSAMPLE {
+                        let fx = x as f32 - 0.5 + (sx as f32 + 0.5) * step - center.0;
+                        let outer = (fx / orx).powi(2) + (fy / ory).powi(2);
+                        if outer > 1.0 {
+                            continue;
+                        }
+                        let inside_hole = match inner {
+                            Some((irx, iry)) if irx > 0.0 && iry > 0.0 => {
+                                (fx / irx).powi(2) + (fy / iry).powi(2) <= 1.0
+                            }
+                            _ => false,
+                        };
+                        if !inside_hole {
+                            hits += 1;
+                        }
+                    }
+                }
+                if hits > 0 {
+                    self.blend_pixel(x as usize, y as usize, color, hits as f32 / total);
+                }
+            }
+        }
+    }
+}
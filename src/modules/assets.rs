@@ -0,0 +1,401 @@
+//! ElectronBot 库的素材管理。
+//!
+//! [`Assets`] 在启动时加载一个目录中的图片和动画（可多线程），一次性转换为设备 BGR 布局，
+//! 之后按名称取出交给场景管理或直接播放，内容第一次出现时不会因为解码而卡顿。
+//!
+//! 目录中的每个图片文件是一张静态图，GIF 是一段动画；每个子目录也是一段动画，
+//! 帧按文件名排序。名称为文件名（不含扩展名）或子目录名。
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use image::codecs::gif::GifDecoder;
+use image::{AnimationDecoder, DynamicImage, ImageFormat};
+
+use crate::modules::constants::FRAME_SIZE;
+use crate::modules::image::{FitOptions, ImageBuffer};
+use crate::modules::scene::Scene;
+use crate::modules::source::{FrameSource, DEFAULT_INTERVAL};
+
+/// 预转换好的一张图或一段动画。
+#[derive(Debug, Clone)]
+pub struct Asset {
+    frames: Arc<[Box<[u8; FRAME_SIZE]>]>,
+    delays: Arc<[Duration]>,
+}
+
+impl Asset {
+    /// 由转换好的帧和各帧的显示时间创建，`delays` 比帧少时缺少的按 30 FPS 补齐。
+    pub fn new(frames: Vec<Box<[u8; FRAME_SIZE]>>, mut delays: Vec<Duration>) -> Self {
+        delays.resize(frames.len(), DEFAULT_INTERVAL);
+        Self {
+            frames: frames.into(),
+            delays: delays.into(),
+        }
+    }
+
+    /// 由一张图创建静态素材。
+    pub fn still(buffer: &ImageBuffer) -> Self {
+        Self::new(vec![boxed_frame(buffer)], Vec::new())
+    }
+
+    /// 帧数。
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// 是否没有帧。
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// 是否为动画（多于一帧）。
+    pub fn is_animation(&self) -> bool {
+        self.frames.len() > 1
+    }
+
+    /// 第 `index` 帧。
+    pub fn frame(&self, index: usize) -> Option<&[u8; FRAME_SIZE]> {
+        self.frames.get(index).map(|frame| &**frame)
+    }
+
+    /// 第 `index` 帧的显示时间。
+    pub fn delay(&self, index: usize) -> Option<Duration> {
+        self.delays.get(index).copied()
+    }
+
+    /// 动画播放一遍的时间（静态图为 0）。
+    pub fn duration(&self) -> Duration {
+        if self.is_animation() {
+            self.delays.iter().sum()
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    /// 循环播放的帧源（共享帧数据，不复制）。
+    pub fn source(&self) -> AssetSource {
+        AssetSource {
+            asset: self.clone(),
+            next: 0,
+            current: 0,
+            looping: true,
+        }
+    }
+}
+
+/// 播放素材的帧源。静态图一直显示，动画默认循环播放。
+#[derive(Debug, Clone)]
+pub struct AssetSource {
+    asset: Asset,
+    next: usize,
+    current: usize,
+    looping: bool,
+}
+
+impl AssetSource {
+    /// 动画只播放一遍。
+    pub fn once(mut self) -> Self {
+        self.looping = false;
+        self
+    }
+
+    /// 从第一帧重新播放。
+    pub fn restart(&mut self) {
+        self.next = 0;
+    }
+}
+
+impl FrameSource for AssetSource {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        if self.next >= self.asset.len() {
+            if !self.looping && self.asset.is_animation() || self.asset.is_empty() {
+                return false;
+            }
+            self.next = 0;
+        }
+        buffer.load_frame(&self.asset.frames[self.next]);
+        self.current = self.next;
+        self.next += 1;
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        if self.asset.is_animation() {
+            self.asset.delay(self.current)
+        } else {
+            None
+        }
+    }
+}
+
+/// 按名称管理的素材。
+#[derive(Debug, Clone, Default)]
+pub struct Assets {
+    assets: BTreeMap<String, Asset>,
+}
+
+impl Assets {
+    /// 创建空的素材集。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 加载目录的加载器。
+    pub fn loader<P: AsRef<Path>>(dir: P) -> AssetLoader {
+        AssetLoader {
+            dir: dir.as_ref().to_path_buf(),
+            fit: FitOptions::default(),
+            parallel: false,
+        }
+    }
+
+    /// 按默认适配方式单线程加载目录。
+    pub fn load_dir<P: AsRef<Path>>(dir: P) -> Result<Self, String> {
+        Self::loader(dir).load()
+    }
+
+    /// 添加素材，同名的被替换。
+    pub fn insert(&mut self, name: &str, asset: Asset) {
+        self.assets.insert(name.to_string(), asset);
+    }
+
+    /// 移除素材，返回是否存在。
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.assets.remove(name).is_some()
+    }
+
+    /// 按名称取出素材。
+    pub fn get(&self, name: &str) -> Option<&Asset> {
+        self.assets.get(name)
+    }
+
+    /// 是否有该名称的素材。
+    pub fn contains(&self, name: &str) -> bool {
+        self.assets.contains_key(name)
+    }
+
+    /// 按名称排序的所有素材名称。
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.assets.keys().map(String::as_str)
+    }
+
+    /// 按名称排序遍历素材。
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Asset)> {
+        self.assets
+            .iter()
+            .map(|(name, asset)| (name.as_str(), asset))
+    }
+
+    /// 素材数量。
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    /// 是否没有素材。
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+
+    /// 所有帧占用的字节数。
+    pub fn memory(&self) -> usize {
+        self.assets
+            .values()
+            .map(|asset| asset.len() * FRAME_SIZE)
+            .sum()
+    }
+
+    /// 循环播放素材的帧源。
+    pub fn source(&self, name: &str) -> Option<AssetSource> {
+        self.get(name).map(Asset::source)
+    }
+
+    /// 以素材名称为场景名，创建循环播放素材的场景。
+    pub fn scene(&self, name: &str) -> Option<Scene> {
+        self.source(name).map(|source| Scene::new(name, source))
+    }
+}
+
+/// 素材目录的加载选项。
+#[derive(Debug, Clone)]
+pub struct AssetLoader {
+    dir: PathBuf,
+    fit: FitOptions,
+    parallel: bool,
+}
+
+impl AssetLoader {
+    /// 设置图片适配方式（默认拉伸填满屏幕）。
+    pub fn fit(mut self, fit: FitOptions) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    /// 每个 CPU 核心一个线程并行解码（默认关闭）。
+    pub fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// 加载目录中的所有素材，任何一个文件解码失败时返回带文件路径的错误。
+    pub fn load(self) -> Result<Assets, String> {
+        let jobs = scan_dir(&self.dir)?;
+        let fit = &self.fit;
+        let threads = if self.parallel {
+            std::thread::available_parallelism().map_or(1, |n| n.get())
+        } else {
+            1
+        };
+
+        #[cfg(feature = "logging")]
+        log::info!(
+            "加载素材目录 {:?}: {} 项，{} 个线程",
+            self.dir,
+            jobs.len(),
+            threads
+        );
+
+        let loaded: Vec<Result<(String, Asset), String>> = if threads > 1 && jobs.len() > 1 {
+            let chunk = jobs.len().div_ceil(threads);
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = jobs
+                    .chunks(chunk)
+                    .map(|chunk| {
+                        scope.spawn(move || {
+                            chunk.iter().map(|job| job.load(fit)).collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .flat_map(|handle| {
+                        handle
+                            .join()
+                            .unwrap_or_else(|_| vec![Err("加载线程崩溃".to_string())])
+                    })
+                    .collect()
+            })
+        } else {
+            jobs.iter().map(|job| job.load(fit)).collect()
+        };
+
+        let mut assets = Assets::new();
+        for result in loaded {
+            let (name, asset) = result?;
+            assets.assets.insert(name, asset);
+        }
+        Ok(assets)
+    }
+}
+
+/// 一项待加载的素材。
+enum Job {
+    /// 静态图片或 GIF 动画。
+    File(String, PathBuf),
+    /// 子目录中按文件名排序的帧。
+    Frames(String, Vec<PathBuf>),
+}
+
+impl Job {
+    fn load(&self, fit: &FitOptions) -> Result<(String, Asset), String> {
+        match self {
+            Job::File(name, path) if is_gif(path) => Ok((name.clone(), load_gif(path, fit)?)),
+            Job::File(name, path) => {
+                let mut buffer = ImageBuffer::new();
+                buffer
+                    .load_from_file_with(path, fit)
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+                Ok((name.clone(), Asset::still(&buffer)))
+            }
+            Job::Frames(name, paths) => {
+                let mut buffer = ImageBuffer::new();
+                let mut frames = Vec::with_capacity(paths.len());
+                for path in paths {
+                    buffer
+                        .load_from_file_with(path, fit)
+                        .map_err(|e| format!("{}: {}", path.display(), e))?;
+                    frames.push(boxed_frame(&buffer));
+                }
+                Ok((name.clone(), Asset::new(frames, Vec::new())))
+            }
+        }
+    }
+}
+
+/// 列出目录中的图片文件和帧子目录，跳过不是图片的文件。
+fn scan_dir(dir: &Path) -> Result<Vec<Job>, String> {
+    let mut jobs = Vec::new();
+    for path in sorted_entries(dir)? {
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if path.is_dir() {
+            let frames: Vec<PathBuf> = sorted_entries(&path)?
+                .into_iter()
+                .filter(|p| is_image(p))
+                .collect();
+            if !frames.is_empty() {
+                let name = path.file_name().and_then(|s| s.to_str()).unwrap_or(name);
+                jobs.push(Job::Frames(name.to_string(), frames));
+            }
+        } else if is_image(&path) {
+            jobs.push(Job::File(name.to_string(), path.clone()));
+        }
+    }
+    Ok(jobs)
+}
+
+fn sorted_entries(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut entries = std::fs::read_dir(dir)
+        .map_err(|e| format!("读取目录 {} 失败: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect::<Vec<_>>();
+    entries.sort();
+    Ok(entries)
+}
+
+fn is_image(path: &Path) -> bool {
+    path.is_file() && ImageFormat::from_path(path).is_ok()
+}
+
+fn is_gif(path: &Path) -> bool {
+    ImageFormat::from_path(path).is_ok_and(|format| format == ImageFormat::Gif)
+}
+
+/// 解码 GIF 的所有帧，保留每帧的显示时间。
+fn load_gif(path: &Path, fit: &FitOptions) -> Result<Asset, String> {
+    let error = |e: &dyn std::fmt::Display| format!("{}: {}", path.display(), e);
+    let file = File::open(path).map_err(|e| error(&e))?;
+    let decoder = GifDecoder::new(BufReader::new(file)).map_err(|e| error(&e))?;
+    let mut buffer = ImageBuffer::new();
+    let mut frames = Vec::new();
+    let mut delays = Vec::new();
+    for frame in decoder.into_frames() {
+        let frame = frame.map_err(|e| error(&e))?;
+        let delay = Duration::from(frame.delay());
+        // 显示时间为 0 的 GIF 按浏览器的习惯以 100ms 播放
+        delays.push(if delay.is_zero() {
+            Duration::from_millis(100)
+        } else {
+            delay
+        });
+        buffer.load_from_image_with(&DynamicImage::ImageRgba8(frame.into_buffer()), fit);
+        frames.push(boxed_frame(&buffer));
+    }
+    if frames.is_empty() {
+        return Err(error(&"GIF 没有帧"));
+    }
+    Ok(Asset::new(frames, delays))
+}
+
+fn boxed_frame(buffer: &ImageBuffer) -> Box<[u8; FRAME_SIZE]> {
+    buffer.as_data()[..FRAME_SIZE]
+        .to_vec()
+        .into_boxed_slice()
+        .try_into()
+        .expect("长度为一帧")
+}
@@ -0,0 +1,151 @@
+//! ElectronBot 库的音频驱动嘴型（口型同步）。
+//!
+//! 音频回调（cpal、TTS 引擎等，可以在任意线程）通过 [`AudioLevel`] 写入采样或 RMS，
+//! [`LipSync`] 每帧读取音量，经起音/释音平滑后得到张嘴程度。
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::modules::face::Expression;
+
+/// 音量输入端，可克隆后交给音频线程。
+///
+/// 两次读取之间写入的多段音频取其中的最大音量，不会因为帧率低于音频回调频率而漏掉音节。
+#[derive(Debug, Clone, Default)]
+pub struct AudioLevel {
+    /// 非负 f32 的位模式，按整数比较与按浮点比较顺序一致。
+    peak: Arc<AtomicU32>,
+}
+
+impl AudioLevel {
+    /// 创建音量输入端。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 写入一段浮点采样（-1 到 1）。
+    pub fn push_samples(&self, samples: &[f32]) {
+        if samples.is_empty() {
+            return;
+        }
+        let sum: f32 = samples.iter().map(|s| s * s).sum();
+        self.push_rms((sum / samples.len() as f32).sqrt());
+    }
+
+    /// 写入一段 16 位整数采样。
+    pub fn push_samples_i16(&self, samples: &[i16]) {
+        if samples.is_empty() {
+            return;
+        }
+        let sum: f32 = samples
+            .iter()
+            .map(|&s| {
+                let s = s as f32 / 32768.0;
+                s * s
+            })
+            .sum();
+        self.push_rms((sum / samples.len() as f32).sqrt());
+    }
+
+    /// 直接写入 RMS 音量（例如 TTS 引擎提供的包络）。
+    pub fn push_rms(&self, rms: f32) {
+        let rms = if rms.is_finite() { rms.max(0.0) } else { 0.0 };
+        self.peak.fetch_max(rms.to_bits(), Ordering::Relaxed);
+    }
+
+    /// 读取并清零上次读取以来的最大音量。
+    pub fn take(&self) -> f32 {
+        f32::from_bits(self.peak.swap(0, Ordering::Relaxed))
+    }
+}
+
+/// 口型同步：音量到张嘴程度的映射和平滑。
+#[derive(Debug, Clone)]
+pub struct LipSync {
+    input: AudioLevel,
+    /// 张嘴的平滑时间常数。
+    pub attack: Duration,
+    /// 闭嘴的平滑时间常数。
+    pub decay: Duration,
+    /// 低于该 RMS 的音量视为静音。
+    pub noise_gate: f32,
+    /// 音量增益，RMS 减去门限后乘以增益得到张嘴程度。
+    pub gain: f32,
+    /// 最大张嘴程度。
+    pub max_open: f32,
+    level: f32,
+}
+
+impl Default for LipSync {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LipSync {
+    /// 使用默认参数创建（起音 30ms，释音 120ms）。
+    pub fn new() -> Self {
+        Self {
+            input: AudioLevel::new(),
+            attack: Duration::from_millis(30),
+            decay: Duration::from_millis(120),
+            noise_gate: 0.02,
+            gain: 4.0,
+            max_open: 0.9,
+            level: 0.0,
+        }
+    }
+
+    /// 设置起音和释音时间。
+    pub fn smoothing(mut self, attack: Duration, decay: Duration) -> Self {
+        self.attack = attack;
+        self.decay = decay;
+        self
+    }
+
+    /// 设置噪声门限。
+    pub fn noise_gate(mut self, rms: f32) -> Self {
+        self.noise_gate = rms;
+        self
+    }
+
+    /// 设置音量增益。
+    pub fn gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    /// 音量输入端（克隆后交给音频线程）。
+    pub fn input(&self) -> AudioLevel {
+        self.input.clone()
+    }
+
+    /// 当前张嘴程度（0 到 `max_open`）。
+    pub fn mouth_open(&self) -> f32 {
+        self.level
+    }
+
+    /// 读取音量并推进平滑，返回张嘴程度。
+    pub fn update(&mut self, dt: Duration) -> f32 {
+        let rms = self.input.take();
+        let target = ((rms - self.noise_gate).max(0.0) * self.gain).min(self.max_open);
+        let tau = if target > self.level {
+            self.attack
+        } else {
+            self.decay
+        };
+        let rate = if tau.is_zero() {
+            1.0
+        } else {
+            1.0 - (-dt.as_secs_f32() / tau.as_secs_f32()).exp()
+        };
+        self.level += (target - self.level) * rate;
+        self.level
+    }
+
+    /// 把张嘴程度叠加到表情上。
+    pub fn apply(&self, expression: &mut Expression) {
+        expression.mouth_open = expression.mouth_open.max(self.level);
+    }
+}
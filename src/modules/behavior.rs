@@ -0,0 +1,352 @@
+//! ElectronBot 库的行为状态机。
+//!
+//! [`StateMachine`] 用声明的方式描述交互行为：每个 [`State`] 播放一个帧源（表情、动画、场景），
+//! 收到事件时按转移表切换到另一个状态。状态可以有父状态，子状态没有处理的事件交给父状态，
+//! 没有帧源的子状态播放父状态的帧源。
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::modules::event::{Event, EventQueue};
+use crate::modules::image::ImageBuffer;
+use crate::modules::source::{FrameSource, DEFAULT_INTERVAL};
+use crate::modules::types::JointAngles;
+
+/// 状态：帧源、父状态、计时和进入/离开时的动作。
+pub struct State {
+    name: String,
+    parent: Option<String>,
+    source: Option<Box<dyn FrameSource>>,
+    timeout: Option<Duration>,
+    terminal: bool,
+    on_enter: Option<Box<dyn FnMut()>>,
+    on_exit: Option<Box<dyn FnMut()>>,
+    /// 进入后经过的时间（按帧间隔计算）。
+    elapsed: Duration,
+    /// 本次进入后是否已经发出过计时事件。
+    timer_fired: bool,
+}
+
+impl fmt::Debug for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("State")
+            .field("name", &self.name)
+            .field("parent", &self.parent)
+            .field("timeout", &self.timeout)
+            .field("terminal", &self.terminal)
+            .field("elapsed", &self.elapsed)
+            .finish_non_exhaustive()
+    }
+}
+
+impl State {
+    /// 创建没有帧源的状态（保持画面不变，或者播放父状态的帧源）。
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            parent: None,
+            source: None,
+            timeout: None,
+            terminal: false,
+            on_enter: None,
+            on_exit: None,
+            elapsed: Duration::ZERO,
+            timer_fired: false,
+        }
+    }
+
+    /// 设置帧源。帧源在多次进入之间保留进度；播放结束时产生 [`Event::Finished`]。
+    pub fn source<S: FrameSource + 'static>(mut self, source: S) -> Self {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    /// 设置父状态。
+    pub fn parent(mut self, parent: &str) -> Self {
+        self.parent = Some(parent.to_string());
+        self
+    }
+
+    /// 进入指定时间后产生 [`Event::TimerElapsed`]（每次进入只产生一次）。
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// 进入该状态后状态机播放结束。
+    pub fn terminal(mut self) -> Self {
+        self.terminal = true;
+        self
+    }
+
+    /// 设置进入状态时的动作。
+    pub fn on_enter<F: FnMut() + 'static>(mut self, action: F) -> Self {
+        self.on_enter = Some(Box::new(action));
+        self
+    }
+
+    /// 设置离开状态时的动作。
+    pub fn on_exit<F: FnMut() + 'static>(mut self, action: F) -> Self {
+        self.on_exit = Some(Box::new(action));
+        self
+    }
+
+    /// 状态名称。
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// 状态转移：在 `from`（或其子状态）中收到 `event` 时切换到 `to`。
+#[derive(Debug, Clone)]
+struct Transition {
+    from: String,
+    event: Event,
+    to: String,
+}
+
+/// 分层行为状态机，本身也是帧源，交给 `ElectronBot::play` 播放。
+///
+/// 第一帧时进入初始状态；每帧开始时处理事件队列中的事件。
+/// 事件从当前状态开始向上查找转移，找不到时忽略。
+#[derive(Debug)]
+pub struct StateMachine {
+    states: Vec<State>,
+    transitions: Vec<Transition>,
+    events: EventQueue,
+    initial: String,
+    current: Option<usize>,
+    /// 最近一帧播放的帧源所在的状态。
+    playing: Option<usize>,
+}
+
+impl StateMachine {
+    /// 创建状态机，`initial` 为初始状态的名称。
+    pub fn new(initial: &str) -> Self {
+        Self {
+            states: Vec::new(),
+            transitions: Vec::new(),
+            events: EventQueue::new(),
+            initial: initial.to_string(),
+            current: None,
+            playing: None,
+        }
+    }
+
+    /// 添加状态，替换同名的状态。
+    pub fn state(mut self, state: State) -> Self {
+        match self.find(&state.name) {
+            Some(index) => self.states[index] = state,
+            None => self.states.push(state),
+        }
+        self
+    }
+
+    /// 添加状态转移。
+    pub fn transition(mut self, from: &str, event: Event, to: &str) -> Self {
+        self.transitions.push(Transition {
+            from: from.to_string(),
+            event,
+            to: to.to_string(),
+        });
+        self
+    }
+
+    /// 事件队列（克隆后交给事件回调或输入线程）。
+    pub fn events(&self) -> EventQueue {
+        self.events.clone()
+    }
+
+    /// 发送事件，下一帧开始时处理。
+    pub fn send(&self, event: Event) {
+        self.events.push(event);
+    }
+
+    /// 当前状态的名称（第一帧之前为 `None`）。
+    pub fn current(&self) -> Option<&str> {
+        self.current.map(|i| self.states[i].name.as_str())
+    }
+
+    /// 当前状态或其某个父状态是否为 `name`。
+    pub fn is_in(&self, name: &str) -> bool {
+        self.current
+            .is_some_and(|i| self.chain(i).iter().any(|&j| self.states[j].name == name))
+    }
+
+    /// 进入当前状态后经过的时间。
+    pub fn time_in_state(&self) -> Duration {
+        self.current
+            .map_or(Duration::ZERO, |i| self.states[i].elapsed)
+    }
+
+    /// 检查状态名称：返回父状态或转移中引用但没有定义的状态。
+    pub fn validate(&self) -> Result<(), String> {
+        let referenced = std::iter::once(&self.initial)
+            .chain(self.states.iter().filter_map(|s| s.parent.as_ref()))
+            .chain(self.transitions.iter().flat_map(|t| [&t.from, &t.to]));
+        for name in referenced {
+            if self.find(name).is_none() {
+                return Err(format!("未定义的状态: {}", name));
+            }
+        }
+        Ok(())
+    }
+
+    fn find(&self, name: &str) -> Option<usize> {
+        self.states.iter().position(|s| s.name == name)
+    }
+
+    /// 从 `index` 到最外层父状态的状态序号。
+    fn chain(&self, index: usize) -> Vec<usize> {
+        let mut chain = vec![index];
+        while let Some(parent) = self.states[*chain.last().unwrap()]
+            .parent
+            .as_deref()
+            .and_then(|p| self.find(p))
+        {
+            // 父状态成环时停止
+            if chain.contains(&parent) {
+                break;
+            }
+            chain.push(parent);
+        }
+        chain
+    }
+
+    /// 从 `from` 开始向上查找并执行 `event` 的转移，返回是否切换了状态。
+    fn dispatch(&mut self, event: &Event, from: usize) -> bool {
+        for index in self.chain(from) {
+            let name = &self.states[index].name;
+            let target = self
+                .transitions
+                .iter()
+                .find(|t| t.from == *name && t.event == *event)
+                .map(|t| t.to.clone());
+            if let Some(target) = target {
+                return self.goto(&target);
+            }
+        }
+        false
+    }
+
+    /// 切换到 `target`：离开到共同父状态为止的状态，再从外到内进入目标状态。
+    fn goto(&mut self, target: &str) -> bool {
+        let Some(target) = self.find(target) else {
+            #[cfg(feature = "logging")]
+            log::warn!("Unknown state '{}'", target);
+            return false;
+        };
+        #[cfg(feature = "logging")]
+        log::debug!(
+            "State {:?} -> '{}'",
+            self.current(),
+            self.states[target].name
+        );
+
+        let old = self.current.map(|i| self.chain(i)).unwrap_or_default();
+        let new = self.chain(target);
+        // 切换到自身时离开并重新进入
+        let common = old
+            .iter()
+            .position(|i| new.contains(i) && *i != target)
+            .unwrap_or(old.len());
+        for &index in &old[..common] {
+            if let Some(action) = &mut self.states[index].on_exit {
+                action();
+            }
+        }
+        let keep = &old[common..];
+        for &index in new.iter().rev().filter(|i| !keep.contains(i)) {
+            let state = &mut self.states[index];
+            state.elapsed = Duration::ZERO;
+            state.timer_fired = false;
+            if let Some(action) = &mut state.on_enter {
+                action();
+            }
+        }
+        self.current = Some(target);
+        true
+    }
+
+    /// 发出到时的计时事件，返回是否切换了状态。
+    fn check_timers(&mut self, current: usize) -> bool {
+        for index in self.chain(current) {
+            let state = &mut self.states[index];
+            if state.timeout.is_some_and(|t| state.elapsed >= t) && !state.timer_fired {
+                state.timer_fired = true;
+                if self.dispatch(&Event::TimerElapsed, index) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// 从当前状态向上找到第一个有帧源的状态并播放一帧；帧源结束时产生 [`Event::Finished`]。
+    fn render(&mut self, buffer: &mut ImageBuffer) {
+        // 每次切换状态后重试，状态数量是切换次数的上限
+        for _ in 0..=self.states.len() {
+            let Some(current) = self.current else { return };
+            if self.states[current].terminal {
+                return;
+            }
+            let chain = self.chain(current);
+            let Some(&index) = chain.iter().find(|&&i| self.states[i].source.is_some()) else {
+                self.playing = None;
+                return;
+            };
+            let source = self.states[index].source.as_mut().unwrap();
+            if source.next_frame(buffer) {
+                self.playing = Some(index);
+                return;
+            }
+            self.playing = None;
+            if !self.dispatch(&Event::Finished, index) {
+                return;
+            }
+        }
+    }
+}
+
+impl FrameSource for StateMachine {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        if self.current.is_none() {
+            let initial = self.initial.clone();
+            if !self.goto(&initial) {
+                return false;
+            }
+        }
+        for event in self.events.take_all() {
+            let current = self.current.unwrap();
+            self.dispatch(&event, current);
+        }
+        let current = self.current.unwrap();
+        self.check_timers(current);
+
+        let current = self.current.unwrap();
+        if self.states[current].terminal {
+            self.playing = None;
+            return false;
+        }
+        self.render(buffer);
+
+        let interval = self.frame_interval().unwrap_or(DEFAULT_INTERVAL);
+        if let Some(current) = self.current {
+            for index in self.chain(current) {
+                self.states[index].elapsed += interval;
+            }
+        }
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        match self.playing {
+            Some(index) => self.states[index].source.as_ref()?.frame_interval(),
+            None => Some(DEFAULT_INTERVAL),
+        }
+    }
+
+    fn joint_angles(&self) -> Option<JointAngles> {
+        self.states[self.playing?].source.as_ref()?.joint_angles()
+    }
+}
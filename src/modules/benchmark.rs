@@ -0,0 +1,131 @@
+//! ElectronBot 库的吞吐量基准测试。
+//!
+//! 在给定时间内尽快发送程序生成的画面（等离子效果，每帧都不同），统计帧率、传输速率、
+//! 重试次数和每次同步的延迟分位数，结果汇总为 [`BenchmarkReport`]。
+//! 连接真机时用来比较不同主机，连接 [`FakeMcu`](crate::FakeMcu) 时只测量主机一侧的开销。
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use electron_bot::ElectronBot;
+//!
+//! let mut bot = ElectronBot::new();
+//! bot.connect()?;
+//! print!("{}", bot.benchmark(Duration::from_secs(10))?);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::time::Duration;
+
+use crate::modules::constants::FRAME_SIZE;
+use crate::modules::error::{BotError, ErrorClass};
+use crate::modules::source::{FrameSource, Plasma};
+use crate::modules::time::Instant;
+use crate::ElectronBot;
+
+/// 基准测试结果。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BenchmarkReport {
+    /// 同步成功的帧数。
+    pub frames: usize,
+    /// 同步失败的帧数。
+    pub failed: usize,
+    /// 收发失败后的重试次数。
+    pub retries: usize,
+    /// 总耗时。
+    pub elapsed: Duration,
+    /// 每次同步的耗时（不含生成画面），从小到大排列。
+    pub latencies: Vec<Duration>,
+}
+
+impl BenchmarkReport {
+    /// 帧率。
+    pub fn fps(&self) -> f32 {
+        let seconds = self.elapsed.as_secs_f32();
+        if seconds > 0.0 {
+            self.frames as f32 / seconds
+        } else {
+            0.0
+        }
+    }
+
+    /// 画面数据的传输速率（字节/秒）。
+    pub fn bytes_per_second(&self) -> f32 {
+        self.fps() * FRAME_SIZE as f32
+    }
+
+    /// 同步延迟的分位数（`p` 为 0.0-1.0），没有同步时为 `None`。
+    pub fn latency(&self, p: f32) -> Option<Duration> {
+        let last = self.latencies.len().checked_sub(1)?;
+        let index = (p.clamp(0.0, 1.0) * last as f32).round() as usize;
+        self.latencies.get(index).copied()
+    }
+}
+
+impl std::fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ms = |p: f32| self.latency(p).map_or(0.0, |d| d.as_secs_f32() * 1000.0);
+        writeln!(
+            f,
+            "帧率: {:.1} FPS ({} 帧，失败 {} 帧，用时 {:.1} 秒)",
+            self.fps(),
+            self.frames,
+            self.failed,
+            self.elapsed.as_secs_f32()
+        )?;
+        writeln!(f, "吞吐量: {:.1} KB/s", self.bytes_per_second() / 1024.0)?;
+        writeln!(f, "重试: {} 次", self.retries)?;
+        writeln!(
+            f,
+            "延迟: p50 {:.1} 毫秒, p90 {:.1} 毫秒, p99 {:.1} 毫秒, 最大 {:.1} 毫秒",
+            ms(0.5),
+            ms(0.9),
+            ms(0.99),
+            ms(1.0)
+        )
+    }
+}
+
+/// 运行基准测试，结束后恢复原来的画面。
+///
+/// 取消、急停和不能重试的错误（断开、没有权限）中止测试并返回错误，其他同步失败计入 `failed`。
+pub(crate) fn run(bot: &mut ElectronBot, duration: Duration) -> Result<BenchmarkReport, BotError> {
+    if !bot.is_connected() {
+        return Err(BotError::NotConnected);
+    }
+    let saved_image = bot.image_buffer().clone();
+    let result = run_frames(bot, duration);
+    *bot.image_buffer() = saved_image;
+    result
+}
+
+fn run_frames(bot: &mut ElectronBot, duration: Duration) -> Result<BenchmarkReport, BotError> {
+    let mut report = BenchmarkReport::default();
+    let mut plasma = Plasma::new();
+    let started = Instant::now();
+    while started.elapsed() < duration {
+        plasma.next_frame(bot.image_buffer());
+        let sync_started = Instant::now();
+        let result = bot.sync();
+        report.latencies.push(sync_started.elapsed());
+        report.retries += bot.last_sync_progress().retries;
+        match result {
+            Ok(true) => report.frames += 1,
+            Ok(false) => report.failed += 1,
+            Err(e) if e.class() == ErrorClass::Transient => {
+                #[cfg(feature = "logging")]
+                log::warn!("Benchmark sync failed: {}", e);
+                report.failed += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    report.elapsed = started.elapsed();
+    report.latencies.sort();
+    #[cfg(feature = "logging")]
+    log::info!(
+        "Benchmark finished: {:.1} FPS, {} retries",
+        report.fps(),
+        report.retries
+    );
+    Ok(report)
+}
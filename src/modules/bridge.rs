@@ -0,0 +1,279 @@
+//! ElectronBot 库的网络桥接。
+//!
+//! 机器人插在另一台主机（如树莓派）上时，在那台主机上运行 [`BridgeServer`]
+//! （`electronbot-bridge` 程序），开发机通过 [`TcpTransport`] 连接，
+//! `ElectronBot` 的其余 API 不需要任何改动。
+//!
+//! 桥接只转发 USB 包，帧和扩展数据的同步协议仍由客户端执行：
+//!
+//! - 握手：客户端发送 `EBB1`，服务端回复 `EBB1` 和状态字节（0 成功；1 失败，随后是错误信息）
+//! - 发送：`T` + 长度（u32 小端）+ 数据，服务端不回复
+//! - 接收：`R` + 最大长度（u32 小端），服务端回复 `R` + 状态字节 + 长度 + 数据（失败时为错误信息）
+//!
+//! 桥接没有认证，只应在可信网络中使用。
+
+use std::io::{BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::modules::transport::Transport;
+
+/// 握手标识和协议版本。
+const MAGIC: &[u8; 4] = b"EBB1";
+/// 单个包的最大字节数。
+const MAX_PACKET_BYTES: usize = 1024 * 1024;
+/// 客户端等待回复的超时。
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+const OP_TRANSMIT: u8 = b'T';
+const OP_RECEIVE: u8 = b'R';
+const STATUS_OK: u8 = 0;
+const STATUS_ERROR: u8 = 1;
+
+/// 通过网络桥接访问远程 ElectronBot 的传输通道。
+#[derive(Debug)]
+pub struct TcpTransport {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl TcpTransport {
+    /// 连接桥接服务并完成握手。
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> Result<Self, String> {
+        let addr = addr
+            .to_socket_addrs()
+            .map_err(|e| format!("解析地址失败: {}", e))?
+            .next()
+            .ok_or_else(|| "没有可用的地址".to_string())?;
+        let stream = TcpStream::connect_timeout(&addr, TIMEOUT)
+            .map_err(|e| format!("连接桥接服务失败: {}", e))?;
+        stream
+            .set_nodelay(true)
+            .and_then(|_| stream.set_read_timeout(Some(TIMEOUT)))
+            .map_err(|e| format!("设置连接参数失败: {}", e))?;
+        let writer = stream
+            .try_clone()
+            .map_err(|e| format!("复制连接失败: {}", e))?;
+        let mut transport = Self {
+            reader: BufReader::new(stream),
+            writer,
+        };
+
+        transport
+            .writer
+            .write_all(MAGIC)
+            .map_err(|e| format!("发送握手失败: {}", e))?;
+        let mut magic = [0u8; 4];
+        read_exact(&mut transport.reader, &mut magic)?;
+        if &magic != MAGIC {
+            return Err("不是 ElectronBot 桥接服务".to_string());
+        }
+        let status = read_u8(&mut transport.reader)?;
+        if status != STATUS_OK {
+            let message = read_payload(&mut transport.reader)?;
+            return Err(format!(
+                "桥接服务无法打开设备: {}",
+                String::from_utf8_lossy(&message)
+            ));
+        }
+
+        #[cfg(feature = "logging")]
+        log::info!("Connected to bridge {}", addr);
+        Ok(transport)
+    }
+}
+
+impl Transport for TcpTransport {
+    fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+        let mut packet = Vec::with_capacity(data.len() + 5);
+        packet.push(OP_TRANSMIT);
+        packet.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        packet.extend_from_slice(data);
+        self.writer
+            .write_all(&packet)
+            .map_err(|e| format!("发送失败: {}", e))?;
+        Ok(true)
+    }
+
+    fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+        let mut request = [OP_RECEIVE, 0, 0, 0, 0];
+        request[1..].copy_from_slice(&(data.len() as u32).to_le_bytes());
+        self.writer
+            .write_all(&request)
+            .map_err(|e| format!("发送失败: {}", e))?;
+
+        if read_u8(&mut self.reader)? != OP_RECEIVE {
+            return Err("桥接服务回复了未知的数据".to_string());
+        }
+        let status = read_u8(&mut self.reader)?;
+        let payload = read_payload(&mut self.reader)?;
+        if status != STATUS_OK {
+            return Err(String::from_utf8_lossy(&payload).into_owned());
+        }
+        let len = payload.len().min(data.len());
+        data[..len].copy_from_slice(&payload[..len]);
+        Ok(len)
+    }
+}
+
+/// 桥接服务：把本机的 ElectronBot 转发给网络上的客户端。
+///
+/// 同一时间只服务一个客户端，其他客户端排队等待。
+#[derive(Debug)]
+pub struct BridgeServer {
+    listener: TcpListener,
+}
+
+impl BridgeServer {
+    /// 监听指定地址，例如 `0.0.0.0:7878`。
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self, String> {
+        let listener = TcpListener::bind(addr).map_err(|e| format!("监听地址失败: {}", e))?;
+        Ok(Self { listener })
+    }
+
+    /// 实际监听的地址。
+    pub fn local_addr(&self) -> Result<SocketAddr, String> {
+        self.listener
+            .local_addr()
+            .map_err(|e| format!("获取监听地址失败: {}", e))
+    }
+
+    /// 持续接受客户端，每个客户端连接时打开本机的 ElectronBot。
+    pub fn serve(&self) -> Result<(), String> {
+        loop {
+            let mut stream = self.accept()?;
+            match crate::modules::usb::open_electron_bot() {
+                Ok(mut usb) => {
+                    if let Err(_e) = relay(&mut stream, &mut usb) {
+                        #[cfg(feature = "logging")]
+                        log::warn!("Bridge client error: {}", _e);
+                    }
+                }
+                Err(e) => {
+                    #[cfg(feature = "logging")]
+                    log::error!("Bridge failed to open device: {}", e);
+                    let _ = write_handshake(&mut stream, Err(&e));
+                }
+            }
+        }
+    }
+
+    /// 接受一个客户端，通过 `transport` 转发，直到客户端断开。
+    pub fn handle_next(&self, transport: &mut dyn Transport) -> Result<(), String> {
+        let mut stream = self.accept()?;
+        relay(&mut stream, transport)
+    }
+
+    fn accept(&self) -> Result<TcpStream, String> {
+        let (stream, _peer) = self
+            .listener
+            .accept()
+            .map_err(|e| format!("接受连接失败: {}", e))?;
+        #[cfg(feature = "logging")]
+        log::info!("Bridge client connected: {}", _peer);
+        stream
+            .set_nodelay(true)
+            .map_err(|e| format!("设置连接参数失败: {}", e))?;
+        Ok(stream)
+    }
+}
+
+/// 完成握手后逐个转发请求，客户端断开时正常返回。
+fn relay(stream: &mut TcpStream, transport: &mut dyn Transport) -> Result<(), String> {
+    let mut reader = BufReader::new(
+        stream
+            .try_clone()
+            .map_err(|e| format!("复制连接失败: {}", e))?,
+    );
+    let mut magic = [0u8; 4];
+    read_exact(&mut reader, &mut magic)?;
+    if &magic != MAGIC {
+        return Err("客户端握手无效".to_string());
+    }
+    write_handshake(stream, Ok(()))?;
+
+    let mut op = [0u8; 1];
+    loop {
+        match reader.read(&mut op) {
+            Ok(0) => {
+                #[cfg(feature = "logging")]
+                log::info!("Bridge client disconnected");
+                return Ok(());
+            }
+            Ok(_) => {}
+            Err(e) => return Err(format!("读取请求失败: {}", e)),
+        }
+        match op[0] {
+            OP_TRANSMIT => {
+                let data = read_payload(&mut reader)?;
+                if let Err(_e) = transport.transmit(&data) {
+                    #[cfg(feature = "logging")]
+                    log::warn!("Bridge transmit failed: {}", _e);
+                }
+            }
+            OP_RECEIVE => {
+                let len = read_len(&mut reader)?;
+                let mut data = vec![0u8; len];
+                let (status, payload) = match transport.receive(&mut data) {
+                    Ok(n) => (STATUS_OK, &data[..n.min(len)]),
+                    Err(e) => {
+                        data = e.into_bytes();
+                        (STATUS_ERROR, &data[..])
+                    }
+                };
+                let mut reply = vec![OP_RECEIVE, status];
+                reply.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+                reply.extend_from_slice(payload);
+                stream
+                    .write_all(&reply)
+                    .map_err(|e| format!("发送回复失败: {}", e))?;
+            }
+            other => return Err(format!("未知的请求: 0x{:02x}", other)),
+        }
+    }
+}
+
+fn write_handshake(stream: &mut TcpStream, result: Result<(), &str>) -> Result<(), String> {
+    let mut reply = MAGIC.to_vec();
+    match result {
+        Ok(()) => reply.push(STATUS_OK),
+        Err(message) => {
+            reply.push(STATUS_ERROR);
+            reply.extend_from_slice(&(message.len() as u32).to_le_bytes());
+            reply.extend_from_slice(message.as_bytes());
+        }
+    }
+    stream
+        .write_all(&reply)
+        .map_err(|e| format!("发送握手失败: {}", e))
+}
+
+fn read_exact<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<(), String> {
+    reader
+        .read_exact(buf)
+        .map_err(|e| format!("读取桥接数据失败: {}", e))
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> Result<u8, String> {
+    let mut byte = [0u8; 1];
+    read_exact(reader, &mut byte)?;
+    Ok(byte[0])
+}
+
+fn read_len<R: Read>(reader: &mut R) -> Result<usize, String> {
+    let mut len = [0u8; 4];
+    read_exact(reader, &mut len)?;
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_PACKET_BYTES {
+        return Err(format!("包过大: {} 字节", len));
+    }
+    Ok(len)
+}
+
+/// 读取长度前缀和数据。
+fn read_payload<R: Read>(reader: &mut R) -> Result<Vec<u8>, String> {
+    let len = read_len(reader)?;
+    let mut data = vec![0u8; len];
+    read_exact(reader, &mut data)?;
+    Ok(data)
+}
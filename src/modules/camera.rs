@@ -0,0 +1,148 @@
+//! ElectronBot 库的摄像头采集（通过 ffmpeg 子进程）。
+//!
+//! ffmpeg 按平台选择采集后端（Linux 为 v4l2，macOS 为 avfoundation，
+//! Windows 为 dshow），居中裁剪为正方形并缩放到 240x240 后输出 BGR 原始帧。
+
+use std::ffi::OsStr;
+use std::io::BufReader;
+use std::process::{Child, ChildStdout};
+
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_WIDTH};
+use crate::modules::image::ImageBuffer;
+use crate::modules::source::FrameSource;
+use crate::modules::video::{read_raw_frame, spawn_ffmpeg};
+
+/// 摄像头采集选项。
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraOptions {
+    /// 设备名：Linux 为 `/dev/videoN` 或序号，macOS 为序号，Windows 为设备名称。
+    pub device: String,
+    /// 水平镜像（像照镜子一样），默认开启。
+    pub mirror: bool,
+    /// 请求的采集帧率。
+    pub frame_rate: Option<u32>,
+    /// 请求的采集分辨率。
+    pub resolution: Option<(u32, u32)>,
+}
+
+impl Default for CameraOptions {
+    fn default() -> Self {
+        Self {
+            device: "0".to_string(),
+            mirror: true,
+            frame_rate: None,
+            resolution: None,
+        }
+    }
+}
+
+impl CameraOptions {
+    /// 使用指定设备创建。
+    pub fn new(device: &str) -> Self {
+        Self {
+            device: device.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// 设置是否水平镜像。
+    pub fn mirror(mut self, mirror: bool) -> Self {
+        self.mirror = mirror;
+        self
+    }
+
+    /// 设置请求的采集帧率。
+    pub fn frame_rate(mut self, fps: u32) -> Self {
+        self.frame_rate = Some(fps);
+        self
+    }
+
+    /// 设置请求的采集分辨率。
+    pub fn resolution(mut self, width: u32, height: u32) -> Self {
+        self.resolution = Some((width, height));
+        self
+    }
+
+    /// ffmpeg 输入参数。
+    pub(crate) fn input_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        let (format, device) = platform_input(&self.device);
+        args.extend(["-f".to_string(), format.to_string()]);
+        if let Some(fps) = self.frame_rate {
+            args.extend(["-framerate".to_string(), fps.to_string()]);
+        }
+        if let Some((w, h)) = self.resolution {
+            args.extend(["-video_size".to_string(), format!("{}x{}", w, h)]);
+        }
+        args.extend(["-i".to_string(), device]);
+        args
+    }
+
+    /// 居中裁剪为正方形并缩放到屏幕大小的滤镜。
+    pub(crate) fn filter(&self) -> String {
+        let mut filter = format!(
+            "crop='min(iw,ih)':'min(iw,ih)',scale={}:{}",
+            FRAME_WIDTH, FRAME_HEIGHT
+        );
+        if self.mirror {
+            filter.push_str(",hflip");
+        }
+        filter
+    }
+}
+
+/// 当前平台的 ffmpeg 采集格式和设备参数。
+fn platform_input(device: &str) -> (&'static str, String) {
+    if cfg!(target_os = "linux") {
+        let device = if device.chars().all(|c| c.is_ascii_digit()) {
+            format!("/dev/video{}", device)
+        } else {
+            device.to_string()
+        };
+        ("v4l2", device)
+    } else if cfg!(target_os = "macos") {
+        ("avfoundation", device.to_string())
+    } else if cfg!(target_os = "windows") {
+        ("dshow", format!("video={}", device))
+    } else {
+        ("v4l2", device.to_string())
+    }
+}
+
+/// 摄像头帧源。
+pub struct CameraSource {
+    child: Child,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl CameraSource {
+    /// 打开默认摄像头（镜像显示）。
+    pub fn open_default() -> Result<Self, String> {
+        Self::open(&CameraOptions::default())
+    }
+
+    /// 按选项打开摄像头。
+    pub fn open(options: &CameraOptions) -> Result<Self, String> {
+        let args = options.input_args();
+        let input: Vec<&OsStr> = args.iter().map(OsStr::new).collect();
+        let (child, stdout) = spawn_ffmpeg(&input, &options.filter())?;
+
+        #[cfg(feature = "logging")]
+        log::info!("Opened camera {:?}", options.device);
+
+        Ok(Self { child, stdout })
+    }
+}
+
+impl FrameSource for CameraSource {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        read_raw_frame(&mut self.stdout, buffer)
+    }
+}
+
+impl Drop for CameraSource {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
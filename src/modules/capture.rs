@@ -0,0 +1,461 @@
+//! ElectronBot 库的协议抓包和回放。
+//!
+//! [`CaptureTransport`] 包装任意传输通道，把双向的每个包带时间戳写入抓包文件；
+//! [`ReplayTransport`] 读取抓包文件，把录下的 MCU 回复按顺序交给 `sync()`，
+//! 并逐包比较发送的数据，不需要硬件就能复现协议问题或编写回归测试。
+//! [`HexDumpTransport`] 在限定时间内把原始包以十六进制文本写入 `tx.hex` 和 `rx.hex`，
+//! 便于和逻辑分析仪的抓取结果逐字节对照。
+//!
+//! 文件格式：文件头 `EBCAP1\n`，随后是若干条记录，每条为
+//! 类型（1 字节：`T` 发送、`R` 接收、`E` 接收失败）+ 时间（u64 小端，抓包开始后的微秒数）
+//! + 长度（u32 小端）+ 数据（接收失败时为错误信息）。
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::modules::error::TransferErrorKind;
+use crate::modules::time::Instant;
+use crate::modules::transport::{Direction, Transport};
+
+/// 文件头和格式版本。
+const MAGIC: &[u8; 7] = b"EBCAP1\n";
+/// 单条记录的最大字节数。
+const MAX_RECORD_BYTES: usize = 1024 * 1024;
+
+/// 抓包记录的类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureKind {
+    /// 主机发送给 MCU。
+    Transmit,
+    /// 从 MCU 接收。
+    Receive,
+    /// 接收失败，数据为错误信息。
+    ReceiveError,
+}
+
+impl CaptureKind {
+    fn tag(self) -> u8 {
+        match self {
+            CaptureKind::Transmit => b'T',
+            CaptureKind::Receive => b'R',
+            CaptureKind::ReceiveError => b'E',
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            b'T' => Some(CaptureKind::Transmit),
+            b'R' => Some(CaptureKind::Receive),
+            b'E' => Some(CaptureKind::ReceiveError),
+            _ => None,
+        }
+    }
+}
+
+/// 一条抓包记录。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureRecord {
+    /// 记录类型。
+    pub kind: CaptureKind,
+    /// 抓包开始后的时间。
+    pub time: Duration,
+    /// 包数据。
+    pub data: Vec<u8>,
+}
+
+/// 读取抓包文件中的所有记录。
+pub fn load_capture<P: AsRef<Path>>(path: P) -> Result<Vec<CaptureRecord>, String> {
+    let file = File::open(path).map_err(|e| format!("打开抓包文件失败: {}", e))?;
+    read_capture(BufReader::new(file))
+}
+
+/// 从任意数据源读取抓包记录。
+pub fn read_capture<R: Read>(mut reader: R) -> Result<Vec<CaptureRecord>, String> {
+    let mut magic = [0u8; 7];
+    reader
+        .read_exact(&mut magic)
+        .map_err(|e| format!("读取抓包文件失败: {}", e))?;
+    if &magic != MAGIC {
+        return Err("不是 ElectronBot 抓包文件".to_string());
+    }
+
+    let mut records = Vec::new();
+    loop {
+        let mut tag = [0u8; 1];
+        match reader.read(&mut tag) {
+            Ok(0) => return Ok(records),
+            Ok(_) => {}
+            Err(e) => return Err(format!("读取抓包文件失败: {}", e)),
+        }
+        let kind = CaptureKind::from_tag(tag[0])
+            .ok_or_else(|| format!("第 {} 条记录类型无效", records.len() + 1))?;
+        let mut header = [0u8; 12];
+        reader
+            .read_exact(&mut header)
+            .map_err(|_| format!("第 {} 条记录不完整", records.len() + 1))?;
+        let micros = u64::from_le_bytes(header[..8].try_into().unwrap_or_default());
+        let len = u32::from_le_bytes(header[8..].try_into().unwrap_or_default()) as usize;
+        if len > MAX_RECORD_BYTES {
+            return Err(format!("第 {} 条记录过大: {} 字节", records.len() + 1, len));
+        }
+        let mut data = vec![0u8; len];
+        reader
+            .read_exact(&mut data)
+            .map_err(|_| format!("第 {} 条记录不完整", records.len() + 1))?;
+        records.push(CaptureRecord {
+            kind,
+            time: Duration::from_micros(micros),
+            data,
+        });
+    }
+}
+
+/// 创建抓包文件并写入文件头。
+pub(crate) fn create_capture_file<P: AsRef<Path>>(path: P) -> Result<BufWriter<File>, String> {
+    let file = File::create(path).map_err(|e| format!("创建抓包文件失败: {}", e))?;
+    let mut writer = BufWriter::new(file);
+    writer
+        .write_all(MAGIC)
+        .map_err(|e| format!("写入抓包文件失败: {}", e))?;
+    Ok(writer)
+}
+
+/// 抓包传输通道：转发给内部通道，同时把每个包写入文件。
+///
+/// 写文件失败不影响通信，只停止抓包。
+pub struct CaptureTransport<T: Transport> {
+    inner: T,
+    writer: Option<BufWriter<File>>,
+    started: Instant,
+}
+
+impl<T: Transport> CaptureTransport<T> {
+    /// 包装 `inner`，创建（或覆盖）抓包文件 `path`。
+    pub fn new<P: AsRef<Path>>(inner: T, path: P) -> Result<Self, String> {
+        Ok(Self::with_writer(inner, create_capture_file(path)?))
+    }
+
+    /// 包装 `inner`，写入已经写好文件头的 `writer`。
+    pub(crate) fn with_writer(inner: T, writer: BufWriter<File>) -> Self {
+        Self {
+            inner,
+            writer: Some(writer),
+            started: Instant::now(),
+        }
+    }
+
+    /// 取回内部通道（缓冲的记录会写入文件）。
+    pub fn into_inner(mut self) -> T {
+        self.flush();
+        self.inner
+    }
+
+    fn record(&mut self, kind: CaptureKind, data: &[u8]) {
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+        let micros = self.started.elapsed().as_micros() as u64;
+        let mut header = [0u8; 13];
+        header[0] = kind.tag();
+        header[1..9].copy_from_slice(&micros.to_le_bytes());
+        header[9..].copy_from_slice(&(data.len() as u32).to_le_bytes());
+        let result = writer
+            .write_all(&header)
+            .and_then(|_| writer.write_all(data));
+        // 每次接收后刷新一次（每个同步周期一次），异常退出时也能保留大部分记录
+        let result = if kind == CaptureKind::Transmit {
+            result
+        } else {
+            result.and_then(|_| writer.flush())
+        };
+        if let Err(_e) = result {
+            #[cfg(feature = "logging")]
+            log::warn!("Capture write failed, capture stopped: {}", _e);
+            self.writer = None;
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Some(writer) = &mut self.writer {
+            let _ = writer.flush();
+        }
+    }
+}
+
+impl<T: Transport> Transport for CaptureTransport<T> {
+    fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+        self.record(CaptureKind::Transmit, data);
+        self.inner.transmit(data)
+    }
+
+    fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+        match self.inner.receive(data) {
+            Ok(len) => {
+                let len = len.min(data.len());
+                self.record(CaptureKind::Receive, &data[..len]);
+                Ok(len)
+            }
+            Err(e) => {
+                self.record(CaptureKind::ReceiveError, e.as_bytes());
+                Err(e)
+            }
+        }
+    }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.inner.set_timeout(timeout)
+    }
+
+    fn last_error_kind(&self) -> Option<TransferErrorKind> {
+        self.inner.last_error_kind()
+    }
+
+    fn clear_halt(&mut self, direction: Direction) -> Result<(), String> {
+        self.inner.clear_halt(direction)
+    }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn std::any::Any> {
+        self.inner.as_any_mut()
+    }
+}
+
+/// 十六进制转储的发送和接收文件。
+pub(crate) type HexDumpWriters = (BufWriter<File>, BufWriter<File>);
+
+/// 在目录 `dir` 中创建（或覆盖）`tx.hex` 和 `rx.hex`。
+pub(crate) fn create_hex_dump_files<P: AsRef<Path>>(dir: P) -> Result<HexDumpWriters, String> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir).map_err(|e| format!("创建转储目录失败: {}", e))?;
+    let create = |name: &str| {
+        File::create(dir.join(name))
+            .map(BufWriter::new)
+            .map_err(|e| format!("创建转储文件失败: {}", e))
+    };
+    Ok((create("tx.hex")?, create("rx.hex")?))
+}
+
+/// 十六进制转储传输通道：在 `duration` 内把发送和接收的每个包分别写入目录中的
+/// `tx.hex` 和 `rx.hex`，之后只转发。
+///
+/// 每行一个包：抓包开始后的微秒数、字节数和以空格分隔的十六进制数据，
+/// 接收失败时写入 `ERROR` 和错误信息。写文件失败不影响通信，只停止转储。
+pub struct HexDumpTransport<T: Transport> {
+    inner: T,
+    tx: Option<BufWriter<File>>,
+    rx: Option<BufWriter<File>>,
+    started: Instant,
+    duration: Duration,
+}
+
+impl<T: Transport> HexDumpTransport<T> {
+    /// 包装 `inner`，在目录 `dir` 中创建（或覆盖）`tx.hex` 和 `rx.hex`，转储 `duration`。
+    pub fn new<P: AsRef<Path>>(inner: T, dir: P, duration: Duration) -> Result<Self, String> {
+        Ok(Self::with_writers(
+            inner,
+            create_hex_dump_files(dir)?,
+            duration,
+        ))
+    }
+
+    /// 包装 `inner`，写入已经创建好的发送和接收文件。
+    pub(crate) fn with_writers(inner: T, writers: HexDumpWriters, duration: Duration) -> Self {
+        let (tx, rx) = writers;
+        Self {
+            inner,
+            tx: Some(tx),
+            rx: Some(rx),
+            started: Instant::now(),
+            duration,
+        }
+    }
+
+    /// 是否仍在转储（没有超过时限，也没有写入失败）。
+    pub fn is_dumping(&self) -> bool {
+        (self.tx.is_some() || self.rx.is_some()) && self.started.elapsed() < self.duration
+    }
+
+    /// 取回内部通道（缓冲的内容会写入文件）。
+    pub fn into_inner(mut self) -> T {
+        self.stop();
+        self.inner
+    }
+
+    fn dump(&mut self, direction: Direction, data: Result<&[u8], &str>) {
+        let elapsed = self.started.elapsed();
+        if elapsed >= self.duration {
+            self.stop();
+            return;
+        }
+        let writer = match direction {
+            Direction::Out => &mut self.tx,
+            Direction::In => &mut self.rx,
+        };
+        let Some(out) = writer else {
+            return;
+        };
+        let mut line = match data {
+            Ok(bytes) => {
+                let mut line = String::with_capacity(24 + bytes.len() * 3);
+                line.push_str(&format!("{} {}", elapsed.as_micros(), bytes.len()));
+                for byte in bytes {
+                    line.push_str(&format!(" {:02x}", byte));
+                }
+                line
+            }
+            Err(e) => format!("{} ERROR {}", elapsed.as_micros(), e),
+        };
+        line.push('\n');
+        if let Err(_e) = out.write_all(line.as_bytes()) {
+            #[cfg(feature = "logging")]
+            log::warn!("Hex dump write failed, dump stopped: {}", _e);
+            *writer = None;
+        }
+    }
+
+    fn stop(&mut self) {
+        for writer in [&mut self.tx, &mut self.rx] {
+            if let Some(mut out) = writer.take() {
+                let _ = out.flush();
+            }
+        }
+    }
+}
+
+impl<T: Transport> Transport for HexDumpTransport<T> {
+    fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+        self.dump(Direction::Out, Ok(data));
+        self.inner.transmit(data)
+    }
+
+    fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+        match self.inner.receive(data) {
+            Ok(len) => {
+                let len = len.min(data.len());
+                self.dump(Direction::In, Ok(&data[..len]));
+                Ok(len)
+            }
+            Err(e) => {
+                self.dump(Direction::In, Err(&e));
+                Err(e)
+            }
+        }
+    }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.inner.set_timeout(timeout)
+    }
+
+    fn last_error_kind(&self) -> Option<TransferErrorKind> {
+        self.inner.last_error_kind()
+    }
+
+    fn clear_halt(&mut self, direction: Direction) -> Result<(), String> {
+        self.inner.clear_halt(direction)
+    }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn std::any::Any> {
+        self.inner.as_any_mut()
+    }
+}
+
+/// 回放统计。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayStats {
+    /// 已发送的包数。
+    pub transmitted: usize,
+    /// 已回放的接收记录数。
+    pub received: usize,
+    /// 与抓包内容不一致的发送包数（超出抓包范围的包也计入）。
+    pub mismatches: usize,
+    /// 第一个不一致的发送包序号（从 0 开始）。
+    pub first_mismatch: Option<usize>,
+}
+
+/// 回放传输通道：接收时按顺序返回抓包中的 MCU 回复，发送时与抓包中的数据比较。
+///
+/// 发送和接收分别按各自的顺序匹配，统计通过 [`ReplayTransport::stats`] 共享，
+/// 交给 `ElectronBot` 之后仍然可以读取。
+pub struct ReplayTransport {
+    transmits: Vec<Vec<u8>>,
+    receives: Vec<Result<Vec<u8>, String>>,
+    next_receive: usize,
+    stats: Arc<Mutex<ReplayStats>>,
+}
+
+impl ReplayTransport {
+    /// 从抓包记录创建。
+    pub fn new(records: Vec<CaptureRecord>) -> Self {
+        let mut transmits = Vec::new();
+        let mut receives = Vec::new();
+        for record in records {
+            match record.kind {
+                CaptureKind::Transmit => transmits.push(record.data),
+                CaptureKind::Receive => receives.push(Ok(record.data)),
+                CaptureKind::ReceiveError => {
+                    receives.push(Err(String::from_utf8_lossy(&record.data).into_owned()))
+                }
+            }
+        }
+        Self {
+            transmits,
+            receives,
+            next_receive: 0,
+            stats: Arc::new(Mutex::new(ReplayStats::default())),
+        }
+    }
+
+    /// 从抓包文件创建。
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        load_capture(path).map(Self::new)
+    }
+
+    /// 共享的回放统计。
+    pub fn stats(&self) -> Arc<Mutex<ReplayStats>> {
+        self.stats.clone()
+    }
+
+    /// 是否已经回放完所有接收记录。
+    pub fn is_finished(&self) -> bool {
+        self.next_receive >= self.receives.len()
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+        let mut stats = self
+            .stats
+            .lock()
+            .map_err(|_| "回放统计不可用".to_string())?;
+        let index = stats.transmitted;
+        stats.transmitted += 1;
+        if self.transmits.get(index).map(Vec::as_slice) != Some(data) {
+            stats.mismatches += 1;
+            stats.first_mismatch.get_or_insert(index);
+            #[cfg(feature = "logging")]
+            log::warn!("Replay transmit {} differs from capture", index);
+        }
+        Ok(true)
+    }
+
+    fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+        let record = self
+            .receives
+            .get(self.next_receive)
+            .ok_or_else(|| "回放数据已用完".to_string())?;
+        self.next_receive += 1;
+        if let Ok(mut stats) = self.stats.lock() {
+            stats.received += 1;
+        }
+        match record {
+            Ok(reply) => {
+                let len = reply.len().min(data.len());
+                data[..len].copy_from_slice(&reply[..len]);
+                Ok(len)
+            }
+            Err(e) => Err(e.clone()),
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! ElectronBot 库的颜色工具（HSV、十六进制解析、调色板和渐变）。
+
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_WIDTH};
+use crate::modules::image::ImageBuffer;
+use crate::modules::types::Color;
+
+impl Color {
+    /// 从 HSV 创建（色相 0-360°，饱和度和明度 0.0-1.0）。
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let h = hue.rem_euclid(360.0) / 60.0;
+        let s = saturation.clamp(0.0, 1.0);
+        let v = value.clamp(0.0, 1.0);
+        let c = v * s;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = v - c;
+        let to_u8 = |f: f32| ((f + m) * 255.0).round() as u8;
+        Color::Custom(to_u8(r), to_u8(g), to_u8(b))
+    }
+
+    /// 转换为 HSV（色相 0-360°，饱和度和明度 0.0-1.0）。
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let (r, g, b) = self.rgb();
+        let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        let saturation = if max == 0.0 { 0.0 } else { delta / max };
+        (hue, saturation, max)
+    }
+
+    /// 从十六进制字符串解析，支持 `#RRGGBB`、`RRGGBB` 和 `#RGB`。
+    pub fn from_hex(hex: &str) -> Result<Self, String> {
+        let digits = hex.trim().trim_start_matches('#');
+        let invalid = || format!("无效的颜色: {}", hex);
+        if !digits.is_ascii() {
+            return Err(invalid());
+        }
+        let parse = |s: &str| u8::from_str_radix(s, 16).map_err(|_| invalid());
+        match digits.len() {
+            6 => Ok(Color::Custom(
+                parse(&digits[0..2])?,
+                parse(&digits[2..4])?,
+                parse(&digits[4..6])?,
+            )),
+            3 => {
+                let short = |i: usize| parse(&digits[i..i + 1]).map(|v| v * 17);
+                Ok(Color::Custom(short(0)?, short(1)?, short(2)?))
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    /// 转换为 `#RRGGBB` 字符串。
+    pub fn to_hex(&self) -> String {
+        let (r, g, b) = self.rgb();
+        format!("#{:02X}{:02X}{:02X}", r, g, b)
+    }
+
+    /// 在两种颜色之间线性插值（`t` 为 0.0 时返回自身，1.0 时返回 `other`）。
+    pub fn lerp(&self, other: Color, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let (r0, g0, b0) = self.rgb();
+        let (r1, g1, b1) = other.rgb();
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Color::Custom(mix(r0, r1), mix(g0, g1), mix(b0, b1))
+    }
+}
+
+impl std::str::FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Color::from_hex(s)
+    }
+}
+
+/// 预定义调色板。
+pub struct Palette;
+
+impl Palette {
+    /// 彩虹色。
+    pub const RAINBOW: &'static [Color] = &[
+        Color::Custom(255, 0, 0),
+        Color::Custom(255, 127, 0),
+        Color::Custom(255, 255, 0),
+        Color::Custom(0, 255, 0),
+        Color::Custom(0, 0, 255),
+        Color::Custom(75, 0, 130),
+        Color::Custom(148, 0, 211),
+    ];
+
+    /// 柔和色。
+    pub const PASTEL: &'static [Color] = &[
+        Color::Custom(255, 179, 186),
+        Color::Custom(255, 223, 186),
+        Color::Custom(255, 255, 186),
+        Color::Custom(186, 255, 201),
+        Color::Custom(186, 225, 255),
+    ];
+
+    /// 暖色（日落）。
+    pub const SUNSET: &'static [Color] = &[
+        Color::Custom(255, 94, 77),
+        Color::Custom(255, 154, 0),
+        Color::Custom(255, 206, 84),
+        Color::Custom(237, 117, 160),
+    ];
+
+    /// 冷色（海洋）。
+    pub const OCEAN: &'static [Color] = &[
+        Color::Custom(0, 31, 63),
+        Color::Custom(0, 116, 217),
+        Color::Custom(127, 219, 255),
+        Color::Custom(57, 204, 204),
+    ];
+
+    /// 灰阶。
+    pub const GRAYSCALE: &'static [Color] = &[
+        Color::Black,
+        Color::Custom(64, 64, 64),
+        Color::Custom(128, 128, 128),
+        Color::Custom(192, 192, 192),
+        Color::White,
+    ];
+}
+
+/// 渐变方向。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientKind {
+    /// 从左到右。
+    Horizontal,
+    /// 从上到下。
+    Vertical,
+    /// 从左上到右下。
+    Diagonal,
+    /// 从中心向外。
+    Radial,
+}
+
+/// 多色标渐变。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    kind: GradientKind,
+    /// 色标（位置 0.0-1.0，按位置排序）。
+    stops: Vec<(f32, Color)>,
+}
+
+impl Gradient {
+    /// 创建双色渐变。
+    pub fn new(kind: GradientKind, start: Color, end: Color) -> Self {
+        Self {
+            kind,
+            stops: vec![(0.0, start), (1.0, end)],
+        }
+    }
+
+    /// 由调色板创建等距色标的渐变，调色板为空时为黑色。
+    pub fn from_palette(kind: GradientKind, colors: &[Color]) -> Self {
+        let stops = match colors.len() {
+            0 => vec![(0.0, Color::Black)],
+            1 => vec![(0.0, colors[0])],
+            n => colors
+                .iter()
+                .enumerate()
+                .map(|(i, &c)| (i as f32 / (n - 1) as f32, c))
+                .collect(),
+        };
+        Self { kind, stops }
+    }
+
+    /// 添加色标（构建器风格）。
+    pub fn with_stop(mut self, position: f32, color: Color) -> Self {
+        let position = position.clamp(0.0, 1.0);
+        let index = self.stops.partition_point(|(p, _)| *p <= position);
+        self.stops.insert(index, (position, color));
+        self
+    }
+
+    /// 所有色标的色相旋转 `degrees` 度后的渐变。
+    pub fn hue_shifted(&self, degrees: f32) -> Gradient {
+        let stops = self
+            .stops
+            .iter()
+            .map(|&(p, c)| {
+                let (h, s, v) = c.to_hsv();
+                (p, Color::from_hsv(h + degrees, s, v))
+            })
+            .collect();
+        Gradient {
+            kind: self.kind,
+            stops,
+        }
+    }
+
+    /// 渐变方向。
+    pub fn kind(&self) -> GradientKind {
+        self.kind
+    }
+
+    /// 获取位置 `t`（0.0-1.0）处的颜色。
+    pub fn at(&self, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let first = self.stops[0];
+        if t <= first.0 {
+            return first.1;
+        }
+        for pair in self.stops.windows(2) {
+            let ((p0, c0), (p1, c1)) = (pair[0], pair[1]);
+            if t <= p1 {
+                let span = p1 - p0;
+                let local = if span > 0.0 { (t - p0) / span } else { 1.0 };
+                return c0.lerp(c1, local);
+            }
+        }
+        self.stops[self.stops.len() - 1].1
+    }
+
+    /// 矩形内 `(dx, dy)` 处的渐变位置。
+    fn position(&self, dx: usize, dy: usize, width: usize, height: usize) -> f32 {
+        let span = |v: usize, len: usize| {
+            if len > 1 {
+                v as f32 / (len - 1) as f32
+            } else {
+                0.0
+            }
+        };
+        match self.kind {
+            GradientKind::Horizontal => span(dx, width),
+            GradientKind::Vertical => span(dy, height),
+            GradientKind::Diagonal => (span(dx, width) + span(dy, height)) / 2.0,
+            GradientKind::Radial => {
+                let cx = (width as f32 - 1.0) / 2.0;
+                let cy = (height as f32 - 1.0) / 2.0;
+                let r = (cx * cx + cy * cy).sqrt().max(1.0);
+                let (x, y) = (dx as f32 - cx, dy as f32 - cy);
+                (x * x + y * y).sqrt() / r
+            }
+        }
+    }
+}
+
+impl ImageBuffer {
+    /// 用渐变填充矩形。
+    pub fn fill_rect_gradient(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        gradient: &Gradient,
+    ) {
+        for dy in 0..height {
+            for dx in 0..width {
+                let t = gradient.position(dx, dy, width, height);
+                self.set_pixel(x + dx, y + dy, gradient.at(t));
+            }
+        }
+    }
+
+    /// 用渐变填充整个缓冲区（背景）。
+    pub fn fill_gradient(&mut self, gradient: &Gradient) {
+        self.fill_rect_gradient(0, 0, FRAME_WIDTH, FRAME_HEIGHT, gradient);
+    }
+}
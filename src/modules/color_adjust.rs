@@ -0,0 +1,125 @@
+//! ElectronBot 库的色彩校正（亮度、对比度、gamma、白点）。
+//!
+//! 校正参数预先计算为每通道 256 项查找表，发送时逐字节查表，
+//! 每帧开销与复制缓冲区相当。
+
+use crate::modules::image::ImageBuffer;
+
+/// 色彩校正参数。
+///
+/// 每个通道按以下顺序处理（数值归一化到 0.0-1.0）：
+/// 白点增益 → 对比度（以 0.5 为中心）→ 亮度偏移 → gamma。
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(default))]
+pub struct ColorAdjust {
+    /// 亮度偏移（-1.0-1.0，0.0 不变）。
+    pub brightness: f32,
+    /// 对比度（1.0 不变，大于 1.0 增强）。
+    pub contrast: f32,
+    /// gamma（1.0 不变，大于 1.0 提亮暗部）。
+    pub gamma: f32,
+    /// 白点增益（R, G, B），用于校正偏色，例如 `[1.0, 0.95, 0.85]` 减轻偏蓝。
+    pub white_point: [f32; 3],
+}
+
+impl Default for ColorAdjust {
+    fn default() -> Self {
+        Self {
+            brightness: 0.0,
+            contrast: 1.0,
+            gamma: 1.0,
+            white_point: [1.0, 1.0, 1.0],
+        }
+    }
+}
+
+impl ColorAdjust {
+    /// 创建不做任何校正的参数。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置亮度偏移。
+    pub fn brightness(mut self, brightness: f32) -> Self {
+        self.brightness = brightness.clamp(-1.0, 1.0);
+        self
+    }
+
+    /// 设置对比度。
+    pub fn contrast(mut self, contrast: f32) -> Self {
+        self.contrast = contrast.max(0.0);
+        self
+    }
+
+    /// 设置 gamma。
+    pub fn gamma(mut self, gamma: f32) -> Self {
+        self.gamma = gamma;
+        self
+    }
+
+    /// 设置白点增益（R, G, B）。
+    pub fn white_point(mut self, r: f32, g: f32, b: f32) -> Self {
+        self.white_point = [r, g, b];
+        self
+    }
+
+    /// 是否不做任何校正。
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 计算查找表。
+    pub fn to_lut(&self) -> ColorLut {
+        let gamma = if self.gamma > 0.0 { self.gamma } else { 1.0 };
+        let mut tables = [[0u8; 256]; 3];
+        // 查找表按缓冲区字节顺序（B, G, R）排列
+        for (table, gain) in tables.iter_mut().zip(self.white_point.iter().rev()) {
+            for (i, out) in table.iter_mut().enumerate() {
+                let mut v = i as f32 / 255.0 * gain;
+                v = (v - 0.5) * self.contrast + 0.5 + self.brightness;
+                v = v.clamp(0.0, 1.0).powf(1.0 / gamma);
+                *out = (v * 255.0).round() as u8;
+            }
+        }
+        ColorLut { tables }
+    }
+}
+
+/// 每通道 256 项查找表（按缓冲区字节顺序 B, G, R）。
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorLut {
+    tables: [[u8; 256]; 3],
+}
+
+impl ColorLut {
+    /// 不做任何变换的查找表。
+    pub fn identity() -> Self {
+        let mut table = [0u8; 256];
+        for (i, v) in table.iter_mut().enumerate() {
+            *v = i as u8;
+        }
+        Self { tables: [table; 3] }
+    }
+
+    /// 从三张表创建（按缓冲区字节顺序 B, G, R）。
+    pub fn from_tables(tables: [[u8; 256]; 3]) -> Self {
+        Self { tables }
+    }
+
+    /// 指定字节位置（0 = B, 1 = G, 2 = R）的查找表。
+    pub fn table(&self, channel: usize) -> Option<&[u8; 256]> {
+        self.tables.get(channel)
+    }
+}
+
+impl ImageBuffer {
+    /// 逐像素应用查找表。
+    pub fn apply_lut(&mut self, lut: &ColorLut) {
+        for pixel in self.data.chunks_exact_mut(3) {
+            for (v, table) in pixel.iter_mut().zip(lut.tables.iter()) {
+                *v = table[*v as usize];
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! ElectronBot 库的配置（启动姿态和启动画面）。
+//!
+//! 启用 `config` feature 后可以从 TOML 文件加载：
+//!
+//! ```toml
+//! [startup]
+//! apply_on_connect = true
+//! pose = [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+//! enable_servos = true
+//! color = [0, 0, 0]
+//! image = "assets/test.png"
+//!
+//! [orientation]
+//! rotation = 90
+//! flip_horizontal = false
+//! flip_vertical = false
+//!
+//! [color_adjust]
+//! contrast = 1.1
+//! gamma = 1.2
+//! white_point = [1.0, 0.95, 0.85]
+//! ```
+
+use std::path::PathBuf;
+
+use crate::modules::color_adjust::ColorAdjust;
+use crate::modules::types::Orientation;
+
+/// 连接后自动应用的启动状态。
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(default))]
+pub struct StartupConfig {
+    /// 连接成功后是否立即应用启动状态。
+    pub apply_on_connect: bool,
+    /// 启动姿态（6 个舵机角度）。
+    pub pose: Option<[f32; 6]>,
+    /// 应用启动姿态时是否使能舵机。
+    pub enable_servos: bool,
+    /// 启动画面纯色（RGB），在 `image` 之前绘制。
+    pub color: Option<[u8; 3]>,
+    /// 启动画面图片路径。
+    pub image: Option<PathBuf>,
+    /// 启动场景名称（见 [`SCENE_NAMES`](crate::SCENE_NAMES)，表情名称也可以），
+    /// 在 `color` 之后、`image` 之前绘制第一帧。
+    pub scene: Option<String>,
+    /// 启动场景的配色主题（见 [`THEME_NAMES`](crate::THEME_NAMES)），
+    /// 只设置主题时显示平静表情。
+    pub theme: Option<String>,
+}
+
+impl StartupConfig {
+    /// 是否有需要应用的内容。
+    pub fn is_empty(&self) -> bool {
+        self.pose.is_none()
+            && self.color.is_none()
+            && self.image.is_none()
+            && self.scene.is_none()
+            && self.theme.is_none()
+    }
+}
+
+/// ElectronBot 配置。
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(default))]
+pub struct BotConfig {
+    /// 启动状态。
+    pub startup: StartupConfig,
+    /// 屏幕安装方向，发送时应用。
+    pub orientation: Orientation,
+    /// 色彩校正，发送时应用。
+    pub color_adjust: ColorAdjust,
+}
+
+impl BotConfig {
+    /// 创建默认配置（连接时不做任何额外操作）。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从 TOML 字符串解析。
+    #[cfg(feature = "config")]
+    pub fn from_toml_str(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|e| format!("解析配置失败: {}", e))
+    }
+
+    /// 从 TOML 文件加载。
+    #[cfg(feature = "config")]
+    pub fn load_from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("读取配置失败: {}", e))?;
+        Self::from_toml_str(&text)
+    }
+}
@@ -0,0 +1,117 @@
+//! ElectronBot 库的 JPEG/PNG 快速解码。
+//!
+//! 直接用解码器把像素读入复用的临时缓冲区，再一遍完成最近邻缩放和
+//! RGB 到 BGR 的转换，不经过 `DynamicImage`、`resize` 和 `to_rgb8`
+//! 的中间分配，适合高帧率幻灯片。
+
+use std::cell::RefCell;
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegDecoder;
+use image::codecs::png::PngDecoder;
+use image::{ColorType, DynamicImage, ImageDecoder, ImageFormat};
+
+use crate::modules::image::{FitOptions, ImageBuffer};
+
+thread_local! {
+    /// 解码用的临时缓冲区，在同一线程的多次解码之间复用。
+    static SCRATCH: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+impl ImageBuffer {
+    /// 从 JPEG 数据快速加载（拉伸填满屏幕，最近邻插值）。
+    pub fn load_jpeg_bytes(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.load_jpeg_bytes_with(bytes, &FitOptions::default())
+    }
+
+    /// 按适配选项从 JPEG 数据快速加载（始终使用最近邻插值）。
+    pub fn load_jpeg_bytes_with(
+        &mut self,
+        bytes: &[u8],
+        options: &FitOptions,
+    ) -> Result<(), String> {
+        let decoder =
+            JpegDecoder::new(Cursor::new(bytes)).map_err(|e| format!("解析 JPEG 失败: {}", e))?;
+        self.load_decoded(decoder, options)
+    }
+
+    /// 从 PNG 数据快速加载（拉伸填满屏幕，最近邻插值）。
+    pub fn load_png_bytes(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.load_png_bytes_with(bytes, &FitOptions::default())
+    }
+
+    /// 按适配选项从 PNG 数据快速加载（始终使用最近邻插值）。
+    pub fn load_png_bytes_with(
+        &mut self,
+        bytes: &[u8],
+        options: &FitOptions,
+    ) -> Result<(), String> {
+        let decoder =
+            PngDecoder::new(Cursor::new(bytes)).map_err(|e| format!("解析 PNG 失败: {}", e))?;
+        self.load_decoded(decoder, options)
+    }
+
+    /// 按适配选项从编码后的图片数据加载，JPEG 和 PNG 走快速路径，其他格式走通用路径。
+    pub fn load_image_bytes_with(
+        &mut self,
+        bytes: &[u8],
+        options: &FitOptions,
+    ) -> Result<(), String> {
+        match image::guess_format(bytes) {
+            Ok(ImageFormat::Jpeg) => self.load_jpeg_bytes_with(bytes, options),
+            Ok(ImageFormat::Png) => self.load_png_bytes_with(bytes, options),
+            _ => {
+                let img =
+                    image::load_from_memory(bytes).map_err(|e| format!("解码图片失败: {}", e))?;
+                self.load_from_image_with(&img, options);
+                Ok(())
+            }
+        }
+    }
+
+    /// 解码到临时缓冲区并采样到帧缓冲区。
+    fn load_decoded<D: ImageDecoder>(
+        &mut self,
+        decoder: D,
+        options: &FitOptions,
+    ) -> Result<(), String> {
+        let (width, height) = decoder.dimensions();
+        let (width, height) = (width as usize, height as usize);
+        if width == 0 || height == 0 {
+            return Err("图片尺寸不能为 0".to_string());
+        }
+        let channels = match decoder.color_type() {
+            ColorType::L8 => 1,
+            ColorType::La8 => 2,
+            ColorType::Rgb8 => 3,
+            ColorType::Rgba8 => 4,
+            _ => {
+                // 16 位等高位深图片需要色调映射，走通用路径
+                let img = DynamicImage::from_decoder(decoder)
+                    .map_err(|e| format!("解码图片失败: {}", e))?;
+                self.load_from_image_with(&img, options);
+                return Ok(());
+            }
+        };
+
+        SCRATCH.with(|scratch| {
+            let mut pixels = scratch.borrow_mut();
+            pixels.resize(decoder.total_bytes() as usize, 0);
+            decoder
+                .read_image(&mut pixels)
+                .map_err(|e| format!("解码图片失败: {}", e))?;
+
+            let stride = width * channels;
+            self.load_nearest(width, height, options, |x, y| {
+                let p = &pixels[y * stride + x * channels..];
+                if channels < 3 {
+                    [p[0], p[0], p[0]]
+                } else {
+                    // 将 RGB 转换为 MCU 所需的 BGR
+                    [p[2], p[1], p[0]]
+                }
+            });
+            Ok(())
+        })
+    }
+}
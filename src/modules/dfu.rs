@@ -0,0 +1,643 @@
+//! ElectronBot 库的固件更新（DFU）。
+//!
+//! ElectronBot 使用 STM32，芯片内置的 bootloader 在 USB 上实现 ST 的 DfuSe 协议
+//! （USB ID `0483:df11`）。[`DfuDevice`] 打开处于 bootloader 模式的设备，
+//! 按闪存布局擦除扇区、逐块写入固件、回读校验，最后让设备离开 bootloader 运行新固件，
+//! 过程中通过回调报告进度。
+//!
+//! 固件提供 DFU 运行时接口时可以用 [`reboot_to_bootloader`] 直接重启进入 bootloader，
+//! 原版固件没有这个接口，需要按住 BOOT0 键重新上电。
+//!
+//! 控制传输通过 [`DfuIo`] 抽象，测试时可以换成模拟的 bootloader。
+
+use std::time::Duration;
+
+use rusb::{Context, DeviceHandle, UsbContext};
+
+use crate::modules::usb::UsbDevice;
+
+/// STM32 bootloader 的 USB 厂商 ID。
+pub const STM32_DFU_VID: u16 = 0x0483;
+
+/// STM32 bootloader 的 USB 产品 ID。
+pub const STM32_DFU_PID: u16 = 0xdf11;
+
+/// STM32 内部闪存的起始地址。
+pub const FLASH_BASE: u32 = 0x0800_0000;
+
+/// 设备没有报告块大小时使用的默认值。
+const DEFAULT_TRANSFER_SIZE: usize = 2048;
+
+/// 控制传输超时。
+const CONTROL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 等待擦除或写入完成时查询状态的最大次数。
+const MAX_POLLS: usize = 1000;
+
+// DFU 请求
+const DFU_DETACH: u8 = 0;
+const DFU_DNLOAD: u8 = 1;
+const DFU_UPLOAD: u8 = 2;
+const DFU_GETSTATUS: u8 = 3;
+const DFU_CLRSTATUS: u8 = 4;
+const DFU_ABORT: u8 = 6;
+
+// DfuSe 命令（块 0）
+const DFUSE_SET_ADDRESS: u8 = 0x21;
+const DFUSE_ERASE: u8 = 0x41;
+
+/// DFU 设备状态。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DfuState {
+    /// 应用程序空闲（运行时模式）。
+    AppIdle,
+    /// 应用程序等待 USB 复位后进入 bootloader。
+    AppDetach,
+    /// bootloader 空闲。
+    Idle,
+    /// 收到下载块，等待查询状态。
+    DownloadSync,
+    /// 正在擦除或写入。
+    DownloadBusy,
+    /// 下载块处理完成。
+    DownloadIdle,
+    /// 下载结束，等待查询状态。
+    ManifestSync,
+    /// 正在应用固件。
+    Manifest,
+    /// 等待 USB 复位。
+    ManifestWaitReset,
+    /// 正在上传。
+    UploadIdle,
+    /// 出错，需要清除状态。
+    Error,
+    /// 未知状态值。
+    Unknown(u8),
+}
+
+impl From<u8> for DfuState {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => DfuState::AppIdle,
+            1 => DfuState::AppDetach,
+            2 => DfuState::Idle,
+            3 => DfuState::DownloadSync,
+            4 => DfuState::DownloadBusy,
+            5 => DfuState::DownloadIdle,
+            6 => DfuState::ManifestSync,
+            7 => DfuState::Manifest,
+            8 => DfuState::ManifestWaitReset,
+            9 => DfuState::UploadIdle,
+            10 => DfuState::Error,
+            other => DfuState::Unknown(other),
+        }
+    }
+}
+
+/// `DFU_GETSTATUS` 的结果。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DfuStatus {
+    /// 状态码，0 表示正常。
+    pub status: u8,
+    /// 再次查询之前需要等待的时间。
+    pub poll_timeout: Duration,
+    /// 设备状态。
+    pub state: DfuState,
+}
+
+impl DfuStatus {
+    /// 解析 6 字节的状态数据。
+    pub fn parse(data: &[u8]) -> Result<Self, String> {
+        if data.len() < 6 {
+            return Err(format!(
+                "DFU 状态长度应为 6 字节，实际为 {} 字节",
+                data.len()
+            ));
+        }
+        let millis = u32::from_le_bytes([data[1], data[2], data[3], 0]);
+        Ok(Self {
+            status: data[0],
+            poll_timeout: Duration::from_millis(millis as u64),
+            state: DfuState::from(data[4]),
+        })
+    }
+}
+
+/// 闪存扇区。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlashSector {
+    /// 起始地址。
+    pub address: u32,
+    /// 字节数。
+    pub size: u32,
+}
+
+/// 解析 DfuSe 接口字符串中的闪存布局，例如 `@Internal Flash  /0x08000000/04*016Kg,01*064Kg,07*128Kg`。
+pub fn parse_layout(text: &str) -> Result<Vec<FlashSector>, String> {
+    let invalid = || format!("闪存布局无效: {}", text);
+    let mut parts = text.split('/').skip(1);
+    let mut sectors = Vec::new();
+    while let Some(address) = parts.next() {
+        let address = address.trim();
+        let address = address
+            .strip_prefix("0x")
+            .or_else(|| address.strip_prefix("0X"))
+            .ok_or_else(invalid)?;
+        let mut address = u32::from_str_radix(address, 16).map_err(|_| invalid())?;
+        let groups = parts.next().ok_or_else(invalid)?;
+        for group in groups.split(',') {
+            let (count, size) = group.trim().split_once('*').ok_or_else(invalid)?;
+            let count: u32 = count.trim().parse().map_err(|_| invalid())?;
+            // 大小后面是单位（K/M/空格）和属性字母
+            let digits: String = size.chars().take_while(char::is_ascii_digit).collect();
+            let unit = size[digits.len()..].chars().next().unwrap_or(' ');
+            let mut size: u32 = digits.parse().map_err(|_| invalid())?;
+            size *= match unit {
+                'K' => 1024,
+                'M' => 1024 * 1024,
+                _ => 1,
+            };
+            for _ in 0..count {
+                sectors.push(FlashSector { address, size });
+                address = address.wrapping_add(size);
+            }
+        }
+    }
+    if sectors.is_empty() {
+        return Err(invalid());
+    }
+    Ok(sectors)
+}
+
+/// 固件更新的阶段。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DfuStage {
+    /// 擦除扇区。
+    Erase,
+    /// 写入固件。
+    Download,
+    /// 回读校验。
+    Verify,
+}
+
+/// 固件更新进度。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DfuProgress {
+    /// 当前阶段。
+    pub stage: DfuStage,
+    /// 当前阶段已完成的量（擦除为扇区数，写入和校验为字节数）。
+    pub done: usize,
+    /// 当前阶段的总量。
+    pub total: usize,
+}
+
+/// 固件更新选项。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlashOptions {
+    /// 写入地址。
+    pub address: u32,
+    /// 写入后回读校验。
+    pub verify: bool,
+    /// 完成后离开 bootloader，运行新固件。
+    pub leave: bool,
+}
+
+impl FlashOptions {
+    /// 默认选项：写入闪存起始地址，校验并运行新固件。
+    pub fn new() -> Self {
+        Self {
+            address: FLASH_BASE,
+            verify: true,
+            leave: true,
+        }
+    }
+
+    /// 设置写入地址。
+    pub fn address(mut self, address: u32) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// 设置是否回读校验。
+    pub fn verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    /// 设置完成后是否离开 bootloader。
+    pub fn leave(mut self, leave: bool) -> Self {
+        self.leave = leave;
+        self
+    }
+}
+
+impl Default for FlashOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// DFU 控制传输。
+pub trait DfuIo {
+    /// 类请求输出（主机到设备）。
+    fn control_out(&mut self, request: u8, value: u16, data: &[u8]) -> Result<(), String>;
+
+    /// 类请求输入（设备到主机），返回读取的字节数。
+    fn control_in(&mut self, request: u8, value: u16, buffer: &mut [u8]) -> Result<usize, String>;
+}
+
+/// 通过 USB 控制传输访问 bootloader 的 DFU 接口。
+pub struct UsbDfu {
+    handle: DeviceHandle<Context>,
+    interface: u8,
+}
+
+impl DfuIo for UsbDfu {
+    fn control_out(&mut self, request: u8, value: u16, data: &[u8]) -> Result<(), String> {
+        self.handle
+            .write_control(
+                0x21,
+                request,
+                value,
+                self.interface as u16,
+                data,
+                CONTROL_TIMEOUT,
+            )
+            .map(|_| ())
+            .map_err(|e| format!("DFU 请求 {} 失败: {}", request, e))
+    }
+
+    fn control_in(&mut self, request: u8, value: u16, buffer: &mut [u8]) -> Result<usize, String> {
+        self.handle
+            .read_control(
+                0xa1,
+                request,
+                value,
+                self.interface as u16,
+                buffer,
+                CONTROL_TIMEOUT,
+            )
+            .map_err(|e| format!("DFU 请求 {} 失败: {}", request, e))
+    }
+}
+
+/// 处于 bootloader 模式的设备。
+pub struct DfuDevice<I: DfuIo = UsbDfu> {
+    io: I,
+    transfer_size: usize,
+    layout: Vec<FlashSector>,
+}
+
+impl DfuDevice<UsbDfu> {
+    /// 打开 STM32 bootloader（`0483:df11`）的内部闪存接口。
+    pub fn open() -> Result<Self, String> {
+        Self::open_id(STM32_DFU_VID, STM32_DFU_PID)
+    }
+
+    /// 打开指定 USB ID 的 DfuSe 设备。
+    pub fn open_id(vid: u16, pid: u16) -> Result<Self, String> {
+        #[cfg(feature = "logging")]
+        log::info!("Opening DFU device {:04x}:{:04x}...", vid, pid);
+        let context = Context::new().map_err(|e| format!("创建上下文失败: {}", e))?;
+        let devices = context
+            .devices()
+            .map_err(|e| format!("获取设备失败: {}", e))?;
+        let device = devices
+            .iter()
+            .find(|device| {
+                device
+                    .device_descriptor()
+                    .map(|desc| desc.vendor_id() == vid && desc.product_id() == pid)
+                    .unwrap_or(false)
+            })
+            .ok_or_else(|| {
+                format!(
+                    "未找到处于 bootloader 模式的设备 ({:04x}:{:04x})，请按住 BOOT0 键重新上电",
+                    vid, pid
+                )
+            })?;
+        let handle = device.open().map_err(|e| format!("打开设备失败: {}", e))?;
+        let config = device
+            .active_config_descriptor()
+            .map_err(|e| format!("读取配置失败: {}", e))?;
+
+        // 第一个 DFU 接口的第一个备用设置是内部闪存
+        let descriptor = config
+            .interfaces()
+            .flat_map(|interface| interface.descriptors())
+            .find(|d| d.class_code() == 0xfe && d.sub_class_code() == 0x01)
+            .ok_or("设备没有 DFU 接口")?;
+        let interface = descriptor.interface_number();
+        let transfer_size = transfer_size(descriptor.extra()).unwrap_or(DEFAULT_TRANSFER_SIZE);
+        let layout = handle
+            .read_languages(CONTROL_TIMEOUT)
+            .ok()
+            .and_then(|languages| languages.first().copied())
+            .and_then(|language| {
+                handle
+                    .read_interface_string(language, &descriptor, CONTROL_TIMEOUT)
+                    .ok()
+            })
+            .and_then(|text| parse_layout(&text).ok())
+            .unwrap_or_default();
+
+        handle
+            .claim_interface(interface)
+            .map_err(|e| format!("声明接口失败: {}", e))?;
+        handle
+            .set_alternate_setting(interface, descriptor.setting_number())
+            .map_err(|e| format!("选择备用设置失败: {}", e))?;
+        #[cfg(feature = "logging")]
+        log::info!(
+            "DFU interface {}: transfer size {}, {} flash sectors",
+            interface,
+            transfer_size,
+            layout.len()
+        );
+        Ok(Self::with_io(
+            UsbDfu { handle, interface },
+            transfer_size,
+            layout,
+        ))
+    }
+}
+
+impl<I: DfuIo> DfuDevice<I> {
+    /// 使用指定的控制传输、块大小和闪存布局。
+    pub fn with_io(io: I, transfer_size: usize, layout: Vec<FlashSector>) -> Self {
+        Self {
+            io,
+            transfer_size: transfer_size.max(1),
+            layout,
+        }
+    }
+
+    /// 每个下载或上传块的字节数。
+    pub fn transfer_size(&self) -> usize {
+        self.transfer_size
+    }
+
+    /// 闪存布局。
+    pub fn layout(&self) -> &[FlashSector] {
+        &self.layout
+    }
+
+    /// 取回控制传输。
+    pub fn into_inner(self) -> I {
+        self.io
+    }
+
+    /// 查询状态。
+    pub fn status(&mut self) -> Result<DfuStatus, String> {
+        let mut data = [0u8; 6];
+        let len = self.io.control_in(DFU_GETSTATUS, 0, &mut data)?;
+        DfuStatus::parse(&data[..len])
+    }
+
+    /// 写入 `firmware`：擦除覆盖的扇区、逐块写入，按选项校验并运行新固件。
+    pub fn flash(
+        &mut self,
+        firmware: &[u8],
+        options: &FlashOptions,
+        progress: &mut dyn FnMut(DfuProgress),
+    ) -> Result<(), String> {
+        if firmware.is_empty() {
+            return Err("固件为空".to_string());
+        }
+        self.ensure_idle()?;
+
+        // 1. 擦除
+        let end = options.address as u64 + firmware.len() as u64;
+        let sectors: Vec<FlashSector> = self
+            .layout
+            .iter()
+            .filter(|s| {
+                (s.address as u64) < end
+                    && s.address as u64 + s.size as u64 > options.address as u64
+            })
+            .copied()
+            .collect();
+        let covered = sectors
+            .first()
+            .is_some_and(|s| s.address <= options.address)
+            && sectors
+                .last()
+                .is_some_and(|s| s.address as u64 + s.size as u64 >= end);
+        if !covered {
+            return Err(format!(
+                "固件 ({} 字节, 地址 0x{:08x}) 超出闪存范围",
+                firmware.len(),
+                options.address
+            ));
+        }
+        #[cfg(feature = "logging")]
+        log::info!("Erasing {} sectors...", sectors.len());
+        for (i, sector) in sectors.iter().enumerate() {
+            progress(DfuProgress {
+                stage: DfuStage::Erase,
+                done: i,
+                total: sectors.len(),
+            });
+            self.command(DFUSE_ERASE, sector.address)?;
+        }
+        progress(DfuProgress {
+            stage: DfuStage::Erase,
+            done: sectors.len(),
+            total: sectors.len(),
+        });
+
+        // 2. 写入：块号从 2 开始，地址为 地址指针 + (块号 - 2) * 块大小
+        #[cfg(feature = "logging")]
+        log::info!("Downloading {} bytes...", firmware.len());
+        self.command(DFUSE_SET_ADDRESS, options.address)?;
+        let mut done = 0;
+        for (i, chunk) in firmware.chunks(self.transfer_size).enumerate() {
+            progress(DfuProgress {
+                stage: DfuStage::Download,
+                done,
+                total: firmware.len(),
+            });
+            self.download(block_number(i)?, chunk)?;
+            done += chunk.len();
+        }
+        progress(DfuProgress {
+            stage: DfuStage::Download,
+            done,
+            total: firmware.len(),
+        });
+
+        // 3. 校验
+        if options.verify {
+            self.verify(firmware, options.address, progress)?;
+        }
+
+        // 4. 运行新固件
+        if options.leave {
+            self.leave(options.address)?;
+        }
+        #[cfg(feature = "logging")]
+        log::info!("Firmware update completed");
+        Ok(())
+    }
+
+    /// 从 `address` 回读 `len` 字节。
+    pub fn read(&mut self, address: u32, len: usize) -> Result<Vec<u8>, String> {
+        self.ensure_idle()?;
+        self.command(DFUSE_SET_ADDRESS, address)?;
+        // 上传之前需要回到空闲状态
+        self.io.control_out(DFU_ABORT, 0, &[])?;
+        let mut data = Vec::with_capacity(len);
+        let mut block = vec![0u8; self.transfer_size];
+        let mut i = 0;
+        while data.len() < len {
+            let want = (len - data.len()).min(self.transfer_size);
+            let got = self
+                .io
+                .control_in(DFU_UPLOAD, block_number(i)?, &mut block[..want])?;
+            if got == 0 {
+                return Err(format!("回读在 {} 字节处提前结束", data.len()));
+            }
+            data.extend_from_slice(&block[..got.min(want)]);
+            i += 1;
+        }
+        self.io.control_out(DFU_ABORT, 0, &[])?;
+        Ok(data)
+    }
+
+    /// 离开 bootloader，从 `address` 运行固件（设备会重新枚举）。
+    pub fn leave(&mut self, address: u32) -> Result<(), String> {
+        self.command(DFUSE_SET_ADDRESS, address)?;
+        self.io.control_out(DFU_DNLOAD, 2, &[])?;
+        // 设备在应用固件后复位，最后一次状态查询可能失败
+        let _ = self.status();
+        Ok(())
+    }
+
+    fn verify(
+        &mut self,
+        firmware: &[u8],
+        address: u32,
+        progress: &mut dyn FnMut(DfuProgress),
+    ) -> Result<(), String> {
+        #[cfg(feature = "logging")]
+        log::info!("Verifying...");
+        progress(DfuProgress {
+            stage: DfuStage::Verify,
+            done: 0,
+            total: firmware.len(),
+        });
+        let data = self.read(address, firmware.len())?;
+        if let Some(offset) = data.iter().zip(firmware).position(|(a, b)| a != b) {
+            return Err(format!(
+                "校验失败: 地址 0x{:08x} 处的数据不一致",
+                address as usize + offset
+            ));
+        }
+        progress(DfuProgress {
+            stage: DfuStage::Verify,
+            done: firmware.len(),
+            total: firmware.len(),
+        });
+        Ok(())
+    }
+
+    /// 清除错误状态，回到空闲。
+    fn ensure_idle(&mut self) -> Result<(), String> {
+        let status = self.status()?;
+        match status.state {
+            DfuState::Idle => Ok(()),
+            DfuState::Error => {
+                self.io.control_out(DFU_CLRSTATUS, 0, &[])?;
+                Ok(())
+            }
+            DfuState::AppIdle | DfuState::AppDetach => {
+                Err("设备处于运行时模式，请先进入 bootloader".to_string())
+            }
+            _ => {
+                self.io.control_out(DFU_ABORT, 0, &[])?;
+                Ok(())
+            }
+        }
+    }
+
+    /// DfuSe 命令：块 0，命令字节加 4 字节小端地址。
+    fn command(&mut self, command: u8, address: u32) -> Result<(), String> {
+        let mut data = [0u8; 5];
+        data[0] = command;
+        data[1..].copy_from_slice(&address.to_le_bytes());
+        self.download(0, &data)
+    }
+
+    /// 下载一个块并等待设备处理完成。
+    fn download(&mut self, block: u16, data: &[u8]) -> Result<(), String> {
+        self.io.control_out(DFU_DNLOAD, block, data)?;
+        for _ in 0..MAX_POLLS {
+            let status = self.status()?;
+            if status.status != 0 || status.state == DfuState::Error {
+                let _ = self.io.control_out(DFU_CLRSTATUS, 0, &[]);
+                return Err(format!(
+                    "DFU 块 {} 处理失败 (状态码 {})",
+                    block, status.status
+                ));
+            }
+            match status.state {
+                DfuState::DownloadIdle | DfuState::Idle => return Ok(()),
+                _ => std::thread::sleep(status.poll_timeout),
+            }
+        }
+        Err(format!("等待 DFU 块 {} 超时", block))
+    }
+}
+
+/// 数据块 `index`（从 0 开始）的 DFU 块号。
+fn block_number(index: usize) -> Result<u16, String> {
+    u16::try_from(index + 2).map_err(|_| "固件过大".to_string())
+}
+
+/// 从 DFU 功能描述符（类型 0x21）读取块大小。
+fn transfer_size(extra: &[u8]) -> Option<usize> {
+    let mut rest = extra;
+    while rest.len() >= 2 {
+        let len = rest[0] as usize;
+        if len < 2 || len > rest.len() {
+            return None;
+        }
+        if rest[1] == 0x21 && len >= 7 {
+            return Some(u16::from_le_bytes([rest[5], rest[6]]) as usize);
+        }
+        rest = &rest[len..];
+    }
+    None
+}
+
+/// 通过固件的 DFU 运行时接口重启进入 bootloader。
+///
+/// 原版固件没有这个接口，返回错误时需要按住 BOOT0 键重新上电。
+pub fn reboot_to_bootloader(device: &mut UsbDevice) -> Result<(), String> {
+    let usb = device.handle.device();
+    let config = usb
+        .active_config_descriptor()
+        .map_err(|e| format!("读取配置失败: {}", e))?;
+    let interface = config
+        .interfaces()
+        .flat_map(|interface| interface.descriptors())
+        .find(|d| d.class_code() == 0xfe && d.sub_class_code() == 0x01)
+        .map(|d| d.interface_number())
+        .ok_or("设备没有 DFU 运行时接口，请按住 BOOT0 键重新上电进入 bootloader")?;
+    #[cfg(feature = "logging")]
+    log::info!("Sending DFU_DETACH to interface {}", interface);
+    device
+        .handle
+        .write_control(
+            0x21,
+            DFU_DETACH,
+            1000,
+            interface as u16,
+            &[],
+            CONTROL_TIMEOUT,
+        )
+        .map_err(|e| format!("发送 DFU_DETACH 失败: {}", e))?;
+    // 不支持自动分离的设备需要总线复位才会进入 bootloader
+    let _ = device.handle.reset();
+    Ok(())
+}
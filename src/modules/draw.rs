@@ -0,0 +1,449 @@
+//! ElectronBot 库的绘图图元（直线、多边形、椭圆、圆弧、圆角矩形、贝塞尔曲线）。
+//!
+//! 坐标使用 `i32`，允许图形部分超出屏幕，超出部分会被裁剪。
+//! 描边函数的 `width` 为线宽（像素），大于 1 时使用圆形笔刷。
+//! 通过 [`ImageBuffer::set_antialias`] 开启抗锯齿后，所有图元按覆盖率与背景混合。
+
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_WIDTH};
+use crate::modules::image::ImageBuffer;
+use crate::modules::types::Color;
+
+/// 屏幕坐标点。
+pub type Point = (i32, i32);
+
+/// 浮点坐标点（用于曲线采样和抗锯齿）。
+pub(crate) type PointF = (f32, f32);
+
+/// 矩形区域。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rect {
+    /// 左上角 x 坐标。
+    pub x: i32,
+    /// 左上角 y 坐标。
+    pub y: i32,
+    /// 宽度。
+    pub width: usize,
+    /// 高度。
+    pub height: usize,
+}
+
+impl Rect {
+    /// 创建矩形。
+    pub fn new(x: i32, y: i32, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// 是否为空。
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// 是否包含指定点。
+    pub fn contains(&self, point: Point) -> bool {
+        point.0 >= self.x
+            && point.1 >= self.y
+            && point.0 < self.x + self.width as i32
+            && point.1 < self.y + self.height as i32
+    }
+}
+
+impl ImageBuffer {
+    /// 设置像素（带裁剪）。
+    fn put(&mut self, x: i32, y: i32, color: Color) {
+        if x >= 0 && y >= 0 {
+            self.set_pixel(x as usize, y as usize, color);
+        }
+    }
+
+    /// 填充一行中 `[x0, x1]` 的像素（带裁剪）。
+    fn fill_span(&mut self, y: i32, x0: i32, x1: i32, color: Color) {
+        if y < 0 || y >= FRAME_HEIGHT as i32 {
+            return;
+        }
+        let x0 = x0.max(0);
+        let x1 = x1.min(FRAME_WIDTH as i32 - 1);
+        for x in x0..=x1 {
+            self.set_pixel(x as usize, y as usize, color);
+        }
+    }
+
+    /// 在指定位置按线宽落笔。
+    fn stamp(&mut self, x: i32, y: i32, width: usize, color: Color) {
+        if width <= 1 {
+            self.put(x, y, color);
+            return;
+        }
+        let r = width as f32 / 2.0;
+        let ri = r.ceil() as i32;
+        let r2 = r * r;
+        for dy in -ri..=ri {
+            for dx in -ri..=ri {
+                let fx = dx as f32 + 0.5 - (width % 2) as f32 * 0.5;
+                let fy = dy as f32 + 0.5 - (width % 2) as f32 * 0.5;
+                if fx * fx + fy * fy <= r2 {
+                    self.put(x + dx, y + dy, color);
+                }
+            }
+        }
+    }
+
+    /// 绘制直线（Bresenham 算法）。
+    pub fn draw_line(&mut self, from: Point, to: Point, width: usize, color: Color) {
+        if self.antialias() {
+            let (a, b) = (to_f(from), to_f(to));
+            if width <= 1 {
+                self.aa_line(a, b, color);
+            } else {
+                self.aa_stroke(&[a, b], width as f32, false, color);
+            }
+            return;
+        }
+        let (mut x0, mut y0) = from;
+        let (x1, y1) = to;
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+
+        loop {
+            self.stamp(x0, y0, width, color);
+            if x0 == x1 && y0 == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x0 += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y0 += sy;
+            }
+        }
+    }
+
+    /// 绘制折线。
+    pub fn draw_polyline(&mut self, points: &[Point], width: usize, color: Color) {
+        if self.antialias() {
+            let points: Vec<PointF> = points.iter().map(|&p| to_f(p)).collect();
+            self.aa_stroke(&points, width.max(1) as f32, false, color);
+            return;
+        }
+        for pair in points.windows(2) {
+            self.draw_line(pair[0], pair[1], width, color);
+        }
+        if points.len() == 1 {
+            self.stamp(points[0].0, points[0].1, width, color);
+        }
+    }
+
+    /// 绘制多边形边框（自动闭合）。
+    pub fn draw_polygon(&mut self, points: &[Point], width: usize, color: Color) {
+        if self.antialias() {
+            let points: Vec<PointF> = points.iter().map(|&p| to_f(p)).collect();
+            self.aa_stroke(&points, width.max(1) as f32, true, color);
+            return;
+        }
+        self.draw_polyline(points, width, color);
+        if points.len() > 2 {
+            self.draw_line(points[points.len() - 1], points[0], width, color);
+        }
+    }
+
+    /// 填充多边形（扫描线，奇偶规则）。
+    pub fn fill_polygon(&mut self, points: &[Point], color: Color) {
+        if self.antialias() {
+            let points: Vec<PointF> = points.iter().map(|&p| to_f(p)).collect();
+            self.aa_fill_path(&points, color);
+            return;
+        }
+        if points.len() < 3 {
+            self.draw_polyline(points, 1, color);
+            return;
+        }
+        let min_y = points.iter().map(|p| p.1).min().unwrap_or(0).max(0);
+        let max_y = points
+            .iter()
+            .map(|p| p.1)
+            .max()
+            .unwrap_or(0)
+            .min(FRAME_HEIGHT as i32 - 1);
+
+        let mut nodes: Vec<i32> = Vec::with_capacity(points.len());
+        for y in min_y..=max_y {
+            // 以像素中心采样
+            let sample_y = y as f32 + 0.5;
+            nodes.clear();
+            for i in 0..points.len() {
+                let (xa, ya) = points[i];
+                let (xb, yb) = points[(i + 1) % points.len()];
+                let (ya, yb) = (ya as f32, yb as f32);
+                if (ya <= sample_y && yb > sample_y) || (yb <= sample_y && ya > sample_y) {
+                    let t = (sample_y - ya) / (yb - ya);
+                    nodes.push((xa as f32 + t * (xb - xa) as f32).round() as i32);
+                }
+            }
+            nodes.sort_unstable();
+            for pair in nodes.chunks(2) {
+                if let [a, b] = pair {
+                    self.fill_span(y, *a, *b - 1, color);
+                }
+            }
+        }
+        // 补齐边缘像素，保证与描边对齐
+        self.draw_polygon(points, 1, color);
+    }
+
+    /// 绘制椭圆边框。
+    pub fn draw_ellipse(
+        &mut self,
+        center: Point,
+        rx: usize,
+        ry: usize,
+        width: usize,
+        color: Color,
+    ) {
+        let width = width.max(1);
+        if width >= rx.min(ry) {
+            self.fill_ellipse(center, rx, ry, color);
+            return;
+        }
+        if self.antialias() {
+            self.aa_ellipse(
+                to_f(center),
+                rx as f32,
+                ry as f32,
+                Some(width as f32),
+                color,
+            );
+            return;
+        }
+        let (cx, cy) = center;
+        let (orx, ory) = (rx as f32 + 0.5, ry as f32 + 0.5);
+        let (irx, iry) = (orx - width as f32, ory - width as f32);
+        for dy in -(ry as i32)..=(ry as i32) {
+            for dx in -(rx as i32)..=(rx as i32) {
+                let (fx, fy) = (dx as f32, dy as f32);
+                let outer = (fx / orx).powi(2) + (fy / ory).powi(2);
+                let inner = (fx / irx).powi(2) + (fy / iry).powi(2);
+                if outer <= 1.0 && inner > 1.0 {
+                    self.put(cx + dx, cy + dy, color);
+                }
+            }
+        }
+    }
+
+    /// 填充椭圆。
+    pub fn fill_ellipse(&mut self, center: Point, rx: usize, ry: usize, color: Color) {
+        if self.antialias() {
+            self.aa_ellipse(to_f(center), rx as f32, ry as f32, None, color);
+            return;
+        }
+        let (cx, cy) = center;
+        let (orx, ory) = (rx as f32 + 0.5, ry as f32 + 0.5);
+        for dy in -(ry as i32)..=(ry as i32) {
+            let t = 1.0 - (dy as f32 / ory).powi(2);
+            if t < 0.0 {
+                continue;
+            }
+            let half = (orx * t.sqrt() - 0.5).round() as i32;
+            self.fill_span(cy + dy, cx - half, cx + half, color);
+        }
+    }
+
+    /// 绘制圆弧。
+    ///
+    /// 角度单位为度，0° 指向右侧，顺时针为正（屏幕坐标 y 轴向下）。
+    pub fn draw_arc(
+        &mut self,
+        center: Point,
+        radius: usize,
+        start_deg: f32,
+        end_deg: f32,
+        width: usize,
+        color: Color,
+    ) {
+        let r = radius as f32;
+        let points = arc_points(to_f(center), r, r, start_deg, end_deg);
+        self.stroke_points(&points, width, false, color);
+    }
+
+    /// 绘制圆角矩形边框。
+    pub fn draw_rounded_rect(&mut self, rect: Rect, radius: usize, width: usize, color: Color) {
+        if rect.is_empty() {
+            return;
+        }
+        let points = rounded_rect_points(rect, radius);
+        self.stroke_points(&points, width, true, color);
+    }
+
+    /// 填充圆角矩形。
+    pub fn fill_rounded_rect(&mut self, rect: Rect, radius: usize, color: Color) {
+        if rect.is_empty() {
+            return;
+        }
+        if self.antialias() {
+            // 轮廓点位于像素中心，向外扩展半个像素覆盖完整区域
+            let outer = rounded_rect_outline(rect, radius, 0.5);
+            self.aa_fill_path(&outer, color);
+            return;
+        }
+        let Rect {
+            x,
+            y,
+            width,
+            height,
+        } = rect;
+        let r = radius.min(width / 2).min(height / 2) as i32;
+        let (w, h) = (width as i32, height as i32);
+        for row in 0..h {
+            // 到最近的圆角圆心的垂直距离
+            let dy = if row < r {
+                r - row
+            } else if row >= h - r {
+                row - (h - r - 1)
+            } else {
+                0
+            };
+            let inset = if dy > 0 {
+                let rf = r as f32;
+                let d = (dy as f32 - 0.5).max(0.0);
+                (rf - (rf * rf - d * d).max(0.0).sqrt()).round() as i32
+            } else {
+                0
+            };
+            self.fill_span(y + row, x + inset, x + w - 1 - inset, color);
+        }
+    }
+
+    /// 绘制二次贝塞尔曲线。
+    pub fn draw_quad_bezier(
+        &mut self,
+        p0: Point,
+        p1: Point,
+        p2: Point,
+        width: usize,
+        color: Color,
+    ) {
+        let steps = bezier_steps(&[p0, p1, p2]);
+        let points: Vec<PointF> = (0..=steps)
+            .map(|i| {
+                let t = i as f32 / steps as f32;
+                let u = 1.0 - t;
+                let x = u * u * p0.0 as f32 + 2.0 * u * t * p1.0 as f32 + t * t * p2.0 as f32;
+                let y = u * u * p0.1 as f32 + 2.0 * u * t * p1.1 as f32 + t * t * p2.1 as f32;
+                (x, y)
+            })
+            .collect();
+        self.stroke_points(&points, width, false, color);
+    }
+
+    /// 绘制三次贝塞尔曲线。
+    pub fn draw_cubic_bezier(
+        &mut self,
+        p0: Point,
+        p1: Point,
+        p2: Point,
+        p3: Point,
+        width: usize,
+        color: Color,
+    ) {
+        let steps = bezier_steps(&[p0, p1, p2, p3]);
+        let points: Vec<PointF> = (0..=steps)
+            .map(|i| {
+                let t = i as f32 / steps as f32;
+                let u = 1.0 - t;
+                let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+                let x = a * p0.0 as f32 + b * p1.0 as f32 + c * p2.0 as f32 + d * p3.0 as f32;
+                let y = a * p0.1 as f32 + b * p1.1 as f32 + c * p2.1 as f32 + d * p3.1 as f32;
+                (x, y)
+            })
+            .collect();
+        self.stroke_points(&points, width, false, color);
+    }
+
+    /// 描边浮点采样点，按当前模式选择抗锯齿或整数像素路径。
+    fn stroke_points(&mut self, points: &[PointF], width: usize, closed: bool, color: Color) {
+        if self.antialias() {
+            self.aa_stroke(points, width.max(1) as f32, closed, color);
+            return;
+        }
+        let points: Vec<Point> = points
+            .iter()
+            .map(|p| (p.0.round() as i32, p.1.round() as i32))
+            .collect();
+        if closed {
+            self.draw_polygon(&points, width, color);
+        } else {
+            self.draw_polyline(&points, width, color);
+        }
+    }
+}
+
+fn to_f(p: Point) -> PointF {
+    (p.0 as f32, p.1 as f32)
+}
+
+/// 按控制多边形长度估算曲线分段数。
+fn bezier_steps(points: &[Point]) -> usize {
+    let length: f32 = points
+        .windows(2)
+        .map(|w| {
+            let dx = (w[1].0 - w[0].0) as f32;
+            let dy = (w[1].1 - w[0].1) as f32;
+            (dx * dx + dy * dy).sqrt()
+        })
+        .sum();
+    ((length / 4.0).ceil() as usize).clamp(4, 256)
+}
+
+/// 椭圆弧上的采样点。
+pub(crate) fn arc_points(
+    center: PointF,
+    rx: f32,
+    ry: f32,
+    start_deg: f32,
+    end_deg: f32,
+) -> Vec<PointF> {
+    let sweep = end_deg - start_deg;
+    // 相邻采样点间距约 2 像素
+    let steps = ((sweep.abs().to_radians() * rx.max(ry) / 2.0).ceil() as usize).max(1);
+    (0..=steps)
+        .map(|i| {
+            let a = (start_deg + sweep * i as f32 / steps as f32).to_radians();
+            (center.0 + rx * a.cos(), center.1 + ry * a.sin())
+        })
+        .collect()
+}
+
+/// 圆角矩形轮廓的采样点（顺时针，经过边缘像素中心）。
+fn rounded_rect_points(rect: Rect, radius: usize) -> Vec<PointF> {
+    rounded_rect_outline(rect, radius, 0.0)
+}
+
+/// 圆角矩形轮廓，`grow` 为向外扩展的距离。
+fn rounded_rect_outline(rect: Rect, radius: usize, grow: f32) -> Vec<PointF> {
+    let r = radius.min(rect.width / 2).min(rect.height / 2) as f32;
+    let x = rect.x as f32 - grow;
+    let y = rect.y as f32 - grow;
+    let right = (rect.x + rect.width as i32 - 1) as f32 + grow;
+    let bottom = (rect.y + rect.height as i32 - 1) as f32 + grow;
+    if r == 0.0 {
+        return vec![(x, y), (right, y), (right, bottom), (x, bottom)];
+    }
+    let rr = r + grow;
+    let (cl, cr) = (x + rr, right - rr);
+    let (ct, cb) = (y + rr, bottom - rr);
+    let mut points = Vec::new();
+    points.extend(arc_points((cr, ct), rr, rr, -90.0, 0.0));
+    points.extend(arc_points((cr, cb), rr, rr, 0.0, 90.0));
+    points.extend(arc_points((cl, cb), rr, rr, 90.0, 180.0));
+    points.extend(arc_points((cl, ct), rr, rr, 180.0, 270.0));
+    points
+}
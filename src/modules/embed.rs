@@ -0,0 +1,92 @@
+//! ElectronBot 库的编译期图片嵌入。
+//!
+//! 在构建脚本中用 [`embed_frame`] 把图片转换为 240x240 BGR 原始帧并写入
+//! `OUT_DIR`，再用 [`include_frame!`](crate::include_frame) 把它作为
+//! `&'static [u8; FRAME_SIZE]` 编译进程序，运行时不需要图片文件也没有解码开销。
+//!
+//! `build.rs`（需要把 `electron-bot` 同时加入 `[build-dependencies]`）：
+//!
+//! ```rust,ignore
+//! fn main() {
+//!     electron_bot::embed_frame("assets/face.png").unwrap();
+//! }
+//! ```
+//!
+//! 程序中：
+//!
+//! ```rust,ignore
+//! static FACE: &[u8; electron_bot::FRAME_SIZE] = electron_bot::include_frame!("assets/face.png");
+//! bot.set_image_frame(FACE);
+//! ```
+
+#[cfg(feature = "image")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "image")]
+use crate::modules::constants::FRAME_SIZE;
+#[cfg(feature = "image")]
+use crate::modules::image::{FitOptions, ImageBuffer};
+
+/// `OUT_DIR` 下存放转换结果的子目录。
+pub const EMBED_DIR: &str = "electron_bot_frames";
+
+/// 嵌入构建脚本转换好的图片帧，得到 `&'static [u8; FRAME_SIZE]`。
+///
+/// 路径与传给 [`embed_frame`](crate::embed_frame) 的相同。
+/// 文件大小不是一帧时编译失败。
+#[macro_export]
+macro_rules! include_frame {
+    ($path:literal) => {{
+        const FRAME: &[u8; $crate::FRAME_SIZE] = include_bytes!(concat!(
+            env!("OUT_DIR"),
+            "/electron_bot_frames/",
+            $path,
+            ".bgr"
+        ));
+        FRAME
+    }};
+}
+
+/// 在构建脚本中转换图片（拉伸填满屏幕），返回输出文件路径。
+///
+/// `path` 相对于包根目录（`CARGO_MANIFEST_DIR`），图片改动时自动重新转换。
+#[cfg(feature = "image")]
+pub fn embed_frame<P: AsRef<Path>>(path: P) -> Result<PathBuf, String> {
+    embed_frame_with(path, &FitOptions::default())
+}
+
+/// 在构建脚本中按适配选项转换图片，返回输出文件路径。
+#[cfg(feature = "image")]
+pub fn embed_frame_with<P: AsRef<Path>>(path: P, options: &FitOptions) -> Result<PathBuf, String> {
+    let path = path.as_ref();
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")
+        .ok_or("未设置 CARGO_MANIFEST_DIR，请在 build.rs 中调用")?;
+    let out_dir = std::env::var_os("OUT_DIR").ok_or("未设置 OUT_DIR，请在 build.rs 中调用")?;
+
+    let src = Path::new(&manifest_dir).join(path);
+    let mut name = path.as_os_str().to_owned();
+    name.push(".bgr");
+    let dst = Path::new(&out_dir).join(EMBED_DIR).join(name);
+    convert_frame(&src, &dst, options)?;
+
+    println!("cargo:rerun-if-changed={}", src.display());
+    Ok(dst)
+}
+
+/// 把图片转换为原始 BGR 帧文件（`FRAME_SIZE` 字节）。
+#[cfg(feature = "image")]
+pub fn convert_frame<P: AsRef<Path>, Q: AsRef<Path>>(
+    src: P,
+    dst: Q,
+    options: &FitOptions,
+) -> Result<(), String> {
+    let mut buffer = ImageBuffer::new();
+    buffer.load_from_file_with(src.as_ref(), options)?;
+
+    let dst = dst.as_ref();
+    if let Some(parent) = dst.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    std::fs::write(dst, &buffer.as_data()[..FRAME_SIZE])
+        .map_err(|e| format!("写入帧文件失败: {}", e))
+}
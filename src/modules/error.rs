@@ -1,5 +1,7 @@
 //! ElectronBot 库的错误类型定义。
 
+use std::fmt;
+
 use thiserror::Error;
 
 /// 与 ElectronBot 通信时可能发生的错误。
@@ -12,10 +14,10 @@ pub enum BotError {
     UsbError(String),
 
     #[error("发送数据失败: {0}")]
-    SendFailed(String),
+    SendFailed(TransferError),
 
     #[error("接收数据失败: {0}")]
-    ReceiveFailed(String),
+    ReceiveFailed(TransferError),
 
     #[error("图片错误: {0}")]
     ImageError(String),
@@ -25,4 +27,164 @@ pub enum BotError {
 
     #[error("未找到接口")]
     InterfaceNotFound,
+
+    #[error("统计数据错误: {0}")]
+    StatsError(String),
+
+    #[error("配置错误: {0}")]
+    ConfigError(String),
+
+    #[error("录制错误: {0}")]
+    RecordError(String),
+
+    #[error("操作已取消")]
+    Cancelled,
+
+    #[error("急停中，解除后才能继续")]
+    EmergencyStop,
+
+    #[error("关节目标会发生碰撞: {0}")]
+    Collision(String),
+
+    #[error("macOS 拒绝访问设备: {0}")]
+    MacOsAccess(String),
+
+    #[error("同步超时（已完成 {0} 个周期，发送 {1} 个包）")]
+    SyncTimeout(usize, usize),
+
+    #[error("创建线程失败: {0}")]
+    ThreadError(String),
+}
+
+impl BotError {
+    /// 发送或接收失败的详情。
+    pub fn transfer_error(&self) -> Option<&TransferError> {
+        match self {
+            BotError::SendFailed(error) | BotError::ReceiveFailed(error) => Some(error),
+            _ => None,
+        }
+    }
+
+    /// 设备是否已经拔出（需要重新连接，重试同步没有用）。
+    pub fn is_device_lost(&self) -> bool {
+        self.transfer_error()
+            .is_some_and(|error| error.kind == TransferErrorKind::NoDevice)
+    }
+
+    /// 错误的恢复方式。
+    ///
+    /// 取消和急停是调用方主动中止，归为 [`ErrorClass::Fatal`]，不应自动重试。
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            BotError::SendFailed(error) | BotError::ReceiveFailed(error) => error.kind.class(),
+            BotError::SyncTimeout(..) => ErrorClass::Transient,
+            BotError::NotConnected | BotError::DeviceNotFound(..) => ErrorClass::Disconnected,
+            _ => ErrorClass::Fatal,
+        }
+    }
+}
+
+/// 错误的恢复方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// 暂时性错误（超时、端点停止、溢出、占用），重试可能成功。
+    Transient,
+    /// 设备已断开或被拔出，需要重新连接。
+    Disconnected,
+    /// 重试和重新连接都不能恢复（没有权限、配置或参数错误、被取消等），应交给调用方处理。
+    Fatal,
+}
+
+/// 底层传输错误的类型（对应 libusb 的错误码），用于决定恢复方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransferErrorKind {
+    /// 超时。
+    Timeout,
+    /// 设备已拔出。
+    NoDevice,
+    /// 端点停止（STALL）。
+    Pipe,
+    /// 设备发来的数据超过缓冲区。
+    Overflow,
+    /// 设备或接口被占用。
+    Busy,
+    /// 没有访问权限。
+    Access,
+    /// 输入输出错误。
+    Io,
+    /// 其他错误，或者通道没有提供错误类型。
+    #[default]
+    Other,
+}
+
+impl TransferErrorKind {
+    /// 错误的恢复方式：没有权限是致命错误，设备拔出需要重新连接，其他都可以重试。
+    pub fn class(self) -> ErrorClass {
+        match self {
+            TransferErrorKind::NoDevice => ErrorClass::Disconnected,
+            TransferErrorKind::Access => ErrorClass::Fatal,
+            _ => ErrorClass::Transient,
+        }
+    }
+}
+
+/// 发送或接收失败的详情：出错的位置、传输的字节数、重试次数和底层错误类型。
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TransferError {
+    /// 错误信息。
+    pub message: String,
+    /// 出错的周期（从 0 开始），不是同步中的错误时为 `None`。
+    pub cycle: Option<usize>,
+    /// 出错的包在本次同步中的序号（从 0 开始，包括尾包），不是同步中的错误时为 `None`。
+    pub packet: Option<usize>,
+    /// 应传输的字节数。
+    pub expected: usize,
+    /// 实际传输的字节数。
+    pub transferred: usize,
+    /// 放弃之前重试的次数。
+    pub retries: u32,
+    /// 底层错误类型。
+    pub kind: TransferErrorKind,
+}
+
+impl TransferError {
+    /// 只有错误信息的传输错误。
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            ..Self::default()
+        }
+    }
+}
+
+impl From<String> for TransferError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+impl fmt::Display for TransferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)?;
+        let mut context = Vec::new();
+        if let Some(cycle) = self.cycle {
+            context.push(format!("周期 {}", cycle));
+        }
+        if let Some(packet) = self.packet {
+            context.push(format!("包 {}", packet));
+        }
+        if self.expected > 0 {
+            context.push(format!("{}/{} 字节", self.transferred, self.expected));
+        }
+        if self.retries > 0 {
+            context.push(format!("重试 {} 次", self.retries));
+        }
+        if self.kind != TransferErrorKind::Other {
+            context.push(format!("{:?}", self.kind));
+        }
+        if !context.is_empty() {
+            write!(f, "（{}）", context.join("，"))?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,91 @@
+//! ElectronBot 库的事件。
+//!
+//! [`ElectronBot::on_event`](crate::ElectronBot::on_event) 注册的回调在连接、断开、同步失败、急停和关节异常时收到 [`Event`]；
+//! 应用也可以自己产生事件（触摸、按键等），写入 [`EventQueue`] 交给行为状态机处理。
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// 事件。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// 设备已连接。
+    Connected,
+    /// 设备已断开。
+    Disconnected,
+    /// 同步失败。
+    SyncFailed,
+    /// 主机休眠后设备重新打开，舵机参数、画面和姿态已恢复。
+    Resumed,
+    /// 关节的上报角度持续跟不上目标角度（关节索引）。
+    JointLagging(usize),
+    /// 关节的上报角度一直不动，可能堵转或舵机没有供电（关节索引）。
+    JointStalled(usize),
+    /// 关节恢复跟随目标角度（关节索引）。
+    JointRecovered(usize),
+    /// 触发了急停。
+    EmergencyStop,
+    /// 被触摸（由应用根据传感器或输入产生）。
+    Touched,
+    /// 状态的计时结束（行为状态机产生）。
+    TimerElapsed,
+    /// 状态的帧源播放结束（行为状态机产生）。
+    Finished,
+    /// 应用自定义事件。
+    Custom(String),
+}
+
+impl Event {
+    /// 创建自定义事件。
+    pub fn custom(name: &str) -> Self {
+        Event::Custom(name.to_string())
+    }
+}
+
+/// 事件回调。
+pub(crate) type EventHandler = Box<dyn FnMut(&Event) + Send>;
+
+/// 周期发送回调，参数为段序号和该周期发送的数据。
+pub(crate) type FrameSentHandler = Box<dyn FnMut(usize, &[u8]) + Send>;
+
+/// 事件队列，可克隆后交给其他线程或事件回调。
+#[derive(Debug, Clone, Default)]
+pub struct EventQueue {
+    queue: Arc<Mutex<VecDeque<Event>>>,
+}
+
+impl EventQueue {
+    /// 创建空队列。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加事件。
+    pub fn push(&self, event: Event) {
+        self.lock().push_back(event);
+    }
+
+    /// 取出最早加入的事件。
+    pub fn pop(&self) -> Option<Event> {
+        self.lock().pop_front()
+    }
+
+    /// 等待处理的事件数量。
+    pub fn pending(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// 清空等待处理的事件。
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// 取出全部等待处理的事件（按加入顺序）。
+    pub(crate) fn take_all(&self) -> VecDeque<Event> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<Event>> {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,195 @@
+//! ElectronBot 库的诊断事件日志。
+//!
+//! [`EventLog`] 是固定容量的环形缓冲区，记录最近的连接和传输事件（连接、断开、
+//! 连接失败、同步失败、超时、取消、急停和休眠恢复），每条带时间戳和错误详情。
+//! 图形界面可以直接显示诊断面板，不需要接入日志后端。
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+
+use crate::modules::time::Instant;
+
+/// 默认保留的事件数。
+pub const DEFAULT_EVENT_LOG_LEN: usize = 64;
+
+/// 诊断事件的类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogKind {
+    /// 已连接，详情为固件版本和 USB ID（通过 `connect_transport` 连接时为空）。
+    Connected,
+    /// 连接失败。
+    ConnectFailed,
+    /// 已断开。
+    Disconnected,
+    /// 同步或逐段发送失败。
+    SyncFailed,
+    /// 同步超过时间预算。
+    SyncTimeout,
+    /// 同步被取消。
+    Cancelled,
+    /// 触发了急停。
+    EmergencyStop,
+    /// 主机休眠后重新打开了设备，详情为固件版本。
+    Resumed,
+}
+
+impl LogKind {
+    /// 是否为错误事件。
+    pub fn is_error(self) -> bool {
+        matches!(
+            self,
+            LogKind::ConnectFailed | LogKind::SyncFailed | LogKind::SyncTimeout
+        )
+    }
+
+    /// 显示用的名称。
+    pub fn name(self) -> &'static str {
+        match self {
+            LogKind::Connected => "已连接",
+            LogKind::ConnectFailed => "连接失败",
+            LogKind::Disconnected => "已断开",
+            LogKind::SyncFailed => "同步失败",
+            LogKind::SyncTimeout => "同步超时",
+            LogKind::Cancelled => "已取消",
+            LogKind::EmergencyStop => "急停",
+            LogKind::Resumed => "已恢复",
+        }
+    }
+}
+
+/// 一条诊断事件。
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    /// 记录时间（单调时钟，用于计算间隔）。
+    pub at: Instant,
+    /// 记录时的本地时间（用于显示）。
+    pub time: DateTime<Local>,
+    /// 事件类型。
+    pub kind: LogKind,
+    /// 详情（错误信息等），没有时为空。
+    pub detail: String,
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.time.format("%H:%M:%S%.3f"),
+            self.kind.name()
+        )?;
+        if !self.detail.is_empty() {
+            write!(f, ": {}", self.detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// 诊断事件日志。
+#[derive(Debug, Clone)]
+pub struct EventLog {
+    capacity: usize,
+    entries: VecDeque<LogEntry>,
+}
+
+impl EventLog {
+    /// 创建保留 `capacity` 条事件的日志，0 表示不记录。
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity.min(DEFAULT_EVENT_LOG_LEN)),
+        }
+    }
+
+    /// 容量。
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 修改容量，超出的旧事件被丢弃。
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.entries.len() > capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    /// 以当前时间记录一条事件。
+    pub fn push(&mut self, kind: LogKind, detail: impl Into<String>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(LogEntry {
+            at: Instant::now(),
+            time: Local::now(),
+            kind,
+            detail: detail.into(),
+        });
+    }
+
+    /// 事件数。
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 是否没有事件。
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 最新的事件。
+    pub fn latest(&self) -> Option<&LogEntry> {
+        self.entries.back()
+    }
+
+    /// 指定类型的最新事件（可修改）。
+    pub(crate) fn latest_mut(&mut self, kind: LogKind) -> Option<&mut LogEntry> {
+        self.entries
+            .iter_mut()
+            .rev()
+            .find(|entry| entry.kind == kind)
+    }
+
+    /// 最新的错误事件。
+    pub fn last_error(&self) -> Option<&LogEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.kind.is_error())
+    }
+
+    /// 从旧到新遍历事件。
+    pub fn iter(&self) -> impl Iterator<Item = &LogEntry> {
+        self.entries.iter()
+    }
+
+    /// 指定类型的事件（从旧到新）。
+    pub fn of_kind(&self, kind: LogKind) -> impl Iterator<Item = &LogEntry> {
+        self.entries.iter().filter(move |entry| entry.kind == kind)
+    }
+
+    /// 最近 `duration` 内的事件（从旧到新）。
+    pub fn since(&self, duration: Duration) -> impl Iterator<Item = &LogEntry> {
+        let now = Instant::now();
+        self.entries
+            .iter()
+            .filter(move |entry| now.duration_since(entry.at) <= duration)
+    }
+
+    /// 清空事件。
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_LOG_LEN)
+    }
+}
@@ -0,0 +1,377 @@
+//! ElectronBot 库的参数化表情引擎。
+//!
+//! [`Expression`] 用一小组参数描述表情（睁眼程度、瞳孔位置、眉毛、嘴型），
+//! [`Face`] 在表情之间平滑过渡并实时绘制，可以直接作为帧源播放。
+
+use std::time::Duration;
+
+use crate::modules::constants::FRAME_WIDTH;
+use crate::modules::draw::Point;
+use crate::modules::image::ImageBuffer;
+use crate::modules::source::{FrameSource, DEFAULT_INTERVAL};
+use crate::modules::types::Color;
+
+/// 眼睛中心的纵坐标。
+const EYE_Y: i32 = 100;
+/// 眼睛中心到屏幕中线的距离。
+const EYE_SPACING: i32 = 48;
+/// 眼睛的水平半径。
+const EYE_RX: i32 = 30;
+/// 眼睛的垂直半径（完全睁开时）。
+const EYE_RY: i32 = 36;
+/// 瞳孔半径（`pupil_size` 为 1 时）。
+const PUPIL_RADIUS: f32 = 14.0;
+/// 嘴巴中心的纵坐标。
+const MOUTH_Y: i32 = 182;
+/// 线条宽度（眉毛、嘴巴）。
+const LINE_WIDTH: usize = 5;
+
+/// 预设表情的名称。
+pub const EXPRESSION_NAMES: [&str; 6] = ["neutral", "happy", "sad", "surprised", "sleepy", "angry"];
+
+/// 表情参数。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Expression {
+    /// 睁眼程度（0 为闭眼，1 为完全睁开）。
+    pub eye_open: f32,
+    /// 瞳孔水平位置（-1 最左，1 最右）。
+    pub pupil_x: f32,
+    /// 瞳孔垂直位置（-1 最上，1 最下）。
+    pub pupil_y: f32,
+    /// 瞳孔大小（1 为正常）。
+    pub pupil_size: f32,
+    /// 眉毛高度（-1 压低，1 抬高）。
+    pub brow_height: f32,
+    /// 眉毛倾斜（正值内侧抬高显得担忧，负值内侧压低显得生气）。
+    pub brow_tilt: f32,
+    /// 嘴角弧度（-1 撇嘴，1 微笑）。
+    pub mouth_curve: f32,
+    /// 张嘴程度（0 闭合，1 最大）。
+    pub mouth_open: f32,
+    /// 嘴巴宽度（0 最窄，1 最宽）。
+    pub mouth_width: f32,
+}
+
+impl Expression {
+    /// 平静。
+    pub const NEUTRAL: Self = Self {
+        eye_open: 1.0,
+        pupil_x: 0.0,
+        pupil_y: 0.0,
+        pupil_size: 1.0,
+        brow_height: 0.0,
+        brow_tilt: 0.0,
+        mouth_curve: 0.2,
+        mouth_open: 0.0,
+        mouth_width: 0.5,
+    };
+
+    /// 开心。
+    pub const HAPPY: Self = Self {
+        eye_open: 0.8,
+        brow_height: 0.3,
+        mouth_curve: 1.0,
+        mouth_open: 0.3,
+        mouth_width: 0.8,
+        ..Self::NEUTRAL
+    };
+
+    /// 难过。
+    pub const SAD: Self = Self {
+        eye_open: 0.7,
+        pupil_y: 0.3,
+        brow_height: -0.2,
+        brow_tilt: 1.0,
+        mouth_curve: -0.8,
+        mouth_width: 0.4,
+        ..Self::NEUTRAL
+    };
+
+    /// 惊讶。
+    pub const SURPRISED: Self = Self {
+        pupil_size: 0.7,
+        brow_height: 1.0,
+        brow_tilt: 0.3,
+        mouth_curve: 0.0,
+        mouth_open: 0.8,
+        mouth_width: 0.3,
+        ..Self::NEUTRAL
+    };
+
+    /// 困倦。
+    pub const SLEEPY: Self = Self {
+        eye_open: 0.25,
+        pupil_y: 0.4,
+        brow_height: -0.5,
+        mouth_curve: 0.0,
+        mouth_open: 0.1,
+        mouth_width: 0.3,
+        ..Self::NEUTRAL
+    };
+
+    /// 生气。
+    pub const ANGRY: Self = Self {
+        eye_open: 0.8,
+        brow_height: -0.6,
+        brow_tilt: -1.0,
+        mouth_curve: -0.4,
+        mouth_open: 0.1,
+        ..Self::NEUTRAL
+    };
+
+    /// 按名称查找预设表情。
+    pub fn named(name: &str) -> Option<Self> {
+        Some(match name {
+            "neutral" => Self::NEUTRAL,
+            "happy" => Self::HAPPY,
+            "sad" => Self::SAD,
+            "surprised" => Self::SURPRISED,
+            "sleepy" => Self::SLEEPY,
+            "angry" => Self::ANGRY,
+            _ => return None,
+        })
+    }
+
+    /// 设置瞳孔位置。
+    pub fn look(mut self, x: f32, y: f32) -> Self {
+        self.pupil_x = x;
+        self.pupil_y = y;
+        self
+    }
+
+    /// 在两个表情之间线性插值（`t` 为 0 时返回 `self`，为 1 时返回 `other`）。
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        Self {
+            eye_open: mix(self.eye_open, other.eye_open),
+            pupil_x: mix(self.pupil_x, other.pupil_x),
+            pupil_y: mix(self.pupil_y, other.pupil_y),
+            pupil_size: mix(self.pupil_size, other.pupil_size),
+            brow_height: mix(self.brow_height, other.brow_height),
+            brow_tilt: mix(self.brow_tilt, other.brow_tilt),
+            mouth_curve: mix(self.mouth_curve, other.mouth_curve),
+            mouth_open: mix(self.mouth_open, other.mouth_open),
+            mouth_width: mix(self.mouth_width, other.mouth_width),
+        }
+    }
+}
+
+impl Default for Expression {
+    fn default() -> Self {
+        Self::NEUTRAL
+    }
+}
+
+/// 表情配色。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaceStyle {
+    /// 背景色。
+    pub background: Color,
+    /// 眼白颜色。
+    pub eye: Color,
+    /// 瞳孔颜色。
+    pub pupil: Color,
+    /// 眉毛和嘴巴颜色。
+    pub line: Color,
+}
+
+impl Default for FaceStyle {
+    fn default() -> Self {
+        Self {
+            background: Color::Black,
+            eye: Color::White,
+            pupil: Color::Black,
+            line: Color::White,
+        }
+    }
+}
+
+impl ImageBuffer {
+    /// 清屏并按表情参数绘制脸。
+    pub fn draw_face(&mut self, expression: &Expression, style: &FaceStyle) {
+        self.clear(style.background);
+        let center = FRAME_WIDTH as i32 / 2;
+        for side in [-1, 1] {
+            let cx = center + side * EYE_SPACING;
+            self.draw_eye(cx, expression, style);
+            self.draw_brow(cx, side, expression, style);
+        }
+        self.draw_mouth(center, expression, style);
+    }
+
+    /// 绘制一只眼睛：眼白、瞳孔，再用背景色画出上眼睑。
+    fn draw_eye(&mut self, cx: i32, e: &Expression, style: &FaceStyle) {
+        let open = e.eye_open.clamp(0.0, 1.0);
+        // 上眼睑位置，闭眼时停在中线偏下
+        let lid_y = EYE_Y - EYE_RY + ((1.0 - open) * EYE_RY as f32 * 1.6) as i32;
+        if open < 0.08 {
+            self.draw_line(
+                (cx - EYE_RX, lid_y),
+                (cx + EYE_RX, lid_y),
+                LINE_WIDTH,
+                style.line,
+            );
+            return;
+        }
+
+        self.fill_ellipse((cx, EYE_Y), EYE_RX as usize, EYE_RY as usize, style.eye);
+
+        // 瞳孔限制在眼白范围内
+        let radius = PUPIL_RADIUS * e.pupil_size.clamp(0.2, 2.0);
+        let max_x = (EYE_RX as f32 - radius - 3.0).max(0.0);
+        let max_y = (EYE_RY as f32 - radius - 3.0).max(0.0);
+        let (mut px, mut py) = (e.pupil_x, e.pupil_y);
+        let len = (px * px + py * py).sqrt();
+        if len > 1.0 {
+            px /= len;
+            py /= len;
+        }
+        let pupil = (cx + (px * max_x) as i32, EYE_Y + (py * max_y) as i32);
+        let r = radius.round() as usize;
+        self.fill_ellipse(pupil, r, r, style.pupil);
+
+        // 上眼睑
+        let top = EYE_Y - EYE_RY - 1;
+        if lid_y > top {
+            self.fill_rect(
+                (cx - EYE_RX - 1).max(0) as usize,
+                top.max(0) as usize,
+                (EYE_RX * 2 + 3) as usize,
+                (lid_y - top) as usize,
+                style.background,
+            );
+        }
+    }
+
+    /// 绘制眉毛，`side` 为 -1（左）或 1（右）。
+    fn draw_brow(&mut self, cx: i32, side: i32, e: &Expression, style: &FaceStyle) {
+        let base = EYE_Y - EYE_RY - 14 - (e.brow_height.clamp(-1.0, 1.0) * 12.0) as i32;
+        let tilt = (e.brow_tilt.clamp(-1.0, 1.0) * 8.0) as i32;
+        let outer = (cx + side * 24, base + tilt);
+        let inner = (cx - side * 24, base - tilt);
+        self.draw_line(outer, inner, LINE_WIDTH + 1, style.line);
+    }
+
+    /// 绘制嘴巴：上唇为抛物线，张嘴时与下唇之间填充。
+    fn draw_mouth(&mut self, cx: i32, e: &Expression, style: &FaceStyle) {
+        const SAMPLES: usize = 24;
+        let half = 20.0 + 30.0 * e.mouth_width.clamp(0.0, 1.0);
+        let curve = e.mouth_curve.clamp(-1.0, 1.0) * 16.0;
+        let open = e.mouth_open.clamp(0.0, 1.0) * 36.0;
+
+        let mut upper: Vec<Point> = Vec::with_capacity(SAMPLES + 1);
+        let mut lower: Vec<Point> = Vec::with_capacity(SAMPLES + 1);
+        for i in 0..=SAMPLES {
+            let t = i as f32 / SAMPLES as f32 * 2.0 - 1.0;
+            let bulge = 1.0 - t * t;
+            // 微笑时中间下沉、嘴角上扬，整体保持在 MOUTH_Y 附近
+            let y = MOUTH_Y as f32 + curve * (bulge - 0.5);
+            let x = cx + (t * half) as i32;
+            upper.push((x, y as i32));
+            lower.push((x, (y + open * bulge.sqrt()) as i32));
+        }
+
+        if open >= 2.0 {
+            let mut outline = upper.clone();
+            outline.extend(lower.iter().rev());
+            self.fill_polygon(&outline, style.line);
+        }
+        self.draw_polyline(&upper, LINE_WIDTH, style.line);
+    }
+}
+
+/// 表情动画：在表情之间按缓动曲线平滑过渡。
+#[derive(Debug, Clone)]
+pub struct Face {
+    style: FaceStyle,
+    from: Expression,
+    to: Expression,
+    elapsed: Duration,
+    duration: Duration,
+}
+
+impl Face {
+    /// 创建平静表情的脸。
+    pub fn new() -> Self {
+        Self {
+            style: FaceStyle::default(),
+            from: Expression::NEUTRAL,
+            to: Expression::NEUTRAL,
+            elapsed: Duration::ZERO,
+            duration: Duration::ZERO,
+        }
+    }
+
+    /// 设置配色。
+    pub fn style(mut self, style: FaceStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// 配色。
+    pub fn face_style(&self) -> &FaceStyle {
+        &self.style
+    }
+
+    /// 当前（过渡中的）表情。
+    pub fn expression(&self) -> Expression {
+        if self.elapsed >= self.duration {
+            return self.to;
+        }
+        let t = self.elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        // smoothstep 缓动
+        self.from.lerp(&self.to, t * t * (3.0 - 2.0 * t))
+    }
+
+    /// 过渡的目标表情。
+    pub fn target(&self) -> Expression {
+        self.to
+    }
+
+    /// 立即切换表情。
+    pub fn set_expression(&mut self, expression: Expression) {
+        self.transition_to(expression, Duration::ZERO);
+    }
+
+    /// 在 `duration` 内从当前表情平滑过渡到 `expression`。
+    pub fn transition_to(&mut self, expression: Expression, duration: Duration) {
+        self.from = self.expression();
+        self.to = expression;
+        self.elapsed = Duration::ZERO;
+        self.duration = duration;
+    }
+
+    /// 是否正在过渡。
+    pub fn is_transitioning(&self) -> bool {
+        self.elapsed < self.duration
+    }
+
+    /// 推进动画时间。
+    pub fn advance(&mut self, dt: Duration) {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+    }
+
+    /// 把当前表情绘制到缓冲区。
+    pub fn render(&self, buffer: &mut ImageBuffer) {
+        buffer.draw_face(&self.expression(), &self.style);
+    }
+}
+
+impl Default for Face {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameSource for Face {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        self.render(buffer);
+        self.advance(DEFAULT_INTERVAL);
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        Some(DEFAULT_INTERVAL)
+    }
+}
@@ -0,0 +1,272 @@
+//! ElectronBot 库的模拟 MCU。
+//!
+//! [`FakeMcu`] 按原版固件的方式应答同步：每个周期先回复 32 字节请求（带上报的关节角度），
+//! 再接收一段图片和尾包，收满所有周期后得到完整的一帧。可以在指定的收发上注入故障
+//! （短读、超时、端点停止、拔出），用于不依赖硬件、结果确定的同步测试。
+//!
+//! 传输通道交给 `ElectronBot` 后，通过 [`FakeMcu::state`] 共享的状态查看 MCU 收到的内容。
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::modules::error::TransferErrorKind;
+use crate::modules::extra_data::ExtraData;
+use crate::modules::protocol::{ProtocolProfile, EXTRA_SIZE};
+use crate::modules::transport::{Direction, Transport};
+use crate::modules::types::JointAngles;
+
+/// 注入的故障。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// 接收只返回这么多字节（只用于接收）。
+    ShortRead(usize),
+    /// 收发超时。
+    Timeout,
+    /// 端点停止，清除停止状态之前该方向的收发一直失败。
+    Stall,
+    /// 设备拔出，之后所有收发都失败。
+    Disconnect,
+}
+
+impl Fault {
+    fn error(self) -> (TransferErrorKind, &'static str) {
+        match self {
+            Fault::ShortRead(_) | Fault::Timeout => {
+                (TransferErrorKind::Timeout, "Operation timed out")
+            }
+            Fault::Stall => (TransferErrorKind::Pipe, "Pipe error"),
+            Fault::Disconnect => (TransferErrorKind::NoDevice, "No such device"),
+        }
+    }
+}
+
+/// 模拟 MCU 收到的内容和统计。
+#[derive(Debug, Clone, Default)]
+pub struct FakeMcuState {
+    /// 回复的请求数。
+    pub requests: u64,
+    /// 收到的包数。
+    pub packets: u64,
+    /// 收满的周期数。
+    pub cycles: u64,
+    /// 没有收满就开始下一个请求的周期数（主机跳过了发送失败的包）。
+    pub torn_cycles: u64,
+    /// 收到的完整帧数（包括有周期没收满的帧）。
+    pub frames: u64,
+    /// 最近收到的一帧。
+    pub frame: Option<Vec<u8>>,
+    /// 最近一个尾包中的扩展数据。
+    pub extra_data: Option<ExtraData>,
+    /// 在请求中上报的关节角度，舵机使能时跟随收到的目标角度。
+    pub reported: JointAngles,
+    /// 清除端点停止状态的次数。
+    pub halts_cleared: u32,
+}
+
+/// 模拟 MCU 的传输通道。
+#[derive(Debug)]
+pub struct FakeMcu {
+    profile: ProtocolProfile,
+    state: Arc<Mutex<FakeMcuState>>,
+    receive_faults: BTreeMap<usize, Fault>,
+    transmit_faults: BTreeMap<usize, Fault>,
+    receives: usize,
+    transmits: usize,
+    stalled_in: bool,
+    stalled_out: bool,
+    disconnected: bool,
+    last_error: Option<TransferErrorKind>,
+    /// 当前周期收到的数据，`None` 表示还没有回复请求。
+    cycle_data: Option<Vec<u8>>,
+    segment: usize,
+    frame: Vec<u8>,
+}
+
+impl FakeMcu {
+    /// 使用原版固件的传输布局。
+    pub fn new() -> Self {
+        Self::with_profile(ProtocolProfile::STOCK)
+    }
+
+    /// 使用指定的传输布局。
+    pub fn with_profile(profile: ProtocolProfile) -> Self {
+        Self {
+            profile,
+            state: Arc::new(Mutex::new(FakeMcuState::default())),
+            receive_faults: BTreeMap::new(),
+            transmit_faults: BTreeMap::new(),
+            receives: 0,
+            transmits: 0,
+            stalled_in: false,
+            stalled_out: false,
+            disconnected: false,
+            last_error: None,
+            cycle_data: None,
+            segment: 0,
+            frame: vec![0; profile.frame_size()],
+        }
+    }
+
+    /// 第 `call` 次接收（从 0 开始，包括失败和重试）时发生 `fault`。
+    pub fn fail_receive(mut self, call: usize, fault: Fault) -> Self {
+        self.receive_faults.insert(call, fault);
+        self
+    }
+
+    /// 第 `call` 次发送（从 0 开始，包括失败和重试）时发生 `fault`；短读在发送时按超时处理。
+    pub fn fail_transmit(mut self, call: usize, fault: Fault) -> Self {
+        self.transmit_faults.insert(call, fault);
+        self
+    }
+
+    /// 共享的状态，交出传输通道后仍然可以查看。
+    pub fn state(&self) -> Arc<Mutex<FakeMcuState>> {
+        Arc::clone(&self.state)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FakeMcuState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn fail(&mut self, fault: Fault, direction: Direction) -> String {
+        match (fault, direction) {
+            (Fault::Disconnect, _) => self.disconnected = true,
+            (Fault::Stall, Direction::In) => self.stalled_in = true,
+            (Fault::Stall, Direction::Out) => self.stalled_out = true,
+            _ => {}
+        }
+        let (kind, message) = fault.error();
+        self.last_error = Some(kind);
+        message.to_string()
+    }
+
+    /// 当前方向持续存在的故障（拔出或端点停止）。
+    fn persistent_fault(&self, direction: Direction) -> Option<Fault> {
+        let stalled = match direction {
+            Direction::In => self.stalled_in,
+            Direction::Out => self.stalled_out,
+        };
+        if self.disconnected {
+            Some(Fault::Disconnect)
+        } else if stalled {
+            Some(Fault::Stall)
+        } else {
+            None
+        }
+    }
+
+    /// 收满一个周期：把图片写入帧，记录尾包中的扩展数据。
+    fn finish_cycle(&mut self, data: &[u8]) {
+        let segment_size = self.profile.segment_size();
+        let start = self.segment * segment_size;
+        self.frame[start..start + segment_size].copy_from_slice(&data[..segment_size]);
+        let mut extra = ExtraData::new();
+        extra.set_raw(&data[segment_size..segment_size + EXTRA_SIZE]);
+
+        let mut state = self.lock();
+        if extra.is_enabled() {
+            state.reported = extra.get_joint_angles();
+        }
+        state.extra_data = Some(extra);
+        state.cycles += 1;
+        drop(state);
+        self.next_segment();
+    }
+
+    fn next_segment(&mut self) {
+        self.segment += 1;
+        if self.segment == self.profile.cycles {
+            self.segment = 0;
+            let frame = self.frame.clone();
+            let mut state = self.lock();
+            state.frames += 1;
+            state.frame = Some(frame);
+        }
+    }
+}
+
+impl Default for FakeMcu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transport for FakeMcu {
+    fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+        let call = self.transmits;
+        self.transmits += 1;
+        if let Some(fault) = self
+            .persistent_fault(Direction::Out)
+            .or_else(|| self.transmit_faults.remove(&call))
+        {
+            return Err(self.fail(fault, Direction::Out));
+        }
+        // 没有回复请求时 MCU 不接收数据
+        let Some(cycle_data) = &mut self.cycle_data else {
+            self.last_error = Some(TransferErrorKind::Timeout);
+            return Err("Operation timed out".to_string());
+        };
+        cycle_data.extend_from_slice(data);
+        let full = cycle_data.len() >= self.profile.segment_size() + EXTRA_SIZE;
+        self.lock().packets += 1;
+        if full {
+            let cycle_data = self.cycle_data.take().unwrap_or_default();
+            self.finish_cycle(&cycle_data);
+        }
+        Ok(true)
+    }
+
+    fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+        let call = self.receives;
+        self.receives += 1;
+        let fault = self
+            .persistent_fault(Direction::In)
+            .or_else(|| self.receive_faults.remove(&call));
+        match fault {
+            Some(Fault::ShortRead(_)) | None => {}
+            Some(fault) => return Err(self.fail(fault, Direction::In)),
+        }
+
+        // 上一个周期没有收满时丢弃，从下一段继续
+        if self.cycle_data.take().is_some_and(|data| !data.is_empty()) {
+            self.lock().torn_cycles += 1;
+            self.next_segment();
+        }
+        let mut request = ExtraData::new();
+        let mut state = self.lock();
+        request.set_joint_angles(&state.reported, false);
+        state.requests += 1;
+        drop(state);
+        self.cycle_data = Some(Vec::with_capacity(self.profile.segment_size() + EXTRA_SIZE));
+
+        let len = data.len().min(self.profile.request_size).min(EXTRA_SIZE);
+        let len = match fault {
+            Some(Fault::ShortRead(n)) => len.min(n),
+            _ => len,
+        };
+        data[..len].copy_from_slice(&request.as_data()[..len]);
+        Ok(len)
+    }
+
+    fn last_error_kind(&self) -> Option<TransferErrorKind> {
+        self.last_error
+    }
+
+    fn clear_halt(&mut self, direction: Direction) -> Result<(), String> {
+        if self.disconnected {
+            return Err("No such device".to_string());
+        }
+        match direction {
+            Direction::In => self.stalled_in = false,
+            Direction::Out => self.stalled_out = false,
+        }
+        self.last_error = None;
+        self.lock().halts_cleared += 1;
+        Ok(())
+    }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        Some(self)
+    }
+}
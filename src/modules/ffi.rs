@@ -0,0 +1,507 @@
+//! ElectronBot 库的 C 接口。
+//!
+//! 以稳定的 C ABI 导出常用 API，C/C++/C#/Unity 等宿主可以直接调用本库，
+//! 不再需要原来的 C++ SDK。头文件为 `include/electron_bot.h`，由 cbindgen 按
+//! `cbindgen.toml` 生成。
+//!
+//! 约定：
+//! - [`ElectronBot`] 是不透明指针，由 [`eb_new`] 创建、[`eb_free`] 释放
+//! - 返回 [`EbError`] 的函数失败时可以用 [`eb_last_error`] 取得当前线程最后一次的错误信息
+//! - 图片数据默认按行紧密排列，左上角为原点，按当前适配方式缩放到屏幕；
+//!   [`eb_set_image_pixels`] 支持行跨度、BGR 顺序和上下翻转
+//!
+//! `bindings/csharp` 下是基于本接口的 C# / Unity 封装。
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::modules::error::BotError;
+use crate::modules::image::{FitMode, FitOptions};
+use crate::modules::types::Color;
+use crate::ElectronBot;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// C 接口的错误码。
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EbError {
+    /// 成功。
+    Ok = 0,
+    /// 传入了空指针。
+    NullPointer = 1,
+    /// 参数无效（如图片尺寸为 0、数据长度不足）。
+    InvalidArgument = 2,
+    /// 未找到设备。
+    DeviceNotFound = 3,
+    /// USB 错误。
+    Usb = 4,
+    /// 发送数据失败。
+    SendFailed = 5,
+    /// 接收数据失败。
+    ReceiveFailed = 6,
+    /// 图片错误。
+    Image = 7,
+    /// 未连接到设备。
+    NotConnected = 8,
+    /// 未找到接口。
+    InterfaceNotFound = 9,
+    /// 其他错误（统计、配置）。
+    Other = 10,
+    /// 库内部发生 panic。
+    Panic = 11,
+}
+
+impl From<&BotError> for EbError {
+    fn from(error: &BotError) -> Self {
+        match error {
+            BotError::DeviceNotFound(..) => EbError::DeviceNotFound,
+            BotError::UsbError(_) | BotError::MacOsAccess(_) => EbError::Usb,
+            BotError::SendFailed(_) | BotError::SyncTimeout(..) => EbError::SendFailed,
+            BotError::ReceiveFailed(_) => EbError::ReceiveFailed,
+            BotError::ImageError(_) => EbError::Image,
+            BotError::NotConnected => EbError::NotConnected,
+            BotError::InterfaceNotFound => EbError::InterfaceNotFound,
+            BotError::StatsError(_)
+            | BotError::ConfigError(_)
+            | BotError::RecordError(_)
+            | BotError::Cancelled
+            | BotError::EmergencyStop
+            | BotError::Collision(_)
+            | BotError::ThreadError(_) => EbError::Other,
+        }
+    }
+}
+
+/// 图片适配方式。
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EbFitMode {
+    /// 拉伸填满屏幕（默认）。
+    Stretch = 0,
+    /// 保持宽高比完整显示。
+    Contain = 1,
+    /// 保持宽高比填满屏幕，居中裁剪。
+    Cover = 2,
+    /// 不缩放，居中显示。
+    Center = 3,
+}
+
+/// 像素格式。
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EbPixelFormat {
+    /// R、G、B 各 1 字节。
+    Rgb24 = 0,
+    /// R、G、B、A 各 1 字节（Unity 的 `TextureFormat.RGBA32`、`Color32[]`）。
+    Rgba32 = 1,
+    /// B、G、R、A 各 1 字节（Windows 位图、Unity 的 `TextureFormat.BGRA32`）。
+    Bgra32 = 2,
+    /// B、G、R 各 1 字节。
+    Bgr24 = 3,
+}
+
+impl TryFrom<u32> for EbFitMode {
+    type Error = EbError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(EbFitMode::Stretch),
+            1 => Ok(EbFitMode::Contain),
+            2 => Ok(EbFitMode::Cover),
+            3 => Ok(EbFitMode::Center),
+            _ => Err(fail(
+                EbError::InvalidArgument,
+                format!("无效的适配方式: {}", value),
+            )),
+        }
+    }
+}
+
+impl TryFrom<u32> for EbPixelFormat {
+    type Error = EbError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(EbPixelFormat::Rgb24),
+            1 => Ok(EbPixelFormat::Rgba32),
+            2 => Ok(EbPixelFormat::Bgra32),
+            3 => Ok(EbPixelFormat::Bgr24),
+            _ => Err(fail(
+                EbError::InvalidArgument,
+                format!("无效的像素格式: {}", value),
+            )),
+        }
+    }
+}
+
+impl EbPixelFormat {
+    fn bytes_per_pixel(self) -> usize {
+        match self {
+            EbPixelFormat::Rgb24 | EbPixelFormat::Bgr24 => 3,
+            EbPixelFormat::Rgba32 | EbPixelFormat::Bgra32 => 4,
+        }
+    }
+
+    /// R、G、B 在一个像素中的偏移。
+    fn rgb_offsets(self) -> [usize; 3] {
+        match self {
+            EbPixelFormat::Rgb24 | EbPixelFormat::Rgba32 => [0, 1, 2],
+            EbPixelFormat::Bgr24 | EbPixelFormat::Bgra32 => [2, 1, 0],
+        }
+    }
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+fn fail(code: EbError, message: String) -> EbError {
+    set_last_error(message);
+    code
+}
+
+/// 执行 `f`，把错误和 panic 转换为错误码。
+fn guard(f: impl FnOnce() -> Result<(), EbError>) -> EbError {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => EbError::Ok,
+        Ok(Err(code)) => code,
+        Err(_) => fail(EbError::Panic, "库内部发生 panic".to_string()),
+    }
+}
+
+fn bot_error(error: BotError) -> EbError {
+    let code = EbError::from(&error);
+    fail(code, error.to_string())
+}
+
+/// 把指针转换为可变引用。
+unsafe fn bot_mut<'a>(bot: *mut ElectronBot) -> Result<&'a mut ElectronBot, EbError> {
+    bot.as_mut()
+        .ok_or_else(|| fail(EbError::NullPointer, "bot 为空指针".to_string()))
+}
+
+/// 图片数据长度计算溢出时的错误。
+fn overflow(width: u32, height: u32) -> EbError {
+    fail(
+        EbError::InvalidArgument,
+        format!("图片尺寸过大: {}x{}", width, height),
+    )
+}
+
+/// 紧密排列的 `width * height` 图片的字节数，溢出时返回错误。
+fn packed_len(width: u32, height: u32, bpp: usize) -> Result<usize, EbError> {
+    (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|n| n.checked_mul(bpp))
+        .ok_or_else(|| overflow(width, height))
+}
+
+/// 读取 `width * height` 图片的 `len` 字节数据。
+unsafe fn pixels<'a>(
+    data: *const u8,
+    width: u32,
+    height: u32,
+    len: usize,
+) -> Result<&'a [u8], EbError> {
+    if data.is_null() {
+        return Err(fail(EbError::NullPointer, "data 为空指针".to_string()));
+    }
+    if width == 0 || height == 0 {
+        return Err(fail(
+            EbError::InvalidArgument,
+            format!("无效的图片尺寸: {}x{}", width, height),
+        ));
+    }
+    Ok(std::slice::from_raw_parts(data, len))
+}
+
+/// 创建实例（不连接），用 [`eb_free`] 释放。
+#[no_mangle]
+pub extern "C" fn eb_new() -> *mut ElectronBot {
+    Box::into_raw(Box::new(ElectronBot::new()))
+}
+
+/// 断开连接并释放实例。
+///
+/// # Safety
+///
+/// `bot` 必须是 [`eb_new`] 返回的指针或空指针，释放后不能再使用。
+#[no_mangle]
+pub unsafe extern "C" fn eb_free(bot: *mut ElectronBot) {
+    if !bot.is_null() {
+        drop(Box::from_raw(bot));
+    }
+}
+
+/// 连接到设备。
+///
+/// # Safety
+///
+/// `bot` 必须是 [`eb_new`] 返回的有效指针。
+#[no_mangle]
+pub unsafe extern "C" fn eb_connect(bot: *mut ElectronBot) -> EbError {
+    guard(|| {
+        bot_mut(bot)?.connect().map_err(bot_error)?;
+        Ok(())
+    })
+}
+
+/// 断开设备连接。
+///
+/// # Safety
+///
+/// `bot` 必须是 [`eb_new`] 返回的有效指针。
+#[no_mangle]
+pub unsafe extern "C" fn eb_disconnect(bot: *mut ElectronBot) {
+    guard(|| {
+        bot_mut(bot)?.disconnect();
+        Ok(())
+    });
+}
+
+/// 是否已连接，空指针返回 `false`。
+///
+/// # Safety
+///
+/// `bot` 必须是 [`eb_new`] 返回的有效指针或空指针。
+#[no_mangle]
+pub unsafe extern "C" fn eb_is_connected(bot: *const ElectronBot) -> bool {
+    bot.as_ref().is_some_and(ElectronBot::is_connected)
+}
+
+/// 设置 RGB 图片（每像素 3 字节），按当前适配方式缩放。
+///
+/// # Safety
+///
+/// `bot` 必须是有效指针，`data` 必须指向至少 `width * height * 3` 字节。
+#[no_mangle]
+pub unsafe extern "C" fn eb_set_image_rgb(
+    bot: *mut ElectronBot,
+    data: *const u8,
+    width: u32,
+    height: u32,
+) -> EbError {
+    guard(|| {
+        let bot = bot_mut(bot)?;
+        let len = packed_len(width, height, 3)?;
+        let data = pixels(data, width, height, len)?;
+        let img = image::RgbImage::from_raw(width, height, data.to_vec())
+            .ok_or_else(|| fail(EbError::InvalidArgument, "图片数据长度不足".to_string()))?;
+        bot.set_image_from_image(&image::DynamicImage::ImageRgb8(img));
+        Ok(())
+    })
+}
+
+/// 设置 RGBA 图片（每像素 4 字节，忽略透明度），按当前适配方式缩放。
+///
+/// # Safety
+///
+/// `bot` 必须是有效指针，`data` 必须指向至少 `width * height * 4` 字节。
+#[no_mangle]
+pub unsafe extern "C" fn eb_set_image_rgba(
+    bot: *mut ElectronBot,
+    data: *const u8,
+    width: u32,
+    height: u32,
+) -> EbError {
+    guard(|| {
+        let bot = bot_mut(bot)?;
+        let len = packed_len(width, height, 4)?;
+        let data = pixels(data, width, height, len)?;
+        let img = image::RgbaImage::from_raw(width, height, data.to_vec())
+            .ok_or_else(|| fail(EbError::InvalidArgument, "图片数据长度不足".to_string()))?;
+        bot.set_image_from_image(&image::DynamicImage::ImageRgba8(img));
+        Ok(())
+    })
+}
+
+/// 设置 JPEG/PNG 等编码后的图片。
+///
+/// # Safety
+///
+/// `bot` 必须是有效指针，`data` 必须指向至少 `len` 字节。
+#[no_mangle]
+pub unsafe extern "C" fn eb_set_image_bytes(
+    bot: *mut ElectronBot,
+    data: *const u8,
+    len: usize,
+) -> EbError {
+    guard(|| {
+        let bot = bot_mut(bot)?;
+        if data.is_null() {
+            return Err(fail(EbError::NullPointer, "data 为空指针".to_string()));
+        }
+        let bytes = std::slice::from_raw_parts(data, len);
+        bot.set_image_from_bytes(bytes).map_err(bot_error)
+    })
+}
+
+/// 设置任意行距和像素格式的图片，按当前适配方式缩放。
+///
+/// `format` 取 [`EbPixelFormat`] 的值，未知的值返回 [`EbError::InvalidArgument`]。
+/// `stride` 为每行字节数，0 表示紧密排列；`flip_y` 为 `true` 时数据的第一行是图片底部
+/// （Unity 的 `Texture2D.GetRawTextureData`、OpenGL 的 `glReadPixels` 都是这种顺序）。
+///
+/// # Safety
+///
+/// `bot` 必须是有效指针，`data` 必须指向至少 `stride * height` 字节。
+#[no_mangle]
+pub unsafe extern "C" fn eb_set_image_pixels(
+    bot: *mut ElectronBot,
+    data: *const u8,
+    width: u32,
+    height: u32,
+    stride: u32,
+    format: u32,
+    flip_y: bool,
+) -> EbError {
+    guard(|| {
+        let bot = bot_mut(bot)?;
+        let format = EbPixelFormat::try_from(format)?;
+        let bpp = format.bytes_per_pixel();
+        let row_bytes = packed_len(width, 1, bpp)?;
+        let stride = match stride as usize {
+            0 => row_bytes,
+            stride if stride < row_bytes => {
+                return Err(fail(
+                    EbError::InvalidArgument,
+                    format!("行距 {} 小于一行的字节数 {}", stride, row_bytes),
+                ))
+            }
+            stride => stride,
+        };
+        // 最后一行不需要补齐到完整行距
+        let len = stride
+            .checked_mul((height as usize).saturating_sub(1))
+            .and_then(|n| n.checked_add(row_bytes))
+            .ok_or_else(|| overflow(width, height))?;
+        let data = pixels(data, width, height, len)?;
+
+        let [r, g, b] = format.rgb_offsets();
+        let mut rgb = Vec::with_capacity(packed_len(width, height, 3)?);
+        for y in 0..height as usize {
+            let row = if flip_y { height as usize - 1 - y } else { y };
+            let row = &data[row * stride..row * stride + row_bytes];
+            for pixel in row.chunks_exact(bpp) {
+                rgb.extend_from_slice(&[pixel[r], pixel[g], pixel[b]]);
+            }
+        }
+        let img = image::RgbImage::from_raw(width, height, rgb)
+            .ok_or_else(|| fail(EbError::InvalidArgument, "图片数据长度不足".to_string()))?;
+        bot.set_image_from_image(&image::DynamicImage::ImageRgb8(img));
+        Ok(())
+    })
+}
+
+/// 设置纯色。
+///
+/// # Safety
+///
+/// `bot` 必须是 [`eb_new`] 返回的有效指针。
+#[no_mangle]
+pub unsafe extern "C" fn eb_set_image_color(bot: *mut ElectronBot, r: u8, g: u8, b: u8) -> EbError {
+    guard(|| {
+        bot_mut(bot)?.set_image_color(Color::Custom(r, g, b));
+        Ok(())
+    })
+}
+
+/// 设置图片适配方式，`mode` 取 [`EbFitMode`] 的值，未知的值返回 [`EbError::InvalidArgument`]。
+///
+/// # Safety
+///
+/// `bot` 必须是 [`eb_new`] 返回的有效指针。
+#[no_mangle]
+pub unsafe extern "C" fn eb_set_fit_mode(bot: *mut ElectronBot, mode: u32) -> EbError {
+    guard(|| {
+        let bot = bot_mut(bot)?;
+        let mode = match EbFitMode::try_from(mode)? {
+            EbFitMode::Stretch => FitMode::Stretch,
+            EbFitMode::Contain => FitMode::Contain,
+            EbFitMode::Cover => FitMode::Cover,
+            EbFitMode::Center => FitMode::Center,
+        };
+        bot.set_fit_options(FitOptions {
+            mode,
+            ..*bot.fit_options()
+        });
+        Ok(())
+    })
+}
+
+/// 设置 6 个关节角度（度），`enable` 为 `false` 时释放舵机。
+///
+/// # Safety
+///
+/// `bot` 必须是有效指针，`angles` 必须指向 6 个 `float`。
+#[no_mangle]
+pub unsafe extern "C" fn eb_set_joints(
+    bot: *mut ElectronBot,
+    angles: *const f32,
+    enable: bool,
+) -> EbError {
+    guard(|| {
+        let bot = bot_mut(bot)?;
+        let angles = angles
+            .cast::<[f32; 6]>()
+            .as_ref()
+            .ok_or_else(|| fail(EbError::NullPointer, "angles 为空指针".to_string()))?;
+        bot.set_joint_angles(angles, enable).map_err(bot_error)
+    })
+}
+
+/// 读取机器人上报的 6 个关节角度（度），还没有同步过时为设置的目标角度。
+///
+/// # Safety
+///
+/// `bot` 必须是有效指针，`out` 必须指向可写入 6 个 `float` 的内存。
+#[no_mangle]
+pub unsafe extern "C" fn eb_get_joints(bot: *const ElectronBot, out: *mut f32) -> EbError {
+    guard(|| {
+        let bot = bot
+            .as_ref()
+            .ok_or_else(|| fail(EbError::NullPointer, "bot 为空指针".to_string()))?;
+        let out = out
+            .cast::<[f32; 6]>()
+            .as_mut()
+            .ok_or_else(|| fail(EbError::NullPointer, "out 为空指针".to_string()))?;
+        *out = *bot.get_joint_angles().as_array();
+        Ok(())
+    })
+}
+
+/// 与机器人同步图片和关节角度。
+///
+/// # Safety
+///
+/// `bot` 必须是 [`eb_new`] 返回的有效指针。
+#[no_mangle]
+pub unsafe extern "C" fn eb_sync(bot: *mut ElectronBot) -> EbError {
+    guard(|| {
+        bot_mut(bot)?.sync().map_err(bot_error)?;
+        Ok(())
+    })
+}
+
+/// 当前线程最后一次的错误信息（UTF-8），没有错误时返回空指针。
+///
+/// 返回的字符串在同一线程下一次出错前有效，调用方不能释放。
+#[no_mangle]
+pub extern "C" fn eb_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| {
+        e.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// 库版本号，例如 `"0.1.0"`。
+#[no_mangle]
+pub extern "C" fn eb_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
@@ -0,0 +1,126 @@
+//! ElectronBot 库的关节角度滤波。
+//!
+//! MCU 上报的角度带有噪声，示教录制和可视化时会抖动。[`JointFilter`] 对每次同步收到的角度
+//! 做指数平滑（一阶低通）或滑动中值滤波，中值滤波能去掉偶发的跳变而不拖慢阶跃。
+
+use std::collections::VecDeque;
+
+use crate::modules::types::JointAngles;
+
+/// 中值滤波的最大窗口。
+const MAX_WINDOW: usize = 31;
+
+/// 滤波方式。
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FilterKind {
+    /// 不滤波（默认）。
+    #[default]
+    None,
+    /// 指数平滑：`输出 = 输出 + alpha * (输入 - 输出)`，`alpha` 越小越平滑（0-1）。
+    Exponential {
+        /// 平滑系数。
+        alpha: f32,
+    },
+    /// 最近 `window` 次采样的中值。
+    Median {
+        /// 窗口大小（1-31）。
+        window: usize,
+    },
+}
+
+/// 关节角度滤波器。
+#[derive(Debug, Clone, Default)]
+pub struct JointFilter {
+    kind: FilterKind,
+    output: Option<JointAngles>,
+    history: VecDeque<JointAngles>,
+}
+
+impl JointFilter {
+    /// 创建指定方式的滤波器（参数限制在有效范围内）。
+    pub fn new(kind: FilterKind) -> Self {
+        let kind = match kind {
+            FilterKind::Exponential { alpha } => FilterKind::Exponential {
+                alpha: if alpha.is_finite() {
+                    alpha.clamp(0.01, 1.0)
+                } else {
+                    1.0
+                },
+            },
+            FilterKind::Median { window } => FilterKind::Median {
+                window: window.clamp(1, MAX_WINDOW),
+            },
+            FilterKind::None => FilterKind::None,
+        };
+        Self {
+            kind,
+            output: None,
+            history: VecDeque::new(),
+        }
+    }
+
+    /// 指数平滑滤波器。
+    pub fn exponential(alpha: f32) -> Self {
+        Self::new(FilterKind::Exponential { alpha })
+    }
+
+    /// 中值滤波器。
+    pub fn median(window: usize) -> Self {
+        Self::new(FilterKind::Median { window })
+    }
+
+    /// 滤波方式。
+    pub fn kind(&self) -> FilterKind {
+        self.kind
+    }
+
+    /// 最近一次的输出，还没有采样时为 `None`。
+    pub fn value(&self) -> Option<&JointAngles> {
+        self.output.as_ref()
+    }
+
+    /// 清空历史，下一次采样直接作为输出。
+    pub fn reset(&mut self) {
+        self.output = None;
+        self.history.clear();
+    }
+
+    /// 加入一次采样，返回滤波后的角度。
+    pub fn update(&mut self, sample: &JointAngles) -> JointAngles {
+        let output = match self.kind {
+            FilterKind::None => sample.clone(),
+            FilterKind::Exponential { alpha } => match &self.output {
+                Some(previous) => {
+                    let mut output = previous.clone();
+                    for (out, input) in output.as_array_mut().iter_mut().zip(sample.as_array()) {
+                        *out += alpha * (input - *out);
+                    }
+                    output
+                }
+                None => sample.clone(),
+            },
+            FilterKind::Median { window } => {
+                if self.history.len() == window {
+                    self.history.pop_front();
+                }
+                self.history.push_back(sample.clone());
+                let mut output = JointAngles::new();
+                let mut values = Vec::with_capacity(self.history.len());
+                for (joint, out) in output.as_array_mut().iter_mut().enumerate() {
+                    values.clear();
+                    values.extend(self.history.iter().map(|angles| angles.as_array()[joint]));
+                    values.sort_by(f32::total_cmp);
+                    let middle = values.len() / 2;
+                    *out = if values.len().is_multiple_of(2) {
+                        (values[middle - 1] + values[middle]) / 2.0
+                    } else {
+                        values[middle]
+                    };
+                }
+                output
+            }
+        };
+        self.output = Some(output.clone());
+        output
+    }
+}
@@ -0,0 +1,227 @@
+//! ElectronBot 库的画面构建。
+//!
+//! [`Frame`] 把绘图调用串成一条链，按调用顺序绘制，最后用 [`Frame::build`] 得到 [`ImageBuffer`]。
+//! 适合一次性的画面（提示页、状态页、测试图），不用先建缓冲区再逐个调用绘图函数：
+//!
+//! ```
+//! use electron_bot::{Color, Frame, Rect, Sprite};
+//!
+//! let icon = Sprite::from_bgr(2, 2, vec![255; 12]).unwrap();
+//! let buffer = Frame::new()
+//!     .antialias(true)
+//!     .background(Color::Black)
+//!     .circle((120, 100), 40, Color::Yellow)
+//!     .rounded_rect(Rect::new(40, 170, 160, 40), 10, Color::Blue)
+//!     .line((0, 0), (239, 239), 3, Color::Red)
+//!     .sprite(&icon, 10, 10)
+//!     .build();
+//! assert_eq!(buffer.get_pixel(120, 100), Some(Color::Custom(0, 255, 255)));
+//! ```
+//!
+//! 坐标使用 `i32`，超出屏幕的部分会被裁剪。链中没有的操作可以用 [`Frame::with`] 直接操作缓冲区。
+
+use crate::modules::color::Gradient;
+use crate::modules::draw::{Point, Rect};
+use crate::modules::face::{Expression, FaceStyle};
+use crate::modules::image::ImageBuffer;
+use crate::modules::sprite::{BlitOptions, Sprite};
+#[cfg(any(feature = "text", feature = "embedded-font"))]
+use crate::modules::text::{Font, TextStyle};
+use crate::modules::types::Color;
+
+/// 链式构建的画面。
+#[derive(Debug, Clone)]
+pub struct Frame {
+    buffer: ImageBuffer,
+}
+
+impl Frame {
+    /// 从黑屏开始构建。
+    pub fn new() -> Self {
+        Self {
+            buffer: ImageBuffer::new(),
+        }
+    }
+
+    /// 在已有的画面上继续绘制。
+    pub fn from_buffer(buffer: ImageBuffer) -> Self {
+        Self { buffer }
+    }
+
+    /// 开启或关闭之后绘制的图元的抗锯齿。
+    pub fn antialias(mut self, enable: bool) -> Self {
+        self.buffer.set_antialias(enable);
+        self
+    }
+
+    /// 用纯色填满整个画面。
+    pub fn background(mut self, color: Color) -> Self {
+        self.buffer.clear(color);
+        self
+    }
+
+    /// 用渐变填满整个画面。
+    pub fn gradient(mut self, gradient: &Gradient) -> Self {
+        self.buffer.fill_gradient(gradient);
+        self
+    }
+
+    /// 设置一个像素（超出屏幕时忽略）。
+    pub fn pixel(mut self, x: i32, y: i32, color: Color) -> Self {
+        if x >= 0 && y >= 0 {
+            self.buffer.set_pixel(x as usize, y as usize, color);
+        }
+        self
+    }
+
+    /// 填充矩形。
+    pub fn rect(mut self, rect: Rect, color: Color) -> Self {
+        self.buffer.fill_rounded_rect(rect, 0, color);
+        self
+    }
+
+    /// 绘制矩形边框。
+    pub fn rect_outline(mut self, rect: Rect, width: usize, color: Color) -> Self {
+        self.buffer.draw_rounded_rect(rect, 0, width, color);
+        self
+    }
+
+    /// 填充圆角矩形。
+    pub fn rounded_rect(mut self, rect: Rect, radius: usize, color: Color) -> Self {
+        self.buffer.fill_rounded_rect(rect, radius, color);
+        self
+    }
+
+    /// 填充圆。
+    pub fn circle(mut self, center: Point, radius: usize, color: Color) -> Self {
+        self.buffer.fill_ellipse(center, radius, radius, color);
+        self
+    }
+
+    /// 绘制圆的边框。
+    pub fn circle_outline(
+        mut self,
+        center: Point,
+        radius: usize,
+        width: usize,
+        color: Color,
+    ) -> Self {
+        self.buffer
+            .draw_ellipse(center, radius, radius, width, color);
+        self
+    }
+
+    /// 填充椭圆。
+    pub fn ellipse(mut self, center: Point, rx: usize, ry: usize, color: Color) -> Self {
+        self.buffer.fill_ellipse(center, rx, ry, color);
+        self
+    }
+
+    /// 绘制圆弧（角度单位为度，0° 指向右侧，顺时针为正）。
+    pub fn arc(
+        mut self,
+        center: Point,
+        radius: usize,
+        start_deg: f32,
+        end_deg: f32,
+        width: usize,
+        color: Color,
+    ) -> Self {
+        self.buffer
+            .draw_arc(center, radius, start_deg, end_deg, width, color);
+        self
+    }
+
+    /// 绘制直线。
+    pub fn line(mut self, from: Point, to: Point, width: usize, color: Color) -> Self {
+        self.buffer.draw_line(from, to, width, color);
+        self
+    }
+
+    /// 绘制折线。
+    pub fn polyline(mut self, points: &[Point], width: usize, color: Color) -> Self {
+        self.buffer.draw_polyline(points, width, color);
+        self
+    }
+
+    /// 填充多边形。
+    pub fn polygon(mut self, points: &[Point], color: Color) -> Self {
+        self.buffer.fill_polygon(points, color);
+        self
+    }
+
+    /// 贴上精灵，`(x, y)` 为左上角。
+    pub fn sprite(mut self, sprite: &Sprite, x: i32, y: i32) -> Self {
+        self.buffer.blit(sprite, x, y);
+        self
+    }
+
+    /// 按选项（翻转、不透明度）贴上精灵。
+    pub fn sprite_with(mut self, sprite: &Sprite, x: i32, y: i32, options: &BlitOptions) -> Self {
+        self.buffer.blit_with(sprite, x, y, options);
+        self
+    }
+
+    /// 绘制单行文字，`(x, y)` 为文字行的左上角。
+    #[cfg(any(feature = "text", feature = "embedded-font"))]
+    pub fn text(
+        mut self,
+        text: &str,
+        x: i32,
+        y: i32,
+        font: &Font,
+        size: f32,
+        color: Color,
+    ) -> Self {
+        self.buffer.draw_text(x, y, text, font, size, color);
+        self
+    }
+
+    /// 绘制多行文字（按样式换行和对齐）。
+    #[cfg(any(feature = "text", feature = "embedded-font"))]
+    pub fn text_block(
+        mut self,
+        text: &str,
+        x: i32,
+        y: i32,
+        font: &Font,
+        style: &TextStyle,
+    ) -> Self {
+        self.buffer.draw_text_block(x, y, text, font, style);
+        self
+    }
+
+    /// 绘制表情（会先用背景色清屏，通常放在链的开头）。
+    pub fn face(mut self, expression: &Expression, style: &FaceStyle) -> Self {
+        self.buffer.draw_face(expression, style);
+        self
+    }
+
+    /// 直接操作缓冲区（链中没有的绘图操作）。
+    pub fn with<F: FnOnce(&mut ImageBuffer)>(mut self, draw: F) -> Self {
+        draw(&mut self.buffer);
+        self
+    }
+
+    /// 当前的画面。
+    pub fn buffer(&self) -> &ImageBuffer {
+        &self.buffer
+    }
+
+    /// 完成构建。
+    pub fn build(self) -> ImageBuffer {
+        self.buffer
+    }
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Frame> for ImageBuffer {
+    fn from(frame: Frame) -> Self {
+        frame.build()
+    }
+}
@@ -0,0 +1,241 @@
+//! ElectronBot 库的预转换帧缓存。
+//!
+//! [`FrameCache`] 按路径或内容哈希保存已经转换为设备 BGR 布局的整帧，
+//! 幻灯片和表情图再次显示时直接复制，跳过解码、缩放和转换。
+//! 超过容量预算时淘汰最久没有使用的帧。
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use crate::modules::constants::FRAME_SIZE;
+#[cfg(feature = "image")]
+use crate::modules::image::FitOptions;
+use crate::modules::image::ImageBuffer;
+
+/// 默认的容量预算：32 帧（约 5.3 MB）。
+pub const DEFAULT_FRAME_CACHE_BUDGET: usize = 32 * FRAME_SIZE;
+
+/// 缓存帧的键。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FrameKey {
+    /// 图片文件路径。
+    Path(PathBuf),
+    /// 编码数据或应用自定义内容的哈希。
+    Hash(u64),
+}
+
+impl FrameKey {
+    /// 以文件路径为键。
+    pub fn path<P: AsRef<Path>>(path: P) -> Self {
+        FrameKey::Path(path.as_ref().to_path_buf())
+    }
+
+    /// 以数据内容的哈希为键。
+    pub fn content(bytes: &[u8]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        FrameKey::Hash(hasher.finish())
+    }
+}
+
+/// 预转换帧的 LRU 缓存。
+#[derive(Debug, Clone)]
+pub struct FrameCache {
+    budget: usize,
+    /// 缓存的帧使用的适配方式。
+    #[cfg(feature = "image")]
+    fit: FitOptions,
+    frames: HashMap<FrameKey, Box<[u8; FRAME_SIZE]>>,
+    /// 从最久没有使用到最近使用。
+    order: VecDeque<FrameKey>,
+    hits: u64,
+    misses: u64,
+}
+
+impl FrameCache {
+    /// 创建容量预算为 `budget` 字节的缓存，每帧占 [`FRAME_SIZE`] 字节。
+    pub fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            #[cfg(feature = "image")]
+            fit: FitOptions::default(),
+            frames: HashMap::new(),
+            order: VecDeque::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// 容量预算（字节）。
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// 修改容量预算，超出时淘汰最久没有使用的帧。
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.evict(0);
+    }
+
+    /// 缓存的帧占用的字节数。
+    pub fn used(&self) -> usize {
+        self.frames.len() * FRAME_SIZE
+    }
+
+    /// 缓存的帧数。
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// 是否没有缓存的帧。
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// 是否缓存了 `key`（不更新使用顺序）。
+    pub fn contains(&self, key: &FrameKey) -> bool {
+        self.frames.contains_key(key)
+    }
+
+    /// 命中次数。
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// 未命中次数。
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// 取出缓存的帧并标记为最近使用。
+    pub fn get(&mut self, key: &FrameKey) -> Option<&[u8; FRAME_SIZE]> {
+        if !self.frames.contains_key(key) {
+            return None;
+        }
+        self.touch(key);
+        self.frames.get(key).map(|frame| &**frame)
+    }
+
+    /// 缓存一帧，超出预算时先淘汰最久没有使用的帧；预算不足一帧时不缓存。
+    pub fn insert(&mut self, key: FrameKey, frame: &[u8; FRAME_SIZE]) {
+        if self.frames.contains_key(&key) {
+            self.touch(&key);
+            if let Some(cached) = self.frames.get_mut(&key) {
+                cached.copy_from_slice(frame);
+            }
+            return;
+        }
+        if self.budget < FRAME_SIZE {
+            return;
+        }
+        self.evict(FRAME_SIZE);
+        let frame: Box<[u8; FRAME_SIZE]> = frame
+            .to_vec()
+            .into_boxed_slice()
+            .try_into()
+            .expect("长度为一帧");
+        self.frames.insert(key.clone(), frame);
+        self.order.push_back(key);
+    }
+
+    /// 移除 `key`，返回是否缓存过。
+    pub fn remove(&mut self, key: &FrameKey) -> bool {
+        self.order.retain(|k| k != key);
+        self.frames.remove(key).is_some()
+    }
+
+    /// 清空缓存（命中统计保留）。
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.order.clear();
+    }
+
+    /// 把 `key` 对应的帧复制到 `target`；没有缓存时用 `render` 生成到 `target` 并缓存。
+    ///
+    /// 返回是否命中，`render` 失败时不缓存。
+    pub fn show_with<F>(
+        &mut self,
+        key: FrameKey,
+        target: &mut ImageBuffer,
+        render: F,
+    ) -> Result<bool, String>
+    where
+        F: FnOnce(&mut ImageBuffer) -> Result<(), String>,
+    {
+        if let Some(frame) = self.get(&key) {
+            target.load_frame(frame);
+            self.hits += 1;
+            return Ok(true);
+        }
+        self.misses += 1;
+        render(target)?;
+        self.insert(key, target.frame_mut());
+        Ok(false)
+    }
+
+    /// 按适配方式 `fit` 加载图片文件到 `target`，以路径为键。
+    ///
+    /// 缓存的帧按上一次的适配方式转换，`fit` 变化时先清空缓存。
+    #[cfg(feature = "image")]
+    pub fn load_file<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        fit: &FitOptions,
+        target: &mut ImageBuffer,
+    ) -> Result<bool, String> {
+        self.use_fit(fit);
+        let path = path.as_ref();
+        self.show_with(FrameKey::path(path), target, |buffer| {
+            buffer.load_from_file_with(path, fit)
+        })
+    }
+
+    /// 按适配方式 `fit` 加载 JPEG/PNG 等编码数据到 `target`，以内容哈希为键。
+    ///
+    /// `fit` 变化时先清空缓存。
+    #[cfg(feature = "image")]
+    pub fn load_bytes(
+        &mut self,
+        bytes: &[u8],
+        fit: &FitOptions,
+        target: &mut ImageBuffer,
+    ) -> Result<bool, String> {
+        self.use_fit(fit);
+        self.show_with(FrameKey::content(bytes), target, |buffer| {
+            buffer.load_image_bytes_with(bytes, fit)
+        })
+    }
+
+    #[cfg(feature = "image")]
+    fn use_fit(&mut self, fit: &FitOptions) {
+        if self.fit != *fit {
+            self.clear();
+            self.fit = *fit;
+        }
+    }
+
+    fn touch(&mut self, key: &FrameKey) {
+        if let Some(index) = self.order.iter().position(|k| k == key) {
+            if let Some(key) = self.order.remove(index) {
+                self.order.push_back(key);
+            }
+        }
+    }
+
+    /// 淘汰最久没有使用的帧，直到还能放下 `extra` 字节。
+    fn evict(&mut self, extra: usize) {
+        while !self.order.is_empty() && self.used() + extra > self.budget {
+            if let Some(key) = self.order.pop_front() {
+                self.frames.remove(&key);
+            }
+        }
+    }
+}
+
+impl Default for FrameCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_FRAME_CACHE_BUDGET)
+    }
+}
@@ -0,0 +1,340 @@
+//! ElectronBot 库的手柄遥控。
+//!
+//! [`GamepadTeleop`] 通过 gilrs 读取手柄，按 [`GamepadMapping`] 把摇杆映射到关节、
+//! 把按键映射到表情和动作，驱动一个 [`ServoSource`]。摇杆经过死区和响应曲线处理，
+//! 可以直接对应角度（松开回中），也可以按速度点动（松开保持）。
+
+use std::time::Duration;
+
+use gilrs::{EventType, GamepadId, Gilrs};
+
+pub use gilrs::{Axis, Button};
+
+use crate::modules::constants::{JOINT_BODY, JOINT_HEAD};
+use crate::modules::face::Expression;
+use crate::modules::image::ImageBuffer;
+use crate::modules::servo::ServoSource;
+use crate::modules::source::{FrameSource, DEFAULT_INTERVAL};
+use crate::modules::types::JointAngles;
+
+/// 摇杆的响应曲线（作用于去掉死区后的 0 到 1 的输入）。
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ResponseCurve {
+    /// 线性（默认）。
+    #[default]
+    Linear,
+    /// 平方，小幅度更精细。
+    Quadratic,
+    /// 立方，小幅度非常精细。
+    Cubic,
+    /// 线性和立方混合，参数为立方所占比例（0 到 1）。
+    Expo(f32),
+}
+
+impl ResponseCurve {
+    /// 对 0 到 1 的输入应用曲线。
+    pub fn apply(self, x: f32) -> f32 {
+        match self {
+            ResponseCurve::Linear => x,
+            ResponseCurve::Quadratic => x * x,
+            ResponseCurve::Cubic => x * x * x,
+            ResponseCurve::Expo(k) => {
+                let k = k.clamp(0.0, 1.0);
+                (1.0 - k) * x + k * x * x * x
+            }
+        }
+    }
+}
+
+/// 摇杆控制关节的方式。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AxisMode {
+    /// 摇杆位置对应关节角度范围，松开时回到中点。
+    Position,
+    /// 摇杆控制转动速度（度每秒），松开时保持当前角度。
+    Rate(f32),
+}
+
+/// 摇杆到关节的映射。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisBinding {
+    /// 关节索引（0-5）。
+    pub joint: usize,
+    /// 控制方式。
+    pub mode: AxisMode,
+    /// 死区（0 到 1），小于死区的输入视为 0。
+    pub deadzone: f32,
+    /// 响应曲线。
+    pub curve: ResponseCurve,
+    /// 是否反向。
+    pub invert: bool,
+}
+
+impl AxisBinding {
+    /// 摇杆位置对应关节角度（死区 0.1，线性）。
+    pub fn position(joint: usize) -> Self {
+        Self {
+            joint,
+            mode: AxisMode::Position,
+            deadzone: 0.1,
+            curve: ResponseCurve::Linear,
+            invert: false,
+        }
+    }
+
+    /// 摇杆控制关节转动速度（度每秒）。
+    pub fn rate(joint: usize, degrees_per_second: f32) -> Self {
+        Self {
+            mode: AxisMode::Rate(degrees_per_second),
+            ..Self::position(joint)
+        }
+    }
+
+    /// 设置死区。
+    pub fn deadzone(mut self, deadzone: f32) -> Self {
+        self.deadzone = deadzone.clamp(0.0, 0.99);
+        self
+    }
+
+    /// 设置响应曲线。
+    pub fn curve(mut self, curve: ResponseCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// 反向。
+    pub fn inverted(mut self) -> Self {
+        self.invert = !self.invert;
+        self
+    }
+
+    /// 摇杆输入（-1 到 1）经过死区、曲线和反向后的值（-1 到 1）。
+    pub fn response(&self, value: f32) -> f32 {
+        let magnitude = value.abs().min(1.0);
+        if magnitude <= self.deadzone {
+            return 0.0;
+        }
+        let x = (magnitude - self.deadzone) / (1.0 - self.deadzone);
+        let shaped = self.curve.apply(x).copysign(value);
+        if self.invert {
+            -shaped
+        } else {
+            shaped
+        }
+    }
+
+    /// 把摇杆输入作用到舵机目标上。
+    fn apply(&self, servo: &mut ServoSource, value: f32, dt: Duration) {
+        let response = self.response(value);
+        match self.mode {
+            AxisMode::Position => {
+                if let Some((min, max)) = servo.limit(self.joint) {
+                    let center = (min + max) / 2.0;
+                    servo.set_target(self.joint, center + response * (max - min) / 2.0);
+                }
+            }
+            AxisMode::Rate(speed) => {
+                if response != 0.0 {
+                    servo.jog(self.joint, response * speed * dt.as_secs_f32());
+                }
+            }
+        }
+    }
+}
+
+/// 按键动作。
+#[derive(Debug, Clone, PartialEq)]
+pub enum ButtonAction {
+    /// 切换表情。
+    Expression(Expression),
+    /// 所有关节回到 0 度。
+    ResetPose,
+    /// 切换到指定姿态。
+    Pose(JointAngles),
+    /// 使能或释放舵机。
+    ToggleServos,
+}
+
+/// 手柄映射：摇杆到关节，按键到动作。
+#[derive(Debug, Clone, PartialEq)]
+pub struct GamepadMapping {
+    axes: Vec<(Axis, AxisBinding)>,
+    buttons: Vec<(Button, ButtonAction)>,
+}
+
+impl Default for GamepadMapping {
+    /// 默认映射：左摇杆控制头部俯仰和身体旋转，右摇杆按速度控制双臂，
+    /// A/B/X/Y 切换开心、难过、生气、惊讶，Select 回到初始姿态，Start 使能或释放舵机。
+    fn default() -> Self {
+        Self::new()
+            .axis(Axis::LeftStickY, AxisBinding::position(JOINT_HEAD))
+            .axis(
+                Axis::LeftStickX,
+                AxisBinding::position(JOINT_BODY).inverted(),
+            )
+            .axis(Axis::RightStickX, AxisBinding::rate(1, 60.0))
+            .axis(Axis::RightStickX, AxisBinding::rate(3, 60.0).inverted())
+            .axis(Axis::RightStickY, AxisBinding::rate(2, 90.0))
+            .axis(Axis::RightStickY, AxisBinding::rate(4, 90.0))
+            .button(Button::South, ButtonAction::Expression(Expression::HAPPY))
+            .button(Button::East, ButtonAction::Expression(Expression::SAD))
+            .button(Button::West, ButtonAction::Expression(Expression::ANGRY))
+            .button(
+                Button::North,
+                ButtonAction::Expression(Expression::SURPRISED),
+            )
+            .button(Button::Select, ButtonAction::ResetPose)
+            .button(Button::Start, ButtonAction::ToggleServos)
+    }
+}
+
+impl GamepadMapping {
+    /// 创建空映射。
+    pub fn new() -> Self {
+        Self {
+            axes: Vec::new(),
+            buttons: Vec::new(),
+        }
+    }
+
+    /// 添加摇杆映射，同一个摇杆可以控制多个关节。
+    pub fn axis(mut self, axis: Axis, binding: AxisBinding) -> Self {
+        self.axes.push((axis, binding));
+        self
+    }
+
+    /// 添加按键映射，替换该按键原来的动作。
+    pub fn button(mut self, button: Button, action: ButtonAction) -> Self {
+        self.buttons.retain(|(b, _)| *b != button);
+        self.buttons.push((button, action));
+        self
+    }
+
+    /// 按键对应的动作。
+    pub fn action(&self, button: Button) -> Option<&ButtonAction> {
+        self.buttons
+            .iter()
+            .find(|(b, _)| *b == button)
+            .map(|(_, action)| action)
+    }
+
+    /// 把各摇杆的当前值作用到舵机目标上。
+    pub fn apply_axes(&self, servo: &mut ServoSource, value: impl Fn(Axis) -> f32, dt: Duration) {
+        for (axis, binding) in &self.axes {
+            binding.apply(servo, value(*axis), dt);
+        }
+    }
+
+    /// 执行按键动作，返回按键是否有映射。
+    pub fn press(&self, servo: &mut ServoSource, button: Button) -> bool {
+        let Some(action) = self.action(button) else {
+            return false;
+        };
+        match action {
+            ButtonAction::Expression(expression) => servo.set_expression(*expression),
+            ButtonAction::ResetPose => servo.set_pose(&JointAngles::new()),
+            ButtonAction::Pose(pose) => servo.set_pose(pose),
+            ButtonAction::ToggleServos => servo.set_enabled(!servo.is_enabled()),
+        }
+        true
+    }
+}
+
+/// 手柄遥控帧源：每帧读取手柄，更新舵机目标和表情。
+///
+/// 使用最近有输入的手柄；没有手柄时保持当前状态。
+#[derive(Debug)]
+pub struct GamepadTeleop {
+    gilrs: Gilrs,
+    mapping: GamepadMapping,
+    servo: ServoSource,
+    active: Option<GamepadId>,
+}
+
+impl GamepadTeleop {
+    /// 使用默认映射创建。
+    pub fn new() -> Result<Self, String> {
+        Self::with_mapping(GamepadMapping::default())
+    }
+
+    /// 使用指定映射创建。
+    pub fn with_mapping(mapping: GamepadMapping) -> Result<Self, String> {
+        let gilrs = Gilrs::new().map_err(|e| format!("初始化手柄失败: {}", e))?;
+        let active = gilrs.gamepads().next().map(|(id, _)| id);
+        #[cfg(feature = "logging")]
+        for (_id, gamepad) in gilrs.gamepads() {
+            log::info!("Gamepad found: {}", gamepad.name());
+        }
+        Ok(Self {
+            gilrs,
+            mapping,
+            servo: ServoSource::new(),
+            active,
+        })
+    }
+
+    /// 设置舵机帧源（角度范围、最大速度、表情样式）。
+    pub fn servo(mut self, servo: ServoSource) -> Self {
+        self.servo = servo;
+        self
+    }
+
+    /// 舵机帧源。
+    pub fn servo_mut(&mut self) -> &mut ServoSource {
+        &mut self.servo
+    }
+
+    /// 手柄映射。
+    pub fn mapping_mut(&mut self) -> &mut GamepadMapping {
+        &mut self.mapping
+    }
+
+    /// 当前使用的手柄名称。
+    pub fn gamepad_name(&self) -> Option<String> {
+        let id = self.active?;
+        self.gilrs
+            .connected_gamepad(id)
+            .map(|g| g.name().to_string())
+    }
+
+    /// 读取手柄事件并更新舵机目标。
+    pub fn update(&mut self, dt: Duration) {
+        while let Some(event) = self.gilrs.next_event() {
+            match event.event {
+                EventType::ButtonPressed(button, _) => {
+                    self.active = Some(event.id);
+                    self.mapping.press(&mut self.servo, button);
+                }
+                EventType::AxisChanged(..) => self.active = Some(event.id),
+                EventType::Connected if self.active.is_none() => self.active = Some(event.id),
+                EventType::Disconnected if self.active == Some(event.id) => {
+                    #[cfg(feature = "logging")]
+                    log::warn!("Active gamepad disconnected");
+                    self.active = self.gilrs.gamepads().next().map(|(id, _)| id);
+                }
+                _ => {}
+            }
+        }
+
+        let Some(gamepad) = self.active.and_then(|id| self.gilrs.connected_gamepad(id)) else {
+            return;
+        };
+        self.mapping
+            .apply_axes(&mut self.servo, |axis| gamepad.value(axis), dt);
+    }
+}
+
+impl FrameSource for GamepadTeleop {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        self.update(DEFAULT_INTERVAL);
+        self.servo.next_frame(buffer)
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        self.servo.frame_interval()
+    }
+
+    fn joint_angles(&self) -> Option<JointAngles> {
+        self.servo.joint_angles()
+    }
+}
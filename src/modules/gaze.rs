@@ -0,0 +1,212 @@
+//! ElectronBot 库的注视控制。
+//!
+//! [`Gaze`] 把注视目标分解为眼球转动和头部转动：目标在眼球范围内时只移动瞳孔，
+//! 超出部分由头部俯仰和身体旋转承担。头部转动的过程中眼球反向补偿，
+//! 视线始终保持在目标上，几帧内协调完成。
+
+use std::time::Duration;
+
+use crate::modules::source::DEFAULT_INTERVAL;
+
+/// 注视目标。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GazeTarget {
+    /// 相对机器人正前方的方向（度），`yaw` 向右为正，`pitch` 向上为正。
+    Direction {
+        /// 水平角。
+        yaw: f32,
+        /// 俯仰角。
+        pitch: f32,
+    },
+    /// 机器人坐标系中的点（x 向右、y 向上、z 向前，单位任意）。
+    World {
+        /// 水平位置。
+        x: f32,
+        /// 垂直位置。
+        y: f32,
+        /// 前方距离。
+        z: f32,
+    },
+    /// 视野中的位置（-1 到 1），例如摄像头画面中检测到的人脸，按视场角换算。
+    Screen {
+        /// 水平位置（-1 最左，1 最右）。
+        x: f32,
+        /// 垂直位置（-1 最上，1 最下）。
+        y: f32,
+    },
+}
+
+impl GazeTarget {
+    /// 正前方。
+    pub const FORWARD: Self = Self::Direction {
+        yaw: 0.0,
+        pitch: 0.0,
+    };
+}
+
+/// 注视控制参数。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GazeOptions {
+    /// 眼球可覆盖的水平角和俯仰角（度）。
+    pub eye_range: (f32, f32),
+    /// 身体旋转的最大角度（度），0 表示不转身。
+    pub body_limit: f32,
+    /// 头部俯仰的最大角度（度），0 表示不抬头低头。
+    pub head_limit: f32,
+    /// `Screen` 目标对应的视场角（水平、垂直，度）。
+    pub field_of_view: (f32, f32),
+    /// 眼球跟随速度（每帧靠近剩余距离的比例）。
+    pub eye_speed: f32,
+    /// 头部跟随速度（每帧靠近剩余距离的比例）。
+    pub head_speed: f32,
+}
+
+impl Default for GazeOptions {
+    fn default() -> Self {
+        Self {
+            eye_range: (20.0, 15.0),
+            body_limit: 60.0,
+            head_limit: 15.0,
+            field_of_view: (60.0, 45.0),
+            eye_speed: 0.5,
+            head_speed: 0.12,
+        }
+    }
+}
+
+impl GazeOptions {
+    /// 只移动眼球，不控制舵机。
+    pub fn eyes_only() -> Self {
+        Self {
+            body_limit: 0.0,
+            head_limit: 0.0,
+            ..Self::default()
+        }
+    }
+}
+
+/// 注视控制器。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gaze {
+    options: GazeOptions,
+    /// 目标方向（yaw, pitch）。
+    target: (f32, f32),
+    /// 眼球方向（相对头部）。
+    eyes: (f32, f32),
+    /// 头部方向（身体 yaw, 头部 pitch）。
+    head: (f32, f32),
+}
+
+impl Default for Gaze {
+    fn default() -> Self {
+        Self::new(GazeOptions::default())
+    }
+}
+
+impl Gaze {
+    /// 创建注视正前方的控制器。
+    pub fn new(options: GazeOptions) -> Self {
+        Self {
+            options,
+            target: (0.0, 0.0),
+            eyes: (0.0, 0.0),
+            head: (0.0, 0.0),
+        }
+    }
+
+    /// 控制参数。
+    pub fn options(&self) -> &GazeOptions {
+        &self.options
+    }
+
+    /// 设置注视目标。
+    pub fn look_at(&mut self, target: GazeTarget) {
+        self.target = self.direction(target);
+    }
+
+    /// 目标方向（yaw, pitch，度）。
+    pub fn target(&self) -> (f32, f32) {
+        self.target
+    }
+
+    /// 把目标换算为方向（yaw, pitch，度）。
+    fn direction(&self, target: GazeTarget) -> (f32, f32) {
+        match target {
+            GazeTarget::Direction { yaw, pitch } => (yaw, pitch),
+            GazeTarget::World { x, y, z } => {
+                let yaw = x.atan2(z).to_degrees();
+                let pitch = y.atan2((x * x + z * z).sqrt()).to_degrees();
+                (yaw, pitch)
+            }
+            GazeTarget::Screen { x, y } => {
+                let (fov_x, fov_y) = self.options.field_of_view;
+                (x * fov_x / 2.0, -y * fov_y / 2.0)
+            }
+        }
+    }
+
+    /// 头部的目标方向：眼球覆盖不到的部分，按舵机限位截断。
+    fn head_target(&self) -> (f32, f32) {
+        let (range_yaw, range_pitch) = self.options.eye_range;
+        let excess = |v: f32, range: f32| v - v.clamp(-range, range);
+        (
+            excess(self.target.0, range_yaw)
+                .clamp(-self.options.body_limit, self.options.body_limit),
+            excess(self.target.1, range_pitch)
+                .clamp(-self.options.head_limit, self.options.head_limit),
+        )
+    }
+
+    /// 头部位于 `head` 时眼球的目标方向。
+    fn eye_target(&self, head: (f32, f32)) -> (f32, f32) {
+        let (range_yaw, range_pitch) = self.options.eye_range;
+        (
+            (self.target.0 - head.0).clamp(-range_yaw, range_yaw),
+            (self.target.1 - head.1).clamp(-range_pitch, range_pitch),
+        )
+    }
+
+    /// 推进时间：眼球快速跟随，头部慢慢转向，眼球同时反向补偿。
+    pub fn advance(&mut self, dt: Duration) {
+        let frames = dt.as_secs_f32() / DEFAULT_INTERVAL.as_secs_f32();
+        let rate = |speed: f32| 1.0 - (1.0 - speed.clamp(0.0, 1.0)).powf(frames);
+
+        let head_target = self.head_target();
+        let head_rate = rate(self.options.head_speed);
+        self.head.0 += (head_target.0 - self.head.0) * head_rate;
+        self.head.1 += (head_target.1 - self.head.1) * head_rate;
+
+        let eye_target = self.eye_target(self.head);
+        let eye_rate = rate(self.options.eye_speed);
+        self.eyes.0 += (eye_target.0 - self.eyes.0) * eye_rate;
+        self.eyes.1 += (eye_target.1 - self.eyes.1) * eye_rate;
+    }
+
+    /// 视线是否已经稳定在目标上（误差小于 0.5 度）。
+    pub fn is_settled(&self) -> bool {
+        let head_target = self.head_target();
+        let eye_target = self.eye_target(head_target);
+        let near =
+            |a: (f32, f32), b: (f32, f32)| (a.0 - b.0).abs() < 0.5 && (a.1 - b.1).abs() < 0.5;
+        near(self.head, head_target) && near(self.eyes, eye_target)
+    }
+
+    /// 瞳孔位置（-1 到 1，与 `Expression` 的瞳孔坐标一致）。
+    pub fn pupil(&self) -> (f32, f32) {
+        let (range_yaw, range_pitch) = self.options.eye_range;
+        (
+            (self.eyes.0 / range_yaw).clamp(-1.0, 1.0),
+            (-self.eyes.1 / range_pitch).clamp(-1.0, 1.0),
+        )
+    }
+
+    /// 头部转动角度：（身体旋转, 头部俯仰），单位度。
+    pub fn head(&self) -> (f32, f32) {
+        self.head
+    }
+
+    /// 是否会控制舵机。
+    pub fn moves_head(&self) -> bool {
+        self.options.body_limit > 0.0 || self.options.head_limit > 0.0
+    }
+}
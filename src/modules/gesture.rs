@@ -0,0 +1,267 @@
+//! ElectronBot 库的预设动作。
+//!
+//! [`Gesture`] 是一串关节关键帧，播放时在关键帧之间平滑插值，结束后回到起始姿态。
+//! 内置挥手、点头、摇头等动作，可以通过名称查找。
+
+use std::time::Duration;
+
+use crate::modules::constants::{JOINT_BODY, JOINT_HEAD};
+use crate::modules::image::ImageBuffer;
+use crate::modules::json::{self, Value};
+use crate::modules::source::{FrameSource, DEFAULT_INTERVAL};
+use crate::modules::types::JointAngles;
+
+/// 内置动作的名称。
+pub const GESTURE_NAMES: [&str; 5] = ["wave", "nod", "shake", "shrug", "cheer"];
+
+/// 关键帧：经过 `duration` 到达 `pose`。
+#[derive(Debug, Clone, PartialEq)]
+struct Keyframe {
+    pose: JointAngles,
+    duration: Duration,
+}
+
+/// 关节动作，只驱动舵机，不修改屏幕内容。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gesture {
+    name: String,
+    start: JointAngles,
+    keyframes: Vec<Keyframe>,
+    elapsed: Duration,
+    angles: JointAngles,
+}
+
+impl Gesture {
+    /// 创建从 `start` 姿态开始的空动作。
+    pub fn new(name: &str, start: JointAngles) -> Self {
+        Self {
+            name: name.to_string(),
+            angles: start.clone(),
+            start,
+            keyframes: Vec::new(),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// 添加关键帧：经过 `duration` 平滑移动到 `pose`。
+    pub fn then(mut self, pose: [f32; 6], duration: Duration) -> Self {
+        self.keyframes.push(Keyframe {
+            pose: JointAngles(pose),
+            duration,
+        });
+        self
+    }
+
+    /// 添加关键帧：回到起始姿态。
+    pub fn then_return(self, duration: Duration) -> Self {
+        let start = self.start.0;
+        self.then(start, duration)
+    }
+
+    /// 按名称查找内置动作（从 0 度姿态开始）。
+    pub fn named(name: &str) -> Option<Self> {
+        let ms = Duration::from_millis;
+        let pose = |joints: &[(usize, f32)]| {
+            let mut pose = [0.0; 6];
+            for &(joint, angle) in joints {
+                pose[joint] = angle;
+            }
+            pose
+        };
+        let gesture = Self::new(name, JointAngles::new());
+        Some(match name {
+            "wave" => gesture
+                .then(pose(&[(3, 20.0), (4, 60.0)]), ms(400))
+                .then(pose(&[(3, 5.0), (4, 60.0)]), ms(250))
+                .then(pose(&[(3, 25.0), (4, 60.0)]), ms(250))
+                .then(pose(&[(3, 5.0), (4, 60.0)]), ms(250))
+                .then_return(ms(400)),
+            "nod" => gesture
+                .then(pose(&[(JOINT_HEAD, -12.0)]), ms(250))
+                .then(pose(&[(JOINT_HEAD, 8.0)]), ms(250))
+                .then(pose(&[(JOINT_HEAD, -12.0)]), ms(250))
+                .then_return(ms(250)),
+            "shake" => gesture
+                .then(pose(&[(JOINT_BODY, 20.0)]), ms(250))
+                .then(pose(&[(JOINT_BODY, -20.0)]), ms(400))
+                .then(pose(&[(JOINT_BODY, 20.0)]), ms(400))
+                .then_return(ms(250)),
+            "shrug" => gesture
+                .then(pose(&[(JOINT_HEAD, 5.0), (1, 20.0), (3, 20.0)]), ms(300))
+                .then(pose(&[(JOINT_HEAD, 5.0), (1, 20.0), (3, 20.0)]), ms(400))
+                .then_return(ms(300)),
+            "cheer" => gesture
+                .then(pose(&[(JOINT_HEAD, 10.0), (2, 50.0), (4, 50.0)]), ms(300))
+                .then(pose(&[(JOINT_HEAD, 10.0), (2, 35.0), (4, 35.0)]), ms(200))
+                .then(pose(&[(JOINT_HEAD, 10.0), (2, 50.0), (4, 50.0)]), ms(200))
+                .then_return(ms(400)),
+            _ => return None,
+        })
+    }
+
+    /// 从 JSON 动作文件创建。
+    ///
+    /// 格式为 `{"name": "hello", "start": [0, 0, 0, 0, 0, 0], "keyframes": [{"pose": [...], "ms": 400}]}`，
+    /// `name` 和 `start` 可以省略（默认为空名称和 0 度姿态）。
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let root = json::parse(text)?;
+        let name = match root.get("name") {
+            Some(name) => name.as_str().ok_or("name 必须是字符串")?,
+            None => "",
+        };
+        let start = match root.get("start") {
+            Some(start) => json_angles(start).map_err(|e| format!("start: {}", e))?,
+            None => [0.0; 6],
+        };
+        let keyframes = root
+            .get("keyframes")
+            .and_then(Value::as_array)
+            .ok_or("缺少 keyframes 数组")?;
+
+        let mut gesture = Self::new(name, JointAngles(start));
+        for (i, keyframe) in keyframes.iter().enumerate() {
+            let pose = keyframe
+                .get("pose")
+                .ok_or_else(|| format!("第 {} 个关键帧缺少 pose", i + 1))
+                .and_then(|pose| {
+                    json_angles(pose).map_err(|e| format!("第 {} 个关键帧: {}", i + 1, e))
+                })?;
+            let ms = keyframe
+                .get("ms")
+                .and_then(Value::as_f64)
+                .filter(|ms| ms.is_finite() && *ms >= 0.0)
+                .ok_or_else(|| format!("第 {} 个关键帧需要非负的 ms", i + 1))?;
+            gesture = gesture.then(pose, Duration::from_secs_f64(ms / 1000.0));
+        }
+        Ok(gesture)
+    }
+
+    /// 转换为 JSON 动作文件，格式见 [`Gesture::from_json`]。
+    pub fn to_json(&self) -> String {
+        let mut out = format!(
+            "{{\n  \"name\": \"{}\",\n  \"start\": {},\n  \"keyframes\": [",
+            json::escape(&self.name),
+            angles_json(&self.start.0)
+        );
+        for (i, keyframe) in self.keyframes.iter().enumerate() {
+            out.push_str(if i == 0 { "\n" } else { ",\n" });
+            out.push_str(&format!(
+                "    {{\"pose\": {}, \"ms\": {}}}",
+                angles_json(&keyframe.pose.0),
+                keyframe.duration.as_secs_f64() * 1000.0
+            ));
+        }
+        out.push_str("\n  ]\n}\n");
+        out
+    }
+
+    /// 从 JSON 动作文件加载。
+    pub fn load_from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("读取动作文件失败: {}", e))?;
+        Self::from_json(&text)
+    }
+
+    /// 保存为 JSON 动作文件。
+    pub fn save_to_file<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), String> {
+        std::fs::write(path, self.to_json()).map_err(|e| format!("保存动作文件失败: {}", e))
+    }
+
+    /// 关键帧数量。
+    pub fn len(&self) -> usize {
+        self.keyframes.len()
+    }
+
+    /// 是否没有关键帧。
+    pub fn is_empty(&self) -> bool {
+        self.keyframes.is_empty()
+    }
+
+    /// 动作名称。
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 总时长。
+    pub fn duration(&self) -> Duration {
+        self.keyframes.iter().map(|k| k.duration).sum()
+    }
+
+    /// 是否已经播放完。
+    pub fn is_finished(&self) -> bool {
+        self.elapsed > self.duration()
+    }
+
+    /// 从头开始播放。
+    pub fn restart(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.angles = self.start.clone();
+    }
+
+    /// `elapsed` 时刻的姿态。
+    pub fn pose_at(&self, elapsed: Duration) -> JointAngles {
+        let mut from = &self.start;
+        let mut begin = Duration::ZERO;
+        for keyframe in &self.keyframes {
+            let end = begin + keyframe.duration;
+            if elapsed < end {
+                let t = (elapsed - begin).as_secs_f32() / keyframe.duration.as_secs_f32();
+                let t = t * t * (3.0 - 2.0 * t);
+                let mut pose = JointAngles::new();
+                for (i, angle) in pose.0.iter_mut().enumerate() {
+                    *angle = from.0[i] + (keyframe.pose.0[i] - from.0[i]) * t;
+                }
+                return pose;
+            }
+            from = &keyframe.pose;
+            begin = end;
+        }
+        from.clone()
+    }
+}
+
+/// 解析 6 个角度的 JSON 数组。
+fn json_angles(value: &Value) -> Result<[f32; 6], String> {
+    let items = value.as_array().ok_or("需要 6 个角度的数组")?;
+    if items.len() != 6 {
+        return Err(format!("需要 6 个角度，收到 {} 个", items.len()));
+    }
+    let mut angles = [0.0; 6];
+    for (angle, item) in angles.iter_mut().zip(items) {
+        *angle = item.as_f64().ok_or("角度必须是数字")? as f32;
+    }
+    Ok(angles)
+}
+
+/// 角度数组的 JSON，非有限值写为 0。
+fn angles_json(angles: &[f32; 6]) -> String {
+    let values: Vec<String> = angles
+        .iter()
+        .map(|a| {
+            if a.is_finite() {
+                a.to_string()
+            } else {
+                "0".to_string()
+            }
+        })
+        .collect();
+    format!("[{}]", values.join(", "))
+}
+
+impl FrameSource for Gesture {
+    fn next_frame(&mut self, _buffer: &mut ImageBuffer) -> bool {
+        if self.elapsed > self.duration() {
+            return false;
+        }
+        self.angles = self.pose_at(self.elapsed);
+        self.elapsed += DEFAULT_INTERVAL;
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        Some(DEFAULT_INTERVAL)
+    }
+
+    fn joint_angles(&self) -> Option<JointAngles> {
+        Some(self.angles.clone())
+    }
+}
@@ -0,0 +1,649 @@
+//! ElectronBot 库的线程安全句柄。
+//!
+//! [`BotHandle`] 把 `ElectronBot` 交给一个后台同步线程，句柄可以廉价克隆并分发给多个线程：
+//! 界面线程更新画面、控制线程设置关节，各自只在写入暂存状态时短暂加锁，
+//! 不会被正在进行的 USB 同步阻塞。同步线程在有新内容时发送，
+//! 设置了保活间隔时还会定期同步以读取机器人上报的角度。
+//!
+//! 同步线程还可以播放动作（[`BotHandle::play_gesture`]）：每个动作返回一个
+//! [`GesturePlayback`]，可以阻塞等待、轮询或作为 `Future` 等待动作结束，也可以取消；
+//! 新动作可以打断当前动作，或排在当前动作之后播放。
+//!
+//! 所有句柄都被丢弃（或调用 [`BotHandle::stop`]）后同步线程退出。
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::modules::error::BotError;
+use crate::modules::gesture::Gesture;
+use crate::modules::image::ImageBuffer;
+use crate::modules::source::DEFAULT_INTERVAL;
+use crate::modules::sync::{CancellationToken, EmergencyStop};
+use crate::modules::time::Instant;
+use crate::modules::types::JointAngles;
+use crate::ElectronBot;
+
+/// 句柄和同步线程共享的状态。
+struct State {
+    /// 下一次同步要发送的画面。
+    image: ImageBuffer,
+    image_dirty: bool,
+    joints: Option<([f32; 6], bool)>,
+    sync_requested: bool,
+    keepalive: Option<Duration>,
+    stop: bool,
+    /// 修改次数，用于 `flush` 判断修改是否已经发送。
+    changes: u64,
+    /// 已经发送的修改次数。
+    sent_changes: u64,
+    /// 最近一次同步失败时的修改次数，等待这些修改的 `flush` 返回 `last_error`。
+    failed_changes: u64,
+    reported: JointAngles,
+    frames: u64,
+    connected: bool,
+    last_error: Option<String>,
+    /// 正在播放的动作。
+    gesture: Option<ActiveGesture>,
+    /// 排队等待播放的动作。
+    gesture_queue: VecDeque<GestureJob>,
+}
+
+impl State {
+    /// 距离下一帧动作还要等多久，没有动作时为 `None`。
+    fn gesture_wait(&self) -> Option<Duration> {
+        match &self.gesture {
+            Some(active) => Some(active.next_frame.map_or(Duration::ZERO, |at| {
+                at.saturating_duration_since(Instant::now())
+            })),
+            None if !self.gesture_queue.is_empty() => Some(Duration::ZERO),
+            None => None,
+        }
+    }
+
+    /// 把当前动作的姿态写入暂存的关节角度，动作播放完时取出它。
+    fn advance_gesture(&mut self, now: Instant) -> Option<GestureJob> {
+        if self.gesture.is_none() {
+            let job = self.gesture_queue.pop_front()?;
+            #[cfg(feature = "logging")]
+            log::debug!("Gesture '{}' started", job.gesture.name());
+            self.gesture = Some(ActiveGesture {
+                job,
+                started: now,
+                next_frame: None,
+            });
+        }
+        let active = self.gesture.as_mut()?;
+        let elapsed = now.duration_since(active.started);
+        self.joints = Some((active.job.gesture.pose_at(elapsed).0, true));
+        active.next_frame = Some(now + DEFAULT_INTERVAL);
+        if elapsed >= active.job.gesture.duration() {
+            return self.gesture.take().map(|active| active.job);
+        }
+        None
+    }
+
+    /// 结束当前动作和排队的动作。
+    fn clear_gestures(&mut self, outcome: GestureOutcome) {
+        if let Some(active) = self.gesture.take() {
+            active.job.status.complete(outcome);
+        }
+        for job in self.gesture_queue.drain(..) {
+            job.status.complete(outcome);
+        }
+    }
+}
+
+struct Shared {
+    state: Mutex<State>,
+    /// 有新内容或需要停止时唤醒同步线程。
+    wake: Condvar,
+    /// 同步完成时唤醒等待的句柄。
+    synced: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // 持锁期间不会 panic，中毒时直接沿用内部数据
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+struct Inner {
+    shared: Arc<Shared>,
+    worker: Mutex<Option<JoinHandle<ElectronBot>>>,
+    cancel: CancellationToken,
+    estop: EmergencyStop,
+}
+
+impl Inner {
+    fn stop(&self) -> Option<ElectronBot> {
+        self.shared.lock().stop = true;
+        self.shared.wake.notify_all();
+        self.shared.synced.notify_all();
+        let worker = self
+            .worker
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()?;
+        // 中止正在进行的同步，取回后恢复原来的取消状态
+        let was_cancelled = self.cancel.is_cancelled();
+        self.cancel.cancel();
+        let bot = worker.join().ok();
+        if !was_cancelled {
+            self.cancel.reset();
+        }
+        bot
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// 新动作与当前动作的关系。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GestureMode {
+    /// 排在当前和已排队的动作之后（默认）。
+    #[default]
+    Queue,
+    /// 打断当前动作并清空队列，立即开始播放。
+    Interrupt,
+}
+
+/// 动作的结束方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GestureOutcome {
+    /// 完整播放完。
+    Finished,
+    /// 被 [`GesturePlayback::cancel`] 或 [`BotHandle::cancel_gestures`] 取消。
+    Cancelled,
+    /// 被以 [`GestureMode::Interrupt`] 播放的新动作打断。
+    Interrupted,
+    /// 同步线程已停止。
+    Stopped,
+    /// 被急停中止。
+    EmergencyStop,
+}
+
+/// 动作的完成状态。
+#[derive(Default)]
+struct PlaybackStatus {
+    state: Mutex<(Option<GestureOutcome>, Option<Waker>)>,
+    done: Condvar,
+}
+
+impl PlaybackStatus {
+    fn lock(&self) -> MutexGuard<'_, (Option<GestureOutcome>, Option<Waker>)> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 记录结束方式（只有第一次生效）并唤醒等待者。
+    fn complete(&self, outcome: GestureOutcome) {
+        let mut state = self.lock();
+        if state.0.is_some() {
+            return;
+        }
+        state.0 = Some(outcome);
+        let waker = state.1.take();
+        drop(state);
+        self.done.notify_all();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+struct GestureJob {
+    gesture: Gesture,
+    status: Arc<PlaybackStatus>,
+}
+
+struct ActiveGesture {
+    job: GestureJob,
+    started: Instant,
+    /// 下一帧的时间，还没有发送过时为 `None`。
+    next_frame: Option<Instant>,
+}
+
+/// 正在播放或排队的动作，可以等待它结束或取消它。
+///
+/// 也实现了 `Future`，在异步代码中 `.await` 得到结束方式。
+#[derive(Clone)]
+pub struct GesturePlayback {
+    name: String,
+    status: Arc<PlaybackStatus>,
+    shared: Arc<Shared>,
+}
+
+impl GesturePlayback {
+    /// 动作名称。
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 结束方式，还在播放或排队时为 `None`。
+    pub fn outcome(&self) -> Option<GestureOutcome> {
+        self.status.lock().0
+    }
+
+    /// 是否已经结束。
+    pub fn is_finished(&self) -> bool {
+        self.outcome().is_some()
+    }
+
+    /// 阻塞等待动作结束。
+    pub fn wait(&self) -> GestureOutcome {
+        let mut state = self.status.lock();
+        loop {
+            if let Some(outcome) = state.0 {
+                return outcome;
+            }
+            state = self
+                .status
+                .done
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// 最多等待 `timeout`，超时返回 `None`。
+    pub fn wait_timeout(&self, timeout: Duration) -> Option<GestureOutcome> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.status.lock();
+        loop {
+            if let Some(outcome) = state.0 {
+                return Some(outcome);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+            state = self
+                .status
+                .done
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    /// 取消动作：排队中的动作不再播放，正在播放的动作停在当前姿态。
+    pub fn cancel(&self) {
+        let mut state = self.shared.lock();
+        if let Some(active) = &state.gesture {
+            if Arc::ptr_eq(&active.job.status, &self.status) {
+                state.gesture = None;
+            }
+        }
+        state
+            .gesture_queue
+            .retain(|job| !Arc::ptr_eq(&job.status, &self.status));
+        drop(state);
+        self.status.complete(GestureOutcome::Cancelled);
+        // 唤醒同步线程开始下一个排队的动作
+        self.shared.wake.notify_one();
+    }
+}
+
+impl Future for GesturePlayback {
+    type Output = GestureOutcome;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<GestureOutcome> {
+        let mut state = self.status.lock();
+        match state.0 {
+            Some(outcome) => Poll::Ready(outcome),
+            None => {
+                state.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// 可克隆、可跨线程共享的机器人句柄。
+///
+/// ```rust,no_run
+/// use electron_bot::{Color, ElectronBot};
+///
+/// let mut bot = ElectronBot::new();
+/// bot.connect()?;
+/// let handle = bot.into_handle()?;
+///
+/// let control = handle.clone();
+/// std::thread::spawn(move || {
+///     control.set_joint_angles(&[0.0, 0.0, 30.0, 0.0, 30.0, 0.0], true);
+/// });
+/// handle.update_image(|buffer| buffer.clear(Color::Blue));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone)]
+pub struct BotHandle {
+    inner: Arc<Inner>,
+}
+
+impl BotHandle {
+    /// 启动同步线程并接管 `bot`，当前画面和关节角度作为初始状态。
+    ///
+    /// 系统无法创建线程时返回 [`BotError::ThreadError`]，`bot` 随之断开并释放。
+    pub fn spawn(mut bot: ElectronBot) -> Result<Self, BotError> {
+        let cancel = bot.cancellation_token();
+        let estop = bot.emergency_stop_handle();
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                image: bot.image_buffer().clone(),
+                image_dirty: false,
+                joints: None,
+                sync_requested: false,
+                keepalive: None,
+                stop: false,
+                changes: 0,
+                sent_changes: 0,
+                failed_changes: 0,
+                reported: bot.get_joint_angles(),
+                frames: 0,
+                connected: bot.is_connected(),
+                last_error: None,
+                gesture: None,
+                gesture_queue: VecDeque::new(),
+            }),
+            wake: Condvar::new(),
+            synced: Condvar::new(),
+        });
+        let worker = {
+            let shared = shared.clone();
+            std::thread::Builder::new()
+                .name("electron-bot-sync".to_string())
+                .spawn(move || run(bot, &shared))
+                .map_err(|e| BotError::ThreadError(format!("同步线程: {}", e)))?
+        };
+        Ok(Self {
+            inner: Arc::new(Inner {
+                shared,
+                worker: Mutex::new(Some(worker)),
+                cancel,
+                estop,
+            }),
+        })
+    }
+
+    /// 设置下一次同步发送的画面（复制一份，之前未发送的画面被替换）。
+    pub fn set_image(&self, image: &ImageBuffer) {
+        self.change(|state| {
+            state.image.as_mut_data().copy_from_slice(image.as_data());
+            state.image_dirty = true;
+        });
+    }
+
+    /// 直接修改下一次同步发送的画面，`f` 执行期间持有锁，应尽快返回。
+    pub fn update_image<F: FnOnce(&mut ImageBuffer)>(&self, f: F) {
+        self.change(|state| {
+            f(&mut state.image);
+            state.image_dirty = true;
+        });
+    }
+
+    /// 设置舵机角度，下一次同步时发送。
+    ///
+    /// 目标被拒绝（如自碰撞保护返回 [`BotError::Collision`]）时不发送，
+    /// 错误与同步失败一样通过 [`BotHandle::take_error`] 取出。
+    pub fn set_joint_angles(&self, angles: &[f32; 6], enable: bool) {
+        self.change(|state| state.joints = Some((*angles, enable)));
+    }
+
+    /// 请求同步一次（内容没有变化时也发送）。
+    pub fn sync(&self) {
+        self.change(|state| state.sync_requested = true);
+    }
+
+    /// 设置保活间隔：没有新内容时至少每隔 `interval` 同步一次，
+    /// 用于持续读取机器人上报的角度；`None`（默认）表示只在内容变化时同步。
+    pub fn set_keepalive(&self, interval: Option<Duration>) {
+        self.inner.shared.lock().keepalive = interval;
+        self.inner.shared.wake.notify_all();
+    }
+
+    /// 等待到目前为止的所有修改发送完毕；发送这些修改的同步失败时返回该次同步的错误，
+    /// 超时或同步线程已停止时也返回错误。
+    pub fn flush(&self, timeout: Duration) -> Result<(), String> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.inner.shared.lock();
+        let target = state.changes;
+        while state.sent_changes < target {
+            if state.stop {
+                return Err("同步线程已停止".to_string());
+            }
+            if state.failed_changes >= target {
+                return Err(state
+                    .last_error
+                    .clone()
+                    .unwrap_or_else(|| "同步失败".to_string()));
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err("等待同步超时".to_string());
+            }
+            state = self
+                .inner
+                .shared
+                .synced
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+        Ok(())
+    }
+
+    /// 最近一次同步时机器人上报的角度（还没有收到机器人数据时为目标角度）。
+    pub fn joint_angles(&self) -> JointAngles {
+        self.inner.shared.lock().reported.clone()
+    }
+
+    /// 已成功同步的次数。
+    pub fn frames(&self) -> u64 {
+        self.inner.shared.lock().frames
+    }
+
+    /// 最近一次同步后是否仍然连接。
+    pub fn is_connected(&self) -> bool {
+        self.inner.shared.lock().connected
+    }
+
+    /// 取出最近一次同步失败或关节目标被拒绝的错误信息。
+    pub fn take_error(&self) -> Option<String> {
+        self.inner.shared.lock().last_error.take()
+    }
+
+    /// 播放内置动作（名称见 [`GESTURE_NAMES`](crate::GESTURE_NAMES)），排在当前动作之后。
+    pub fn play_gesture(&self, name: &str) -> Result<GesturePlayback, String> {
+        let gesture = Gesture::named(name).ok_or_else(|| format!("未知的动作: {}", name))?;
+        Ok(self.play_gesture_with(gesture, GestureMode::Queue))
+    }
+
+    /// 播放动作，`mode` 决定打断当前动作还是排队。
+    ///
+    /// 动作按实际经过的时间插值，每 [`DEFAULT_INTERVAL`] 同步一次（舵机使能）；
+    /// 播放期间 [`BotHandle::set_joint_angles`] 的设置会被动作覆盖。
+    /// 同步线程已停止时返回的动作立即以 [`GestureOutcome::Stopped`] 结束，
+    /// 急停中立即以 [`GestureOutcome::EmergencyStop`] 结束。
+    pub fn play_gesture_with(&self, gesture: Gesture, mode: GestureMode) -> GesturePlayback {
+        let playback = GesturePlayback {
+            name: gesture.name().to_string(),
+            status: Arc::new(PlaybackStatus::default()),
+            shared: self.inner.shared.clone(),
+        };
+        let mut state = self.inner.shared.lock();
+        if state.stop {
+            playback.status.complete(GestureOutcome::Stopped);
+            return playback;
+        }
+        if self.inner.estop.is_triggered() {
+            playback.status.complete(GestureOutcome::EmergencyStop);
+            return playback;
+        }
+        if mode == GestureMode::Interrupt {
+            state.clear_gestures(GestureOutcome::Interrupted);
+        }
+        state.gesture_queue.push_back(GestureJob {
+            gesture,
+            status: playback.status.clone(),
+        });
+        drop(state);
+        self.inner.shared.wake.notify_one();
+        playback
+    }
+
+    /// 取消当前动作和所有排队的动作，关节停在当前姿态。
+    pub fn cancel_gestures(&self) {
+        self.inner
+            .shared
+            .lock()
+            .clear_gestures(GestureOutcome::Cancelled);
+    }
+
+    /// 急停：中止所有动作，立即同步发送舵机失能并锁定，直到调用 [`BotHandle::clear_emergency_stop`]。
+    ///
+    /// 正在进行的同步在下一个包之前中止并重新发送失能。
+    pub fn emergency_stop(&self) {
+        self.inner.estop.trigger();
+        let mut state = self.inner.shared.lock();
+        state.clear_gestures(GestureOutcome::EmergencyStop);
+        state.sync_requested = true;
+        drop(state);
+        self.inner.shared.wake.notify_one();
+    }
+
+    /// 解除急停，舵机保持失能，需要重新设置角度并使能。
+    pub fn clear_emergency_stop(&self) {
+        self.inner.estop.clear();
+    }
+
+    /// 是否处于急停状态。
+    pub fn is_emergency_stopped(&self) -> bool {
+        self.inner.estop.is_triggered()
+    }
+
+    /// 正在播放的动作名称。
+    pub fn current_gesture(&self) -> Option<String> {
+        let state = self.inner.shared.lock();
+        state
+            .gesture
+            .as_ref()
+            .map(|active| active.job.gesture.name().to_string())
+    }
+
+    /// 停止同步线程并取回 `ElectronBot`（只有第一次调用返回 `Some`）。
+    ///
+    /// 正在进行的同步在下一个包之前中止，已暂存但还没有发送的内容会被丢弃，
+    /// 需要时先调用 [`BotHandle::flush`]。
+    pub fn stop(&self) -> Option<ElectronBot> {
+        self.inner.stop()
+    }
+
+    fn change<F: FnOnce(&mut State)>(&self, f: F) {
+        let mut state = self.inner.shared.lock();
+        if state.stop {
+            return;
+        }
+        f(&mut state);
+        state.changes += 1;
+        drop(state);
+        self.inner.shared.wake.notify_one();
+    }
+}
+
+/// 同步线程：等待新内容，在锁外发送。
+fn run(mut bot: ElectronBot, shared: &Shared) -> ElectronBot {
+    #[cfg(feature = "logging")]
+    log::info!("Sync thread started");
+    loop {
+        let mut state = shared.lock();
+        let mut idle_since = Instant::now();
+        loop {
+            if state.stop {
+                state.clear_gestures(GestureOutcome::Stopped);
+                #[cfg(feature = "logging")]
+                log::info!("Sync thread stopped");
+                return bot;
+            }
+            if state.image_dirty || state.joints.is_some() || state.sync_requested {
+                break;
+            }
+            let keepalive = state
+                .keepalive
+                .map(|interval| interval.saturating_sub(idle_since.elapsed()));
+            match [keepalive, state.gesture_wait()]
+                .into_iter()
+                .flatten()
+                .min()
+            {
+                Some(wait) if wait.is_zero() => break,
+                Some(wait) => {
+                    let (guard, _) = shared
+                        .wake
+                        .wait_timeout(state, wait)
+                        .unwrap_or_else(|e| e.into_inner());
+                    state = guard;
+                }
+                None => {
+                    state = shared.wake.wait(state).unwrap_or_else(|e| e.into_inner());
+                    idle_since = Instant::now();
+                }
+            }
+        }
+
+        // 在锁内取出暂存的内容
+        let finished = state.advance_gesture(Instant::now());
+        if state.image_dirty {
+            bot.image_buffer()
+                .as_mut_data()
+                .copy_from_slice(state.image.as_data());
+            state.image_dirty = false;
+        }
+        if let Some((angles, enable)) = state.joints.take() {
+            if let Err(e) = bot.set_joint_angles(&angles, enable) {
+                #[cfg(feature = "logging")]
+                log::warn!("Joint target rejected: {}", e);
+                state.last_error = Some(e.to_string());
+            }
+        }
+        state.sync_requested = false;
+        let changes = state.changes;
+        drop(state);
+
+        let result = bot.sync();
+
+        let mut state = shared.lock();
+        state.reported = bot.get_joint_angles();
+        state.connected = bot.is_connected();
+        match result {
+            Ok(true) => {
+                state.sent_changes = changes;
+                state.frames += 1;
+            }
+            Ok(false) => {
+                state.failed_changes = changes;
+                state.last_error = Some("同步未完成".to_string());
+            }
+            Err(e) => {
+                #[cfg(feature = "logging")]
+                log::warn!("Background sync failed: {}", e);
+                state.failed_changes = changes;
+                state.last_error = Some(e.to_string());
+            }
+        }
+        drop(state);
+        shared.synced.notify_all();
+        if let Some(job) = finished {
+            #[cfg(feature = "logging")]
+            log::debug!("Gesture '{}' finished", job.gesture.name());
+            job.status.complete(GestureOutcome::Finished);
+        }
+    }
+}
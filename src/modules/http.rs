@@ -0,0 +1,538 @@
+//! ElectronBot 库的 HTTP 接口。
+//!
+//! [`HttpServer`] 提供简单的 REST 接口，家庭自动化脚本用 `curl` 就能控制机器人：
+//!
+//! | 请求 | 说明 |
+//! |------|------|
+//! | `GET /status` | 连接状态、屏幕、舵机角度和发送帧数（JSON） |
+//! | `POST /image` | 显示图片（请求体为图片数据或 `multipart/form-data`），自动缩放到屏幕 |
+//! | `POST /color` | 纯色填充（请求体为十六进制颜色，如 `#ff8800`） |
+//! | `POST /joints` | 设置 6 个关节角度（JSON 数组），`?enable=false` 释放舵机 |
+//! | `GET /gestures` | 内置动作列表 |
+//! | `POST /gesture/{name}` | 播放内置动作，播放完后返回 |
+//!
+//! 每个连接在单独的线程中读取请求，整个请求（请求行、头部和请求体）必须在超时时间内读完，
+//! 慢速客户端不会阻塞其他客户端；读完的请求按顺序交给机器人处理，处理完后关闭连接。
+//! 请求行和头部有长度限制，超出时返回 400 或 431。
+//!
+//! 接口可以控制舵机和屏幕，示例和文档默认只监听 `127.0.0.1`；监听其他网卡时应该用
+//! [`HttpServer::token`] 要求 `Authorization: Bearer <token>`。
+//!
+//! 服务器基于标准库的 `TcpListener` 实现，没有使用 axum：axum 需要 tokio 运行时，
+//! 而本库的接口是同步的（`&mut ElectronBot`），`http` feature 也保持不引入额外依赖，
+//! 与 `mqtt`、`bridge` 一致。
+
+use std::io::{self, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::modules::error::BotError;
+use crate::modules::gesture::{Gesture, GESTURE_NAMES};
+use crate::modules::json::escape as json_escape;
+use crate::modules::net::{parse_angles, read_line_limited, status_json};
+use crate::modules::time::Instant;
+use crate::modules::types::Color;
+use crate::ElectronBot;
+
+/// 默认监听地址，只接受本机的连接。
+pub const DEFAULT_HTTP_ADDR: &str = "127.0.0.1:8080";
+
+/// 请求行或单个请求头的最大字节数。
+const MAX_LINE_BYTES: usize = 8 * 1024;
+
+/// 全部请求头的最大字节数。
+const MAX_HEADER_BYTES: usize = 64 * 1024;
+
+/// 请求体最大字节数。
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// HTTP 请求。
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Request {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn query(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// HTTP 响应（JSON）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn ok() -> Self {
+        Self::json(200, "{\"ok\":true}".to_string())
+    }
+
+    fn json(status: u16, body: String) -> Self {
+        Self { status, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(
+            status,
+            format!("{{\"error\":\"{}\"}}", json_escape(message)),
+        )
+    }
+
+    fn bot_error(error: BotError) -> Self {
+        let status = match error {
+            BotError::NotConnected | BotError::DeviceNotFound(..) => 503,
+            BotError::ImageError(_) => 400,
+            _ => 500,
+        };
+        Self::error(status, &error.to_string())
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            401 => "Unauthorized",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            411 => "Length Required",
+            413 => "Payload Too Large",
+            431 => "Request Header Fields Too Large",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        };
+        let challenge = if self.status == 401 {
+            "WWW-Authenticate: Bearer\r\n"
+        } else {
+            ""
+        };
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\n\
+             {}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason,
+            challenge,
+            self.body.len(),
+            self.body
+        )?;
+        writer.flush()
+    }
+}
+
+/// HTTP 接口服务器。
+#[derive(Debug)]
+pub struct HttpServer {
+    listener: TcpListener,
+    timeout: Duration,
+    token: Option<String>,
+}
+
+impl HttpServer {
+    /// 监听指定地址，例如 [`DEFAULT_HTTP_ADDR`]；监听 `0.0.0.0` 时局域网中的设备都可以访问。
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self, String> {
+        let listener = TcpListener::bind(addr).map_err(|e| format!("监听失败: {}", e))?;
+        Ok(Self {
+            listener,
+            timeout: Duration::from_secs(10),
+            token: None,
+        })
+    }
+
+    /// 设置读取整个请求的超时时间（默认 10 秒）。
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 要求请求带有 `Authorization: Bearer <token>`，否则返回 401（默认不检查）。
+    pub fn token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    /// 实际监听的地址（端口为 0 时由系统分配）。
+    pub fn local_addr(&self) -> Result<SocketAddr, String> {
+        self.listener
+            .local_addr()
+            .map_err(|e| format!("获取监听地址失败: {}", e))
+    }
+
+    /// 持续处理请求，单个连接出错时继续运行。
+    ///
+    /// 后台线程接受连接，每个连接在单独的线程中读取请求，读完后在当前线程按顺序处理。
+    pub fn serve(&self, bot: &mut ElectronBot) -> Result<(), String> {
+        #[cfg(feature = "logging")]
+        if let Ok(addr) = self.listener.local_addr() {
+            log::info!("HTTP server listening on {}", addr);
+        }
+        let listener = self
+            .listener
+            .try_clone()
+            .map_err(|e| format!("复制监听套接字失败: {}", e))?;
+        let timeout = self.timeout;
+        let (tx, rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("electron-bot-http".to_string())
+            .spawn(move || accept_loop(listener, timeout, tx))
+            .map_err(|e| format!("创建接受连接线程失败: {}", e))?;
+
+        for (stream, request) in rx {
+            if let Err(_e) = self.respond(stream, request, bot) {
+                #[cfg(feature = "logging")]
+                log::warn!("HTTP request failed: {}", _e);
+            }
+        }
+        Ok(())
+    }
+
+    /// 等待并处理一个请求。
+    pub fn handle_next(&self, bot: &mut ElectronBot) -> Result<(), String> {
+        let (stream, _) = self
+            .listener
+            .accept()
+            .map_err(|e| format!("接受连接失败: {}", e))?;
+        let request = read_request(&stream, self.timeout);
+        self.respond(stream, request, bot)
+    }
+
+    /// 检查令牌、处理请求并发送响应。
+    fn respond(
+        &self,
+        mut stream: TcpStream,
+        request: Result<Request, Response>,
+        bot: &mut ElectronBot,
+    ) -> Result<(), String> {
+        let response = match request {
+            Ok(request) if !self.authorized(&request) => {
+                Response::error(401, "缺少或错误的访问令牌")
+            }
+            Ok(request) => {
+                #[cfg(feature = "logging")]
+                log::debug!("{} {}", request.method, request.path);
+                route(bot, &request)
+            }
+            Err(response) => response,
+        };
+        stream
+            .set_write_timeout(Some(self.timeout))
+            .map_err(|e| format!("设置超时失败: {}", e))?;
+        response
+            .write_to(&mut stream)
+            .map_err(|e| format!("发送响应失败: {}", e))
+    }
+
+    fn authorized(&self, request: &Request) -> bool {
+        let Some(token) = &self.token else {
+            return true;
+        };
+        request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|given| constant_time_eq(given.trim().as_bytes(), token.as_bytes()))
+    }
+}
+
+/// 接受连接，每个连接交给单独的线程读取请求。
+///
+/// 接受失败（如文件描述符耗尽、客户端中途断开）时记录后继续，短暂等待避免空转。
+fn accept_loop(
+    listener: TcpListener,
+    timeout: Duration,
+    tx: mpsc::Sender<(TcpStream, Result<Request, Response>)>,
+) {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(_e) => {
+                #[cfg(feature = "logging")]
+                log::warn!("HTTP accept failed: {}", _e);
+                std::thread::sleep(Duration::from_millis(10));
+                continue;
+            }
+        };
+        let tx = tx.clone();
+        let spawned = std::thread::Builder::new()
+            .name("electron-bot-http-conn".to_string())
+            .spawn(move || {
+                let request = read_request(&stream, timeout);
+                let _ = tx.send((stream, request));
+            });
+        if let Err(_e) = spawned {
+            #[cfg(feature = "logging")]
+            log::warn!("HTTP connection thread failed: {}", _e);
+        }
+    }
+}
+
+/// 比较令牌，耗时与内容无关。
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// 整个请求共用一个截止时间的读取器，每次读取前把超时设为剩余时间。
+struct DeadlineReader<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(remaining))?;
+        let mut stream = self.stream;
+        stream.read(buf)
+    }
+}
+
+/// 读取错误转换为响应：超时返回 408，其他错误返回 400。
+fn read_error(error: io::Error, message: &str) -> Response {
+    match error.kind() {
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => Response::error(408, "读取请求超时"),
+        _ => Response::error(400, message),
+    }
+}
+
+/// 在 `timeout` 内读取请求行、头部和请求体。
+fn read_request(stream: &TcpStream, timeout: Duration) -> Result<Request, Response> {
+    let bad = |message: &str| Response::error(400, message);
+    let mut reader = BufReader::new(DeadlineReader {
+        stream,
+        deadline: Instant::now() + timeout,
+    });
+    let mut request_line = String::new();
+    match read_line_limited(&mut reader, &mut request_line, MAX_LINE_BYTES) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(bad("请求行过长")),
+        Err(e) => return Err(read_error(e, "读取请求失败")),
+    }
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(bad("无效的请求行"));
+    };
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path, parse_query(query)),
+        None => (target, Vec::new()),
+    };
+
+    let too_large = || Response::error(431, "请求头过大");
+    let mut headers = Vec::new();
+    let mut header_bytes = 0;
+    let mut line = String::new();
+    loop {
+        match read_line_limited(&mut reader, &mut line, MAX_LINE_BYTES) {
+            Ok(Some(read)) => header_bytes += read,
+            Ok(None) => return Err(too_large()),
+            Err(e) => return Err(read_error(e, "读取请求头失败")),
+        }
+        if header_bytes > MAX_HEADER_BYTES {
+            return Err(too_large());
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    let mut request = Request {
+        method: method.to_ascii_uppercase(),
+        path: path.trim_end_matches('/').to_string(),
+        query,
+        headers,
+        body: Vec::new(),
+    };
+
+    let length = match request.header("content-length") {
+        Some(len) => len
+            .parse::<usize>()
+            .map_err(|_| bad("无效的 Content-Length"))?,
+        None if request.header("transfer-encoding").is_some() => {
+            return Err(Response::error(
+                411,
+                "不支持分块传输，请提供 Content-Length",
+            ));
+        }
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(Response::error(413, "请求体过大"));
+    }
+    // curl 上传较大的文件时先等待 100 Continue
+    if length > 0
+        && request
+            .header("expect")
+            .is_some_and(|e| e.eq_ignore_ascii_case("100-continue"))
+    {
+        let _ = (&*stream).write_all(b"HTTP/1.1 100 Continue\r\n\r\n");
+    }
+    request.body = vec![0; length];
+    reader
+        .read_exact(&mut request.body)
+        .map_err(|e| read_error(e, "读取请求体失败"))?;
+    Ok(request)
+}
+
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => (key.to_string(), value.to_string()),
+            None => (pair.to_string(), String::new()),
+        })
+        .collect()
+}
+
+/// 分派请求。
+fn route(bot: &mut ElectronBot, request: &Request) -> Response {
+    let segments: Vec<&str> = request.path.split('/').filter(|s| !s.is_empty()).collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["status"]) => status(bot),
+        ("POST", ["image"]) => post_image(bot, request),
+        ("POST", ["color"]) => post_color(bot, request),
+        ("POST", ["joints"]) => post_joints(bot, request),
+        ("GET", ["gestures"]) => {
+            let names: Vec<String> = GESTURE_NAMES.iter().map(|n| format!("\"{}\"", n)).collect();
+            Response::json(200, format!("[{}]", names.join(",")))
+        }
+        ("POST", ["gesture", name]) => post_gesture(bot, name),
+        (_, ["status" | "image" | "color" | "joints" | "gestures"] | ["gesture", _]) => {
+            Response::error(405, "不支持的请求方法")
+        }
+        _ => Response::error(404, "未知的路径"),
+    }
+}
+
+fn status(bot: &mut ElectronBot) -> Response {
+    Response::json(200, status_json(bot))
+}
+
+fn post_image(bot: &mut ElectronBot, request: &Request) -> Response {
+    let content_type = request.header("content-type").unwrap_or_default();
+    let data = match multipart_boundary(content_type) {
+        Some(boundary) => match multipart_file(&request.body, &boundary) {
+            Some(data) => data,
+            None => return Response::error(400, "multipart 请求中没有文件"),
+        },
+        None => &request.body[..],
+    };
+    if data.is_empty() {
+        return Response::error(400, "请求体为空");
+    }
+    if let Err(e) = bot.set_image_from_bytes(data) {
+        return Response::bot_error(e);
+    }
+    sync(bot)
+}
+
+fn post_color(bot: &mut ElectronBot, request: &Request) -> Response {
+    let text = String::from_utf8_lossy(&request.body);
+    match Color::from_hex(text.trim().trim_matches('"')) {
+        Ok(color) => {
+            bot.set_image_color(color);
+            sync(bot)
+        }
+        Err(e) => Response::error(400, &e),
+    }
+}
+
+fn post_joints(bot: &mut ElectronBot, request: &Request) -> Response {
+    let angles = match parse_angles(&String::from_utf8_lossy(&request.body)) {
+        Ok(angles) => angles,
+        Err(e) => return Response::error(400, &e),
+    };
+    let enable = !matches!(request.query("enable"), Some("false" | "0"));
+    if let Err(e) = bot.set_joint_angles(&angles, enable) {
+        return Response::bot_error(e);
+    }
+    sync(bot)
+}
+
+fn post_gesture(bot: &mut ElectronBot, name: &str) -> Response {
+    let Some(mut gesture) = Gesture::named(name) else {
+        return Response::error(404, &format!("未知的动作: {}", name));
+    };
+    if !bot.is_connected() {
+        return Response::bot_error(BotError::NotConnected);
+    }
+    match bot.play(&mut gesture, None) {
+        Ok(stats) => Response::json(200, format!("{{\"ok\":true,\"frames\":{}}}", stats.frames)),
+        Err(e) => Response::bot_error(e),
+    }
+}
+
+fn sync(bot: &mut ElectronBot) -> Response {
+    match bot.sync() {
+        Ok(_) => Response::ok(),
+        Err(e) => Response::bot_error(e),
+    }
+}
+
+/// 从 `multipart/form-data; boundary=xxx` 中取出分隔符。
+fn multipart_boundary(content_type: &str) -> Option<String> {
+    if !content_type
+        .to_ascii_lowercase()
+        .starts_with("multipart/form-data")
+    {
+        return None;
+    }
+    content_type.split(';').find_map(|part| {
+        let (key, value) = part.trim().split_once('=')?;
+        let value = value.trim().trim_matches('"');
+        (key.trim().eq_ignore_ascii_case("boundary") && !value.is_empty())
+            .then(|| value.to_string())
+    })
+}
+
+/// 取出第一个文件分段的数据（没有文件名时取第一个分段）。
+fn multipart_file<'a>(body: &'a [u8], boundary: &str) -> Option<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut rest = body;
+    while let Some(start) = find(rest, delimiter.as_bytes()) {
+        rest = &rest[start + delimiter.len()..];
+        if rest.starts_with(b"--") {
+            break;
+        }
+        let end = find(rest, delimiter.as_bytes()).unwrap_or(rest.len());
+        let part = &rest[..end];
+        if let Some(split) = find(part, b"\r\n\r\n") {
+            let headers = String::from_utf8_lossy(&part[..split]).to_ascii_lowercase();
+            let data = &part[split + 4..];
+            parts.push((headers, data.strip_suffix(b"\r\n").unwrap_or(data)));
+        }
+        rest = &rest[end..];
+    }
+    parts
+        .iter()
+        .find(|(headers, _)| headers.contains("filename="))
+        .or(parts.first())
+        .map(|(_, data)| *data)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
@@ -0,0 +1,403 @@
+//! ElectronBot 库的空闲动画。
+//!
+//! [`IdleBehavior`] 在表情引擎之上叠加随机眨眼、瞳孔漂移和轻微的头部、
+//! 身体摆动，交给 `ElectronBot::play` 播放即可让机器人显得“活着”。
+//! 注视目标（[`IdleBehavior::gaze_at`]）同样叠加在空闲动画之上。
+
+use std::ops::Range;
+use std::time::Duration;
+
+#[cfg(feature = "audio")]
+use crate::modules::audio::LipSync;
+use crate::modules::constants::{JOINT_BODY, JOINT_HEAD};
+use crate::modules::face::{Expression, Face};
+use crate::modules::gaze::{Gaze, GazeOptions, GazeTarget};
+use crate::modules::image::ImageBuffer;
+use crate::modules::source::{FrameSource, DEFAULT_INTERVAL};
+use crate::modules::time::{SystemTime, UNIX_EPOCH};
+use crate::modules::types::JointAngles;
+#[cfg(feature = "audio")]
+use crate::modules::viseme::VisemePlayer;
+
+/// 空闲动画参数。
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdleOptions {
+    /// 两次眨眼之间的间隔范围。
+    pub blink_interval: Range<Duration>,
+    /// 一次眨眼的时长。
+    pub blink_duration: Duration,
+    /// 连续眨两次的概率。
+    pub double_blink_chance: f32,
+    /// 瞳孔漂移幅度（0 到 1）。
+    pub pupil_drift: f32,
+    /// 瞳孔换位置的间隔范围。
+    pub drift_interval: Range<Duration>,
+    /// 头部和身体摆动幅度（度），0 表示不摆动。
+    pub head_motion: f32,
+    /// 头部换姿态的间隔范围。
+    pub motion_interval: Range<Duration>,
+}
+
+impl Default for IdleOptions {
+    fn default() -> Self {
+        Self {
+            blink_interval: Duration::from_secs(2)..Duration::from_secs(6),
+            blink_duration: Duration::from_millis(150),
+            double_blink_chance: 0.2,
+            pupil_drift: 0.35,
+            drift_interval: Duration::from_secs(1)..Duration::from_secs(3),
+            head_motion: 4.0,
+            motion_interval: Duration::from_secs(3)..Duration::from_secs(8),
+        }
+    }
+}
+
+impl IdleOptions {
+    /// 设置瞳孔漂移幅度。
+    pub fn pupil_drift(mut self, amount: f32) -> Self {
+        self.pupil_drift = amount;
+        self
+    }
+
+    /// 设置头部和身体摆动幅度（度），0 表示不摆动。
+    ///
+    /// 同时使用 [`GazeOptions::eyes_only`] 时完全不控制舵机。
+    pub fn head_motion(mut self, degrees: f32) -> Self {
+        self.head_motion = degrees;
+        self
+    }
+
+    /// 设置眨眼间隔范围。
+    pub fn blink_interval(mut self, interval: Range<Duration>) -> Self {
+        self.blink_interval = interval;
+        self
+    }
+}
+
+/// 空闲动画帧源。
+#[derive(Debug, Clone)]
+pub struct IdleBehavior {
+    face: Face,
+    gaze: Gaze,
+    options: IdleOptions,
+    rng: XorShift,
+    base_pose: JointAngles,
+    /// 距下次眨眼的时间。
+    next_blink: Duration,
+    /// 当前眨眼已持续的时间，`None` 表示没在眨眼。
+    blink: Option<Duration>,
+    /// 当前眨眼结束后是否紧接着再眨一次。
+    double_blink: bool,
+    next_drift: Duration,
+    pupil: (f32, f32),
+    pupil_target: (f32, f32),
+    next_motion: Duration,
+    head: (f32, f32),
+    head_target: (f32, f32),
+    #[cfg(feature = "audio")]
+    lip_sync: Option<LipSync>,
+    #[cfg(feature = "audio")]
+    speech: Option<VisemePlayer>,
+}
+
+impl IdleBehavior {
+    /// 使用默认参数创建。
+    pub fn new() -> Self {
+        Self::with_rng(IdleOptions::default(), XorShift::from_time())
+    }
+
+    /// 使用固定随机种子创建（动画可复现）。
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(IdleOptions::default(), XorShift::new(seed))
+    }
+
+    fn with_rng(options: IdleOptions, mut rng: XorShift) -> Self {
+        let next_blink = random_duration(&mut rng, &options.blink_interval);
+        Self {
+            face: Face::new(),
+            gaze: Gaze::default(),
+            options,
+            rng,
+            base_pose: JointAngles::new(),
+            next_blink,
+            blink: None,
+            double_blink: false,
+            next_drift: Duration::ZERO,
+            pupil: (0.0, 0.0),
+            pupil_target: (0.0, 0.0),
+            next_motion: Duration::ZERO,
+            head: (0.0, 0.0),
+            head_target: (0.0, 0.0),
+            #[cfg(feature = "audio")]
+            lip_sync: None,
+            #[cfg(feature = "audio")]
+            speech: None,
+        }
+    }
+
+    /// 设置动画参数。
+    pub fn options(mut self, options: IdleOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 设置摆动围绕的基础姿态。
+    pub fn base_pose(mut self, pose: JointAngles) -> Self {
+        self.base_pose = pose;
+        self
+    }
+
+    /// 设置注视控制参数。
+    pub fn gaze_options(mut self, options: GazeOptions) -> Self {
+        self.gaze = Gaze::new(options);
+        self
+    }
+
+    /// 注视目标，超出眼球范围时转动头部和身体。
+    pub fn gaze_at(&mut self, target: GazeTarget) {
+        self.gaze.look_at(target);
+    }
+
+    /// 注视控制器。
+    pub fn gaze(&self) -> &Gaze {
+        &self.gaze
+    }
+
+    /// 用音频驱动嘴型。
+    #[cfg(feature = "audio")]
+    pub fn lip_sync(mut self, lip_sync: LipSync) -> Self {
+        self.lip_sync = Some(lip_sync);
+        self
+    }
+
+    /// 口型同步的可变引用。
+    #[cfg(feature = "audio")]
+    pub fn lip_sync_mut(&mut self) -> Option<&mut LipSync> {
+        self.lip_sync.as_mut()
+    }
+
+    /// 按视位序列播放口型，播放期间优先于音量驱动的嘴型。
+    #[cfg(feature = "audio")]
+    pub fn speak(&mut self, player: VisemePlayer) {
+        self.speech = Some(player);
+    }
+
+    /// 正在播放的视位序列（可用于按音频进度校准时钟）。
+    #[cfg(feature = "audio")]
+    pub fn speech_mut(&mut self) -> Option<&mut VisemePlayer> {
+        self.speech.as_mut()
+    }
+
+    /// 底层的表情动画，可用于切换基础表情。
+    pub fn face(&self) -> &Face {
+        &self.face
+    }
+
+    /// 底层表情动画的可变引用。
+    pub fn face_mut(&mut self) -> &mut Face {
+        &mut self.face
+    }
+
+    /// 叠加眨眼、注视和瞳孔漂移后的当前表情。
+    pub fn expression(&self) -> Expression {
+        let mut e = self.face.expression();
+        let (gaze_x, gaze_y) = self.gaze.pupil();
+        e.eye_open *= self.blink_openness();
+        e.pupil_x = (e.pupil_x + gaze_x + self.pupil.0).clamp(-1.0, 1.0);
+        e.pupil_y = (e.pupil_y + gaze_y + self.pupil.1).clamp(-1.0, 1.0);
+        #[cfg(feature = "audio")]
+        if let Some(lip_sync) = &self.lip_sync {
+            lip_sync.apply(&mut e);
+        }
+        #[cfg(feature = "audio")]
+        if let Some(speech) = &self.speech {
+            speech.mouth().apply(&mut e);
+        }
+        e
+    }
+
+    /// 是否正在眨眼。
+    pub fn is_blinking(&self) -> bool {
+        self.blink.is_some()
+    }
+
+    /// 立即眨一次眼。
+    pub fn blink(&mut self) {
+        self.blink = Some(Duration::ZERO);
+    }
+
+    /// 推进动画时间。
+    pub fn advance(&mut self, dt: Duration) {
+        self.face.advance(dt);
+        self.gaze.advance(dt);
+        self.update_blink(dt);
+        #[cfg(feature = "audio")]
+        if let Some(lip_sync) = &mut self.lip_sync {
+            lip_sync.update(dt);
+        }
+        #[cfg(feature = "audio")]
+        if self.speech.as_ref().is_some_and(VisemePlayer::is_finished) {
+            self.speech = None;
+        }
+
+        // 瞳孔和头部按指数缓动靠近目标
+        let (drift, motion) = (self.options.pupil_drift, self.options.head_motion);
+        if countdown(&mut self.next_drift, dt) {
+            self.pupil_target = (self.rng.signed() * drift, self.rng.signed() * drift * 0.6);
+            self.next_drift = random_duration(&mut self.rng, &self.options.drift_interval);
+        }
+        if countdown(&mut self.next_motion, dt) {
+            self.head_target = (self.rng.signed() * motion, self.rng.signed() * motion * 1.5);
+            self.next_motion = random_duration(&mut self.rng, &self.options.motion_interval);
+        }
+        let frames = dt.as_secs_f32() / DEFAULT_INTERVAL.as_secs_f32();
+        approach(
+            &mut self.pupil,
+            self.pupil_target,
+            1.0 - 0.8f32.powf(frames),
+        );
+        approach(&mut self.head, self.head_target, 1.0 - 0.95f32.powf(frames));
+    }
+
+    fn update_blink(&mut self, dt: Duration) {
+        match self.blink {
+            Some(elapsed) => {
+                let elapsed = elapsed + dt;
+                if elapsed < self.options.blink_duration {
+                    self.blink = Some(elapsed);
+                } else if self.double_blink {
+                    self.double_blink = false;
+                    self.blink = Some(Duration::ZERO);
+                } else {
+                    self.blink = None;
+                    self.next_blink = random_duration(&mut self.rng, &self.options.blink_interval);
+                }
+            }
+            None => {
+                if countdown(&mut self.next_blink, dt) {
+                    self.blink = Some(Duration::ZERO);
+                    self.double_blink = self.rng.unit() < self.options.double_blink_chance;
+                }
+            }
+        }
+    }
+
+    /// 眨眼过程中的睁眼系数（先闭后睁）。
+    fn blink_openness(&self) -> f32 {
+        match self.blink {
+            Some(elapsed) => {
+                let t = elapsed.as_secs_f32() / self.options.blink_duration.as_secs_f32();
+                (t * 2.0 - 1.0).abs().min(1.0)
+            }
+            None => 1.0,
+        }
+    }
+}
+
+impl Default for IdleBehavior {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameSource for IdleBehavior {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        buffer.draw_face(&self.expression(), self.face.face_style());
+        self.advance(DEFAULT_INTERVAL);
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        Some(DEFAULT_INTERVAL)
+    }
+
+    fn joint_angles(&self) -> Option<JointAngles> {
+        if self.options.head_motion == 0.0 && !self.gaze.moves_head() {
+            return None;
+        }
+        let (gaze_yaw, gaze_pitch) = self.gaze.head();
+        let mut pose = self.base_pose.clone();
+        pose.0[JOINT_HEAD] += self.head.0 + gaze_pitch;
+        pose.0[JOINT_BODY] += self.head.1 + gaze_yaw;
+        Some(pose)
+    }
+}
+
+/// 注视过渡的最大帧数（3 秒）。
+pub(crate) const GAZE_MAX_FRAMES: u64 = 90;
+
+/// 播放空闲动画直到视线稳定在目标上。
+pub(crate) struct UntilGazeSettled<'a>(pub(crate) &'a mut IdleBehavior);
+
+impl FrameSource for UntilGazeSettled<'_> {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        !self.0.gaze.is_settled() && self.0.next_frame(buffer)
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        self.0.frame_interval()
+    }
+
+    fn joint_angles(&self) -> Option<JointAngles> {
+        self.0.joint_angles()
+    }
+}
+
+/// 倒计时，到期时返回 `true`。
+fn countdown(remaining: &mut Duration, dt: Duration) -> bool {
+    if *remaining <= dt {
+        *remaining = Duration::ZERO;
+        true
+    } else {
+        *remaining -= dt;
+        false
+    }
+}
+
+/// xorshift 随机数（与 `PerlinNoise` 打乱排列的方法相同），空闲动画不需要更好的随机性。
+#[derive(Debug, Clone)]
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        // 相近的种子打散成差别很大的初始状态，状态不能为 0
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    /// 以当前时间为种子。
+    fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Self::new(nanos)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// 0.0-1.0（不含 1.0）。
+    fn unit(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// -1.0-1.0。
+    fn signed(&mut self) -> f32 {
+        self.unit() * 2.0 - 1.0
+    }
+}
+
+fn random_duration(rng: &mut XorShift, range: &Range<Duration>) -> Duration {
+    if range.is_empty() {
+        return range.start;
+    }
+    range.start + (range.end - range.start).mul_f32(rng.unit())
+}
+
+fn approach(value: &mut (f32, f32), target: (f32, f32), rate: f32) {
+    value.0 += (target.0 - value.0) * rate;
+    value.1 += (target.1 - value.1) * rate;
+}
@@ -0,0 +1,214 @@
+//! ElectronBot 库的程序化空闲动作。
+//!
+//! [`IdleMotion`] 用 Perlin 噪声给每个关节生成缓慢、不重复的小幅摆动，
+//! 再叠加一个正弦的呼吸起伏，让机器人静止时看起来是“活”的。
+//! 输出的是相对 `base` 的偏移（默认 `base` 为 0，即只输出偏移），
+//! 适合在 [`AnimationMixer`](crate::AnimationMixer) 中以叠加模式放在最底层，
+//! 上层的动作照常播放，空闲动作的细微起伏始终叠加在上面。帧源不修改画面。
+//!
+//! ```
+//! use electron_bot::{AnimationMixer, IdleMotion, IdleMotionOptions, JointAngles, TrackOptions};
+//!
+//! let mut mixer = AnimationMixer::new(JointAngles::new());
+//! mixer.add_track(
+//!     "idle",
+//!     IdleMotion::new(IdleMotionOptions::new().frequency(0.2).breathing(3.0, 0.3)),
+//!     TrackOptions::new().additive(),
+//! );
+//! ```
+
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use crate::modules::image::ImageBuffer;
+use crate::modules::source::{FrameSource, PerlinNoise, DEFAULT_INTERVAL};
+use crate::modules::types::JointAngles;
+
+/// 默认的摆动幅度（度）：头部和身体明显一些，手臂很小。
+pub const DEFAULT_IDLE_AMPLITUDE: [f32; 6] = [3.0, 1.0, 2.0, 1.0, 2.0, 4.0];
+
+/// 呼吸起伏作用到各关节的比例：头部轻微点动，两臂抬起。
+const BREATHING_WEIGHTS: [f32; 6] = [0.5, 0.0, 1.0, 0.0, 1.0, 0.0];
+
+/// 空闲动作选项。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IdleMotionOptions {
+    /// 每个关节噪声摆动的最大幅度（度）。
+    pub amplitude: [f32; 6],
+    /// 噪声摆动的频率（Hz），越大动作越快。
+    pub frequency: f32,
+    /// 呼吸起伏的幅度（度）。
+    pub breathing: f32,
+    /// 呼吸频率（Hz）。
+    pub breathing_rate: f32,
+    /// 噪声种子，不同种子的动作不同。
+    pub seed: u64,
+}
+
+impl IdleMotionOptions {
+    /// 默认选项：默认幅度，摆动 0.3 Hz，呼吸 2° 0.25 Hz（每 4 秒一次）。
+    pub fn new() -> Self {
+        Self {
+            amplitude: DEFAULT_IDLE_AMPLITUDE,
+            frequency: 0.3,
+            breathing: 2.0,
+            breathing_rate: 0.25,
+            seed: 1,
+        }
+    }
+
+    /// 设置每个关节的摆动幅度。
+    pub fn amplitude(mut self, amplitude: [f32; 6]) -> Self {
+        self.amplitude = amplitude.map(|a| if a.is_finite() { a.abs() } else { 0.0 });
+        self
+    }
+
+    /// 设置单个关节的摆动幅度（超出范围的关节被忽略）。
+    pub fn joint_amplitude(mut self, joint: usize, degrees: f32) -> Self {
+        if let Some(amplitude) = self.amplitude.get_mut(joint) {
+            *amplitude = if degrees.is_finite() {
+                degrees.abs()
+            } else {
+                0.0
+            };
+        }
+        self
+    }
+
+    /// 所有关节的摆动幅度和呼吸幅度乘以 `factor`。
+    pub fn scale(mut self, factor: f32) -> Self {
+        let factor = if factor.is_finite() {
+            factor.abs()
+        } else {
+            0.0
+        };
+        self.amplitude = self.amplitude.map(|a| a * factor);
+        self.breathing *= factor;
+        self
+    }
+
+    /// 设置摆动频率。
+    pub fn frequency(mut self, hz: f32) -> Self {
+        self.frequency = if hz.is_finite() { hz.max(0.0) } else { 0.0 };
+        self
+    }
+
+    /// 设置呼吸幅度和频率，幅度为 0 关闭呼吸。
+    pub fn breathing(mut self, degrees: f32, hz: f32) -> Self {
+        self.breathing = if degrees.is_finite() {
+            degrees.abs()
+        } else {
+            0.0
+        };
+        self.breathing_rate = if hz.is_finite() { hz.max(0.0) } else { 0.0 };
+        self
+    }
+
+    /// 设置噪声种子。
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+impl Default for IdleMotionOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 程序化空闲动作帧源，永不结束。
+#[derive(Debug, Clone)]
+pub struct IdleMotion {
+    options: IdleMotionOptions,
+    noise: PerlinNoise,
+    base: JointAngles,
+    elapsed: Duration,
+    angles: JointAngles,
+    interval: Duration,
+}
+
+impl IdleMotion {
+    /// 按指定选项创建，从偏移为 0 的姿态开始。
+    pub fn new(options: IdleMotionOptions) -> Self {
+        Self {
+            noise: PerlinNoise::new(options.seed, 1.0),
+            options,
+            base: JointAngles::new(),
+            elapsed: Duration::ZERO,
+            angles: JointAngles::new(),
+            interval: DEFAULT_INTERVAL,
+        }
+    }
+
+    /// 设置基础姿态，输出为基础姿态加偏移（单独播放时使用）。
+    pub fn base(mut self, base: JointAngles) -> Self {
+        self.angles = base.clone();
+        self.base = base;
+        self
+    }
+
+    /// 设置帧间隔（每帧经过一个间隔）。
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval.max(Duration::from_millis(1));
+        self
+    }
+
+    /// 动作选项。
+    pub fn options(&self) -> &IdleMotionOptions {
+        &self.options
+    }
+
+    /// 已播放的时间。
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// 回到开始时刻。
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.angles = self.base.clone();
+    }
+
+    /// 时刻 `t` 的偏移（不含基础姿态），`t = 0` 时所有关节为 0。
+    pub fn offsets_at(&self, t: Duration) -> JointAngles {
+        let t = t.as_secs_f32();
+        let x = t * self.options.frequency;
+        let breath = (TAU * self.options.breathing_rate * t).sin() * self.options.breathing;
+        let mut offsets = JointAngles::new();
+        for (joint, offset) in offsets.as_array_mut().iter_mut().enumerate() {
+            // 每个关节取噪声场中不同的一行，两个倍频叠加后整体更自然
+            let row = joint as f32 * 2.0;
+            let value =
+                self.noise.noise(x, row, 0.0) + 0.5 * self.noise.noise(x * 2.0, row + 1.0, 0.0);
+            let noise = (value / 1.5).clamp(-1.0, 1.0) * self.options.amplitude[joint];
+            *offset = noise + breath * BREATHING_WEIGHTS[joint];
+        }
+        offsets
+    }
+}
+
+impl FrameSource for IdleMotion {
+    fn next_frame(&mut self, _buffer: &mut ImageBuffer) -> bool {
+        self.elapsed += self.interval;
+        let offsets = self.offsets_at(self.elapsed);
+        self.angles = self.base.clone();
+        for (angle, offset) in self
+            .angles
+            .as_array_mut()
+            .iter_mut()
+            .zip(offsets.as_array())
+        {
+            *angle += offset;
+        }
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        Some(self.interval)
+    }
+
+    fn joint_angles(&self) -> Option<JointAngles> {
+        Some(self.angles.clone())
+    }
+}
@@ -1,15 +1,189 @@
 //! ElectronBot 库的图片缓冲区操作。
 
-use crate::modules::constants::{FRAME_HEIGHT, FRAME_SIZE, FRAME_WIDTH};
-use crate::modules::types::Color;
-use image::DynamicImage;
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_SIZE, FRAME_WIDTH, QUARTER_SIZE};
+use crate::modules::types::{Color, Orientation, Rotation};
+#[cfg(feature = "image")]
+use image::imageops::FilterType;
+#[cfg(feature = "image")]
+use image::{DynamicImage, RgbImage};
+#[cfg(feature = "image")]
 use rand::Rng;
 
+/// 图片适配屏幕的方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FitMode {
+    /// 拉伸填满屏幕（不保持宽高比）。
+    #[default]
+    Stretch,
+    /// 保持宽高比完整显示，留边填充背景色。
+    Contain,
+    /// 保持宽高比填满屏幕，超出部分居中裁剪。
+    Cover,
+    /// 不缩放，居中显示（大图裁剪，小图留边）。
+    Center,
+}
+
+/// 16 位或浮点（HDR）图片转换为 8 位时的色调映射方式。
+///
+/// 8 位图片不受影响。
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ToneMap {
+    /// 扩展 Reinhard：以图片最亮值为白点压缩高光，
+    /// 不超过 1.0 的普通 16 位图片基本保持不变。
+    #[default]
+    Reinhard,
+    /// 按曝光值（EV，2 的幂）线性缩放后截断。
+    Exposure(f32),
+}
+
+/// 图片加载时的适配选项。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FitOptions {
+    /// 适配方式。
+    pub mode: FitMode,
+    /// 缩放滤波器（`image` 功能）。
+    #[cfg(feature = "image")]
+    pub filter: FilterType,
+    /// 留边背景色。
+    pub background: Color,
+    /// 16 位/浮点图片的色调映射方式。
+    pub tone_map: ToneMap,
+}
+
+impl Default for FitOptions {
+    fn default() -> Self {
+        Self {
+            mode: FitMode::Stretch,
+            #[cfg(feature = "image")]
+            filter: FilterType::Nearest,
+            background: Color::Black,
+            tone_map: ToneMap::default(),
+        }
+    }
+}
+
+impl FitOptions {
+    /// 使用指定适配方式创建（三角滤波，黑色背景）。
+    pub fn new(mode: FitMode) -> Self {
+        Self {
+            mode,
+            #[cfg(feature = "image")]
+            filter: FilterType::Triangle,
+            background: Color::Black,
+            tone_map: ToneMap::default(),
+        }
+    }
+
+    /// 设置缩放滤波器。
+    #[cfg(feature = "image")]
+    pub fn filter(mut self, filter: FilterType) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// 设置留边背景色。
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = color;
+        self
+    }
+
+    /// 设置 16 位/浮点图片的色调映射方式。
+    pub fn tone_map(mut self, tone_map: ToneMap) -> Self {
+        self.tone_map = tone_map;
+        self
+    }
+}
+
+/// 原始像素数据的行布局。
+///
+/// 默认为行紧密排列、从上到下；Windows DIB、OpenCV 的 ROI 和 GPU 回读的数据
+/// 行尾常有填充，DIB 还常从最下面一行开始存放。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RowLayout {
+    /// 相邻两行起始位置相差的字节数，`None` 表示行紧密排列。
+    pub stride: Option<usize>,
+    /// 数据的第一行是否为图片最下面一行。
+    pub bottom_up: bool,
+}
+
+impl RowLayout {
+    /// 行紧密排列、从上到下。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置行跨度（字节）。
+    pub fn stride(mut self, stride: usize) -> Self {
+        self.stride = Some(stride);
+        self
+    }
+
+    /// 设置是否从最下面一行开始存放。
+    pub fn bottom_up(mut self, bottom_up: bool) -> Self {
+        self.bottom_up = bottom_up;
+        self
+    }
+
+    /// 检查行跨度和数据长度，返回实际的行跨度。
+    ///
+    /// 尺寸大到所需字节数溢出 `usize` 时同样按数据太小处理。
+    pub(crate) fn resolve(
+        &self,
+        len: usize,
+        width: usize,
+        bytes_per_pixel: usize,
+        height: usize,
+    ) -> Result<usize, String> {
+        let too_small =
+            |needed: String| format!("数据太小: 需要 {} 字节，实际 {} 字节", needed, len);
+        let row_bytes = width
+            .checked_mul(bytes_per_pixel)
+            .ok_or_else(|| too_small(format!("{} x {}", width, bytes_per_pixel)))?;
+        let stride = self.stride.unwrap_or(row_bytes);
+        if stride < row_bytes {
+            return Err(format!(
+                "行跨度太小: 每行至少 {} 字节，实际 {} 字节",
+                row_bytes, stride
+            ));
+        }
+        // 最后一行不要求包含填充
+        let needed = match height {
+            0 => Some(0),
+            _ => stride
+                .checked_mul(height - 1)
+                .and_then(|n| n.checked_add(row_bytes)),
+        }
+        .ok_or_else(|| too_small(format!("{} x {} + {}", stride, height - 1, row_bytes)))?;
+        if len < needed {
+            return Err(too_small(needed.to_string()));
+        }
+        Ok(stride)
+    }
+
+    /// 图片第 `y` 行在数据中的起始位置。
+    pub(crate) fn row_offset(&self, y: usize, height: usize, stride: usize) -> usize {
+        if self.bottom_up {
+            (height - 1 - y) * stride
+        } else {
+            y * stride
+        }
+    }
+
+    pub(crate) fn is_packed_top_down(&self, row_bytes: usize) -> bool {
+        !self.bottom_up && self.stride.is_none_or(|stride| stride == row_bytes)
+    }
+}
+
+/// 发送前对画面副本运行的后处理回调。
+pub(crate) type PostProcessor = Box<dyn FnMut(&mut ImageBuffer) + Send>;
+
 /// 图片缓冲区（用于 ElectronBot 显示屏）。
 #[derive(Debug, Clone)]
 pub struct ImageBuffer {
     /// RGB/BGR 像素数据。
     pub data: Vec<u8>,
+    /// 绘图图元是否抗锯齿。
+    antialias: bool,
 }
 
 impl ImageBuffer {
@@ -17,9 +191,20 @@ impl ImageBuffer {
     pub fn new() -> Self {
         Self {
             data: vec![0u8; FRAME_SIZE],
+            antialias: false,
         }
     }
 
+    /// 开启或关闭绘图图元的抗锯齿（默认关闭）。
+    pub fn set_antialias(&mut self, enable: bool) {
+        self.antialias = enable;
+    }
+
+    /// 绘图图元是否抗锯齿。
+    pub fn antialias(&self) -> bool {
+        self.antialias
+    }
+
     /// 用颜色填充缓冲区。
     pub fn clear(&mut self, color: Color) {
         let (r, g, b) = color.bgr();
@@ -43,6 +228,27 @@ impl ImageBuffer {
         self.data[idx + 2] = r;
     }
 
+    /// 以指定不透明度（0.0-1.0）将颜色混合到单个像素。
+    pub fn blend_pixel(&mut self, x: usize, y: usize, color: Color, alpha: f32) {
+        if x >= FRAME_WIDTH || y >= FRAME_HEIGHT {
+            return;
+        }
+        let alpha = alpha.clamp(0.0, 1.0);
+        if alpha <= 0.0 {
+            return;
+        }
+        if alpha >= 1.0 {
+            self.set_pixel(x, y, color);
+            return;
+        }
+        let idx = (y * FRAME_WIDTH + x) * 3;
+        let (r, g, b) = color.bgr();
+        for (i, src) in [b, g, r].into_iter().enumerate() {
+            let dst = self.data[idx + i] as f32;
+            self.data[idx + i] = (dst + (src as f32 - dst) * alpha).round() as u8;
+        }
+    }
+
     /// 获取单个像素。
     pub fn get_pixel(&self, x: usize, y: usize) -> Option<Color> {
         if x >= FRAME_WIDTH || y >= FRAME_HEIGHT {
@@ -67,6 +273,10 @@ impl ImageBuffer {
 
     /// 画圆。
     pub fn draw_circle(&mut self, cx: usize, cy: usize, radius: usize, color: Color) {
+        if self.antialias {
+            self.fill_ellipse((cx as i32, cy as i32), radius, radius, color);
+            return;
+        }
         let r2 = radius * radius;
         for y in 0..FRAME_HEIGHT {
             for x in 0..FRAME_WIDTH {
@@ -80,23 +290,67 @@ impl ImageBuffer {
     }
 
     /// 从文件加载图片。
+    #[cfg(feature = "image")]
     pub fn load_from_file<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<(), String> {
         let img = image::open(path).map_err(|e| format!("打开图片失败: {}", e))?;
         self.load_from_image(&img);
         Ok(())
     }
 
-    /// 从 DynamicImage 加载。
+    /// 按适配选项从文件加载图片。
+    #[cfg(feature = "image")]
+    pub fn load_from_file_with<P: AsRef<std::path::Path>>(
+        &mut self,
+        path: P,
+        options: &FitOptions,
+    ) -> Result<(), String> {
+        let img = image::open(path).map_err(|e| format!("打开图片失败: {}", e))?;
+        self.load_from_image_with(&img, options);
+        Ok(())
+    }
+
+    /// 从 DynamicImage 加载（拉伸填满屏幕，最近邻插值）。
+    #[cfg(feature = "image")]
     pub fn load_from_image(&mut self, img: &DynamicImage) {
-        let resized = img.resize_exact(
-            FRAME_WIDTH as u32,
-            FRAME_HEIGHT as u32,
-            image::imageops::FilterType::Nearest,
-        );
-        let rgb = resized.to_rgb8();
-
-        for (i, pixel) in rgb.pixels().enumerate() {
-            let idx = i * 3;
+        self.load_from_image_with(img, &FitOptions::default());
+    }
+
+    /// 按适配选项从 DynamicImage 加载。
+    #[cfg(feature = "image")]
+    pub fn load_from_image_with(&mut self, img: &DynamicImage, options: &FitOptions) {
+        let (fw, fh) = (FRAME_WIDTH as u32, FRAME_HEIGHT as u32);
+
+        // 16 位和浮点图片先色调映射到 8 位（浮点缩放会截断超过 1.0 的高光）
+        let converted;
+        let img = if is_high_depth(img) {
+            converted = DynamicImage::ImageRgb8(tone_map(&img.to_rgb32f(), options.tone_map));
+            &converted
+        } else {
+            img
+        };
+
+        let rgb = match options.mode {
+            FitMode::Stretch => img.resize_exact(fw, fh, options.filter).to_rgb8(),
+            FitMode::Contain => img.resize(fw, fh, options.filter).to_rgb8(),
+            FitMode::Cover => img.resize_to_fill(fw, fh, options.filter).to_rgb8(),
+            FitMode::Center => img.to_rgb8(),
+        };
+
+        if rgb.width() != fw || rgb.height() != fh {
+            // 留边部分填充背景色
+            self.clear(options.background);
+        }
+
+        // 居中放置，超出屏幕的部分被裁剪
+        let offset_x = (fw as i64 - rgb.width() as i64) / 2;
+        let offset_y = (fh as i64 - rgb.height() as i64) / 2;
+        for (sx, sy, pixel) in rgb.enumerate_pixels() {
+            let x = sx as i64 + offset_x;
+            let y = sy as i64 + offset_y;
+            if x < 0 || y < 0 || x >= fw as i64 || y >= fh as i64 {
+                continue;
+            }
+            let idx = (y as usize * FRAME_WIDTH + x as usize) * 3;
             // 将 RGB 转换为 MCU 所需的 BGR
             self.data[idx] = pixel[2];
             self.data[idx + 1] = pixel[1];
@@ -104,6 +358,63 @@ impl ImageBuffer {
         }
     }
 
+    /// 按适配选项最近邻采样源图片，`pixel(x, y)` 返回源像素的 BGR 值。
+    ///
+    /// 只访问落在屏幕上的源像素，缩放和格式转换在同一遍完成。
+    pub(crate) fn load_nearest<F>(
+        &mut self,
+        width: usize,
+        height: usize,
+        options: &FitOptions,
+        pixel: F,
+    ) where
+        F: Fn(usize, usize) -> [u8; 3],
+    {
+        // 每个屏幕像素对应的源像素数
+        let (fw, fh) = (FRAME_WIDTH as f32, FRAME_HEIGHT as f32);
+        let (w, h) = (width as f32, height as f32);
+        let (scale_x, scale_y) = match options.mode {
+            FitMode::Stretch => (w / fw, h / fh),
+            FitMode::Contain => {
+                let s = (w / fw).max(h / fh);
+                (s, s)
+            }
+            FitMode::Cover => {
+                let s = (w / fw).min(h / fh);
+                (s, s)
+            }
+            FitMode::Center => (1.0, 1.0),
+        };
+        let map = |i: usize, screen: f32, scale: f32, size: f32| {
+            let s = ((i as f32 + 0.5 - screen / 2.0) * scale + size / 2.0).floor();
+            (s >= 0.0 && s < size).then_some(s as usize)
+        };
+        let columns: [Option<usize>; FRAME_WIDTH] = std::array::from_fn(|x| map(x, fw, scale_x, w));
+        // 背景色与 set_pixel 使用相同的字节顺序
+        let background = {
+            let (r, g, b) = options.background.bgr();
+            [b, g, r]
+        };
+
+        for (y, row) in self.data[..FRAME_SIZE]
+            .chunks_exact_mut(FRAME_WIDTH * 3)
+            .enumerate()
+        {
+            let sy = map(y, fh, scale_y, h);
+            for (out, sx) in row.chunks_exact_mut(3).zip(&columns) {
+                match (*sx, sy) {
+                    (Some(sx), Some(sy)) => out.copy_from_slice(&pixel(sx, sy)),
+                    _ => out.copy_from_slice(&background),
+                }
+            }
+        }
+    }
+
+    /// 直接复制一帧 BGR 原始数据（不做转换）。
+    pub fn load_frame(&mut self, frame: &[u8; FRAME_SIZE]) {
+        self.data[..FRAME_SIZE].copy_from_slice(frame);
+    }
+
     /// 从原始 RGB/BGR 数据加载。
     pub fn load_from_data(
         &mut self,
@@ -111,53 +422,133 @@ impl ImageBuffer {
         width: usize,
         height: usize,
     ) -> Result<(), String> {
-        if data.len() < width * height * 3 {
-            return Err("数据太小".to_string());
-        }
-
-        if width == FRAME_WIDTH && height == FRAME_HEIGHT {
-            // 直接复制并转换 BGR
-            for i in 0..FRAME_SIZE / 3 {
-                let dst_idx = i * 3;
-                let src_idx = i * 3;
-                self.data[dst_idx] = data[src_idx + 2]; // B -> R
-                self.data[dst_idx + 1] = data[src_idx + 1]; // G -> G
-                self.data[dst_idx + 2] = data[src_idx]; // R -> B
+        self.load_from_data_with(data, width, height, &RowLayout::default())
+    }
+
+    /// 按行布局 `layout` 从原始 RGB 数据加载（不缩放，居中放置）。
+    pub fn load_from_data_with(
+        &mut self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        layout: &RowLayout,
+    ) -> Result<(), String> {
+        let stride = layout.resolve(data.len(), width, 3, height)?;
+
+        // 超出屏幕的部分裁掉，不足的部分居中并用黑色填充
+        let min_w = width.min(FRAME_WIDTH);
+        let min_h = height.min(FRAME_HEIGHT);
+        let offset_x = (FRAME_WIDTH - min_w) / 2;
+        let offset_y = (FRAME_HEIGHT - min_h) / 2;
+
+        for (y, row) in self.data[..FRAME_SIZE]
+            .chunks_exact_mut(FRAME_WIDTH * 3)
+            .enumerate()
+        {
+            if y < offset_y || y >= offset_y + min_h {
+                row.fill(0);
+                continue;
             }
-        } else {
-            // 缩放到合适大小
-            let min_w = width.min(FRAME_WIDTH);
-            let min_h = height.min(FRAME_HEIGHT);
-            let offset_x = (FRAME_WIDTH - min_w) / 2;
-            let offset_y = (FRAME_HEIGHT - min_h) / 2;
-
-            for y in 0..FRAME_HEIGHT {
-                for x in 0..FRAME_WIDTH {
-                    let dst_idx = (y * FRAME_WIDTH + x) * 3;
-
-                    if x >= offset_x
-                        && x < offset_x + min_w
-                        && y >= offset_y
-                        && y < offset_y + min_h
-                    {
-                        let src_x = x - offset_x;
-                        let src_y = y - offset_y;
-                        let src_idx = (src_y * width + src_x) * 3;
-                        self.data[dst_idx] = data[src_idx + 2];
-                        self.data[dst_idx + 1] = data[src_idx + 1];
-                        self.data[dst_idx + 2] = data[src_idx];
-                    } else {
-                        self.data[dst_idx] = 0;
-                        self.data[dst_idx + 1] = 0;
-                        self.data[dst_idx + 2] = 0;
-                    }
-                }
+            let start = layout.row_offset(y - offset_y, height, stride);
+            let src = &data[start..start + min_w * 3];
+            row[..offset_x * 3].fill(0);
+            row[(offset_x + min_w) * 3..].fill(0);
+            // RGB 转换为 MCU 所需的 BGR
+            for (out, pixel) in row[offset_x * 3..(offset_x + min_w) * 3]
+                .chunks_exact_mut(3)
+                .zip(src.chunks_exact(3))
+            {
+                out[0] = pixel[2];
+                out[1] = pixel[1];
+                out[2] = pixel[0];
             }
         }
 
         Ok(())
     }
 
+    /// 按比例缩放亮度（0.0 为全黑，1.0 保持不变）。
+    pub fn scale_brightness(&mut self, factor: f32) {
+        let factor = factor.clamp(0.0, 1.0);
+        if factor >= 1.0 {
+            return;
+        }
+        for v in self.data.iter_mut() {
+            *v = (*v as f32 * factor).round() as u8;
+        }
+    }
+
+    /// Floyd–Steinberg 误差扩散抖动，量化到屏幕的 RGB565 色深。
+    ///
+    /// 每个通道量化后再扩展回 8 位，MCU 截断低位时得到的正是抖动后的颜色，
+    /// 可以消除平滑渐变中的色带。
+    pub fn dither_rgb565(&mut self) {
+        // 缓冲区字节顺序为 B, G, R
+        const BITS: [u32; 3] = [5, 6, 5];
+        let row_len = FRAME_WIDTH * 3;
+        let mut current = vec![0i16; row_len + 6];
+        let mut next = vec![0i16; row_len + 6];
+
+        for y in 0..FRAME_HEIGHT {
+            for x in 0..FRAME_WIDTH {
+                for (c, &bits) in BITS.iter().enumerate() {
+                    let idx = (y * FRAME_WIDTH + x) * 3 + c;
+                    // 误差缓冲区左右各留一个像素的边界
+                    let e = (x + 1) * 3 + c;
+                    let old = (self.data[idx] as i16 + current[e]).clamp(0, 255);
+                    let q = (old as u8) >> (8 - bits);
+                    let new = (q << (8 - bits)) | (q >> (2 * bits - 8));
+                    self.data[idx] = new;
+
+                    let err = old - new as i16;
+                    current[e + 3] += err * 7 / 16;
+                    next[e - 3] += err * 3 / 16;
+                    next[e] += err * 5 / 16;
+                    next[e + 3] += err / 16;
+                }
+            }
+            std::mem::swap(&mut current, &mut next);
+            next.fill(0);
+        }
+    }
+
+    /// 按安装方向变换画面（先顺时针旋转，再翻转）。
+    pub fn apply_orientation(&mut self, orientation: &Orientation) {
+        if orientation.is_identity() {
+            return;
+        }
+        // 旋转 90°/270° 依赖屏幕为正方形
+        const N: usize = FRAME_WIDTH;
+        debug_assert_eq!(FRAME_WIDTH, FRAME_HEIGHT);
+
+        let src = self.data.clone();
+        for y in 0..N {
+            for x in 0..N {
+                // 先撤销翻转，得到旋转后画面中的坐标
+                let rx = if orientation.flip_horizontal {
+                    N - 1 - x
+                } else {
+                    x
+                };
+                let ry = if orientation.flip_vertical {
+                    N - 1 - y
+                } else {
+                    y
+                };
+                // 再撤销旋转，得到原画面中的坐标
+                let (sx, sy) = match orientation.rotation {
+                    Rotation::Deg0 => (rx, ry),
+                    Rotation::Deg90 => (ry, N - 1 - rx),
+                    Rotation::Deg180 => (N - 1 - rx, N - 1 - ry),
+                    Rotation::Deg270 => (N - 1 - ry, rx),
+                };
+                let di = (y * N + x) * 3;
+                let si = (sy * N + sx) * 3;
+                self.data[di..di + 3].copy_from_slice(&src[si..si + 3]);
+            }
+        }
+    }
+
     /// 获取原始数据引用。
     pub fn as_data(&self) -> &[u8] {
         &self.data
@@ -168,6 +559,115 @@ impl ImageBuffer {
         &mut self.data
     }
 
+    /// 整帧 BGR 数据的可变引用，长度固定为 [`FRAME_SIZE`]。
+    pub fn frame_mut(&mut self) -> &mut [u8; FRAME_SIZE] {
+        (&mut self.data[..FRAME_SIZE])
+            .try_into()
+            .expect("图片缓冲区至少有一帧")
+    }
+
+    /// 第 `index` 段（0-3，每段 60 行）的原始数据，即一个同步周期发送的内容。
+    pub fn quarter(&self, index: usize) -> Option<&[u8]> {
+        self.data.chunks(QUARTER_SIZE).nth(index)
+    }
+
+    /// 第 `index` 段的原始数据可变引用。
+    pub fn quarter_mut(&mut self, index: usize) -> Option<&mut [u8]> {
+        self.data.chunks_mut(QUARTER_SIZE).nth(index)
+    }
+
+    /// 用 BGR 数据（60 行，43200 字节）替换第 `index` 段。
+    pub fn set_quarter(&mut self, index: usize, data: &[u8]) -> Result<(), String> {
+        if data.len() != QUARTER_SIZE {
+            return Err(format!(
+                "分段数据长度应为 {} 字节，实际为 {} 字节",
+                QUARTER_SIZE,
+                data.len()
+            ));
+        }
+        let quarter = self
+            .quarter_mut(index)
+            .ok_or_else(|| format!("分段序号无效: {}", index))?;
+        quarter.copy_from_slice(data);
+        Ok(())
+    }
+
+    /// 转换为 RGB 图片（BGR 到 RGB 通道反转）。
+    #[cfg(feature = "image")]
+    pub fn to_rgb_image(&self) -> RgbImage {
+        let mut rgb = self.data.to_vec();
+        for pixel in rgb.chunks_exact_mut(3) {
+            pixel.swap(0, 2);
+        }
+        RgbImage::from_raw(FRAME_WIDTH as u32, FRAME_HEIGHT as u32, rgb)
+            .expect("帧大小与屏幕尺寸一致")
+    }
+
+    /// 保存为图片文件，格式由扩展名决定（如 `.png`）。
+    #[cfg(feature = "image")]
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), String> {
+        self.to_rgb_image()
+            .save(path)
+            .map_err(|e| format!("保存图片失败: {}", e))
+    }
+
+    /// 生成终端预览：`width` 列（1-240）的 24 位真彩色半块字符画。
+    ///
+    /// 每个字符用 `▀` 显示上下两个像素（前景色为上方、背景色为下方），
+    /// 行数为 `width / 2`（向上取整），在正方形字符格的终端中近似保持比例。
+    /// 每格取对应区域的平均颜色，每行以 `\x1b[0m` 复位颜色并换行。
+    ///
+    /// ```
+    /// use electron_bot::{Color, ImageBuffer};
+    ///
+    /// let mut buffer = ImageBuffer::new();
+    /// buffer.fill_rect(0, 0, 240, 240, Color::Red);
+    /// print!("{}", buffer.render_ansi(60));
+    /// ```
+    pub fn render_ansi(&self, width: usize) -> String {
+        let cols = width.clamp(1, FRAME_WIDTH);
+        let rows = cols.div_ceil(2);
+        let mut out = String::with_capacity(rows * (cols * 40 + 8));
+        for row in 0..rows {
+            for col in 0..cols {
+                let (tr, tg, tb) = self.average_block(col, row * 2, cols);
+                let (br, bg, bb) = self.average_block(col, row * 2 + 1, cols);
+                out.push_str(&format!(
+                    "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m▀",
+                    tr, tg, tb, br, bg, bb
+                ));
+            }
+            out.push_str("\x1b[0m\n");
+        }
+        out
+    }
+
+    /// 预览网格（`cells` x `cells`）中第 (`col`, `row`) 格的平均 RGB 颜色，超出范围为黑色。
+    fn average_block(&self, col: usize, row: usize, cells: usize) -> (u8, u8, u8) {
+        let x0 = col * FRAME_WIDTH / cells;
+        let x1 = ((col + 1) * FRAME_WIDTH / cells).max(x0 + 1);
+        let y0 = row * FRAME_HEIGHT / cells;
+        let y1 = ((row + 1) * FRAME_HEIGHT / cells).max(y0 + 1);
+        if y0 >= FRAME_HEIGHT {
+            return (0, 0, 0);
+        }
+        let mut sum = [0u32; 3];
+        for y in y0..y1.min(FRAME_HEIGHT) {
+            for x in x0..x1.min(FRAME_WIDTH) {
+                let idx = (y * FRAME_WIDTH + x) * 3;
+                sum[0] += self.data[idx + 2] as u32;
+                sum[1] += self.data[idx + 1] as u32;
+                sum[2] += self.data[idx] as u32;
+            }
+        }
+        let count = ((y1.min(FRAME_HEIGHT) - y0) * (x1.min(FRAME_WIDTH) - x0)) as u32;
+        (
+            (sum[0] / count) as u8,
+            (sum[1] / count) as u8,
+            (sum[2] / count) as u8,
+        )
+    }
+
     /// 生成随机色块测试图案（40x40 色块平铺）。
     ///
     /// # 参数
@@ -185,6 +685,7 @@ impl ImageBuffer {
     /// let mut buffer = ImageBuffer::new();
     /// buffer.render_test_pattern(&mut rng, 40);
     /// ```
+    #[cfg(feature = "image")]
     pub fn render_test_pattern<R: Rng>(&mut self, rng: &mut R, block_size: usize) {
         // 清空背景为黑色
         self.clear(Color::Black);
@@ -205,6 +706,7 @@ impl ImageBuffer {
     }
 
     /// 生成随机色块测试图案（使用默认随机源）。
+    #[cfg(feature = "image")]
     pub fn render_test_pattern_with_rng(block_size: usize) -> Self {
         let mut rng = rand::thread_rng();
         let mut buffer = Self::new();
@@ -213,6 +715,47 @@ impl ImageBuffer {
     }
 }
 
+/// 是否为 16 位或浮点通道的图片。
+#[cfg(feature = "image")]
+fn is_high_depth(img: &DynamicImage) -> bool {
+    matches!(
+        img,
+        DynamicImage::ImageLuma16(_)
+            | DynamicImage::ImageLumaA16(_)
+            | DynamicImage::ImageRgb16(_)
+            | DynamicImage::ImageRgba16(_)
+            | DynamicImage::ImageRgb32F(_)
+            | DynamicImage::ImageRgba32F(_)
+    )
+}
+
+/// 将浮点图片色调映射到 8 位。
+#[cfg(feature = "image")]
+fn tone_map(img: &image::Rgb32FImage, tone_map: ToneMap) -> image::RgbImage {
+    let map: Box<dyn Fn(f32) -> f32> = match tone_map {
+        ToneMap::Reinhard => {
+            let white = img
+                .pixels()
+                .flat_map(|p| p.0)
+                .filter(|v| v.is_finite())
+                .fold(1.0f32, f32::max);
+            let white_sq = white * white;
+            Box::new(move |v| v * (1.0 + v / white_sq) / (1.0 + v))
+        }
+        ToneMap::Exposure(ev) => {
+            let scale = 2f32.powf(ev);
+            Box::new(move |v| v * scale)
+        }
+    };
+    image::RgbImage::from_fn(img.width(), img.height(), |x, y| {
+        let p = img.get_pixel(x, y);
+        image::Rgb(p.0.map(|v| {
+            let v = if v.is_finite() { v.max(0.0) } else { 0.0 };
+            (map(v).clamp(0.0, 1.0) * 255.0).round() as u8
+        }))
+    })
+}
+
 impl Default for ImageBuffer {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,119 @@
+//! ElectronBot 库的灰度和索引色输入。
+//!
+//! 每个像素只占 1 字节，通过 256 项查找表直接展开为 BGR 帧缓冲区，
+//! 数据量是 RGB 的三分之一，适合在性能较弱的主机上生成滚动文字、等离子等程序化画面。
+//! 只修改调色板就能实现调色板循环动画，不需要重新生成像素。
+
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_SIZE, FRAME_WIDTH};
+use crate::modules::image::{FitOptions, ImageBuffer};
+use crate::modules::types::Color;
+
+/// 256 色调色板，预先转换为 MCU 所需的 BGR 字节。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedPalette {
+    entries: [[u8; 3]; 256],
+}
+
+impl IndexedPalette {
+    /// 按顺序使用 `colors` 作为索引 0、1、2…的颜色，不足 256 项的部分为黑色，多余的忽略。
+    pub fn new(colors: &[Color]) -> Self {
+        let mut palette = Self {
+            entries: [[0; 3]; 256],
+        };
+        for (index, color) in colors.iter().take(256).enumerate() {
+            palette.set(index as u8, *color);
+        }
+        palette
+    }
+
+    /// 灰阶调色板：索引即亮度。
+    pub fn grayscale() -> Self {
+        Self {
+            entries: std::array::from_fn(|i| [i as u8; 3]),
+        }
+    }
+
+    /// 从连续的 RGB 三元组创建（GIF、PNG 等格式的调色板布局），末尾不完整的三元组忽略。
+    pub fn from_rgb(rgb: &[u8]) -> Self {
+        let colors: Vec<Color> = rgb
+            .chunks_exact(3)
+            .map(|c| Color::Custom(c[0], c[1], c[2]))
+            .collect();
+        Self::new(&colors)
+    }
+
+    /// 修改一项颜色。
+    pub fn set(&mut self, index: u8, color: Color) {
+        let (r, g, b) = color.rgb();
+        self.entries[index as usize] = [b, g, r];
+    }
+
+    /// 读取一项颜色。
+    pub fn get(&self, index: u8) -> Color {
+        let [b, g, r] = self.entries[index as usize];
+        Color::Custom(r, g, b)
+    }
+
+    /// 调色板整体向前循环移动 `shift` 项（索引 `i` 的颜色变为原来索引 `i + shift` 的颜色）。
+    pub fn rotate(&mut self, shift: u8) {
+        self.entries.rotate_left(shift as usize);
+    }
+}
+
+impl Default for IndexedPalette {
+    fn default() -> Self {
+        Self::grayscale()
+    }
+}
+
+impl ImageBuffer {
+    /// 从 8 位灰度数据加载（最近邻缩放，按 `options` 的适配方式和背景色）。
+    pub fn load_from_gray(
+        &mut self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        options: &FitOptions,
+    ) -> Result<(), String> {
+        self.load_from_indexed(data, width, height, &IndexedPalette::grayscale(), options)
+    }
+
+    /// 从 8 位索引数据加载，每个字节按 `palette` 展开为颜色（最近邻缩放，按 `options` 的
+    /// 适配方式和背景色）。
+    ///
+    /// 尺寸与屏幕相同时逐字节查表，不经过缩放。
+    pub fn load_from_indexed(
+        &mut self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        palette: &IndexedPalette,
+        options: &FitOptions,
+    ) -> Result<(), String> {
+        if width == 0 || height == 0 {
+            return Err("索引图片尺寸不能为 0".to_string());
+        }
+        match width.checked_mul(height) {
+            Some(needed) if data.len() >= needed => {}
+            needed => {
+                return Err(format!(
+                    "索引数据太小: 需要 {} 字节，实际 {} 字节",
+                    needed.map_or_else(|| format!("{} x {}", width, height), |n| n.to_string()),
+                    data.len()
+                ))
+            }
+        }
+
+        let entries = &palette.entries;
+        if width == FRAME_WIDTH && height == FRAME_HEIGHT {
+            for (out, &index) in self.data[..FRAME_SIZE].chunks_exact_mut(3).zip(data) {
+                out.copy_from_slice(&entries[index as usize]);
+            }
+        } else {
+            self.load_nearest(width, height, options, |x, y| {
+                entries[data[y * width + x] as usize]
+            });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,229 @@
+//! ElectronBot 库的键盘点动。
+//!
+//! [`KeyboardJog`] 把按键转换为关节的增量命令：WASD 控制头部俯仰和身体旋转，方向键控制双臂，
+//! `+`/`-` 调整步长。命令写入 [`ServoSource`]，由它限制角度范围和转动速度。
+//! [`KeyboardTeleop`] 把终端切换到原始模式并每帧读取按键，适合标定和快速测试。
+
+use std::io::Write;
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+
+pub use crossterm::event::KeyCode;
+
+use crate::modules::constants::{JOINT_BODY, JOINT_HEAD};
+use crate::modules::image::ImageBuffer;
+use crate::modules::servo::ServoSource;
+use crate::modules::source::FrameSource;
+use crate::modules::types::JointAngles;
+
+/// 可选的步长（度），`+`/`-` 在其中切换。
+const STEPS: [f32; 6] = [0.5, 1.0, 2.0, 5.0, 10.0, 20.0];
+
+/// 按键处理的结果。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JogResult {
+    /// 按键已处理。
+    Handled,
+    /// 退出（`q` 或 `Esc`）。
+    Quit,
+    /// 没有映射的按键。
+    Ignored,
+}
+
+/// 键盘点动：按键到关节增量命令的映射。
+///
+/// | 按键 | 动作 |
+/// |------|------|
+/// | `W`/`S` | 头部俯仰 |
+/// | `A`/`D` | 身体旋转 |
+/// | `↑`/`↓` | 双臂前后摆动 |
+/// | `←`/`→` | 双臂向外/向内张开 |
+/// | `+`/`-` | 增大/减小步长 |
+/// | `0` | 回到初始姿态 |
+/// | 空格 | 使能或释放舵机 |
+/// | `q`/`Esc` | 退出 |
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyboardJog {
+    step: usize,
+}
+
+impl Default for KeyboardJog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeyboardJog {
+    /// 创建点动映射（步长 5 度）。
+    pub fn new() -> Self {
+        Self { step: 3 }
+    }
+
+    /// 当前步长（度）。
+    pub fn step(&self) -> f32 {
+        STEPS[self.step]
+    }
+
+    /// 处理一个按键，把增量命令写入 `servo`。
+    pub fn handle(&mut self, servo: &mut ServoSource, key: KeyCode) -> JogResult {
+        let step = self.step();
+        let jogs: &[(usize, f32)] = match key {
+            KeyCode::Char('w' | 'W') => &[(JOINT_HEAD, step)],
+            KeyCode::Char('s' | 'S') => &[(JOINT_HEAD, -step)],
+            KeyCode::Char('a' | 'A') => &[(JOINT_BODY, step)],
+            KeyCode::Char('d' | 'D') => &[(JOINT_BODY, -step)],
+            KeyCode::Up => &[(2, step), (4, step)],
+            KeyCode::Down => &[(2, -step), (4, -step)],
+            KeyCode::Left => &[(1, step), (3, -step)],
+            KeyCode::Right => &[(1, -step), (3, step)],
+            KeyCode::Char('+' | '=') => {
+                self.step = (self.step + 1).min(STEPS.len() - 1);
+                &[]
+            }
+            KeyCode::Char('-' | '_') => {
+                self.step = self.step.saturating_sub(1);
+                &[]
+            }
+            KeyCode::Char('0') => {
+                servo.set_pose(&JointAngles::new());
+                &[]
+            }
+            KeyCode::Char(' ') => {
+                servo.set_enabled(!servo.is_enabled());
+                &[]
+            }
+            KeyCode::Char('q' | 'Q') | KeyCode::Esc => return JogResult::Quit,
+            _ => return JogResult::Ignored,
+        };
+        for &(joint, delta) in jogs {
+            servo.jog(joint, delta);
+        }
+        JogResult::Handled
+    }
+
+    /// 一行状态：步长、舵机使能和各关节的目标角度。
+    pub fn status(&self, servo: &ServoSource) -> String {
+        let angles = servo.target().as_array();
+        format!(
+            "步长 {:>4}° | 舵机 {} | 头部 {:>6.1} | 左臂 {:>6.1} {:>6.1} | 右臂 {:>6.1} {:>6.1} | 身体 {:>6.1}",
+            self.step(),
+            if servo.is_enabled() { "使能" } else { "释放" },
+            angles[JOINT_HEAD],
+            angles[1],
+            angles[2],
+            angles[3],
+            angles[4],
+            angles[JOINT_BODY],
+        )
+    }
+}
+
+/// 终端键盘遥控帧源：创建时把终端切换到原始模式，释放时恢复。
+///
+/// 每帧读取全部按键并在同一行刷新状态，按 `q` 或 `Esc` 结束播放。
+#[derive(Debug)]
+pub struct KeyboardTeleop {
+    jog: KeyboardJog,
+    servo: ServoSource,
+    show_status: bool,
+    /// 下一次更新时刷新状态行。
+    dirty: bool,
+    quit: bool,
+}
+
+impl KeyboardTeleop {
+    /// 切换终端到原始模式并创建。
+    pub fn new() -> Result<Self, String> {
+        terminal::enable_raw_mode().map_err(|e| format!("切换终端原始模式失败: {}", e))?;
+        Ok(Self {
+            jog: KeyboardJog::new(),
+            servo: ServoSource::new(),
+            show_status: true,
+            dirty: true,
+            quit: false,
+        })
+    }
+
+    /// 设置舵机帧源（角度范围、最大速度、表情样式）。
+    pub fn servo(mut self, servo: ServoSource) -> Self {
+        self.servo = servo;
+        self
+    }
+
+    /// 不在终端显示状态。
+    pub fn quiet(mut self) -> Self {
+        self.show_status = false;
+        self
+    }
+
+    /// 舵机帧源。
+    pub fn servo_mut(&mut self) -> &mut ServoSource {
+        &mut self.servo
+    }
+
+    /// 读取全部等待处理的按键，返回是否收到退出键。
+    pub fn update(&mut self) -> bool {
+        let mut changed = std::mem::take(&mut self.dirty);
+        while event::poll(Duration::ZERO).unwrap_or(false) {
+            let Ok(Event::Key(KeyEvent {
+                code,
+                kind,
+                modifiers,
+                ..
+            })) = event::read()
+            else {
+                continue;
+            };
+            if kind == KeyEventKind::Release {
+                continue;
+            }
+            // 原始模式下 Ctrl+C 不会产生信号
+            if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('c') {
+                self.quit = true;
+                break;
+            }
+            match self.jog.handle(&mut self.servo, code) {
+                JogResult::Handled => changed = true,
+                JogResult::Quit => {
+                    self.quit = true;
+                    break;
+                }
+                JogResult::Ignored => {}
+            }
+        }
+        if self.show_status && (changed || self.quit) {
+            let mut stdout = std::io::stdout();
+            let _ = write!(stdout, "\r{}", self.jog.status(&self.servo));
+            if self.quit {
+                let _ = write!(stdout, "\r\n");
+            }
+            let _ = stdout.flush();
+        }
+        self.quit
+    }
+}
+
+impl Drop for KeyboardTeleop {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+impl FrameSource for KeyboardTeleop {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        if self.update() {
+            return false;
+        }
+        self.servo.next_frame(buffer)
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        self.servo.frame_interval()
+    }
+
+    fn joint_angles(&self) -> Option<JointAngles> {
+        self.servo.joint_angles()
+    }
+}
@@ -0,0 +1,273 @@
+//! ElectronBot 库的正运动学和碰撞保护。
+//!
+//! [`BodyModel`] 用简化的几何描述机器人：身体是长方体，头部是球，手臂是从肩部伸出的线段。
+//! 手臂关节先绕肩部前后摆动（关节 2 / 4，正值向前抬起），再向外侧展开（关节 1 / 3，正值远离身体）；
+//! 身体旋转和头部俯仰不改变手臂相对身体的位置。
+//!
+//! [`CollisionGuard`] 用这个模型检查关节目标：手臂向内收得太多会打到身体，
+//! 抬过头顶再内收会打到头部。拒绝模式下 `set_joint_angles` 返回错误，
+//! 限制模式下把手臂的内收角度减小到刚好不碰撞。默认尺寸按原版外壳估计，
+//! 改装过的机器人可以修改 [`BodyModel`] 的字段。
+
+use crate::modules::types::JointAngles;
+
+/// 左臂展开和摆动关节的索引。
+const LEFT_ARM: (usize, usize) = (1, 2);
+
+/// 右臂展开和摆动关节的索引。
+const RIGHT_ARM: (usize, usize) = (3, 4);
+
+/// 沿手臂检查的采样点数。
+const ARM_SAMPLES: usize = 8;
+
+/// 手臂。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arm {
+    /// 左臂（关节 1、2）。
+    Left,
+    /// 右臂（关节 3、4）。
+    Right,
+}
+
+impl Arm {
+    /// 展开和摆动关节的索引。
+    pub fn joints(self) -> (usize, usize) {
+        match self {
+            Arm::Left => LEFT_ARM,
+            Arm::Right => RIGHT_ARM,
+        }
+    }
+
+    /// 肩部在 x 方向的符号（左负右正）。
+    fn side(self) -> f32 {
+        match self {
+            Arm::Left => -1.0,
+            Arm::Right => 1.0,
+        }
+    }
+}
+
+/// 会被手臂碰到的部位。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyPart {
+    /// 头部。
+    Head,
+    /// 身体。
+    Body,
+}
+
+/// 一次碰撞：哪只手臂碰到了哪个部位。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Collision {
+    /// 手臂。
+    pub arm: Arm,
+    /// 被碰到的部位。
+    pub part: BodyPart,
+}
+
+impl std::fmt::Display for Collision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let arm = match self.arm {
+            Arm::Left => "左臂",
+            Arm::Right => "右臂",
+        };
+        let part = match self.part {
+            BodyPart::Head => "头部",
+            BodyPart::Body => "身体",
+        };
+        write!(f, "{}会碰到{}", arm, part)
+    }
+}
+
+/// 机器人的简化几何（毫米）。
+///
+/// 坐标原点在两肩连线的中点，x 向右、y 向上、z 向前。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BodyModel {
+    /// 肩关节到中线的距离。
+    pub shoulder_offset: f32,
+    /// 手臂长度。
+    pub arm_length: f32,
+    /// 身体的半宽（x）。
+    pub body_half_width: f32,
+    /// 身体的半厚（z）。
+    pub body_half_depth: f32,
+    /// 身体从肩部向下的高度。
+    pub body_height: f32,
+    /// 头部球心在肩部上方的高度。
+    pub head_height: f32,
+    /// 头部半径。
+    pub head_radius: f32,
+}
+
+impl BodyModel {
+    /// 按原版外壳估计的尺寸。
+    pub fn new() -> Self {
+        Self {
+            shoulder_offset: 24.0,
+            arm_length: 30.0,
+            body_half_width: 14.0,
+            body_half_depth: 12.0,
+            body_height: 45.0,
+            head_height: 25.0,
+            head_radius: 18.0,
+        }
+    }
+
+    /// 肩关节的位置。
+    pub fn shoulder(&self, arm: Arm) -> [f32; 3] {
+        [arm.side() * self.shoulder_offset, 0.0, 0.0]
+    }
+
+    /// 正运动学：`angles` 下手臂末端的位置。
+    pub fn hand(&self, arm: Arm, angles: &JointAngles) -> [f32; 3] {
+        self.arm_point(arm, angles, 1.0)
+    }
+
+    /// 手臂上距肩部 `t`（0-1，按臂长比例）处的位置。
+    fn arm_point(&self, arm: Arm, angles: &JointAngles, t: f32) -> [f32; 3] {
+        let (roll, pitch) = arm.joints();
+        let roll = angles.as_array()[roll].to_radians();
+        let pitch = angles.as_array()[pitch].to_radians();
+        let length = self.arm_length * t;
+        let [x, y, z] = self.shoulder(arm);
+        [
+            x + arm.side() * roll.sin() * length,
+            y - roll.cos() * pitch.cos() * length,
+            z + roll.cos() * pitch.sin() * length,
+        ]
+    }
+
+    /// 手臂在 `angles` 下碰到的部位，`margin` 为额外留出的间隙。
+    pub fn arm_collision(&self, arm: Arm, angles: &JointAngles, margin: f32) -> Option<BodyPart> {
+        for i in 1..=ARM_SAMPLES {
+            let [x, y, z] = self.arm_point(arm, angles, i as f32 / ARM_SAMPLES as f32);
+            let (dx, dy) = (x, y - self.head_height);
+            if (dx * dx + dy * dy + z * z).sqrt() < self.head_radius + margin {
+                return Some(BodyPart::Head);
+            }
+            if x.abs() < self.body_half_width + margin
+                && z.abs() < self.body_half_depth + margin
+                && y < margin
+                && y > -self.body_height - margin
+            {
+                return Some(BodyPart::Body);
+            }
+        }
+        None
+    }
+}
+
+impl Default for BodyModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 碰撞保护的处理方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GuardMode {
+    /// 拒绝会碰撞的目标（默认）。
+    #[default]
+    Reject,
+    /// 减小手臂的内收角度直到不碰撞。
+    Clamp,
+}
+
+/// 自碰撞保护。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CollisionGuard {
+    /// 几何模型。
+    pub model: BodyModel,
+    /// 处理方式。
+    pub mode: GuardMode,
+    /// 额外留出的间隙（毫米）。
+    pub margin: f32,
+}
+
+impl CollisionGuard {
+    /// 默认保护：原版尺寸，拒绝模式，间隙 2 毫米。
+    pub fn new() -> Self {
+        Self {
+            model: BodyModel::new(),
+            mode: GuardMode::Reject,
+            margin: 2.0,
+        }
+    }
+
+    /// 设置几何模型。
+    pub fn model(mut self, model: BodyModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// 设置处理方式。
+    pub fn mode(mut self, mode: GuardMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// 设置间隙。
+    pub fn margin(mut self, millimeters: f32) -> Self {
+        self.margin = millimeters.max(0.0);
+        self
+    }
+
+    /// 检查关节目标，返回第一个碰撞。
+    pub fn check(&self, angles: &JointAngles) -> Result<(), Collision> {
+        for arm in [Arm::Left, Arm::Right] {
+            if let Some(part) = self.model.arm_collision(arm, angles, self.margin) {
+                return Err(Collision { arm, part });
+            }
+        }
+        Ok(())
+    }
+
+    /// 把会碰撞的手臂的展开角度向 0 调整到刚好不碰撞，其他关节不变。
+    ///
+    /// 展开角度为 0 仍然碰撞时（自定义模型）使用 0。
+    pub fn clamp(&self, angles: &JointAngles) -> JointAngles {
+        let mut angles = angles.clone();
+        for arm in [Arm::Left, Arm::Right] {
+            if self
+                .model
+                .arm_collision(arm, &angles, self.margin)
+                .is_none()
+            {
+                continue;
+            }
+            let (roll, _) = arm.joints();
+            // 在目标展开角度（碰撞）和 0 之间二分查找最接近目标的安全角度
+            let (mut unsafe_roll, mut safe_roll) = (angles.as_array()[roll], 0.0);
+            for _ in 0..16 {
+                let middle = (unsafe_roll + safe_roll) / 2.0;
+                angles.as_array_mut()[roll] = middle;
+                if self
+                    .model
+                    .arm_collision(arm, &angles, self.margin)
+                    .is_some()
+                {
+                    unsafe_roll = middle;
+                } else {
+                    safe_roll = middle;
+                }
+            }
+            angles.as_array_mut()[roll] = safe_roll;
+        }
+        angles
+    }
+
+    /// 按处理方式检查目标：拒绝模式下返回碰撞，限制模式下返回调整后的角度。
+    pub fn apply(&self, angles: &JointAngles) -> Result<JointAngles, Collision> {
+        match self.mode {
+            GuardMode::Reject => self.check(angles).map(|()| angles.clone()),
+            GuardMode::Clamp => Ok(self.clamp(angles)),
+        }
+    }
+}
+
+impl Default for CollisionGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
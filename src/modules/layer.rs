@@ -0,0 +1,227 @@
+//! ElectronBot 库的离屏图层和 z 顺序合成。
+//!
+//! 背景、控件和表情元素可以分别绘制到独立的图层，
+//! 同步前按 z 顺序合成到最终的 [`ImageBuffer`]。
+//! 图层栈可以设置平铺的背景图块，铺在所有图层之下。
+
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_WIDTH};
+use crate::modules::draw::{Point, Rect};
+use crate::modules::image::ImageBuffer;
+use crate::modules::sprite::Sprite;
+use crate::modules::types::Color;
+
+/// 离屏图层。
+#[derive(Debug, Clone)]
+pub struct Layer {
+    name: String,
+    /// 图层内容。
+    pub buffer: ImageBuffer,
+    /// z 顺序，数值大的在上层。
+    pub z: i32,
+    /// 是否可见。
+    pub visible: bool,
+    /// 整体不透明度（0.0-1.0）。
+    pub opacity: f32,
+    /// 透明色键，与之相同的像素不参与合成。
+    pub color_key: Option<Color>,
+}
+
+impl Layer {
+    /// 创建空图层（黑色、可见、完全不透明、无透明色）。
+    pub fn new(name: &str, z: i32) -> Self {
+        Self {
+            name: name.to_string(),
+            buffer: ImageBuffer::new(),
+            z,
+            visible: true,
+            opacity: 1.0,
+            color_key: None,
+        }
+    }
+
+    /// 图层名称。
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 设置整体不透明度（自动限制在 0.0-1.0）。
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+
+    /// 将图层合成到目标缓冲区。
+    pub fn composite_onto(&self, target: &mut ImageBuffer) {
+        let opacity = self.opacity.clamp(0.0, 1.0);
+        if !self.visible || opacity <= 0.0 {
+            return;
+        }
+        // 与 set_pixel 使用相同的字节顺序
+        let key = self.color_key.map(|c| {
+            let (r, g, b) = c.bgr();
+            [b, g, r]
+        });
+
+        if key.is_none() && opacity >= 1.0 {
+            target.data.copy_from_slice(&self.buffer.data);
+            return;
+        }
+
+        for (dst, src) in target
+            .data
+            .chunks_exact_mut(3)
+            .zip(self.buffer.data.chunks_exact(3))
+        {
+            if key.is_some_and(|k| src == k) {
+                continue;
+            }
+            if opacity >= 1.0 {
+                dst.copy_from_slice(src);
+            } else {
+                for (d, &s) in dst.iter_mut().zip(src) {
+                    let dv = *d as f32;
+                    *d = (dv + (s as f32 - dv) * opacity).round() as u8;
+                }
+            }
+        }
+    }
+}
+
+/// 按 z 顺序合成的图层栈。
+#[derive(Debug, Clone, Default)]
+pub struct LayerStack {
+    layers: Vec<Layer>,
+    /// 平铺背景图块和偏移。
+    background: Option<(Sprite, Point)>,
+}
+
+impl LayerStack {
+    /// 创建空图层栈。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加图层并返回其可变引用；同名图层已存在时返回已有图层。
+    pub fn add_layer(&mut self, name: &str, z: i32) -> &mut Layer {
+        let index = match self.index_of(name) {
+            Some(index) => index,
+            None => {
+                self.layers.push(Layer::new(name, z));
+                self.layers.len() - 1
+            }
+        };
+        &mut self.layers[index]
+    }
+
+    /// 移除图层。
+    pub fn remove_layer(&mut self, name: &str) -> Option<Layer> {
+        self.index_of(name).map(|index| self.layers.remove(index))
+    }
+
+    /// 获取图层。
+    pub fn layer(&self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|l| l.name == name)
+    }
+
+    /// 获取图层的可变引用。
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut Layer> {
+        self.layers.iter_mut().find(|l| l.name == name)
+    }
+
+    /// 获取图层的绘图缓冲区。
+    pub fn buffer_mut(&mut self, name: &str) -> Option<&mut ImageBuffer> {
+        self.layer_mut(name).map(|l| &mut l.buffer)
+    }
+
+    /// 设置图层可见性，图层不存在时返回 `false`。
+    pub fn set_visible(&mut self, name: &str, visible: bool) -> bool {
+        match self.layer_mut(name) {
+            Some(layer) => {
+                layer.visible = visible;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 设置图层不透明度，图层不存在时返回 `false`。
+    pub fn set_opacity(&mut self, name: &str, opacity: f32) -> bool {
+        match self.layer_mut(name) {
+            Some(layer) => {
+                layer.set_opacity(opacity);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 图层数量。
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// 是否没有图层。
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// 按 z 顺序遍历图层（z 相同时按添加顺序）。
+    pub fn iter(&self) -> impl Iterator<Item = &Layer> {
+        let mut order: Vec<&Layer> = self.layers.iter().collect();
+        order.sort_by_key(|l| l.z);
+        order.into_iter()
+    }
+
+    /// 设置平铺背景：合成时先用 `tile` 铺满整个屏幕，再叠加图层。
+    pub fn set_background(&mut self, tile: Sprite) {
+        let offset = self.background_offset();
+        self.background = Some((tile, offset));
+    }
+
+    /// 设置平铺背景的偏移，逐帧改变可以做出滚动背景。
+    pub fn set_background_offset(&mut self, offset: Point) {
+        if let Some((_, current)) = &mut self.background {
+            *current = offset;
+        }
+    }
+
+    /// 平铺背景的偏移，没有背景时为 `(0, 0)`。
+    pub fn background_offset(&self) -> Point {
+        self.background
+            .as_ref()
+            .map_or((0, 0), |(_, offset)| *offset)
+    }
+
+    /// 平铺背景图块。
+    pub fn background(&self) -> Option<&Sprite> {
+        self.background.as_ref().map(|(tile, _)| tile)
+    }
+
+    /// 移除平铺背景。
+    pub fn clear_background(&mut self) {
+        self.background = None;
+    }
+
+    /// 将所有可见图层按 z 顺序合成到目标缓冲区（覆盖在目标现有内容之上）。
+    ///
+    /// 设置了平铺背景时先铺背景。
+    pub fn composite_onto(&self, target: &mut ImageBuffer) {
+        if let Some((tile, offset)) = &self.background {
+            let screen = Rect::new(0, 0, FRAME_WIDTH, FRAME_HEIGHT);
+            target.fill_pattern_offset(screen, tile, *offset);
+        }
+        for layer in self.iter() {
+            layer.composite_onto(target);
+        }
+    }
+
+    /// 合成到新的缓冲区（没有平铺背景时为黑色背景）。
+    pub fn flatten(&self) -> ImageBuffer {
+        let mut target = ImageBuffer::new();
+        self.composite_onto(&mut target);
+        target
+    }
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|l| l.name == name)
+    }
+}
@@ -0,0 +1,397 @@
+//! ElectronBot 库的动作混合。
+//!
+//! [`AnimationMixer`] 同时播放多条关节动作轨道，按从下到上的顺序混合：
+//! 每条轨道有 6 个关节的权重，覆盖模式按权重把下层的角度插值到本轨道的角度，
+//! 叠加模式把本轨道的角度乘以权重后加到下层上（适合呼吸、抖动等偏移量）。
+//! 例如底层播放空闲动作，上层的挥手只给手臂关节权重，头部和身体仍然跟随空闲动作。
+//!
+//! 替换轨道的动作时在 `crossfade` 时间内从旧动作过渡到新动作，
+//! 停止或播放结束的轨道淡出后移除，不会出现角度跳变。
+//! 画面由各轨道按顺序渲染到同一个缓冲区，上层覆盖下层。
+//!
+//! ```
+//! use std::time::Duration;
+//! use electron_bot::{AnimationMixer, Gesture, JointAngles, TrackOptions};
+//!
+//! let mut mixer = AnimationMixer::new(JointAngles::new());
+//! mixer.add_track("idle", Gesture::named("nod").unwrap(), TrackOptions::new());
+//! mixer.add_track(
+//!     "arms",
+//!     Gesture::named("wave").unwrap(),
+//!     TrackOptions::new().joints(&[1, 2, 3, 4]).fade_in(Duration::from_millis(200)),
+//! );
+//! mixer.play("arms", Gesture::named("cheer").unwrap(), Duration::from_millis(300));
+//! ```
+
+use std::time::Duration;
+
+use crate::modules::image::ImageBuffer;
+use crate::modules::source::{FrameSource, DEFAULT_INTERVAL};
+use crate::modules::types::JointAngles;
+
+/// 轨道的混合方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlendMode {
+    /// 按权重从下层的角度插值到本轨道的角度（默认）。
+    #[default]
+    Override,
+    /// 本轨道的角度乘以权重后加到下层上。
+    Additive,
+}
+
+/// 轨道选项。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackOptions {
+    /// 每个关节的权重（0-1）。
+    pub weights: [f32; 6],
+    /// 混合方式。
+    pub mode: BlendMode,
+    /// 加入时的淡入时间。
+    pub fade_in: Duration,
+    /// 动作播放结束后的淡出时间。
+    pub fade_out: Duration,
+}
+
+impl TrackOptions {
+    /// 默认选项：所有关节权重为 1，覆盖模式，不淡入，结束后 200 毫秒淡出。
+    pub fn new() -> Self {
+        Self {
+            weights: [1.0; 6],
+            mode: BlendMode::Override,
+            fade_in: Duration::ZERO,
+            fade_out: Duration::from_millis(200),
+        }
+    }
+
+    /// 设置每个关节的权重。
+    pub fn weights(mut self, weights: [f32; 6]) -> Self {
+        self.weights = weights.map(|w| {
+            if w.is_finite() {
+                w.clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        });
+        self
+    }
+
+    /// 只驱动指定的关节（权重为 1），其他关节权重为 0。
+    pub fn joints(mut self, joints: &[usize]) -> Self {
+        self.weights = [0.0; 6];
+        for &joint in joints {
+            if let Some(weight) = self.weights.get_mut(joint) {
+                *weight = 1.0;
+            }
+        }
+        self
+    }
+
+    /// 使用叠加模式。
+    pub fn additive(mut self) -> Self {
+        self.mode = BlendMode::Additive;
+        self
+    }
+
+    /// 设置淡入时间。
+    pub fn fade_in(mut self, duration: Duration) -> Self {
+        self.fade_in = duration;
+        self
+    }
+
+    /// 设置动作结束后的淡出时间。
+    pub fn fade_out(mut self, duration: Duration) -> Self {
+        self.fade_out = duration;
+        self
+    }
+}
+
+impl Default for TrackOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 淡入淡出进度。
+#[derive(Debug, Clone, Copy)]
+struct Fade {
+    elapsed: Duration,
+    duration: Duration,
+}
+
+impl Fade {
+    fn new(duration: Duration) -> Self {
+        Self {
+            elapsed: Duration::ZERO,
+            duration,
+        }
+    }
+
+    /// 平滑后的进度（0-1）。
+    fn progress(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        let t = (self.elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    fn is_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    fn advance(&mut self, dt: Duration) {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+    }
+}
+
+/// 一个动作及其最近的角度。
+struct Clip {
+    source: Box<dyn FrameSource>,
+    pose: Option<JointAngles>,
+    finished: bool,
+}
+
+impl Clip {
+    fn new(source: Box<dyn FrameSource>) -> Self {
+        Self {
+            source,
+            pose: None,
+            finished: false,
+        }
+    }
+
+    /// 播放一帧，结束后保持最后的角度。
+    fn advance(&mut self, buffer: &mut ImageBuffer) {
+        if self.finished {
+            return;
+        }
+        if self.source.next_frame(buffer) {
+            if let Some(pose) = self.source.joint_angles() {
+                self.pose = Some(pose);
+            }
+        } else {
+            self.finished = true;
+        }
+    }
+}
+
+struct Track {
+    name: String,
+    options: TrackOptions,
+    clip: Clip,
+    /// 交叉淡化中的旧动作。
+    outgoing: Option<(Clip, Fade)>,
+    fade_in: Fade,
+    /// 正在淡出，结束后移除。
+    fade_out: Option<Fade>,
+}
+
+impl Track {
+    /// 交叉淡化后的角度，还没有角度时为 `None`。
+    fn pose(&self) -> Option<JointAngles> {
+        let current = self.clip.pose.as_ref();
+        match &self.outgoing {
+            Some((old, fade)) => match (old.pose.as_ref(), current) {
+                (Some(old), Some(new)) => {
+                    let t = fade.progress();
+                    let mut pose = old.clone();
+                    for (angle, target) in pose.as_array_mut().iter_mut().zip(new.as_array()) {
+                        *angle += (target - *angle) * t;
+                    }
+                    Some(pose)
+                }
+                (old, new) => new.or(old).cloned(),
+            },
+            None => current.cloned(),
+        }
+    }
+
+    /// 淡入淡出后的整体权重。
+    fn weight(&self) -> f32 {
+        let out = self.fade_out.map_or(1.0, |fade| 1.0 - fade.progress());
+        self.fade_in.progress() * out
+    }
+}
+
+/// 多轨道动作混合器。
+pub struct AnimationMixer {
+    base: JointAngles,
+    tracks: Vec<Track>,
+    angles: JointAngles,
+    interval: Duration,
+    /// 交叉淡化中的旧动作渲染到这里，不影响画面。
+    scratch: Option<ImageBuffer>,
+}
+
+impl AnimationMixer {
+    /// 创建混合器，`base` 是没有轨道驱动时的姿态。
+    pub fn new(base: JointAngles) -> Self {
+        Self {
+            angles: base.clone(),
+            base,
+            tracks: Vec::new(),
+            interval: DEFAULT_INTERVAL,
+            scratch: None,
+        }
+    }
+
+    /// 在最上层加入一条轨道；同名轨道已存在时替换它的动作和选项（立即生效）。
+    pub fn add_track<S: FrameSource + 'static>(
+        &mut self,
+        name: &str,
+        source: S,
+        options: TrackOptions,
+    ) -> &mut Self {
+        self.tracks.retain(|track| track.name != name);
+        self.tracks.push(Track {
+            name: name.to_string(),
+            options,
+            clip: Clip::new(Box::new(source)),
+            outgoing: None,
+            fade_in: Fade::new(options.fade_in),
+            fade_out: None,
+        });
+        self
+    }
+
+    /// 在轨道 `name` 上播放新动作，在 `crossfade` 时间内从旧动作过渡过去。
+    ///
+    /// 轨道不存在时以默认选项加入最上层，并在 `crossfade` 时间内淡入。
+    pub fn play<S: FrameSource + 'static>(&mut self, name: &str, source: S, crossfade: Duration) {
+        let Some(track) = self.tracks.iter_mut().find(|track| track.name == name) else {
+            self.add_track(name, source, TrackOptions::new().fade_in(crossfade));
+            return;
+        };
+        // 上一次交叉淡化还没结束时，从当前混合结果开始
+        let pose = track.pose();
+        let old = std::mem::replace(&mut track.clip, Clip::new(Box::new(source)));
+        // 正在淡出的轨道重新开始播放
+        if let Some(fade) = track.fade_out.take() {
+            track.fade_in = Fade {
+                elapsed: fade.duration.saturating_sub(fade.elapsed),
+                duration: fade.duration,
+            };
+        }
+        let outgoing = Clip {
+            pose: pose.or(old.pose),
+            ..old
+        };
+        track.outgoing = Some((outgoing, Fade::new(crossfade)));
+    }
+
+    /// 在 `fade` 时间内淡出轨道 `name`，之后移除。
+    pub fn stop(&mut self, name: &str, fade: Duration) {
+        if let Some(track) = self.tracks.iter_mut().find(|track| track.name == name) {
+            if track.fade_out.is_none() {
+                track.fade_out = Some(Fade::new(fade));
+            }
+        }
+    }
+
+    /// 修改轨道的关节权重。
+    pub fn set_weights(&mut self, name: &str, weights: [f32; 6]) {
+        if let Some(track) = self.tracks.iter_mut().find(|track| track.name == name) {
+            track.options = track.options.weights(weights);
+        }
+    }
+
+    /// 是否有名为 `name` 的轨道（包括正在淡出的轨道）。
+    pub fn contains(&self, name: &str) -> bool {
+        self.tracks.iter().any(|track| track.name == name)
+    }
+
+    /// 轨道名称（从下到上）。
+    pub fn track_names(&self) -> Vec<&str> {
+        self.tracks
+            .iter()
+            .map(|track| track.name.as_str())
+            .collect()
+    }
+
+    /// 没有轨道驱动时的姿态。
+    pub fn base(&self) -> &JointAngles {
+        &self.base
+    }
+
+    /// 修改没有轨道驱动时的姿态。
+    pub fn set_base(&mut self, base: JointAngles) {
+        self.base = base;
+    }
+
+    /// 最近一帧混合后的角度。
+    pub fn pose(&self) -> &JointAngles {
+        &self.angles
+    }
+
+    /// 设置帧间隔（淡入淡出按每帧经过一个间隔计时）。
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval.max(Duration::from_millis(1));
+        self
+    }
+
+    /// 按当前各轨道的角度和权重计算混合结果。
+    fn blend(&self) -> JointAngles {
+        let mut angles = self.base.clone();
+        for track in &self.tracks {
+            let Some(pose) = track.pose() else {
+                continue;
+            };
+            let weight = track.weight();
+            let joints = angles.as_array_mut().iter_mut().zip(pose.as_array());
+            for ((angle, target), joint_weight) in joints.zip(track.options.weights) {
+                let w = joint_weight * weight;
+                match track.options.mode {
+                    BlendMode::Override => *angle += (target - *angle) * w,
+                    BlendMode::Additive => *angle += target * w,
+                }
+            }
+        }
+        angles
+    }
+}
+
+impl FrameSource for AnimationMixer {
+    /// 推进所有轨道一帧，所有轨道结束并淡出后返回 `false`。
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        if self.tracks.is_empty() {
+            return false;
+        }
+        let dt = self.interval;
+        for track in &mut self.tracks {
+            track.clip.advance(buffer);
+            if let Some((old, fade)) = &mut track.outgoing {
+                old.advance(self.scratch.get_or_insert_with(ImageBuffer::new));
+                fade.advance(dt);
+            }
+            track.fade_in.advance(dt);
+            if let Some(fade) = &mut track.fade_out {
+                fade.advance(dt);
+            } else if track.clip.finished {
+                #[cfg(feature = "logging")]
+                log::debug!("Animation track '{}' finished, fading out", track.name);
+                track.fade_out = Some(Fade::new(track.options.fade_out));
+            }
+        }
+        self.angles = self.blend();
+        for track in &mut self.tracks {
+            if track
+                .outgoing
+                .as_ref()
+                .is_some_and(|(_, fade)| fade.is_done())
+            {
+                track.outgoing = None;
+            }
+        }
+        self.tracks
+            .retain(|track| !track.fade_out.is_some_and(|fade| fade.is_done()));
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        Some(self.interval)
+    }
+
+    fn joint_angles(&self) -> Option<JointAngles> {
+        Some(self.angles.clone())
+    }
+}
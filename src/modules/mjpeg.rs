@@ -0,0 +1,210 @@
+//! ElectronBot 库的 MJPEG 网络流播放。
+//!
+//! 通过 HTTP 读取 `multipart/x-mixed-replace` 格式的 MJPEG 流（IP 摄像头常用），
+//! 逐帧解码到屏幕。连接断开时按重连策略自动重连，期间保持显示最后一帧。
+
+use std::io::{BufRead, BufReader};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use crate::modules::image::{FitMode, FitOptions, ImageBuffer};
+use crate::modules::net::{header, read_headers, read_line, HttpUrl};
+use crate::modules::source::FrameSource;
+
+/// 单帧最大字节数，防止异常流占满内存。
+const MAX_FRAME_BYTES: usize = 8 * 1024 * 1024;
+
+/// 重连策略。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReconnectPolicy {
+    /// 连续重连的最大次数，`None` 表示无限重连。
+    pub max_retries: Option<u32>,
+    /// 首次重连前的等待时间，之后每次翻倍。
+    pub initial_delay: Duration,
+    /// 最长等待时间。
+    pub max_delay: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: None,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// 不重连。
+    pub fn never() -> Self {
+        Self {
+            max_retries: Some(0),
+            ..Self::default()
+        }
+    }
+
+    /// 第 `attempt` 次（从 0 开始）重连前的等待时间。
+    fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1u32 << attempt.min(16);
+        (self.initial_delay * factor).min(self.max_delay)
+    }
+}
+
+/// MJPEG 网络流帧源。
+pub struct MjpegSource {
+    url: HttpUrl,
+    fit: FitOptions,
+    reconnect: ReconnectPolicy,
+    timeout: Duration,
+    stream: Option<(BufReader<TcpStream>, String)>,
+}
+
+impl MjpegSource {
+    /// 创建 MJPEG 帧源（首帧时才连接），默认保持宽高比完整显示、无限重连。
+    pub fn new(url: &str) -> Result<Self, String> {
+        Ok(Self {
+            url: HttpUrl::parse(url)?,
+            fit: FitOptions::new(FitMode::Contain),
+            reconnect: ReconnectPolicy::default(),
+            timeout: Duration::from_secs(5),
+            stream: None,
+        })
+    }
+
+    /// 设置图片适配方式。
+    pub fn fit(mut self, options: FitOptions) -> Self {
+        self.fit = options;
+        self
+    }
+
+    /// 设置重连策略。
+    pub fn reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect = policy;
+        self
+    }
+
+    /// 设置连接和读取超时。
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 是否已连接。
+    pub fn is_connected(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// 连接并读取 HTTP 响应头，返回读取器和分隔符。
+    fn connect(&self) -> Result<(BufReader<TcpStream>, String), String> {
+        let (reader, headers) = self.url.open(self.timeout)?;
+        let content_type = header(&headers, "content-type").unwrap_or_default();
+        let boundary = parse_boundary(&content_type)
+            .ok_or_else(|| format!("不是 MJPEG 流: {}", content_type))?;
+
+        #[cfg(feature = "logging")]
+        log::info!(
+            "Connected to MJPEG stream {}:{}{} (boundary {})",
+            self.url.host,
+            self.url.port,
+            self.url.path,
+            boundary
+        );
+        Ok((reader, boundary))
+    }
+}
+
+impl FrameSource for MjpegSource {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        let mut attempt = 0u32;
+        loop {
+            if self.stream.is_none() {
+                match self.connect() {
+                    Ok(stream) => self.stream = Some(stream),
+                    Err(_e) => {
+                        #[cfg(feature = "logging")]
+                        log::warn!("MJPEG connect failed: {}", _e);
+                        if self.reconnect.max_retries.is_some_and(|max| attempt >= max) {
+                            return false;
+                        }
+                        std::thread::sleep(self.reconnect.delay(attempt));
+                        attempt += 1;
+                        continue;
+                    }
+                }
+            }
+
+            let (reader, boundary) = self.stream.as_mut().expect("已连接");
+            let frame = read_part(reader, boundary)
+                .and_then(|jpeg| image::load_from_memory(&jpeg).map_err(|e| e.to_string()));
+            match frame {
+                Ok(img) => {
+                    buffer.load_from_image_with(&img, &self.fit);
+                    return true;
+                }
+                Err(_e) => {
+                    #[cfg(feature = "logging")]
+                    log::warn!("MJPEG stream error, reconnecting: {}", _e);
+                    self.stream = None;
+                }
+            }
+        }
+    }
+}
+
+/// 从 `multipart/x-mixed-replace; boundary=xxx` 中取出分隔符。
+pub(crate) fn parse_boundary(content_type: &str) -> Option<String> {
+    if !content_type.to_ascii_lowercase().starts_with("multipart/") {
+        return None;
+    }
+    content_type.split(';').find_map(|part| {
+        let (key, value) = part.trim().split_once('=')?;
+        if !key.trim().eq_ignore_ascii_case("boundary") {
+            return None;
+        }
+        let value = value.trim().trim_matches('"').trim_start_matches("--");
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+/// 读取下一个分段的 JPEG 数据。
+///
+/// 有 `Content-Length` 时按长度读取，否则扫描 JPEG 结束标记（FFD9）。
+pub(crate) fn read_part<R: BufRead>(reader: &mut R, boundary: &str) -> Result<Vec<u8>, String> {
+    // 跳到分隔符行
+    loop {
+        let line = read_line(reader)?;
+        if line.trim_start_matches("--").starts_with(boundary) {
+            break;
+        }
+    }
+    let headers = read_headers(reader)?;
+    if let Some(len) = header(&headers, "content-length") {
+        let len: usize = len
+            .parse()
+            .map_err(|_| format!("无效的 Content-Length: {}", len))?;
+        if len > MAX_FRAME_BYTES {
+            return Err(format!("MJPEG 帧过大: {} 字节", len));
+        }
+        let mut data = vec![0u8; len];
+        reader
+            .read_exact(&mut data)
+            .map_err(|e| format!("读取 MJPEG 帧失败: {}", e))?;
+        return Ok(data);
+    }
+
+    let mut data = Vec::new();
+    let mut byte = [0u8; 1];
+    loop {
+        reader
+            .read_exact(&mut byte)
+            .map_err(|e| format!("读取 MJPEG 帧失败: {}", e))?;
+        data.push(byte[0]);
+        if data.ends_with(&[0xFF, 0xD9]) {
+            return Ok(data);
+        }
+        if data.len() > MAX_FRAME_BYTES {
+            return Err("MJPEG 帧过大".to_string());
+        }
+    }
+}
@@ -9,14 +9,258 @@ pub mod error;
 // 公共类型
 pub mod types;
 
+// 时钟
+pub mod time;
+
+// 颜色工具
+pub mod color;
+
+// 协议参数
+pub mod protocol;
+
+// 传输层抽象
+pub mod transport;
+
+// 协议抓包和回放
+pub mod capture;
+
+// 模拟 MCU
+pub mod fake_mcu;
+
 // USB 底层操作
 pub mod usb;
 
+// WebUSB 传输
+#[cfg(feature = "wasm")]
+pub mod webusb;
+
+// WinUSB 传输
+#[cfg(all(windows, feature = "winusb"))]
+pub mod winusb;
+
+// 平台相关工具
+pub mod platform;
+
+// 固件更新
+#[cfg(feature = "dfu")]
+pub mod dfu;
+
 // 图片缓冲区
 pub mod image;
 
+// YUV 输入转换
+pub mod yuv;
+
+// 灰度和索引色输入
+pub mod indexed;
+
+// 打包像素格式输入
+pub mod pixel_format;
+
+// JPEG/PNG 快速解码
+#[cfg(feature = "image")]
+pub mod decode;
+
+// 编译期图片嵌入
+pub mod embed;
+
+// 绘图图元
+pub mod draw;
+
+// 画面构建
+pub mod frame;
+
+// 抗锯齿光栅化
+mod antialias;
+
+// 精灵图
+pub mod sprite;
+
+// 平铺图案填充
+pub mod pattern;
+
+// 图层合成
+pub mod layer;
+
+// 帧源和测试图案
+pub mod source;
+
+// 预转换帧缓存
+pub mod frame_cache;
+
+// 素材管理
+#[cfg(feature = "image")]
+pub mod assets;
+
+// 帧队列
+pub mod queue;
+
+// 帧率控制
+pub mod pacer;
+
+// 逐段发送
+pub mod stream;
+
+// 线程安全句柄
+pub mod handle;
+
+// 小部件（时钟、仪表、图表）
+pub mod widgets;
+
+// 通知显示
+pub mod notify;
+
+// 场景管理
+pub mod scene;
+
+// 事件
+pub mod event;
+
+// 行为状态机
+pub mod behavior;
+
+// 表情引擎
+pub mod face;
+
+// 注视控制
+pub mod gaze;
+
+// 音频驱动嘴型
+#[cfg(feature = "audio")]
+pub mod audio;
+
+// 视位口型同步
+#[cfg(feature = "audio")]
+pub mod viseme;
+
+// 音频频谱
+#[cfg(feature = "audio")]
+pub mod spectrum;
+
+// 空闲动画
+pub mod idle;
+
+// JSON 解析
+pub(crate) mod json;
+
+// 预设动作
+pub mod gesture;
+
+// 舵机帧源
+pub mod servo;
+
+// 动作混合
+pub mod mixer;
+
+// 程序化空闲动作
+pub mod idle_motion;
+
+// 手柄遥控
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+
+// 键盘点动
+#[cfg(feature = "keyboard")]
+pub mod keyboard;
+
+// 行为脚本
+#[cfg(feature = "scripting")]
+pub mod script;
+
+// 视频播放
+#[cfg(feature = "video")]
+pub mod video;
+
+// 摄像头采集
+#[cfg(feature = "camera")]
+pub mod camera;
+
+// 网络工具（各 feature 只用到其中一部分）
+#[cfg(any(feature = "mjpeg", feature = "http", feature = "mqtt"))]
+#[allow(dead_code)]
+pub(crate) mod net;
+
+// MJPEG 网络流
+#[cfg(feature = "mjpeg")]
+pub mod mjpeg;
+
+// HTTP 接口
+#[cfg(feature = "http")]
+pub mod http;
+
+// MQTT 接入
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+
+// OpenTelemetry 追踪导出
+#[cfg(feature = "otlp")]
+pub mod otlp;
+
+// 网络桥接
+#[cfg(feature = "bridge")]
+pub mod bridge;
+
+// C 接口
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+// 桌面控制面板
+#[cfg(feature = "panel")]
+pub mod panel;
+
+// 色彩校正
+pub mod color_adjust;
+
 // 数据同步
 pub mod sync;
 
 // 扩展数据
 pub mod extra_data;
+
+// 使用统计
+pub mod stats;
+
+// 自检
+pub mod selftest;
+
+// 吞吐量基准测试
+pub mod benchmark;
+
+// 关节角度滤波
+pub mod filter;
+
+// 遥测历史
+pub mod telemetry;
+
+// 诊断事件日志
+pub mod event_log;
+
+// 关节堵转检测
+pub mod stall;
+
+// 舵机软启动
+pub mod soft_start;
+
+// 舵机参数透传
+pub mod tuning;
+
+// 正运动学和碰撞保护
+pub mod kinematics;
+
+// 简易接口
+pub mod simple;
+
+// 会话录制
+#[cfg(feature = "image")]
+pub mod recorder;
+
+// 配置
+pub mod config;
+
+// 二维码绘制
+#[cfg(feature = "qr")]
+pub mod qr;
+
+// 文字渲染
+#[cfg(any(feature = "text", feature = "embedded-font"))]
+pub mod text;
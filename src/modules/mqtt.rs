@@ -0,0 +1,616 @@
+//! ElectronBot 库的 MQTT 接入。
+//!
+//! [`MqttBridge`] 连接 MQTT 服务器（3.1.1，QoS 0），订阅命令主题控制屏幕和关节，
+//! 定时发布在线状态和遥测数据，并发送 Home Assistant 的 MQTT 自动发现消息，
+//! 机器人会作为设备自动出现在 Home Assistant 中。
+//!
+//! 主题（`<prefix>` 默认为 `electronbot/<client_id>`）：
+//!
+//! | 主题 | 方向 | 内容 |
+//! |------|------|------|
+//! | `<prefix>/image/set` | 订阅 | 图片地址（`http://`） |
+//! | `<prefix>/text/set` | 订阅 | 显示文字（需要 `text` 或 `embedded-font` feature） |
+//! | `<prefix>/expression/set` | 订阅 | 表情名称，见 [`EXPRESSION_NAMES`] |
+//! | `<prefix>/pose/set` | 订阅 | 6 个关节角度的 JSON 数组 |
+//! | `<prefix>/gesture/set` | 订阅 | 动作名称，见 [`GESTURE_NAMES`] |
+//! | `<prefix>/availability` | 发布 | `online` / `offline`（保留消息，断线时由遗嘱消息发布） |
+//! | `<prefix>/state` | 发布 | 状态 JSON（连接、关节角度、已发送帧数） |
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::modules::face::{Expression, Face, EXPRESSION_NAMES};
+use crate::modules::gesture::{Gesture, GESTURE_NAMES};
+use crate::modules::json::escape as json_escape;
+use crate::modules::net::{http_get, parse_angles, status_json};
+#[cfg(any(feature = "text", feature = "embedded-font"))]
+use crate::modules::notify::Notification;
+#[cfg(any(feature = "text", feature = "embedded-font"))]
+use crate::modules::text::{Font, TextStyle};
+use crate::modules::time::Instant;
+#[cfg(any(feature = "text", feature = "embedded-font"))]
+use crate::modules::types::Color;
+use crate::ElectronBot;
+
+/// 单个报文的最大字节数。
+const MAX_PACKET_BYTES: usize = 1024 * 1024;
+/// 每次轮询等待报文的时间。
+const POLL_WAIT: Duration = Duration::from_millis(50);
+/// 订阅报文的标识符。
+const SUBSCRIBE_ID: u16 = 1;
+
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+const PUBLISH: u8 = 0x30;
+const PUBACK: u8 = 0x40;
+const SUBSCRIBE: u8 = 0x82;
+const SUBACK: u8 = 0x90;
+const PINGREQ: u8 = 0xC0;
+const DISCONNECT: u8 = 0xE0;
+
+/// MQTT 连接选项。
+#[derive(Debug, Clone, PartialEq)]
+pub struct MqttOptions {
+    host: String,
+    port: u16,
+    client_id: String,
+    credentials: Option<(String, String)>,
+    topic_prefix: Option<String>,
+    discovery_prefix: Option<String>,
+    device_name: String,
+    keep_alive: Duration,
+    telemetry_interval: Duration,
+    timeout: Duration,
+}
+
+impl MqttOptions {
+    /// 连接 `host:port`，客户端标识为 `electronbot`。
+    pub fn new(host: &str, port: u16) -> Self {
+        Self {
+            host: host.to_string(),
+            port,
+            client_id: "electronbot".to_string(),
+            credentials: None,
+            topic_prefix: None,
+            discovery_prefix: Some("homeassistant".to_string()),
+            device_name: "ElectronBot".to_string(),
+            keep_alive: Duration::from_secs(30),
+            telemetry_interval: Duration::from_secs(10),
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// 设置客户端标识，同时用作默认主题前缀和 Home Assistant 设备标识。
+    pub fn client_id(mut self, client_id: &str) -> Self {
+        self.client_id = client_id.to_string();
+        self
+    }
+
+    /// 设置用户名和密码。
+    pub fn credentials(mut self, username: &str, password: &str) -> Self {
+        self.credentials = Some((username.to_string(), password.to_string()));
+        self
+    }
+
+    /// 设置主题前缀（默认 `electronbot/<client_id>`）。
+    pub fn topic_prefix(mut self, prefix: &str) -> Self {
+        self.topic_prefix = Some(prefix.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// 设置 Home Assistant 自动发现前缀（默认 `homeassistant`），`None` 表示不发送。
+    pub fn discovery_prefix(mut self, prefix: Option<&str>) -> Self {
+        self.discovery_prefix = prefix.map(|p| p.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// 设置 Home Assistant 中显示的设备名称。
+    pub fn device_name(mut self, name: &str) -> Self {
+        self.device_name = name.to_string();
+        self
+    }
+
+    /// 设置心跳间隔（默认 30 秒）。
+    pub fn keep_alive(mut self, keep_alive: Duration) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    /// 设置遥测发布间隔（默认 10 秒）。
+    pub fn telemetry_interval(mut self, interval: Duration) -> Self {
+        self.telemetry_interval = interval;
+        self
+    }
+
+    /// 设置连接、读写和图片下载的超时（默认 5 秒）。
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 主题和发现消息中使用的标识（只保留字母、数字、`-` 和 `_`）。
+    fn node_id(&self) -> String {
+        self.client_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+
+    fn prefix(&self) -> String {
+        self.topic_prefix
+            .clone()
+            .unwrap_or_else(|| format!("electronbot/{}", self.node_id()))
+    }
+}
+
+/// 收到的消息。
+struct Message {
+    topic: String,
+    payload: Vec<u8>,
+}
+
+/// MQTT 桥接：把命令主题转换为机器人操作，并发布状态。
+///
+/// ```no_run
+/// use electron_bot::{ElectronBot, MqttBridge, MqttOptions};
+///
+/// let mut bot = ElectronBot::new();
+/// bot.connect().unwrap();
+/// let mut bridge = MqttBridge::connect(MqttOptions::new("192.168.1.2", 1883)).unwrap();
+/// bridge.run(&mut bot).unwrap();
+/// ```
+pub struct MqttBridge {
+    options: MqttOptions,
+    prefix: String,
+    stream: TcpStream,
+    /// 订阅完成前收到的消息。
+    pending: Vec<Message>,
+    last_sent: Instant,
+    last_telemetry: Option<Instant>,
+    #[cfg(any(feature = "text", feature = "embedded-font"))]
+    font: Option<Font>,
+}
+
+impl MqttBridge {
+    /// 连接服务器，订阅命令主题并发送自动发现消息和在线状态。
+    pub fn connect(options: MqttOptions) -> Result<Self, String> {
+        let addr = (options.host.as_str(), options.port)
+            .to_socket_addrs()
+            .map_err(|e| format!("解析地址失败: {}", e))?
+            .next()
+            .ok_or_else(|| format!("无法解析主机: {}", options.host))?;
+        let stream = TcpStream::connect_timeout(&addr, options.timeout)
+            .map_err(|e| format!("连接 MQTT 服务器失败: {}", e))?;
+        stream
+            .set_write_timeout(Some(options.timeout))
+            .map_err(|e| format!("设置超时失败: {}", e))?;
+
+        let mut bridge = Self {
+            prefix: options.prefix(),
+            options,
+            stream,
+            pending: Vec::new(),
+            last_sent: Instant::now(),
+            last_telemetry: None,
+            #[cfg(feature = "embedded-font")]
+            font: Some(Font::embedded()),
+            #[cfg(all(feature = "text", not(feature = "embedded-font")))]
+            font: None,
+        };
+        bridge.handshake()?;
+
+        #[cfg(feature = "logging")]
+        log::info!(
+            "Connected to MQTT broker {}:{} (prefix {})",
+            bridge.options.host,
+            bridge.options.port,
+            bridge.prefix
+        );
+        Ok(bridge)
+    }
+
+    /// 设置显示文字使用的字体（启用 `embedded-font` 时默认使用内置字体）。
+    #[cfg(any(feature = "text", feature = "embedded-font"))]
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// 主题前缀。
+    pub fn topic_prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// 处理收到的命令、发送心跳，到时间时发布遥测，返回处理的命令数量。
+    ///
+    /// 没有消息时最多等待 50 毫秒。命令执行失败只记录日志，连接断开时返回错误。
+    pub fn poll(&mut self, bot: &mut ElectronBot) -> Result<usize, String> {
+        let mut messages = std::mem::take(&mut self.pending);
+        let mut wait = POLL_WAIT;
+        while self.readable(wait)? {
+            if let Some(message) = self.read_message()? {
+                messages.push(message);
+            }
+            wait = Duration::from_millis(1);
+        }
+
+        let mut handled = 0;
+        for message in messages {
+            let Some(command) = message
+                .topic
+                .strip_prefix(&self.prefix)
+                .and_then(|t| t.strip_prefix('/'))
+                .and_then(|t| t.strip_suffix("/set"))
+            else {
+                continue;
+            };
+            let payload = String::from_utf8_lossy(&message.payload);
+            match self.execute(bot, command, payload.trim()) {
+                Ok(()) => handled += 1,
+                Err(_e) => {
+                    #[cfg(feature = "logging")]
+                    log::warn!("MQTT command {} failed: {}", command, _e);
+                }
+            }
+        }
+
+        let telemetry_due = self
+            .last_telemetry
+            .is_none_or(|t| t.elapsed() >= self.options.telemetry_interval);
+        if handled > 0 || telemetry_due {
+            self.publish_state(bot)?;
+        }
+        if self.last_sent.elapsed() >= self.options.keep_alive / 2 {
+            self.send(PINGREQ, &[])?;
+        }
+        Ok(handled)
+    }
+
+    /// 持续处理命令，直到连接断开。
+    pub fn run(&mut self, bot: &mut ElectronBot) -> Result<(), String> {
+        loop {
+            self.poll(bot)?;
+        }
+    }
+
+    /// 发布状态 JSON 到 `<prefix>/state`。
+    pub fn publish_state(&mut self, bot: &ElectronBot) -> Result<(), String> {
+        self.last_telemetry = Some(Instant::now());
+        let topic = format!("{}/state", self.prefix);
+        self.publish(&topic, status_json(bot).as_bytes(), false)
+    }
+
+    /// 发布消息（QoS 0）。
+    pub fn publish(&mut self, topic: &str, payload: &[u8], retain: bool) -> Result<(), String> {
+        let mut body = Vec::with_capacity(topic.len() + payload.len() + 2);
+        put_str(&mut body, topic);
+        body.extend_from_slice(payload);
+        self.send(PUBLISH | retain as u8, &body)
+    }
+
+    /// 执行一条命令。
+    fn execute(
+        &mut self,
+        bot: &mut ElectronBot,
+        command: &str,
+        payload: &str,
+    ) -> Result<(), String> {
+        match command {
+            "image" => {
+                let data = http_get(payload, self.options.timeout)?;
+                bot.set_image_from_bytes(&data).map_err(|e| e.to_string())?;
+            }
+            "text" => self.show_text(bot, payload)?,
+            "expression" => {
+                let expression =
+                    Expression::named(payload).ok_or_else(|| format!("未知的表情: {}", payload))?;
+                let mut face = Face::new();
+                face.set_expression(expression);
+                face.render(bot.image_buffer());
+            }
+            "pose" => {
+                let angles = parse_angles(payload)?;
+                bot.set_joint_angles(&angles, true)
+                    .map_err(|e| e.to_string())?;
+            }
+            "gesture" => {
+                let mut gesture =
+                    Gesture::named(payload).ok_or_else(|| format!("未知的动作: {}", payload))?;
+                bot.play(&mut gesture, None).map_err(|e| e.to_string())?;
+                return Ok(());
+            }
+            _ => return Err(format!("未知的命令: {}", command)),
+        }
+        bot.sync().map(|_| ()).map_err(|e| e.to_string())
+    }
+
+    #[cfg(any(feature = "text", feature = "embedded-font"))]
+    fn show_text(&self, bot: &mut ElectronBot, text: &str) -> Result<(), String> {
+        let font = self.font.as_ref().ok_or("没有设置字体")?;
+        let card = Notification::text(
+            text,
+            font,
+            &TextStyle::new(28.0, Color::White),
+            Duration::ZERO,
+        );
+        *bot.image_buffer() = card.content().clone();
+        Ok(())
+    }
+
+    #[cfg(not(any(feature = "text", feature = "embedded-font")))]
+    fn show_text(&self, _bot: &mut ElectronBot, _text: &str) -> Result<(), String> {
+        Err("显示文字需要启用 text 或 embedded-font feature".to_string())
+    }
+
+    /// 发送连接和订阅报文，然后发布自动发现消息和在线状态。
+    fn handshake(&mut self) -> Result<(), String> {
+        let availability = format!("{}/availability", self.prefix);
+        let credentials = self.options.credentials.clone();
+        let mut flags = 0x02 | 0x04 | 0x20; // 清除会话、遗嘱消息、保留遗嘱
+        if credentials.is_some() {
+            flags |= 0x80 | 0x40;
+        }
+        let mut body = Vec::new();
+        put_str(&mut body, "MQTT");
+        body.push(4);
+        body.push(flags);
+        let keep_alive = self.options.keep_alive.as_secs().clamp(1, u16::MAX as u64) as u16;
+        body.extend_from_slice(&keep_alive.to_be_bytes());
+        put_str(&mut body, &self.options.client_id);
+        put_str(&mut body, &availability);
+        put_str(&mut body, "offline");
+        if let Some((username, password)) = &credentials {
+            put_str(&mut body, username);
+            put_str(&mut body, password);
+        }
+        self.send(CONNECT, &body)?;
+
+        let (header, body) = self.read_packet()?;
+        if header & 0xF0 != CONNACK || body.len() < 2 {
+            return Err("MQTT 服务器没有返回 CONNACK".to_string());
+        }
+        match body[1] {
+            0 => {}
+            4 | 5 => return Err("MQTT 认证失败".to_string()),
+            code => return Err(format!("MQTT 服务器拒绝连接: {}", code)),
+        }
+
+        let mut body = SUBSCRIBE_ID.to_be_bytes().to_vec();
+        put_str(&mut body, &format!("{}/+/set", self.prefix));
+        body.push(0);
+        self.send(SUBSCRIBE, &body)?;
+        loop {
+            let (header, body) = self.read_packet()?;
+            match header & 0xF0 {
+                SUBACK if body.get(2) == Some(&0x80) => {
+                    return Err("MQTT 服务器拒绝订阅".to_string())
+                }
+                SUBACK => break,
+                PUBLISH => {
+                    if let Some(message) = self.parse_publish(header, &body)? {
+                        self.pending.push(message);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (topic, payload) in self.discovery_messages() {
+            self.publish(&topic, payload.as_bytes(), true)?;
+        }
+        self.publish(&availability, b"online", true)
+    }
+
+    /// Home Assistant 自动发现消息（主题和配置 JSON）。
+    fn discovery_messages(&self) -> Vec<(String, String)> {
+        let Some(discovery) = &self.options.discovery_prefix else {
+            return Vec::new();
+        };
+        let node = self.options.node_id();
+        // 主题前缀和设备名称由用户提供，写入 JSON 前都要转义
+        let string = |value: &str| format!("\"{}\"", json_escape(value));
+        let topic = |suffix: &str| string(&format!("{}/{}", self.prefix, suffix));
+        let options = |names: &[&str]| {
+            let names: Vec<String> = names.iter().map(|n| string(n)).collect();
+            names.join(",")
+        };
+        let state = format!("\"state_topic\":{}", topic("state"));
+        let mut entities = vec![
+            (
+                "text",
+                "image",
+                "图片地址",
+                format!("\"command_topic\":{},\"max\":255", topic("image/set")),
+            ),
+            (
+                "select",
+                "expression",
+                "表情",
+                format!(
+                    "\"command_topic\":{},\"options\":[{}]",
+                    topic("expression/set"),
+                    options(&EXPRESSION_NAMES)
+                ),
+            ),
+            (
+                "select",
+                "gesture",
+                "动作",
+                format!(
+                    "\"command_topic\":{},\"options\":[{}]",
+                    topic("gesture/set"),
+                    options(&GESTURE_NAMES)
+                ),
+            ),
+            (
+                "text",
+                "pose",
+                "关节姿态",
+                format!("\"command_topic\":{}", topic("pose/set")),
+            ),
+            (
+                "binary_sensor",
+                "connected",
+                "USB 连接",
+                format!(
+                    "{},\"device_class\":\"connectivity\",\"value_template\":{}",
+                    state,
+                    string("{{ 'ON' if value_json.connected else 'OFF' }}")
+                ),
+            ),
+            (
+                "sensor",
+                "frames",
+                "已发送帧数",
+                format!(
+                    "{},\"state_class\":\"total_increasing\",\"value_template\":{}",
+                    state,
+                    string("{{ value_json.frames }}")
+                ),
+            ),
+        ];
+        if cfg!(any(feature = "text", feature = "embedded-font")) {
+            entities.push((
+                "text",
+                "text",
+                "显示文字",
+                format!("\"command_topic\":{},\"max\":255", topic("text/set")),
+            ));
+        }
+
+        let device = format!(
+            "{{\"identifiers\":[{}],\"name\":{},\"manufacturer\":\"ElectronBot\",\"model\":\"ElectronBot\",\"sw_version\":{}}}",
+            string(&node),
+            string(&self.options.device_name),
+            string(env!("CARGO_PKG_VERSION"))
+        );
+        let availability = topic("availability");
+        entities
+            .into_iter()
+            .map(|(component, object, name, fields)| {
+                let topic = format!("{}/{}/{}/{}/config", discovery, component, node, object);
+                let config = format!(
+                    "{{\"name\":{},\"unique_id\":{},\"availability_topic\":{},{},\"device\":{}}}",
+                    string(name),
+                    string(&format!("{}_{}", node, object)),
+                    availability,
+                    fields,
+                    device
+                );
+                (topic, config)
+            })
+            .collect()
+    }
+
+    /// 等待可读数据，超时返回 `false`。
+    fn readable(&mut self, wait: Duration) -> Result<bool, String> {
+        self.stream
+            .set_read_timeout(Some(wait))
+            .map_err(|e| format!("设置超时失败: {}", e))?;
+        match self.stream.peek(&mut [0u8; 1]) {
+            Ok(0) => Err("MQTT 服务器关闭了连接".to_string()),
+            Ok(_) => Ok(true),
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => Ok(false),
+            Err(e) => Err(format!("读取 MQTT 报文失败: {}", e)),
+        }
+    }
+
+    /// 读取一个报文，返回其中的消息。
+    fn read_message(&mut self) -> Result<Option<Message>, String> {
+        let (header, body) = self.read_packet()?;
+        match header & 0xF0 {
+            PUBLISH => self.parse_publish(header, &body),
+            _ => Ok(None),
+        }
+    }
+
+    /// 解析 PUBLISH 报文，QoS 1 的消息回复 PUBACK。
+    fn parse_publish(&mut self, header: u8, body: &[u8]) -> Result<Option<Message>, String> {
+        let qos = (header >> 1) & 0x03;
+        let Some(len) = body
+            .get(..2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]) as usize)
+        else {
+            return Ok(None);
+        };
+        let id_len = if qos > 0 { 2 } else { 0 };
+        if body.len() < 2 + len + id_len {
+            return Ok(None);
+        }
+        let topic = String::from_utf8_lossy(&body[2..2 + len]).into_owned();
+        if qos == 1 {
+            self.send(PUBACK, &body[2 + len..2 + len + 2])?;
+        }
+        Ok(Some(Message {
+            topic,
+            payload: body[2 + len + id_len..].to_vec(),
+        }))
+    }
+
+    fn read_packet(&mut self) -> Result<(u8, Vec<u8>), String> {
+        self.stream
+            .set_read_timeout(Some(self.options.timeout))
+            .map_err(|e| format!("设置超时失败: {}", e))?;
+        let read_err = |e: std::io::Error| format!("读取 MQTT 报文失败: {}", e);
+        let mut byte = [0u8; 1];
+        self.stream.read_exact(&mut byte).map_err(read_err)?;
+        let header = byte[0];
+        let mut len = 0usize;
+        for shift in [0, 7, 14, 21] {
+            self.stream.read_exact(&mut byte).map_err(read_err)?;
+            len |= ((byte[0] & 0x7F) as usize) << shift;
+            if byte[0] & 0x80 == 0 {
+                break;
+            }
+        }
+        if len > MAX_PACKET_BYTES {
+            return Err(format!("MQTT 报文过大: {} 字节", len));
+        }
+        let mut body = vec![0u8; len];
+        self.stream.read_exact(&mut body).map_err(read_err)?;
+        Ok((header, body))
+    }
+
+    fn send(&mut self, header: u8, body: &[u8]) -> Result<(), String> {
+        let mut packet = Vec::with_capacity(body.len() + 5);
+        packet.push(header);
+        let mut len = body.len();
+        loop {
+            let byte = (len % 128) as u8;
+            len /= 128;
+            if len == 0 {
+                packet.push(byte);
+                break;
+            }
+            packet.push(byte | 0x80);
+        }
+        packet.extend_from_slice(body);
+        self.stream
+            .write_all(&packet)
+            .map_err(|e| format!("发送 MQTT 报文失败: {}", e))?;
+        self.last_sent = Instant::now();
+        Ok(())
+    }
+}
+
+impl Drop for MqttBridge {
+    /// 发布离线状态并断开连接（正常退出时不会触发遗嘱消息）。
+    fn drop(&mut self) {
+        let availability = format!("{}/availability", self.prefix);
+        let _ = self.publish(&availability, b"offline", true);
+        let _ = self.send(DISCONNECT, &[]);
+    }
+}
+
+/// 写入带 2 字节长度前缀的字符串。
+fn put_str(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u16).to_be_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
@@ -0,0 +1,238 @@
+//! ElectronBot 库的网络工具。
+//!
+//! 基于标准库的最小 HTTP 客户端和状态 JSON，供 MJPEG 流、HTTP 接口和 MQTT 共用。
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::ElectronBot;
+
+/// 下载内容的最大字节数，防止异常响应占满内存。
+const MAX_BODY_BYTES: u64 = 16 * 1024 * 1024;
+
+/// 状态行和单个头部行的最大字节数。
+const MAX_LINE_BYTES: usize = 8 * 1024;
+
+/// 全部响应头的最大字节数。
+const MAX_HEADER_BYTES: usize = 64 * 1024;
+
+/// 响应头：小写的名称和值。
+pub(crate) type Headers = Vec<(String, String)>;
+
+/// 解析后的 HTTP 地址。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HttpUrl {
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) path: String,
+    /// `user:password`（Basic 认证）。
+    pub(crate) credentials: Option<String>,
+}
+
+impl HttpUrl {
+    /// 解析 `http://[user:pass@]host[:port][/path]`。
+    pub(crate) fn parse(url: &str) -> Result<Self, String> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| format!("只支持 http:// 地址: {}", url))?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (credentials, host_port) = match authority.rsplit_once('@') {
+            Some((cred, host)) => (Some(cred.to_string()), host),
+            None => (None, authority),
+        };
+        let (host, port) = match host_port.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse().map_err(|_| format!("无效的端口: {}", port))?,
+            ),
+            None => (host_port, 80),
+        };
+        if host.is_empty() {
+            return Err(format!("地址缺少主机名: {}", url));
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+            credentials,
+        })
+    }
+
+    /// 连接服务器并发送 GET 请求，检查状态码后返回读取器和响应头。
+    pub(crate) fn open(
+        &self,
+        timeout: Duration,
+    ) -> Result<(BufReader<TcpStream>, Headers), String> {
+        let addr = (self.host.as_str(), self.port)
+            .to_socket_addrs()
+            .map_err(|e| format!("解析地址失败: {}", e))?
+            .next()
+            .ok_or_else(|| format!("无法解析主机: {}", self.host))?;
+        let mut tcp = TcpStream::connect_timeout(&addr, timeout)
+            .map_err(|e| format!("连接 {}:{} 失败: {}", self.host, self.port, e))?;
+        tcp.set_read_timeout(Some(timeout))
+            .map_err(|e| format!("设置超时失败: {}", e))?;
+
+        let mut request = format!(
+            "GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: electron-bot\r\n",
+            self.path, self.host
+        );
+        if let Some(cred) = &self.credentials {
+            request.push_str(&format!(
+                "Authorization: Basic {}\r\n",
+                base64(cred.as_bytes())
+            ));
+        }
+        request.push_str("\r\n");
+        tcp.write_all(request.as_bytes())
+            .map_err(|e| format!("发送请求失败: {}", e))?;
+
+        let mut reader = BufReader::new(tcp);
+        let status = read_line(&mut reader)?;
+        if status.split_whitespace().nth(1) != Some("200") {
+            return Err(format!("服务器返回错误: {}", status));
+        }
+        let headers = read_headers(&mut reader)?;
+        Ok((reader, headers))
+    }
+}
+
+/// 下载 `http://` 地址的内容。
+pub(crate) fn http_get(url: &str, timeout: Duration) -> Result<Vec<u8>, String> {
+    let (reader, headers) = HttpUrl::parse(url)?.open(timeout)?;
+    let mut body = Vec::new();
+    reader
+        .take(MAX_BODY_BYTES + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| format!("下载失败: {}", e))?;
+    if body.len() as u64 > MAX_BODY_BYTES {
+        return Err(format!("下载内容超过 {} 字节", MAX_BODY_BYTES));
+    }
+    if let Some(len) = header(&headers, "content-length")
+        .and_then(|len| len.parse::<usize>().ok())
+        .filter(|&len| len != body.len())
+    {
+        return Err(format!("下载不完整: {} / {} 字节", body.len(), len));
+    }
+    Ok(body)
+}
+
+/// 读取一行（最多 `limit` 字节），超过长度时返回 `None`。
+pub(crate) fn read_line_limited<R: BufRead>(
+    reader: &mut R,
+    line: &mut String,
+    limit: usize,
+) -> std::io::Result<Option<usize>> {
+    line.clear();
+    let read = reader.take(limit as u64).read_line(line)?;
+    if read == limit && !line.ends_with('\n') {
+        return Ok(None);
+    }
+    Ok(Some(read))
+}
+
+/// 读取一行（去掉行尾换行），超过 `MAX_LINE_BYTES` 或连接关闭时返回错误。
+pub(crate) fn read_line<R: BufRead>(reader: &mut R) -> Result<String, String> {
+    let mut line = String::new();
+    read_raw_line(reader, &mut line)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// 读取一行到 `line`（保留换行），返回读取的字节数。
+fn read_raw_line<R: BufRead>(reader: &mut R, line: &mut String) -> Result<usize, String> {
+    match read_line_limited(reader, line, MAX_LINE_BYTES) {
+        Ok(Some(0)) => Err("连接已关闭".to_string()),
+        Ok(Some(read)) => Ok(read),
+        Ok(None) => Err(format!("网络数据行超过 {} 字节", MAX_LINE_BYTES)),
+        Err(e) => Err(format!("读取网络数据失败: {}", e)),
+    }
+}
+
+/// 读取头部直到空行，全部头部超过 `MAX_HEADER_BYTES` 时返回错误。
+pub(crate) fn read_headers<R: BufRead>(reader: &mut R) -> Result<Headers, String> {
+    let mut headers = Vec::new();
+    let mut header_bytes = 0;
+    let mut line = String::new();
+    loop {
+        header_bytes += read_raw_line(reader, &mut line)?;
+        if header_bytes > MAX_HEADER_BYTES {
+            return Err(format!("响应头超过 {} 字节", MAX_HEADER_BYTES));
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            return Ok(headers);
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+}
+
+pub(crate) fn header(headers: &[(String, String)], name: &str) -> Option<String> {
+    headers
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.clone())
+}
+
+/// Basic 认证使用的 base64 编码。
+pub(crate) fn base64(input: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(TABLE[(n >> (18 - i * 6)) as usize & 63] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// 解析 `[a, b, c, d, e, f]` 形式的 6 个角度。
+pub(crate) fn parse_angles(text: &str) -> Result<[f32; 6], String> {
+    let inner = text
+        .trim()
+        .strip_prefix('[')
+        .and_then(|t| t.strip_suffix(']'))
+        .ok_or_else(|| "需要 JSON 数组，例如 [0, 0, 0, 0, 0, 0]".to_string())?;
+    let values = inner
+        .split(',')
+        .map(|v| {
+            v.trim()
+                .parse::<f32>()
+                .map_err(|_| format!("无效的角度: {}", v.trim()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    <[f32; 6]>::try_from(values).map_err(|v| format!("需要 6 个角度，收到 {} 个", v.len()))
+}
+
+/// 机器人状态的 JSON：连接、屏幕、亮度、关节角度和已发送帧数。
+pub(crate) fn status_json(bot: &ElectronBot) -> String {
+    let joints: Vec<String> = bot
+        .get_joint_angles()
+        .as_array()
+        .iter()
+        .map(|a| format!("{:.2}", a))
+        .collect();
+    format!(
+        "{{\"connected\":{},\"display_on\":{},\"brightness\":{:.2},\"joints\":[{}],\"frames\":{}}}",
+        bot.is_connected(),
+        bot.is_display_on(),
+        bot.brightness(),
+        joints.join(","),
+        bot.stats().frames_transmitted
+    )
+}
@@ -0,0 +1,359 @@
+//! ElectronBot 库的通知显示。
+//!
+//! [`Notification`] 从屏幕边缘滑入、停留一段时间后滑出。[`NotificationOverlay`] 包装一个帧源，
+//! 有通知时暂停该帧源并显示通知，结束后恢复原来的内容；通知按优先级排队，
+//! 优先级更高的通知会打断正在显示的通知。
+
+use std::cmp::Reverse;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_WIDTH};
+use crate::modules::image::ImageBuffer;
+use crate::modules::source::{FrameSource, DEFAULT_INTERVAL};
+use crate::modules::types::JointAngles;
+#[cfg(any(feature = "text", feature = "embedded-font"))]
+use crate::modules::{
+    draw::Rect,
+    text::{Font, TextAlign, TextStyle},
+    types::Color,
+};
+
+/// 通知滑入的方向。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SlideFrom {
+    /// 从上方滑入（默认）。
+    #[default]
+    Top,
+    /// 从下方滑入。
+    Bottom,
+    /// 从左侧滑入。
+    Left,
+    /// 从右侧滑入。
+    Right,
+}
+
+/// 通知。
+#[derive(Debug, Clone)]
+pub struct Notification {
+    content: ImageBuffer,
+    duration: Duration,
+    priority: i32,
+    transition: Duration,
+    slide_from: SlideFrom,
+}
+
+impl Notification {
+    /// 用整屏图片创建通知，`duration` 为完全展示的时间（不含滑入滑出）。
+    pub fn image(content: ImageBuffer, duration: Duration) -> Self {
+        Self {
+            content,
+            duration,
+            priority: 0,
+            transition: Duration::from_millis(250),
+            slide_from: SlideFrom::Top,
+        }
+    }
+
+    /// 用文字创建通知：深色背景上的圆角卡片，文字自动换行并居中。
+    #[cfg(any(feature = "text", feature = "embedded-font"))]
+    pub fn text(text: &str, font: &Font, style: &TextStyle, duration: Duration) -> Self {
+        const CARD: Rect = Rect {
+            x: 24,
+            y: 48,
+            width: FRAME_WIDTH - 48,
+            height: FRAME_HEIGHT - 96,
+        };
+        let mut content = ImageBuffer::new();
+        content.clear(Color::Black);
+        content.fill_rounded_rect(CARD, 16, Color::Custom(48, 48, 56));
+
+        let style = (*style)
+            .align(TextAlign::Center)
+            .max_width(CARD.width as f32 - 24.0);
+        let lines = font.wrap(text, style.size, style.max_width).len();
+        let height = lines as f32 * font.line_height(style.size) * style.line_spacing;
+        let y = CARD.y + ((CARD.height as f32 - height) / 2.0).max(8.0) as i32;
+        content.draw_text_block(CARD.x + 12, y, text, font, &style);
+        Self::image(content, duration)
+    }
+
+    /// 设置优先级，数值大的优先显示，并打断正在显示的低优先级通知（默认 0）。
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// 设置滑入和滑出的时间。
+    pub fn transition(mut self, transition: Duration) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    /// 设置滑入方向（滑出时沿原方向退回）。
+    pub fn slide_from(mut self, slide_from: SlideFrom) -> Self {
+        self.slide_from = slide_from;
+        self
+    }
+
+    /// 通知内容。
+    pub fn content(&self) -> &ImageBuffer {
+        &self.content
+    }
+
+    /// 包括滑入和滑出在内的总时长。
+    pub fn total_duration(&self) -> Duration {
+        self.transition * 2 + self.duration
+    }
+
+    /// `elapsed` 时刻通知进入屏幕的比例（0 到 1，平滑插值）。
+    fn visibility(&self, elapsed: Duration) -> f32 {
+        if self.transition.is_zero() {
+            return 1.0;
+        }
+        let out_at = self.transition + self.duration;
+        let t = if elapsed < self.transition {
+            elapsed.as_secs_f32() / self.transition.as_secs_f32()
+        } else if elapsed < out_at {
+            1.0
+        } else {
+            1.0 - (elapsed - out_at).as_secs_f32() / self.transition.as_secs_f32()
+        };
+        let t = t.clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    /// 在背景上绘制 `elapsed` 时刻的通知。
+    fn render(&self, background: &ImageBuffer, elapsed: Duration, out: &mut ImageBuffer) {
+        let hidden = 1.0 - self.visibility(elapsed);
+        let (dx, dy) = match self.slide_from {
+            SlideFrom::Top => (0, -(hidden * FRAME_HEIGHT as f32) as isize),
+            SlideFrom::Bottom => (0, (hidden * FRAME_HEIGHT as f32) as isize),
+            SlideFrom::Left => (-(hidden * FRAME_WIDTH as f32) as isize, 0),
+            SlideFrom::Right => ((hidden * FRAME_WIDTH as f32) as isize, 0),
+        };
+
+        out.as_mut_data().copy_from_slice(background.as_data());
+        // 按行复制通知中仍在屏幕内的部分
+        let (x0, x1) = (
+            dx.max(0) as usize,
+            (FRAME_WIDTH as isize + dx.min(0)) as usize,
+        );
+        if x0 >= x1 {
+            return;
+        }
+        let src_x = (x0 as isize - dx) as usize;
+        let width = (x1 - x0) * 3;
+        for y in 0..FRAME_HEIGHT {
+            let src_y = y as isize - dy;
+            if src_y < 0 || src_y >= FRAME_HEIGHT as isize {
+                continue;
+            }
+            let src = (src_y as usize * FRAME_WIDTH + src_x) * 3;
+            let dst = (y * FRAME_WIDTH + x0) * 3;
+            out.as_mut_data()[dst..dst + width]
+                .copy_from_slice(&self.content.as_data()[src..src + width]);
+        }
+    }
+}
+
+/// 正在显示的通知。
+#[derive(Debug, Clone)]
+pub(crate) struct NotificationPlayback {
+    notification: Notification,
+    background: ImageBuffer,
+    /// 第一帧时把缓冲区中的画面作为背景。
+    capture: bool,
+    frame: u32,
+}
+
+impl NotificationPlayback {
+    /// 在 `background` 上播放通知。
+    pub(crate) fn new(notification: Notification, background: ImageBuffer) -> Self {
+        Self {
+            notification,
+            background,
+            capture: false,
+            frame: 0,
+        }
+    }
+
+    /// 在开始播放时缓冲区中的画面上播放通知。
+    pub(crate) fn over_current(notification: Notification) -> Self {
+        Self {
+            capture: true,
+            ..Self::new(notification, ImageBuffer::new())
+        }
+    }
+
+    /// 通知的优先级。
+    pub(crate) fn priority(&self) -> i32 {
+        self.notification.priority
+    }
+
+    /// 被打断时的背景（恢复用）。
+    pub(crate) fn background(&self) -> &ImageBuffer {
+        &self.background
+    }
+}
+
+impl FrameSource for NotificationPlayback {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        let elapsed = DEFAULT_INTERVAL * self.frame;
+        if elapsed > self.notification.total_duration() {
+            return false;
+        }
+        if self.capture && self.frame == 0 {
+            self.background
+                .as_mut_data()
+                .copy_from_slice(buffer.as_data());
+        }
+        self.notification.render(&self.background, elapsed, buffer);
+        self.frame += 1;
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        Some(DEFAULT_INTERVAL)
+    }
+}
+
+/// 通知队列，可克隆后交给其他线程（邮件检查、CI 轮询等）。
+#[derive(Debug, Clone, Default)]
+pub struct Notifier {
+    queue: Arc<Mutex<Vec<Notification>>>,
+}
+
+impl Notifier {
+    /// 创建空队列。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加通知。
+    pub fn notify(&self, notification: Notification) {
+        self.lock().push(notification);
+    }
+
+    /// 等待显示的通知数量。
+    pub fn pending(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// 清空等待显示的通知。
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// 取出优先级高于 `above` 的通知中优先级最高、最早加入的一个。
+    fn pop_above(&self, above: Option<i32>) -> Option<Notification> {
+        let mut queue = self.lock();
+        let best = (0..queue.len())
+            .filter(|&i| above.is_none_or(|p| queue[i].priority > p))
+            .min_by_key(|&i| (Reverse(queue[i].priority), i))?;
+        Some(queue.remove(best))
+    }
+
+    /// 取出全部等待显示的通知（按加入顺序）。
+    pub(crate) fn take_all(&self) -> Vec<Notification> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    /// 放回队首（被打断的通知之后重新显示）。
+    fn requeue(&self, notification: Notification) {
+        self.lock().insert(0, notification);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Notification>> {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 带通知的帧源：显示通知期间暂停内部帧源和舵机，结束后恢复原来的内容。
+#[derive(Debug)]
+pub struct NotificationOverlay<S> {
+    source: S,
+    notifier: Notifier,
+    current: Option<NotificationPlayback>,
+}
+
+impl<S: FrameSource> NotificationOverlay<S> {
+    /// 包装帧源。
+    pub fn new(source: S) -> Self {
+        Self::with_notifier(source, Notifier::new())
+    }
+
+    /// 包装帧源并使用已有的通知队列。
+    pub fn with_notifier(source: S, notifier: Notifier) -> Self {
+        Self {
+            source,
+            notifier,
+            current: None,
+        }
+    }
+
+    /// 通知队列（克隆后交给其他线程）。
+    pub fn notifier(&self) -> Notifier {
+        self.notifier.clone()
+    }
+
+    /// 是否正在显示通知。
+    pub fn is_showing(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// 内部帧源。
+    pub fn source_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+}
+
+impl<S: FrameSource> FrameSource for NotificationOverlay<S> {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        let current = self.current.as_ref().map(|c| c.notification.priority);
+        if let Some(next) = self.notifier.pop_above(current) {
+            // 被打断的通知放回队列，沿用原来的背景
+            let background = match self.current.take() {
+                Some(previous) => {
+                    let background = previous.background.clone();
+                    self.notifier.requeue(previous.notification);
+                    background
+                }
+                None => buffer.clone(),
+            };
+            self.current = Some(NotificationPlayback::new(next, background));
+        }
+
+        while let Some(playback) = &mut self.current {
+            if playback.next_frame(buffer) {
+                return true;
+            }
+            let background = self.current.take().map(|c| c.background);
+            match self.notifier.pop_above(None) {
+                Some(next) => {
+                    self.current = background.map(|b| NotificationPlayback::new(next, b));
+                }
+                None => {
+                    if let Some(background) = background {
+                        *buffer = background;
+                    }
+                }
+            }
+        }
+        self.source.next_frame(buffer)
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        match &self.current {
+            Some(playback) => playback.frame_interval(),
+            None => self.source.frame_interval(),
+        }
+    }
+
+    fn joint_angles(&self) -> Option<JointAngles> {
+        match &self.current {
+            Some(_) => None,
+            None => self.source.joint_angles(),
+        }
+    }
+}
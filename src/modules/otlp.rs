@@ -0,0 +1,66 @@
+//! ElectronBot 库的 OpenTelemetry 追踪导出。
+//!
+//! `tracing` feature 为连接、每次同步、每个周期、重试和重新连接创建 span 和事件；
+//! 本模块把它们通过 OTLP/HTTP 发送到 Jaeger、Tempo 等收集器。同时控制多台机器人时，
+//! 在外层创建带机器人名称的 span，每台机器人的同步会归到各自的 span 下。
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing_subscriber::layer::SubscriberExt;
+
+/// 默认的 OTLP/HTTP 追踪地址。
+pub const DEFAULT_OTLP_ENDPOINT: &str = "http://localhost:4318/v1/traces";
+
+/// OTLP 导出的生命周期，drop 时发送剩余的 span 并关闭导出。
+pub struct OtlpGuard {
+    provider: SdkTracerProvider,
+}
+
+impl OtlpGuard {
+    /// 立即发送缓存的 span。
+    pub fn flush(&self) -> Result<(), String> {
+        self.provider
+            .force_flush()
+            .map_err(|e| format!("发送追踪数据失败: {}", e))
+    }
+}
+
+impl Drop for OtlpGuard {
+    fn drop(&mut self) {
+        if let Err(_e) = self.provider.shutdown() {
+            #[cfg(feature = "logging")]
+            log::warn!("Failed to shut down OTLP exporter: {}", _e);
+        }
+    }
+}
+
+/// 安装全局的 `tracing` 订阅者，把 span 批量发送到 `endpoint`（OTLP/HTTP，
+/// 例如 [`DEFAULT_OTLP_ENDPOINT`]），服务名为 `service_name`。
+///
+/// 已经安装过全局订阅者时返回错误；需要同时输出到终端等其他目标时，
+/// 自行组合 `tracing_opentelemetry::layer()` 和其他层。
+pub fn init_otlp(endpoint: &str, service_name: &str) -> Result<OtlpGuard, String> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()
+        .map_err(|e| format!("创建 OTLP 导出失败: {}", e))?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(service_name.to_string())
+                .build(),
+        )
+        .build();
+    let tracer = provider.tracer(env!("CARGO_PKG_NAME"));
+    // 安装失败时 guard 被 drop，关闭导出线程
+    let guard = OtlpGuard { provider };
+    let subscriber =
+        tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+    tracing::subscriber::set_global_default(subscriber)
+        .map_err(|e| format!("安装追踪订阅者失败: {}", e))?;
+    Ok(guard)
+}
@@ -0,0 +1,208 @@
+//! ElectronBot 库的帧率控制。
+//!
+//! [`FramePacer`] 按绝对时间表安排每一帧：睡眠到下一帧的时刻再发送，
+//! 睡眠和同步的耗时不会累积成漂移。同时统计实际帧间隔的抖动，
+//! 并用每次同步的耗时（由 MCU 请求数据的节奏决定）估算设备实际能接收的帧率。
+//!
+//! ```rust,no_run
+//! use electron_bot::{ElectronBot, FramePacer};
+//!
+//! let mut bot = ElectronBot::new();
+//! bot.connect()?;
+//! let mut pacer = FramePacer::new(20.0);
+//! for _ in 0..100 {
+//!     // ... 渲染画面、设置关节 ...
+//!     pacer.sync(&mut bot)?;
+//! }
+//! println!("{}", pacer.stats());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::modules::error::BotError;
+use crate::modules::time::Instant;
+use crate::ElectronBot;
+
+/// 统计窗口的帧数。
+const WINDOW: usize = 60;
+
+/// 帧率统计（最近 60 帧）。
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PacerStats {
+    /// 已调度的帧数。
+    pub frames: u64,
+    /// 目标帧率。
+    pub target_fps: f32,
+    /// 实际帧率（按帧间隔的平均值计算）。
+    pub actual_fps: f32,
+    /// 设备能接收的帧率（按同步耗时计算，还没有同步时为 0）。
+    pub device_fps: f32,
+    /// 帧间隔的标准差。
+    pub jitter: Duration,
+    /// 帧间隔与目标间隔的最大偏差。
+    pub max_deviation: Duration,
+    /// 落后超过一帧、重新对齐时间表的次数。
+    pub missed: u64,
+}
+
+impl std::fmt::Display for PacerStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "帧数: {}", self.frames)?;
+        writeln!(
+            f,
+            "帧率: {:.1} / 目标 {:.1} (设备 {:.1})",
+            self.actual_fps, self.target_fps, self.device_fps
+        )?;
+        writeln!(
+            f,
+            "抖动: {:.2} 毫秒 (最大偏差 {:.2} 毫秒)",
+            self.jitter.as_secs_f64() * 1000.0,
+            self.max_deviation.as_secs_f64() * 1000.0
+        )?;
+        writeln!(f, "掉帧: {}", self.missed)
+    }
+}
+
+/// 帧率控制器。
+#[derive(Debug, Clone)]
+pub struct FramePacer {
+    interval: Duration,
+    next_at: Option<Instant>,
+    last_frame: Option<Instant>,
+    intervals: VecDeque<Duration>,
+    sync_times: VecDeque<Duration>,
+    frames: u64,
+    missed: u64,
+}
+
+impl FramePacer {
+    /// 以目标帧率创建（限制在 0.1-1000 FPS）。
+    pub fn new(fps: f32) -> Self {
+        let fps = if fps.is_finite() { fps } else { 1000.0 };
+        Self::with_interval(Duration::from_secs_f32(1.0 / fps.clamp(0.1, 1000.0)))
+    }
+
+    /// 以目标帧间隔创建。
+    pub fn with_interval(interval: Duration) -> Self {
+        Self {
+            interval: interval.max(Duration::from_millis(1)),
+            next_at: None,
+            last_frame: None,
+            intervals: VecDeque::with_capacity(WINDOW),
+            sync_times: VecDeque::with_capacity(WINDOW),
+            frames: 0,
+            missed: 0,
+        }
+    }
+
+    /// 目标帧间隔。
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// 修改目标帧率，从下一帧开始生效。
+    pub fn set_fps(&mut self, fps: f32) {
+        self.interval = Self::new(fps).interval;
+    }
+
+    /// 睡眠到下一帧的时刻，返回睡眠的时间。
+    ///
+    /// 第一帧立即返回。落后超过一帧时不补发，从当前时刻重新对齐时间表。
+    pub fn wait(&mut self) -> Duration {
+        let now = Instant::now();
+        let slept = match self.next_at {
+            Some(next) if next > now => {
+                std::thread::sleep(next - now);
+                next - now
+            }
+            _ => Duration::ZERO,
+        };
+
+        let start = Instant::now();
+        let scheduled = self.next_at.unwrap_or(start);
+        self.next_at = if start >= scheduled + self.interval {
+            self.missed += 1;
+            Some(start + self.interval)
+        } else {
+            Some(scheduled + self.interval)
+        };
+        if let Some(last) = self.last_frame {
+            push(&mut self.intervals, start - last);
+        }
+        self.last_frame = Some(start);
+        self.frames += 1;
+        slept
+    }
+
+    /// 记录一次同步的耗时，用于估算设备能接收的帧率。
+    pub fn record_sync(&mut self, elapsed: Duration) {
+        push(&mut self.sync_times, elapsed);
+    }
+
+    /// 等待下一帧的时刻，然后同步并记录耗时。
+    pub fn sync(&mut self, bot: &mut ElectronBot) -> Result<bool, BotError> {
+        self.wait();
+        let start = Instant::now();
+        let result = bot.sync();
+        if result.is_ok() {
+            self.record_sync(start.elapsed());
+        }
+        result
+    }
+
+    /// 统计信息。
+    pub fn stats(&self) -> PacerStats {
+        let target = self.interval.as_secs_f64();
+        let average = mean(&self.intervals);
+        let variance = self
+            .intervals
+            .iter()
+            .map(|d| (d.as_secs_f64() - average).powi(2))
+            .sum::<f64>()
+            / self.intervals.len().max(1) as f64;
+        let max_deviation = self
+            .intervals
+            .iter()
+            .map(|d| (d.as_secs_f64() - target).abs())
+            .fold(0.0, f64::max);
+        PacerStats {
+            frames: self.frames,
+            target_fps: (1.0 / target) as f32,
+            actual_fps: rate(average),
+            device_fps: rate(mean(&self.sync_times)),
+            jitter: Duration::from_secs_f64(variance.sqrt()),
+            max_deviation: Duration::from_secs_f64(max_deviation),
+            missed: self.missed,
+        }
+    }
+
+    /// 清空时间表和统计，下一帧立即开始。
+    pub fn reset(&mut self) {
+        *self = Self::with_interval(self.interval);
+    }
+}
+
+fn push(window: &mut VecDeque<Duration>, value: Duration) {
+    if window.len() == WINDOW {
+        window.pop_front();
+    }
+    window.push_back(value);
+}
+
+/// 平均值（秒），窗口为空时为 0。
+fn mean(window: &VecDeque<Duration>) -> f64 {
+    if window.is_empty() {
+        return 0.0;
+    }
+    window.iter().map(Duration::as_secs_f64).sum::<f64>() / window.len() as f64
+}
+
+fn rate(seconds: f64) -> f32 {
+    if seconds > 0.0 {
+        (1.0 / seconds) as f32
+    } else {
+        0.0
+    }
+}
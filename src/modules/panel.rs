@@ -0,0 +1,461 @@
+//! ElectronBot 库的桌面控制面板。
+//!
+//! [`ControlPanel`] 是基于 egui 的桌面程序，可以代替官方的 Windows 上位机：
+//! 实时预览屏幕画面，用滑块调整 6 个关节，一键切换表情，加载动作并在时间轴上播放、暂停和拖动，
+//! 同时显示连接状态。面板通过 [`BotHandle`] 驱动机器人，界面不会被 USB 同步阻塞。
+//!
+//! 用 [`run_panel`] 打开窗口，或直接运行 `electronbot-panel`。
+
+use std::time::Duration;
+
+use eframe::egui;
+
+use crate::modules::face::{Expression, Face, EXPRESSION_NAMES};
+use crate::modules::gesture::{Gesture, GESTURE_NAMES};
+use crate::modules::handle::BotHandle;
+use crate::modules::image::ImageBuffer;
+use crate::modules::servo::DEFAULT_JOINT_LIMITS;
+use crate::modules::source::DEFAULT_INTERVAL;
+use crate::modules::time::Instant;
+use crate::modules::types::JointAngles;
+
+/// 关节滑块的标签。
+const JOINT_LABELS: [&str; 6] = ["头部", "左臂 1", "左臂 2", "右臂 1", "右臂 2", "身体"];
+
+/// 表情切换的过渡时间。
+const EXPRESSION_TRANSITION: Duration = Duration::from_millis(200);
+
+/// 预览画面的显示倍数。
+const PREVIEW_SCALE: f32 = 2.0;
+
+/// 按顺序尝试的系统中文字体，找到第一个后作为后备字体，否则中文标签显示为方框。
+const CJK_FONTS: [&str; 6] = [
+    "C:\\Windows\\Fonts\\msyh.ttc",
+    "C:\\Windows\\Fonts\\simhei.ttf",
+    "/System/Library/Fonts/PingFang.ttc",
+    "/System/Library/Fonts/STHeiti Medium.ttc",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+];
+
+/// 时间轴：正在编辑或播放的动作和播放位置。
+struct Timeline {
+    gesture: Gesture,
+    position: Duration,
+    playing: bool,
+    looping: bool,
+}
+
+/// 面板显示的连接状态。
+#[derive(Debug, Clone, PartialEq)]
+pub struct PanelStatus {
+    /// 最近一次同步后是否仍然连接。
+    pub connected: bool,
+    /// 已成功同步的次数。
+    pub frames: u64,
+    /// 机器人上报的角度。
+    pub reported: JointAngles,
+    /// 最近一次同步失败的错误信息。
+    pub error: Option<String>,
+}
+
+/// 桌面控制面板。
+///
+/// 面板状态（关节滑块、表情、时间轴）可以不打开窗口直接操作，
+/// 每次 [`ControlPanel::advance`] 把画面和关节发送给同步线程。
+pub struct ControlPanel {
+    handle: BotHandle,
+    face: Face,
+    expression: &'static str,
+    joints: [f32; 6],
+    torque: bool,
+    timeline: Option<Timeline>,
+    preview: ImageBuffer,
+    error: Option<String>,
+    gesture_path: String,
+    last_update: Option<Instant>,
+    texture: Option<egui::TextureHandle>,
+}
+
+impl ControlPanel {
+    /// 创建面板，关节从 0 度开始，舵机使能。
+    pub fn new(handle: BotHandle) -> Self {
+        Self {
+            handle,
+            face: Face::new(),
+            expression: EXPRESSION_NAMES[0],
+            joints: [0.0; 6],
+            torque: true,
+            timeline: None,
+            preview: ImageBuffer::new(),
+            error: None,
+            gesture_path: String::new(),
+            last_update: None,
+            texture: None,
+        }
+    }
+
+    /// 面板使用的句柄。
+    pub fn handle(&self) -> &BotHandle {
+        &self.handle
+    }
+
+    /// 当前关节角度。
+    pub fn joints(&self) -> [f32; 6] {
+        self.joints
+    }
+
+    /// 设置一个关节的角度，限制在 [`DEFAULT_JOINT_LIMITS`] 内；超出范围的关节索引被忽略。
+    ///
+    /// 手动调整关节会暂停时间轴播放。
+    pub fn set_joint(&mut self, joint: usize, angle: f32) {
+        if let Some(&(min, max)) = DEFAULT_JOINT_LIMITS.get(joint) {
+            self.joints[joint] = angle.clamp(min, max);
+            self.pause();
+        }
+    }
+
+    /// 所有关节回到 0 度。
+    pub fn reset_joints(&mut self) {
+        self.joints = [0.0; 6];
+        self.pause();
+    }
+
+    /// 舵机是否使能。
+    pub fn torque(&self) -> bool {
+        self.torque
+    }
+
+    /// 使能或释放舵机。
+    pub fn set_torque(&mut self, enable: bool) {
+        self.torque = enable;
+    }
+
+    /// 当前表情名称。
+    pub fn expression(&self) -> &str {
+        self.expression
+    }
+
+    /// 按名称切换表情（名称见 [`EXPRESSION_NAMES`]），带过渡动画。
+    pub fn set_expression(&mut self, name: &str) -> Result<(), String> {
+        let index = EXPRESSION_NAMES
+            .iter()
+            .position(|&n| n == name)
+            .ok_or_else(|| format!("未知的表情: {}", name))?;
+        let expression = Expression::named(name).ok_or_else(|| format!("未知的表情: {}", name))?;
+        self.face.transition_to(expression, EXPRESSION_TRANSITION);
+        self.expression = EXPRESSION_NAMES[index];
+        Ok(())
+    }
+
+    /// 把动作放到时间轴上（停在开头，不自动播放）。
+    pub fn load_timeline(&mut self, gesture: Gesture) {
+        self.joints = gesture.pose_at(Duration::ZERO).0;
+        self.timeline = Some(Timeline {
+            gesture,
+            position: Duration::ZERO,
+            playing: false,
+            looping: false,
+        });
+    }
+
+    /// 时间轴上的动作。
+    pub fn timeline(&self) -> Option<&Gesture> {
+        self.timeline.as_ref().map(|t| &t.gesture)
+    }
+
+    /// 时间轴的播放位置，没有动作时为 `None`。
+    pub fn timeline_position(&self) -> Option<Duration> {
+        self.timeline.as_ref().map(|t| t.position)
+    }
+
+    /// 时间轴是否正在播放。
+    pub fn is_playing(&self) -> bool {
+        self.timeline.as_ref().is_some_and(|t| t.playing)
+    }
+
+    /// 从当前位置开始播放，已经在结尾时从头播放。
+    pub fn play(&mut self) {
+        if let Some(timeline) = &mut self.timeline {
+            if timeline.position >= timeline.gesture.duration() {
+                timeline.position = Duration::ZERO;
+            }
+            timeline.playing = true;
+        }
+    }
+
+    /// 暂停播放，关节停在当前姿态。
+    pub fn pause(&mut self) {
+        if let Some(timeline) = &mut self.timeline {
+            timeline.playing = false;
+        }
+    }
+
+    /// 设置是否循环播放。
+    pub fn set_looping(&mut self, looping: bool) {
+        if let Some(timeline) = &mut self.timeline {
+            timeline.looping = looping;
+        }
+    }
+
+    /// 跳到 `position`（不超过动作时长），关节立即切换到该时刻的姿态。
+    pub fn seek(&mut self, position: Duration) {
+        if let Some(timeline) = &mut self.timeline {
+            timeline.position = position.min(timeline.gesture.duration());
+            self.joints = timeline.gesture.pose_at(timeline.position).0;
+        }
+    }
+
+    /// 前进 `dt`：推进表情过渡和时间轴，重新绘制预览，并把画面和关节交给同步线程。
+    pub fn advance(&mut self, dt: Duration) {
+        self.face.advance(dt);
+        if let Some(timeline) = &mut self.timeline {
+            if timeline.playing {
+                let duration = timeline.gesture.duration();
+                timeline.position += dt;
+                if timeline.position >= duration {
+                    if timeline.looping && !duration.is_zero() {
+                        timeline.position = Duration::from_nanos(
+                            (timeline.position.as_nanos() % duration.as_nanos()) as u64,
+                        );
+                    } else {
+                        timeline.position = duration;
+                        timeline.playing = false;
+                    }
+                }
+                self.joints = timeline.gesture.pose_at(timeline.position).0;
+            }
+        }
+
+        self.face.render(&mut self.preview);
+        self.handle.set_image(&self.preview);
+        self.handle.set_joint_angles(&self.joints, self.torque);
+        if let Some(error) = self.handle.take_error() {
+            self.error = Some(error);
+        }
+    }
+
+    /// 当前预览画面（与发送给机器人的画面相同）。
+    pub fn preview(&self) -> &ImageBuffer {
+        &self.preview
+    }
+
+    /// 连接状态。
+    pub fn status(&self) -> PanelStatus {
+        PanelStatus {
+            connected: self.handle.is_connected(),
+            frames: self.handle.frames(),
+            reported: self.handle.joint_angles(),
+            error: self.error.clone(),
+        }
+    }
+
+    /// 连接状态区域。
+    fn status_ui(&mut self, ui: &mut egui::Ui) {
+        let status = self.status();
+        ui.heading("连接");
+        if status.connected {
+            ui.colored_label(egui::Color32::GREEN, "已连接");
+        } else {
+            ui.colored_label(egui::Color32::RED, "未连接");
+        }
+        ui.label(format!("已同步 {} 帧", status.frames));
+        if let Some(error) = &status.error {
+            ui.colored_label(egui::Color32::YELLOW, error);
+            if ui.button("清除错误").clicked() {
+                self.error = None;
+            }
+        }
+    }
+
+    /// 关节滑块区域。
+    fn joints_ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("关节");
+        let mut torque = self.torque;
+        if ui.checkbox(&mut torque, "舵机使能").changed() {
+            self.set_torque(torque);
+        }
+        let reported = self.handle.joint_angles();
+        for (joint, label) in JOINT_LABELS.iter().enumerate() {
+            let (min, max) = DEFAULT_JOINT_LIMITS[joint];
+            let mut angle = self.joints[joint];
+            ui.horizontal(|ui| {
+                let slider = egui::Slider::new(&mut angle, min..=max)
+                    .text(*label)
+                    .suffix("°");
+                if ui.add(slider).changed() {
+                    self.set_joint(joint, angle);
+                }
+                ui.weak(format!("上报 {:.1}°", reported.0[joint]));
+            });
+        }
+        if ui.button("归零").clicked() {
+            self.reset_joints();
+        }
+    }
+
+    /// 表情按钮区域。
+    fn expressions_ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("表情");
+        ui.horizontal_wrapped(|ui| {
+            for name in EXPRESSION_NAMES {
+                if ui.selectable_label(self.expression == name, name).clicked() {
+                    let _ = self.set_expression(name);
+                }
+            }
+        });
+    }
+
+    /// 动作时间轴区域。
+    fn timeline_ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("动作");
+        ui.horizontal_wrapped(|ui| {
+            for name in GESTURE_NAMES {
+                if ui.button(name).clicked() {
+                    if let Some(gesture) = Gesture::named(name) {
+                        self.load_timeline(gesture);
+                        self.play();
+                    }
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.gesture_path).hint_text("动作文件 (.json)"),
+            );
+            if ui.button("加载").clicked() {
+                match Gesture::load_from_file(&self.gesture_path) {
+                    Ok(gesture) => self.load_timeline(gesture),
+                    Err(e) => self.error = Some(e),
+                }
+            }
+        });
+
+        let Some(timeline) = &self.timeline else {
+            ui.weak("没有加载动作");
+            return;
+        };
+        let name = timeline.gesture.name().to_string();
+        let total = timeline.gesture.duration().as_secs_f32();
+        let mut position = timeline.position.as_secs_f32();
+        let mut looping = timeline.looping;
+        let playing = timeline.playing;
+
+        ui.label(format!("{}（{} 个关键帧）", name, timeline.gesture.len()));
+        ui.horizontal(|ui| {
+            if ui.button(if playing { "暂停" } else { "播放" }).clicked() {
+                if playing {
+                    self.pause();
+                } else {
+                    self.play();
+                }
+            }
+            if ui.checkbox(&mut looping, "循环").changed() {
+                self.set_looping(looping);
+            }
+        });
+        let slider = egui::Slider::new(&mut position, 0.0..=total.max(0.001))
+            .suffix(" 秒")
+            .fixed_decimals(2);
+        if ui.add(slider).changed() {
+            self.pause();
+            self.seek(Duration::from_secs_f32(position));
+        }
+    }
+
+    /// 预览画面区域。
+    fn preview_ui(&mut self, ui: &mut egui::Ui) {
+        let rgb = self.preview.to_rgb_image();
+        let size = [rgb.width() as usize, rgb.height() as usize];
+        let image = egui::ColorImage::from_rgb(size, rgb.as_raw());
+        let texture = match &mut self.texture {
+            Some(texture) => {
+                texture.set(image, egui::TextureOptions::NEAREST);
+                texture
+            }
+            None => self.texture.insert(ui.ctx().load_texture(
+                "preview",
+                image,
+                egui::TextureOptions::NEAREST,
+            )),
+        };
+        let size = texture.size_vec2() * PREVIEW_SCALE;
+        ui.image((texture.id(), size));
+    }
+}
+
+impl eframe::App for ControlPanel {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let now = Instant::now();
+        let dt = self
+            .last_update
+            .map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+        self.last_update = Some(now);
+        self.advance(dt);
+
+        egui::SidePanel::left("controls")
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    self.status_ui(ui);
+                    ui.separator();
+                    self.joints_ui(ui);
+                    ui.separator();
+                    self.expressions_ui(ui);
+                    ui.separator();
+                    self.timeline_ui(ui);
+                });
+            });
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.centered_and_justified(|ui| self.preview_ui(ui));
+        });
+        ctx.request_repaint_after(DEFAULT_INTERVAL);
+    }
+}
+
+/// 加载第一个找到的系统中文字体作为后备字体。
+fn install_cjk_font(ctx: &egui::Context) {
+    let Some(data) = CJK_FONTS.iter().find_map(|path| std::fs::read(path).ok()) else {
+        return;
+    };
+    let mut fonts = egui::FontDefinitions::default();
+    fonts
+        .font_data
+        .insert("cjk".to_string(), egui::FontData::from_owned(data).into());
+    for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+        fonts
+            .families
+            .entry(family)
+            .or_default()
+            .push("cjk".to_string());
+    }
+    ctx.set_fonts(fonts);
+}
+
+/// 打开控制面板窗口，阻塞到窗口关闭。
+///
+/// ```no_run
+/// use electron_bot::{run_panel, BotHandle, ElectronBot};
+///
+/// let mut bot = ElectronBot::new();
+/// bot.connect()?;
+/// run_panel(BotHandle::spawn(bot)?)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn run_panel(handle: BotHandle) -> Result<(), String> {
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_title("ElectronBot")
+            .with_inner_size([860.0, 560.0]),
+        ..Default::default()
+    };
+    eframe::run_native(
+        "ElectronBot",
+        options,
+        Box::new(|cc| {
+            install_cjk_font(&cc.egui_ctx);
+            Ok(Box::new(ControlPanel::new(handle)))
+        }),
+    )
+    .map_err(|e| format!("控制面板启动失败: {}", e))
+}
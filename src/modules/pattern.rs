@@ -0,0 +1,147 @@
+//! ElectronBot 库的平铺图案填充。
+//!
+//! 用 [`Sprite`] 作为图块在矩形区域内重复铺满，用于表盘和测试画面的纹理背景。
+//! 内置棋盘格和条纹图块，也可以使用任意精灵图。
+
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_WIDTH};
+use crate::modules::draw::{Point, Rect};
+use crate::modules::image::ImageBuffer;
+use crate::modules::sprite::Sprite;
+use crate::modules::types::Color;
+
+/// 条纹方向。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StripeDirection {
+    /// 横条纹。
+    Horizontal,
+    /// 竖条纹。
+    Vertical,
+    /// 左下到右上的斜条纹。
+    Diagonal,
+}
+
+impl Sprite {
+    /// `cell` 像素见方、两色交替的棋盘格图块（`2 * cell` 见方）。
+    pub fn checker(cell: usize, a: Color, b: Color) -> Sprite {
+        let cell = cell.max(1);
+        Self::generate(cell * 2, cell * 2, |x, y| {
+            if (x / cell + y / cell).is_multiple_of(2) {
+                a
+            } else {
+                b
+            }
+        })
+    }
+
+    /// 每条 `width` 像素、两色交替的条纹图块。
+    pub fn stripes(width: usize, a: Color, b: Color, direction: StripeDirection) -> Sprite {
+        let width = width.max(1);
+        let size = width * 2;
+        let (w, h) = match direction {
+            StripeDirection::Horizontal => (1, size),
+            StripeDirection::Vertical => (size, 1),
+            StripeDirection::Diagonal => (size, size),
+        };
+        Self::generate(w, h, |x, y| {
+            let t = match direction {
+                StripeDirection::Horizontal => y,
+                StripeDirection::Vertical => x,
+                StripeDirection::Diagonal => x + y,
+            };
+            if (t / width).is_multiple_of(2) {
+                a
+            } else {
+                b
+            }
+        })
+    }
+
+    fn generate<F: Fn(usize, usize) -> Color>(width: usize, height: usize, color: F) -> Sprite {
+        let mut data = Vec::with_capacity(width * height * 3);
+        for y in 0..height {
+            for x in 0..width {
+                let (r, g, b) = color(x, y).rgb();
+                data.extend_from_slice(&[b, g, r]);
+            }
+        }
+        Sprite::from_bgr(width, height, data).expect("图块数据长度与尺寸一致")
+    }
+}
+
+impl ImageBuffer {
+    /// 用图块 `tile` 平铺填满 `rect`（图块左上角与矩形左上角对齐），超出屏幕部分会被裁剪。
+    ///
+    /// 图块的透明像素保留原有内容，半透明像素与之混合。
+    pub fn fill_pattern(&mut self, rect: Rect, tile: &Sprite) {
+        self.fill_pattern_offset(rect, tile, (0, 0));
+    }
+
+    /// 平铺填满 `rect`，图块整体偏移 `offset`；逐帧改变偏移可以做出滚动背景。
+    pub fn fill_pattern_offset(&mut self, rect: Rect, tile: &Sprite, offset: Point) {
+        let (tw, th) = (tile.width(), tile.height());
+        if tw == 0 || th == 0 || rect.is_empty() {
+            return;
+        }
+        let x0 = rect.x.max(0);
+        let y0 = rect.y.max(0);
+        let x1 = (rect.x + rect.width as i32).min(FRAME_WIDTH as i32);
+        let y1 = (rect.y + rect.height as i32).min(FRAME_HEIGHT as i32);
+        if x0 >= x1 || y0 >= y1 {
+            return;
+        }
+        // 屏幕坐标对应的图块坐标
+        let origin = (rect.x + offset.0, rect.y + offset.1);
+        let wrap = |v: i32, origin: i32, size: usize| (v - origin).rem_euclid(size as i32) as usize;
+        let opaque = tile.alpha().iter().all(|&a| a == 255);
+
+        // 不透明图块只有 th 种不同的行，先展开成整行再逐行复制
+        let span = (x1 - x0) as usize;
+        let columns: Vec<usize> = (x0..x1).map(|x| wrap(x, origin.0, tw)).collect();
+        let rows: Vec<Vec<u8>> = if opaque {
+            (0..th)
+                .map(|ty| {
+                    columns
+                        .iter()
+                        .flat_map(|&tx| {
+                            let si = (ty * tw + tx) * 3;
+                            [
+                                tile.as_data()[si],
+                                tile.as_data()[si + 1],
+                                tile.as_data()[si + 2],
+                            ]
+                        })
+                        .collect()
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        for y in y0..y1 {
+            let ty = wrap(y, origin.1, th);
+            let dst = (y as usize * FRAME_WIDTH + x0 as usize) * 3;
+            if opaque {
+                self.data[dst..dst + span * 3].copy_from_slice(&rows[ty]);
+                continue;
+            }
+            for (out, &tx) in self.data[dst..dst + span * 3]
+                .chunks_exact_mut(3)
+                .zip(&columns)
+            {
+                let si = ty * tw + tx;
+                let a = tile.alpha()[si];
+                let src = &tile.as_data()[si * 3..si * 3 + 3];
+                match a {
+                    0 => {}
+                    255 => out.copy_from_slice(src),
+                    _ => {
+                        let a = a as f32 / 255.0;
+                        for (d, &s) in out.iter_mut().zip(src) {
+                            let dv = *d as f32;
+                            *d = (dv + (s as f32 - dv) * a).round() as u8;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
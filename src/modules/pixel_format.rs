@@ -0,0 +1,225 @@
+//! ElectronBot 库的打包像素格式输入。
+//!
+//! 嵌入式和视频源常见的 RGB565、RGBA 等格式直接采样转换到 BGR 帧缓冲区，
+//! 缩放与转换在同一遍完成，不需要先手动展开成 RGB888。
+//! [`PixelView`] 借用更大的源图片（截图、图集），[`ImageBuffer::copy_from`] 直接复制其中的区域。
+
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_SIZE, FRAME_WIDTH};
+use crate::modules::draw::{Point, Rect};
+use crate::modules::image::{FitOptions, ImageBuffer, RowLayout};
+
+/// 打包像素格式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 每像素 3 字节，R、G、B。
+    Rgb888,
+    /// 每像素 3 字节，B、G、R（与 MCU 帧相同）。
+    Bgr888,
+    /// 每像素 2 字节，小端 16 位，高 5 位红、中 6 位绿、低 5 位蓝。
+    Rgb565,
+    /// 每像素 4 字节，R、G、B、A，按 alpha 与黑色混合。
+    Rgba8888,
+    /// 每像素 1 字节灰度。
+    Gray8,
+}
+
+impl PixelFormat {
+    /// 每像素字节数。
+    pub fn bytes_per_pixel(&self) -> usize {
+        match self {
+            PixelFormat::Rgb888 | PixelFormat::Bgr888 => 3,
+            PixelFormat::Rgb565 => 2,
+            PixelFormat::Rgba8888 => 4,
+            PixelFormat::Gray8 => 1,
+        }
+    }
+
+    /// 指定尺寸的一帧所需字节数，超出 `usize` 范围时返回 `None`。
+    pub fn frame_len(&self, width: usize, height: usize) -> Option<usize> {
+        width
+            .checked_mul(height)?
+            .checked_mul(self.bytes_per_pixel())
+    }
+
+    /// 读取一个像素，返回 MCU 所需的 BGR。
+    fn sample(&self, pixel: &[u8]) -> [u8; 3] {
+        match self {
+            PixelFormat::Rgb888 => [pixel[2], pixel[1], pixel[0]],
+            PixelFormat::Bgr888 => [pixel[0], pixel[1], pixel[2]],
+            PixelFormat::Rgb565 => {
+                let (r, g, b) = rgb565_to_rgb(u16::from_le_bytes([pixel[0], pixel[1]]));
+                [b, g, r]
+            }
+            PixelFormat::Rgba8888 => {
+                let a = pixel[3] as u16;
+                let mul = |c: u8| ((c as u16 * a + 127) / 255) as u8;
+                [mul(pixel[2]), mul(pixel[1]), mul(pixel[0])]
+            }
+            PixelFormat::Gray8 => [pixel[0]; 3],
+        }
+    }
+}
+
+/// RGB565 转 RGB888，低位用高位补齐（`0x1f` 扩展为 255）。
+pub fn rgb565_to_rgb(value: u16) -> (u8, u8, u8) {
+    let r = ((value >> 11) & 0x1f) as u8;
+    let g = ((value >> 5) & 0x3f) as u8;
+    let b = (value & 0x1f) as u8;
+    (
+        (r << 3) | (r >> 2),
+        (g << 2) | (g >> 4),
+        (b << 3) | (b >> 2),
+    )
+}
+
+/// 借用的原始像素数据，带格式、尺寸和行布局，创建时检查数据长度。
+#[derive(Debug, Clone, Copy)]
+pub struct PixelView<'a> {
+    data: &'a [u8],
+    format: PixelFormat,
+    width: usize,
+    height: usize,
+    stride: usize,
+    layout: RowLayout,
+}
+
+impl<'a> PixelView<'a> {
+    /// 行紧密排列、从上到下的数据。
+    pub fn new(
+        data: &'a [u8],
+        format: PixelFormat,
+        width: usize,
+        height: usize,
+    ) -> Result<Self, String> {
+        Self::with_layout(data, format, width, height, &RowLayout::default())
+    }
+
+    /// 按行布局 `layout` 解释的数据。
+    pub fn with_layout(
+        data: &'a [u8],
+        format: PixelFormat,
+        width: usize,
+        height: usize,
+        layout: &RowLayout,
+    ) -> Result<Self, String> {
+        if width == 0 || height == 0 {
+            return Err("图片尺寸不能为 0".to_string());
+        }
+        let stride = layout
+            .resolve(data.len(), width, format.bytes_per_pixel(), height)
+            .map_err(|e| format!("{:?} {}", format, e))?;
+        Ok(Self {
+            data,
+            format,
+            width,
+            height,
+            stride,
+            layout: *layout,
+        })
+    }
+
+    /// 像素格式。
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// 宽度。
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// 高度。
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// 第 `y` 行的像素数据（不含行尾填充）。
+    fn row(&self, y: usize) -> &'a [u8] {
+        let start = self.layout.row_offset(y, self.height, self.stride);
+        &self.data[start..start + self.width * self.format.bytes_per_pixel()]
+    }
+
+    /// `(x, y)` 处的像素，返回 MCU 所需的 BGR。
+    fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        let bpp = self.format.bytes_per_pixel();
+        self.format.sample(&self.row(y)[x * bpp..(x + 1) * bpp])
+    }
+}
+
+impl ImageBuffer {
+    /// 从打包像素数据加载（行紧密排列，最近邻缩放，按 `options` 的适配方式和背景色）。
+    ///
+    /// 尺寸与屏幕相同的 BGR888 数据直接复制。
+    pub fn load_pixels(
+        &mut self,
+        data: &[u8],
+        format: PixelFormat,
+        width: usize,
+        height: usize,
+        options: &FitOptions,
+    ) -> Result<(), String> {
+        self.load_pixels_with(data, format, width, height, &RowLayout::default(), options)
+    }
+
+    /// 按行布局 `layout` 从打包像素数据加载（最近邻缩放，按 `options` 的适配方式和背景色）。
+    pub fn load_pixels_with(
+        &mut self,
+        data: &[u8],
+        format: PixelFormat,
+        width: usize,
+        height: usize,
+        layout: &RowLayout,
+        options: &FitOptions,
+    ) -> Result<(), String> {
+        let view = PixelView::with_layout(data, format, width, height, layout)?;
+        if format == PixelFormat::Bgr888
+            && width == FRAME_WIDTH
+            && height == FRAME_HEIGHT
+            && layout.is_packed_top_down(width * 3)
+        {
+            self.data[..FRAME_SIZE].copy_from_slice(&data[..FRAME_SIZE]);
+            return Ok(());
+        }
+        self.load_nearest(width, height, options, |x, y| view.pixel(x, y));
+        Ok(())
+    }
+
+    /// 把源图片中 `src_rect` 区域按 1:1 复制到屏幕上的 `dst`（区域左上角），不缩放。
+    ///
+    /// 超出源图片或屏幕的部分会被裁剪；BGR888 数据按行直接复制，其他格式逐像素转换。
+    /// 不需要每帧先裁剪出中间图片，适合从大截图或图集中取一块显示。
+    pub fn copy_from(&mut self, src: &PixelView, src_rect: Rect, dst: Point) {
+        // 目标坐标 = 源坐标 + 偏移
+        let (off_x, off_y) = (
+            dst.0 as i64 - src_rect.x as i64,
+            dst.1 as i64 - src_rect.y as i64,
+        );
+        let clip = |start: i32, len: usize, size: usize, off: i64, screen: usize| {
+            let lo = (start as i64).max(0).max(-off);
+            let hi = (start as i64 + len as i64)
+                .min(size as i64)
+                .min(screen as i64 - off);
+            (lo < hi).then_some((lo as usize, hi as usize))
+        };
+        let (Some((sx0, sx1)), Some((sy0, sy1))) = (
+            clip(src_rect.x, src_rect.width, src.width, off_x, FRAME_WIDTH),
+            clip(src_rect.y, src_rect.height, src.height, off_y, FRAME_HEIGHT),
+        ) else {
+            return;
+        };
+
+        let dx0 = (sx0 as i64 + off_x) as usize;
+        for sy in sy0..sy1 {
+            let dy = (sy as i64 + off_y) as usize;
+            let dst_start = (dy * FRAME_WIDTH + dx0) * 3;
+            let out = &mut self.data[dst_start..dst_start + (sx1 - sx0) * 3];
+            if src.format == PixelFormat::Bgr888 {
+                out.copy_from_slice(&src.row(sy)[sx0 * 3..sx1 * 3]);
+            } else {
+                for (pixel, sx) in out.chunks_exact_mut(3).zip(sx0..sx1) {
+                    pixel.copy_from_slice(&src.pixel(sx, sy));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! ElectronBot 库的平台相关工具。
+
+/// Linux 上的设备访问权限检查和 udev 规则安装。
+#[cfg(target_os = "linux")]
+pub mod linux {
+    use std::path::Path;
+    use std::process::Command;
+
+    use rusb::UsbContext;
+
+    use crate::modules::types::FirmwareVariant;
+    use crate::modules::usb::find_variant;
+
+    /// 默认的 udev 规则文件，`99-` 保证不会被系统规则覆盖。
+    pub const UDEV_RULE_PATH: &str = "/etc/udev/rules.d/99-electronbot.rules";
+
+    /// 当前用户对设备的访问权限。
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Permission {
+        /// 没有找到匹配的设备。
+        NotFound,
+        /// 设备可以打开。
+        Granted(FirmwareVariant),
+        /// 打开设备时被拒绝（EACCES），需要安装 udev 规则。
+        Denied(FirmwareVariant),
+    }
+
+    /// 找到第一个匹配 `variants` 的设备并尝试打开，检查当前用户是否有访问权限。
+    pub fn check_permissions(variants: &[FirmwareVariant]) -> Result<Permission, String> {
+        let context = rusb::Context::new().map_err(|e| format!("创建上下文失败: {}", e))?;
+        let devices = context
+            .devices()
+            .map_err(|e| format!("获取设备失败: {}", e))?;
+        for device in devices.iter() {
+            let Ok(desc) = device.device_descriptor() else {
+                continue;
+            };
+            let Some(variant) = find_variant(variants, desc.vendor_id(), desc.product_id()) else {
+                continue;
+            };
+            return match device.open() {
+                Ok(_) => Ok(Permission::Granted(variant.clone())),
+                Err(rusb::Error::Access) => Ok(Permission::Denied(variant.clone())),
+                Err(e) => Err(format!("打开设备失败: {}", e)),
+            };
+        }
+        Ok(Permission::NotFound)
+    }
+
+    /// 生成允许普通用户读写 `variants` 中所有设备的 udev 规则。
+    pub fn udev_rule(variants: &[FirmwareVariant]) -> String {
+        let mut rule = String::from("# ElectronBot\n");
+        for variant in variants {
+            rule.push_str(&format!(
+                "SUBSYSTEM==\"usb\", ATTR{{idVendor}}==\"{:04x}\", ATTR{{idProduct}}==\"{:04x}\", \
+                 MODE=\"0666\", GROUP=\"plugdev\"\n",
+                variant.vid, variant.pid
+            ));
+        }
+        rule
+    }
+
+    /// 把 `variants` 的规则写入 [`UDEV_RULE_PATH`] 并重新加载 udev（需要 root）。
+    ///
+    /// 写入前用要写入的路径和规则调用 `confirm`，返回 false 时不写入并返回 `Ok(false)`。
+    pub fn install_udev_rule(
+        variants: &[FirmwareVariant],
+        confirm: impl FnOnce(&Path, &str) -> bool,
+    ) -> Result<bool, String> {
+        let path = Path::new(UDEV_RULE_PATH);
+        let rule = udev_rule(variants);
+        if !confirm(path, &rule) {
+            return Ok(false);
+        }
+        std::fs::write(path, &rule).map_err(|e| {
+            format!(
+                "写入 {} 失败: {}（需要 root 权限，可以用 sudo 运行）",
+                UDEV_RULE_PATH, e
+            )
+        })?;
+
+        #[cfg(feature = "logging")]
+        log::info!("Wrote udev rule to {}", UDEV_RULE_PATH);
+
+        for args in [&["control", "--reload-rules"][..], &["trigger"][..]] {
+            let status = Command::new("udevadm")
+                .args(args)
+                .status()
+                .map_err(|e| format!("规则已写入，但运行 udevadm 失败: {}", e))?;
+            if !status.success() {
+                return Err(format!(
+                    "规则已写入，但 udevadm {} 失败（{}），请重新插拔设备",
+                    args.join(" "),
+                    status
+                ));
+            }
+        }
+        Ok(true)
+    }
+}
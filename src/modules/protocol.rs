@@ -0,0 +1,10 @@
+//! ElectronBot 库的协议参数。
+//!
+//! [`ProtocolProfile`] 描述一帧在 USB 上的布局：屏幕尺寸、每帧的同步周期数、
+//! 每个周期的整包数和包大小，以及尾包中图片数据的字节数（尾包最后附加 32 字节扩展数据）。
+//! 原版固件使用 [`ProtocolProfile::STOCK`]：240×240，4 个周期，每周期 84 个 512 字节的包加
+//! 224 字节的尾包。屏幕或分段方式不同的固件分支只需要换一个参数表，不必修改 `constants.rs`。
+//!
+//! 布局的定义在 `electron_bot_core` 中，这里重新导出。
+
+pub use electron_bot_core::protocol::{ProtocolProfile, EXTRA_SIZE};
@@ -0,0 +1,57 @@
+//! ElectronBot 库的二维码绘制。
+//!
+//! 用于配网、配对或显示网址。每个模块按整数像素绘制以保证清晰，
+//! 四周保留 2 个模块宽的白色静区。
+
+use qrcode::{Color as Module, QrCode};
+
+use crate::modules::image::ImageBuffer;
+use crate::modules::types::Color;
+
+/// 静区宽度（模块数）。
+const QUIET_ZONE: usize = 2;
+
+impl ImageBuffer {
+    /// 在 `(x, y)` 处绘制边长不超过 `size` 像素的二维码（含白色静区）。
+    ///
+    /// 模块尺寸取能放下的最大整数像素，二维码在区域内居中，返回实际边长。
+    pub fn draw_qr<D: AsRef<[u8]>>(
+        &mut self,
+        data: D,
+        x: usize,
+        y: usize,
+        size: usize,
+    ) -> Result<usize, String> {
+        let code = QrCode::new(data).map_err(|e| format!("生成二维码失败: {}", e))?;
+        let width = code.width();
+        let modules = width + QUIET_ZONE * 2;
+        let scale = size / modules;
+        if scale == 0 {
+            return Err(format!(
+                "区域太小: 二维码需要至少 {} 像素，实际 {} 像素",
+                modules, size
+            ));
+        }
+
+        let side = modules * scale;
+        let x = x + (size - side) / 2;
+        let y = y + (size - side) / 2;
+        self.fill_rect(x, y, side, side, Color::White);
+
+        let origin_x = x + QUIET_ZONE * scale;
+        let origin_y = y + QUIET_ZONE * scale;
+        for (i, module) in code.to_colors().into_iter().enumerate() {
+            if module == Module::Dark {
+                let (mx, my) = (i % width, i / width);
+                self.fill_rect(
+                    origin_x + mx * scale,
+                    origin_y + my * scale,
+                    scale,
+                    scale,
+                    Color::Black,
+                );
+            }
+        }
+        Ok(side)
+    }
+}
@@ -0,0 +1,268 @@
+//! ElectronBot 库的帧队列。
+//!
+//! [`FrameQueue`] 是应用线程和同步线程之间的有界队列：渲染线程 `push` 画面，
+//! 同步线程把队列当作 [`FrameSource`] 交给 `ElectronBot::play` 逐帧发送。
+//! 生产者比 USB 快时按 [`QueuePolicy`] 处理：阻塞等待、丢弃最旧的帧（默认，总是发送最新画面）
+//! 或丢弃新来的帧，丢帧数通过 [`FrameQueue::stats`] 查询。
+//!
+//! ```
+//! use electron_bot::{FrameQueue, ImageBuffer, QueuePolicy};
+//!
+//! let queue = FrameQueue::new(2, QueuePolicy::DropOldest);
+//! let producer = queue.clone();
+//! std::thread::spawn(move || {
+//!     for _ in 0..10 {
+//!         producer.push(ImageBuffer::new()).unwrap();
+//!     }
+//!     producer.close();
+//! });
+//! while let Some(_frame) = queue.pop() {}
+//! assert_eq!(queue.stats().pushed, 10);
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::modules::image::ImageBuffer;
+use crate::modules::source::FrameSource;
+use crate::modules::time::Instant;
+use crate::modules::types::JointAngles;
+
+/// 队列已满时的处理方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QueuePolicy {
+    /// 生产者阻塞，直到同步线程取走一帧。
+    Block,
+    /// 丢弃最旧的帧，总是保留最新的画面（默认）。
+    #[default]
+    DropOldest,
+    /// 丢弃新来的帧，保留已经排队的画面。
+    DropNewest,
+}
+
+/// 队列中的一帧。
+#[derive(Debug, Clone)]
+pub struct QueuedFrame {
+    /// 画面。
+    pub image: ImageBuffer,
+    /// 与画面一起发送的关节角度，`None` 表示不改变舵机。
+    pub joints: Option<JointAngles>,
+}
+
+/// 队列统计。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueueStats {
+    /// 放入的帧数（包括被丢弃的帧）。
+    pub pushed: u64,
+    /// 取出的帧数。
+    pub popped: u64,
+    /// 因队列已满丢弃的帧数。
+    pub dropped: u64,
+}
+
+struct State {
+    frames: VecDeque<QueuedFrame>,
+    stats: QueueStats,
+    closed: bool,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+    policy: QueuePolicy,
+}
+
+/// 有界帧队列，克隆后共享同一个队列，可以在多个线程中使用。
+#[derive(Clone)]
+pub struct FrameQueue {
+    shared: Arc<Shared>,
+    /// 作为帧源时最近取出的帧的关节角度。
+    joints: Option<JointAngles>,
+}
+
+impl FrameQueue {
+    /// 创建容量为 `capacity`（至少为 1）的队列。
+    pub fn new(capacity: usize, policy: QueuePolicy) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            shared: Arc::new(Shared {
+                state: Mutex::new(State {
+                    frames: VecDeque::with_capacity(capacity),
+                    stats: QueueStats::default(),
+                    closed: false,
+                }),
+                not_empty: Condvar::new(),
+                not_full: Condvar::new(),
+                capacity,
+                policy,
+            }),
+            joints: None,
+        }
+    }
+
+    /// 放入一帧画面，返回 `false` 表示这一帧被丢弃（[`QueuePolicy::DropNewest`]）。
+    ///
+    /// 队列关闭后返回错误。
+    pub fn push(&self, image: ImageBuffer) -> Result<bool, String> {
+        self.push_frame(QueuedFrame {
+            image,
+            joints: None,
+        })
+    }
+
+    /// 放入一帧画面和对应的关节角度。
+    pub fn push_with_joints(
+        &self,
+        image: ImageBuffer,
+        joints: JointAngles,
+    ) -> Result<bool, String> {
+        self.push_frame(QueuedFrame {
+            image,
+            joints: Some(joints),
+        })
+    }
+
+    /// 放入一帧，队列已满时按策略处理。
+    pub fn push_frame(&self, frame: QueuedFrame) -> Result<bool, String> {
+        let mut state = self.lock();
+        if self.shared.policy == QueuePolicy::Block {
+            while state.frames.len() >= self.shared.capacity && !state.closed {
+                state = self
+                    .shared
+                    .not_full
+                    .wait(state)
+                    .unwrap_or_else(|e| e.into_inner());
+            }
+        }
+        if state.closed {
+            return Err("帧队列已关闭".to_string());
+        }
+        state.stats.pushed += 1;
+
+        let mut accepted = true;
+        if state.frames.len() >= self.shared.capacity {
+            state.stats.dropped += 1;
+            match self.shared.policy {
+                QueuePolicy::DropNewest => accepted = false,
+                _ => {
+                    state.frames.pop_front();
+                }
+            }
+            #[cfg(feature = "logging")]
+            log::debug!("Frame queue full, dropped a frame");
+        }
+        if accepted {
+            state.frames.push_back(frame);
+            self.shared.not_empty.notify_one();
+        }
+        Ok(accepted)
+    }
+
+    /// 取出一帧，队列为空时立即返回 `None`。
+    pub fn try_pop(&self) -> Option<QueuedFrame> {
+        let mut state = self.lock();
+        self.take(&mut state)
+    }
+
+    /// 取出一帧，队列为空时阻塞；队列关闭且取完后返回 `None`。
+    pub fn pop(&self) -> Option<QueuedFrame> {
+        let mut state = self.lock();
+        while state.frames.is_empty() && !state.closed {
+            state = self
+                .shared
+                .not_empty
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+        self.take(&mut state)
+    }
+
+    /// 取出一帧，最多等待 `timeout`。
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<QueuedFrame> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.lock();
+        while state.frames.is_empty() && !state.closed {
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+            state = self
+                .shared
+                .not_empty
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+        self.take(&mut state)
+    }
+
+    /// 关闭队列：之后的 `push` 返回错误，已排队的帧仍然可以取出。
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.shared.not_empty.notify_all();
+        self.shared.not_full.notify_all();
+    }
+
+    /// 队列是否已关闭。
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    /// 排队中的帧数。
+    pub fn len(&self) -> usize {
+        self.lock().frames.len()
+    }
+
+    /// 队列是否为空。
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 队列容量。
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+
+    /// 队列已满时的处理方式。
+    pub fn policy(&self) -> QueuePolicy {
+        self.shared.policy
+    }
+
+    /// 统计信息。
+    pub fn stats(&self) -> QueueStats {
+        self.lock().stats
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // 持锁期间不会 panic，中毒时直接沿用内部数据
+        self.shared.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn take(&self, state: &mut State) -> Option<QueuedFrame> {
+        let frame = state.frames.pop_front()?;
+        state.stats.popped += 1;
+        self.shared.not_full.notify_one();
+        Some(frame)
+    }
+}
+
+/// 作为帧源：阻塞等待下一帧，队列关闭且取完后结束播放。
+impl FrameSource for FrameQueue {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        match self.pop() {
+            Some(frame) => {
+                buffer.as_mut_data().copy_from_slice(frame.image.as_data());
+                self.joints = frame.joints;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn joint_angles(&self) -> Option<JointAngles> {
+        self.joints.clone()
+    }
+}
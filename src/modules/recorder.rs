@@ -0,0 +1,319 @@
+//! ElectronBot 库的会话录制。
+//!
+//! [`SessionRecorder`] 记录每次同步实际发送到机器人的内容，写入一个目录：
+//!
+//! - `session.log`：每行一条记录，以会话开始后的秒数开头
+//!   - `<秒> frame <序号> [文件名]`：一次同步
+//!   - `<秒> joints <6 个角度> on|off`：关节指令变化（第一帧总会记录）
+//! - 画面按 [`FrameFormat`] 保存为 PNG 序列（`frame_000001.png`）、GIF 动画（`session.gif`）
+//!   或视频（`session.mp4`，需要 `video` feature 和 ffmpeg）
+//!
+//! 一般通过 `ElectronBot::start_recording` 和 `stop_recording` 在运行时开关。
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, RgbaImage};
+
+use crate::modules::extra_data::ExtraData;
+use crate::modules::image::ImageBuffer;
+use crate::modules::time::Instant;
+
+/// 画面的保存格式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrameFormat {
+    /// 不保存画面，只写日志。
+    None,
+    /// PNG 图片序列（默认）。
+    #[default]
+    Png,
+    /// 单个 GIF 动画，帧间隔为实际同步间隔。
+    Gif,
+    /// 单个 H.264 视频，通过 ffmpeg 编码。
+    #[cfg(feature = "video")]
+    Video,
+}
+
+/// 录制选项。
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecorderOptions {
+    format: FrameFormat,
+    every: u32,
+}
+
+impl Default for RecorderOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecorderOptions {
+    /// 每帧保存为 PNG。
+    pub fn new() -> Self {
+        Self {
+            format: FrameFormat::Png,
+            every: 1,
+        }
+    }
+
+    /// 设置画面的保存格式。
+    pub fn format(mut self, format: FrameFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// 每 `n` 次同步保存一帧画面（日志仍然记录每次同步）。
+    pub fn every(mut self, n: u32) -> Self {
+        self.every = n.max(1);
+        self
+    }
+}
+
+/// 会话录制器。
+pub struct SessionRecorder {
+    dir: PathBuf,
+    options: RecorderOptions,
+    log: BufWriter<File>,
+    started: Instant,
+    frames: u64,
+    saved: u64,
+    last_joints: Option<[u8; 32]>,
+    sink: Sink,
+}
+
+/// 画面输出。
+enum Sink {
+    None,
+    Png,
+    /// GIF 帧的延迟要等下一帧到来才知道，因此缓存上一帧。
+    Gif {
+        encoder: Box<GifEncoder<BufWriter<File>>>,
+        pending: Option<(RgbaImage, Instant)>,
+    },
+    #[cfg(feature = "video")]
+    Video(std::process::Child),
+}
+
+impl SessionRecorder {
+    /// 在 `dir` 中开始录制（目录不存在时自动创建）。
+    pub fn start<P: AsRef<Path>>(dir: P, options: RecorderOptions) -> Result<Self, String> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir).map_err(|e| format!("创建录制目录失败: {}", e))?;
+        let file = File::create(dir.join("session.log"))
+            .map_err(|e| format!("创建录制日志失败: {}", e))?;
+        let mut log = BufWriter::new(file);
+        writeln!(
+            log,
+            "# ElectronBot session {}",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f %z")
+        )
+        .map_err(|e| format!("写入录制日志失败: {}", e))?;
+
+        let sink = match options.format {
+            FrameFormat::None => Sink::None,
+            FrameFormat::Png => Sink::Png,
+            FrameFormat::Gif => {
+                let file = File::create(dir.join("session.gif"))
+                    .map_err(|e| format!("创建 GIF 失败: {}", e))?;
+                let mut encoder = GifEncoder::new_with_speed(BufWriter::new(file), 10);
+                encoder
+                    .set_repeat(Repeat::Infinite)
+                    .map_err(|e| format!("写入 GIF 失败: {}", e))?;
+                Sink::Gif {
+                    encoder: Box::new(encoder),
+                    pending: None,
+                }
+            }
+            #[cfg(feature = "video")]
+            FrameFormat::Video => Sink::Video(spawn_encoder(&dir.join("session.mp4"))?),
+        };
+
+        #[cfg(feature = "logging")]
+        log::info!("Recording session to {}", dir.display());
+        Ok(Self {
+            dir,
+            options,
+            log,
+            started: Instant::now(),
+            frames: 0,
+            saved: 0,
+            last_joints: None,
+            sink,
+        })
+    }
+
+    /// 录制目录。
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 已记录的同步次数。
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// 已保存的画面数量。
+    pub fn saved_frames(&self) -> u64 {
+        self.saved
+    }
+
+    /// 记录一次同步：`frame` 为实际发送的画面，`extra_data` 为发送的扩展数据。
+    pub fn record(&mut self, frame: &ImageBuffer, extra_data: &ExtraData) -> Result<(), String> {
+        let now = Instant::now();
+        let seconds = now.duration_since(self.started).as_secs_f64();
+        self.frames += 1;
+
+        let file = if (self.frames - 1).is_multiple_of(self.options.every as u64) {
+            self.save_frame(frame, now)?
+        } else {
+            None
+        };
+        let line = match file {
+            Some(file) => format!("{:.3} frame {} {}", seconds, self.frames, file),
+            None => format!("{:.3} frame {}", seconds, self.frames),
+        };
+        self.write_line(&line)?;
+
+        let raw = *extra_data.get_raw();
+        if self.last_joints != Some(raw) {
+            self.last_joints = Some(raw);
+            let angles: Vec<String> = extra_data
+                .get_joint_angles()
+                .as_array()
+                .iter()
+                .map(|a| format!("{:.2}", a))
+                .collect();
+            let state = if extra_data.is_enabled() { "on" } else { "off" };
+            self.write_line(&format!(
+                "{:.3} joints {} {}",
+                seconds,
+                angles.join(" "),
+                state
+            ))?;
+        }
+        self.log
+            .flush()
+            .map_err(|e| format!("写入录制日志失败: {}", e))
+    }
+
+    /// 结束录制：写完 GIF 的最后一帧或等待视频编码完成。
+    pub fn finish(mut self) -> Result<(), String> {
+        self.finish_sink()
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<(), String> {
+        writeln!(self.log, "{}", line).map_err(|e| format!("写入录制日志失败: {}", e))
+    }
+
+    /// 保存画面，返回 PNG 文件名（其他格式返回 `None`）。
+    fn save_frame(&mut self, frame: &ImageBuffer, now: Instant) -> Result<Option<String>, String> {
+        let file = match &mut self.sink {
+            Sink::None => return Ok(None),
+            Sink::Png => {
+                let name = format!("frame_{:06}.png", self.frames);
+                frame.save(self.dir.join(&name))?;
+                Some(name)
+            }
+            Sink::Gif { encoder, pending } => {
+                if let Some((image, at)) = pending.take() {
+                    write_gif_frame(encoder, image, now.duration_since(at))?;
+                }
+                *pending = Some((
+                    image::DynamicImage::ImageRgb8(frame.to_rgb_image()).to_rgba8(),
+                    now,
+                ));
+                None
+            }
+            #[cfg(feature = "video")]
+            Sink::Video(child) => {
+                child
+                    .stdin
+                    .as_mut()
+                    .ok_or("ffmpeg 输入已关闭")?
+                    .write_all(frame.as_data())
+                    .map_err(|e| format!("写入 ffmpeg 失败: {}", e))?;
+                None
+            }
+        };
+        self.saved += 1;
+        Ok(file)
+    }
+
+    fn finish_sink(&mut self) -> Result<(), String> {
+        let _ = self.log.flush();
+        match std::mem::replace(&mut self.sink, Sink::None) {
+            Sink::None | Sink::Png => Ok(()),
+            Sink::Gif {
+                mut encoder,
+                pending,
+            } => {
+                if let Some((image, _)) = pending {
+                    write_gif_frame(&mut encoder, image, Duration::from_millis(100))?;
+                }
+                Ok(())
+            }
+            #[cfg(feature = "video")]
+            Sink::Video(mut child) => {
+                drop(child.stdin.take());
+                let status = child
+                    .wait()
+                    .map_err(|e| format!("等待 ffmpeg 失败: {}", e))?;
+                if status.success() {
+                    Ok(())
+                } else {
+                    Err(format!("ffmpeg 编码失败: {}", status))
+                }
+            }
+        }
+    }
+}
+
+impl Drop for SessionRecorder {
+    fn drop(&mut self) {
+        if let Err(_e) = self.finish_sink() {
+            #[cfg(feature = "logging")]
+            log::warn!("Failed to finish recording: {}", _e);
+        }
+    }
+}
+
+fn write_gif_frame(
+    encoder: &mut GifEncoder<BufWriter<File>>,
+    image: RgbaImage,
+    delay: Duration,
+) -> Result<(), String> {
+    let frame = Frame::from_parts(image, 0, 0, Delay::from_saturating_duration(delay));
+    encoder
+        .encode_frame(frame)
+        .map_err(|e| format!("写入 GIF 失败: {}", e))
+}
+
+/// 启动 ffmpeg 编码进程：标准输入为 BGR 原始帧，时间戳取写入时刻。
+#[cfg(feature = "video")]
+fn spawn_encoder(path: &Path) -> Result<std::process::Child, String> {
+    use std::process::{Command, Stdio};
+
+    use crate::modules::constants::{FRAME_HEIGHT, FRAME_WIDTH};
+
+    Command::new("ffmpeg")
+        .args([
+            "-loglevel",
+            "error",
+            "-y",
+            "-use_wallclock_as_timestamps",
+            "1",
+        ])
+        .args(["-f", "rawvideo", "-pix_fmt", "bgr24"])
+        .args(["-s", &format!("{}x{}", FRAME_WIDTH, FRAME_HEIGHT)])
+        .args([
+            "-i", "-", "-vsync", "vfr", "-c:v", "libx264", "-pix_fmt", "yuv420p",
+        ])
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("启动 ffmpeg 失败: {}", e))
+}
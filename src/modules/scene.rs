@@ -0,0 +1,308 @@
+//! ElectronBot 库的场景管理。
+//!
+//! [`SceneManager`] 持有多个场景（帧源及其舵机角度），每帧选出优先级最高的可用场景驱动机器人，
+//! 例如空闲表情、无操作时的时钟、通知和手动控制。被抢占的场景暂停，重新获得控制后继续播放。
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::modules::face::{Expression, Face, FaceStyle};
+use crate::modules::image::ImageBuffer;
+use crate::modules::notify::{Notification, NotificationPlayback, Notifier};
+use crate::modules::source::{Activity, ColorBars, FrameSource, DEFAULT_INTERVAL};
+use crate::modules::types::{Color, JointAngles};
+use crate::modules::widgets::{Clock, ClockKind, ClockStyle};
+
+/// 通知场景的名称。
+pub const NOTIFICATION_SCENE: &str = "notification";
+
+/// 内置场景的名称，见 [`Scene::named`]；表情名称（见 [`EXPRESSION_NAMES`](crate::EXPRESSION_NAMES)）也可以作为场景名。
+pub const SCENE_NAMES: [&str; 4] = ["face", "clock", "digital-clock", "color-bars"];
+
+/// 内置配色主题的名称，见 [`Theme::named`]。
+pub const THEME_NAMES: [&str; 3] = ["dark", "light", "night"];
+
+/// 配色主题：内置场景中表情和时钟的配色。
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Theme {
+    /// 表情配色。
+    pub face: FaceStyle,
+    /// 时钟配色（表盘类型由场景决定）。
+    pub clock: ClockStyle,
+}
+
+impl Theme {
+    /// 按名称查找内置主题：`dark`（默认，黑底白线）、`light`（白底黑线）、`night`（暗红，夜间不刺眼）。
+    pub fn named(name: &str) -> Option<Self> {
+        let dark = Self::default();
+        Some(match name {
+            "dark" => dark,
+            "light" => Self {
+                face: FaceStyle {
+                    background: Color::White,
+                    eye: Color::Custom(230, 230, 230),
+                    pupil: Color::Black,
+                    line: Color::Black,
+                },
+                clock: ClockStyle {
+                    background: Color::White,
+                    dial: Color::Custom(220, 220, 228),
+                    foreground: Color::Black,
+                    ..dark.clock
+                },
+            },
+            "night" => Self {
+                face: FaceStyle {
+                    background: Color::Black,
+                    eye: Color::Custom(96, 0, 0),
+                    pupil: Color::Black,
+                    line: Color::Custom(96, 0, 0),
+                },
+                clock: ClockStyle {
+                    dial: Color::Custom(24, 0, 0),
+                    foreground: Color::Custom(96, 0, 0),
+                    accent: Color::Custom(128, 24, 0),
+                    ..dark.clock
+                },
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// 场景：帧源和调度参数。
+pub struct Scene {
+    name: String,
+    source: Box<dyn FrameSource>,
+    priority: i32,
+    duration: Option<Duration>,
+    idle_after: Option<Duration>,
+    enabled: bool,
+    /// 作为当前场景播放的时间。
+    played: Duration,
+    /// 加入顺序，优先级相同时后加入的优先。
+    seq: u64,
+}
+
+impl fmt::Debug for Scene {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scene")
+            .field("name", &self.name)
+            .field("priority", &self.priority)
+            .field("duration", &self.duration)
+            .field("idle_after", &self.idle_after)
+            .field("enabled", &self.enabled)
+            .field("played", &self.played)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Scene {
+    /// 创建场景（优先级 0，一直可用）。
+    pub fn new<S: FrameSource + 'static>(name: &str, source: S) -> Self {
+        Self {
+            name: name.to_string(),
+            source: Box::new(source),
+            priority: 0,
+            duration: None,
+            idle_after: None,
+            enabled: true,
+            played: Duration::ZERO,
+            seq: 0,
+        }
+    }
+
+    /// 设置优先级，数值大的优先；相同时后加入的优先。
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// 播放指定时间后移除场景（只累计作为当前场景的时间，按帧间隔计算）。
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// 只在无操作超过指定时间后可用（例如桌面时钟）。
+    pub fn after_inactivity(mut self, timeout: Duration) -> Self {
+        self.idle_after = Some(timeout);
+        self
+    }
+
+    /// 按名称创建内置场景，按 `theme` 配色，名称未知时返回 `None`。
+    ///
+    /// `face` 为平静表情，表情名称（如 `happy`）为对应表情，`clock` 和 `digital-clock`
+    /// 为指针和数字时钟，`color-bars` 为彩条测试图案。
+    pub fn named(name: &str, theme: &Theme) -> Option<Self> {
+        let clock = |kind| {
+            Clock::new().style(ClockStyle {
+                kind,
+                ..theme.clock
+            })
+        };
+        Some(match name {
+            "face" => Self::new(name, Face::new().style(theme.face)),
+            "clock" => Self::new(name, clock(ClockKind::Analog)),
+            "digital-clock" => Self::new(name, clock(ClockKind::Digital)),
+            "color-bars" => Self::new(name, ColorBars::new()),
+            _ => {
+                let mut face = Face::new().style(theme.face);
+                face.set_expression(Expression::named(name)?);
+                Self::new(name, face)
+            }
+        })
+    }
+
+    /// 场景名称。
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 是否启用。
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// 作为当前场景已播放的时间。
+    pub fn played(&self) -> Duration {
+        self.played
+    }
+
+    /// 当前是否可以被选中。
+    fn is_eligible(&self, idle_for: Duration) -> bool {
+        self.enabled && self.idle_after.is_none_or(|t| idle_for >= t)
+    }
+
+    /// 是否已经播放完指定时间。
+    fn is_expired(&self) -> bool {
+        self.duration.is_some_and(|d| self.played >= d)
+    }
+}
+
+/// 场景管理器，本身也是帧源，交给 `ElectronBot::play` 播放。
+///
+/// 帧源结束或超过播放时间的场景会被移除；没有可用场景时播放结束。
+#[derive(Debug, Default)]
+pub struct SceneManager {
+    scenes: Vec<Scene>,
+    activity: Activity,
+    notifier: Notifier,
+    active: Option<u64>,
+    next_seq: u64,
+}
+
+impl SceneManager {
+    /// 创建空的场景管理器。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加场景，替换同名的场景。
+    pub fn add(&mut self, scene: Scene) {
+        self.remove(&scene.name);
+        self.push(scene);
+    }
+
+    fn push(&mut self, mut scene: Scene) {
+        scene.seq = self.next_seq;
+        self.next_seq += 1;
+        self.scenes.push(scene);
+    }
+
+    /// 显示通知（作为名为 [`NOTIFICATION_SCENE`] 的场景，优先级为通知的优先级）。
+    pub fn notify(&mut self, notification: Notification) {
+        let playback = NotificationPlayback::over_current(notification);
+        let priority = playback.priority();
+        self.push(Scene::new(NOTIFICATION_SCENE, playback).priority(priority));
+    }
+
+    /// 移除场景，返回是否存在。
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len = self.scenes.len();
+        self.scenes.retain(|s| s.name != name);
+        self.scenes.len() != len
+    }
+
+    /// 启用或禁用场景，返回是否存在。
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        let mut found = false;
+        for scene in self.scenes.iter_mut().filter(|s| s.name == name) {
+            scene.enabled = enabled;
+            found = true;
+        }
+        found
+    }
+
+    /// 是否包含指定场景。
+    pub fn contains(&self, name: &str) -> bool {
+        self.scenes.iter().any(|s| s.name == name)
+    }
+
+    /// 场景数量。
+    pub fn len(&self) -> usize {
+        self.scenes.len()
+    }
+
+    /// 是否没有场景。
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    /// 最近一帧驱动机器人的场景名称。
+    pub fn active(&self) -> Option<&str> {
+        self.active_scene().map(Scene::name)
+    }
+
+    /// 活动记录（克隆后交给输入处理代码），用于 [`Scene::after_inactivity`]。
+    pub fn activity(&self) -> Activity {
+        self.activity.clone()
+    }
+
+    /// 通知队列（克隆后交给其他线程），下一帧开始时加入场景。
+    pub fn notifier(&self) -> Notifier {
+        self.notifier.clone()
+    }
+
+    fn active_scene(&self) -> Option<&Scene> {
+        let seq = self.active?;
+        self.scenes.iter().find(|s| s.seq == seq)
+    }
+
+    /// 选出当前应该播放的场景。
+    fn select(&self) -> Option<usize> {
+        let idle_for = self.activity.idle_for();
+        (0..self.scenes.len())
+            .filter(|&i| self.scenes[i].is_eligible(idle_for))
+            .max_by_key(|&i| (self.scenes[i].priority, self.scenes[i].seq))
+    }
+}
+
+impl FrameSource for SceneManager {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        for notification in self.notifier.take_all() {
+            self.notify(notification);
+        }
+        while let Some(index) = self.select() {
+            let scene = &mut self.scenes[index];
+            if !scene.is_expired() && scene.source.next_frame(buffer) {
+                scene.played += scene.source.frame_interval().unwrap_or(DEFAULT_INTERVAL);
+                self.active = Some(scene.seq);
+                return true;
+            }
+            #[cfg(feature = "logging")]
+            log::debug!("Scene '{}' finished", scene.name);
+            self.scenes.remove(index);
+        }
+        self.active = None;
+        false
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        self.active_scene().and_then(|s| s.source.frame_interval())
+    }
+
+    fn joint_angles(&self) -> Option<JointAngles> {
+        self.active_scene().and_then(|s| s.source.joint_angles())
+    }
+}
@@ -0,0 +1,455 @@
+//! ElectronBot 库的行为脚本（基于 Rhai）。
+//!
+//! [`ScriptSource`] 加载 Rhai 脚本并作为帧源播放：脚本可以绘图、切换表情、控制舵机，
+//! 每帧调用脚本中的 `frame(t)` 函数。从文件加载时修改脚本会自动重新加载，无需重新编译主程序。
+//!
+//! 脚本可用的函数：
+//!
+//! - 绘图：`fill_screen(color)`、`fill_rect(x, y, w, h, color)`、`line(x1, y1, x2, y2, width, color)`、
+//!   `circle(x, y, r, color)`、`digits(text, x, y, height, color)`、`image(path)`
+//! - 颜色：颜色名（`"red"`）、十六进制（`"#ff8800"`）或 `rgb(r, g, b)`
+//! - 表情：`show_face(bool)`、`expression(name)`、`expression(name, ms)`、`look(x, y)`，
+//!   预设名为 `neutral`、`happy`、`sad`、`surprised`、`sleepy`、`angry`
+//! - 舵机：`joint(index, angle)`、`move_joint(index, angle, ms)`、`pose([a0, a1, a2, a3, a4, a5])`
+//! - 时间：`hour()`、`minute()`、`second()`、`weekday()`（0 为星期一）
+//!
+//! 脚本加载后调用一次 `init()`（如果定义了），之后每帧调用 `frame(t)`，`t` 为播放的秒数。
+//! 两个函数中的 `this` 是跨帧保留的对象，可以保存状态，重新加载脚本后仍然保留。
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, SystemTime};
+
+use rhai::{Array, CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_WIDTH};
+use crate::modules::face::{Expression, Face};
+use crate::modules::image::ImageBuffer;
+use crate::modules::source::{FrameSource, DEFAULT_INTERVAL};
+use crate::modules::types::{Color, JointAngles};
+use crate::modules::widgets::ClockTime;
+
+/// 每次调用脚本的最大操作数，防止死循环卡住播放。
+const MAX_OPERATIONS: u64 = 1_000_000;
+/// 检查脚本文件是否修改的间隔（帧）。
+const RELOAD_CHECK_FRAMES: u64 = 15;
+/// `expression(name)` 的默认过渡时间。
+const EXPRESSION_TRANSITION: Duration = Duration::from_millis(300);
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// 舵机补间。
+#[derive(Debug, Clone, Copy)]
+struct Tween {
+    from: f32,
+    to: f32,
+    duration: Duration,
+    elapsed: Duration,
+}
+
+/// 脚本可以访问的状态。
+#[derive(Debug)]
+struct ScriptState {
+    buffer: ImageBuffer,
+    face: Face,
+    face_visible: bool,
+    pose: JointAngles,
+    tweens: [Option<Tween>; 6],
+    /// 脚本是否控制过舵机。
+    servos: bool,
+}
+
+impl ScriptState {
+    fn advance(&mut self, dt: Duration) {
+        self.face.advance(dt);
+        for (i, slot) in self.tweens.iter_mut().enumerate() {
+            if let Some(tween) = slot {
+                tween.elapsed += dt;
+                let t = if tween.duration.is_zero() {
+                    1.0
+                } else {
+                    (tween.elapsed.as_secs_f32() / tween.duration.as_secs_f32()).min(1.0)
+                };
+                self.pose.0[i] = tween.from + (tween.to - tween.from) * t;
+                if t >= 1.0 {
+                    *slot = None;
+                }
+            }
+        }
+    }
+}
+
+/// 把脚本中的整数或浮点数转换为 `f32`。
+fn num(value: &Dynamic) -> ScriptResult<f32> {
+    if let Ok(i) = value.as_int() {
+        Ok(i as f32)
+    } else if let Ok(f) = value.as_float() {
+        Ok(f as f32)
+    } else {
+        Err(format!("需要数字，实际为 {}", value.type_name()).into())
+    }
+}
+
+/// 坐标和尺寸（负数截断为 0）。
+fn coord(value: &Dynamic) -> ScriptResult<usize> {
+    Ok(num(value)?.max(0.0).round() as usize)
+}
+
+/// 解析颜色：颜色名、十六进制字符串或 `[r, g, b]` 数组。
+fn color(value: &Dynamic) -> ScriptResult<Color> {
+    if let Some(name) = value.read_lock::<rhai::ImmutableString>() {
+        return Ok(match name.to_ascii_lowercase().as_str() {
+            "black" => Color::Black,
+            "white" => Color::White,
+            "red" => Color::Red,
+            "green" => Color::Green,
+            "blue" => Color::Blue,
+            "yellow" => Color::Yellow,
+            "cyan" => Color::Cyan,
+            "magenta" => Color::Magenta,
+            hex => Color::from_hex(hex)?,
+        });
+    }
+    if let Some(array) = value.read_lock::<Array>() {
+        if let [r, g, b] = array.as_slice() {
+            let channel = |c: &Dynamic| num(c).map(|v| v.clamp(0.0, 255.0) as u8);
+            return Ok(Color::Custom(channel(r)?, channel(g)?, channel(b)?));
+        }
+    }
+    Err(format!("无效的颜色: {}", value).into())
+}
+
+/// 预设表情。
+fn preset(name: &str) -> ScriptResult<Expression> {
+    Expression::named(name).ok_or_else(|| format!("未知的表情: {}", name).into())
+}
+
+fn joint_index(index: i64) -> ScriptResult<usize> {
+    usize::try_from(index)
+        .ok()
+        .filter(|&i| i < 6)
+        .ok_or_else(|| format!("关节索引超出范围: {}", index).into())
+}
+
+/// 创建注册了机器人 API 的脚本引擎。
+fn create_engine(state: &Rc<RefCell<ScriptState>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    let s = state.clone();
+    engine.register_fn("fill_screen", move |c: Dynamic| -> ScriptResult<()> {
+        s.borrow_mut().buffer.clear(color(&c)?);
+        Ok(())
+    });
+    let s = state.clone();
+    engine.register_fn(
+        "fill_rect",
+        move |x: Dynamic, y: Dynamic, w: Dynamic, h: Dynamic, c: Dynamic| -> ScriptResult<()> {
+            let (x, y, w, h) = (coord(&x)?, coord(&y)?, coord(&w)?, coord(&h)?);
+            let w = w.min(FRAME_WIDTH.saturating_sub(x));
+            let h = h.min(FRAME_HEIGHT.saturating_sub(y));
+            s.borrow_mut().buffer.fill_rect(x, y, w, h, color(&c)?);
+            Ok(())
+        },
+    );
+    let s = state.clone();
+    engine.register_fn(
+        "line",
+        move |x1: Dynamic,
+              y1: Dynamic,
+              x2: Dynamic,
+              y2: Dynamic,
+              width: Dynamic,
+              c: Dynamic|
+              -> ScriptResult<()> {
+            let from = (num(&x1)? as i32, num(&y1)? as i32);
+            let to = (num(&x2)? as i32, num(&y2)? as i32);
+            let width = coord(&width)?;
+            s.borrow_mut().buffer.draw_line(from, to, width, color(&c)?);
+            Ok(())
+        },
+    );
+    let s = state.clone();
+    engine.register_fn(
+        "circle",
+        move |x: Dynamic, y: Dynamic, r: Dynamic, c: Dynamic| -> ScriptResult<()> {
+            let center = (num(&x)? as i32, num(&y)? as i32);
+            let r = coord(&r)?;
+            s.borrow_mut().buffer.fill_ellipse(center, r, r, color(&c)?);
+            Ok(())
+        },
+    );
+    let s = state.clone();
+    engine.register_fn(
+        "digits",
+        move |text: &str, x: Dynamic, y: Dynamic, h: Dynamic, c: Dynamic| -> ScriptResult<i64> {
+            let (x, y, h) = (coord(&x)?, coord(&y)?, coord(&h)?);
+            let width = s
+                .borrow_mut()
+                .buffer
+                .draw_segment_text(text, x, y, h, color(&c)?);
+            Ok(width as i64)
+        },
+    );
+    #[cfg(feature = "image")]
+    let s = state.clone();
+    #[cfg(feature = "image")]
+    engine.register_fn("image", move |path: &str| -> ScriptResult<()> {
+        s.borrow_mut().buffer.load_from_file(path)?;
+        Ok(())
+    });
+    #[cfg(not(feature = "image"))]
+    engine.register_fn("image", |path: &str| -> ScriptResult<()> {
+        Err(format!("image({}) 需要 `image` 功能", path).into())
+    });
+    engine.register_fn("rgb", |r: Dynamic, g: Dynamic, b: Dynamic| -> Array {
+        vec![r, g, b]
+    });
+
+    let s = state.clone();
+    engine.register_fn("show_face", move |visible: bool| {
+        s.borrow_mut().face_visible = visible;
+    });
+    let s = state.clone();
+    engine.register_fn("expression", move |name: &str| -> ScriptResult<()> {
+        let expression = preset(name)?;
+        s.borrow_mut()
+            .face
+            .transition_to(expression, EXPRESSION_TRANSITION);
+        Ok(())
+    });
+    let s = state.clone();
+    engine.register_fn(
+        "expression",
+        move |name: &str, ms: Dynamic| -> ScriptResult<()> {
+            let expression = preset(name)?;
+            let duration = Duration::from_millis(coord(&ms)? as u64);
+            s.borrow_mut().face.transition_to(expression, duration);
+            Ok(())
+        },
+    );
+    let s = state.clone();
+    engine.register_fn("look", move |x: Dynamic, y: Dynamic| -> ScriptResult<()> {
+        let (x, y) = (num(&x)?, num(&y)?);
+        let mut state = s.borrow_mut();
+        let target = state.face.target().look(x, y);
+        state.face.transition_to(target, EXPRESSION_TRANSITION);
+        Ok(())
+    });
+
+    let s = state.clone();
+    engine.register_fn(
+        "joint",
+        move |index: i64, angle: Dynamic| -> ScriptResult<()> {
+            let (index, angle) = (joint_index(index)?, num(&angle)?);
+            let mut state = s.borrow_mut();
+            state.tweens[index] = None;
+            state.pose.0[index] = angle;
+            state.servos = true;
+            Ok(())
+        },
+    );
+    let s = state.clone();
+    engine.register_fn(
+        "move_joint",
+        move |index: i64, angle: Dynamic, ms: Dynamic| -> ScriptResult<()> {
+            let (index, angle) = (joint_index(index)?, num(&angle)?);
+            let duration = Duration::from_millis(coord(&ms)? as u64);
+            let mut state = s.borrow_mut();
+            let from = state.pose.0[index];
+            state.tweens[index] = Some(Tween {
+                from,
+                to: angle,
+                duration,
+                elapsed: Duration::ZERO,
+            });
+            state.servos = true;
+            Ok(())
+        },
+    );
+    let s = state.clone();
+    engine.register_fn("pose", move |angles: Array| -> ScriptResult<()> {
+        if angles.len() != 6 {
+            return Err(format!("需要 6 个关节角度，实际为 {}", angles.len()).into());
+        }
+        let mut state = s.borrow_mut();
+        for (i, angle) in angles.iter().enumerate() {
+            state.pose.0[i] = num(angle)?;
+        }
+        state.tweens = [None; 6];
+        state.servos = true;
+        Ok(())
+    });
+
+    engine.register_fn("hour", || ClockTime::now().hour as i64);
+    engine.register_fn("minute", || ClockTime::now().minute as i64);
+    engine.register_fn("second", || ClockTime::now().second as i64);
+    engine.register_fn("weekday", || ClockTime::now().weekday as i64);
+    engine
+}
+
+/// 脚本帧源。
+pub struct ScriptSource {
+    engine: Engine,
+    state: Rc<RefCell<ScriptState>>,
+    ast: AST,
+    /// 跨帧保留的 `this` 对象。
+    this: Dynamic,
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    frame: u64,
+    last_error: Option<String>,
+}
+
+impl std::fmt::Debug for ScriptSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptSource")
+            .field("path", &self.path)
+            .field("frame", &self.frame)
+            .field("last_error", &self.last_error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ScriptSource {
+    /// 从脚本代码创建。
+    pub fn from_code(code: &str) -> Result<Self, String> {
+        let state = Rc::new(RefCell::new(ScriptState {
+            buffer: ImageBuffer::new(),
+            face: Face::new(),
+            face_visible: true,
+            pose: JointAngles::new(),
+            tweens: [None; 6],
+            servos: false,
+        }));
+        let engine = create_engine(&state);
+        let mut source = Self {
+            engine,
+            state,
+            ast: AST::empty(),
+            this: Dynamic::from_map(Map::new()),
+            path: None,
+            modified: None,
+            frame: 0,
+            last_error: None,
+        };
+        source.load(code)?;
+        Ok(source)
+    }
+
+    /// 从文件加载，文件修改后自动重新加载。
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let code = std::fs::read_to_string(path)
+            .map_err(|e| format!("读取脚本失败 {}: {}", path.display(), e))?;
+        let mut source = Self::from_code(&code)?;
+        source.modified = modified_time(path);
+        source.path = Some(path.to_path_buf());
+        Ok(source)
+    }
+
+    /// 编译并运行脚本的顶层代码，然后调用 `init()`。
+    fn load(&mut self, code: &str) -> Result<(), String> {
+        let ast = self
+            .engine
+            .compile(code)
+            .map_err(|e| format!("脚本编译失败: {}", e))?;
+        self.engine
+            .run_ast_with_scope(&mut Scope::new(), &ast)
+            .map_err(|e| format!("脚本运行失败: {}", e))?;
+        self.ast = ast;
+        self.call("init", ())
+    }
+
+    /// 重新读取脚本文件，失败时保留原来的脚本。
+    pub fn reload(&mut self) -> Result<(), String> {
+        let Some(path) = self.path.clone() else {
+            return Ok(());
+        };
+        self.modified = modified_time(&path);
+        let result = std::fs::read_to_string(&path)
+            .map_err(|e| format!("读取脚本失败 {}: {}", path.display(), e))
+            .and_then(|code| self.load(&code));
+        #[cfg(feature = "logging")]
+        match &result {
+            Ok(()) => log::info!("Reloaded script {}", path.display()),
+            Err(e) => log::warn!("{}", e),
+        }
+        self.last_error = result.as_ref().err().cloned();
+        result
+    }
+
+    /// 最近一次脚本错误（编译或运行），成功运行后清除。
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    /// 调用脚本函数（未定义时忽略）。
+    fn call(&mut self, name: &str, args: impl rhai::FuncArgs) -> Result<(), String> {
+        if !self.ast.iter_functions().any(|f| f.name == name) {
+            return Ok(());
+        }
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut self.this);
+        self.engine
+            .call_fn_with_options::<Dynamic>(options, &mut Scope::new(), &self.ast, name, args)
+            .map(|_| ())
+            .map_err(|e| format!("脚本函数 {} 出错: {}", name, e))
+    }
+
+    /// 文件修改后重新加载。
+    fn check_reload(&mut self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if modified_time(path) != self.modified {
+            let _ = self.reload();
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl FrameSource for ScriptSource {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        if self.frame.is_multiple_of(RELOAD_CHECK_FRAMES) {
+            self.check_reload();
+        }
+        self.frame += 1;
+
+        {
+            let mut state = self.state.borrow_mut();
+            state.advance(DEFAULT_INTERVAL);
+            if state.face_visible {
+                let ScriptState { face, buffer, .. } = &mut *state;
+                face.render(buffer);
+            }
+        }
+        let t = (DEFAULT_INTERVAL * self.frame as u32).as_secs_f64();
+        match self.call("frame", (t,)) {
+            Ok(()) => self.last_error = None,
+            Err(e) => {
+                #[cfg(feature = "logging")]
+                log::warn!("{}", e);
+                self.last_error = Some(e);
+            }
+        }
+        buffer
+            .as_mut_data()
+            .copy_from_slice(self.state.borrow().buffer.as_data());
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        Some(DEFAULT_INTERVAL)
+    }
+
+    fn joint_angles(&self) -> Option<JointAngles> {
+        let state = self.state.borrow();
+        state.servos.then(|| state.pose.clone())
+    }
+}
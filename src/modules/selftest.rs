@@ -0,0 +1,461 @@
+//! ElectronBot 库的自检。
+//!
+//! 组装机器人之后运行一遍固定的检查流程：依次显示纯色和渐变（人工确认屏幕颜色和坏点），
+//! 每个关节小幅转动并比较机器人上报的角度，最后连续发送若干帧测量吞吐量，
+//! 结果汇总为 [`SelfTestReport`]。检查结束后恢复原来的画面和关节指令。
+//!
+//! ```rust,no_run
+//! use electron_bot::ElectronBot;
+//!
+//! let mut bot = ElectronBot::new();
+//! bot.connect()?;
+//! let report = bot.self_test()?;
+//! print!("{}", report);
+//! assert!(report.passed());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::time::Duration;
+
+use crate::modules::color::{Gradient, GradientKind};
+use crate::modules::constants::FRAME_SIZE;
+use crate::modules::error::BotError;
+use crate::modules::servo::DEFAULT_JOINT_LIMITS;
+use crate::modules::time::Instant;
+use crate::modules::types::{Color, JointAngles};
+use crate::ElectronBot;
+
+/// 关节名称（报告中使用）。
+const JOINT_NAMES: [&str; 6] = [
+    "头部",
+    "左臂旋转",
+    "左臂抬起",
+    "右臂旋转",
+    "右臂抬起",
+    "身体",
+];
+
+/// 依次显示的纯色。
+const FILL_COLORS: [(Color, &str); 5] = [
+    (Color::Red, "红色"),
+    (Color::Green, "绿色"),
+    (Color::Blue, "蓝色"),
+    (Color::White, "白色"),
+    (Color::Black, "黑色"),
+];
+
+/// 自检选项。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SelfTestOptions {
+    /// 每个纯色和渐变画面的显示时间。
+    pub hold: Duration,
+    /// 是否检查关节。
+    pub joints: bool,
+    /// 关节转动的角度（度）。
+    pub joint_delta: f32,
+    /// 关节到位的最长等待时间。
+    pub settle: Duration,
+    /// 上报角度与目标角度的允许误差（度）。
+    pub tolerance: f32,
+    /// 测量吞吐量发送的帧数，0 表示跳过。
+    pub throughput_frames: usize,
+}
+
+impl SelfTestOptions {
+    /// 默认选项：每个画面显示 500 毫秒，关节转动 10°，允许误差 3°，发送 30 帧测量吞吐量。
+    pub fn new() -> Self {
+        Self {
+            hold: Duration::from_millis(500),
+            joints: true,
+            joint_delta: 10.0,
+            settle: Duration::from_millis(1500),
+            tolerance: 3.0,
+            throughput_frames: 30,
+        }
+    }
+
+    /// 设置每个画面的显示时间。
+    pub fn hold(mut self, hold: Duration) -> Self {
+        self.hold = hold;
+        self
+    }
+
+    /// 设置是否检查关节（舵机没有装好时可以先跳过）。
+    pub fn joints(mut self, enable: bool) -> Self {
+        self.joints = enable;
+        self
+    }
+
+    /// 设置关节转动的角度。
+    pub fn joint_delta(mut self, degrees: f32) -> Self {
+        self.joint_delta = degrees.abs();
+        self
+    }
+
+    /// 设置关节到位的最长等待时间。
+    pub fn settle(mut self, settle: Duration) -> Self {
+        self.settle = settle;
+        self
+    }
+
+    /// 设置允许误差。
+    pub fn tolerance(mut self, degrees: f32) -> Self {
+        self.tolerance = degrees.abs();
+        self
+    }
+
+    /// 设置测量吞吐量发送的帧数。
+    pub fn throughput_frames(mut self, frames: usize) -> Self {
+        self.throughput_frames = frames;
+        self
+    }
+}
+
+impl Default for SelfTestOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 单项检查的结果。
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckResult {
+    /// 检查名称。
+    pub name: String,
+    /// 是否通过。
+    pub passed: bool,
+    /// 说明（失败时为错误信息）。
+    pub detail: String,
+}
+
+/// 单个关节的检查结果。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointCheck {
+    /// 关节索引。
+    pub joint: usize,
+    /// 目标角度。
+    pub target: f32,
+    /// 机器人上报的角度。
+    pub reported: f32,
+    /// 到位用时，没有到位时为 `None`。
+    pub settled_in: Option<Duration>,
+}
+
+impl JointCheck {
+    /// 上报角度与目标角度的误差。
+    pub fn error(&self) -> f32 {
+        (self.reported - self.target).abs()
+    }
+
+    /// 是否在等待时间内到位。
+    pub fn passed(&self) -> bool {
+        self.settled_in.is_some()
+    }
+}
+
+/// 吞吐量测量结果。
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Throughput {
+    /// 成功发送的帧数。
+    pub frames: usize,
+    /// 总耗时。
+    pub elapsed: Duration,
+}
+
+impl Throughput {
+    /// 帧率。
+    pub fn fps(&self) -> f32 {
+        let seconds = self.elapsed.as_secs_f32();
+        if seconds > 0.0 {
+            self.frames as f32 / seconds
+        } else {
+            0.0
+        }
+    }
+
+    /// 画面数据的传输速率（字节/秒）。
+    pub fn bytes_per_second(&self) -> f32 {
+        self.fps() * FRAME_SIZE as f32
+    }
+}
+
+/// 自检报告。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SelfTestReport {
+    /// 屏幕检查（纯色和渐变）。
+    pub display: Vec<CheckResult>,
+    /// 关节检查，跳过时为空。
+    pub joints: Vec<JointCheck>,
+    /// 允许误差（度）。
+    pub tolerance: f32,
+    /// 吞吐量，跳过或失败时为 `None`。
+    pub throughput: Option<Throughput>,
+    /// 吞吐量测量失败的错误信息。
+    pub throughput_error: Option<String>,
+    /// 总耗时。
+    pub elapsed: Duration,
+}
+
+impl SelfTestReport {
+    /// 所有检查是否都通过。
+    pub fn passed(&self) -> bool {
+        self.display.iter().all(|c| c.passed)
+            && self.joints.iter().all(JointCheck::passed)
+            && self.throughput_error.is_none()
+    }
+
+    /// 没有通过的检查项名称。
+    pub fn failures(&self) -> Vec<String> {
+        let mut failures: Vec<String> = self
+            .display
+            .iter()
+            .filter(|c| !c.passed)
+            .map(|c| c.name.clone())
+            .collect();
+        failures.extend(
+            self.joints
+                .iter()
+                .filter(|j| !j.passed())
+                .map(|j| format!("关节 {} ({})", j.joint, JOINT_NAMES[j.joint])),
+        );
+        if self.throughput_error.is_some() {
+            failures.push("吞吐量".to_string());
+        }
+        failures
+    }
+}
+
+impl std::fmt::Display for SelfTestReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mark = |passed: bool| if passed { "通过" } else { "失败" };
+        writeln!(f, "屏幕:")?;
+        for check in &self.display {
+            writeln!(
+                f,
+                "  [{}] {} {}",
+                mark(check.passed),
+                check.name,
+                check.detail
+            )?;
+        }
+        if !self.joints.is_empty() {
+            writeln!(f, "关节 (允许误差 {:.1}°):", self.tolerance)?;
+            for joint in &self.joints {
+                write!(
+                    f,
+                    "  [{}] {} {}: 目标 {:.1}° 上报 {:.1}° 误差 {:.1}°",
+                    mark(joint.passed()),
+                    joint.joint,
+                    JOINT_NAMES[joint.joint],
+                    joint.target,
+                    joint.reported,
+                    joint.error()
+                )?;
+                match joint.settled_in {
+                    Some(time) => writeln!(f, " ({} 毫秒到位)", time.as_millis())?,
+                    None => writeln!(f, " (未到位)")?,
+                }
+            }
+        }
+        match (&self.throughput, &self.throughput_error) {
+            (Some(throughput), _) => writeln!(
+                f,
+                "吞吐量: {:.1} FPS, {:.1} KB/s ({} 帧)",
+                throughput.fps(),
+                throughput.bytes_per_second() / 1024.0,
+                throughput.frames
+            )?,
+            (None, Some(e)) => writeln!(f, "吞吐量: 失败 {}", e)?,
+            (None, None) => {}
+        }
+        writeln!(
+            f,
+            "结果: {} (用时 {:.1} 秒)",
+            mark(self.passed()),
+            self.elapsed.as_secs_f32()
+        )
+    }
+}
+
+/// 运行自检。
+///
+/// 取消（[`BotError::Cancelled`]）、急停和断开连接会中止自检并返回错误，其他同步失败记录在报告中。
+pub(crate) fn run(
+    bot: &mut ElectronBot,
+    options: &SelfTestOptions,
+) -> Result<SelfTestReport, BotError> {
+    if !bot.is_connected() {
+        return Err(BotError::NotConnected);
+    }
+    if bot.is_emergency_stopped() {
+        return Err(BotError::EmergencyStop);
+    }
+    let started = Instant::now();
+    let saved_image = bot.image_buffer().clone();
+    let saved_joints = bot.target_joint_angles();
+    let saved_enable = bot.extra_data().is_enabled();
+
+    let result = run_checks(bot, options);
+
+    // 恢复原来的画面和关节指令
+    *bot.image_buffer() = saved_image;
+    bot.set_joint_angles(saved_joints.as_array(), saved_enable)?;
+    if bot.is_connected() {
+        let _ = bot.sync();
+    }
+    let mut report = result?;
+    report.elapsed = started.elapsed();
+    #[cfg(feature = "logging")]
+    log::info!(
+        "Self-test finished: {}",
+        if report.passed() { "passed" } else { "failed" }
+    );
+    Ok(report)
+}
+
+fn run_checks(
+    bot: &mut ElectronBot,
+    options: &SelfTestOptions,
+) -> Result<SelfTestReport, BotError> {
+    let mut report = SelfTestReport {
+        tolerance: options.tolerance,
+        ..Default::default()
+    };
+
+    // 1. 屏幕
+    #[cfg(feature = "logging")]
+    log::info!("Self-test: display");
+    for (color, name) in FILL_COLORS {
+        bot.set_image_color(color);
+        report.display.push(show(bot, name, options.hold)?);
+    }
+    let gradient = Gradient::from_palette(
+        GradientKind::Horizontal,
+        &[Color::Red, Color::Green, Color::Blue],
+    );
+    bot.set_image_gradient(&gradient);
+    report.display.push(show(bot, "渐变", options.hold)?);
+
+    // 2. 关节：以当前上报的角度为基准，逐个转动 joint_delta 度再转回
+    if options.joints {
+        #[cfg(feature = "logging")]
+        log::info!("Self-test: joints");
+        let base = bot.get_joint_angles();
+        let mut limbs = base.clone();
+        for (joint, (min, max)) in DEFAULT_JOINT_LIMITS.iter().enumerate() {
+            let start = base.as_array()[joint].clamp(*min, *max);
+            let target = if start + options.joint_delta <= *max {
+                start + options.joint_delta
+            } else {
+                start - options.joint_delta
+            };
+            limbs.as_array_mut()[joint] = target;
+            report.joints.push(move_joint(bot, &limbs, joint, options)?);
+            limbs.as_array_mut()[joint] = start;
+            move_joint(bot, &limbs, joint, options)?;
+        }
+    }
+
+    // 3. 吞吐量：连续发送变化的画面
+    if options.throughput_frames > 0 {
+        #[cfg(feature = "logging")]
+        log::info!("Self-test: throughput");
+        let started = Instant::now();
+        let mut frames = 0;
+        for i in 0..options.throughput_frames {
+            let hue = (i * 360 / options.throughput_frames) as f32;
+            bot.set_image_gradient(&gradient.hue_shifted(hue));
+            match bot.sync() {
+                Ok(true) => frames += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    abort_on(&e)?;
+                    report.throughput_error = Some(e.to_string());
+                    break;
+                }
+            }
+        }
+        if report.throughput_error.is_none() {
+            report.throughput = Some(Throughput {
+                frames,
+                elapsed: started.elapsed(),
+            });
+            if frames < options.throughput_frames {
+                report.throughput_error = Some(format!(
+                    "{} 帧中只有 {} 帧同步成功",
+                    options.throughput_frames, frames
+                ));
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// 发送当前画面并保持 `hold`。
+fn show(bot: &mut ElectronBot, name: &str, hold: Duration) -> Result<CheckResult, BotError> {
+    let started = Instant::now();
+    let result = bot.sync();
+    let (passed, detail) = match result {
+        Ok(true) => (true, format!("({} 毫秒)", started.elapsed().as_millis())),
+        Ok(false) => (false, "同步未完成".to_string()),
+        Err(e) => {
+            abort_on(&e)?;
+            (false, e.to_string())
+        }
+    };
+    // 保持期间继续同步，画面不会被固件的超时处理清掉
+    while started.elapsed() < hold {
+        match bot.sync() {
+            Ok(_) => {}
+            Err(e) => {
+                abort_on(&e)?;
+                break;
+            }
+        }
+    }
+    Ok(CheckResult {
+        name: name.to_string(),
+        passed,
+        detail,
+    })
+}
+
+/// 设置目标角度并同步，直到 `joint` 的上报角度进入允许误差或超过等待时间。
+fn move_joint(
+    bot: &mut ElectronBot,
+    angles: &JointAngles,
+    joint: usize,
+    options: &SelfTestOptions,
+) -> Result<JointCheck, BotError> {
+    let target = angles.as_array()[joint];
+    bot.set_joint_angles(angles.as_array(), true)?;
+    let started = Instant::now();
+    loop {
+        if bot.is_emergency_stopped() {
+            return Err(BotError::EmergencyStop);
+        }
+        if let Err(e) = bot.sync() {
+            abort_on(&e)?;
+        }
+        let reported = bot.get_joint_angles().as_array()[joint];
+        let elapsed = started.elapsed();
+        let settled = (reported - target).abs() <= options.tolerance;
+        if settled || elapsed >= options.settle {
+            return Ok(JointCheck {
+                joint,
+                target,
+                reported,
+                settled_in: settled.then_some(elapsed),
+            });
+        }
+    }
+}
+
+/// 取消和断开连接时中止自检。
+fn abort_on(error: &BotError) -> Result<(), BotError> {
+    match error {
+        BotError::Cancelled => Err(BotError::Cancelled),
+        BotError::NotConnected => Err(BotError::NotConnected),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,173 @@
+//! ElectronBot 库的舵机帧源。
+//!
+//! [`ServoSource`] 保存 6 个关节的目标角度，每帧按最大速度向目标移动并限制在角度范围内，
+//! 同时显示表情。手柄、键盘等遥控输入只需要修改目标角度和表情。
+
+use std::time::Duration;
+
+use crate::modules::face::{Expression, Face};
+use crate::modules::image::ImageBuffer;
+use crate::modules::source::{FrameSource, DEFAULT_INTERVAL};
+use crate::modules::types::JointAngles;
+
+/// 默认的关节角度范围（度）：头部、左臂两个关节、右臂两个关节、身体。
+pub const DEFAULT_JOINT_LIMITS: [(f32, f32); 6] = [
+    (-15.0, 15.0),
+    (-30.0, 30.0),
+    (-60.0, 60.0),
+    (-30.0, 30.0),
+    (-60.0, 60.0),
+    (-60.0, 60.0),
+];
+
+/// 表情切换的过渡时间。
+const EXPRESSION_TRANSITION: Duration = Duration::from_millis(200);
+
+/// 按目标角度驱动舵机的帧源，同时显示表情。
+#[derive(Debug, Clone)]
+pub struct ServoSource {
+    face: Face,
+    target: JointAngles,
+    angles: JointAngles,
+    limits: [(f32, f32); 6],
+    /// 每秒最大转动角度，`None` 表示立即到达目标。
+    max_speed: Option<f32>,
+    enabled: bool,
+}
+
+impl Default for ServoSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ServoSource {
+    /// 创建帧源：所有关节在 0 度，最大速度 90 度每秒，使用默认角度范围。
+    pub fn new() -> Self {
+        Self {
+            face: Face::new(),
+            target: JointAngles::new(),
+            angles: JointAngles::new(),
+            limits: DEFAULT_JOINT_LIMITS,
+            max_speed: Some(90.0),
+            enabled: true,
+        }
+    }
+
+    /// 设置表情引擎。
+    pub fn face(mut self, face: Face) -> Self {
+        self.face = face;
+        self
+    }
+
+    /// 设置全部关节的角度范围（度）。
+    pub fn limits(mut self, limits: [(f32, f32); 6]) -> Self {
+        self.limits = limits;
+        self.clamp_target();
+        self
+    }
+
+    /// 设置每秒最大转动角度，`None` 表示立即到达目标。
+    pub fn max_speed(mut self, degrees_per_second: Option<f32>) -> Self {
+        self.max_speed = degrees_per_second.map(f32::abs);
+        self
+    }
+
+    /// 设置单个关节的角度范围（度），索引超出范围时忽略。
+    pub fn set_limit(&mut self, joint: usize, min: f32, max: f32) {
+        if let Some(limit) = self.limits.get_mut(joint) {
+            *limit = (min.min(max), min.max(max));
+            self.clamp_target();
+        }
+    }
+
+    /// 关节的角度范围。
+    pub fn limit(&self, joint: usize) -> Option<(f32, f32)> {
+        self.limits.get(joint).copied()
+    }
+
+    /// 设置关节的目标角度（限制在角度范围内），索引超出范围时忽略。
+    pub fn set_target(&mut self, joint: usize, angle: f32) {
+        if let Some(&(min, max)) = self.limits.get(joint) {
+            self.target.0[joint] = angle.clamp(min, max);
+        }
+    }
+
+    /// 在当前目标上增加角度（点动）。
+    pub fn jog(&mut self, joint: usize, delta: f32) {
+        if let Some(angle) = self.target.get(joint) {
+            self.set_target(joint, angle + delta);
+        }
+    }
+
+    /// 设置全部关节的目标角度。
+    pub fn set_pose(&mut self, pose: &JointAngles) {
+        for (joint, &angle) in pose.as_array().iter().enumerate() {
+            self.set_target(joint, angle);
+        }
+    }
+
+    /// 目标角度。
+    pub fn target(&self) -> &JointAngles {
+        &self.target
+    }
+
+    /// 当前发送的角度（按最大速度靠近目标）。
+    pub fn angles(&self) -> &JointAngles {
+        &self.angles
+    }
+
+    /// 使能或释放舵机；释放时不发送角度。
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// 舵机是否使能。
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// 平滑切换到表情。
+    pub fn set_expression(&mut self, expression: Expression) {
+        self.face.transition_to(expression, EXPRESSION_TRANSITION);
+    }
+
+    /// 表情引擎。
+    pub fn face_mut(&mut self) -> &mut Face {
+        &mut self.face
+    }
+
+    /// 推进时间：当前角度按最大速度靠近目标。
+    pub fn advance(&mut self, dt: Duration) {
+        let step = self.max_speed.map(|speed| speed * dt.as_secs_f32());
+        for (angle, &target) in self.angles.0.iter_mut().zip(&self.target.0) {
+            *angle = match step {
+                Some(step) => *angle + (target - *angle).clamp(-step, step),
+                None => target,
+            };
+        }
+        self.face.advance(dt);
+    }
+
+    fn clamp_target(&mut self) {
+        for (angle, &(min, max)) in self.target.0.iter_mut().zip(&self.limits) {
+            *angle = angle.clamp(min, max);
+        }
+    }
+}
+
+impl FrameSource for ServoSource {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        self.face.render(buffer);
+        self.advance(DEFAULT_INTERVAL);
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        Some(DEFAULT_INTERVAL)
+    }
+
+    fn joint_angles(&self) -> Option<JointAngles> {
+        self.enabled.then(|| self.angles.clone())
+    }
+}
@@ -0,0 +1,344 @@
+//! ElectronBot 库的简易接口。
+//!
+//! [`SimpleBot`] 面向课堂和初学者：不需要了解图片缓冲区、扩展数据和同步，
+//! 每个方法做一件看得见的事，内部完成同步（失败时自动重试，USB 断开时重新连接），
+//! 动作播放完才返回。需要更多控制时用 [`SimpleBot::bot_mut`] 访问底层的 `ElectronBot`。
+//!
+//! ```rust,no_run
+//! use electron_bot::{Color, LookDirection, SimpleBot};
+//!
+//! let mut bot = SimpleBot::connect()?;
+//! bot.show_color(Color::Blue)?;
+//! bot.say_face("happy")?;
+//! bot.wave()?;
+//! bot.look(LookDirection::Left)?;
+//! bot.move_head(10.0, -20.0)?;
+//! # Ok::<(), electron_bot::BotError>(())
+//! ```
+
+use std::time::Duration;
+
+use crate::modules::constants::{JOINT_BODY, JOINT_HEAD};
+use crate::modules::error::{BotError, ErrorClass, TransferError};
+use crate::modules::face::{Expression, Face, EXPRESSION_NAMES};
+use crate::modules::gesture::{Gesture, GESTURE_NAMES};
+use crate::modules::servo::DEFAULT_JOINT_LIMITS;
+use crate::modules::source::DEFAULT_INTERVAL;
+use crate::modules::time::Instant;
+use crate::modules::types::{Color, JointAngles};
+use crate::ElectronBot;
+
+/// 默认的同步重试次数。
+const DEFAULT_RETRIES: u32 = 3;
+
+/// 两次重试之间的等待时间。
+const RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// 表情切换的过渡时间。
+const FACE_TRANSITION: Duration = Duration::from_millis(300);
+
+/// 关节移动的最短时间。
+const MIN_MOVE_TIME: Duration = Duration::from_millis(200);
+
+/// 看向左右时身体转动的角度。
+const LOOK_YAW: f32 = 30.0;
+
+/// 看向上下时头部俯仰的角度。
+const LOOK_PITCH: f32 = 10.0;
+
+/// 看向某个方向时瞳孔移动的距离（-1 到 1）。
+const LOOK_PUPIL: f32 = 0.6;
+
+/// 看的方向。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LookDirection {
+    /// 正前方（默认）。
+    #[default]
+    Center,
+    /// 左边。
+    Left,
+    /// 右边。
+    Right,
+    /// 上面。
+    Up,
+    /// 下面。
+    Down,
+}
+
+impl LookDirection {
+    /// 按名称查找方向（`center`、`left`、`right`、`up`、`down`）。
+    pub fn named(name: &str) -> Option<Self> {
+        Some(match name {
+            "center" => Self::Center,
+            "left" => Self::Left,
+            "right" => Self::Right,
+            "up" => Self::Up,
+            "down" => Self::Down,
+            _ => return None,
+        })
+    }
+
+    /// 头部的（俯仰, 身体旋转）角度，单位度。
+    fn head(self) -> (f32, f32) {
+        match self {
+            Self::Center => (0.0, 0.0),
+            Self::Left => (0.0, -LOOK_YAW),
+            Self::Right => (0.0, LOOK_YAW),
+            Self::Up => (LOOK_PITCH, 0.0),
+            Self::Down => (-LOOK_PITCH, 0.0),
+        }
+    }
+
+    /// 瞳孔位置（与 `Expression` 的瞳孔坐标一致）。
+    fn pupil(self) -> (f32, f32) {
+        match self {
+            Self::Center => (0.0, 0.0),
+            Self::Left => (-LOOK_PUPIL, 0.0),
+            Self::Right => (LOOK_PUPIL, 0.0),
+            Self::Up => (0.0, -LOOK_PUPIL),
+            Self::Down => (0.0, LOOK_PUPIL),
+        }
+    }
+}
+
+/// 阻塞式的简易接口。
+pub struct SimpleBot {
+    bot: ElectronBot,
+    retries: u32,
+    /// 通过 USB 连接，同步失败时可以重新连接。
+    usb: bool,
+    /// 关节移动速度（度每秒）。
+    speed: f32,
+    face: Face,
+    /// 屏幕上显示的是表情（`look` 会同时移动瞳孔）。
+    showing_face: bool,
+}
+
+impl SimpleBot {
+    /// 查找并通过 USB 连接机器人。
+    pub fn connect() -> Result<Self, BotError> {
+        let mut bot = ElectronBot::new();
+        bot.connect()?;
+        let mut simple = Self::from_bot(bot);
+        simple.usb = true;
+        Ok(simple)
+    }
+
+    /// 包装已经连接好的 `ElectronBot`（例如网络桥接），同步失败时不会重新连接。
+    pub fn from_bot(bot: ElectronBot) -> Self {
+        Self {
+            bot,
+            retries: DEFAULT_RETRIES,
+            usb: false,
+            speed: 90.0,
+            face: Face::new(),
+            showing_face: false,
+        }
+    }
+
+    /// 设置同步失败时的重试次数（默认 3 次）。
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// 设置 `move_head` 和 `look` 的转动速度（度每秒，默认 90）。
+    pub fn speed(mut self, degrees_per_second: f32) -> Self {
+        if degrees_per_second.is_finite() && degrees_per_second > 0.0 {
+            self.speed = degrees_per_second;
+        }
+        self
+    }
+
+    /// 底层的 `ElectronBot`。
+    pub fn bot(&self) -> &ElectronBot {
+        &self.bot
+    }
+
+    /// 底层的 `ElectronBot`（可变）。
+    pub fn bot_mut(&mut self) -> &mut ElectronBot {
+        &mut self.bot
+    }
+
+    /// 取出底层的 `ElectronBot`。
+    pub fn into_inner(self) -> ElectronBot {
+        self.bot
+    }
+
+    /// 整个屏幕显示一种颜色。
+    pub fn show_color(&mut self, color: Color) -> Result<(), BotError> {
+        self.bot.set_image_color(color);
+        self.showing_face = false;
+        self.sync()
+    }
+
+    /// 显示图片文件（自动缩放到屏幕大小）。
+    #[cfg(feature = "image")]
+    pub fn show_image<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<(), BotError> {
+        self.bot.set_image(path)?;
+        self.showing_face = false;
+        self.sync()
+    }
+
+    /// 显示表情（`EXPRESSION_NAMES` 中的名称，如 `"happy"`），平滑过渡后返回。
+    pub fn say_face(&mut self, name: &str) -> Result<(), BotError> {
+        let expression = Expression::named(name).ok_or_else(|| {
+            BotError::ConfigError(format!(
+                "未知的表情: {}（可用: {}）",
+                name,
+                EXPRESSION_NAMES.join(", ")
+            ))
+        })?;
+        #[cfg(feature = "logging")]
+        log::info!("Face: {}", name);
+        // 保持当前的视线方向
+        let current = self.face.expression();
+        let expression = expression.look(current.pupil_x, current.pupil_y);
+        if self.showing_face {
+            self.face.transition_to(expression, FACE_TRANSITION);
+        } else {
+            self.face.set_expression(expression);
+            self.showing_face = true;
+        }
+        let start = self.bot.target_joint_angles();
+        self.animate(FACE_TRANSITION, |_| start.clone())
+    }
+
+    /// 挥手，播放完后回到初始姿态。
+    pub fn wave(&mut self) -> Result<(), BotError> {
+        self.gesture("wave")
+    }
+
+    /// 播放内置动作（`GESTURE_NAMES` 中的名称，如 `"nod"`），播放完才返回。
+    ///
+    /// 内置动作从所有关节为 0 的姿态开始，先平滑回到这个姿态再播放。
+    pub fn gesture(&mut self, name: &str) -> Result<(), BotError> {
+        let gesture = Gesture::named(name).ok_or_else(|| {
+            BotError::ConfigError(format!(
+                "未知的动作: {}（可用: {}）",
+                name,
+                GESTURE_NAMES.join(", ")
+            ))
+        })?;
+        #[cfg(feature = "logging")]
+        log::info!("Gesture: {}", name);
+        self.move_to(gesture.pose_at(Duration::ZERO))?;
+        self.animate(gesture.duration(), |t| gesture.pose_at(t))
+    }
+
+    /// 看向某个方向：转动头部和身体，显示表情时瞳孔也跟着移动。
+    pub fn look(&mut self, direction: LookDirection) -> Result<(), BotError> {
+        if self.showing_face {
+            let (x, y) = direction.pupil();
+            let expression = self.face.target().look(x, y);
+            self.face.transition_to(expression, MIN_MOVE_TIME);
+        }
+        let (pitch, yaw) = direction.head();
+        self.move_head(pitch, yaw)
+    }
+
+    /// 把头部俯仰转到 `pitch` 度（向上为正），身体转到 `yaw` 度（向右为正），转到后返回。
+    ///
+    /// 超出关节范围的角度被限制在范围内。
+    pub fn move_head(&mut self, pitch: f32, yaw: f32) -> Result<(), BotError> {
+        let mut target = self.bot.target_joint_angles();
+        let limit = |joint: usize, angle: f32| {
+            let (min, max) = DEFAULT_JOINT_LIMITS[joint];
+            if angle.is_finite() {
+                angle.clamp(min, max)
+            } else {
+                0.0
+            }
+        };
+        target.0[JOINT_HEAD] = limit(JOINT_HEAD, pitch);
+        target.0[JOINT_BODY] = limit(JOINT_BODY, yaw);
+        self.move_to(target)
+    }
+
+    /// 按转动速度平滑移动到 `target`。
+    fn move_to(&mut self, target: JointAngles) -> Result<(), BotError> {
+        let start = self.bot.target_joint_angles();
+        let distance = start
+            .as_array()
+            .iter()
+            .zip(target.as_array())
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f32::max);
+        let duration = Duration::from_secs_f32(distance / self.speed).max(MIN_MOVE_TIME);
+        self.animate(duration, |t| {
+            let t = t.as_secs_f32() / duration.as_secs_f32();
+            let t = t * t * (3.0 - 2.0 * t);
+            let mut pose = start.clone();
+            for (angle, target) in pose.as_array_mut().iter_mut().zip(target.as_array()) {
+                *angle += (target - *angle) * t;
+            }
+            pose
+        })
+    }
+
+    /// 按帧间隔播放 `duration` 长的动画，`pose` 给出每一帧（经过的时间）的关节角度。
+    fn animate(
+        &mut self,
+        duration: Duration,
+        mut pose: impl FnMut(Duration) -> JointAngles,
+    ) -> Result<(), BotError> {
+        let frames = duration
+            .as_micros()
+            .div_ceil(DEFAULT_INTERVAL.as_micros())
+            .max(1) as u32;
+        let started = Instant::now();
+        for frame in 1..=frames {
+            let t = (DEFAULT_INTERVAL * frame).min(duration);
+            if self.showing_face {
+                self.face.advance(DEFAULT_INTERVAL);
+                self.face.render(self.bot.image_buffer());
+            }
+            self.bot.set_joint_angles(pose(t).as_array(), true)?;
+            self.sync()?;
+            let next = started + DEFAULT_INTERVAL * frame;
+            let now = Instant::now();
+            if frame < frames && next > now {
+                std::thread::sleep(next - now);
+            }
+        }
+        Ok(())
+    }
+
+    /// 同步一次，暂时性错误时重试，USB 断开时重新连接后重试，其他错误直接返回。
+    fn sync(&mut self) -> Result<(), BotError> {
+        let mut attempt = 0;
+        loop {
+            let error = match self.bot.sync() {
+                Ok(true) => return Ok(()),
+                Ok(false) => BotError::SendFailed(TransferError::new("同步未完成")),
+                Err(e) => e,
+            };
+            let class = error.class();
+            match class {
+                ErrorClass::Fatal => return Err(error),
+                ErrorClass::Disconnected if !self.usb => return Err(error),
+                _ => {}
+            }
+            if attempt >= self.retries {
+                return Err(error);
+            }
+            attempt += 1;
+            #[cfg(feature = "logging")]
+            log::warn!(
+                "Sync failed ({}), retrying {}/{}",
+                error,
+                attempt,
+                self.retries
+            );
+            std::thread::sleep(RETRY_DELAY);
+            #[cfg(feature = "tracing")]
+            tracing::warn!(attempt, retries = self.retries, error = %error, "sync retry");
+            if self.usb && class == ErrorClass::Disconnected {
+                #[cfg(feature = "tracing")]
+                let _span = tracing::info_span!("reconnect", attempt).entered();
+                // 重新连接失败时下一次同步返回未连接，继续重试直到次数用完
+                let _ = self.bot.connect();
+            }
+        }
+    }
+}
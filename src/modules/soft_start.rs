@@ -0,0 +1,105 @@
+//! ElectronBot 库的舵机软启动。
+//!
+//! 舵机从失能切换到使能时，如果直接发送应用的目标角度，舵机会从停放时的姿态猛地跳过去。
+//! [`SoftStart`] 在使能的那一刻先发送机器人上报的当前角度，再在设定的时间内平滑过渡到目标角度；
+//! 还没有收到上报角度时，先以失能状态同步一次读取角度。
+
+use std::time::Duration;
+
+use crate::modules::extra_data::ExtraData;
+use crate::modules::time::Instant;
+use crate::modules::types::JointAngles;
+
+/// 正在进行的过渡。
+#[derive(Debug, Clone)]
+struct Ramp {
+    from: JointAngles,
+    started: Instant,
+}
+
+/// 舵机软启动状态。
+#[derive(Debug, Clone)]
+pub struct SoftStart {
+    duration: Duration,
+    ramp: Option<Ramp>,
+    /// 上一次发送的使能标志。
+    was_enabled: bool,
+}
+
+impl SoftStart {
+    /// 创建过渡时间为 `duration` 的软启动。
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            ramp: None,
+            was_enabled: false,
+        }
+    }
+
+    /// 过渡时间。
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// 是否正在从上报角度过渡到目标角度。
+    pub fn is_ramping(&self) -> bool {
+        self.ramp.is_some()
+    }
+
+    /// 忘记之前的使能状态（断开连接后调用），下一次使能重新过渡。
+    pub fn reset(&mut self) {
+        self.ramp = None;
+        self.was_enabled = false;
+    }
+
+    /// 计算这一次同步实际发送的扩展数据，与 `target` 相同时返回 `None`。
+    ///
+    /// `measured` 是机器人最近上报的角度，还没有收到时为 `None`。
+    pub fn apply(
+        &mut self,
+        now: Instant,
+        target: &ExtraData,
+        measured: Option<&JointAngles>,
+    ) -> Option<ExtraData> {
+        if !target.is_enabled() {
+            self.reset();
+            return None;
+        }
+        if !self.was_enabled {
+            let Some(measured) = measured else {
+                // 先失能同步一次，读取舵机当前的角度
+                let mut data = target.clone();
+                data.set_enable(false);
+                return Some(data);
+            };
+            self.was_enabled = true;
+            if self.duration.is_zero() {
+                return None;
+            }
+            #[cfg(feature = "logging")]
+            log::debug!("Soft start over {:?} from {:?}", self.duration, measured);
+            self.ramp = Some(Ramp {
+                from: measured.clone(),
+                started: now,
+            });
+        }
+        let ramp = self.ramp.as_ref()?;
+        let t = now.duration_since(ramp.started).as_secs_f32() / self.duration.as_secs_f32();
+        if t >= 1.0 {
+            self.ramp = None;
+            return None;
+        }
+        let t = t * t * (3.0 - 2.0 * t);
+        let mut pose = ramp.from.clone();
+        for (angle, target) in pose
+            .as_array_mut()
+            .iter_mut()
+            .zip(target.get_joint_angles().as_array())
+        {
+            *angle += (target - *angle) * t;
+        }
+        let mut data = target.clone();
+        data.set_joint_angles(&pose, true);
+        Some(data)
+    }
+}
@@ -0,0 +1,543 @@
+//! ElectronBot 库的帧源和程序化测试图案。
+//!
+//! [`FrameSource`] 每次把下一帧渲染到 [`ImageBuffer`]，
+//! 可以交给 `ElectronBot::play` 以固定帧率推送到屏幕。
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::modules::color::{Gradient, GradientKind};
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_WIDTH};
+use crate::modules::image::ImageBuffer;
+use crate::modules::time::Instant;
+use crate::modules::types::{Color, JointAngles};
+
+/// 帧源。
+pub trait FrameSource {
+    /// 将下一帧渲染到缓冲区，返回 `false` 表示没有更多帧。
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool;
+
+    /// 期望的帧间隔，`None` 表示尽快发送。
+    fn frame_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// 与当前帧对应的舵机角度，`None` 表示不改变舵机。
+    fn joint_angles(&self) -> Option<JointAngles> {
+        None
+    }
+}
+
+impl<T: FrameSource + ?Sized> FrameSource for Box<T> {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        (**self).next_frame(buffer)
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        (**self).frame_interval()
+    }
+
+    fn joint_angles(&self) -> Option<JointAngles> {
+        (**self).joint_angles()
+    }
+}
+
+/// 帧源播放结果。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlayStats {
+    /// 已发送的帧数。
+    pub frames: u64,
+    /// 因发送跟不上源帧率而丢弃的帧数。
+    pub dropped: u64,
+}
+
+/// 默认动画帧间隔（30 FPS）。
+pub(crate) const DEFAULT_INTERVAL: Duration = Duration::from_nanos(1_000_000_000 / 30);
+
+/// 彩条（静态，白、黄、青、绿、品红、红、蓝、黑）。
+#[derive(Debug, Clone, Default)]
+pub struct ColorBars;
+
+impl ColorBars {
+    /// 彩条颜色。
+    pub const COLORS: [Color; 8] = [
+        Color::White,
+        Color::Yellow,
+        Color::Cyan,
+        Color::Green,
+        Color::Magenta,
+        Color::Red,
+        Color::Blue,
+        Color::Black,
+    ];
+
+    /// 创建彩条。
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl FrameSource for ColorBars {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        let n = Self::COLORS.len();
+        for (i, &color) in Self::COLORS.iter().enumerate() {
+            let x0 = i * FRAME_WIDTH / n;
+            let x1 = (i + 1) * FRAME_WIDTH / n;
+            buffer.fill_rect(x0, 0, x1 - x0, FRAME_HEIGHT, color);
+        }
+        true
+    }
+}
+
+/// 棋盘格，可按帧滚动。
+#[derive(Debug, Clone)]
+pub struct Checkerboard {
+    cell: usize,
+    colors: [Color; 2],
+    /// 每帧滚动的像素数。
+    speed: usize,
+    frame: usize,
+}
+
+impl Checkerboard {
+    /// 创建黑白棋盘格（格子边长 `cell` 像素，静止）。
+    pub fn new(cell: usize) -> Self {
+        Self {
+            cell: cell.max(1),
+            colors: [Color::Black, Color::White],
+            speed: 0,
+            frame: 0,
+        }
+    }
+
+    /// 设置两种格子颜色。
+    pub fn colors(mut self, a: Color, b: Color) -> Self {
+        self.colors = [a, b];
+        self
+    }
+
+    /// 设置每帧沿对角线滚动的像素数。
+    pub fn scroll(mut self, speed: usize) -> Self {
+        self.speed = speed;
+        self
+    }
+}
+
+impl FrameSource for Checkerboard {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        let offset = self.frame * self.speed;
+        for y in 0..FRAME_HEIGHT {
+            for x in 0..FRAME_WIDTH {
+                let cx = (x + offset) / self.cell;
+                let cy = (y + offset) / self.cell;
+                buffer.set_pixel(x, y, self.colors[(cx + cy) % 2]);
+            }
+        }
+        self.frame += 1;
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        (self.speed > 0).then_some(DEFAULT_INTERVAL)
+    }
+}
+
+/// 渐变，可按帧旋转色相。
+#[derive(Debug, Clone)]
+pub struct GradientSweep {
+    gradient: Gradient,
+    /// 每帧旋转的色相角度，0 表示静止。
+    hue_step: f32,
+    frame: usize,
+}
+
+impl GradientSweep {
+    /// 使用指定渐变创建（静止）。
+    pub fn new(gradient: Gradient) -> Self {
+        Self {
+            gradient,
+            hue_step: 0.0,
+            frame: 0,
+        }
+    }
+
+    /// 全色相水平彩虹，每帧旋转 `hue_step` 度。
+    pub fn rainbow(hue_step: f32) -> Self {
+        let stops = (0..=6).map(|i| Color::from_hsv(i as f32 * 60.0, 1.0, 1.0));
+        let colors: Vec<Color> = stops.collect();
+        Self {
+            gradient: Gradient::from_palette(GradientKind::Horizontal, &colors),
+            hue_step,
+            frame: 0,
+        }
+    }
+}
+
+impl FrameSource for GradientSweep {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        if self.hue_step == 0.0 {
+            buffer.fill_gradient(&self.gradient);
+        } else {
+            let shift = self.hue_step * self.frame as f32;
+            buffer.fill_gradient(&self.gradient.hue_shifted(shift));
+        }
+        self.frame += 1;
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        (self.hue_step != 0.0).then_some(DEFAULT_INTERVAL)
+    }
+}
+
+/// 正弦等离子动画。
+#[derive(Debug, Clone, Default)]
+pub struct Plasma {
+    frame: usize,
+}
+
+impl Plasma {
+    /// 创建等离子动画。
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl FrameSource for Plasma {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        let t = self.frame as f32 * 0.05;
+        for y in 0..FRAME_HEIGHT {
+            for x in 0..FRAME_WIDTH {
+                let (fx, fy) = (x as f32 / 24.0, y as f32 / 24.0);
+                let v = (fx + t).sin()
+                    + (fy * 0.8 - t * 1.3).sin()
+                    + ((fx + fy + t) * 0.7).sin()
+                    + ((fx * fx + fy * fy).sqrt() - t * 2.0).sin();
+                let hue = (v + 4.0) / 8.0 * 360.0;
+                buffer.set_pixel(x, y, Color::from_hsv(hue, 1.0, 1.0));
+            }
+        }
+        self.frame += 1;
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        Some(DEFAULT_INTERVAL)
+    }
+}
+
+/// Perlin 噪声动画（灰度或按渐变着色）。
+#[derive(Debug, Clone)]
+pub struct PerlinNoise {
+    perm: [u8; 512],
+    scale: f32,
+    gradient: Option<Gradient>,
+    frame: usize,
+}
+
+impl PerlinNoise {
+    /// 使用随机种子创建（`scale` 为每个噪声格子的像素数）。
+    pub fn new(seed: u64, scale: f32) -> Self {
+        // 用种子打乱 0-255 的排列（xorshift）
+        let mut table: [u8; 256] = std::array::from_fn(|i| i as u8);
+        let mut state = seed | 1;
+        for i in (1..256).rev() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            table.swap(i, (state % (i as u64 + 1)) as usize);
+        }
+        Self {
+            perm: std::array::from_fn(|i| table[i % 256]),
+            scale: scale.max(1.0),
+            gradient: None,
+            frame: 0,
+        }
+    }
+
+    /// 按渐变着色。
+    pub fn gradient(mut self, gradient: Gradient) -> Self {
+        self.gradient = Some(gradient);
+        self
+    }
+
+    /// 三维 Perlin 噪声，返回约 -1.0-1.0。
+    pub(crate) fn noise(&self, x: f32, y: f32, z: f32) -> f32 {
+        let fade = |t: f32| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+        let grad = |hash: u8, x: f32, y: f32, z: f32| {
+            let h = hash & 15;
+            let u = if h < 8 { x } else { y };
+            let v = if h < 4 {
+                y
+            } else if h == 12 || h == 14 {
+                x
+            } else {
+                z
+            };
+            (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+        };
+        let p = |i: usize| self.perm[i] as usize;
+
+        let (xi, yi, zi) = (
+            x.floor() as i32 as usize & 255,
+            y.floor() as i32 as usize & 255,
+            z.floor() as i32 as usize & 255,
+        );
+        let (x, y, z) = (x - x.floor(), y - y.floor(), z - z.floor());
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        let a = p(xi) + yi;
+        let (aa, ab) = (p(a) + zi, p(a + 1) + zi);
+        let b = p(xi + 1) + yi;
+        let (ba, bb) = (p(b) + zi, p(b + 1) + zi);
+
+        lerp(
+            lerp(
+                lerp(
+                    grad(self.perm[aa], x, y, z),
+                    grad(self.perm[ba], x - 1.0, y, z),
+                    u,
+                ),
+                lerp(
+                    grad(self.perm[ab], x, y - 1.0, z),
+                    grad(self.perm[bb], x - 1.0, y - 1.0, z),
+                    u,
+                ),
+                v,
+            ),
+            lerp(
+                lerp(
+                    grad(self.perm[aa + 1], x, y, z - 1.0),
+                    grad(self.perm[ba + 1], x - 1.0, y, z - 1.0),
+                    u,
+                ),
+                lerp(
+                    grad(self.perm[ab + 1], x, y - 1.0, z - 1.0),
+                    grad(self.perm[bb + 1], x - 1.0, y - 1.0, z - 1.0),
+                    u,
+                ),
+                v,
+            ),
+            w,
+        )
+    }
+}
+
+impl FrameSource for PerlinNoise {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        let z = self.frame as f32 * 0.03;
+        for y in 0..FRAME_HEIGHT {
+            for x in 0..FRAME_WIDTH {
+                let n = self.noise(x as f32 / self.scale, y as f32 / self.scale, z);
+                let t = ((n + 1.0) / 2.0).clamp(0.0, 1.0);
+                let color = match &self.gradient {
+                    Some(gradient) => gradient.at(t),
+                    None => {
+                        let v = (t * 255.0).round() as u8;
+                        Color::Custom(v, v, v)
+                    }
+                };
+                buffer.set_pixel(x, y, color);
+            }
+        }
+        self.frame += 1;
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        Some(DEFAULT_INTERVAL)
+    }
+}
+
+/// 坏点测试：依次显示纯色满屏，并叠加一个从边缘向中心收缩的 1 像素边框。
+///
+/// 纯色满屏便于发现常亮/常暗像素，移动的边框用于检查边缘像素和整行/整列缺失。
+#[derive(Debug, Clone)]
+pub struct DeadPixelTest {
+    /// 每种颜色持续的帧数。
+    frames_per_color: usize,
+    frame: usize,
+}
+
+impl DeadPixelTest {
+    /// 依次测试的颜色。
+    pub const COLORS: [Color; 5] = [
+        Color::Red,
+        Color::Green,
+        Color::Blue,
+        Color::White,
+        Color::Black,
+    ];
+
+    /// 创建坏点测试（每种颜色持续 `frames_per_color` 帧）。
+    pub fn new(frames_per_color: usize) -> Self {
+        Self {
+            frames_per_color: frames_per_color.max(1),
+            frame: 0,
+        }
+    }
+}
+
+impl FrameSource for DeadPixelTest {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        let index = self.frame / self.frames_per_color;
+        if index >= Self::COLORS.len() {
+            return false;
+        }
+        let color = Self::COLORS[index];
+        buffer.clear(color);
+
+        // 边框在每种颜色的持续时间内从最外圈收缩到中心
+        let progress = (self.frame % self.frames_per_color) as f32 / self.frames_per_color as f32;
+        let inset = (progress * (FRAME_WIDTH / 2) as f32) as usize;
+        let size = FRAME_WIDTH - inset * 2;
+        let border = if color == Color::Black {
+            Color::White
+        } else {
+            Color::Black
+        };
+        buffer.fill_rect(inset, inset, size, 1, border);
+        buffer.fill_rect(inset, inset + size - 1, size, 1, border);
+        buffer.fill_rect(inset, inset, 1, size, border);
+        buffer.fill_rect(inset + size - 1, inset, 1, size, border);
+
+        self.frame += 1;
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        Some(DEFAULT_INTERVAL)
+    }
+}
+
+/// 随机色块（[`ImageBuffer::render_test_pattern`] 的帧源版本），每帧重新生成。
+#[cfg(feature = "image")]
+#[derive(Debug, Clone)]
+pub struct RandomBlocks {
+    block_size: usize,
+}
+
+#[cfg(feature = "image")]
+impl RandomBlocks {
+    /// 创建随机色块帧源。
+    pub fn new(block_size: usize) -> Self {
+        Self {
+            block_size: block_size.max(1),
+        }
+    }
+}
+
+#[cfg(feature = "image")]
+impl FrameSource for RandomBlocks {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        buffer.render_test_pattern(&mut rand::thread_rng(), self.block_size);
+        true
+    }
+}
+
+/// 活动记录，可克隆后交给输入处理代码（触摸、按键、网络请求等），有操作时调用 [`Activity::touch`]。
+#[derive(Debug, Clone)]
+pub struct Activity {
+    last: Arc<Mutex<Instant>>,
+}
+
+impl Default for Activity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Activity {
+    /// 创建活动记录，从现在开始计时。
+    pub fn new() -> Self {
+        Self {
+            last: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// 记录一次操作。
+    pub fn touch(&self) {
+        *self.last.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+
+    /// 距离上次操作的时间。
+    pub fn idle_for(&self) -> Duration {
+        self.last
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .elapsed()
+    }
+}
+
+/// 无操作超过指定时间后切换到空闲帧源（例如时钟），有操作时切回。
+///
+/// 每帧开始时检查一次，切回的延迟不超过空闲帧源的一个帧间隔。
+#[derive(Debug, Clone)]
+pub struct InactivitySwitch<A, I> {
+    active: A,
+    idle: I,
+    timeout: Duration,
+    activity: Activity,
+    is_idle: bool,
+}
+
+impl<A: FrameSource, I: FrameSource> InactivitySwitch<A, I> {
+    /// 创建切换器，`timeout` 为切换到空闲帧源前的无操作时间。
+    pub fn new(active: A, idle: I, timeout: Duration) -> Self {
+        Self {
+            active,
+            idle,
+            timeout,
+            activity: Activity::new(),
+            is_idle: false,
+        }
+    }
+
+    /// 活动记录（克隆后交给输入处理代码）。
+    pub fn activity(&self) -> Activity {
+        self.activity.clone()
+    }
+
+    /// 当前是否显示空闲帧源。
+    pub fn is_idle(&self) -> bool {
+        self.is_idle
+    }
+
+    /// 正常帧源。
+    pub fn active_mut(&mut self) -> &mut A {
+        &mut self.active
+    }
+
+    /// 空闲帧源。
+    pub fn idle_mut(&mut self) -> &mut I {
+        &mut self.idle
+    }
+}
+
+impl<A: FrameSource, I: FrameSource> FrameSource for InactivitySwitch<A, I> {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        self.is_idle = self.activity.idle_for() >= self.timeout;
+        if self.is_idle {
+            self.idle.next_frame(buffer)
+        } else {
+            self.active.next_frame(buffer)
+        }
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        if self.is_idle {
+            self.idle.frame_interval()
+        } else {
+            self.active.frame_interval()
+        }
+    }
+
+    fn joint_angles(&self) -> Option<JointAngles> {
+        if self.is_idle {
+            self.idle.joint_angles()
+        } else {
+            self.active.joint_angles()
+        }
+    }
+}
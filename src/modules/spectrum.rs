@@ -0,0 +1,308 @@
+//! ElectronBot 库的音频频谱可视化。
+//!
+//! 音频线程（cpal 回环采集、`parec --monitor` 等）把采样写入 [`AudioBuffer`]，
+//! [`Spectrum`] 每帧对最近的采样做 FFT，按对数频率分组后绘制为柱状或环形频谱。
+
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::modules::color::{Gradient, GradientKind, Palette};
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_WIDTH};
+use crate::modules::image::ImageBuffer;
+use crate::modules::source::{FrameSource, DEFAULT_INTERVAL};
+use crate::modules::types::Color;
+
+/// FFT 点数。
+const FFT_SIZE: usize = 1024;
+/// 显示的最低频率（Hz）。
+const MIN_FREQ: f32 = 40.0;
+/// 显示的最高频率（Hz）。
+const MAX_FREQ: f32 = 16000.0;
+/// 显示的动态范围（dB）。
+const RANGE_DB: f32 = 60.0;
+
+/// 采样缓冲区，可克隆后交给音频线程，只保留最近的一个 FFT 窗口。
+#[derive(Debug, Clone, Default)]
+pub struct AudioBuffer {
+    samples: Arc<Mutex<VecDeque<f32>>>,
+}
+
+impl AudioBuffer {
+    /// 创建采样缓冲区。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 写入浮点采样（-1 到 1，单声道）。
+    pub fn push_samples(&self, samples: &[f32]) {
+        self.extend(samples.iter().copied());
+    }
+
+    /// 写入 16 位整数采样（单声道）。
+    pub fn push_samples_i16(&self, samples: &[i16]) {
+        self.extend(samples.iter().map(|&s| s as f32 / 32768.0));
+    }
+
+    fn extend(&self, samples: impl Iterator<Item = f32>) {
+        let mut buffer = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        buffer.extend(samples);
+        let excess = buffer.len().saturating_sub(FFT_SIZE);
+        buffer.drain(..excess);
+    }
+
+    /// 复制最近的采样到 `out`，不足的部分补 0。
+    fn snapshot(&self, out: &mut [f32]) {
+        let buffer = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        let pad = out.len().saturating_sub(buffer.len());
+        out[..pad].fill(0.0);
+        let skip = buffer.len().saturating_sub(out.len());
+        for (o, s) in out[pad..].iter_mut().zip(buffer.iter().skip(skip)) {
+            *o = *s;
+        }
+    }
+}
+
+/// 频谱样式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpectrumStyle {
+    /// 底部向上的柱状图，带峰值标记（默认）。
+    #[default]
+    Bars,
+    /// 从中心向外的环形。
+    Radial,
+}
+
+/// 音频频谱帧源。
+#[derive(Debug, Clone)]
+pub struct Spectrum {
+    input: AudioBuffer,
+    sample_rate: u32,
+    style: SpectrumStyle,
+    gradient: Gradient,
+    background: Color,
+    /// 每帧下降的比例（0 到 1）。
+    falloff: f32,
+    unthrottled: bool,
+    levels: Vec<f32>,
+    peaks: Vec<f32>,
+    samples: Vec<f32>,
+}
+
+impl Spectrum {
+    /// 创建频谱（32 条，彩虹色，柱状）。
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            input: AudioBuffer::new(),
+            sample_rate,
+            style: SpectrumStyle::Bars,
+            gradient: Gradient::from_palette(GradientKind::Vertical, Palette::RAINBOW),
+            background: Color::Black,
+            falloff: 0.08,
+            unthrottled: false,
+            levels: vec![0.0; 32],
+            peaks: vec![0.0; 32],
+            samples: vec![0.0; FFT_SIZE],
+        }
+    }
+
+    /// 设置样式。
+    pub fn style(mut self, style: SpectrumStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// 设置频带数量（柱数）。
+    pub fn bars(mut self, count: usize) -> Self {
+        let count = count.clamp(1, FRAME_WIDTH / 2);
+        self.levels = vec![0.0; count];
+        self.peaks = vec![0.0; count];
+        self
+    }
+
+    /// 设置配色：柱状时从下到上，环形时沿圆周。
+    pub fn gradient(mut self, gradient: Gradient) -> Self {
+        self.gradient = gradient;
+        self
+    }
+
+    /// 使用调色板配色。
+    pub fn palette(self, colors: &[Color]) -> Self {
+        self.gradient(Gradient::from_palette(GradientKind::Vertical, colors))
+    }
+
+    /// 设置背景色。
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = color;
+        self
+    }
+
+    /// 设置每帧下降的比例（越大回落越快）。
+    pub fn falloff(mut self, falloff: f32) -> Self {
+        self.falloff = falloff.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 不限制帧率，尽快发送（用于测试同步吞吐量，电平回落速度随帧率变化）。
+    pub fn unthrottled(mut self) -> Self {
+        self.unthrottled = true;
+        self
+    }
+
+    /// 采样输入端（克隆后交给音频线程）。
+    pub fn input(&self) -> AudioBuffer {
+        self.input.clone()
+    }
+
+    /// 各频带当前的电平（0 到 1）。
+    pub fn levels(&self) -> &[f32] {
+        &self.levels
+    }
+
+    /// 对最近的采样做 FFT 并更新各频带电平。
+    pub fn update(&mut self) {
+        self.input.snapshot(&mut self.samples);
+        let magnitudes = magnitude_spectrum(&self.samples);
+
+        let bin_hz = self.sample_rate as f32 / FFT_SIZE as f32;
+        let max_freq = MAX_FREQ.min(self.sample_rate as f32 / 2.0);
+        let count = self.levels.len();
+        // Hann 窗下满幅正弦波的幅值约为 N/4
+        let reference = FFT_SIZE as f32 / 4.0;
+        for i in 0..count {
+            let band = |k: usize| MIN_FREQ * (max_freq / MIN_FREQ).powf(k as f32 / count as f32);
+            let lo = ((band(i) / bin_hz) as usize).clamp(1, magnitudes.len() - 1);
+            let hi = ((band(i + 1) / bin_hz).ceil() as usize).clamp(lo + 1, magnitudes.len());
+            let peak = magnitudes[lo..hi].iter().copied().fold(0.0, f32::max);
+            let db = 20.0 * (peak / reference).max(1e-9).log10();
+            let level = ((db + RANGE_DB) / RANGE_DB).clamp(0.0, 1.0);
+
+            // 上升立即跟随，下降按比例回落，峰值标记回落更慢
+            self.levels[i] = level.max(self.levels[i] - self.falloff);
+            self.peaks[i] = self.levels[i].max(self.peaks[i] - self.falloff / 4.0);
+        }
+    }
+
+    /// 绘制当前电平。
+    pub fn render(&self, buffer: &mut ImageBuffer) {
+        buffer.clear(self.background);
+        match self.style {
+            SpectrumStyle::Bars => self.render_bars(buffer),
+            SpectrumStyle::Radial => self.render_radial(buffer),
+        }
+    }
+
+    fn render_bars(&self, buffer: &mut ImageBuffer) {
+        let count = self.levels.len();
+        let slot = FRAME_WIDTH / count;
+        let width = slot.saturating_sub(2).max(1);
+        let offset = (FRAME_WIDTH - slot * count) / 2;
+        let rows: Vec<Color> = (0..FRAME_HEIGHT)
+            .map(|row| self.gradient.at(row as f32 / (FRAME_HEIGHT - 1) as f32))
+            .collect();
+
+        for (i, (&level, &peak)) in self.levels.iter().zip(&self.peaks).enumerate() {
+            let x = offset + i * slot;
+            let height = (level * FRAME_HEIGHT as f32) as usize;
+            for (row, &color) in rows.iter().enumerate().take(height) {
+                buffer.fill_rect(x, FRAME_HEIGHT - 1 - row, width, 1, color);
+            }
+            let peak_row = ((peak * FRAME_HEIGHT as f32) as usize).min(FRAME_HEIGHT - 1);
+            if peak_row > height {
+                buffer.fill_rect(x, FRAME_HEIGHT - 1 - peak_row, width, 2, Color::White);
+            }
+        }
+    }
+
+    fn render_radial(&self, buffer: &mut ImageBuffer) {
+        const INNER: f32 = 36.0;
+        const LENGTH: f32 = 80.0;
+        let count = self.levels.len();
+        let center = (FRAME_WIDTH as f32 / 2.0, FRAME_HEIGHT as f32 / 2.0);
+        let width = ((2.0 * PI * INNER / count as f32) as usize).clamp(1, 6);
+        for (i, &level) in self.levels.iter().enumerate() {
+            let angle = i as f32 / count as f32 * 2.0 * PI - PI / 2.0;
+            let (sin, cos) = angle.sin_cos();
+            let point = |r: f32| ((center.0 + cos * r) as i32, (center.1 + sin * r) as i32);
+            let color = self.gradient.at(i as f32 / count as f32);
+            buffer.draw_line(
+                point(INNER),
+                point(INNER + 2.0 + level * LENGTH),
+                width,
+                color,
+            );
+        }
+    }
+}
+
+impl FrameSource for Spectrum {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        self.update();
+        self.render(buffer);
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        (!self.unthrottled).then_some(DEFAULT_INTERVAL)
+    }
+}
+
+/// 加 Hann 窗后做 FFT，返回前一半频点的幅值。
+fn magnitude_spectrum(samples: &[f32]) -> Vec<f32> {
+    let n = samples.len();
+    let mut re: Vec<f32> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, s)| s * (0.5 - 0.5 * (2.0 * PI * i as f32 / (n - 1) as f32).cos()))
+        .collect();
+    let mut im = vec![0.0; n];
+    fft(&mut re, &mut im);
+    re.iter()
+        .zip(&im)
+        .take(n / 2)
+        .map(|(r, i)| (r * r + i * i).sqrt())
+        .collect()
+}
+
+/// 原地基 2 FFT，长度必须是 2 的幂。
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    debug_assert!(n.is_power_of_two());
+
+    // 位反转重排
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let (sin, cos) = (-2.0 * PI / len as f32).sin_cos();
+        for start in (0..n).step_by(len) {
+            let (mut wr, mut wi) = (1.0f32, 0.0f32);
+            for k in 0..len / 2 {
+                let (a, b) = (start + k, start + k + len / 2);
+                let tr = re[b] * wr - im[b] * wi;
+                let ti = re[b] * wi + im[b] * wr;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+                let next = wr * cos - wi * sin;
+                wi = wr * sin + wi * cos;
+                wr = next;
+            }
+        }
+        len <<= 1;
+    }
+}
@@ -0,0 +1,315 @@
+//! ElectronBot 库的精灵图和贴图操作。
+//!
+//! 精灵在加载时一次性转换为与 [`ImageBuffer`] 相同的 BGR 布局，
+//! 贴图时直接按行复制，无需每帧重新解码或缩放。
+
+#[cfg(feature = "image")]
+use image::DynamicImage;
+
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_WIDTH};
+use crate::modules::draw::Rect;
+use crate::modules::image::ImageBuffer;
+use crate::modules::types::Color;
+
+/// 预转换为 BGR 的精灵图。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sprite {
+    width: usize,
+    height: usize,
+    /// BGR 像素数据。
+    data: Vec<u8>,
+    /// 每个像素的不透明度（0 为透明，255 为不透明）。
+    alpha: Vec<u8>,
+}
+
+impl Sprite {
+    /// 从 BGR 数据创建（完全不透明）。
+    pub fn from_bgr(width: usize, height: usize, data: Vec<u8>) -> Result<Self, String> {
+        if data.len() != width * height * 3 {
+            return Err(format!(
+                "精灵数据大小不匹配: 需要 {} 字节，实际 {} 字节",
+                width * height * 3,
+                data.len()
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            data,
+            alpha: vec![255; width * height],
+        })
+    }
+
+    /// 从 DynamicImage 创建，保留 alpha 通道。
+    #[cfg(feature = "image")]
+    pub fn from_image(img: &DynamicImage) -> Self {
+        let rgba = img.to_rgba8();
+        let (width, height) = (rgba.width() as usize, rgba.height() as usize);
+        let mut data = Vec::with_capacity(width * height * 3);
+        let mut alpha = Vec::with_capacity(width * height);
+        for pixel in rgba.pixels() {
+            // 将 RGB 转换为 MCU 所需的 BGR
+            data.extend_from_slice(&[pixel[2], pixel[1], pixel[0]]);
+            alpha.push(pixel[3]);
+        }
+        Self {
+            width,
+            height,
+            data,
+            alpha,
+        }
+    }
+
+    /// 从文件加载。
+    #[cfg(feature = "image")]
+    pub fn load_from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, String> {
+        let img = image::open(path).map_err(|e| format!("打开图片失败: {}", e))?;
+        Ok(Self::from_image(&img))
+    }
+
+    /// 将指定颜色设为透明（颜色键）。
+    pub fn set_color_key(&mut self, key: Color) {
+        let (r, g, b) = key.rgb();
+        for (i, a) in self.alpha.iter_mut().enumerate() {
+            if self.data[i * 3..i * 3 + 3] == [b, g, r] {
+                *a = 0;
+            }
+        }
+    }
+
+    /// 使用颜色键（构建器风格）。
+    pub fn with_color_key(mut self, key: Color) -> Self {
+        self.set_color_key(key);
+        self
+    }
+
+    /// 宽度。
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// 高度。
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// BGR 像素数据。
+    pub fn as_data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// 不透明度数据。
+    pub fn alpha(&self) -> &[u8] {
+        &self.alpha
+    }
+
+    /// 裁剪出子区域（超出部分会被截掉）。
+    pub fn crop(&self, rect: Rect) -> Sprite {
+        let x0 = rect.x.clamp(0, self.width as i32) as usize;
+        let y0 = rect.y.clamp(0, self.height as i32) as usize;
+        let x1 = (rect.x + rect.width as i32).clamp(0, self.width as i32) as usize;
+        let y1 = (rect.y + rect.height as i32).clamp(0, self.height as i32) as usize;
+        let (w, h) = (x1.saturating_sub(x0), y1.saturating_sub(y0));
+
+        let mut data = Vec::with_capacity(w * h * 3);
+        let mut alpha = Vec::with_capacity(w * h);
+        for y in y0..y1 {
+            let row = y * self.width;
+            data.extend_from_slice(&self.data[(row + x0) * 3..(row + x1) * 3]);
+            alpha.extend_from_slice(&self.alpha[row + x0..row + x1]);
+        }
+        Sprite {
+            width: w,
+            height: h,
+            data,
+            alpha,
+        }
+    }
+}
+
+/// 精灵表（按固定大小网格切分的动画帧）。
+#[derive(Debug, Clone)]
+pub struct SpriteSheet {
+    frames: Vec<Sprite>,
+    frame_width: usize,
+    frame_height: usize,
+}
+
+impl SpriteSheet {
+    /// 将图集按 `frame_width x frame_height` 网格切分，按行优先顺序排列。
+    pub fn new(atlas: &Sprite, frame_width: usize, frame_height: usize) -> Result<Self, String> {
+        if frame_width == 0 || frame_height == 0 {
+            return Err("精灵帧尺寸不能为 0".to_string());
+        }
+        let cols = atlas.width() / frame_width;
+        let rows = atlas.height() / frame_height;
+        let mut frames = Vec::with_capacity(cols * rows);
+        for row in 0..rows {
+            for col in 0..cols {
+                frames.push(atlas.crop(Rect::new(
+                    (col * frame_width) as i32,
+                    (row * frame_height) as i32,
+                    frame_width,
+                    frame_height,
+                )));
+            }
+        }
+        Ok(Self {
+            frames,
+            frame_width,
+            frame_height,
+        })
+    }
+
+    /// 从文件加载并切分。
+    #[cfg(feature = "image")]
+    pub fn load_from_file<P: AsRef<std::path::Path>>(
+        path: P,
+        frame_width: usize,
+        frame_height: usize,
+    ) -> Result<Self, String> {
+        let atlas = Sprite::load_from_file(path)?;
+        Self::new(&atlas, frame_width, frame_height)
+    }
+
+    /// 对所有帧应用颜色键。
+    pub fn set_color_key(&mut self, key: Color) {
+        for frame in self.frames.iter_mut() {
+            frame.set_color_key(key);
+        }
+    }
+
+    /// 帧数。
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// 是否没有帧。
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// 获取指定帧。
+    pub fn frame(&self, index: usize) -> Option<&Sprite> {
+        self.frames.get(index)
+    }
+
+    /// 单帧宽度。
+    pub fn frame_width(&self) -> usize {
+        self.frame_width
+    }
+
+    /// 单帧高度。
+    pub fn frame_height(&self) -> usize {
+        self.frame_height
+    }
+}
+
+/// 贴图选项。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlitOptions {
+    /// 水平翻转。
+    pub flip_horizontal: bool,
+    /// 垂直翻转。
+    pub flip_vertical: bool,
+    /// 整体不透明度（0.0-1.0），与精灵自身的 alpha 相乘。
+    pub opacity: f32,
+}
+
+impl Default for BlitOptions {
+    fn default() -> Self {
+        Self {
+            flip_horizontal: false,
+            flip_vertical: false,
+            opacity: 1.0,
+        }
+    }
+}
+
+impl BlitOptions {
+    /// 水平翻转。
+    pub fn flip_horizontal(mut self) -> Self {
+        self.flip_horizontal = true;
+        self
+    }
+
+    /// 垂直翻转。
+    pub fn flip_vertical(mut self) -> Self {
+        self.flip_vertical = true;
+        self
+    }
+
+    /// 设置整体不透明度。
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+}
+
+impl ImageBuffer {
+    /// 将精灵贴到 `(x, y)` 位置（左上角），超出屏幕部分会被裁剪。
+    pub fn blit(&mut self, sprite: &Sprite, x: i32, y: i32) {
+        self.blit_with(sprite, x, y, &BlitOptions::default());
+    }
+
+    /// 按选项贴图（翻转、整体不透明度）。
+    pub fn blit_with(&mut self, sprite: &Sprite, x: i32, y: i32, options: &BlitOptions) {
+        let opacity = options.opacity.clamp(0.0, 1.0);
+        if opacity <= 0.0 {
+            return;
+        }
+        // 目标区域裁剪到屏幕
+        let dx0 = x.max(0);
+        let dy0 = y.max(0);
+        let dx1 = (x + sprite.width as i32).min(FRAME_WIDTH as i32);
+        let dy1 = (y + sprite.height as i32).min(FRAME_HEIGHT as i32);
+        if dx0 >= dx1 || dy0 >= dy1 {
+            return;
+        }
+
+        for dy in dy0..dy1 {
+            let mut sy = (dy - y) as usize;
+            if options.flip_vertical {
+                sy = sprite.height - 1 - sy;
+            }
+            let dst_row = dy as usize * FRAME_WIDTH;
+            let src_row = sy * sprite.width;
+
+            // 快速路径：不翻转、完全不透明的整行直接复制
+            if !options.flip_horizontal && opacity >= 1.0 {
+                let sx0 = (dx0 - x) as usize;
+                let sx1 = (dx1 - x) as usize;
+                let alpha = &sprite.alpha[src_row + sx0..src_row + sx1];
+                if alpha.iter().all(|&a| a == 255) {
+                    let dst = (dst_row + dx0 as usize) * 3;
+                    let src = (src_row + sx0) * 3;
+                    let len = (sx1 - sx0) * 3;
+                    self.data[dst..dst + len].copy_from_slice(&sprite.data[src..src + len]);
+                    continue;
+                }
+            }
+
+            for dx in dx0..dx1 {
+                let mut sx = (dx - x) as usize;
+                if options.flip_horizontal {
+                    sx = sprite.width - 1 - sx;
+                }
+                let si = src_row + sx;
+                let a = sprite.alpha[si] as f32 / 255.0 * opacity;
+                if a <= 0.0 {
+                    continue;
+                }
+                let di = (dst_row + dx as usize) * 3;
+                let src = &sprite.data[si * 3..si * 3 + 3];
+                if a >= 1.0 {
+                    self.data[di..di + 3].copy_from_slice(src);
+                } else {
+                    for (d, &s) in self.data[di..di + 3].iter_mut().zip(src) {
+                        let dv = *d as f32;
+                        *d = (dv + (s as f32 - dv) * a).round() as u8;
+                    }
+                }
+            }
+        }
+    }
+}
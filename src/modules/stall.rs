@@ -0,0 +1,185 @@
+//! ElectronBot 库的关节堵转检测。
+//!
+//! [`StallDetector`] 在每次同步后比较目标角度和机器人上报的角度：
+//! 误差持续超过允许范围时报告关节跟不上（[`Event::JointLagging`]），
+//! 上报角度在误差存在期间一直不动时报告堵转或舵机没有供电（[`Event::JointStalled`]），
+//! 回到允许范围后报告恢复（[`Event::JointRecovered`]）。舵机未使能时不检测。
+
+use std::time::Duration;
+
+use crate::modules::event::Event;
+use crate::modules::time::Instant;
+use crate::modules::types::JointAngles;
+
+/// 舵机数量。
+const JOINT_COUNT: usize = 6;
+
+/// 堵转检测选项。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StallOptions {
+    /// 上报角度与目标角度的允许误差（度）。
+    pub tolerance: f32,
+    /// 误差持续多久算跟不上。
+    pub lag_time: Duration,
+    /// 误差存在期间上报角度不动多久算堵转。
+    pub stall_time: Duration,
+    /// 上报角度变化超过多少算在运动（度）。
+    pub min_motion: f32,
+}
+
+impl StallOptions {
+    /// 默认选项：允许误差 5°，跟不上 1 秒，堵转 1.5 秒，运动阈值 1°。
+    pub fn new() -> Self {
+        Self {
+            tolerance: 5.0,
+            lag_time: Duration::from_secs(1),
+            stall_time: Duration::from_millis(1500),
+            min_motion: 1.0,
+        }
+    }
+
+    /// 设置允许误差。
+    pub fn tolerance(mut self, degrees: f32) -> Self {
+        self.tolerance = degrees.abs();
+        self
+    }
+
+    /// 设置跟不上的判定时间。
+    pub fn lag_time(mut self, time: Duration) -> Self {
+        self.lag_time = time;
+        self
+    }
+
+    /// 设置堵转的判定时间。
+    pub fn stall_time(mut self, time: Duration) -> Self {
+        self.stall_time = time;
+        self
+    }
+
+    /// 设置运动阈值。
+    pub fn min_motion(mut self, degrees: f32) -> Self {
+        self.min_motion = degrees.abs();
+        self
+    }
+}
+
+impl Default for StallOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 关节状态。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JointStatus {
+    /// 正常跟随目标角度。
+    #[default]
+    Ok,
+    /// 误差持续超过允许范围，但仍在运动。
+    Lagging,
+    /// 误差存在期间上报角度一直不动。
+    Stalled,
+}
+
+/// 单个关节的检测状态。
+#[derive(Debug, Clone, Copy, Default)]
+struct JointState {
+    status: JointStatus,
+    /// 误差开始超过允许范围的时间。
+    error_since: Option<Instant>,
+    /// 上一次运动后的上报角度和时间。
+    anchor: Option<(f32, Instant)>,
+}
+
+/// 关节堵转检测器。
+#[derive(Debug, Clone, Default)]
+pub struct StallDetector {
+    options: StallOptions,
+    joints: [JointState; JOINT_COUNT],
+}
+
+impl StallDetector {
+    /// 按指定选项创建。
+    pub fn new(options: StallOptions) -> Self {
+        Self {
+            options,
+            joints: [JointState::default(); JOINT_COUNT],
+        }
+    }
+
+    /// 检测选项。
+    pub fn options(&self) -> &StallOptions {
+        &self.options
+    }
+
+    /// 每个关节的当前状态。
+    pub fn status(&self) -> [JointStatus; JOINT_COUNT] {
+        self.joints.map(|joint| joint.status)
+    }
+
+    /// 清除所有关节的状态（不产生事件）。
+    pub fn reset(&mut self) {
+        self.joints = [JointState::default(); JOINT_COUNT];
+    }
+
+    /// 记录一次同步的目标角度和上报角度，返回状态变化产生的事件。
+    pub fn update(
+        &mut self,
+        now: Instant,
+        target: &JointAngles,
+        reported: &JointAngles,
+        enabled: bool,
+    ) -> Vec<Event> {
+        if !enabled {
+            // 舵机释放时上报角度不跟随目标，重新开始计时
+            self.reset();
+            return Vec::new();
+        }
+        let options = self.options;
+        let mut events = Vec::new();
+        for (index, joint) in self.joints.iter_mut().enumerate() {
+            let target = target.as_array()[index];
+            let reported = reported.as_array()[index];
+            if (reported - target).abs() <= options.tolerance {
+                if joint.status != JointStatus::Ok {
+                    #[cfg(feature = "logging")]
+                    log::info!("Joint {} recovered", index);
+                    events.push(Event::JointRecovered(index));
+                }
+                *joint = JointState::default();
+                continue;
+            }
+
+            let error_since = *joint.error_since.get_or_insert(now);
+            let (anchor, mut moved_at) = *joint.anchor.get_or_insert((reported, now));
+            if (reported - anchor).abs() > options.min_motion {
+                joint.anchor = Some((reported, now));
+                moved_at = now;
+            }
+            let status = if now.duration_since(moved_at) >= options.stall_time {
+                JointStatus::Stalled
+            } else if now.duration_since(error_since) >= options.lag_time {
+                JointStatus::Lagging
+            } else {
+                joint.status
+            };
+            if status != joint.status {
+                #[cfg(feature = "logging")]
+                log::warn!(
+                    "Joint {} {:?}: target {:.1}, reported {:.1}",
+                    index,
+                    status,
+                    target,
+                    reported
+                );
+                events.push(match status {
+                    JointStatus::Stalled => Event::JointStalled(index),
+                    JointStatus::Lagging => Event::JointLagging(index),
+                    JointStatus::Ok => Event::JointRecovered(index),
+                });
+                joint.status = status;
+            }
+        }
+        events
+    }
+}
@@ -0,0 +1,245 @@
+//! ElectronBot 库的使用统计（舵机使能时间、发送帧数、占空比）。
+
+use std::time::Duration;
+
+use crate::modules::extra_data::ExtraData;
+use crate::modules::time::Instant;
+
+/// 舵机数量。
+const JOINT_COUNT: usize = 6;
+
+/// 累计使用统计。
+///
+/// 每次成功同步后记录一帧，用于估算舵机磨损和热保护策略。
+/// 统计可以保存到文件并在下次会话时加载，实现跨会话累计。
+#[derive(Debug, Clone, Default)]
+pub struct UsageStats {
+    /// 已发送的帧数。
+    pub frames_transmitted: u64,
+    /// 会话数（连接次数）。
+    pub sessions: u64,
+    /// 累计连接时间。
+    pub connected_time: Duration,
+    /// 累计舵机使能时间。
+    pub servo_enabled_time: Duration,
+    /// 每个关节的累计运动时间（使能且目标角度发生变化）。
+    pub joint_active_time: [Duration; JOINT_COUNT],
+    /// 连接时间已经计到的时刻（会话开始或上一帧），未连接时为 `None`。
+    counted_until: Option<Instant>,
+    /// 上一帧的使能状态。
+    last_enabled: bool,
+    /// 上一帧的关节角度。
+    last_angles: [f32; JOINT_COUNT],
+}
+
+impl UsageStats {
+    /// 创建空的统计。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 开始新的会话（连接时调用）。
+    pub fn begin_session(&mut self) {
+        self.begin_session_at(Instant::now());
+    }
+
+    /// 在指定时间开始新的会话，从这一刻起计入连接时间。
+    pub fn begin_session_at(&mut self, now: Instant) {
+        self.end_session_at(now);
+        self.sessions += 1;
+        self.counted_until = Some(now);
+        self.last_enabled = false;
+    }
+
+    /// 结束当前会话（断开时调用），之后的空闲时间不计入统计。
+    pub fn end_session(&mut self) {
+        self.end_session_at(Instant::now());
+    }
+
+    /// 在指定时间结束当前会话。
+    ///
+    /// 最后一帧到断开之间的时间也计入连接时间，最后一帧使能时同时计入使能时间。
+    pub fn end_session_at(&mut self, now: Instant) {
+        if let Some(last) = self.counted_until.take() {
+            let dt = now.saturating_duration_since(last);
+            self.connected_time += dt;
+            if self.last_enabled {
+                self.servo_enabled_time += dt;
+            }
+        }
+        self.last_enabled = false;
+    }
+
+    /// 记录一帧（使用当前时间）。
+    pub fn record_frame(&mut self, extra_data: &ExtraData) {
+        self.record_frame_at(Instant::now(), extra_data);
+    }
+
+    /// 在指定时间记录一帧。
+    ///
+    /// 两帧之间的时间按上一帧的状态计入：上一帧使能则计入使能时间，
+    /// 关节目标角度在两帧之间发生变化则计入该关节的运动时间。
+    /// 会话开始到第一帧之间只计入连接时间。
+    pub fn record_frame_at(&mut self, now: Instant, extra_data: &ExtraData) {
+        let angles = *extra_data.get_joint_angles().as_array();
+
+        if let Some(last) = self.counted_until {
+            let dt = now.saturating_duration_since(last);
+            self.connected_time += dt;
+            if self.last_enabled {
+                self.servo_enabled_time += dt;
+                for (i, active) in self.joint_active_time.iter_mut().enumerate() {
+                    if angles[i] != self.last_angles[i] {
+                        *active += dt;
+                    }
+                }
+            }
+        }
+
+        self.frames_transmitted += 1;
+        self.counted_until = Some(now);
+        self.last_enabled = extra_data.is_enabled();
+        self.last_angles = angles;
+    }
+
+    /// 舵机使能占空比（0.0-1.0）。
+    pub fn servo_duty_cycle(&self) -> f32 {
+        ratio(self.servo_enabled_time, self.connected_time)
+    }
+
+    /// 指定关节的估算占空比（0.0-1.0），索引越界返回 `None`。
+    pub fn joint_duty_cycle(&self, joint: usize) -> Option<f32> {
+        self.joint_active_time
+            .get(joint)
+            .map(|active| ratio(*active, self.connected_time))
+    }
+
+    /// 全部关节的估算占空比。
+    pub fn joint_duty_cycles(&self) -> [f32; JOINT_COUNT] {
+        let mut cycles = [0.0f32; JOINT_COUNT];
+        for (i, c) in cycles.iter_mut().enumerate() {
+            *c = ratio(self.joint_active_time[i], self.connected_time);
+        }
+        cycles
+    }
+
+    /// 合并另一份统计的累计值。
+    pub fn merge(&mut self, other: &UsageStats) {
+        self.frames_transmitted += other.frames_transmitted;
+        self.sessions += other.sessions;
+        self.connected_time += other.connected_time;
+        self.servo_enabled_time += other.servo_enabled_time;
+        for (a, b) in self
+            .joint_active_time
+            .iter_mut()
+            .zip(other.joint_active_time.iter())
+        {
+            *a += *b;
+        }
+    }
+
+    /// 序列化为 `key=value` 文本。
+    pub fn to_text(&self) -> String {
+        let joints: Vec<String> = self
+            .joint_active_time
+            .iter()
+            .map(|d| d.as_secs_f64().to_string())
+            .collect();
+        format!(
+            "frames_transmitted={}\nsessions={}\nconnected_secs={}\nservo_enabled_secs={}\njoint_active_secs={}\n",
+            self.frames_transmitted,
+            self.sessions,
+            self.connected_time.as_secs_f64(),
+            self.servo_enabled_time.as_secs_f64(),
+            joints.join(",")
+        )
+    }
+
+    /// 从 `key=value` 文本解析，未知的键会被忽略。
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let mut stats = Self::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("无效的统计行: {}", line))?;
+            let value = value.trim();
+            match key.trim() {
+                "frames_transmitted" => stats.frames_transmitted = parse_u64(key, value)?,
+                "sessions" => stats.sessions = parse_u64(key, value)?,
+                "connected_secs" => stats.connected_time = parse_secs(key, value)?,
+                "servo_enabled_secs" => stats.servo_enabled_time = parse_secs(key, value)?,
+                "joint_active_secs" => {
+                    let parts: Vec<&str> = value.split(',').collect();
+                    if parts.len() != JOINT_COUNT {
+                        return Err(format!("joint_active_secs 需要 {} 个值", JOINT_COUNT));
+                    }
+                    for (i, part) in parts.iter().enumerate() {
+                        stats.joint_active_time[i] = parse_secs(key, part.trim())?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(stats)
+    }
+
+    /// 保存到文件。
+    pub fn save_to_file<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), String> {
+        std::fs::write(path, self.to_text()).map_err(|e| format!("保存统计失败: {}", e))
+    }
+
+    /// 从文件加载。
+    pub fn load_from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("读取统计失败: {}", e))?;
+        Self::from_text(&text)
+    }
+}
+
+impl std::fmt::Display for UsageStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "会话数: {}", self.sessions)?;
+        writeln!(f, "已发送帧数: {}", self.frames_transmitted)?;
+        writeln!(f, "连接时间: {:.1} 秒", self.connected_time.as_secs_f64())?;
+        writeln!(
+            f,
+            "舵机使能时间: {:.1} 秒 (占空比 {:.1}%)",
+            self.servo_enabled_time.as_secs_f64(),
+            self.servo_duty_cycle() * 100.0
+        )?;
+        for (i, duty) in self.joint_duty_cycles().iter().enumerate() {
+            writeln!(
+                f,
+                "  关节 {}: {:.1} 秒 (占空比 {:.1}%)",
+                i,
+                self.joint_active_time[i].as_secs_f64(),
+                duty * 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn ratio(part: Duration, total: Duration) -> f32 {
+    if total.is_zero() {
+        0.0
+    } else {
+        (part.as_secs_f64() / total.as_secs_f64()) as f32
+    }
+}
+
+fn parse_u64(key: &str, value: &str) -> Result<u64, String> {
+    value
+        .parse()
+        .map_err(|e| format!("无效的 {} 值 '{}': {}", key, value, e))
+}
+
+fn parse_secs(key: &str, value: &str) -> Result<Duration, String> {
+    let secs: f64 = value
+        .parse()
+        .map_err(|e| format!("无效的 {} 值 '{}': {}", key, value, e))?;
+    Duration::try_from_secs_f64(secs).map_err(|e| format!("无效的 {} 值 '{}': {}", key, value, e))
+}
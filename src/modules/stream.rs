@@ -0,0 +1,121 @@
+//! ElectronBot 库的逐段发送。
+//!
+//! 一帧按同步周期分为 4 段，每段 60 行（43200 字节）。[`FrameStream`] 让解码器每产出一段就交给
+//! 设备：第 `i` 段及之前的段都已设置时立即发送第 `i` 段，不必等整帧解码完成，
+//! 缩短从解码到显示的延迟。段可以按任意顺序设置，但只按顺序发送。
+//!
+//! ```rust,no_run
+//! use electron_bot::ElectronBot;
+//!
+//! let mut bot = ElectronBot::new();
+//! bot.connect()?;
+//! let mut stream = bot.stream_frame()?;
+//! for i in 0..4 {
+//!     let rows = vec![0u8; 43200]; // 解码器输出的 60 行 BGR 数据
+//!     stream.set_quarter(i, &rows)?;
+//! }
+//! stream.finish()?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::modules::error::BotError;
+use crate::modules::sync::{QuarterMask, SyncProgress};
+use crate::ElectronBot;
+
+/// 正在逐段发送的一帧，由 `ElectronBot::stream_frame` 创建。
+///
+/// 没有调用 [`FrameStream::finish`] 就丢弃时，剩余的段按缓冲区当前内容发送，错误被忽略。
+pub struct FrameStream<'a> {
+    bot: &'a mut ElectronBot,
+    ready: QuarterMask,
+    next: usize,
+    progress: SyncProgress,
+    /// 旋转或垂直翻转时段与屏幕行不对应，只能在整帧设置完后发送。
+    deferred: bool,
+    aborted: bool,
+    finished: bool,
+}
+
+impl<'a> FrameStream<'a> {
+    pub(crate) fn new(bot: &'a mut ElectronBot, deferred: bool) -> Self {
+        Self {
+            bot,
+            ready: QuarterMask::NONE,
+            next: 0,
+            progress: SyncProgress::default(),
+            deferred,
+            aborted: false,
+            finished: false,
+        }
+    }
+
+    /// 设置第 `index` 段（0-3）的 BGR 数据，并发送所有可以按顺序发送的段。
+    ///
+    /// 返回已发送的段数。发送失败后本帧中止，之后的段只写入缓冲区。
+    pub fn set_quarter(&mut self, index: usize, data: &[u8]) -> Result<usize, BotError> {
+        self.bot
+            .image_buffer()
+            .set_quarter(index, data)
+            .map_err(BotError::ImageError)?;
+        self.ready.insert(index);
+        if !self.deferred {
+            self.send_ready()?;
+        }
+        Ok(self.next)
+    }
+
+    /// 已设置的段。
+    pub fn ready(&self) -> QuarterMask {
+        self.ready
+    }
+
+    /// 已发送的段数。
+    pub fn sent(&self) -> usize {
+        self.next
+    }
+
+    /// 发送剩余的段（未设置的段使用缓冲区当前内容）并结束本帧。
+    ///
+    /// 返回 `false` 表示本帧因之前的发送失败而中止。
+    pub fn finish(mut self) -> Result<bool, BotError> {
+        self.complete()
+    }
+
+    fn send_ready(&mut self) -> Result<(), BotError> {
+        while !self.aborted && self.next < 4 && self.ready.contains(self.next) {
+            self.send_next()?;
+        }
+        Ok(())
+    }
+
+    fn send_next(&mut self) -> Result<(), BotError> {
+        if let Err(e) = self.bot.stream_segment(self.next, &mut self.progress) {
+            self.aborted = true;
+            return Err(e);
+        }
+        self.next += 1;
+        Ok(())
+    }
+
+    fn complete(&mut self) -> Result<bool, BotError> {
+        if self.finished {
+            return Ok(!self.aborted);
+        }
+        self.finished = true;
+        while !self.aborted && self.next < 4 {
+            self.send_next()?;
+        }
+        if self.aborted {
+            return Ok(false);
+        }
+        self.bot.stream_end();
+        Ok(true)
+    }
+}
+
+impl Drop for FrameStream<'_> {
+    fn drop(&mut self) {
+        // MCU 按顺序等待 4 段，补齐剩余的段，否则下一帧会错位
+        let _ = self.complete();
+    }
+}
@@ -1,32 +1,50 @@
 //! ElectronBot 库的数据同步操作。
 
-use crate::modules::constants::{PACKET_COUNT, PACKET_SIZE, TAIL_SIZE};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use electron_bot_core::packet::{segment_packets, tail_packet};
+
+use crate::modules::constants::{QUARTER_ROWS, QUARTER_SIZE};
+use crate::modules::error::{BotError, ErrorClass, TransferError, TransferErrorKind};
 use crate::modules::extra_data::ExtraData;
 use crate::modules::image::ImageBuffer;
+use crate::modules::protocol::ProtocolProfile;
+use crate::modules::transport::{Direction, Transport};
 use crate::modules::types::JointAngles;
-use crate::modules::usb::UsbDevice;
 
 /// 同步操作结果。
 pub type SyncResult = Result<bool, String>;
 
 /// 同步上下文（用于乒乓缓冲）。
+///
+/// 乒乓索引只记录时间戳的奇偶，不选择扩展数据缓冲区：每个周期的尾包都写入调用方传入的
+/// 扩展数据，最近一次设置的关节角度总是随下一个发送的尾包发出，不会交替发送旧角度。
 #[derive(Debug)]
 pub struct SyncContext {
     /// 当前时间戳。
     pub timestamp: u32,
     /// 当前乒乓缓冲区索引。
     pub ping_pong_index: u8,
-    /// 同步周期数。
+    /// 每次同步的周期数（默认等于 `profile.cycles`，不超过它）。
     pub cycles: usize,
+    /// 传输布局。
+    pub profile: ProtocolProfile,
 }
 
 impl SyncContext {
-    /// 创建新的同步上下文。
+    /// 创建新的同步上下文（原版固件的传输布局）。
     pub fn new() -> Self {
+        Self::with_profile(ProtocolProfile::STOCK)
+    }
+
+    /// 使用指定的传输布局创建同步上下文。
+    pub fn with_profile(profile: ProtocolProfile) -> Self {
         Self {
             timestamp: 0,
             ping_pong_index: 0,
-            cycles: 4,
+            cycles: profile.cycles,
+            profile,
         }
     }
 
@@ -48,66 +66,444 @@ impl Default for SyncContext {
     }
 }
 
-/// 尝试接收指定长度的数据，带重试
+/// 一帧中 4 个 60 行分段的位掩码，第 `i` 位表示第 `i` 段。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub struct QuarterMask(pub u8);
+
+impl QuarterMask {
+    /// 不包含任何分段。
+    pub const NONE: Self = Self(0);
+    /// 包含全部 4 个分段。
+    pub const ALL: Self = Self(0b1111);
+
+    /// 覆盖第 `y` 行开始的 `height` 行的分段（超出屏幕的部分忽略）。
+    pub fn from_rows(y: usize, height: usize) -> Self {
+        let mut mask = Self::NONE;
+        let end = y.saturating_add(height).min(QUARTER_ROWS * 4);
+        let mut row = y;
+        while row < end {
+            mask.insert(row / QUARTER_ROWS);
+            row = (row / QUARTER_ROWS + 1) * QUARTER_ROWS;
+        }
+        mask
+    }
+
+    /// 比较两帧，返回内容不同的分段。
+    pub fn diff(a: &ImageBuffer, b: &ImageBuffer) -> Self {
+        let mut mask = Self::NONE;
+        for (i, (x, y)) in a
+            .as_data()
+            .chunks(QUARTER_SIZE)
+            .zip(b.as_data().chunks(QUARTER_SIZE))
+            .enumerate()
+        {
+            if x != y {
+                mask.insert(i);
+            }
+        }
+        mask
+    }
+
+    /// 是否包含第 `index` 段。
+    pub fn contains(&self, index: usize) -> bool {
+        index < 4 && self.0 & (1 << index) != 0
+    }
+
+    /// 加入第 `index` 段（0-3，超出范围时忽略）。
+    pub fn insert(&mut self, index: usize) {
+        if index < 4 {
+            self.0 |= 1 << index;
+        }
+    }
+
+    /// 是否为空。
+    pub fn is_empty(&self) -> bool {
+        self.0 & 0b1111 == 0
+    }
+
+    /// 分段数。
+    pub fn count(&self) -> usize {
+        (self.0 & 0b1111).count_ones() as usize
+    }
+
+    /// 按顺序遍历包含的分段序号。
+    pub fn iter(&self) -> impl Iterator<Item = usize> {
+        let mask = *self;
+        (0..4).filter(move |&i| mask.contains(i))
+    }
+}
+
+impl std::ops::BitOr for QuarterMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for QuarterMask {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// 取消标志，克隆后共享同一个标志。
+///
+/// 取消后正在进行的同步在下一个包之前中止，之后的同步也立即失败，直到调用 [`CancellationToken::reset`]。
+///
+/// ```
+/// use electron_bot::CancellationToken;
+///
+/// let token = CancellationToken::new();
+/// let handler = token.clone();
+/// // 例如在 Ctrl+C 回调中调用
+/// handler.cancel();
+/// assert!(token.is_cancelled());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// 创建未取消的标志。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 请求取消。
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// 是否已请求取消。
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// 清除取消标志，恢复正常同步。
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+/// 急停标志，克隆后共享同一个标志。
+///
+/// 触发后所有同步都发送舵机失能（使能标志为 0），帧源播放和自检中止，
+/// 直到调用 [`EmergencyStop::clear`]。与取消不同，急停期间同步照常进行。
+///
+/// ```
+/// use electron_bot::EmergencyStop;
+///
+/// let estop = EmergencyStop::new();
+/// let button = estop.clone();
+/// button.trigger();
+/// assert!(estop.is_triggered());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EmergencyStop(Arc<AtomicBool>);
+
+impl EmergencyStop {
+    /// 创建未触发的标志。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 触发急停。
+    pub fn trigger(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// 是否处于急停状态。
+    pub fn is_triggered(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// 解除急停（舵机保持失能，需要重新设置角度并使能）。
+    pub fn clear(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+/// 同步进度，同步中止时说明已经完成了多少。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncProgress {
+    /// 已完成的周期数。
+    pub cycles: usize,
+    /// 已发送的包数（包括尾包）。
+    pub packets: usize,
+    /// 收发失败后的重试次数。
+    pub retries: usize,
+}
+
+/// 每次收发之前调用的检查（可以调整通道的超时），返回错误时中止同步。
+type Check<'a> = &'a mut dyn FnMut(&mut dyn Transport) -> Result<(), String>;
+
+/// 尝试接收指定长度的数据，带重试；不是暂时性错误时不再重试
 fn receive_with_retry(
-    usb: &mut UsbDevice,
+    transport: &mut dyn Transport,
     buf: &mut [u8],
     expected_len: usize,
     max_retries: u32,
-) -> Result<usize, String> {
+    retries: &mut usize,
+    check: Check,
+) -> Result<usize, TransferError> {
+    let mut error = TransferError {
+        expected: expected_len,
+        ..TransferError::default()
+    };
     for retry in 0..max_retries {
-        match usb.receive(buf) {
-            Ok(_len) if _len == expected_len => {
+        if retry > 0 {
+            *retries += 1;
+        }
+        check(transport)?;
+        match transport.receive(buf) {
+            Ok(len) if len == expected_len => {
                 #[cfg(feature = "logging")]
                 log::debug!("Received {} bytes on attempt {}", expected_len, retry + 1);
-                return Ok(_len);
+                return Ok(len);
             }
-            Ok(_len) => {
+            Ok(len) => {
                 #[cfg(feature = "logging")]
-                log::warn!("Received {} bytes, expected {}", _len, expected_len);
+                log::warn!("Received {} bytes, expected {}", len, expected_len);
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    attempt = retry + 1,
+                    max_retries,
+                    received = len,
+                    expected = expected_len,
+                    "receive retry"
+                );
+                error.message = format!("Received {} bytes, expected {}", len, expected_len);
+                error.transferred = len;
+                error.kind = TransferErrorKind::Other;
             }
-            Err(_) => {
+            Err(e) => {
                 #[cfg(feature = "logging")]
                 log::warn!("Receive failed (attempt {}/{})", retry + 1, max_retries);
+                #[cfg(feature = "tracing")]
+                tracing::warn!(attempt = retry + 1, max_retries, error = %e, "receive retry");
+                error.message = e;
+                error.transferred = 0;
+                error.kind = transport.last_error_kind().unwrap_or_default();
+                recover_stall(transport, error.kind, Direction::In);
             }
         }
+        error.retries = retry;
+        if error.kind.class() != ErrorClass::Transient {
+            break;
+        }
 
         if retry < max_retries - 1 {
             std::thread::sleep(std::time::Duration::from_millis(5));
         }
     }
 
-    Err(format!(
-        "Failed to receive {} bytes after {} retries",
-        expected_len, max_retries
-    ))
+    Err(error)
 }
 
-/// 发送数据，带重试
-fn transmit_with_retry(usb: &mut UsbDevice, data: &[u8], max_retries: u32) -> Result<(), String> {
+/// 发送数据，带重试；不是暂时性错误时不再重试
+fn transmit_with_retry(
+    transport: &mut dyn Transport,
+    data: &[u8],
+    max_retries: u32,
+    retries: &mut usize,
+    check: Check,
+) -> Result<(), TransferError> {
+    let mut error = TransferError {
+        expected: data.len(),
+        ..TransferError::default()
+    };
     for retry in 0..max_retries {
-        if usb.transmit(data).is_ok() {
-            return Ok(());
+        if retry > 0 {
+            *retries += 1;
         }
+        check(transport)?;
+        let e = match transport.transmit(data) {
+            Ok(_) => return Ok(()),
+            Err(e) => e,
+        };
 
         #[cfg(feature = "logging")]
         log::warn!("Transmit failed (attempt {}/{})", retry + 1, max_retries);
+        #[cfg(feature = "tracing")]
+        tracing::warn!(attempt = retry + 1, max_retries, error = %e, "transmit retry");
+        error.message = e;
+        error.retries = retry;
+        error.kind = transport.last_error_kind().unwrap_or_default();
+        recover_stall(transport, error.kind, Direction::Out);
+        if error.kind.class() != ErrorClass::Transient {
+            break;
+        }
 
         if retry < max_retries - 1 {
             std::thread::sleep(std::time::Duration::from_millis(5));
         }
     }
 
-    Err("Transmit failed after retries".to_string())
+    Err(error)
+}
+
+/// 端点停止（STALL，例如主机睡眠唤醒之后）时清除停止状态，下一次重试继续当前周期，
+/// 不需要重新连接。
+fn recover_stall(transport: &mut dyn Transport, kind: TransferErrorKind, direction: Direction) {
+    if kind != TransferErrorKind::Pipe {
+        return;
+    }
+    match transport.clear_halt(direction) {
+        Ok(()) => {
+            #[cfg(feature = "logging")]
+            log::warn!("Endpoint {:?} stalled, halt cleared", direction);
+            #[cfg(feature = "tracing")]
+            tracing::warn!(?direction, "endpoint halt cleared");
+        }
+        Err(_e) => {
+            #[cfg(feature = "logging")]
+            log::warn!(
+                "Endpoint {:?} stalled, clearing halt failed: {}",
+                direction,
+                _e
+            );
+        }
+    }
 }
 
 /// 执行同步操作。
 pub fn sync(
-    usb: &mut UsbDevice,
+    transport: &mut dyn Transport,
     image_buffer: &ImageBuffer,
     extra_data: &ExtraData,
     context: &mut SyncContext,
 ) -> SyncResult {
+    sync_with_feedback(transport, image_buffer, extra_data, context, &mut None)
+}
+
+/// 执行同步操作，并把 MCU 最后一次发来的扩展数据（包含实际的关节角度）写入 `received`。
+///
+/// 本次同步没有收到完整的扩展数据时 `received` 保持不变。
+pub fn sync_with_feedback(
+    transport: &mut dyn Transport,
+    image_buffer: &ImageBuffer,
+    extra_data: &ExtraData,
+    context: &mut SyncContext,
+    received: &mut Option<ExtraData>,
+) -> SyncResult {
+    sync_checked(
+        transport,
+        image_buffer.as_data(),
+        extra_data,
+        context,
+        received,
+        None,
+        &mut SyncProgress::default(),
+        &mut |_| Ok(()),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// 可取消的同步：每个包之前检查 `cancel`，取消后立即返回错误。
+///
+/// 中途取消时 MCU 只收到了部分数据，下一次同步会重新发送完整的一帧。
+pub fn sync_cancellable(
+    transport: &mut dyn Transport,
+    image_buffer: &ImageBuffer,
+    extra_data: &ExtraData,
+    context: &mut SyncContext,
+    received: &mut Option<ExtraData>,
+    cancel: &CancellationToken,
+) -> SyncResult {
+    sync_checked(
+        transport,
+        image_buffer.as_data(),
+        extra_data,
+        context,
+        received,
+        None,
+        &mut SyncProgress::default(),
+        &mut |_| {
+            if cancel.is_cancelled() {
+                Err("Sync cancelled".to_string())
+            } else {
+                Ok(())
+            }
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// 局部刷新：只发送 `quarters` 中的分段，尾包扩展数据的最后一个字节标记段序号。
+///
+/// 需要固件支持按标记写入对应的行，原版固件按顺序接收 4 段，不能使用。
+/// `quarters` 为空时不发送。
+pub fn sync_partial(
+    transport: &mut dyn Transport,
+    image_buffer: &ImageBuffer,
+    extra_data: &ExtraData,
+    context: &mut SyncContext,
+    received: &mut Option<ExtraData>,
+    quarters: QuarterMask,
+) -> SyncResult {
+    sync_checked(
+        transport,
+        image_buffer.as_data(),
+        extra_data,
+        context,
+        received,
+        Some(quarters),
+        &mut SyncProgress::default(),
+        &mut |_| Ok(()),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// 按同步上下文的传输布局发送原始 BGR 数据（长度为 `profile.frame_size()`），
+/// 用于屏幕尺寸与 [`ImageBuffer`] 不同的固件分支。
+pub fn sync_frame(
+    transport: &mut dyn Transport,
+    data: &[u8],
+    extra_data: &ExtraData,
+    context: &mut SyncContext,
+    received: &mut Option<ExtraData>,
+) -> SyncResult {
+    sync_checked(
+        transport,
+        data,
+        extra_data,
+        context,
+        received,
+        None,
+        &mut SyncProgress::default(),
+        &mut |_| Ok(()),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// 执行同步操作，每次收发之前调用 `check`，返回错误时中止并返回该错误，
+/// 已完成的进度写入 `progress`。`quarters` 不为 `None` 时按局部刷新协议只发送其中的分段。
+///
+/// 暂时性的收发失败（超时、端点停止、占用）先重试，重试用完或者设备拔出、没有权限时中止，
+/// 返回带周期、包序号、字节数、重试次数和错误类型的 `SendFailed` 或 `ReceiveFailed`。
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn sync_checked(
+    transport: &mut dyn Transport,
+    data: &[u8],
+    extra_data: &ExtraData,
+    context: &mut SyncContext,
+    received: &mut Option<ExtraData>,
+    quarters: Option<QuarterMask>,
+    progress: &mut SyncProgress,
+    check: Check,
+) -> Result<bool, BotError> {
+    *progress = SyncProgress::default();
+    let profile = context.profile;
+    profile
+        .validate()
+        .map_err(|e| BotError::SendFailed(e.into()))?;
+    if data.len() < profile.frame_size() {
+        return Err(BotError::SendFailed(TransferError::new(format!(
+            "Frame is {} bytes, profile expects {}",
+            data.len(),
+            profile.frame_size()
+        ))));
+    }
     context.toggle();
 
     #[cfg(feature = "logging")]
@@ -117,86 +513,156 @@ pub fn sync(
         context.current_index()
     );
 
-    let data = image_buffer.as_data();
-    let extra = extra_data.as_data();
+    // 每个周期发送一段，原版固件为 84 * 512 + 192 = 43008 + 192 = 43200 字节
+    for segment in sync_segments(context, quarters) {
+        #[cfg(feature = "logging")]
+        log::debug!("Sync segment {}/{}", segment + 1, profile.cycles);
+        sync_segment(
+            transport,
+            data,
+            &profile,
+            extra_data,
+            segment,
+            quarters.is_some(),
+            received,
+            progress,
+            check,
+        )?;
+    }
 
-    // 计算每次循环的偏移增量：84 * 512 + 192 = 43008 + 192 = 43200
-    let _cycle_increment = PACKET_COUNT * PACKET_SIZE + 192;
-    let mut frame_buffer_offset = 0usize;
+    #[cfg(feature = "logging")]
+    log::info!("Sync completed: timestamp={}", context.timestamp);
+    Ok(true)
+}
 
-    for _cycle in 0..context.cycles {
-        #[cfg(feature = "logging")]
-        log::debug!("Sync cycle {}/{}", _cycle + 1, context.cycles);
+/// 一个同步周期：接收 MCU 的请求，发送第 `segment` 段（60 行）和尾包。
+///
+/// `marked` 为 `true` 时按局部刷新协议在尾包中标记段序号。
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn sync_segment(
+    transport: &mut dyn Transport,
+    data: &[u8],
+    profile: &ProtocolProfile,
+    extra_data: &ExtraData,
+    segment: usize,
+    marked: bool,
+    received: &mut Option<ExtraData>,
+    progress: &mut SyncProgress,
+    check: Check,
+) -> Result<(), BotError> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("cycle", segment).entered();
 
-        // 1. 接收 32 字节 extra data（MCU 发送的请求）
-        let mut rx_buf = [0u8; 32];
-        if let Err(e) = receive_with_retry(usb, &mut rx_buf, 32, 5) {
+    // 1. 接收 32 字节 extra data（MCU 发送的请求）
+    let mut rx_buf = [0u8; 32];
+    let request = &mut rx_buf[..profile.request_size.min(32)];
+    let request_len = request.len();
+    match receive_with_retry(
+        transport,
+        request,
+        request_len,
+        5,
+        &mut progress.retries,
+        check,
+    ) {
+        Ok(_) => received.get_or_insert_with(ExtraData::new).set_raw(request),
+        Err(e) => {
+            // 检查失败（取消等）时返回检查的错误
+            run_check(check, transport)?;
             #[cfg(feature = "logging")]
             log::warn!("Packet receive failed: {}", e);
-            // Suppress unused variable warning when logging is disabled
-            #[cfg(not(feature = "logging"))]
-            let _ = e;
+            return Err(BotError::ReceiveFailed(locate(e, segment, progress)));
         }
+    }
 
-        // 2. 发送 84 个 512 字节包（带偏移）
-        #[cfg(feature = "logging")]
-        log::debug!(
-            "Transmitting {} packets with offset {}...",
-            PACKET_COUNT,
-            frame_buffer_offset
-        );
-
-        for i in 0..PACKET_COUNT {
-            let start = frame_buffer_offset + i * PACKET_SIZE;
-            let end = start + PACKET_SIZE;
+    // 2. 发送 84 个 512 字节包（带偏移）
+    #[cfg(feature = "logging")]
+    log::debug!(
+        "Transmitting {} packets of segment {}...",
+        profile.packet_count,
+        segment
+    );
 
-            if transmit_with_retry(usb, &data[start..end], 3).is_err() {
-                #[cfg(feature = "logging")]
-                log::error!("Failed to transmit packet {}", i);
-            }
+    for packet in segment_packets(profile, data, segment) {
+        if let Err(e) = transmit_with_retry(transport, packet, 3, &mut progress.retries, check) {
+            run_check(check, transport)?;
+            #[cfg(feature = "logging")]
+            log::error!("Failed to transmit packet {}", progress.packets);
+            return Err(BotError::SendFailed(locate(e, segment, progress)));
         }
+        progress.packets += 1;
+    }
 
-        // 更新偏移量（84 * 512 = 43008）
-        frame_buffer_offset += PACKET_COUNT * PACKET_SIZE;
-
-        // 3. 准备尾数据（192 字节从当前偏移取 + 32 字节 extra data）
-        let mut tail_data = [0u8; TAIL_SIZE];
-        tail_data[..192].copy_from_slice(&data[frame_buffer_offset..frame_buffer_offset + 192]);
-        tail_data[192..].copy_from_slice(extra);
+    // 3. 准备尾数据（192 字节从当前偏移取 + 32 字节 extra data）
+    let tail_data = tail_packet(profile, data, extra_data, segment, marked);
 
-        // 更新偏移量（加上 192）
-        frame_buffer_offset += 192;
+    // 4. 发送尾包（224 字节）
+    #[cfg(feature = "logging")]
+    log::debug!("Transmitting tail packet ({} bytes)...", tail_data.len());
 
-        // 4. 发送尾包（224 字节）
+    if let Err(e) = transmit_with_retry(transport, &tail_data, 3, &mut progress.retries, check) {
+        run_check(check, transport)?;
         #[cfg(feature = "logging")]
-        log::debug!("Transmitting tail packet (224 bytes)...");
+        log::error!("Failed to transmit tail data");
+        return Err(BotError::SendFailed(locate(e, segment, progress)));
+    }
+    progress.packets += 1;
+    progress.cycles += 1;
+    Ok(())
+}
 
-        if transmit_with_retry(usb, &tail_data, 3).is_err() {
-            #[cfg(feature = "logging")]
-            log::error!("Failed to transmit tail data");
-        }
+/// 一次同步依次发送的段序号：局部刷新时为 `quarters` 中的分段，否则为前 `context.cycles` 段。
+pub(crate) fn sync_segments(context: &SyncContext, quarters: Option<QuarterMask>) -> Vec<usize> {
+    match quarters {
+        Some(mask) => mask.iter().collect(),
+        None => (0..context.cycles.min(context.profile.cycles)).collect(),
     }
+}
 
-    #[cfg(feature = "logging")]
-    log::info!("Sync completed: timestamp={}", context.timestamp);
-    Ok(true)
+/// 第 `segment` 个周期发送的全部数据：所有图片包加尾包，与传输通道上的顺序一致。
+pub(crate) fn cycle_data(
+    profile: &ProtocolProfile,
+    data: &[u8],
+    extra_data: &ExtraData,
+    segment: usize,
+    marked: bool,
+) -> Vec<u8> {
+    let mut bytes =
+        Vec::with_capacity(profile.packet_count * profile.packet_size + profile.tail_size());
+    for packet in segment_packets(profile, data, segment) {
+        bytes.extend_from_slice(packet);
+    }
+    bytes.extend_from_slice(&tail_packet(profile, data, extra_data, segment, marked));
+    bytes
+}
+
+/// 运行检查，失败时中止同步。
+fn run_check(check: Check, transport: &mut dyn Transport) -> Result<(), BotError> {
+    check(transport).map_err(|e| BotError::SendFailed(e.into()))
+}
+
+/// 给收发失败补充出错的周期和包序号。
+fn locate(mut error: TransferError, segment: usize, progress: &SyncProgress) -> TransferError {
+    error.cycle = Some(segment);
+    error.packet = Some(progress.packets);
+    error
 }
 
 /// 快速同步（仅图片）。
 pub fn sync_image(
-    usb: &mut UsbDevice,
+    transport: &mut dyn Transport,
     image_buffer: &ImageBuffer,
     context: &mut SyncContext,
 ) -> SyncResult {
     #[cfg(feature = "logging")]
     log::info!("Starting image sync...");
     let extra = ExtraData::new();
-    sync(usb, image_buffer, &extra, context)
+    sync(transport, image_buffer, &extra, context)
 }
 
 /// 快速同步（带关节角度）。
 pub fn sync_joints(
-    usb: &mut UsbDevice,
+    transport: &mut dyn Transport,
     angles: &JointAngles,
     context: &mut SyncContext,
 ) -> SyncResult {
@@ -205,5 +671,5 @@ pub fn sync_joints(
     let image = ImageBuffer::new();
     let mut extra = ExtraData::new();
     extra.set_joint_angles(angles, true);
-    sync(usb, &image, &extra, context)
+    sync(transport, &image, &extra, context)
 }
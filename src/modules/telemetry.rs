@@ -0,0 +1,146 @@
+//! ElectronBot 库的遥测历史。
+//!
+//! [`TelemetryHistory`] 是固定容量的环形缓冲区，每次成功同步后记录一条带时间戳的
+//! [`TelemetrySample`]：机器人发来的 32 字节扩展数据、解码后的原始和滤波角度、
+//! 当时的目标角度和舵机使能状态。容量用完后丢弃最旧的记录，
+//! 可以导出为 CSV 画曲线，事后排查动作问题。
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::modules::time::Instant;
+use crate::modules::types::JointAngles;
+
+/// 默认保留的记录数（20 FPS 下约 12 秒）。
+pub const DEFAULT_HISTORY_LEN: usize = 256;
+
+/// 一条遥测记录。
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetrySample {
+    /// 记录时间。
+    pub at: Instant,
+    /// 机器人发来的扩展数据。
+    pub raw: [u8; 32],
+    /// 上报的原始角度。
+    pub reported: JointAngles,
+    /// 滤波后的角度（没有设置滤波时与原始角度相同）。
+    pub filtered: JointAngles,
+    /// 目标角度。
+    pub target: JointAngles,
+    /// 舵机是否使能。
+    pub enabled: bool,
+}
+
+/// 遥测历史。
+#[derive(Debug, Clone)]
+pub struct TelemetryHistory {
+    capacity: usize,
+    samples: VecDeque<TelemetrySample>,
+}
+
+impl TelemetryHistory {
+    /// 创建保留 `capacity` 条记录的历史，0 表示不记录。
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            samples: VecDeque::with_capacity(capacity.min(DEFAULT_HISTORY_LEN)),
+        }
+    }
+
+    /// 容量。
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 修改容量，超出的旧记录被丢弃。
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.samples.len() > capacity {
+            self.samples.pop_front();
+        }
+    }
+
+    /// 加入一条记录。
+    pub fn push(&mut self, sample: TelemetrySample) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// 记录数。
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// 是否没有记录。
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// 最新的记录。
+    pub fn latest(&self) -> Option<&TelemetrySample> {
+        self.samples.back()
+    }
+
+    /// 从旧到新遍历记录。
+    pub fn iter(&self) -> impl Iterator<Item = &TelemetrySample> {
+        self.samples.iter()
+    }
+
+    /// 最近 `duration` 内的记录（从旧到新）。
+    pub fn since(&self, duration: Duration) -> impl Iterator<Item = &TelemetrySample> {
+        let now = Instant::now();
+        self.samples
+            .iter()
+            .filter(move |sample| now.duration_since(sample.at) <= duration)
+    }
+
+    /// 清空记录。
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// 导出为 CSV：时间（毫秒，从第一条记录开始）、使能、每个关节的目标、原始和滤波角度。
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("ms,enabled");
+        for kind in ["target", "reported", "filtered"] {
+            for joint in 0..6 {
+                csv.push_str(&format!(",{}{}", kind, joint));
+            }
+        }
+        csv.push('\n');
+        let Some(first) = self.samples.front() else {
+            return csv;
+        };
+        for sample in &self.samples {
+            csv.push_str(&format!(
+                "{},{}",
+                sample.at.duration_since(first.at).as_millis(),
+                sample.enabled as u8
+            ));
+            for angles in [&sample.target, &sample.reported, &sample.filtered] {
+                for angle in angles.as_array() {
+                    csv.push_str(&format!(",{:.2}", angle));
+                }
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// 保存为 CSV 文件。
+    pub fn save_csv<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        std::fs::write(path, self.to_csv()).map_err(|e| format!("保存遥测历史失败: {}", e))
+    }
+}
+
+impl Default for TelemetryHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_LEN)
+    }
+}
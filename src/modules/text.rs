@@ -0,0 +1,465 @@
+//! ElectronBot 库的文字渲染。
+//!
+//! - `text` feature：TTF/OTF 字体（基于 fontdue），支持字距调整
+//! - `embedded-font` feature：内置 Unifont 点阵字体（8x16/16x16），
+//!   覆盖中日韩文字，无需附带字体文件
+//!
+//! 多个字体可以组成回退链，缺字时依次查找下一个字体。
+
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_WIDTH};
+use crate::modules::image::ImageBuffer;
+use crate::modules::types::Color;
+
+/// Unifont 的原生字号（像素）。
+#[cfg(feature = "embedded-font")]
+const UNIFONT_SIZE: f32 = 16.0;
+
+/// Unifont 基线以上的行数。
+#[cfg(feature = "embedded-font")]
+const UNIFONT_ASCENT: f32 = 14.0;
+
+/// 单个字体。
+enum Face {
+    /// TTF/OTF 矢量字体。
+    #[cfg(feature = "text")]
+    Ttf(fontdue::Font),
+    /// 内置 Unifont 点阵字体。
+    #[cfg(feature = "embedded-font")]
+    Unifont,
+}
+
+/// 栅格化后的字形。
+struct RasterGlyph {
+    /// 相对笔触位置的左偏移。
+    xmin: i32,
+    /// 位图顶部相对基线的偏移（向上为正）。
+    top: i32,
+    width: usize,
+    height: usize,
+    advance: f32,
+    coverage: Vec<u8>,
+}
+
+impl Face {
+    fn has_glyph(&self, ch: char) -> bool {
+        match self {
+            #[cfg(feature = "text")]
+            Face::Ttf(f) => f.has_glyph(ch),
+            #[cfg(feature = "embedded-font")]
+            Face::Unifont => unifont::get_glyph(ch).is_some(),
+        }
+    }
+
+    fn ascent(&self, size: f32) -> f32 {
+        match self {
+            #[cfg(feature = "text")]
+            Face::Ttf(f) => f
+                .horizontal_line_metrics(size)
+                .map(|m| m.ascent)
+                .unwrap_or(size),
+            #[cfg(feature = "embedded-font")]
+            Face::Unifont => size * UNIFONT_ASCENT / UNIFONT_SIZE,
+        }
+    }
+
+    fn line_height(&self, size: f32) -> f32 {
+        match self {
+            #[cfg(feature = "text")]
+            Face::Ttf(f) => f
+                .horizontal_line_metrics(size)
+                .map(|m| m.new_line_size)
+                .unwrap_or(size),
+            #[cfg(feature = "embedded-font")]
+            Face::Unifont => size,
+        }
+    }
+
+    fn advance(&self, ch: char, size: f32) -> f32 {
+        match self {
+            #[cfg(feature = "text")]
+            Face::Ttf(f) => f.metrics(ch, size).advance_width,
+            #[cfg(feature = "embedded-font")]
+            Face::Unifont => {
+                let width = unifont::get_glyph(ch).map(|g| g.get_width()).unwrap_or(8);
+                width as f32 * size / UNIFONT_SIZE
+            }
+        }
+    }
+
+    fn kern(&self, _left: char, _right: char, _size: f32) -> f32 {
+        match self {
+            #[cfg(feature = "text")]
+            Face::Ttf(f) => f.horizontal_kern(_left, _right, _size).unwrap_or(0.0),
+            #[cfg(feature = "embedded-font")]
+            Face::Unifont => 0.0,
+        }
+    }
+
+    fn rasterize(&self, ch: char, size: f32) -> RasterGlyph {
+        match self {
+            #[cfg(feature = "text")]
+            Face::Ttf(f) => {
+                let (m, coverage) = f.rasterize(ch, size);
+                RasterGlyph {
+                    xmin: m.xmin,
+                    top: m.ymin + m.height as i32,
+                    width: m.width,
+                    height: m.height,
+                    advance: m.advance_width,
+                    coverage,
+                }
+            }
+            #[cfg(feature = "embedded-font")]
+            Face::Unifont => {
+                let scale = size / UNIFONT_SIZE;
+                let glyph = match unifont::get_glyph(ch) {
+                    Some(g) => g,
+                    None => {
+                        return RasterGlyph {
+                            xmin: 0,
+                            top: 0,
+                            width: 0,
+                            height: 0,
+                            advance: 8.0 * scale,
+                            coverage: Vec::new(),
+                        }
+                    }
+                };
+                let width = (glyph.get_width() as f32 * scale).round() as usize;
+                let height = size.round() as usize;
+                let mut coverage = vec![0u8; width * height];
+                for y in 0..height {
+                    let gy = (y as f32 / scale) as usize;
+                    for x in 0..width {
+                        let gx = (x as f32 / scale) as usize;
+                        if glyph.get_pixel(gx, gy) {
+                            coverage[y * width + x] = 255;
+                        }
+                    }
+                }
+                RasterGlyph {
+                    xmin: 0,
+                    top: self.ascent(size).round() as i32,
+                    width,
+                    height,
+                    advance: glyph.get_width() as f32 * scale,
+                    coverage,
+                }
+            }
+        }
+    }
+}
+
+/// 字体（可带回退链）。
+pub struct Font {
+    faces: Vec<Face>,
+}
+
+impl Font {
+    /// 从 TTF/OTF 字体文件字节创建。
+    #[cfg(feature = "text")]
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        let inner = fontdue::Font::from_bytes(data, fontdue::FontSettings::default())
+            .map_err(|e| format!("加载字体失败: {}", e))?;
+        Ok(Self {
+            faces: vec![Face::Ttf(inner)],
+        })
+    }
+
+    /// 从 TTF/OTF 字体文件加载。
+    #[cfg(feature = "text")]
+    pub fn load_from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, String> {
+        let data = std::fs::read(path).map_err(|e| format!("读取字体失败: {}", e))?;
+        Self::from_bytes(&data)
+    }
+
+    /// 内置 Unifont 点阵字体（半角 8x16，全角 16x16）。
+    ///
+    /// 字号为 16 的整数倍时显示效果最好。
+    #[cfg(feature = "embedded-font")]
+    pub fn embedded() -> Self {
+        Self {
+            faces: vec![Face::Unifont],
+        }
+    }
+
+    /// 追加回退字体，当前字体缺字时使用。
+    pub fn with_fallback(mut self, fallback: Font) -> Self {
+        self.faces.extend(fallback.faces);
+        self
+    }
+
+    /// 字体（含回退）是否包含指定字符。
+    pub fn has_glyph(&self, ch: char) -> bool {
+        self.faces.iter().any(|f| f.has_glyph(ch))
+    }
+
+    /// 查找包含字符的字体，都不包含时使用第一个字体。
+    fn face_for(&self, ch: char) -> &Face {
+        self.faces
+            .iter()
+            .find(|f| f.has_glyph(ch))
+            .unwrap_or(&self.faces[0])
+    }
+
+    /// 基线以上的高度（像素）。
+    pub fn ascent(&self, size: f32) -> f32 {
+        self.faces[0].ascent(size)
+    }
+
+    /// 行高（像素）。
+    pub fn line_height(&self, size: f32) -> f32 {
+        self.faces[0].line_height(size)
+    }
+
+    /// 两个相邻字符之间的字距调整（仅同一字体内有效）。
+    fn kern(&self, left: char, right: char, size: f32) -> f32 {
+        let face = self.face_for(right);
+        if std::ptr::eq(face, self.face_for(left)) {
+            face.kern(left, right, size)
+        } else {
+            0.0
+        }
+    }
+
+    /// 测量单行文字的宽度和高度（像素）。
+    pub fn measure(&self, text: &str, size: f32) -> (f32, f32) {
+        let mut width = 0.0f32;
+        let mut prev: Option<char> = None;
+        for ch in text.chars() {
+            if let Some(p) = prev {
+                width += self.kern(p, ch, size);
+            }
+            width += self.face_for(ch).advance(ch, size);
+            prev = Some(ch);
+        }
+        (width, self.line_height(size))
+    }
+
+    /// 按最大宽度自动换行。
+    ///
+    /// 遇到 `\n` 强制换行；拉丁文字在空白处断行，中日韩文字可在任意字符间断行，
+    /// 单个单词超过最大宽度时按字符断开。
+    pub fn wrap(&self, text: &str, size: f32, max_width: Option<f32>) -> Vec<String> {
+        let mut lines = Vec::new();
+        for paragraph in text.split('\n') {
+            let max_width = match max_width {
+                Some(w) => w,
+                None => {
+                    lines.push(paragraph.to_string());
+                    continue;
+                }
+            };
+
+            let mut line = String::new();
+            let mut last_break: Option<usize> = None;
+            for ch in paragraph.chars() {
+                if is_cjk(ch) {
+                    last_break = Some(line.len());
+                }
+                let mut candidate = line.clone();
+                candidate.push(ch);
+                if !line.is_empty() && self.measure(&candidate, size).0 > max_width {
+                    match last_break.filter(|&b| b > 0) {
+                        Some(b) => {
+                            let rest = line[b..].trim_start().to_string();
+                            lines.push(line[..b].trim_end().to_string());
+                            line = rest;
+                        }
+                        None => lines.push(std::mem::take(&mut line)),
+                    }
+                    last_break = None;
+                    if ch.is_whitespace() && line.is_empty() {
+                        continue;
+                    }
+                }
+                line.push(ch);
+                if ch.is_whitespace() || is_cjk(ch) {
+                    last_break = Some(line.len());
+                }
+            }
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+impl std::fmt::Debug for Font {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Font")
+            .field("faces", &self.faces.len())
+            .finish()
+    }
+}
+
+/// 是否为中日韩（含全角标点、谚文）字符，可在其前后断行。
+pub fn is_cjk(ch: char) -> bool {
+    matches!(ch as u32,
+        0x2E80..=0x9FFF
+        | 0xAC00..=0xD7AF
+        | 0xF900..=0xFAFF
+        | 0xFF00..=0xFFEF
+        | 0x20000..=0x3FFFF)
+}
+
+/// 多行文字的水平对齐方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextAlign {
+    /// 左对齐
+    #[default]
+    Left,
+    /// 居中
+    Center,
+    /// 右对齐
+    Right,
+}
+
+/// 多行文字样式。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextStyle {
+    /// 字号（像素）。
+    pub size: f32,
+    /// 颜色。
+    pub color: Color,
+    /// 对齐方式（相对 `max_width`，未设置时相对最宽的行）。
+    pub align: TextAlign,
+    /// 最大行宽，超出时自动换行。
+    pub max_width: Option<f32>,
+    /// 行距倍数。
+    pub line_spacing: f32,
+}
+
+impl TextStyle {
+    /// 创建指定字号和颜色的样式（左对齐、不自动换行）。
+    pub fn new(size: f32, color: Color) -> Self {
+        Self {
+            size,
+            color,
+            align: TextAlign::Left,
+            max_width: None,
+            line_spacing: 1.0,
+        }
+    }
+
+    /// 设置对齐方式。
+    pub fn align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// 设置最大行宽。
+    pub fn max_width(mut self, max_width: f32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// 设置行距倍数。
+    pub fn line_spacing(mut self, line_spacing: f32) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+}
+
+impl ImageBuffer {
+    /// 绘制单行文字。
+    ///
+    /// `(x, y)` 为文字行的左上角，基线位于 `y + ascent`。
+    /// 字形按覆盖率与背景混合，超出屏幕的部分会被裁剪。
+    /// 返回绘制的宽度（像素）。
+    pub fn draw_text(
+        &mut self,
+        x: i32,
+        y: i32,
+        text: &str,
+        font: &Font,
+        size: f32,
+        color: Color,
+    ) -> f32 {
+        let baseline = y as f32 + font.ascent(size);
+        let mut pen_x = x as f32;
+        let mut prev: Option<char> = None;
+
+        for ch in text.chars() {
+            if let Some(p) = prev {
+                pen_x += font.kern(p, ch, size);
+            }
+            let glyph = font.face_for(ch).rasterize(ch, size);
+            let left = pen_x.round() as i32 + glyph.xmin;
+            let top = baseline.round() as i32 - glyph.top;
+            self.blend_coverage(left, top, glyph.width, glyph.height, &glyph.coverage, color);
+            pen_x += glyph.advance;
+            prev = Some(ch);
+        }
+
+        pen_x - x as f32
+    }
+
+    /// 绘制多行文字，支持自动换行和对齐。
+    ///
+    /// 返回文字块的宽度和高度（像素）。
+    pub fn draw_text_block(
+        &mut self,
+        x: i32,
+        y: i32,
+        text: &str,
+        font: &Font,
+        style: &TextStyle,
+    ) -> (f32, f32) {
+        let lines = font.wrap(text, style.size, style.max_width);
+        let widths: Vec<f32> = lines
+            .iter()
+            .map(|l| font.measure(l, style.size).0)
+            .collect();
+        let block_width = style
+            .max_width
+            .unwrap_or_else(|| widths.iter().cloned().fold(0.0, f32::max));
+        let line_height = font.line_height(style.size) * style.line_spacing;
+
+        for (i, line) in lines.iter().enumerate() {
+            let offset = match style.align {
+                TextAlign::Left => 0.0,
+                TextAlign::Center => (block_width - widths[i]) / 2.0,
+                TextAlign::Right => block_width - widths[i],
+            };
+            let line_y = y as f32 + i as f32 * line_height;
+            self.draw_text(
+                x + offset.round() as i32,
+                line_y.round() as i32,
+                line,
+                font,
+                style.size,
+                style.color,
+            );
+        }
+
+        (block_width, lines.len() as f32 * line_height)
+    }
+
+    /// 按覆盖率位图混合颜色。
+    pub(crate) fn blend_coverage(
+        &mut self,
+        left: i32,
+        top: i32,
+        width: usize,
+        height: usize,
+        coverage: &[u8],
+        color: Color,
+    ) {
+        for row in 0..height {
+            let py = top + row as i32;
+            if py < 0 || py >= FRAME_HEIGHT as i32 {
+                continue;
+            }
+            for col in 0..width {
+                let px = left + col as i32;
+                if px < 0 || px >= FRAME_WIDTH as i32 {
+                    continue;
+                }
+                let alpha = coverage[row * width + col];
+                if alpha > 0 {
+                    self.blend_pixel(px as usize, py as usize, color, alpha as f32 / 255.0);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,9 @@
+//! ElectronBot 库的时钟。
+//!
+//! `std::time::Instant` 和 `SystemTime` 在 `wasm32-unknown-unknown` 上调用时会 panic，
+//! 浏览器中改用 `web-time`（基于 `performance.now()` 和 `Date.now()`），其他平台就是标准库的类型。
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::{Instant, SystemTime, UNIX_EPOCH};
+#[cfg(target_arch = "wasm32")]
+pub use web_time::{Instant, SystemTime, UNIX_EPOCH};
@@ -0,0 +1,76 @@
+//! ElectronBot 库的传输层抽象。
+//!
+//! 同步协议只依赖“发送一个包”和“接收一个包”两种操作。[`Transport`] 把这两种操作抽象出来，
+//! 本地 USB 设备、网络桥接等都可以作为 [`ElectronBot`](crate::ElectronBot) 的底层连接。
+
+use std::any::Any;
+use std::time::Duration;
+
+use crate::modules::error::TransferErrorKind;
+
+/// 收发方向。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// 设备到主机（接收）。
+    In,
+    /// 主机到设备（发送）。
+    Out,
+}
+
+/// 数据包传输通道。
+///
+/// 语义与 USB 批量传输一致：`transmit` 发送一个完整的包（长度为 512 的整数倍时
+/// 由实现负责补发零长度包），`receive` 接收一个包并返回实际长度。
+pub trait Transport: Send {
+    /// 发送一个包。
+    fn transmit(&mut self, data: &[u8]) -> Result<bool, String>;
+
+    /// 接收一个包，返回接收的字节数。
+    fn receive(&mut self, data: &mut [u8]) -> Result<usize, String>;
+
+    /// 设置之后每次收发的超时，`None` 恢复默认值；不支持的实现忽略。
+    fn set_timeout(&mut self, _timeout: Option<Duration>) {}
+
+    /// 最近一次失败的收发的底层错误类型；不提供错误类型的实现返回 `None`。
+    fn last_error_kind(&self) -> Option<TransferErrorKind> {
+        None
+    }
+
+    /// 清除 `direction` 方向端点的停止（STALL）状态，之后可以继续收发；
+    /// 没有端点概念的实现返回错误。
+    fn clear_halt(&mut self, _direction: Direction) -> Result<(), String> {
+        Err("不支持清除端点停止状态".to_string())
+    }
+
+    /// 具体的通道类型，用于取回底层句柄（例如 [`UsbDevice`](crate::modules::usb::UsbDevice)）；
+    /// 包装其他通道的实现返回内部通道。
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        None
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+        (**self).transmit(data)
+    }
+
+    fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+        (**self).receive(data)
+    }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        (**self).set_timeout(timeout)
+    }
+
+    fn last_error_kind(&self) -> Option<TransferErrorKind> {
+        (**self).last_error_kind()
+    }
+
+    fn clear_halt(&mut self, direction: Direction) -> Result<(), String> {
+        (**self).clear_halt(direction)
+    }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        (**self).as_any_mut()
+    }
+}
@@ -0,0 +1,180 @@
+//! ElectronBot 库的舵机参数透传。
+//!
+//! 原版固件只使用扩展数据的前 25 字节（使能标志和 6 个角度），社区固件用后面的保留字节
+//! 接收每个关节的速度、加速度或 PID 参数。[`ServoTuning`] 描述要设置的参数，
+//! [`TuningEncoder`] 把它编码成若干条 [`TUNING_LEN`] 字节的消息，每次同步在保留字节中发送一条。
+//! 不同的固件分支实现自己的编码器即可，内置的 [`ParamEncoder`] 每条消息设置一个关节的一个参数。
+//!
+//! 保留字节的最后一个（字节 31）用于局部刷新的段序号，消息不使用它。
+
+/// 消息在扩展数据中的偏移。
+pub const TUNING_OFFSET: usize = 25;
+
+/// 消息长度（字节 25-30）。
+pub const TUNING_LEN: usize = 6;
+
+/// 一条参数消息。
+pub type TuningMessage = [u8; TUNING_LEN];
+
+/// PID 参数。
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Pid {
+    /// 比例系数。
+    pub kp: f32,
+    /// 积分系数。
+    pub ki: f32,
+    /// 微分系数。
+    pub kd: f32,
+}
+
+impl Pid {
+    /// 创建 PID 参数。
+    pub fn new(kp: f32, ki: f32, kd: f32) -> Self {
+        Self { kp, ki, kd }
+    }
+}
+
+/// 单个关节要设置的参数，`None` 表示不修改。
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct JointTuning {
+    /// 最大速度（度每秒）。
+    pub speed: Option<f32>,
+    /// 最大加速度（度每平方秒）。
+    pub acceleration: Option<f32>,
+    /// PID 参数。
+    pub pid: Option<Pid>,
+}
+
+impl JointTuning {
+    /// 是否没有要设置的参数。
+    pub fn is_empty(&self) -> bool {
+        self.speed.is_none() && self.acceleration.is_none() && self.pid.is_none()
+    }
+}
+
+/// 舵机参数。
+///
+/// ```
+/// use electron_bot::{Pid, ServoTuning};
+///
+/// let tuning = ServoTuning::new()
+///     .speed_all(120.0)
+///     .acceleration(0, 400.0)
+///     .pid(2, Pid::new(1.2, 0.0, 0.05));
+/// assert_eq!(tuning.joints()[2].speed, Some(120.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ServoTuning {
+    joints: [JointTuning; 6],
+}
+
+impl ServoTuning {
+    /// 创建不修改任何参数的设置。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置关节的最大速度（超出范围的关节被忽略）。
+    pub fn speed(mut self, joint: usize, degrees_per_second: f32) -> Self {
+        if let Some(tuning) = self.joints.get_mut(joint) {
+            tuning.speed = Some(degrees_per_second.abs());
+        }
+        self
+    }
+
+    /// 设置所有关节的最大速度。
+    pub fn speed_all(mut self, degrees_per_second: f32) -> Self {
+        for tuning in &mut self.joints {
+            tuning.speed = Some(degrees_per_second.abs());
+        }
+        self
+    }
+
+    /// 设置关节的最大加速度（超出范围的关节被忽略）。
+    pub fn acceleration(mut self, joint: usize, degrees_per_second2: f32) -> Self {
+        if let Some(tuning) = self.joints.get_mut(joint) {
+            tuning.acceleration = Some(degrees_per_second2.abs());
+        }
+        self
+    }
+
+    /// 设置关节的 PID 参数（超出范围的关节被忽略）。
+    pub fn pid(mut self, joint: usize, pid: Pid) -> Self {
+        if let Some(tuning) = self.joints.get_mut(joint) {
+            tuning.pid = Some(pid);
+        }
+        self
+    }
+
+    /// 每个关节要设置的参数。
+    pub fn joints(&self) -> &[JointTuning; 6] {
+        &self.joints
+    }
+
+    /// 是否没有要设置的参数。
+    pub fn is_empty(&self) -> bool {
+        self.joints.iter().all(JointTuning::is_empty)
+    }
+}
+
+/// 把舵机参数编码为固件能理解的消息。
+pub trait TuningEncoder: Send {
+    /// 编码为按顺序发送的消息，固件不支持的参数返回错误。
+    fn encode(&self, tuning: &ServoTuning) -> Result<Vec<TuningMessage>, String>;
+}
+
+/// 内置的参数编码：每条消息设置一个关节的一个参数。
+///
+/// | 字节 | 内容 |
+/// |------|------|
+/// | 25 | 参数：1 速度、2 加速度、3 Kp、4 Ki、5 Kd（0 表示没有消息） |
+/// | 26 | 关节序号（0-5） |
+/// | 27-30 | 参数值（`f32`，小端） |
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParamEncoder;
+
+impl ParamEncoder {
+    /// 速度。
+    pub const SPEED: u8 = 1;
+    /// 加速度。
+    pub const ACCELERATION: u8 = 2;
+    /// 比例系数。
+    pub const KP: u8 = 3;
+    /// 积分系数。
+    pub const KI: u8 = 4;
+    /// 微分系数。
+    pub const KD: u8 = 5;
+
+    fn message(param: u8, joint: usize, value: f32) -> TuningMessage {
+        let mut message = [0; TUNING_LEN];
+        message[0] = param;
+        message[1] = joint as u8;
+        message[2..].copy_from_slice(&value.to_le_bytes());
+        message
+    }
+}
+
+impl TuningEncoder for ParamEncoder {
+    fn encode(&self, tuning: &ServoTuning) -> Result<Vec<TuningMessage>, String> {
+        let mut messages = Vec::new();
+        for (joint, params) in tuning.joints().iter().enumerate() {
+            let mut values = Vec::new();
+            if let Some(speed) = params.speed {
+                values.push((Self::SPEED, speed));
+            }
+            if let Some(acceleration) = params.acceleration {
+                values.push((Self::ACCELERATION, acceleration));
+            }
+            if let Some(pid) = params.pid {
+                values.extend([(Self::KP, pid.kp), (Self::KI, pid.ki), (Self::KD, pid.kd)]);
+            }
+            for (param, value) in values {
+                if !value.is_finite() {
+                    return Err(format!("关节 {} 的参数 {} 不是有效数字", joint, param));
+                }
+                messages.push(Self::message(param, joint, value));
+            }
+        }
+        Ok(messages)
+    }
+}
@@ -1,61 +1,8 @@
 //! ElectronBot 库的公共类型定义。
 
-/// 6 个舵机的角度。
-#[derive(Debug, Clone, PartialEq)]
-pub struct JointAngles(pub [f32; 6]);
-
-impl JointAngles {
-    /// 创建新的角度数组（初始化为零）。
-    pub fn new() -> Self {
-        Self([0.0; 6])
-    }
-
-    /// 获取角度数组的引用。
-    pub fn as_array(&self) -> &[f32; 6] {
-        &self.0
-    }
-
-    /// 获取角度数组的可变引用。
-    pub fn as_array_mut(&mut self) -> &mut [f32; 6] {
-        &mut self.0
-    }
-
-    /// 通过索引获取单个角度（0-5）。
-    pub fn get(&self, index: usize) -> Option<f32> {
-        self.0.get(index).copied()
-    }
-
-    /// 通过索引设置单个角度（0-5）。
-    pub fn set(&mut self, index: usize, value: f32) -> Option<()> {
-        self.0.get_mut(index).map(|v| *v = value)
-    }
-
-    /// 转换为字节（小端序）。
-    pub fn to_bytes(&self) -> [u8; 24] {
-        let mut bytes = [0u8; 24];
-        for (i, &val) in self.0.iter().enumerate() {
-            bytes[i * 4..i * 4 + 4].copy_from_slice(&val.to_le_bytes());
-        }
-        bytes
-    }
-
-    /// 从字节创建（小端序）。
-    pub fn from_bytes(bytes: &[u8; 24]) -> Self {
-        let mut angles = [0.0f32; 6];
-        for i in 0..6 {
-            let mut val_bytes = [0u8; 4];
-            val_bytes.copy_from_slice(&bytes[i * 4..i * 4 + 4]);
-            angles[i] = f32::from_le_bytes(val_bytes);
-        }
-        Self(angles)
-    }
-}
+use crate::modules::constants::{USB_PID, USB_VID};
 
-impl Default for JointAngles {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+pub use electron_bot_core::types::{Angle, JointAngles, POSE_NAMES};
 
 /// 用于测试的常用颜色。
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -103,6 +50,91 @@ impl Color {
     }
 }
 
+/// 屏幕顺时针旋转角度。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(try_from = "u16"))]
+pub enum Rotation {
+    /// 不旋转。
+    #[default]
+    Deg0,
+    /// 顺时针 90°。
+    Deg90,
+    /// 180°。
+    Deg180,
+    /// 顺时针 270°。
+    Deg270,
+}
+
+impl Rotation {
+    /// 角度值。
+    pub fn degrees(&self) -> u16 {
+        match self {
+            Rotation::Deg0 => 0,
+            Rotation::Deg90 => 90,
+            Rotation::Deg180 => 180,
+            Rotation::Deg270 => 270,
+        }
+    }
+}
+
+impl TryFrom<u16> for Rotation {
+    type Error = String;
+
+    fn try_from(degrees: u16) -> Result<Self, Self::Error> {
+        match degrees {
+            0 => Ok(Rotation::Deg0),
+            90 => Ok(Rotation::Deg90),
+            180 => Ok(Rotation::Deg180),
+            270 => Ok(Rotation::Deg270),
+            _ => Err(format!(
+                "无效的旋转角度: {}（只支持 0/90/180/270）",
+                degrees
+            )),
+        }
+    }
+}
+
+/// 屏幕安装方向（先旋转，再翻转）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(default))]
+pub struct Orientation {
+    /// 顺时针旋转角度。
+    pub rotation: Rotation,
+    /// 水平翻转（左右镜像）。
+    pub flip_horizontal: bool,
+    /// 垂直翻转（上下镜像）。
+    pub flip_vertical: bool,
+}
+
+impl Orientation {
+    /// 创建只旋转的方向。
+    pub fn new(rotation: Rotation) -> Self {
+        Self {
+            rotation,
+            ..Self::default()
+        }
+    }
+
+    /// 水平翻转（构建器风格）。
+    pub fn flip_horizontal(mut self) -> Self {
+        self.flip_horizontal = true;
+        self
+    }
+
+    /// 垂直翻转（构建器风格）。
+    pub fn flip_vertical(mut self) -> Self {
+        self.flip_vertical = true;
+        self
+    }
+
+    /// 是否为默认方向（不做任何变换）。
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 /// 设备信息。
 #[derive(Debug, Clone)]
 pub struct DeviceInfo {
@@ -112,4 +144,38 @@ pub struct DeviceInfo {
     pub pid: u16,
     /// 设备信息字符串。
     pub info: String,
+    /// 匹配的固件版本名称，不是 ElectronBot 时为 `None`。
+    pub variant: Option<String>,
+}
+
+/// 已知的固件版本及其 USB ID。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FirmwareVariant {
+    /// 名称。
+    pub name: String,
+    /// 厂商 ID。
+    pub vid: u16,
+    /// 产品 ID。
+    pub pid: u16,
+}
+
+impl FirmwareVariant {
+    /// 创建固件版本。
+    pub fn new(name: &str, vid: u16, pid: u16) -> Self {
+        Self {
+            name: name.to_string(),
+            vid,
+            pid,
+        }
+    }
+
+    /// 原版固件。
+    pub fn stock() -> Self {
+        Self::new("stock", USB_VID, USB_PID)
+    }
+
+    /// 是否匹配指定的 USB ID。
+    pub fn matches(&self, vid: u16, pid: u16) -> bool {
+        self.vid == vid && self.pid == pid
+    }
 }
@@ -1,10 +1,38 @@
 //! ElectronBot 库的 USB 底层操作。
 
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
 use rusb::{Context, DeviceHandle, UsbContext};
 
-use crate::modules::constants::{TIMEOUT_MS, USB_PID, USB_VID};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::modules::constants::TIMEOUT_MS;
+use crate::modules::error::BotError;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::modules::error::TransferErrorKind;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::modules::transport::Direction;
+use crate::modules::transport::Transport;
+use crate::modules::types::FirmwareVariant;
+
+/// 额外 USB ID 的环境变量，格式为 `vid:pid[=名称]`，多个用逗号分隔（十六进制）。
+pub const DEVICE_IDS_ENV: &str = "ELECTRONBOT_USB_IDS";
+
+/// `connect` 使用的 USB 后端。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UsbBackend {
+    /// 先用 libusb，失败时再尝试 WinUSB（仅 Windows 的 `winusb` feature，默认）。
+    #[default]
+    Auto,
+    /// 只用 libusb。
+    Libusb,
+    /// 只用 WinUSB（需要 Windows 和 `winusb` feature）。
+    WinUsb,
+}
 
 /// 内部 USB 设备句柄。
+#[cfg(not(target_arch = "wasm32"))]
 pub struct UsbDevice {
     /// 设备句柄。
     pub handle: DeviceHandle<Context>,
@@ -12,8 +40,13 @@ pub struct UsbDevice {
     pub write_endpoint: u8,
     /// 接收端点地址。
     pub read_endpoint: u8,
+    /// 单次批量传输的超时。
+    timeout: Duration,
+    /// 最近一次失败的批量传输的错误类型。
+    last_error: Option<TransferErrorKind>,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl UsbDevice {
     /// 创建新的 USB 设备。
     pub fn new(handle: DeviceHandle<Context>, write_endpoint: u8, read_endpoint: u8) -> Self {
@@ -21,12 +54,14 @@ impl UsbDevice {
             handle,
             write_endpoint,
             read_endpoint,
+            timeout: Duration::from_millis(TIMEOUT_MS),
+            last_error: None,
         }
     }
 
     /// 通过批量传输发送数据。
     pub fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
-        let timeout = std::time::Duration::from_millis(TIMEOUT_MS);
+        let timeout = self.timeout;
 
         // 发送数据
         match self.handle.write_bulk(self.write_endpoint, data, timeout) {
@@ -37,11 +72,13 @@ impl UsbDevice {
             Ok(_written) => {
                 #[cfg(feature = "logging")]
                 log::warn!("USB transmit incomplete: {} of {}", _written, data.len());
+                self.last_error = Some(TransferErrorKind::Other);
                 return Err("发送不完整".to_string());
             }
             Err(e) => {
                 #[cfg(feature = "logging")]
                 log::error!("USB transmit failed: {}", e);
+                self.last_error = Some(error_kind(e));
                 return Err(format!("发送失败: {}", e));
             }
         }
@@ -51,6 +88,7 @@ impl UsbDevice {
             if let Err(e) = self.handle.write_bulk(self.write_endpoint, &[], timeout) {
                 #[cfg(feature = "logging")]
                 log::error!("USB zero packet failed: {}", e);
+                self.last_error = Some(error_kind(e));
                 return Err(format!("零包失败: {}", e));
             }
         }
@@ -60,7 +98,7 @@ impl UsbDevice {
 
     /// 通过批量传输接收数据。
     pub fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
-        let timeout = std::time::Duration::from_millis(TIMEOUT_MS);
+        let timeout = self.timeout;
         match self.handle.read_bulk(self.read_endpoint, data, timeout) {
             Ok(read) => {
                 #[cfg(feature = "logging")]
@@ -70,6 +108,7 @@ impl UsbDevice {
             Err(e) => {
                 #[cfg(feature = "logging")]
                 log::error!("USB receive failed: {}", e);
+                self.last_error = Some(error_kind(e));
                 Err(format!("接收失败: {}", e))
             }
         }
@@ -116,7 +155,74 @@ impl UsbDevice {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl Transport for UsbDevice {
+    fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+        UsbDevice::transmit(self, data)
+    }
+
+    fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+        UsbDevice::receive(self, data)
+    }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout.unwrap_or(Duration::from_millis(TIMEOUT_MS));
+    }
+
+    fn last_error_kind(&self) -> Option<TransferErrorKind> {
+        self.last_error
+    }
+
+    fn clear_halt(&mut self, direction: Direction) -> Result<(), String> {
+        let endpoint = match direction {
+            Direction::In => self.read_endpoint,
+            Direction::Out => self.write_endpoint,
+        };
+        self.handle
+            .clear_halt(endpoint)
+            .map_err(|e| format!("清除端点 {:#04x} 停止状态失败: {}", endpoint, e))?;
+        self.last_error = None;
+        Ok(())
+    }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn std::any::Any> {
+        Some(self)
+    }
+}
+
+/// libusb 错误对应的传输错误类型。
+#[cfg(not(target_arch = "wasm32"))]
+pub fn error_kind(error: rusb::Error) -> TransferErrorKind {
+    match error {
+        rusb::Error::Timeout => TransferErrorKind::Timeout,
+        rusb::Error::NoDevice => TransferErrorKind::NoDevice,
+        rusb::Error::Pipe => TransferErrorKind::Pipe,
+        rusb::Error::Overflow => TransferErrorKind::Overflow,
+        rusb::Error::Busy => TransferErrorKind::Busy,
+        rusb::Error::Access => TransferErrorKind::Access,
+        rusb::Error::Io => TransferErrorKind::Io,
+        _ => TransferErrorKind::Other,
+    }
+}
+
+/// 浏览器中没有 libusb，这个类型没有值（用 [`WebUsb`](crate::modules::webusb::WebUsb) 连接设备）。
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug)]
+pub enum UsbDevice {}
+
+#[cfg(target_arch = "wasm32")]
+impl Transport for UsbDevice {
+    fn transmit(&mut self, _data: &[u8]) -> Result<bool, String> {
+        match *self {}
+    }
+
+    fn receive(&mut self, _data: &mut [u8]) -> Result<usize, String> {
+        match *self {}
+    }
+}
+
 /// 扫描所有 USB 设备。
+#[cfg(not(target_arch = "wasm32"))]
 pub fn scan_devices() -> Vec<(u16, u16, String)> {
     #[cfg(feature = "logging")]
     log::info!("Scanning USB devices...");
@@ -154,11 +260,87 @@ pub fn scan_devices() -> Vec<(u16, u16, String)> {
     devices
 }
 
-/// 检查 ElectronBot 是否存在。
+/// 浏览器不能枚举 USB 设备，总是返回空列表。
+#[cfg(target_arch = "wasm32")]
+pub fn scan_devices() -> Vec<(u16, u16, String)> {
+    Vec::new()
+}
+
+/// 已知的固件版本：内置的原版固件，加上环境变量 [`DEVICE_IDS_ENV`] 中的 ID。
+///
+/// 社区固件分支的 ID 确认后加入内置表，在此之前可以通过环境变量或
+/// `ElectronBot::add_variant` 添加。
+pub fn known_variants() -> Vec<FirmwareVariant> {
+    let mut variants = vec![FirmwareVariant::stock()];
+    if let Ok(text) = std::env::var(DEVICE_IDS_ENV) {
+        match parse_device_ids(&text) {
+            Ok(extra) => {
+                for variant in extra {
+                    if find_variant(&variants, variant.vid, variant.pid).is_none() {
+                        variants.push(variant);
+                    }
+                }
+            }
+            Err(_e) => {
+                #[cfg(feature = "logging")]
+                log::warn!("Ignoring {}: {}", DEVICE_IDS_ENV, _e);
+            }
+        }
+    }
+    variants
+}
+
+/// 解析 `vid:pid[=名称]` 列表（逗号分隔，十六进制，可带 `0x` 前缀），没有名称时为 `custom`。
+pub fn parse_device_ids(text: &str) -> Result<Vec<FirmwareVariant>, String> {
+    let hex = |s: &str| {
+        let s = s.trim();
+        let s = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+        u16::from_str_radix(s, 16).map_err(|_| format!("USB ID 无效: {}", s))
+    };
+    text.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (ids, name) = match entry.split_once('=') {
+                Some((ids, name)) => (ids, name.trim()),
+                None => (entry, "custom"),
+            };
+            let (vid, pid) = ids
+                .split_once(':')
+                .ok_or_else(|| format!("应为 vid:pid 格式: {}", entry))?;
+            Ok(FirmwareVariant::new(name, hex(vid)?, hex(pid)?))
+        })
+        .collect()
+}
+
+/// 在固件版本表中查找 USB ID。
+pub fn find_variant(variants: &[FirmwareVariant], vid: u16, pid: u16) -> Option<&FirmwareVariant> {
+    variants.iter().find(|v| v.matches(vid, pid))
+}
+
+/// 从 Windows 设备接口路径（`\\?\usb#vid_1001&pid_8023#...`，不区分大小写）中取出 USB ID。
+pub fn parse_device_path(path: &str) -> Option<(u16, u16)> {
+    let lower = path.to_ascii_lowercase();
+    let id = |key: &str| {
+        let start = lower.find(key)? + key.len();
+        let digits = lower.get(start..start + 4)?;
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        u16::from_str_radix(digits, 16).ok()
+    };
+    Some((id("vid_")?, id("pid_")?))
+}
+
+/// 检查 ElectronBot 是否存在（任一已知固件版本）。
 pub fn is_electron_bot_present() -> bool {
+    let variants = known_variants();
     let present = scan_devices()
         .iter()
-        .any(|(vid, pid, _)| *vid == USB_VID && *pid == USB_PID);
+        .any(|(vid, pid, _)| find_variant(&variants, *vid, *pid).is_some());
 
     #[cfg(feature = "logging")]
     {
@@ -171,19 +353,32 @@ pub fn is_electron_bot_present() -> bool {
     present
 }
 
-/// 打开 ElectronBot 设备并声明接口。
+/// 打开 ElectronBot 设备并声明接口（匹配任一已知固件版本）。
 pub fn open_electron_bot() -> Result<UsbDevice, String> {
+    open_matching(&known_variants()).map(|(device, _)| device)
+}
+
+/// 打开第一个匹配 `variants` 中任一 USB ID 的设备，返回设备和匹配的固件版本。
+pub fn open_matching(variants: &[FirmwareVariant]) -> Result<(UsbDevice, FirmwareVariant), String> {
+    open_libusb(variants).map_err(|e| match e {
+        BotError::UsbError(message) => message,
+        e => e.to_string(),
+    })
+}
+
+/// 通过 libusb 打开，macOS 上的访问错误单独返回 `MacOsAccess`。
+#[cfg(not(target_arch = "wasm32"))]
+fn open_libusb(variants: &[FirmwareVariant]) -> Result<(UsbDevice, FirmwareVariant), BotError> {
     #[cfg(feature = "logging")]
     log::info!(
-        "Opening ElectronBot device (VID={:04x}, PID={:04x})...",
-        USB_VID,
-        USB_PID
+        "Opening ElectronBot device ({} known variants)...",
+        variants.len()
     );
 
     let context = rusb::Context::new().map_err(|e| {
         #[cfg(feature = "logging")]
         log::error!("Failed to create USB context: {}", e);
-        format!("创建上下文失败: {}", e)
+        BotError::UsbError(format!("创建上下文失败: {}", e))
     })?;
 
     for device in context
@@ -191,120 +386,276 @@ pub fn open_electron_bot() -> Result<UsbDevice, String> {
         .map_err(|e| {
             #[cfg(feature = "logging")]
             log::error!("Failed to get devices: {}", e);
-            format!("获取设备失败: {}", e)
+            BotError::UsbError(format!("获取设备失败: {}", e))
         })?
         .iter()
     {
         if let Ok(desc) = device.device_descriptor() {
-            if desc.vendor_id() == USB_VID && desc.product_id() == USB_PID {
+            if let Some(variant) = find_variant(variants, desc.vendor_id(), desc.product_id()) {
                 #[cfg(feature = "logging")]
-                log::info!("Found matching device, attempting to open...");
+                log::info!(
+                    "Found {} device {:04x}:{:04x}, attempting to open...",
+                    variant.name,
+                    variant.vid,
+                    variant.pid
+                );
 
                 // 尝试打开设备
                 let handle = device.open().map_err(|e| {
                     #[cfg(feature = "logging")]
                     log::error!("Failed to open device: {}", e);
-                    format!("打开设备失败: {}", e)
+                    open_error(e)
                 })?;
 
-                // 如果有内核驱动附着，先分离
-                if let Ok(true) = handle.kernel_driver_active(0) {
+                return claim_bulk_interface(handle).map(|device| (device, variant.clone()));
+            }
+        }
+    }
+
+    #[cfg(feature = "logging")]
+    log::error!("ElectronBot device not found");
+    Err(BotError::UsbError("未找到 ElectronBot".to_string()))
+}
+
+/// 打开设备失败时的错误（macOS 和 Linux 上被拒绝访问时给出原因和解决办法）。
+#[cfg(not(target_arch = "wasm32"))]
+fn open_error(error: rusb::Error) -> BotError {
+    open_error_on(error, std::env::consts::OS)
+}
+
+/// 按操作系统 `os`（`std::env::consts::OS` 的取值）给出打开设备失败时的错误。
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn open_error_on(error: rusb::Error, os: &str) -> BotError {
+    match (os, error) {
+        ("macos", rusb::Error::Access) => BotError::MacOsAccess(format!(
+            "打开设备被拒绝（{}），沙盒应用需要 com.apple.security.device.usb 权限",
+            error
+        )),
+        ("linux", rusb::Error::Access) => BotError::UsbError(format!(
+            "打开设备被拒绝（{}），当前用户没有访问权限，运行 `sudo electronbot udev --install` \
+             安装 udev 规则后重新插拔设备",
+            error
+        )),
+        _ => BotError::UsbError(format!("打开设备失败: {}", error)),
+    }
+}
+
+/// macOS 上声明接口被拒绝或占用时的说明，其他系统和其他错误返回 `None`。
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn claim_error_on(interface: u8, error: rusb::Error, os: &str) -> Option<String> {
+    match (os, error) {
+        ("macos", rusb::Error::Access | rusb::Error::Busy) => Some(format!(
+            "接口 {} 被其他进程独占（{}），请关闭其他正在使用机器人的程序\
+             （另一个本库实例、原厂上位机或打开了 WebUSB 页面的浏览器）后重试",
+            interface, error
+        )),
+        _ => None,
+    }
+}
+
+/// 按后端打开第一个匹配 `variants` 的设备，返回传输通道和匹配的固件版本。
+///
+/// `Auto` 在 libusb 失败时尝试 WinUSB，两者都失败时返回 libusb 的错误。
+pub fn open_with_backend(
+    backend: UsbBackend,
+    variants: &[FirmwareVariant],
+) -> Result<(Box<dyn Transport>, FirmwareVariant), BotError> {
+    let libusb = || {
+        open_libusb(variants)
+            .map(|(device, variant)| (Box::new(device) as Box<dyn Transport>, variant))
+    };
+    match backend {
+        UsbBackend::Libusb => libusb(),
+        UsbBackend::WinUsb => open_winusb(variants),
+        #[cfg(all(windows, feature = "winusb"))]
+        UsbBackend::Auto => libusb().or_else(|e| {
+            #[cfg(feature = "logging")]
+            log::info!("libusb open failed ({}), trying WinUSB", e);
+            open_winusb(variants).map_err(|_| e)
+        }),
+        #[cfg(not(all(windows, feature = "winusb")))]
+        UsbBackend::Auto => libusb(),
+    }
+}
+
+/// 通过 WinUSB 打开。
+#[cfg(all(windows, feature = "winusb"))]
+fn open_winusb(
+    variants: &[FirmwareVariant],
+) -> Result<(Box<dyn Transport>, FirmwareVariant), BotError> {
+    crate::modules::winusb::WinUsbDevice::open_matching(variants)
+        .map(|(device, variant)| (Box::new(device) as Box<dyn Transport>, variant))
+        .map_err(BotError::UsbError)
+}
+
+/// 其他平台和没有 `winusb` feature 时总是返回错误。
+#[cfg(not(all(windows, feature = "winusb")))]
+fn open_winusb(
+    _variants: &[FirmwareVariant],
+) -> Result<(Box<dyn Transport>, FirmwareVariant), BotError> {
+    Err(BotError::UsbError(
+        "WinUSB 后端需要 Windows 和 `winusb` feature".to_string(),
+    ))
+}
+
+/// 用已经打开的文件描述符打开设备（Android 上由 `UsbManager` 打开），
+/// 返回设备和匹配的固件版本（不在 `variants` 中时名称为 `custom`）。
+///
+/// Android 上应用没有枚举 USB 设备的权限，先关闭 libusb 的设备扫描。
+///
+/// # Safety
+///
+/// `fd` 必须是 USB 设备节点的有效描述符，并且在返回的设备释放之前保持打开；libusb 不会关闭它。
+#[cfg(unix)]
+pub unsafe fn open_with_fd(
+    fd: std::os::fd::RawFd,
+    variants: &[FirmwareVariant],
+) -> Result<(UsbDevice, FirmwareVariant), BotError> {
+    #[cfg(feature = "logging")]
+    log::info!("Opening ElectronBot from file descriptor {}...", fd);
+
+    #[cfg(target_os = "android")]
+    if let Err(_e) = rusb::disable_device_discovery() {
+        #[cfg(feature = "logging")]
+        log::warn!("Failed to disable device discovery: {}", _e);
+    }
+
+    let context = rusb::Context::new().map_err(|e| {
+        #[cfg(feature = "logging")]
+        log::error!("Failed to create USB context: {}", e);
+        BotError::UsbError(format!("创建上下文失败: {}", e))
+    })?;
+    let handle = context.open_device_with_fd(fd).map_err(|e| {
+        #[cfg(feature = "logging")]
+        log::error!("Failed to wrap file descriptor: {}", e);
+        open_error(e)
+    })?;
+    let desc = handle
+        .device()
+        .device_descriptor()
+        .map_err(|e| BotError::UsbError(format!("读取设备描述符失败: {}", e)))?;
+    let (vid, pid) = (desc.vendor_id(), desc.product_id());
+    let variant = find_variant(variants, vid, pid)
+        .cloned()
+        .unwrap_or_else(|| FirmwareVariant::new("custom", vid, pid));
+    claim_bulk_interface(handle).map(|device| (device, variant))
+}
+
+/// 分离内核驱动，声明第一个同时有批量输入和输出端点的接口。
+///
+/// macOS 不支持查询和分离内核驱动（会输出令人困惑的错误），直接声明接口；
+/// 接口被其他进程独占时返回 `MacOsAccess`。
+#[cfg(not(target_arch = "wasm32"))]
+fn claim_bulk_interface(handle: DeviceHandle<Context>) -> Result<UsbDevice, BotError> {
+    let mut exclusive: Option<String> = None;
+
+    // 如果有内核驱动附着，先分离
+    #[cfg(not(target_os = "macos"))]
+    if let Ok(true) = handle.kernel_driver_active(0) {
+        #[cfg(feature = "logging")]
+        log::info!("Detaching kernel driver...");
+        if let Err(_e) = handle.detach_kernel_driver(0) {
+            #[cfg(feature = "logging")]
+            log::warn!("Failed to detach kernel driver");
+        }
+    }
+
+    // 获取活动配置
+    if let Ok(config) = handle.device().active_config_descriptor() {
+        #[cfg(feature = "logging")]
+        log::info!("Active configuration: {}", config.number());
+
+        // 尝试所有接口
+        for interface in config.interfaces() {
+            let interface_number = interface.number();
+            #[cfg(feature = "logging")]
+            log::info!("Trying interface {}...", interface_number);
+
+            for descriptor in interface.descriptors() {
+                // 声明接口
+                if let Err(e) = handle.claim_interface(interface_number) {
                     #[cfg(feature = "logging")]
-                    log::info!("Detaching kernel driver...");
-                    if let Err(_e) = handle.detach_kernel_driver(0) {
-                        #[cfg(feature = "logging")]
-                        log::warn!("Failed to detach kernel driver");
+                    log::warn!("Failed to claim interface {}: {}", interface_number, e);
+                    if let Some(details) = claim_error_on(interface_number, e, std::env::consts::OS)
+                    {
+                        exclusive = Some(details);
                     }
+                    continue;
                 }
 
-                // 获取活动配置
-                if let Ok(config) = device.active_config_descriptor() {
-                    #[cfg(feature = "logging")]
-                    log::info!("Active configuration: {}", config.number());
-
-                    // 尝试所有接口
-                    for interface in config.interfaces() {
-                        let interface_number = interface.number();
-                        #[cfg(feature = "logging")]
-                        log::info!("Trying interface {}...", interface_number);
-
-                        for descriptor in interface.descriptors() {
-                            // 声明接口
-                            if let Err(_e) = handle.claim_interface(interface_number) {
-                                #[cfg(feature = "logging")]
-                                log::warn!("Failed to claim interface {}", interface_number);
-                                continue;
-                            }
+                #[cfg(feature = "logging")]
+                log::info!(
+                    "Interface {} claimed, searching for bulk endpoints...",
+                    interface_number
+                );
+
+                // 查找批量端点
+                let mut write_ep = 0x01u8;
+                let mut read_ep = 0x81u8;
+                let mut found_in = false;
+                let mut found_out = false;
+
+                for endpoint in descriptor.endpoint_descriptors() {
+                    let addr = endpoint.address();
+                    let dir = endpoint.direction();
+                    let transfer_type = endpoint.transfer_type();
 
+                    #[cfg(feature = "logging")]
+                    log::debug!(
+                        "  Endpoint 0x{:02x}: dir={:?}, type={:?}",
+                        addr,
+                        dir,
+                        transfer_type
+                    );
+
+                    if transfer_type == rusb::TransferType::Bulk {
+                        if dir == rusb::Direction::In {
+                            read_ep = addr;
+                            found_in = true;
                             #[cfg(feature = "logging")]
-                            log::info!(
-                                "Interface {} claimed, searching for bulk endpoints...",
-                                interface_number
-                            );
-
-                            // 查找批量端点
-                            let mut write_ep = 0x01u8;
-                            let mut read_ep = 0x81u8;
-                            let mut found_in = false;
-                            let mut found_out = false;
-
-                            for endpoint in descriptor.endpoint_descriptors() {
-                                let addr = endpoint.address();
-                                let dir = endpoint.direction();
-                                let transfer_type = endpoint.transfer_type();
-
-                                #[cfg(feature = "logging")]
-                                log::debug!(
-                                    "  Endpoint 0x{:02x}: dir={:?}, type={:?}",
-                                    addr,
-                                    dir,
-                                    transfer_type
-                                );
-
-                                if transfer_type == rusb::TransferType::Bulk {
-                                    if dir == rusb::Direction::In {
-                                        read_ep = addr;
-                                        found_in = true;
-                                        #[cfg(feature = "logging")]
-                                        log::debug!("    Found IN bulk endpoint: 0x{:02x}", addr);
-                                    } else {
-                                        write_ep = addr;
-                                        found_out = true;
-                                        #[cfg(feature = "logging")]
-                                        log::debug!("    Found OUT bulk endpoint: 0x{:02x}", addr);
-                                    }
-                                }
-                            }
-
-                            if found_in && found_out {
-                                #[cfg(feature = "logging")]
-                                log::info!(
-                                    "Successfully opened ElectronBot: IN=0x{:02x}, OUT=0x{:02x}",
-                                    read_ep,
-                                    write_ep
-                                );
-                                return Ok(UsbDevice::new(handle, write_ep, read_ep));
-                            }
-
-                            // 如果没有批量端点，释放接口
+                            log::debug!("    Found IN bulk endpoint: 0x{:02x}", addr);
+                        } else {
+                            write_ep = addr;
+                            found_out = true;
                             #[cfg(feature = "logging")]
-                            log::warn!(
-                                "No bulk endpoints found on interface {}, releasing...",
-                                interface_number
-                            );
-                            let _ = handle.release_interface(interface_number);
+                            log::debug!("    Found OUT bulk endpoint: 0x{:02x}", addr);
                         }
                     }
                 }
 
+                if found_in && found_out {
+                    #[cfg(feature = "logging")]
+                    log::info!(
+                        "Successfully opened ElectronBot: IN=0x{:02x}, OUT=0x{:02x}",
+                        read_ep,
+                        write_ep
+                    );
+                    return Ok(UsbDevice::new(handle, write_ep, read_ep));
+                }
+
+                // 如果没有批量端点，释放接口
                 #[cfg(feature = "logging")]
-                log::error!("No suitable interface found on ElectronBot");
-                return Err("未找到合适的接口".to_string());
+                log::warn!(
+                    "No bulk endpoints found on interface {}, releasing...",
+                    interface_number
+                );
+                let _ = handle.release_interface(interface_number);
             }
         }
     }
 
     #[cfg(feature = "logging")]
-    log::error!("ElectronBot device not found");
-    Err("未找到 ElectronBot".to_string())
+    log::error!("No suitable interface found on ElectronBot");
+    match exclusive {
+        Some(details) => Err(BotError::MacOsAccess(details)),
+        None => Err(BotError::UsbError("未找到合适的接口".to_string())),
+    }
+}
+
+/// 浏览器中不能直接打开 USB 设备，总是返回错误。
+#[cfg(target_arch = "wasm32")]
+fn open_libusb(_variants: &[FirmwareVariant]) -> Result<(UsbDevice, FirmwareVariant), BotError> {
+    Err(BotError::UsbError(
+        "浏览器中不能直接打开 USB 设备，请用 WebUsb::request 连接".to_string(),
+    ))
 }
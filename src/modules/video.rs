@@ -0,0 +1,194 @@
+//! ElectronBot 库的视频播放（通过 ffmpeg 子进程解码）。
+//!
+//! 需要系统中可以找到 `ffmpeg` 和 `ffprobe` 可执行文件。ffmpeg 负责解码、
+//! 按宽高比缩放并留边到 240x240，直接输出 BGR 原始帧，
+//! 不需要链接任何 libav 库。
+
+use std::ffi::OsStr;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::time::Duration;
+
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_SIZE, FRAME_WIDTH};
+use crate::modules::image::{FitMode, ImageBuffer};
+use crate::modules::source::FrameSource;
+
+/// 无法探测帧率时使用的默认帧率。
+const DEFAULT_FPS: f64 = 30.0;
+
+/// 视频文件帧源。
+pub struct VideoSource {
+    path: PathBuf,
+    fit: FitMode,
+    looping: bool,
+    fps: f64,
+    child: Child,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl VideoSource {
+    /// 打开视频文件（保持宽高比完整显示，留边为黑色）。
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        Self::open_with(path, FitMode::Contain)
+    }
+
+    /// 按指定适配方式打开视频文件。
+    pub fn open_with<P: AsRef<Path>>(path: P, fit: FitMode) -> Result<Self, String> {
+        let path = path.as_ref().to_path_buf();
+        if !path.exists() {
+            return Err(format!("视频文件不存在: {}", path.display()));
+        }
+        let fps = probe_fps(&path).unwrap_or(DEFAULT_FPS);
+        let (child, stdout) = spawn_decoder(&path, fit)?;
+
+        #[cfg(feature = "logging")]
+        log::info!("Opened video {:?} at {:.2} fps", path, fps);
+
+        Ok(Self {
+            path,
+            fit,
+            looping: false,
+            fps,
+            child,
+            stdout,
+        })
+    }
+
+    /// 播放结束后从头循环。
+    pub fn looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// 源帧率。
+    pub fn fps(&self) -> f64 {
+        self.fps
+    }
+
+    /// 读取一帧 BGR 数据，视频结束时返回 `false`。
+    fn read_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        if read_raw_frame(&mut self.stdout, buffer) {
+            return true;
+        }
+        let _ = self.child.wait();
+        false
+    }
+
+    /// 从头重新开始解码。
+    fn restart(&mut self) -> Result<(), String> {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let (child, stdout) = spawn_decoder(&self.path, self.fit)?;
+        self.child = child;
+        self.stdout = stdout;
+        Ok(())
+    }
+}
+
+impl FrameSource for VideoSource {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        if self.read_frame(buffer) {
+            return true;
+        }
+        if self.looping && self.restart().is_ok() {
+            return self.read_frame(buffer);
+        }
+        false
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f64(1.0 / self.fps))
+    }
+}
+
+impl Drop for VideoSource {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// ffmpeg 缩放滤镜。
+fn scale_filter(fit: FitMode) -> String {
+    let (w, h) = (FRAME_WIDTH, FRAME_HEIGHT);
+    match fit {
+        FitMode::Stretch => format!("scale={w}:{h}"),
+        FitMode::Contain => format!(
+            "scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2"
+        ),
+        FitMode::Cover => {
+            format!("scale={w}:{h}:force_original_aspect_ratio=increase,crop={w}:{h}")
+        }
+        FitMode::Center => {
+            format!("crop='min(iw,{w})':'min(ih,{h})',pad={w}:{h}:(ow-iw)/2:(oh-ih)/2")
+        }
+    }
+}
+
+/// 启动 ffmpeg 解码进程，输出 240x240 BGR 原始帧到标准输出。
+fn spawn_decoder(path: &Path, fit: FitMode) -> Result<(Child, BufReader<ChildStdout>), String> {
+    let input = [OsStr::new("-i"), path.as_os_str()];
+    spawn_ffmpeg(&input, &scale_filter(fit))
+}
+
+/// 启动 ffmpeg 进程：`input` 为输入相关参数，`filter` 为视频滤镜。
+pub(crate) fn spawn_ffmpeg(
+    input: &[&OsStr],
+    filter: &str,
+) -> Result<(Child, BufReader<ChildStdout>), String> {
+    let mut child = Command::new("ffmpeg")
+        .args(["-v", "error", "-nostdin"])
+        .args(input)
+        .args(["-an", "-vf", filter])
+        .args(["-f", "rawvideo", "-pix_fmt", "bgr24", "-"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("启动 ffmpeg 失败: {}", e))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| "无法读取 ffmpeg 输出".to_string())?;
+    Ok((child, BufReader::with_capacity(FRAME_SIZE * 2, stdout)))
+}
+
+/// 从 ffmpeg 输出读取一帧 BGR 数据。
+pub(crate) fn read_raw_frame(
+    stdout: &mut BufReader<ChildStdout>,
+    buffer: &mut ImageBuffer,
+) -> bool {
+    stdout.read_exact(&mut buffer.data[..FRAME_SIZE]).is_ok()
+}
+
+/// 用 ffprobe 探测视频帧率。
+fn probe_fps(path: &Path) -> Option<f64> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "v:0"])
+        .args(["-show_entries", "stream=avg_frame_rate,r_frame_rate"])
+        .args(["-of", "default=noprint_wrappers=1:nokey=1"])
+        .arg(path)
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_rate)
+        .next()
+}
+
+/// 解析 `30000/1001` 或 `25` 形式的帧率。
+pub(crate) fn parse_rate(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let fps = match text.split_once('/') {
+        Some((num, den)) => {
+            let den: f64 = den.parse().ok()?;
+            if den == 0.0 {
+                return None;
+            }
+            num.parse::<f64>().ok()? / den
+        }
+        None => text.parse().ok()?,
+    };
+    (fps.is_finite() && fps > 0.0).then_some(fps)
+}
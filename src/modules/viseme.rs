@@ -0,0 +1,333 @@
+//! ElectronBot 库的视位（viseme）口型同步。
+//!
+//! TTS 引擎（Azure、Amazon Polly、espeak 等）合成语音时会给出带时间戳的视位或音素序列，
+//! [`VisemeTrack`] 把它们统一映射为 15 个常用口型，[`VisemePlayer`] 按时钟播放，
+//! 相邻口型之间短暂过渡，与音频同步。
+
+use std::time::Duration;
+
+use crate::modules::face::Expression;
+use crate::modules::time::Instant;
+
+/// 相邻口型之间的过渡时间。
+const BLEND: Duration = Duration::from_millis(60);
+
+/// 口型参数。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MouthShape {
+    /// 张嘴程度（0 到 1）。
+    pub open: f32,
+    /// 嘴巴宽度（0 到 1）。
+    pub width: f32,
+}
+
+impl MouthShape {
+    /// 线性插值。
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        Self {
+            open: self.open + (other.open - self.open) * t,
+            width: self.width + (other.width - self.width) * t,
+        }
+    }
+
+    /// 把口型应用到表情上。
+    pub fn apply(&self, expression: &mut Expression) {
+        expression.mouth_open = self.open;
+        expression.mouth_width = self.width;
+    }
+}
+
+/// 视位（常用的 15 个口型）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Viseme {
+    /// 静音。
+    Silence,
+    /// p、b、m（双唇闭合）。
+    PP,
+    /// f、v（上齿咬下唇）。
+    FF,
+    /// th（舌尖齿间）。
+    TH,
+    /// t、d。
+    DD,
+    /// k、g、ng。
+    KK,
+    /// ch、j、sh、zh（圆唇前突）。
+    CH,
+    /// s、z。
+    SS,
+    /// n、l。
+    NN,
+    /// r。
+    RR,
+    /// a（大开口）。
+    AA,
+    /// e。
+    E,
+    /// i（扁唇）。
+    IH,
+    /// o（圆唇）。
+    OH,
+    /// u、w（小圆唇）。
+    OU,
+}
+
+impl Viseme {
+    /// 对应的口型。
+    pub fn mouth(&self) -> MouthShape {
+        let (open, width) = match self {
+            Viseme::Silence => (0.0, 0.5),
+            Viseme::PP => (0.0, 0.4),
+            Viseme::FF => (0.1, 0.5),
+            Viseme::TH => (0.15, 0.5),
+            Viseme::DD => (0.25, 0.5),
+            Viseme::KK => (0.3, 0.5),
+            Viseme::CH => (0.25, 0.3),
+            Viseme::SS => (0.1, 0.7),
+            Viseme::NN => (0.2, 0.5),
+            Viseme::RR => (0.25, 0.3),
+            Viseme::AA => (0.8, 0.6),
+            Viseme::E => (0.5, 0.8),
+            Viseme::IH => (0.3, 0.9),
+            Viseme::OH => (0.6, 0.25),
+            Viseme::OU => (0.35, 0.15),
+        };
+        MouthShape { open, width }
+    }
+
+    /// Azure 语音服务的视位 ID（0 到 21）。
+    pub fn from_azure_id(id: u32) -> Option<Self> {
+        Some(match id {
+            0 => Viseme::Silence,
+            1 | 2 | 9 | 11 => Viseme::AA,
+            3 | 8 | 10 => Viseme::OH,
+            4 | 12 => Viseme::E,
+            5 | 13 => Viseme::RR,
+            6 => Viseme::IH,
+            7 => Viseme::OU,
+            14 => Viseme::NN,
+            15 => Viseme::SS,
+            16 => Viseme::CH,
+            17 => Viseme::TH,
+            18 => Viseme::FF,
+            19 => Viseme::DD,
+            20 => Viseme::KK,
+            21 => Viseme::PP,
+            _ => return None,
+        })
+    }
+
+    /// Amazon Polly 语音标记中的视位值（区分大小写）。
+    pub fn from_polly(value: &str) -> Option<Self> {
+        Some(match value {
+            "sil" => Viseme::Silence,
+            "p" => Viseme::PP,
+            "f" => Viseme::FF,
+            "T" => Viseme::TH,
+            "t" => Viseme::DD,
+            "k" => Viseme::KK,
+            "S" => Viseme::CH,
+            "s" => Viseme::SS,
+            "r" => Viseme::RR,
+            "a" => Viseme::AA,
+            "@" | "e" | "E" => Viseme::E,
+            "i" => Viseme::IH,
+            "o" | "O" => Viseme::OH,
+            "u" => Viseme::OU,
+            _ => return None,
+        })
+    }
+
+    /// espeak 使用的 X-SAMPA 音素（区分大小写）。
+    pub fn from_xsampa(phoneme: &str) -> Option<Self> {
+        // 去掉长音、重音等附加符号
+        let p = phoneme
+            .trim()
+            .trim_matches(|c| matches!(c, ':' | '\'' | ',' | '#'));
+        Some(match p {
+            "" | "_" => Viseme::Silence,
+            "p" | "b" | "m" => Viseme::PP,
+            "f" | "v" => Viseme::FF,
+            "T" | "D" => Viseme::TH,
+            "t" | "d" => Viseme::DD,
+            "k" | "g" | "N" => Viseme::KK,
+            "S" | "Z" | "tS" | "dZ" => Viseme::CH,
+            "s" | "z" => Viseme::SS,
+            "n" | "l" => Viseme::NN,
+            "r" | "3" | "r-" => Viseme::RR,
+            "a" | "A" | "@" | "V" | "{" | "aI" | "aU" => Viseme::AA,
+            "e" | "E" | "eI" | "h" => Viseme::E,
+            "i" | "I" | "j" | "y" => Viseme::IH,
+            "o" | "O" | "oU" | "OI" => Viseme::OH,
+            "u" | "U" | "w" => Viseme::OU,
+            _ => return None,
+        })
+    }
+
+    /// ARPAbet 音素（CMU 词典等使用，不区分大小写，可带重音数字）。
+    pub fn from_arpabet(phoneme: &str) -> Option<Self> {
+        let p = phoneme
+            .trim()
+            .trim_end_matches(|c: char| c.is_ascii_digit())
+            .to_ascii_uppercase();
+        Some(match p.as_str() {
+            "" | "SIL" | "SP" | "PAU" => Viseme::Silence,
+            "P" | "B" | "M" => Viseme::PP,
+            "F" | "V" => Viseme::FF,
+            "TH" | "DH" => Viseme::TH,
+            "T" | "D" => Viseme::DD,
+            "K" | "G" | "NG" => Viseme::KK,
+            "CH" | "JH" | "SH" | "ZH" => Viseme::CH,
+            "S" | "Z" => Viseme::SS,
+            "N" | "L" => Viseme::NN,
+            "R" | "ER" => Viseme::RR,
+            "AA" | "AE" | "AH" | "AY" | "AW" => Viseme::AA,
+            "EH" | "EY" | "HH" => Viseme::E,
+            "IH" | "IY" | "Y" => Viseme::IH,
+            "AO" | "OW" | "OY" => Viseme::OH,
+            "UW" | "UH" | "W" => Viseme::OU,
+            _ => return None,
+        })
+    }
+}
+
+/// 带时间戳的视位序列。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VisemeTrack {
+    events: Vec<(Duration, Viseme)>,
+}
+
+impl VisemeTrack {
+    /// 创建空序列。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加视位，`offset` 为相对语音开始的时间（可以乱序添加）。
+    pub fn push(&mut self, offset: Duration, viseme: Viseme) {
+        let index = self.events.partition_point(|(t, _)| *t <= offset);
+        self.events.insert(index, (offset, viseme));
+    }
+
+    /// 添加 Azure 视位事件（`audio_offset` 单位为 100 纳秒）。
+    pub fn push_azure(&mut self, audio_offset: u64, viseme_id: u32) {
+        if let Some(viseme) = Viseme::from_azure_id(viseme_id) {
+            self.push(Duration::from_nanos(audio_offset * 100), viseme);
+        }
+    }
+
+    /// 添加 espeak（X-SAMPA）音素事件，无法识别的音素被忽略。
+    pub fn push_phoneme(&mut self, offset: Duration, phoneme: &str) {
+        if let Some(viseme) = Viseme::from_xsampa(phoneme) {
+            self.push(offset, viseme);
+        }
+    }
+
+    /// 解析 Amazon Polly 的语音标记（每行一个 JSON 对象），只取 `viseme` 类型。
+    pub fn from_polly_marks(marks: &str) -> Result<Self, String> {
+        let mut track = Self::new();
+        for line in marks.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if json_field(line, "type") != Some("viseme") {
+                continue;
+            }
+            let time: u64 = json_field(line, "time")
+                .and_then(|t| t.parse().ok())
+                .ok_or_else(|| format!("语音标记缺少时间: {}", line))?;
+            let value =
+                json_field(line, "value").ok_or_else(|| format!("语音标记缺少视位: {}", line))?;
+            let viseme =
+                Viseme::from_polly(value).ok_or_else(|| format!("未知的视位: {}", value))?;
+            track.push(Duration::from_millis(time), viseme);
+        }
+        Ok(track)
+    }
+
+    /// 事件列表（按时间排序）。
+    pub fn events(&self) -> &[(Duration, Viseme)] {
+        &self.events
+    }
+
+    /// 是否为空。
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// 最后一个口型过渡完成的时间。
+    pub fn duration(&self) -> Duration {
+        self.events
+            .last()
+            .map_or(Duration::ZERO, |(t, _)| *t + BLEND)
+    }
+
+    /// `time` 时刻的口型（从上一个口型过渡到当前口型）。
+    pub fn mouth_at(&self, time: Duration) -> MouthShape {
+        let index = self.events.partition_point(|(t, _)| *t <= time);
+        if index == 0 {
+            return Viseme::Silence.mouth();
+        }
+        let (start, current) = self.events[index - 1];
+        let previous = match index {
+            1 => Viseme::Silence,
+            _ => self.events[index - 2].1,
+        };
+        let t = (time - start).as_secs_f32() / BLEND.as_secs_f32();
+        previous.mouth().lerp(&current.mouth(), t)
+    }
+}
+
+/// 视位播放器，按时钟与音频同步。
+#[derive(Debug, Clone)]
+pub struct VisemePlayer {
+    track: VisemeTrack,
+    start: Instant,
+}
+
+impl VisemePlayer {
+    /// 从现在开始播放。
+    pub fn start(track: VisemeTrack) -> Self {
+        Self::start_at(track, Instant::now())
+    }
+
+    /// 从指定时刻开始播放（例如音频实际开始输出的时刻）。
+    pub fn start_at(track: VisemeTrack, start: Instant) -> Self {
+        Self { track, start }
+    }
+
+    /// 按音频播放进度校准时钟。
+    pub fn sync(&mut self, position: Duration) {
+        self.start = Instant::now().checked_sub(position).unwrap_or(self.start);
+    }
+
+    /// 当前播放进度。
+    pub fn position(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// 视位序列。
+    pub fn track(&self) -> &VisemeTrack {
+        &self.track
+    }
+
+    /// 是否已经播放完毕。
+    pub fn is_finished(&self) -> bool {
+        self.position() >= self.track.duration()
+    }
+
+    /// 当前口型。
+    pub fn mouth(&self) -> MouthShape {
+        self.track.mouth_at(self.position())
+    }
+}
+
+/// 从单行 JSON 中取出字段值（只支持字符串和数字，足够解析语音标记）。
+fn json_field<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let pattern = format!("\"{}\"", key);
+    let rest = line[line.find(&pattern)? + pattern.len()..].trim_start();
+    let rest = rest.strip_prefix(':')?.trim_start();
+    if let Some(rest) = rest.strip_prefix('"') {
+        return Some(&rest[..rest.find('"')?]);
+    }
+    let end = rest.find([',', '}']).unwrap_or(rest.len());
+    Some(rest[..end].trim())
+}
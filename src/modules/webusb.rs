@@ -0,0 +1,399 @@
+//! ElectronBot 库的 WebUSB 传输（`wasm` feature）。
+//!
+//! 浏览器（Chrome、Edge）中通过 WebUSB 连接机器人，网页版表情编辑器等应用不需要安装本地程序。
+//! WebUSB 的收发都是异步的（返回 Promise），而 [`Transport`] 和 `ElectronBot::sync` 是同步的，
+//! 浏览器中又不能阻塞等待 Promise，所以分成两部分：
+//!
+//! - [`WebUsbTransport`] 实现 [`Transport`]，同步时只记录要收发的包，
+//!   MCU 的请求从上一次收发的结果中按顺序取出；
+//! - [`WebUsb`] 持有浏览器的设备对象，[`WebUsb::flush`] 按记录的顺序实际收发。
+//!
+//! [`WebUsb::sync`] 把两步合在一起：
+//!
+//! ```rust,ignore
+//! use electron_bot::{Color, ElectronBot, WebUsb};
+//!
+//! // 需要在用户操作（点击按钮）的回调中调用，浏览器会弹出设备选择框
+//! let usb = WebUsb::request().await?;
+//! let mut bot = ElectronBot::new();
+//! bot.connect_transport(Box::new(usb.transport()))?;
+//! bot.set_image_color(Color::Blue);
+//! usb.sync(&mut bot).await?;
+//! ```
+//!
+//! 上报的关节角度来自上一次收发，比发送的画面晚一帧；连接后第一次同步时还没有收到数据，读到的是全 0。
+//!
+//! 记录和回放的部分与浏览器无关，在其他平台上也会编译，便于测试；[`WebUsb`] 只在 `wasm32` 上可用。
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[cfg(target_arch = "wasm32")]
+use js_sys::{Array, DataView, Object, Promise, Reflect, Uint8Array};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::JsCast;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_futures::JsFuture;
+
+#[cfg(target_arch = "wasm32")]
+use crate::modules::error::BotError;
+use crate::modules::transport::Transport;
+#[cfg(target_arch = "wasm32")]
+use crate::modules::types::FirmwareVariant;
+#[cfg(target_arch = "wasm32")]
+use crate::modules::usb::{find_variant, known_variants};
+#[cfg(target_arch = "wasm32")]
+use crate::ElectronBot;
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+extern "C" {
+    /// `navigator.usb`。
+    type Usb;
+
+    #[wasm_bindgen(method, js_name = requestDevice)]
+    fn request_device(this: &Usb, options: &Object) -> Promise;
+
+    #[wasm_bindgen(method, js_name = getDevices)]
+    fn get_devices(this: &Usb) -> Promise;
+
+    /// 浏览器的 `USBDevice`。
+    #[derive(Debug, Clone)]
+    type UsbDeviceJs;
+
+    #[wasm_bindgen(method, getter, js_name = vendorId)]
+    fn vendor_id(this: &UsbDeviceJs) -> u16;
+
+    #[wasm_bindgen(method, getter, js_name = productId)]
+    fn product_id(this: &UsbDeviceJs) -> u16;
+
+    #[wasm_bindgen(method, getter)]
+    fn opened(this: &UsbDeviceJs) -> bool;
+
+    #[wasm_bindgen(method, getter)]
+    fn configuration(this: &UsbDeviceJs) -> JsValue;
+
+    #[wasm_bindgen(method)]
+    fn open(this: &UsbDeviceJs) -> Promise;
+
+    #[wasm_bindgen(method)]
+    fn close(this: &UsbDeviceJs) -> Promise;
+
+    #[wasm_bindgen(method, js_name = selectConfiguration)]
+    fn select_configuration(this: &UsbDeviceJs, value: u8) -> Promise;
+
+    #[wasm_bindgen(method, js_name = claimInterface)]
+    fn claim_interface(this: &UsbDeviceJs, number: u8) -> Promise;
+
+    #[wasm_bindgen(method, js_name = transferIn)]
+    fn transfer_in(this: &UsbDeviceJs, endpoint: u8, length: u32) -> Promise;
+
+    #[wasm_bindgen(method, js_name = transferOut)]
+    fn transfer_out(this: &UsbDeviceJs, endpoint: u8, data: &[u8]) -> Promise;
+}
+
+/// 同步时记录的一次收发。
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub(crate) enum Op {
+    /// 接收指定长度的 MCU 请求。
+    Receive(usize),
+    /// 发送一个包。
+    Transmit(Vec<u8>),
+}
+
+/// `WebUsbTransport` 和 `WebUsb` 共享的状态。
+#[derive(Debug, Default)]
+struct Pending {
+    /// 还没有实际收发的操作。
+    ops: Vec<Op>,
+    /// 上一次收发收到的 MCU 请求，按顺序交给 `receive`。
+    replies: VecDeque<Vec<u8>>,
+}
+
+/// 记录收发的传输通道，交给 `ElectronBot::connect_transport`。
+///
+/// 收发总是立即成功，实际的 USB 传输在 [`WebUsb::flush`] 中进行。
+#[derive(Debug, Clone, Default)]
+pub struct WebUsbTransport {
+    pending: Arc<Mutex<Pending>>,
+}
+
+// 记录和回放只在浏览器中由 `WebUsb` 调用，其他平台上只用于测试
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+impl WebUsbTransport {
+    fn lock(&self) -> MutexGuard<'_, Pending> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 取出还没有实际收发的操作。
+    pub(crate) fn take_ops(&self) -> Vec<Op> {
+        std::mem::take(&mut self.lock().ops)
+    }
+
+    /// 保存实际收发时收到的 MCU 请求，下一次同步按顺序取出。
+    pub(crate) fn set_replies(&self, replies: VecDeque<Vec<u8>>) {
+        self.lock().replies = replies;
+    }
+}
+
+impl Transport for WebUsbTransport {
+    fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+        self.lock().ops.push(Op::Transmit(data.to_vec()));
+        Ok(true)
+    }
+
+    fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+        let mut pending = self.lock();
+        pending.ops.push(Op::Receive(data.len()));
+        data.fill(0);
+        if let Some(reply) = pending.replies.pop_front() {
+            let len = reply.len().min(data.len());
+            data[..len].copy_from_slice(&reply[..len]);
+        }
+        Ok(data.len())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+/// 通过 WebUSB 打开的设备。
+#[derive(Debug)]
+pub struct WebUsb {
+    device: UsbDeviceJs,
+    variant: FirmwareVariant,
+    read_endpoint: u8,
+    write_endpoint: u8,
+    transport: WebUsbTransport,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl WebUsb {
+    /// 弹出浏览器的设备选择框（只列出已知固件版本的设备），打开选中的设备。
+    ///
+    /// 浏览器要求在用户操作（点击、按键）的回调中调用。
+    pub async fn request() -> Result<Self, String> {
+        Self::request_matching(&known_variants()).await
+    }
+
+    /// 弹出设备选择框，只列出 `variants` 中的 USB ID。
+    pub async fn request_matching(variants: &[FirmwareVariant]) -> Result<Self, String> {
+        let filters = Array::new();
+        for variant in variants {
+            let filter = Object::new();
+            set(&filter, "vendorId", &variant.vid.into())?;
+            set(&filter, "productId", &variant.pid.into())?;
+            filters.push(&filter);
+        }
+        let options = Object::new();
+        set(&options, "filters", &filters)?;
+        let device = wait(usb()?.request_device(&options), "选择设备").await?;
+        Self::from_device(device).await
+    }
+
+    /// 打开之前已经授权过的设备（不弹出选择框），没有时返回 `None`。
+    pub async fn paired() -> Result<Option<Self>, String> {
+        let variants = known_variants();
+        let devices: Array = wait(usb()?.get_devices(), "获取已授权的设备")
+            .await?
+            .unchecked_into();
+        for device in devices.iter() {
+            let usb_device: &UsbDeviceJs = device.unchecked_ref();
+            if find_variant(&variants, usb_device.vendor_id(), usb_device.product_id()).is_some() {
+                return Self::from_device(device).await.map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// 打开 JavaScript 中取得的 `USBDevice` 并声明带批量端点的接口。
+    pub async fn from_device(device: JsValue) -> Result<Self, String> {
+        let device: UsbDeviceJs = device.unchecked_into();
+        let (vid, pid) = (device.vendor_id(), device.product_id());
+        let variant = find_variant(&known_variants(), vid, pid)
+            .cloned()
+            .unwrap_or_else(|| FirmwareVariant::new("custom", vid, pid));
+        #[cfg(feature = "logging")]
+        log::info!("Opening WebUSB device {:04x}:{:04x}", vid, pid);
+
+        if !device.opened() {
+            wait(device.open(), "打开设备").await?;
+        }
+        if device.configuration().is_null() {
+            wait(device.select_configuration(1), "选择配置").await?;
+        }
+        let (interface, read_endpoint, write_endpoint) =
+            find_bulk_interface(&device.configuration())?;
+        wait(device.claim_interface(interface), "声明接口").await?;
+        #[cfg(feature = "logging")]
+        log::info!(
+            "WebUSB interface {} claimed: IN={}, OUT={}",
+            interface,
+            read_endpoint,
+            write_endpoint
+        );
+
+        Ok(Self {
+            device,
+            variant,
+            read_endpoint,
+            write_endpoint,
+            transport: WebUsbTransport::default(),
+        })
+    }
+
+    /// 设备匹配的固件版本（不在已知列表中时名称为 `custom`）。
+    pub fn variant(&self) -> &FirmwareVariant {
+        &self.variant
+    }
+
+    /// 记录收发的传输通道（克隆共享同一份记录）。
+    pub fn transport(&self) -> WebUsbTransport {
+        self.transport.clone()
+    }
+
+    /// 按记录的顺序实际收发，收到的 MCU 请求留给下一次同步。
+    ///
+    /// 失败时丢弃剩余的记录，下一次同步重新发送完整的一帧。
+    pub async fn flush(&self) -> Result<(), String> {
+        let ops = self.transport.take_ops();
+        let mut replies = VecDeque::new();
+        let result = self.run(ops, &mut replies).await;
+        self.transport.set_replies(replies);
+        result
+    }
+
+    async fn run(&self, ops: Vec<Op>, replies: &mut VecDeque<Vec<u8>>) -> Result<(), String> {
+        for op in ops {
+            match op {
+                Op::Receive(len) => {
+                    let result = wait(
+                        self.device.transfer_in(self.read_endpoint, len as u32),
+                        "接收",
+                    )
+                    .await?;
+                    check_status(&result, "接收")?;
+                    let view: DataView = get(&result, "data")?.unchecked_into();
+                    let bytes = Uint8Array::new_with_byte_offset_and_length(
+                        &view.buffer(),
+                        view.byte_offset() as u32,
+                        view.byte_length() as u32,
+                    );
+                    replies.push_back(bytes.to_vec());
+                }
+                Op::Transmit(data) => {
+                    self.transmit(&data).await?;
+                    // 与 libusb 路径相同，长度为 512 的整数倍时补发零长度包
+                    if data.len().is_multiple_of(512) {
+                        self.transmit(&[]).await?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn transmit(&self, data: &[u8]) -> Result<(), String> {
+        let result = wait(self.device.transfer_out(self.write_endpoint, data), "发送").await?;
+        check_status(&result, "发送")
+    }
+
+    /// 同步一次：`bot.sync()` 记录这一帧的包，再实际收发。
+    pub async fn sync(&self, bot: &mut ElectronBot) -> Result<bool, BotError> {
+        let synced = bot.sync()?;
+        self.flush().await.map_err(BotError::UsbError)?;
+        Ok(synced)
+    }
+
+    /// 关闭设备。
+    pub async fn close(self) -> Result<(), String> {
+        wait(self.device.close(), "关闭设备").await.map(|_| ())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+/// `navigator.usb`（页面和 Worker 中都可用）。
+fn usb() -> Result<Usb, String> {
+    let usb = get(&get(&js_sys::global(), "navigator")?, "usb")?;
+    if usb.is_undefined() {
+        return Err(
+            "浏览器不支持 WebUSB（需要 Chrome 或 Edge，页面通过 HTTPS 或 localhost 提供）"
+                .to_string(),
+        );
+    }
+    Ok(usb.unchecked_into())
+}
+
+#[cfg(target_arch = "wasm32")]
+/// 在配置中查找同时有批量输入和输出端点的接口，返回（接口号, 输入端点号, 输出端点号）。
+fn find_bulk_interface(configuration: &JsValue) -> Result<(u8, u8, u8), String> {
+    if configuration.is_null() || configuration.is_undefined() {
+        return Err("设备没有活动的配置".to_string());
+    }
+    let interfaces: Array = get(configuration, "interfaces")?.unchecked_into();
+    for interface in interfaces.iter() {
+        let interface_number = to_u8(&get(&interface, "interfaceNumber")?);
+        let endpoints: Array = get(&get(&interface, "alternate")?, "endpoints")?.unchecked_into();
+        let (mut read, mut write) = (None, None);
+        for endpoint in endpoints.iter() {
+            if get(&endpoint, "type")?.as_string().as_deref() != Some("bulk") {
+                continue;
+            }
+            let endpoint_number = to_u8(&get(&endpoint, "endpointNumber")?);
+            match get(&endpoint, "direction")?.as_string().as_deref() {
+                Some("in") => read = Some(endpoint_number),
+                Some("out") => write = Some(endpoint_number),
+                _ => {}
+            }
+        }
+        if let (Some(read), Some(write)) = (read, write) {
+            return Ok((interface_number, read, write));
+        }
+    }
+    Err("未找到合适的接口".to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+/// 检查 `transferIn` / `transferOut` 结果的状态。
+fn check_status(result: &JsValue, what: &str) -> Result<(), String> {
+    match get(result, "status")?.as_string().as_deref() {
+        Some("ok") => Ok(()),
+        Some(status) => Err(format!("{}失败: {}", what, status)),
+        None => Err(format!("{}失败: 没有状态", what)),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+/// 等待 Promise，失败时把 JavaScript 异常转为错误信息。
+async fn wait(promise: Promise, what: &str) -> Result<JsValue, String> {
+    JsFuture::from(promise)
+        .await
+        .map_err(|e| format!("{}失败: {}", what, describe(&e)))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn get(target: &JsValue, key: &str) -> Result<JsValue, String> {
+    Reflect::get(target, &JsValue::from_str(key)).map_err(|e| describe(&e))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn set(target: &Object, key: &str, value: &JsValue) -> Result<(), String> {
+    Reflect::set(target, &JsValue::from_str(key), value)
+        .map(|_| ())
+        .map_err(|e| describe(&e))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn to_u8(value: &JsValue) -> u8 {
+    value.as_f64().unwrap_or(0.0) as u8
+}
+
+#[cfg(target_arch = "wasm32")]
+/// JavaScript 异常的说明文字。
+fn describe(value: &JsValue) -> String {
+    match value.dyn_ref::<js_sys::Error>() {
+        Some(error) => String::from(error.message()),
+        None => value.as_string().unwrap_or_else(|| format!("{:?}", value)),
+    }
+}
@@ -0,0 +1,653 @@
+//! ElectronBot 库的小部件。
+//!
+//! [`Clock`] 按本地时间绘制指针或数字表盘，可以直接作为帧源播放，
+//! 配合 [`InactivitySwitch`](crate::InactivitySwitch) 在无操作一段时间后作为桌面时钟显示。
+//!
+//! [`Gauge`]、[`ProgressBar`] 和 [`Sparkline`] 实现 [`Widget`]，绘制到缓冲区的指定区域，
+//! 可以组合成 CPU 监视器或传感器仪表盘。
+
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, Timelike};
+
+use crate::modules::color::Gradient;
+use crate::modules::constants::{FRAME_HEIGHT, FRAME_WIDTH};
+use crate::modules::draw::{Point, Rect};
+use crate::modules::image::ImageBuffer;
+use crate::modules::source::FrameSource;
+use crate::modules::types::Color;
+
+/// 时钟的刷新间隔。
+const CLOCK_INTERVAL: Duration = Duration::from_millis(250);
+/// 表盘半径。
+const DIAL_RADIUS: usize = 112;
+
+/// 七段数码管的段（a 到 g 依次为上、右上、右下、下、左下、左上、中）。
+const SEGMENTS: [u8; 10] = [0x3F, 0x06, 0x5B, 0x4F, 0x66, 0x6D, 0x7D, 0x07, 0x7F, 0x6F];
+
+/// 时钟显示的时间。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockTime {
+    /// 年。
+    pub year: i32,
+    /// 月（1 到 12）。
+    pub month: u32,
+    /// 日（1 到 31）。
+    pub day: u32,
+    /// 星期（0 为星期一，6 为星期日）。
+    pub weekday: u32,
+    /// 时（0 到 23）。
+    pub hour: u32,
+    /// 分。
+    pub minute: u32,
+    /// 秒。
+    pub second: u32,
+}
+
+impl ClockTime {
+    /// 指定日期和时间，日期或时间无效时返回 `None`。
+    pub fn new(
+        year: i32,
+        month: u32,
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: u32,
+    ) -> Option<Self> {
+        let time = NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(hour, minute, second)?;
+        Some(Self::from_naive(&time))
+    }
+
+    /// 当前本地时间。
+    pub fn now() -> Self {
+        Self::from_naive(&Local::now().naive_local())
+    }
+
+    fn from_naive(time: &NaiveDateTime) -> Self {
+        Self {
+            year: time.year(),
+            month: time.month(),
+            day: time.day(),
+            weekday: time.weekday().num_days_from_monday(),
+            hour: time.hour(),
+            minute: time.minute(),
+            second: time.second(),
+        }
+    }
+}
+
+/// 表盘类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClockKind {
+    /// 指针表盘（默认）。
+    #[default]
+    Analog,
+    /// 数字表盘。
+    Digital,
+}
+
+/// 时钟样式。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockStyle {
+    /// 表盘类型。
+    pub kind: ClockKind,
+    /// 背景色。
+    pub background: Color,
+    /// 表盘颜色（数字表盘中为未选中的星期标记）。
+    pub dial: Color,
+    /// 指针、刻度和数字颜色。
+    pub foreground: Color,
+    /// 秒针和强调色。
+    pub accent: Color,
+    /// 是否显示秒。
+    pub show_seconds: bool,
+    /// 是否显示日期。
+    pub show_date: bool,
+    /// 数字表盘是否使用 24 小时制。
+    pub hour24: bool,
+}
+
+impl Default for ClockStyle {
+    fn default() -> Self {
+        Self {
+            kind: ClockKind::Analog,
+            background: Color::Black,
+            dial: Color::Custom(40, 40, 48),
+            foreground: Color::White,
+            accent: Color::Custom(255, 96, 0),
+            show_seconds: true,
+            show_date: true,
+            hour24: true,
+        }
+    }
+}
+
+/// 时钟帧源。
+#[derive(Debug, Clone, Default)]
+pub struct Clock {
+    style: ClockStyle,
+}
+
+impl Clock {
+    /// 创建指针时钟。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建数字时钟。
+    pub fn digital() -> Self {
+        Self::new().style(ClockStyle {
+            kind: ClockKind::Digital,
+            ..ClockStyle::default()
+        })
+    }
+
+    /// 设置样式。
+    pub fn style(mut self, style: ClockStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// 当前样式。
+    pub fn clock_style(&self) -> &ClockStyle {
+        &self.style
+    }
+
+    /// 绘制指定时间。
+    pub fn render_at(&self, buffer: &mut ImageBuffer, time: &ClockTime) {
+        buffer.clear(self.style.background);
+        match self.style.kind {
+            ClockKind::Analog => self.render_analog(buffer, time),
+            ClockKind::Digital => self.render_digital(buffer, time),
+        }
+    }
+
+    fn render_analog(&self, buffer: &mut ImageBuffer, time: &ClockTime) {
+        let style = &self.style;
+        let center = (FRAME_WIDTH as i32 / 2, FRAME_HEIGHT as i32 / 2);
+        // 角度从 12 点方向顺时针计算
+        let point = |angle: f32, r: f32| {
+            let (sin, cos) = angle.sin_cos();
+            (
+                center.0 + (sin * r).round() as i32,
+                center.1 - (cos * r).round() as i32,
+            )
+        };
+
+        buffer.fill_ellipse(center, DIAL_RADIUS, DIAL_RADIUS, style.dial);
+        for i in 0..12 {
+            let angle = i as f32 * PI / 6.0;
+            let (inner, width) = if i % 3 == 0 { (86.0, 4) } else { (94.0, 2) };
+            buffer.draw_line(
+                point(angle, inner),
+                point(angle, 106.0),
+                width,
+                style.foreground,
+            );
+        }
+
+        if style.show_date {
+            let label = format!("{:02}", time.day);
+            let width = segment_text_width(&label, 18);
+            buffer.fill_rect(158, 108, width + 6, 24, style.background);
+            buffer.draw_segment_text(&label, 161, 111, 18, style.foreground);
+        }
+
+        let second = time.second as f32;
+        let minute = time.minute as f32 + second / 60.0;
+        let hour = (time.hour % 12) as f32 + minute / 60.0;
+        let hand = |buffer: &mut ImageBuffer, angle: f32, length: f32, width, color| {
+            buffer.draw_line(point(angle + PI, 12.0), point(angle, length), width, color);
+        };
+        hand(buffer, hour * PI / 6.0, 56.0, 7, style.foreground);
+        hand(buffer, minute * PI / 30.0, 84.0, 5, style.foreground);
+        if style.show_seconds {
+            hand(buffer, second * PI / 30.0, 98.0, 2, style.accent);
+        }
+        buffer.fill_ellipse(center, 6, 6, style.accent);
+    }
+
+    fn render_digital(&self, buffer: &mut ImageBuffer, time: &ClockTime) {
+        let style = &self.style;
+        let center = (FRAME_WIDTH as i32 / 2, FRAME_HEIGHT as i32 / 2);
+
+        let hour = match (style.hour24, time.hour % 12) {
+            (true, _) => time.hour,
+            (false, 0) => 12,
+            (false, h) => h,
+        };
+        let label = if style.hour24 {
+            format!("{:02}:{:02}", hour, time.minute)
+        } else {
+            format!("{:>2}:{:02}", hour, time.minute)
+        };
+        let width = segment_text_width(&label, 64);
+        let left = (FRAME_WIDTH - width) / 2;
+        buffer.draw_segment_text(&label, left, 88, 64, style.foreground);
+        // 12 小时制下午在时间右上方显示一个点
+        if !style.hour24 && time.hour >= 12 {
+            buffer.fill_ellipse(((left + width) as i32 - 4, 76), 4, 4, style.accent);
+        }
+
+        if style.show_seconds && time.second > 0 {
+            let end = -90.0 + time.second as f32 * 6.0;
+            buffer.draw_arc(center, DIAL_RADIUS, -90.0, end, 4, style.accent);
+        }
+
+        if style.show_date {
+            for day in 0..7 {
+                let color = if day == time.weekday {
+                    style.accent
+                } else {
+                    style.dial
+                };
+                buffer.fill_ellipse((center.0 + (day as i32 - 3) * 16, 60), 4, 4, color);
+            }
+            let label = format!("{:02}-{:02}", time.month, time.day);
+            let width = segment_text_width(&label, 24);
+            buffer.draw_segment_text(&label, (FRAME_WIDTH - width) / 2, 172, 24, style.foreground);
+        }
+    }
+}
+
+impl FrameSource for Clock {
+    fn next_frame(&mut self, buffer: &mut ImageBuffer) -> bool {
+        self.render_at(buffer, &ClockTime::now());
+        true
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        Some(CLOCK_INTERVAL)
+    }
+}
+
+/// 可以绘制到缓冲区指定区域的小部件。
+pub trait Widget {
+    /// 在 `rect` 区域内绘制。
+    fn draw(&self, buffer: &mut ImageBuffer, rect: Rect);
+}
+
+impl ImageBuffer {
+    /// 在指定区域绘制小部件。
+    pub fn draw_widget<W: Widget + ?Sized>(&mut self, widget: &W, rect: Rect) {
+        if !rect.is_empty() {
+            widget.draw(self, rect);
+        }
+    }
+}
+
+/// 数值在 `min` 到 `max` 之间的比例（0 到 1）。
+fn fraction(value: f32, min: f32, max: f32) -> f32 {
+    if max > min {
+        ((value - min) / (max - min)).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// 圆形仪表：沿 270° 圆弧显示数值，中间显示读数。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gauge {
+    min: f32,
+    max: f32,
+    value: f32,
+    width: usize,
+    track: Color,
+    fill: Color,
+    gradient: Option<Gradient>,
+    label: Option<usize>,
+}
+
+impl Gauge {
+    /// 创建量程为 `min` 到 `max` 的仪表，默认显示整数读数。
+    pub fn new(min: f32, max: f32) -> Self {
+        Self {
+            min,
+            max,
+            value: min,
+            width: 10,
+            track: Color::Custom(40, 40, 48),
+            fill: Color::Cyan,
+            gradient: None,
+            label: Some(0),
+        }
+    }
+
+    /// 设置圆弧宽度。
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width.max(1);
+        self
+    }
+
+    /// 设置底色和填充色。
+    pub fn colors(mut self, track: Color, fill: Color) -> Self {
+        self.track = track;
+        self.fill = fill;
+        self
+    }
+
+    /// 按数值比例从渐变中取填充色（例如绿到红）。
+    pub fn gradient(mut self, gradient: Gradient) -> Self {
+        self.gradient = Some(gradient);
+        self
+    }
+
+    /// 设置读数的小数位数，`None` 表示不显示读数。
+    pub fn label(mut self, decimals: Option<usize>) -> Self {
+        self.label = decimals;
+        self
+    }
+
+    /// 设置当前数值。
+    pub fn set_value(&mut self, value: f32) {
+        self.value = value;
+    }
+
+    /// 当前数值。
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+}
+
+impl Widget for Gauge {
+    fn draw(&self, buffer: &mut ImageBuffer, rect: Rect) {
+        const START: f32 = 135.0;
+        const SWEEP: f32 = 270.0;
+        let size = rect.width.min(rect.height);
+        let radius = (size / 2).saturating_sub(self.width.div_ceil(2)).max(1);
+        let center = (
+            rect.x + rect.width as i32 / 2,
+            rect.y + rect.height as i32 / 2,
+        );
+        let t = fraction(self.value, self.min, self.max);
+        let fill = self.gradient.as_ref().map_or(self.fill, |g| g.at(t));
+
+        buffer.draw_arc(center, radius, START, START + SWEEP, self.width, self.track);
+        if t > 0.0 {
+            buffer.draw_arc(center, radius, START, START + SWEEP * t, self.width, fill);
+        }
+        if let Some(decimals) = self.label {
+            let text = format!("{:.*}", decimals, self.value);
+            let height = (radius * 2 / 3).max(9);
+            let width = segment_text_width(&text, height);
+            let x = (center.0 - width as i32 / 2).max(0) as usize;
+            let y = (center.1 - height as i32 / 2).max(0) as usize;
+            buffer.draw_segment_text(&text, x, y, height, fill);
+        }
+    }
+}
+
+/// 水平进度条。
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressBar {
+    progress: f32,
+    track: Color,
+    fill: Color,
+    gradient: Option<Gradient>,
+}
+
+impl Default for ProgressBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressBar {
+    /// 创建进度条。
+    pub fn new() -> Self {
+        Self {
+            progress: 0.0,
+            track: Color::Custom(40, 40, 48),
+            fill: Color::Green,
+            gradient: None,
+        }
+    }
+
+    /// 设置底色和填充色。
+    pub fn colors(mut self, track: Color, fill: Color) -> Self {
+        self.track = track;
+        self.fill = fill;
+        self
+    }
+
+    /// 按进度从渐变中取填充色。
+    pub fn gradient(mut self, gradient: Gradient) -> Self {
+        self.gradient = Some(gradient);
+        self
+    }
+
+    /// 设置进度（0 到 1）。
+    pub fn set_progress(&mut self, progress: f32) {
+        self.progress = progress.clamp(0.0, 1.0);
+    }
+
+    /// 当前进度。
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+}
+
+impl Widget for ProgressBar {
+    fn draw(&self, buffer: &mut ImageBuffer, rect: Rect) {
+        let radius = rect.height / 2;
+        buffer.fill_rounded_rect(rect, radius, self.track);
+        let width = (rect.width as f32 * self.progress).round() as usize;
+        if width > 0 {
+            let fill = self
+                .gradient
+                .as_ref()
+                .map_or(self.fill, |g| g.at(self.progress));
+            buffer.fill_rounded_rect(Rect { width, ..rect }, radius, fill);
+        }
+    }
+}
+
+/// 折线图，保留最近的一段数据（滚动窗口）。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sparkline {
+    data: VecDeque<f32>,
+    capacity: usize,
+    range: Option<(f32, f32)>,
+    line: Color,
+    width: usize,
+    area: Option<Color>,
+    grid: Option<Color>,
+}
+
+impl Sparkline {
+    /// 创建折线图，最多保留 `capacity` 个数据点，纵轴按数据自动缩放。
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            data: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(2),
+            range: None,
+            line: Color::Cyan,
+            width: 2,
+            area: None,
+            grid: None,
+        }
+    }
+
+    /// 固定纵轴范围。
+    pub fn range(mut self, min: f32, max: f32) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    /// 设置线条颜色和宽度。
+    pub fn line(mut self, color: Color, width: usize) -> Self {
+        self.line = color;
+        self.width = width.max(1);
+        self
+    }
+
+    /// 填充折线下方区域。
+    pub fn area(mut self, color: Color) -> Self {
+        self.area = Some(color);
+        self
+    }
+
+    /// 绘制边框和 25%、50%、75% 处的网格线。
+    pub fn grid(mut self, color: Color) -> Self {
+        self.grid = Some(color);
+        self
+    }
+
+    /// 添加数据点，超出窗口时丢弃最旧的点。
+    pub fn push(&mut self, value: f32) {
+        if self.data.len() == self.capacity {
+            self.data.pop_front();
+        }
+        self.data.push_back(value);
+    }
+
+    /// 窗口内的数据（从旧到新）。
+    pub fn data(&self) -> impl Iterator<Item = f32> + '_ {
+        self.data.iter().copied()
+    }
+
+    /// 清空数据。
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// 纵轴范围：固定范围或窗口内数据的最小、最大值。
+    pub fn bounds(&self) -> (f32, f32) {
+        self.range.unwrap_or_else(|| {
+            self.data
+                .iter()
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &v| {
+                    (lo.min(v), hi.max(v))
+                })
+        })
+    }
+}
+
+impl Widget for Sparkline {
+    fn draw(&self, buffer: &mut ImageBuffer, rect: Rect) {
+        let (w, h) = (rect.width as i32 - 1, rect.height as i32 - 1);
+        if let Some(grid) = self.grid {
+            buffer.draw_rounded_rect(rect, 0, 1, grid);
+            for i in 1..4 {
+                let y = rect.y + h * i / 4;
+                buffer.draw_line((rect.x, y), (rect.x + w, y), 1, grid);
+            }
+        }
+        if self.data.is_empty() {
+            return;
+        }
+
+        let (min, max) = self.bounds();
+        // 数据恒定时画在中间
+        let (min, max) = if max > min {
+            (min, max)
+        } else {
+            (min - 1.0, min + 1.0)
+        };
+        // 最新的点在右侧，数据不满一个窗口时从右侧开始
+        let step = w as f32 / (self.capacity - 1) as f32;
+        let offset = self.capacity - self.data.len();
+        let points: Vec<Point> = self
+            .data
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| {
+                let x = rect.x + ((i + offset) as f32 * step).round() as i32;
+                let y = rect.y + h - (fraction(v, min, max) * h as f32).round() as i32;
+                (x, y)
+            })
+            .collect();
+
+        if let Some(area) = self.area {
+            let bottom = rect.y + h;
+            let mut polygon = points.clone();
+            polygon.push((points[points.len() - 1].0, bottom));
+            polygon.push((points[0].0, bottom));
+            buffer.fill_polygon(&polygon, area);
+        }
+        buffer.draw_polyline(&points, self.width, self.line);
+    }
+}
+
+/// 七段数码管字符的尺寸：（宽度, 线宽, 字间距）。
+fn segment_metrics(height: usize) -> (usize, usize, usize) {
+    let thickness = (height / 9).max(1);
+    (height * 5 / 9, thickness, thickness.max(2))
+}
+
+/// 七段数码管文字的宽度。
+fn segment_text_width(text: &str, height: usize) -> usize {
+    let (width, thickness, gap) = segment_metrics(height);
+    let total: usize = text
+        .chars()
+        .map(|c| match c {
+            ':' | '.' => thickness * 2,
+            _ => width,
+        })
+        .map(|w| w + gap)
+        .sum();
+    total.saturating_sub(gap)
+}
+
+impl ImageBuffer {
+    /// 用七段数码管样式绘制数字（支持 `0-9`、`:`、`.`、`-` 和空格），返回绘制的宽度。
+    ///
+    /// 不依赖字体，适合时钟和数值显示。
+    pub fn draw_segment_text(
+        &mut self,
+        text: &str,
+        x: usize,
+        y: usize,
+        height: usize,
+        color: Color,
+    ) -> usize {
+        let (width, t, gap) = segment_metrics(height);
+        let half = height / 2;
+        let mut cx = x;
+        for c in text.chars() {
+            let advance = match c {
+                ':' => {
+                    self.fill_rect(cx, y + height / 3 - t / 2, t * 2, t * 2, color);
+                    self.fill_rect(cx, y + height * 2 / 3 - t / 2, t * 2, t * 2, color);
+                    t * 2
+                }
+                '.' => {
+                    self.fill_rect(cx, y + height - t * 2, t * 2, t * 2, color);
+                    t * 2
+                }
+                _ => {
+                    let bits = match c {
+                        '0'..='9' => SEGMENTS[c as usize - '0' as usize],
+                        '-' => 0x40,
+                        _ => 0,
+                    };
+                    let inner = width.saturating_sub(2 * t);
+                    let rects = [
+                        (cx + t, y, inner, t),
+                        (cx + width - t, y + t, t, half.saturating_sub(t)),
+                        (
+                            cx + width - t,
+                            y + half,
+                            t,
+                            (height - half).saturating_sub(t),
+                        ),
+                        (cx + t, y + height - t, inner, t),
+                        (cx, y + half, t, (height - half).saturating_sub(t)),
+                        (cx, y + t, t, half.saturating_sub(t)),
+                        (cx + t, y + half - t / 2, inner, t),
+                    ];
+                    for (i, &(rx, ry, rw, rh)) in rects.iter().enumerate() {
+                        if bits & (1 << i) != 0 {
+                            self.fill_rect(rx, ry, rw, rh, color);
+                        }
+                    }
+                    width
+                }
+            };
+            cx += advance + gap;
+        }
+        (cx - x).saturating_sub(gap)
+    }
+}
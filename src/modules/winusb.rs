@@ -0,0 +1,284 @@
+//! ElectronBot 库的 WinUSB 传输（`winusb` feature，仅 Windows）。
+//!
+//! 设备已经绑定 WinUSB 驱动（固件提供 Microsoft OS 描述符，或者之前用 Zadig 安装过）时，
+//! 直接通过系统的 `winusb.dll` 收发，不需要 libusb。[`UsbBackend::Auto`](crate::modules::usb::UsbBackend)
+//! 在 libusb 打开失败后自动尝试这里，也可以用 `ElectronBot::set_usb_backend` 只使用 WinUSB。
+
+use std::time::Duration;
+
+use windows::core::PCWSTR;
+use windows::Win32::Devices::DeviceAndDriverInstallation::{
+    SetupDiDestroyDeviceInfoList, SetupDiEnumDeviceInterfaces, SetupDiGetClassDevsW,
+    SetupDiGetDeviceInterfaceDetailW, DIGCF_DEVICEINTERFACE, DIGCF_PRESENT,
+    SP_DEVICE_INTERFACE_DATA, SP_DEVICE_INTERFACE_DETAIL_DATA_W,
+};
+use windows::Win32::Devices::Usb::{
+    UsbdPipeTypeBulk, WinUsb_Free, WinUsb_Initialize, WinUsb_QueryInterfaceSettings,
+    WinUsb_QueryPipe, WinUsb_ReadPipe, WinUsb_SetPipePolicy, WinUsb_WritePipe,
+    GUID_DEVINTERFACE_USB_DEVICE, PIPE_TRANSFER_TIMEOUT, USB_INTERFACE_DESCRIPTOR,
+    WINUSB_INTERFACE_HANDLE, WINUSB_PIPE_INFORMATION,
+};
+use windows::Win32::Foundation::{CloseHandle, HANDLE};
+use windows::Win32::Storage::FileSystem::{
+    CreateFileW, FILE_ATTRIBUTE_NORMAL, FILE_FLAG_OVERLAPPED, FILE_GENERIC_READ,
+    FILE_GENERIC_WRITE, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+};
+
+use crate::modules::constants::TIMEOUT_MS;
+use crate::modules::transport::Transport;
+use crate::modules::types::FirmwareVariant;
+use crate::modules::usb::{find_variant, parse_device_path};
+
+/// 通过 WinUSB 打开的设备。
+pub struct WinUsbDevice {
+    file: HANDLE,
+    interface: WINUSB_INTERFACE_HANDLE,
+    write_pipe: u8,
+    read_pipe: u8,
+    timeout: Duration,
+    path: String,
+}
+
+// WinUSB 句柄可以在任意线程使用，`WinUsbDevice` 同一时间只被一个持有者访问
+unsafe impl Send for WinUsbDevice {}
+
+impl WinUsbDevice {
+    /// 打开第一个匹配 `variants` 中任一 USB ID 并且绑定了 WinUSB 的设备，返回设备和匹配的固件版本。
+    pub fn open_matching(variants: &[FirmwareVariant]) -> Result<(Self, FirmwareVariant), String> {
+        let mut last_error = None;
+        for path in device_paths()? {
+            let Some((vid, pid)) = parse_device_path(&path) else {
+                continue;
+            };
+            let Some(variant) = find_variant(variants, vid, pid) else {
+                continue;
+            };
+            #[cfg(feature = "logging")]
+            log::info!("Found {} device at {}", variant.name, path);
+            match Self::open(&path) {
+                Ok(device) => return Ok((device, variant.clone())),
+                Err(e) => {
+                    #[cfg(feature = "logging")]
+                    log::warn!("WinUSB open failed: {}", e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| "未找到 ElectronBot".to_string()))
+    }
+
+    /// 打开设备接口路径（`\\?\usb#vid_1001&pid_8023#...`），查找批量端点。
+    pub fn open(path: &str) -> Result<Self, String> {
+        let wide: Vec<u16> = path.encode_utf16().chain(Some(0)).collect();
+        // WinUSB 要求以重叠方式打开，不传 OVERLAPPED 时收发仍然是同步的
+        let file = unsafe {
+            CreateFileW(
+                PCWSTR(wide.as_ptr()),
+                (FILE_GENERIC_READ | FILE_GENERIC_WRITE).0,
+                FILE_SHARE_READ | FILE_SHARE_WRITE,
+                None,
+                OPEN_EXISTING,
+                FILE_ATTRIBUTE_NORMAL | FILE_FLAG_OVERLAPPED,
+                None,
+            )
+        }
+        .map_err(|e| format!("打开设备失败: {}", e))?;
+
+        let mut interface = WINUSB_INTERFACE_HANDLE::default();
+        if let Err(e) = unsafe { WinUsb_Initialize(file, &mut interface) } {
+            unsafe {
+                let _ = CloseHandle(file);
+            }
+            return Err(format!("设备没有绑定 WinUSB 驱动: {}", e));
+        }
+        // 之后出错时由 drop 释放句柄
+        let mut device = Self {
+            file,
+            interface,
+            write_pipe: 0,
+            read_pipe: 0,
+            timeout: Duration::from_millis(TIMEOUT_MS),
+            path: path.to_string(),
+        };
+
+        let mut descriptor = USB_INTERFACE_DESCRIPTOR::default();
+        unsafe { WinUsb_QueryInterfaceSettings(interface, 0, &mut descriptor) }
+            .map_err(|e| format!("读取接口描述符失败: {}", e))?;
+        for index in 0..descriptor.bNumEndpoints {
+            let mut pipe = WINUSB_PIPE_INFORMATION::default();
+            if unsafe { WinUsb_QueryPipe(interface, 0, index, &mut pipe) }.is_err()
+                || pipe.PipeType != UsbdPipeTypeBulk
+            {
+                continue;
+            }
+            if pipe.PipeId & 0x80 != 0 {
+                device.read_pipe = pipe.PipeId;
+            } else {
+                device.write_pipe = pipe.PipeId;
+            }
+        }
+        if device.read_pipe == 0 || device.write_pipe == 0 {
+            return Err("未找到合适的接口".to_string());
+        }
+        device.apply_timeout();
+        #[cfg(feature = "logging")]
+        log::info!(
+            "Opened ElectronBot via WinUSB: IN=0x{:02x}, OUT=0x{:02x}",
+            device.read_pipe,
+            device.write_pipe
+        );
+        Ok(device)
+    }
+
+    /// 设备接口路径。
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// 通过批量传输发送数据，长度为 512 的整数倍时补发零长度包。
+    pub fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+        if self.write(data)? != data.len() {
+            return Err("发送不完整".to_string());
+        }
+        if data.len().is_multiple_of(512) {
+            self.write(&[])?;
+        }
+        Ok(true)
+    }
+
+    /// 通过批量传输接收数据。
+    pub fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+        let mut read = 0u32;
+        unsafe {
+            WinUsb_ReadPipe(
+                self.interface,
+                self.read_pipe,
+                Some(data),
+                Some(&mut read),
+                None,
+            )
+        }
+        .map_err(|e| format!("接收失败: {}", e))?;
+        Ok(read as usize)
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<usize, String> {
+        let mut written = 0u32;
+        unsafe {
+            WinUsb_WritePipe(
+                self.interface,
+                self.write_pipe,
+                data,
+                Some(&mut written),
+                None,
+            )
+        }
+        .map_err(|e| format!("发送失败: {}", e))?;
+        Ok(written as usize)
+    }
+
+    /// 把超时写入两个端点的管道策略。
+    fn apply_timeout(&mut self) {
+        let millis = self.timeout.as_millis().min(u32::MAX as u128) as u32;
+        for pipe in [self.read_pipe, self.write_pipe] {
+            let _ = unsafe {
+                WinUsb_SetPipePolicy(
+                    self.interface,
+                    pipe,
+                    PIPE_TRANSFER_TIMEOUT,
+                    std::mem::size_of::<u32>() as u32,
+                    &millis as *const u32 as *const _,
+                )
+            };
+        }
+    }
+}
+
+impl Drop for WinUsbDevice {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = WinUsb_Free(self.interface);
+            let _ = CloseHandle(self.file);
+        }
+    }
+}
+
+impl Transport for WinUsbDevice {
+    fn transmit(&mut self, data: &[u8]) -> Result<bool, String> {
+        WinUsbDevice::transmit(self, data)
+    }
+
+    fn receive(&mut self, data: &mut [u8]) -> Result<usize, String> {
+        WinUsbDevice::receive(self, data)
+    }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout.unwrap_or(Duration::from_millis(TIMEOUT_MS));
+        self.apply_timeout();
+    }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn std::any::Any> {
+        Some(self)
+    }
+}
+
+/// 所有 USB 设备的接口路径。
+fn device_paths() -> Result<Vec<String>, String> {
+    let info = unsafe {
+        SetupDiGetClassDevsW(
+            Some(&GUID_DEVINTERFACE_USB_DEVICE),
+            PCWSTR::null(),
+            None,
+            DIGCF_PRESENT | DIGCF_DEVICEINTERFACE,
+        )
+    }
+    .map_err(|e| format!("枚举设备失败: {}", e))?;
+
+    let mut paths = Vec::new();
+    for index in 0.. {
+        let mut data = SP_DEVICE_INTERFACE_DATA {
+            cbSize: std::mem::size_of::<SP_DEVICE_INTERFACE_DATA>() as u32,
+            ..Default::default()
+        };
+        if unsafe {
+            SetupDiEnumDeviceInterfaces(info, None, &GUID_DEVINTERFACE_USB_DEVICE, index, &mut data)
+        }
+        .is_err()
+        {
+            break;
+        }
+        // 第一次调用只取得需要的大小（返回缓冲区不足的错误）
+        let mut size = 0u32;
+        let _ = unsafe {
+            SetupDiGetDeviceInterfaceDetailW(info, &data, None, 0, Some(&mut size), None)
+        };
+        let offset = std::mem::offset_of!(SP_DEVICE_INTERFACE_DETAIL_DATA_W, DevicePath);
+        if (size as usize) <= offset {
+            continue;
+        }
+        // 按 u32 分配以满足结构体的对齐
+        let mut buffer = vec![0u32; (size as usize).div_ceil(4)];
+        let detail = buffer.as_mut_ptr() as *mut SP_DEVICE_INTERFACE_DETAIL_DATA_W;
+        unsafe {
+            (*detail).cbSize = std::mem::size_of::<SP_DEVICE_INTERFACE_DETAIL_DATA_W>() as u32;
+        }
+        if unsafe { SetupDiGetDeviceInterfaceDetailW(info, &data, Some(detail), size, None, None) }
+            .is_err()
+        {
+            continue;
+        }
+        // DevicePath 是以 0 结尾的 UTF-16 字符串
+        let wide = unsafe {
+            std::slice::from_raw_parts(
+                (buffer.as_ptr() as *const u8).add(offset) as *const u16,
+                (size as usize - offset) / 2,
+            )
+        };
+        let len = wide.iter().position(|&c| c == 0).unwrap_or(wide.len());
+        paths.push(String::from_utf16_lossy(&wide[..len]));
+    }
+    unsafe {
+        let _ = SetupDiDestroyDeviceInfoList(info);
+    }
+    Ok(paths)
+}
@@ -0,0 +1,98 @@
+//! ElectronBot 库的 YUV 输入转换（NV12、I420、YUYV）。
+//!
+//! 直接从 YUV 采样并转换到 BGR 帧缓冲区（BT.601 有限范围），
+//! 缩放与转换在同一遍完成，不需要中间的 RGB 图片。
+
+use crate::modules::image::{FitOptions, ImageBuffer};
+
+/// YUV 像素格式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YuvFormat {
+    /// Y 平面 + UV 交错平面（4:2:0）。
+    Nv12,
+    /// Y、U、V 三个平面（4:2:0）。
+    I420,
+    /// 打包格式 Y0 U Y1 V（4:2:2）。
+    Yuyv,
+}
+
+impl YuvFormat {
+    /// 指定尺寸的一帧所需字节数，超出 `usize` 范围时返回 `None`。
+    pub fn frame_len(&self, width: usize, height: usize) -> Option<usize> {
+        let (cw, ch) = (width.div_ceil(2), height.div_ceil(2));
+        match self {
+            YuvFormat::Nv12 | YuvFormat::I420 => width
+                .checked_mul(height)?
+                .checked_add(cw.checked_mul(ch)?.checked_mul(2)?),
+            YuvFormat::Yuyv => cw.checked_mul(4)?.checked_mul(height),
+        }
+    }
+
+    /// 读取 `(x, y)` 处的 Y、U、V 分量。
+    fn sample(&self, data: &[u8], width: usize, height: usize, x: usize, y: usize) -> [u8; 3] {
+        let (cw, ch) = (width.div_ceil(2), height.div_ceil(2));
+        match self {
+            YuvFormat::Nv12 => {
+                let uv = width * height + ((y / 2) * cw + x / 2) * 2;
+                [data[y * width + x], data[uv], data[uv + 1]]
+            }
+            YuvFormat::I420 => {
+                let c = (y / 2) * cw + x / 2;
+                let u = width * height;
+                let v = u + cw * ch;
+                [data[y * width + x], data[u + c], data[v + c]]
+            }
+            YuvFormat::Yuyv => {
+                let pair = (y * cw + x / 2) * 4;
+                [data[pair + (x % 2) * 2], data[pair + 1], data[pair + 3]]
+            }
+        }
+    }
+}
+
+/// BT.601 有限范围 YUV 转 RGB。
+pub fn yuv_to_rgb(y: u8, u: u8, v: u8) -> (u8, u8, u8) {
+    let c = (y as i32 - 16).max(0) * 298;
+    let d = u as i32 - 128;
+    let e = v as i32 - 128;
+    let clamp = |v: i32| ((v + 128) >> 8).clamp(0, 255) as u8;
+    (
+        clamp(c + 409 * e),
+        clamp(c - 100 * d - 208 * e),
+        clamp(c + 516 * d),
+    )
+}
+
+impl ImageBuffer {
+    /// 从 YUV 数据加载（最近邻缩放，按 `options` 的适配方式和背景色）。
+    pub fn load_from_yuv(
+        &mut self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        format: YuvFormat,
+        options: &FitOptions,
+    ) -> Result<(), String> {
+        if width == 0 || height == 0 {
+            return Err("YUV 图片尺寸不能为 0".to_string());
+        }
+        let needed = format
+            .frame_len(width, height)
+            .ok_or_else(|| format!("YUV 图片尺寸过大: {}x{}", width, height))?;
+        if data.len() < needed {
+            return Err(format!(
+                "YUV 数据太小: 需要 {} 字节，实际 {} 字节",
+                needed,
+                data.len()
+            ));
+        }
+
+        self.load_nearest(width, height, options, |x, y| {
+            let [py, pu, pv] = format.sample(data, width, height, x, y);
+            let (r, g, b) = yuv_to_rgb(py, pu, pv);
+            // MCU 所需的 BGR
+            [b, g, r]
+        });
+        Ok(())
+    }
+}